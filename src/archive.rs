@@ -0,0 +1,168 @@
+//! Uploading tar archives built on the fly.
+//!
+//! `upload_tar` turns a sequence of named streams into a tar archive and sends it as a multipart
+//! upload while it is being produced, so a backup of many files never has to be staged on disk:
+//!
+//! ```rust,ignore
+//! let entries = files.into_iter().map(|(name, file, len)| {
+//!     (name, ByteStream::from_file(file, len))
+//! });
+//! upload_tar(&client, "backups", "2020-12-01.tar", entries).await?;
+//! ```
+
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::{Bytes, BytesMut};
+use futures::{future, stream, Stream, StreamExt};
+
+use crate::multipart::{upload_stream, MultipartError, MIN_PART_SIZE};
+use crate::s3::ops::*;
+use crate::s3::S3;
+use crate::signature::ByteStream;
+
+const BLOCK_SIZE: usize = 512;
+
+/// The largest entry that fits the 11 octal digits of the size field.
+const MAX_ENTRY_SIZE: u64 = 0o777_7777_7777;
+
+type EntryStream = std::pin::Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + Sync>>;
+
+/// Upload the archive of `entries` to `bucket` under `key`.
+///
+/// See `tar_stream` for how the entries are laid out.
+pub async fn upload_tar<C, I>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    entries: I,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+    I: IntoIterator<Item = (String, ByteStream)>,
+    I::IntoIter: Send + Sync + 'static,
+{
+    let input = CreateMultipartUploadRequest {
+        bucket: bucket.to_owned(),
+        key: key.to_owned(),
+        content_type: Some("application/x-tar".to_owned()),
+        ..Default::default()
+    };
+    upload_stream(client, input, tar_stream(entries), MIN_PART_SIZE).await
+}
+
+/// Produce a ustar archive holding each `(name, body)` entry as a regular file.
+///
+/// The header of an entry records its size, which is taken from the size hint of its body;
+/// bodies without one are read into memory first. An entry whose body doesn't match its size
+/// hint, whose name doesn't fit in a ustar header, or that is larger than 8 GiB ends the archive
+/// with an error.
+pub fn tar_stream<I>(entries: I) -> ByteStream
+where
+    I: IntoIterator<Item = (String, ByteStream)>,
+    I::IntoIter: Send + Sync + 'static,
+{
+    let mtime = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let body = stream::iter(entries)
+        .then(move |(name, body)| tar_entry(name, body, mtime))
+        .map(|entry| match entry {
+            Ok(entry) => entry,
+            Err(err) => Box::pin(stream::once(future::ready(Err(err)))) as EntryStream,
+        })
+        .flatten()
+        .chain(stream::once(future::ready(Ok(zeros(2 * BLOCK_SIZE)))));
+    ByteStream::new(body)
+}
+
+/// The header, content and padding of a single entry.
+async fn tar_entry(name: String, mut body: ByteStream, mtime: u64) -> io::Result<EntryStream> {
+    let size = match body.size_hint() {
+        Some(size) => size as u64,
+        None => {
+            let mut buffer = BytesMut::new();
+            while let Some(chunk) = body.next().await {
+                buffer.extend_from_slice(&chunk?);
+            }
+            body = ByteStream::from(buffer.freeze());
+            body.size_hint().unwrap_or(0) as u64
+        }
+    };
+    let header = Bytes::from(tar_header(&name, size, mtime)?);
+    let padding = (BLOCK_SIZE - (size as usize % BLOCK_SIZE)) % BLOCK_SIZE;
+
+    let content = stream::unfold(Some((body, size)), |state| async move {
+        let (mut body, remaining) = state?;
+        match body.next().await {
+            Some(Ok(chunk)) if chunk.len() as u64 <= remaining => {
+                let remaining = remaining - chunk.len() as u64;
+                Some((Ok(chunk), Some((body, remaining))))
+            }
+            Some(Ok(_)) => Some((Err(size_mismatch("longer")), None)),
+            Some(Err(err)) => Some((Err(err), None)),
+            None if remaining == 0 => None,
+            None => Some((Err(size_mismatch("shorter")), None)),
+        }
+    });
+    Ok(Box::pin(
+        stream::once(future::ready(Ok(header)))
+            .chain(content)
+            .chain(stream::once(future::ready(Ok(zeros(padding))))),
+    ))
+}
+
+fn zeros(len: usize) -> Bytes {
+    Bytes::from(vec![0; len])
+}
+
+fn size_mismatch(comparison: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("archive entry is {} than its size hint", comparison),
+    )
+}
+
+/// Build the ustar header of a regular file.
+fn tar_header(name: &str, size: u64, mtime: u64) -> io::Result<Vec<u8>> {
+    if size > MAX_ENTRY_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("archive entry {} is too large", name),
+        ));
+    }
+    let (prefix, name) = split_name(name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("archive entry name {} is too long", name),
+        )
+    })?;
+
+    let mut header = vec![0u8; BLOCK_SIZE];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+    header[136..148].copy_from_slice(format!("{:011o}\0", mtime & MAX_ENTRY_SIZE).as_bytes());
+    header[148..156].copy_from_slice(b"        ");
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    Ok(header)
+}
+
+/// Split `name` into the prefix and name fields of a ustar header, which hold 155 and 100 bytes.
+fn split_name(name: &str) -> Option<(&str, &str)> {
+    if name.len() <= 100 {
+        return Some(("", name));
+    }
+    name.match_indices('/')
+        .map(|(i, _)| (&name[..i], &name[i + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && !name.is_empty() && name.len() <= 100)
+}
@@ -0,0 +1,107 @@
+//! An audit trail of the changes made through `S3Client`.
+//!
+//! Set `Ks3Config::audit_log` to an implementation of `AuditLog` to be told of every request
+//! that changes a bucket or an object, i.e. every `PUT`, `POST` and `DELETE`: uploads, copies,
+//! deletions, ACL changes and multipart upload steps. Reads, including `select_object_content`
+//! queries, aren't recorded.
+//!
+//! ```rust,ignore
+//! #[derive(Debug)]
+//! struct Syslog;
+//!
+//! impl AuditLog for Syslog {
+//!     fn record(&self, record: &AuditRecord) {
+//!         log::info!(target: "audit", "{}", record);
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+
+/// A mutating request sent by `S3Client`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditRecord {
+    /// The operation, e.g. `DeleteObject`.
+    pub operation: &'static str,
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The bucket the operation applies to.
+    pub bucket: String,
+    /// The key of the object the operation applies to, for object operations.
+    pub key: Option<String>,
+    /// The access key ID the request was signed with, or `None` for anonymous requests and
+    /// requests whose credentials couldn't be fetched.
+    pub access_key_id: Option<String>,
+    /// When the request was sent, according to the clock of the client.
+    pub time: DateTime<Utc>,
+    /// The status code of the response, or `None` if no response was received.
+    pub status: Option<StatusCode>,
+    /// The ID the service assigned to the request.
+    pub request_id: Option<String>,
+    /// Why the request failed, if it did.
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    /// Whether the change was made.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none() && self.status.is_some_and(|status| status.is_success())
+    }
+}
+
+/// Renders the record on one line, e.g.
+/// `2020-06-01T12:00:00Z AKID DeleteObject photos/cat.jpg: 204 No Content`.
+impl fmt::Display for AuditRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.time.to_rfc3339(),
+            self.access_key_id.as_deref().unwrap_or("-"),
+            self.operation,
+            self.bucket
+        )?;
+        if let Some(ref key) = self.key {
+            write!(f, "/{}", key)?;
+        }
+        match (self.status, self.error.as_ref()) {
+            (_, Some(error)) => write!(f, ": failed: {}", error)?,
+            (Some(status), None) => write!(f, ": {}", status)?,
+            (None, None) => {}
+        }
+        if let Some(ref request_id) = self.request_id {
+            write!(f, " ({})", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Receives the mutating requests sent by `S3Client`, once their outcome is known.
+///
+/// `record` is called on the task sending the request, so it should be quick, e.g. only queue
+/// the record to be shipped elsewhere.
+pub trait AuditLog: fmt::Debug + Send + Sync {
+    /// A mutating request completed or failed.
+    fn record(&self, record: &AuditRecord);
+}
+
+/// Whether `operation` requests, sent with `method`, change a bucket or an object.
+pub(crate) fn is_mutating(operation: &str, method: &str) -> bool {
+    // Queries are sent as POST requests, but only read the object.
+    operation != "SelectObjectContent" && matches!(method, "PUT" | "POST" | "DELETE")
+}
+
+/// Split the path of a request, `/bucket` or `/bucket/key`, into the bucket and the key.
+pub(crate) fn bucket_and_key(path: &str) -> (String, Option<String>) {
+    let path = path.trim_start_matches('/');
+    match path.find('/') {
+        Some(slash) => (
+            path[..slash].to_owned(),
+            Some(path[slash + 1..].to_owned()).filter(|key| !key.is_empty()),
+        ),
+        None => (path.to_owned(), None),
+    }
+}
@@ -0,0 +1,327 @@
+//! Applying an operation to every object under a prefix, e.g. to retag or change the ACL of a
+//! whole "directory":
+//!
+//! ```rust,ignore
+//! let client = &client;
+//! let report = batch_apply(client, "logs", "2020/", &BatchOptions::default(), |object| {
+//!     async move {
+//!         client
+//!             .put_object_acl(PutObjectAclRequest {
+//!                 bucket: "logs".to_owned(),
+//!                 key: object.key.unwrap_or_default(),
+//!                 acl: Some(CannedAcl::Private),
+//!                 ..Default::default()
+//!             })
+//!             .await
+//!     }
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{stream, StreamExt};
+use tracing::{debug_span, Instrument};
+
+use crate::core::clock::{Clock, SystemClock};
+use crate::core::error::Ks3Error;
+use crate::filter::KeyFilter;
+use crate::metrics::MetricsObserver;
+use crate::request::*;
+use crate::retry::{Retries, RetryBudget};
+use crate::s3::S3;
+use crate::sync::dir_prefix;
+
+/// Options of `batch_apply`.
+#[derive(Clone, Debug)]
+pub struct BatchOptions {
+    /// Maximum number of operations in flight at once.
+    pub concurrency: usize,
+    /// How many times a failed operation is retried before the object is reported as failed.
+    pub max_retries: u32,
+    /// The delay before the first retry of an object, doubled for every further retry.
+    pub retry_delay: Duration,
+    /// The clock the delays between retries are waited on.
+    pub clock: Arc<dyn Clock>,
+    /// Told of every retry, as the `BatchApply` operation.
+    pub metrics: Option<Arc<dyn MetricsObserver>>,
+    /// Take every retry from this budget, shared with other batches or syncs, and stop
+    /// retrying once it is spent.
+    pub retry_budget: Option<RetryBudget>,
+    /// Only apply the operation to objects whose key, relative to the prefix, passes this
+    /// filter. Other objects are left out of the report.
+    pub filter: KeyFilter,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            concurrency: 8,
+            max_retries: 2,
+            retry_delay: Duration::from_millis(200),
+            clock: Arc::new(SystemClock),
+            metrics: None,
+            retry_budget: None,
+            filter: KeyFilter::new(),
+        }
+    }
+}
+
+/// An object the operation kept failing on.
+#[derive(Debug)]
+pub struct BatchFailure<E> {
+    /// The key of the object.
+    pub key: String,
+    /// How many times the operation was attempted.
+    pub attempts: u32,
+    /// The error of the last attempt.
+    pub error: E,
+}
+
+/// The outcome of a batch helper, keyed by object key: the keys it succeeded on, the keys it
+/// failed on with the typed error of each, and how many retries it took.
+#[derive(Debug)]
+pub struct BatchResult<E> {
+    /// Keys the operation succeeded on.
+    pub succeeded: Vec<String>,
+    /// Keys the operation failed on, even after retrying.
+    pub failed: Vec<BatchFailure<E>>,
+    /// The retries made across all keys, whether they ended up succeeding or not.
+    pub retries: u32,
+}
+
+impl<E> Default for BatchResult<E> {
+    fn default() -> Self {
+        BatchResult {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+            retries: 0,
+        }
+    }
+}
+
+impl<E> BatchResult<E> {
+    /// The number of keys the operation succeeded on.
+    pub fn succeeded_count(&self) -> usize {
+        self.succeeded.len()
+    }
+
+    /// The number of keys the operation failed on.
+    pub fn failed_count(&self) -> usize {
+        self.failed.len()
+    }
+
+    /// The number of keys the operation was applied to.
+    pub fn total(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    /// Whether the operation succeeded on every key.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// The keys the operation failed on.
+    pub fn failed_keys(&self) -> impl Iterator<Item = &str> {
+        self.failed.iter().map(|failure| failure.key.as_str())
+    }
+
+    /// Record the outcome of the operation on `key`, after `attempts` attempts.
+    pub(crate) fn record<T>(&mut self, key: String, attempts: u32, result: Result<T, E>) {
+        self.retries += attempts.saturating_sub(1);
+        match result {
+            Ok(_) => self.succeeded.push(key),
+            Err(error) => self.failed.push(BatchFailure {
+                key,
+                attempts,
+                error,
+            }),
+        }
+    }
+}
+
+/// A listing error that stopped a batch, along with the outcome of the objects that were
+/// already processed.
+#[derive(Debug)]
+pub struct BatchError<E> {
+    /// The error of the listing request.
+    pub error: Ks3Error<ListObjectsError>,
+    /// The objects processed before the listing failed.
+    pub report: BatchResult<E>,
+}
+
+impl<E> fmt::Display for BatchError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<E: fmt::Debug> Error for BatchError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Apply `op` to every object in `bucket` under `prefix` that passes `BatchOptions::filter`.
+///
+/// The listing is processed a page at a time, running up to `BatchOptions::concurrency`
+/// operations at once. An operation that fails is retried with an increasing delay, as long as
+/// `BatchOptions::retry_budget` allows; objects that still fail are recorded in the report
+/// rather than stopping the batch. Only a failure of the listing itself stops it early.
+pub async fn batch_apply<C, F, Fut, T, E>(
+    client: &C,
+    bucket: &str,
+    prefix: &str,
+    options: &BatchOptions,
+    op: F,
+) -> Result<BatchResult<E>, BatchError<E>>
+where
+    C: S3 + Sync,
+    F: Fn(Object) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut report = BatchResult::default();
+    let mut marker = None;
+    let base = dir_prefix(prefix);
+    loop {
+        let output = client
+            .list_objects(ListObjectsRequest {
+                bucket: bucket.to_owned(),
+                prefix: Some(base.clone()).filter(|p| !p.is_empty()),
+                marker: marker.take(),
+                ..Default::default()
+            })
+            .await;
+        let output = match output {
+            Ok(output) => output,
+            Err(error) => return Err(BatchError { error, report }),
+        };
+
+        let objects = output.contents.unwrap_or_default();
+        let last_key = objects.last().and_then(|object| object.key.clone());
+        let selected = objects.into_iter().filter(|object| match object.key {
+            Some(ref key) => options
+                .filter
+                .matches(key.strip_prefix(&base[..]).unwrap_or(key)),
+            None => false,
+        });
+        let results = stream::iter(selected)
+            .map(|object| apply_with_retries(&op, object, options))
+            .buffer_unordered(options.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        for (key, attempts, result) in results {
+            report.record(key, attempts, result);
+        }
+
+        if !output.is_truncated.unwrap_or(false) {
+            break;
+        }
+        marker = output.next_marker.or(last_key);
+        if marker.is_none() {
+            break;
+        }
+    }
+    Ok(report)
+}
+
+/// Delete every object in `bucket` under `prefix` that passes `BatchOptions::filter`,
+/// running `batch_apply` with `delete_object`.
+pub async fn delete_prefix<C>(
+    client: &C,
+    bucket: &str,
+    prefix: &str,
+    options: &BatchOptions,
+) -> Result<BatchResult<Ks3Error<DeleteObjectError>>, BatchError<Ks3Error<DeleteObjectError>>>
+where
+    C: S3 + Sync,
+{
+    batch_apply(client, bucket, prefix, options, |object| {
+        client.delete_object(DeleteObjectRequest {
+            bucket: bucket.to_owned(),
+            key: object.key.unwrap_or_default(),
+            ..Default::default()
+        })
+    })
+    .await
+}
+
+async fn apply_with_retries<F, Fut, T, E>(
+    op: &F,
+    object: Object,
+    options: &BatchOptions,
+) -> (String, u32, Result<T, E>)
+where
+    F: Fn(Object) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let key = object.key.clone().unwrap_or_default();
+    let retries = Retries {
+        max_retries: options.max_retries,
+        delay: options.retry_delay,
+        clock: &*options.clock,
+        budget: options.retry_budget.as_ref(),
+        metrics: options.metrics.as_deref(),
+        operation: "BatchApply",
+    };
+    let (attempts, result) = retries
+        .run(|attempt| {
+            op(object.clone()).instrument(debug_span!("attempt", key = %key, attempt = attempt))
+        })
+        .await;
+    (key, attempts, result)
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::credential::StaticProvider;
+    use crate::filter::Pattern;
+    use crate::s3::S3Client;
+    use crate::signature::Region;
+    use crate::test::MockRequestDispatcher;
+
+    #[tokio::test]
+    async fn delete_prefix_skips_filtered_out_keys() {
+        let deleted = Arc::new(Mutex::new(Vec::new()));
+        let recorded = deleted.clone();
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_body(
+                "<ListBucketResult><IsTruncated>false</IsTruncated>\
+                <Contents><Key>logs/a.log</Key></Contents>\
+                <Contents><Key>logs/b.tmp</Key></Contents>\
+                <Contents><Key>logs/c.log</Key></Contents>\
+                </ListBucketResult>",
+            )
+            .with_request_checker(move |request| {
+                if request.method() == "DELETE" {
+                    recorded.lock().unwrap().push(request.path().to_owned());
+                }
+            });
+        let client = S3Client::new_with(
+            dispatcher,
+            StaticProvider::new_minimal("a".to_owned(), "b".to_owned()),
+            Region::default(),
+        );
+        let options = BatchOptions {
+            filter: KeyFilter::new().exclude(Pattern::glob("*.tmp").unwrap()),
+            ..Default::default()
+        };
+
+        let report = delete_prefix(&client, "bucket", "logs", &options)
+            .await
+            .unwrap();
+        let mut succeeded = report.succeeded.clone();
+        succeeded.sort();
+        assert_eq!(succeeded, vec!["logs/a.log", "logs/c.log"]);
+        let mut deleted = deleted.lock().unwrap().clone();
+        deleted.sort();
+        assert_eq!(deleted, vec!["/bucket/logs/a.log", "/bucket/logs/c.log"]);
+    }
+}
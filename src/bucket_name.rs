@@ -0,0 +1,74 @@
+//! Validation of bucket names.
+//!
+//! Bucket names are host labels in virtual-hosted URLs (`bucket.endpoint`), so the service
+//! only accepts names that are valid DNS labels: 3 to 63 lowercase letters, digits and hyphens,
+//! starting and ending with a letter or digit. `create_bucket` checks the name before sending
+//! the request.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::core::error::Ks3Error;
+
+/// The shortest bucket name allowed.
+pub const MIN_BUCKET_NAME_LEN: usize = 3;
+/// The longest bucket name allowed.
+pub const MAX_BUCKET_NAME_LEN: usize = 63;
+
+/// Errors returned for bucket names the service doesn't accept.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BucketNameError {
+    /// The name is shorter than `MIN_BUCKET_NAME_LEN` or longer than `MAX_BUCKET_NAME_LEN`.
+    Length(usize),
+    /// The name has a character other than a lowercase letter, a digit or a hyphen.
+    InvalidCharacter(char),
+    /// The name starts or ends with a hyphen.
+    HyphenAtEnds,
+}
+
+impl fmt::Display for BucketNameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BucketNameError::Length(len) => write!(
+                f,
+                "Bucket name is {} characters long, it must have between {} and {}",
+                len, MIN_BUCKET_NAME_LEN, MAX_BUCKET_NAME_LEN
+            ),
+            BucketNameError::InvalidCharacter(c) => write!(
+                f,
+                "Bucket name has the invalid character {:?}, only lowercase letters, digits and \
+                 hyphens are allowed",
+                c
+            ),
+            BucketNameError::HyphenAtEnds => {
+                write!(f, "Bucket name must start and end with a letter or digit")
+            }
+        }
+    }
+}
+
+impl Error for BucketNameError {}
+
+impl<E> From<BucketNameError> for Ks3Error<E> {
+    fn from(err: BucketNameError) -> Ks3Error<E> {
+        Ks3Error::Validation(err.to_string())
+    }
+}
+
+/// Check that `name` can be used as a bucket name.
+pub fn validate_bucket_name(name: &str) -> Result<(), BucketNameError> {
+    let len = name.chars().count();
+    if !(MIN_BUCKET_NAME_LEN..=MAX_BUCKET_NAME_LEN).contains(&len) {
+        return Err(BucketNameError::Length(len));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|&c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'))
+    {
+        return Err(BucketNameError::InvalidCharacter(c));
+    }
+    if name.starts_with('-') || name.ends_with('-') {
+        return Err(BucketNameError::HyphenAtEnds);
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! Settings that only apply to the requests to one bucket.
+//!
+//! Applications working with several buckets often need some of them handled differently:
+//! a bucket in another region, a requester-pays bucket, a bucket whose objects must be
+//! encrypted with a given KMS key. Registering a `BucketProfile` for such a bucket saves
+//! passing these settings at every call site:
+//!
+//! ```rust,ignore
+//! client.set_bucket_profile("archive", BucketProfile {
+//!     region: Some(Region::Custom {
+//!         name: "SHANGHAI".to_owned(),
+//!         endpoint: "ks3-cn-shanghai.ksyuncs.com".to_owned(),
+//!     }),
+//!     requester_pays: true,
+//!     server_side_encryption: Some(ServerSideEncryption::AwsKms),
+//!     ssekms_key_id: Some("key-id".to_owned()),
+//!     ..Default::default()
+//! });
+//! ```
+
+use crate::audit::bucket_and_key;
+use crate::config::Ks3Config;
+use crate::enums::ServerSideEncryption;
+use crate::signature::{Region, SignedRequest};
+
+/// The settings of the requests to a bucket, set with `S3Client::set_bucket_profile`.
+///
+/// Settings left unset fall back to those of the client, and a value set on a request always
+/// wins.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BucketProfile {
+    /// The region requests to the bucket are sent to, instead of the region of the client.
+    pub region: Option<Region>,
+    /// Whether the object URLs of the bucket have the bucket in the host name, instead of
+    /// `Ks3Config::virtual_hosted_urls`.
+    pub virtual_hosted_urls: Option<bool>,
+    /// Send every request to the bucket with `x-amz-request-payer: requester`, accepting the
+    /// charges of a requester-pays bucket.
+    pub requester_pays: bool,
+    /// The server-side encryption of uploads to the bucket, instead of
+    /// `Ks3Config::default_server_side_encryption`.
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// The KMS key of uploads to the bucket encrypted with `aws:kms`.
+    pub ssekms_key_id: Option<String>,
+}
+
+impl Ks3Config {
+    /// The profile of `bucket`, if one was set.
+    pub fn bucket_profile(&self, bucket: &str) -> Option<&BucketProfile> {
+        self.bucket_profiles.get(bucket)
+    }
+
+    /// The server-side encryption of uploads to `bucket` that don't set one.
+    pub(crate) fn server_side_encryption_for(&self, bucket: &str) -> Option<&ServerSideEncryption> {
+        self.bucket_profile(bucket)
+            .and_then(|profile| profile.server_side_encryption.as_ref())
+            .or(self.default_server_side_encryption.as_ref())
+    }
+
+    /// The KMS key of uploads to `bucket` that don't set one, when they are encrypted with
+    /// `server_side_encryption`.
+    pub(crate) fn ssekms_key_id_for(
+        &self,
+        bucket: &str,
+        server_side_encryption: Option<&ServerSideEncryption>,
+    ) -> Option<&String> {
+        if server_side_encryption != Some(&ServerSideEncryption::AwsKms) {
+            return None;
+        }
+        self.bucket_profile(bucket)
+            .and_then(|profile| profile.ssekms_key_id.as_ref())
+    }
+
+    /// Whether the object URLs of `bucket` have the bucket in the host name.
+    pub(crate) fn virtual_hosted_urls_for(&self, bucket: &str) -> bool {
+        self.bucket_profile(bucket)
+            .and_then(|profile| profile.virtual_hosted_urls)
+            .unwrap_or(self.virtual_hosted_urls)
+    }
+}
+
+/// Apply the profile of the bucket `request` is sent to, if any, before it's signed.
+pub(crate) fn apply_bucket_profile(config: &Ks3Config, request: &mut SignedRequest) {
+    if config.bucket_profiles.is_empty() {
+        return;
+    }
+    let (bucket, _) = bucket_and_key(&request.path);
+    let profile = match config.bucket_profile(&bucket) {
+        Some(profile) => profile,
+        None => return,
+    };
+    if let Some(ref region) = profile.region {
+        request.region = region.clone();
+        request.invalidate();
+    }
+    if profile.requester_pays && !request.headers.contains_key("x-amz-request-payer") {
+        request.add_header("x-amz-request-payer", "requester");
+    }
+}
@@ -0,0 +1,142 @@
+//! Listing the buckets of an account with the region each of them resides in, e.g. for tools
+//! managing buckets across regions:
+//!
+//! ```rust,ignore
+//! let located = locate_buckets(&client, "logs-", 8).await?;
+//! for (location, buckets) in located.by_location() {
+//!     println!("{}: {} buckets", location.unwrap_or("default"), buckets.len());
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt};
+
+use crate::core::error::Ks3Error;
+use crate::request::*;
+use crate::s3::S3;
+
+/// A bucket with the location it resides in.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LocatedBucket {
+    /// The name of the bucket.
+    pub name: String,
+    /// When the bucket was created.
+    pub creation_date: Option<DateTime<Utc>>,
+    /// The location constraint of the bucket, e.g. `BEIJING`, or `None` for a bucket in the
+    /// default region of the endpoint.
+    pub location: Option<String>,
+}
+
+/// A bucket whose location couldn't be read, e.g. because it belongs to another account.
+#[derive(Debug)]
+pub struct BucketLocationFailure {
+    /// The name of the bucket.
+    pub bucket: String,
+    /// The error of `get_bucket_location`.
+    pub error: Ks3Error<GetBucketLocationError>,
+}
+
+/// The buckets listed by `locate_buckets`, in the order of the listing.
+#[derive(Debug, Default)]
+pub struct LocatedBuckets {
+    /// Buckets whose location was read.
+    pub buckets: Vec<LocatedBucket>,
+    /// Buckets whose location couldn't be read.
+    pub failed: Vec<BucketLocationFailure>,
+}
+
+impl LocatedBuckets {
+    /// The buckets grouped by location, `None` standing for the default region of the endpoint.
+    pub fn by_location(&self) -> BTreeMap<Option<&str>, Vec<&LocatedBucket>> {
+        let mut groups: BTreeMap<Option<&str>, Vec<&LocatedBucket>> = BTreeMap::new();
+        for bucket in &self.buckets {
+            groups
+                .entry(bucket.location.as_deref())
+                .or_default()
+                .push(bucket);
+        }
+        groups
+    }
+
+    /// The buckets in `location`, compared case-insensitively.
+    pub fn in_location<'a>(
+        &'a self,
+        location: &'a str,
+    ) -> impl Iterator<Item = &'a LocatedBucket> + 'a {
+        self.buckets.iter().filter(move |bucket| {
+            bucket
+                .location
+                .as_deref()
+                .is_some_and(|l| l.eq_ignore_ascii_case(location))
+        })
+    }
+}
+
+/// The buckets whose name starts with `prefix`, in the order of the listing.
+pub async fn list_buckets_with_prefix<C>(
+    client: &C,
+    prefix: &str,
+) -> Result<Vec<Bucket>, Ks3Error<ListBucketsError>>
+where
+    C: S3 + Sync,
+{
+    let output = client.list_buckets().await?;
+    Ok(output
+        .buckets
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|bucket| {
+            bucket
+                .name
+                .as_deref()
+                .is_some_and(|name| name.starts_with(prefix))
+        })
+        .collect())
+}
+
+/// The buckets whose name starts with `prefix` with their location, read with up to
+/// `concurrency` `get_bucket_location` requests at once.
+///
+/// Only a failure of the listing itself is returned as an error; buckets whose location
+/// couldn't be read are recorded in `LocatedBuckets::failed`.
+pub async fn locate_buckets<C>(
+    client: &C,
+    prefix: &str,
+    concurrency: usize,
+) -> Result<LocatedBuckets, Ks3Error<ListBucketsError>>
+where
+    C: S3 + Sync,
+{
+    let buckets = list_buckets_with_prefix(client, prefix).await?;
+    let results = stream::iter(buckets)
+        .map(|bucket| async move {
+            let name = bucket.name.unwrap_or_default();
+            let location = client
+                .get_bucket_location(GetBucketLocationRequest {
+                    bucket: name.clone(),
+                })
+                .await;
+            (name, bucket.creation_date, location)
+        })
+        .buffered(concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut located = LocatedBuckets::default();
+    for (name, creation_date, location) in results {
+        match location {
+            Ok(output) => located.buckets.push(LocatedBucket {
+                name,
+                creation_date,
+                location: output.location_constraint,
+            }),
+            Err(error) => located.failed.push(BucketLocationFailure {
+                bucket: name,
+                error,
+            }),
+        }
+    }
+    Ok(located)
+}
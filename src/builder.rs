@@ -0,0 +1,455 @@
+//! Builders for the request structs.
+//!
+//! Each request has a `builder()` constructor returning a builder with one setter per field.
+//! `build()` fails if a required field wasn't set, instead of sending a request with an empty
+//! bucket or key as `Default::default()` would:
+//!
+//! ```rust,ignore
+//! let request = PutObjectRequest::builder()
+//!     .bucket("photos")
+//!     .key("2020/puppy.jpg")
+//!     .body(data)
+//!     .content_type("image/jpeg")
+//!     .build()?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::enums::{CannedAcl, ObjectLockMode, RequestPayer, ServerSideEncryption, StorageClass};
+use crate::metadata::Metadata;
+use crate::range::Range;
+use crate::request::*;
+#[cfg(feature = "multipart")]
+use crate::s3::ops::*;
+
+/// Errors returned by the `build()` method of the request builders.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildError {
+    /// A required field wasn't set.
+    MissingField(&'static str),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::MissingField(field) => write!(f, "Missing required field: {}", field),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+macro_rules! request_builder {
+    (
+        $request:ident, $builder:ident {
+            required { $($required:ident: $required_ty:ty,)* }
+            optional { $($optional:ident: $optional_ty:ty,)* }
+        }
+    ) => {
+        #[doc = concat!("Builder for [`", stringify!($request), "`].")]
+        #[derive(Debug, Default)]
+        pub struct $builder {
+            $($required: Option<$required_ty>,)*
+            $($optional: Option<$optional_ty>,)*
+        }
+
+        impl $request {
+            #[doc = concat!("Start building a `", stringify!($request), "`.")]
+            pub fn builder() -> $builder {
+                $builder::default()
+            }
+        }
+
+        impl $builder {
+            $(
+                #[doc = concat!("Set the required `", stringify!($required), "` field.")]
+                pub fn $required<V: Into<$required_ty>>(mut self, value: V) -> $builder {
+                    self.$required = Some(value.into());
+                    self
+                }
+            )*
+
+            $(
+                #[doc = concat!("Set the `", stringify!($optional), "` field.")]
+                pub fn $optional<V: Into<$optional_ty>>(mut self, value: V) -> $builder {
+                    self.$optional = Some(value.into());
+                    self
+                }
+            )*
+
+            /// Build the request, failing if a required field wasn't set.
+            pub fn build(self) -> Result<$request, BuildError> {
+                Ok($request {
+                    $($required: self
+                        .$required
+                        .ok_or(BuildError::MissingField(stringify!($required)))?,)*
+                    $($optional: self.$optional,)*
+                })
+            }
+        }
+    };
+}
+
+request_builder! {
+    CreateBucketRequest, CreateBucketRequestBuilder {
+        required {
+            bucket: String,
+        }
+        optional {
+            acl: CannedAcl,
+            create_bucket_configuration: CreateBucketConfiguration,
+            grant_full_control: String,
+            grant_read: String,
+            grant_read_acp: String,
+            grant_write: String,
+            grant_write_acp: String,
+            object_lock_enabled_for_bucket: bool,
+        }
+    }
+}
+
+request_builder! {
+    PutObjectRequest, PutObjectRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+        }
+        optional {
+            acl: CannedAcl,
+            body: StreamingBody,
+            cache_control: String,
+            content_disposition: String,
+            content_encoding: String,
+            content_language: String,
+            content_length: i64,
+            content_md5: String,
+            content_type: String,
+            expires: DateTime<Utc>,
+            grant_full_control: String,
+            grant_read: String,
+            grant_read_acp: String,
+            grant_write_acp: String,
+            metadata: Metadata,
+            object_lock_legal_hold_status: String,
+            object_lock_mode: ObjectLockMode,
+            object_lock_retain_until_date: DateTime<Utc>,
+            request_payer: RequestPayer,
+            sse_customer_algorithm: String,
+            sse_customer_key: String,
+            sse_customer_key_md5: String,
+            ssekms_encryption_context: String,
+            ssekms_key_id: String,
+            server_side_encryption: ServerSideEncryption,
+            storage_class: StorageClass,
+            tagging: String,
+            website_redirect_location: String,
+        }
+    }
+}
+
+request_builder! {
+    ListObjectsRequest, ListObjectsRequestBuilder {
+        required {
+            bucket: String,
+        }
+        optional {
+            delimiter: String,
+            encoding_type: String,
+            marker: String,
+            max_keys: i64,
+            prefix: String,
+            request_payer: RequestPayer,
+        }
+    }
+}
+
+request_builder! {
+    DeleteObjectRequest, DeleteObjectRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+        }
+        optional {
+            mfa: String,
+            request_payer: RequestPayer,
+            version_id: String,
+        }
+    }
+}
+
+request_builder! {
+    GetObjectRequest, GetObjectRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+        }
+        optional {
+            if_match: String,
+            if_modified_since: DateTime<Utc>,
+            if_none_match: String,
+            if_unmodified_since: DateTime<Utc>,
+            part_number: i64,
+            range: Range,
+            request_payer: RequestPayer,
+            response_cache_control: String,
+            response_content_disposition: String,
+            response_content_encoding: String,
+            response_content_language: String,
+            response_content_type: String,
+            response_expires: DateTime<Utc>,
+            sse_customer_algorithm: String,
+            sse_customer_key: String,
+            sse_customer_key_md5: String,
+            version_id: String,
+        }
+    }
+}
+
+request_builder! {
+    HeadBucketRequest, HeadBucketRequestBuilder {
+        required {
+            bucket: String,
+        }
+        optional {
+        }
+    }
+}
+
+request_builder! {
+    HeadObjectRequest, HeadObjectRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+        }
+        optional {
+            if_match: String,
+            if_modified_since: DateTime<Utc>,
+            if_none_match: String,
+            if_unmodified_since: DateTime<Utc>,
+            part_number: i64,
+            range: Range,
+            request_payer: RequestPayer,
+            sse_customer_algorithm: String,
+            sse_customer_key: String,
+            sse_customer_key_md5: String,
+            version_id: String,
+        }
+    }
+}
+
+#[cfg(feature = "multipart")]
+request_builder! {
+    CreateMultipartUploadRequest, CreateMultipartUploadRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+        }
+        optional {
+            acl: CannedAcl,
+            cache_control: String,
+            content_disposition: String,
+            content_encoding: String,
+            content_language: String,
+            content_type: String,
+            expires: DateTime<Utc>,
+            grant_full_control: String,
+            grant_read: String,
+            grant_read_acp: String,
+            grant_write_acp: String,
+            metadata: Metadata,
+            request_payer: RequestPayer,
+            sse_customer_algorithm: String,
+            sse_customer_key: String,
+            sse_customer_key_md5: String,
+            ssekms_key_id: String,
+            server_side_encryption: ServerSideEncryption,
+            storage_class: StorageClass,
+            tagging: String,
+            website_redirect_location: String,
+        }
+    }
+}
+
+#[cfg(feature = "multipart")]
+request_builder! {
+    UploadPartRequest, UploadPartRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+            part_number: i64,
+            upload_id: String,
+        }
+        optional {
+            body: StreamingBody,
+            content_length: i64,
+            content_md5: String,
+            request_payer: RequestPayer,
+            sse_customer_algorithm: String,
+            sse_customer_key: String,
+            sse_customer_key_md5: String,
+        }
+    }
+}
+
+#[cfg(feature = "multipart")]
+request_builder! {
+    CompleteMultipartUploadRequest, CompleteMultipartUploadRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+            upload_id: String,
+        }
+        optional {
+            multipart_upload: CompletedMultipartUpload,
+            request_payer: RequestPayer,
+        }
+    }
+}
+
+#[cfg(feature = "multipart")]
+request_builder! {
+    AbortMultipartUploadRequest, AbortMultipartUploadRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+            upload_id: String,
+        }
+        optional {
+            request_payer: RequestPayer,
+        }
+    }
+}
+
+request_builder! {
+    PutObjectTaggingRequest, PutObjectTaggingRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+            tagging: Tagging,
+        }
+        optional {
+            content_md5: String,
+            version_id: String,
+        }
+    }
+}
+
+request_builder! {
+    PutObjectAclRequest, PutObjectAclRequestBuilder {
+        required {
+            bucket: String,
+            key: String,
+        }
+        optional {
+            acl: CannedAcl,
+            access_control_policy: AccessControlPolicy,
+            content_md5: String,
+            grant_full_control: String,
+            grant_read: String,
+            grant_read_acp: String,
+            grant_write: String,
+            grant_write_acp: String,
+            request_payer: RequestPayer,
+            version_id: String,
+        }
+    }
+}
+
+request_builder! {
+    CopyObjectRequest, CopyObjectRequestBuilder {
+        required {
+            bucket: String,
+            copy_source: String,
+            key: String,
+        }
+        optional {
+            acl: CannedAcl,
+            cache_control: String,
+            content_disposition: String,
+            content_encoding: String,
+            content_language: String,
+            content_type: String,
+            copy_source_if_match: String,
+            copy_source_if_modified_since: DateTime<Utc>,
+            copy_source_if_none_match: String,
+            copy_source_if_unmodified_since: DateTime<Utc>,
+            copy_source_sse_customer_algorithm: String,
+            copy_source_sse_customer_key: String,
+            copy_source_sse_customer_key_md5: String,
+            expires: DateTime<Utc>,
+            grant_full_control: String,
+            grant_read: String,
+            grant_read_acp: String,
+            grant_write_acp: String,
+            metadata: Metadata,
+            metadata_directive: String,
+            request_payer: RequestPayer,
+            sse_customer_algorithm: String,
+            sse_customer_key: String,
+            sse_customer_key_md5: String,
+            ssekms_key_id: String,
+            server_side_encryption: ServerSideEncryption,
+            storage_class: StorageClass,
+            tagging: String,
+            tagging_directive: String,
+            website_redirect_location: String,
+        }
+    }
+}
+
+#[cfg(feature = "multipart")]
+request_builder! {
+    UploadPartCopyRequest, UploadPartCopyRequestBuilder {
+        required {
+            bucket: String,
+            copy_source: String,
+            key: String,
+            part_number: i64,
+            upload_id: String,
+        }
+        optional {
+            copy_source_if_match: String,
+            copy_source_if_modified_since: DateTime<Utc>,
+            copy_source_if_none_match: String,
+            copy_source_if_unmodified_since: DateTime<Utc>,
+            copy_source_range: Range,
+            copy_source_sse_customer_algorithm: String,
+            copy_source_sse_customer_key: String,
+            copy_source_sse_customer_key_md5: String,
+            request_payer: RequestPayer,
+            sse_customer_algorithm: String,
+            sse_customer_key: String,
+            sse_customer_key_md5: String,
+        }
+    }
+}
+
+request_builder! {
+    DeleteBucketRequest, DeleteBucketRequestBuilder {
+        required {
+            bucket: String,
+        }
+        optional {
+        }
+    }
+}
+
+#[cfg(feature = "multipart")]
+request_builder! {
+    ListMultipartUploadsRequest, ListMultipartUploadsRequestBuilder {
+        required {
+            bucket: String,
+        }
+        optional {
+            delimiter: String,
+            encoding_type: String,
+            key_marker: String,
+            max_uploads: i64,
+            prefix: String,
+            upload_id_marker: String,
+        }
+    }
+}
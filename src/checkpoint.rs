@@ -0,0 +1,336 @@
+//! Resuming the multipart upload of a file from a checkpoint saved along the way, e.g. after the
+//! process was restarted:
+//!
+//! ```rust,ignore
+//! let mut checkpoint: UploadCheckpoint = serde_json::from_slice(&fs::read(saved)?)?;
+//! let output = resume_file_upload(&client, &mut checkpoint, "backup.tar").await?;
+//! ```
+//!
+//! A checkpoint records the parts uploaded so far with their ETags, but it may be stale: a part
+//! may have been uploaded after it was last saved, or uploaded again with other bytes. Before
+//! completing the upload, the parts the service holds are listed and checked against the
+//! checkpoint and the file, and only the parts that are missing or don't match are sent again.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::Ks3Error;
+use crate::core::runtime;
+use crate::multipart::{complete, upload_part, MultipartError, MAX_PARTS, MIN_PART_SIZE};
+use crate::s3::ops::*;
+use crate::s3::S3;
+
+/// A part recorded in an `UploadCheckpoint`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointPart {
+    /// The size of the part in bytes.
+    pub size: u64,
+    /// The ETag the service returned for the part.
+    pub e_tag: String,
+}
+
+/// The state of a multipart upload of a file of known size, cut into parts of `part_size`
+/// bytes, to save between the uploads of the parts and resume from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadCheckpoint {
+    /// The bucket of the object.
+    pub bucket: String,
+    /// The key of the object.
+    pub key: String,
+    /// The ID of the multipart upload.
+    pub upload_id: String,
+    /// The size of the file.
+    pub size: u64,
+    /// The size of every part but the last one.
+    pub part_size: u64,
+    /// The parts uploaded so far, by part number.
+    pub parts: BTreeMap<i64, CheckpointPart>,
+}
+
+impl UploadCheckpoint {
+    /// A checkpoint of the upload `upload_id` of a file of `size` bytes to `key` in `bucket`,
+    /// in parts of `part_size` bytes raised to `MIN_PART_SIZE` if needed, with no part
+    /// uploaded yet.
+    pub fn new(
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        size: u64,
+        part_size: u64,
+    ) -> UploadCheckpoint {
+        UploadCheckpoint {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            upload_id: upload_id.to_owned(),
+            size,
+            part_size: part_size.max(MIN_PART_SIZE as u64),
+            parts: BTreeMap::new(),
+        }
+    }
+
+    /// The number of parts of the file, at least one even for an empty file.
+    pub fn part_count(&self) -> i64 {
+        let part_size = self.part_size.max(1);
+        self.size.div_ceil(part_size).max(1) as i64
+    }
+
+    /// The offset and size in the file of the part `part_number`, counted from 1.
+    pub fn part_range(&self, part_number: i64) -> (u64, u64) {
+        let offset = (part_number.max(1) as u64 - 1) * self.part_size;
+        (offset, self.part_size.min(self.size.saturating_sub(offset)))
+    }
+
+    /// Record that the part `part_number` was uploaded and got `e_tag`.
+    pub fn record(&mut self, part_number: i64, e_tag: &str) {
+        let (_, size) = self.part_range(part_number);
+        self.parts.insert(
+            part_number,
+            CheckpointPart {
+                size,
+                e_tag: e_tag.to_owned(),
+            },
+        );
+    }
+}
+
+/// Why a part of an upload has to be sent again, as found by `reconcile`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PartDivergence {
+    /// The service doesn't hold the part.
+    Missing,
+    /// The part the service holds doesn't have the size of the part of the file.
+    Size {
+        /// The size of the part of the file.
+        expected: u64,
+        /// The size of the part the service holds.
+        actual: u64,
+    },
+    /// The part the service holds doesn't have the ETag recorded in the checkpoint.
+    ETag {
+        /// The ETag recorded in the checkpoint.
+        expected: String,
+        /// The ETag of the part the service holds.
+        actual: String,
+    },
+    /// The service holds a part of the right size the checkpoint doesn't record, with this
+    /// ETag. It may be the part of the file, uploaded after the checkpoint was saved, or a
+    /// leftover of another attempt; only its content can tell.
+    Unrecorded(String),
+}
+
+/// The parts of an upload that can be kept and the ones that have to be sent again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Reconciliation {
+    /// The parts the service holds as recorded in the checkpoint, in order.
+    pub verified: Vec<CompletedPart>,
+    /// The other parts of the file, by part number.
+    pub divergent: BTreeMap<i64, PartDivergence>,
+}
+
+impl Reconciliation {
+    /// Whether every part of the file is held by the service as recorded.
+    pub fn is_complete(&self) -> bool {
+        self.divergent.is_empty()
+    }
+}
+
+/// An ETag without the quotes around it, which some listings leave out.
+fn unquoted(e_tag: &str) -> &str {
+    e_tag.trim_matches('"')
+}
+
+/// Check the parts of the file described by `checkpoint` against `parts`, the parts the service
+/// holds as listed by `list_all_parts`.
+///
+/// A part is verified when the service holds it with the size of the part of the file and the
+/// ETag recorded in the checkpoint. Parts the service holds beyond the end of the file are
+/// ignored, as completing the upload leaves them out.
+pub fn reconcile(checkpoint: &UploadCheckpoint, parts: &[Part]) -> Reconciliation {
+    let listed = parts
+        .iter()
+        .filter_map(|part| Some((part.part_number?, part)))
+        .collect::<BTreeMap<_, _>>();
+    let mut reconciliation = Reconciliation::default();
+    for part_number in 1..=checkpoint.part_count() {
+        let (_, expected_size) = checkpoint.part_range(part_number);
+        let part = match listed.get(&part_number) {
+            Some(part) => part,
+            None => {
+                reconciliation
+                    .divergent
+                    .insert(part_number, PartDivergence::Missing);
+                continue;
+            }
+        };
+        let size = part.size.unwrap_or(0).max(0) as u64;
+        let e_tag = part.e_tag.clone().unwrap_or_default();
+        let divergence = if size != expected_size {
+            Some(PartDivergence::Size {
+                expected: expected_size,
+                actual: size,
+            })
+        } else {
+            match checkpoint.parts.get(&part_number) {
+                Some(recorded) if unquoted(&recorded.e_tag) == unquoted(&e_tag) => None,
+                Some(recorded) => Some(PartDivergence::ETag {
+                    expected: recorded.e_tag.clone(),
+                    actual: e_tag.clone(),
+                }),
+                None => Some(PartDivergence::Unrecorded(e_tag.clone())),
+            }
+        };
+        match divergence {
+            Some(divergence) => {
+                reconciliation.divergent.insert(part_number, divergence);
+            }
+            None => reconciliation.verified.push(CompletedPart {
+                e_tag: Some(e_tag),
+                part_number: Some(part_number),
+            }),
+        }
+    }
+    reconciliation
+}
+
+/// Every part of the upload `upload_id` of `key` in `bucket`, listed a page at a time.
+pub async fn list_all_parts<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+) -> Result<Vec<Part>, Ks3Error<ListPartsError>>
+where
+    C: S3 + Sync,
+{
+    let mut parts = Vec::new();
+    let mut part_number_marker = None;
+    loop {
+        let output = client
+            .list_parts(ListPartsRequest {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                upload_id: upload_id.to_owned(),
+                part_number_marker,
+                ..Default::default()
+            })
+            .await?;
+        parts.extend(output.parts.unwrap_or_default());
+        part_number_marker = match output.next_part_number_marker {
+            Some(marker) if output.is_truncated == Some(true) => Some(marker),
+            _ => break,
+        };
+    }
+    Ok(parts)
+}
+
+/// Read the part `part_number` of `checkpoint` from `file`.
+async fn read_part(
+    file: &File,
+    checkpoint: &UploadCheckpoint,
+    part_number: i64,
+) -> io::Result<Bytes> {
+    let (offset, size) = checkpoint.part_range(part_number);
+    let mut file = file.try_clone()?;
+    runtime::unblock(move || {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut data = vec![0; size as usize];
+        file.read_exact(&mut data)?;
+        Ok(Bytes::from(data))
+    })
+    .await
+}
+
+/// Finish the upload of the file at `path` described by `checkpoint` and complete it.
+///
+/// The parts the service holds are listed and checked with `reconcile`. An unrecorded part is
+/// kept if its ETag is the MD5 digest of the part of the file, which holds unless the upload
+/// is encrypted with SSE-KMS or SSE-C; every other divergent part is read from the file and
+/// sent again, one at a time. `checkpoint` is updated as parts are verified or uploaded, so it
+/// can be saved again if this fails. The upload is never aborted here: after a failure, it can
+/// be resumed again from the checkpoint.
+pub async fn resume_file_upload<C, P>(
+    client: &C,
+    checkpoint: &mut UploadCheckpoint,
+    path: P,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref().to_owned();
+    let (file, size) = runtime::unblock(move || -> io::Result<_> {
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        Ok((file, size))
+    })
+    .await
+    .map_err(MultipartError::Body)?;
+    if size != checkpoint.size {
+        return Err(MultipartError::Body(io::Error::other(format!(
+            "the file is {} bytes but the checkpoint was saved for {} bytes",
+            size, checkpoint.size
+        ))));
+    }
+    if checkpoint.part_count() > MAX_PARTS {
+        return Err(MultipartError::TooManyParts);
+    }
+
+    let listed = list_all_parts(
+        client,
+        &checkpoint.bucket,
+        &checkpoint.key,
+        &checkpoint.upload_id,
+    )
+    .await
+    .map_err(MultipartError::ListParts)?;
+    let reconciliation = reconcile(checkpoint, &listed);
+    let mut parts = reconciliation
+        .verified
+        .into_iter()
+        .filter_map(|part| Some((part.part_number?, part)))
+        .collect::<BTreeMap<_, _>>();
+
+    for (part_number, divergence) in reconciliation.divergent {
+        let data = read_part(&file, checkpoint, part_number)
+            .await
+            .map_err(MultipartError::Body)?;
+        if let PartDivergence::Unrecorded(ref e_tag) = divergence {
+            if unquoted(e_tag) == format!("{:x}", md5::compute(&data)) {
+                checkpoint.record(part_number, e_tag);
+                parts.insert(
+                    part_number,
+                    CompletedPart {
+                        e_tag: Some(e_tag.clone()),
+                        part_number: Some(part_number),
+                    },
+                );
+                continue;
+            }
+        }
+        let part = upload_part(
+            client,
+            &checkpoint.bucket,
+            &checkpoint.key,
+            &checkpoint.upload_id,
+            part_number,
+            data,
+        )
+        .await?;
+        checkpoint.record(part_number, part.e_tag.as_deref().unwrap_or_default());
+        parts.insert(part_number, part);
+    }
+
+    complete(
+        client,
+        &checkpoint.bucket,
+        &checkpoint.key,
+        &checkpoint.upload_id,
+        parts.into_values().collect(),
+    )
+    .await
+}
@@ -0,0 +1,550 @@
+//! Moving the objects of a bucket to the `ARCHIVE` storage class and getting them back, with
+//! `ArchiveManager`:
+//!
+//! ```rust,ignore
+//! let manager = ArchiveManager::new(Arc::new(client), "backups");
+//! manager.archive_after("archive-logs", "logs/", 30).await?;
+//!
+//! manager.request_restores(keys, Some(7)).await;
+//! let mut progress = manager.watch();
+//! while let Some(progress) = progress.next().await {
+//!     println!("{} of {} restored", progress.restored, progress.total);
+//! }
+//! ```
+//!
+//! Objects are archived by a rule of the lifecycle configuration of the bucket, merged with the
+//! rules already there. An archived object can't be read until a copy of it was restored, which
+//! takes hours: the manager tracks the restores it requested and polls the objects until their
+//! copies are readable.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+
+use crate::core::error::Ks3Error;
+use crate::core::runtime::default_runtime;
+use crate::dates::parse_http_date;
+use crate::enums::StorageClass;
+use crate::exists::translate;
+use crate::request::*;
+use crate::s3::ops::*;
+use crate::s3::S3;
+
+/// The number of requests `ArchiveManager` sends at once by default.
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// The state of the restore of an archived object, as reported by the `restore` field of
+/// `HeadObjectOutput`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RestoreStatus {
+    /// The restore was requested and the copy isn't readable yet.
+    InProgress,
+    /// The restored copy is readable until `expiry_date`.
+    Restored {
+        /// When the restored copy expires, if the service reported it.
+        expiry_date: Option<DateTime<Utc>>,
+    },
+}
+
+impl RestoreStatus {
+    /// Parse the value of the `x-amz-restore` header, e.g.
+    /// `ongoing-request="false", expiry-date="Fri, 21 Dec 2012 00:00:00 GMT"`.
+    pub fn parse(header: &str) -> Option<RestoreStatus> {
+        let field = |name: &str| {
+            let start = header.find(&format!("{}=\"", name))? + name.len() + 2;
+            let len = header[start..].find('"')?;
+            Some(&header[start..start + len])
+        };
+        match field("ongoing-request")? {
+            "true" => Some(RestoreStatus::InProgress),
+            "false" => Some(RestoreStatus::Restored {
+                expiry_date: field("expiry-date").and_then(parse_http_date),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl HeadObjectOutput {
+    /// The state of the restore of the object, if one was requested.
+    pub fn restore_status(&self) -> Option<RestoreStatus> {
+        self.restore.as_deref().and_then(RestoreStatus::parse)
+    }
+}
+
+/// Where an object tracked by `ArchiveManager` stands.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RestoreState {
+    /// The restore was requested and hasn't completed yet.
+    Pending,
+    /// The restored copy is readable.
+    Restored {
+        /// When the restored copy expires, if known.
+        expiry_date: Option<DateTime<Utc>>,
+    },
+    /// The restore couldn't be requested, or the object disappeared, for this reason.
+    Failed(String),
+}
+
+/// The restores tracked by `ArchiveManager`, counted by state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RestoreProgress {
+    /// The number of objects tracked.
+    pub total: usize,
+    /// The number of restores still pending.
+    pub pending: usize,
+    /// The number of objects restored.
+    pub restored: usize,
+    /// The number of restores that failed.
+    pub failed: usize,
+    /// The objects whose state changed since the previous progress, with their new state.
+    pub changed: Vec<(String, RestoreState)>,
+}
+
+impl RestoreProgress {
+    /// Whether no restore is pending anymore.
+    pub fn is_done(&self) -> bool {
+        self.pending == 0
+    }
+}
+
+/// Errors returned by `ArchiveManager::archive_after`.
+#[derive(Debug)]
+pub enum ArchiveError {
+    /// The lifecycle configuration couldn't be read.
+    GetLifecycle(Ks3Error<GetBucketLifecycleConfigurationError>),
+    /// The lifecycle configuration couldn't be written.
+    PutLifecycle(Ks3Error<PutBucketLifecycleConfigurationError>),
+    /// The rule `rule_id` of the lifecycle configuration holds `elements` that aren't modeled,
+    /// so the configuration wasn't written back, which would have dropped them.
+    UnknownElements {
+        /// The ID of the rule, if it has one.
+        rule_id: Option<String>,
+        /// The elements that aren't modeled, by path, see `LifecycleRule::unknown_elements`.
+        elements: Vec<String>,
+    },
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArchiveError::GetLifecycle(ref err) => write!(f, "{}", err),
+            ArchiveError::PutLifecycle(ref err) => write!(f, "{}", err),
+            ArchiveError::UnknownElements {
+                ref rule_id,
+                ref elements,
+            } => write!(
+                f,
+                "Lifecycle rule {:?} has elements that can't be written back: {}",
+                rule_id.as_deref().unwrap_or(""),
+                elements.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for ArchiveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ArchiveError::GetLifecycle(ref err) => Some(err),
+            ArchiveError::PutLifecycle(ref err) => Some(err),
+            ArchiveError::UnknownElements { .. } => None,
+        }
+    }
+}
+
+type Restores = Arc<Mutex<BTreeMap<String, RestoreState>>>;
+
+/// Archives the objects of a bucket with lifecycle rules, and restores them, tracking the
+/// restores it requested.
+///
+/// The manager can be cloned, the clones sharing the restores tracked, e.g. to request more
+/// restores while watching the progress of the previous ones.
+pub struct ArchiveManager<C: S3 + Send + Sync + 'static> {
+    client: Arc<C>,
+    bucket: String,
+    concurrency: usize,
+    poll_interval: Duration,
+    max_poll_interval: Duration,
+    restores: Restores,
+}
+
+impl<C: S3 + Send + Sync + 'static> ArchiveManager<C> {
+    /// A manager of the objects of `bucket`, sending 8 requests at once and polling the
+    /// pending restores every minute at first, backing off to every hour.
+    pub fn new(client: Arc<C>, bucket: &str) -> ArchiveManager<C> {
+        ArchiveManager {
+            client,
+            bucket: bucket.to_owned(),
+            concurrency: DEFAULT_CONCURRENCY,
+            poll_interval: Duration::from_secs(60),
+            max_poll_interval: Duration::from_secs(3600),
+            restores: Arc::default(),
+        }
+    }
+
+    /// The manager sending up to `concurrency` requests at once instead.
+    pub fn with_concurrency(mut self, concurrency: usize) -> ArchiveManager<C> {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// The manager polling the pending restores every `interval` instead, doubling the delay
+    /// after each round in which no restore completed, up to `max_interval`.
+    pub fn with_poll_interval(
+        mut self,
+        interval: Duration,
+        max_interval: Duration,
+    ) -> ArchiveManager<C> {
+        self.poll_interval = interval;
+        self.max_poll_interval = max_interval.max(interval);
+        self
+    }
+
+    /// Archive the objects under `prefix` `days` days after their creation, with the lifecycle
+    /// rule `rule_id`.
+    ///
+    /// The rule replaces the rule of the same ID, if any, and the other rules of the bucket are
+    /// kept. The configuration is left as it is, and `ArchiveError::UnknownElements` returned,
+    /// when one of the other rules holds elements that aren't modeled and would be lost. Objects already older than `days` are archived as the service next applies the
+    /// configuration, which can take a day.
+    pub async fn archive_after(
+        &self,
+        rule_id: &str,
+        prefix: &str,
+        days: i64,
+    ) -> Result<(), ArchiveError> {
+        let input = GetBucketLifecycleConfigurationRequest {
+            bucket: self.bucket.clone(),
+        };
+        let mut rules = match self.client.get_bucket_lifecycle_configuration(input).await {
+            Ok(output) => output.rules.unwrap_or_default(),
+            Err(err) => match err.into_inner() {
+                Ks3Error::Service(
+                    GetBucketLifecycleConfigurationError::NoSuchLifecycleConfiguration(_),
+                ) => Vec::new(),
+                err => return Err(ArchiveError::GetLifecycle(err)),
+            },
+        };
+        rules.retain(|rule| rule.id.as_deref() != Some(rule_id));
+        if let Some(rule) = rules
+            .iter()
+            .find(|rule| !rule.unknown_elements().is_empty())
+        {
+            return Err(ArchiveError::UnknownElements {
+                rule_id: rule.id.clone(),
+                elements: rule.unknown_elements(),
+            });
+        }
+        rules.push(LifecycleRule {
+            id: Some(rule_id.to_owned()),
+            status: "Enabled".to_owned(),
+            filter: Some(LifecycleRuleFilter {
+                prefix: Some(prefix.to_owned()),
+                ..Default::default()
+            }),
+            transitions: Some(vec![Transition {
+                days: Some(days),
+                storage_class: Some(StorageClass::Archive),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+        let input = PutBucketLifecycleConfigurationRequest {
+            bucket: self.bucket.clone(),
+            lifecycle_configuration: BucketLifecycleConfiguration { rules },
+        };
+        self.client
+            .put_bucket_lifecycle_configuration(input)
+            .await
+            .map_err(ArchiveError::PutLifecycle)
+    }
+
+    /// Request the restore of each of `keys`, for `days` days or the service's default, and
+    /// track it. Restores already in progress are tracked as pending, and failed requests as
+    /// failed rather than returned: the progress tells how many went through.
+    pub async fn request_restores<I>(&self, keys: I, days: Option<i64>) -> RestoreProgress
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let client = &self.client;
+        let bucket = &self.bucket;
+        let outcomes = stream::iter(keys)
+            .map(|key| async move {
+                let input = RestoreObjectRequest {
+                    bucket: bucket.clone(),
+                    key: key.clone(),
+                    days,
+                    version_id: None,
+                };
+                let state = match client.restore_object(input).await {
+                    Ok(output) if output.already_restored => {
+                        RestoreState::Restored { expiry_date: None }
+                    }
+                    Ok(_) => RestoreState::Pending,
+                    Err(err) => match err.into_inner() {
+                        Ks3Error::Service(RestoreObjectError::RestoreAlreadyInProgress(_)) => {
+                            RestoreState::Pending
+                        }
+                        err => RestoreState::Failed(err.to_string()),
+                    },
+                };
+                (key, state)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+        update(&self.restores, outcomes)
+    }
+
+    /// The state of the restore of `key`, if it's tracked.
+    pub fn state(&self, key: &str) -> Option<RestoreState> {
+        self.restores.lock().unwrap().get(key).cloned()
+    }
+
+    /// The restores tracked, counted by state, with no change listed.
+    pub fn progress(&self) -> RestoreProgress {
+        update(&self.restores, Vec::new())
+    }
+
+    /// Stop tracking the restores that are no longer pending.
+    pub fn forget_completed(&self) {
+        self.restores
+            .lock()
+            .unwrap()
+            .retain(|_, state| *state == RestoreState::Pending);
+    }
+
+    /// Poll the pending restores until none is left, yielding the progress after each round.
+    ///
+    /// The first round is sent right away. The delay before the next one starts at the poll
+    /// interval and doubles after each round in which no restore completed, up to the maximum
+    /// interval. Objects that disappeared are marked as failed; other errors, e.g. of the
+    /// connection, leave the restores pending until the next round. The stream ends after the
+    /// progress reporting the last pending restore, right away if none is pending.
+    pub fn watch(&self) -> Pin<Box<dyn Stream<Item = RestoreProgress> + Send>> {
+        let watch = Watch {
+            client: self.client.clone(),
+            bucket: self.bucket.clone(),
+            concurrency: self.concurrency,
+            restores: self.restores.clone(),
+            delay: None,
+            interval: self.poll_interval,
+            max_interval: self.max_poll_interval,
+        };
+        Box::pin(stream::unfold(watch, |mut watch| async move {
+            let progress = watch.poll().await?;
+            Some((progress, watch))
+        }))
+    }
+}
+
+impl<C: S3 + Send + Sync + 'static> Clone for ArchiveManager<C> {
+    fn clone(&self) -> ArchiveManager<C> {
+        ArchiveManager {
+            client: self.client.clone(),
+            bucket: self.bucket.clone(),
+            concurrency: self.concurrency,
+            poll_interval: self.poll_interval,
+            max_poll_interval: self.max_poll_interval,
+            restores: self.restores.clone(),
+        }
+    }
+}
+
+impl<C: S3 + Send + Sync + 'static> fmt::Debug for ArchiveManager<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArchiveManager")
+            .field("bucket", &self.bucket)
+            .field("concurrency", &self.concurrency)
+            .field("poll_interval", &self.poll_interval)
+            .field("max_poll_interval", &self.max_poll_interval)
+            .field("restores", &self.progress())
+            .finish()
+    }
+}
+
+/// Record `changes` in `restores`, returning the progress listing those that changed a state.
+fn update(restores: &Restores, changes: Vec<(String, RestoreState)>) -> RestoreProgress {
+    let mut restores = restores.lock().unwrap();
+    let mut changed = Vec::new();
+    for (key, state) in changes {
+        if restores.get(&key) != Some(&state) {
+            restores.insert(key.clone(), state.clone());
+            changed.push((key, state));
+        }
+    }
+    let mut progress = RestoreProgress {
+        total: restores.len(),
+        changed,
+        ..Default::default()
+    };
+    for state in restores.values() {
+        match *state {
+            RestoreState::Pending => progress.pending += 1,
+            RestoreState::Restored { .. } => progress.restored += 1,
+            RestoreState::Failed(_) => progress.failed += 1,
+        }
+    }
+    progress
+}
+
+/// The state of the stream returned by `ArchiveManager::watch`.
+struct Watch<C> {
+    client: Arc<C>,
+    bucket: String,
+    concurrency: usize,
+    restores: Restores,
+    /// The delay before the next round, none before the first one.
+    delay: Option<Duration>,
+    interval: Duration,
+    max_interval: Duration,
+}
+
+impl<C: S3 + Send + Sync + 'static> Watch<C> {
+    /// Check the pending restores once, after the delay, unless none is pending.
+    async fn poll(&mut self) -> Option<RestoreProgress> {
+        let pending = self
+            .restores
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, state)| **state == RestoreState::Pending)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return None;
+        }
+        if let Some(delay) = self.delay {
+            default_runtime().sleep(delay).await;
+        }
+
+        let client = &self.client;
+        let bucket = &self.bucket;
+        let changes = stream::iter(pending)
+            .map(|key| async move {
+                let state = check(&**client, bucket, &key).await;
+                state.map(|state| (key, state))
+            })
+            .buffer_unordered(self.concurrency)
+            .filter_map(|change| async move { change })
+            .collect::<Vec<_>>()
+            .await;
+        let progress = update(&self.restores, changes);
+
+        self.delay = Some(match self.delay {
+            Some(delay) if progress.changed.is_empty() => (delay * 2).min(self.max_interval),
+            _ => self.interval,
+        });
+        Some(progress)
+    }
+}
+
+/// The state of the restore of `key`, unless it's still pending or couldn't be checked.
+async fn check<C: S3 + Sync>(client: &C, bucket: &str, key: &str) -> Option<RestoreState> {
+    let input = HeadObjectRequest {
+        bucket: bucket.to_owned(),
+        key: key.to_owned(),
+        ..Default::default()
+    };
+    match client.head_object(input).await {
+        Ok(output) => match output.restore_status() {
+            Some(RestoreStatus::Restored { expiry_date }) => {
+                Some(RestoreState::Restored { expiry_date })
+            }
+            Some(RestoreStatus::InProgress) => None,
+            // An object moved out of the archive can be read as it is.
+            None if output.storage_class != Some(StorageClass::Archive) => {
+                Some(RestoreState::Restored { expiry_date: None })
+            }
+            None => None,
+        },
+        Err(err) => {
+            let message = err.to_string();
+            match translate(err) {
+                None => Some(RestoreState::Failed(message)),
+                Some(_) => None,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::credential::StaticProvider;
+    use crate::s3::S3Client;
+    use crate::signature::signer::SignedRequestPayload;
+    use crate::signature::Region;
+    use crate::test::MockRequestDispatcher;
+
+    /// A manager whose requests all get `body`, recording the payloads of the `PUT`s.
+    fn manager(body: &'static str) -> (ArchiveManager<S3Client>, Arc<Mutex<Vec<String>>>) {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let recorded = written.clone();
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_body(body)
+            .with_request_checker(move |request| {
+                if let Some(SignedRequestPayload::Buffer(ref payload)) = request.payload {
+                    if request.method() == "PUT" {
+                        let payload = String::from_utf8(payload.to_vec()).unwrap();
+                        recorded.lock().unwrap().push(payload);
+                    }
+                }
+            });
+        let client = S3Client::new_with(
+            dispatcher,
+            StaticProvider::new_minimal("a".to_owned(), "b".to_owned()),
+            Region::default(),
+        );
+        (ArchiveManager::new(Arc::new(client), "bucket"), written)
+    }
+
+    #[tokio::test]
+    async fn archive_after_keeps_the_other_rules() {
+        let (manager, written) = manager(
+            "<LifecycleConfiguration><Rule><ID>tmp</ID>\
+            <Filter><And><Prefix>tmp/</Prefix><Tag><Key>k</Key><Value>v</Value></Tag></And></Filter>\
+            <Status>Enabled</Status><Expiration><Days>1</Days></Expiration>\
+            </Rule></LifecycleConfiguration>",
+        );
+        manager.archive_after("archive", "logs/", 30).await.unwrap();
+
+        let written = written.lock().unwrap();
+        assert_eq!(written.len(), 1);
+        assert!(
+            written[0].contains(
+                "<Filter><And><Prefix>tmp/</Prefix><Tag><Key>k</Key><Value>v</Value></Tag></And></Filter>"
+            ),
+            "{}",
+            written[0]
+        );
+        assert!(written[0].contains("<ID>archive</ID>"), "{}", written[0]);
+    }
+
+    #[tokio::test]
+    async fn archive_after_refuses_unknown_elements() {
+        let (manager, written) = manager(
+            "<LifecycleConfiguration><Rule><ID>tmp</ID>\
+            <Filter><Or><Prefix>tmp/</Prefix></Or></Filter>\
+            <Status>Enabled</Status><Expiration><Days>1</Days></Expiration>\
+            </Rule></LifecycleConfiguration>",
+        );
+        match manager.archive_after("archive", "logs/", 30).await {
+            Err(ArchiveError::UnknownElements { rule_id, elements }) => {
+                assert_eq!(rule_id.as_deref(), Some("tmp"));
+                assert_eq!(elements, vec!["Filter/Or"]);
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+        assert!(written.lock().unwrap().is_empty());
+    }
+}
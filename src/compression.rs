@@ -0,0 +1,145 @@
+//! Gzip compression of uploads whose content compresses well, such as logs and text.
+//!
+//! Compressed objects are stored with `Content-Encoding: gzip`, so that HTTP clients
+//! downloading them decompress them transparently. Their ETag is the digest of the compressed
+//! bytes, and their size the compressed size.
+
+use std::io::{self, Write};
+
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::{stream, StreamExt};
+
+use crate::mime::mime_type_for_key;
+use crate::request::PutObjectRequest;
+use crate::signature::ByteStream;
+
+/// The `Content-Encoding` of compressed uploads.
+pub const GZIP_ENCODING: &str = "gzip";
+
+/// Which uploads to compress, and how.
+#[derive(Clone, Debug)]
+pub struct UploadCompression {
+    /// The content types to compress. Entries ending with `/`, such as `text/`, match every
+    /// subtype. Parameters such as `; charset=utf-8` are ignored.
+    pub content_types: Vec<String>,
+    /// Bodies smaller than this many bytes are sent as they are.
+    pub min_size: usize,
+    /// The gzip level, from 0 (no compression) to 9 (best compression).
+    pub level: u32,
+}
+
+impl Default for UploadCompression {
+    fn default() -> Self {
+        UploadCompression {
+            content_types: vec![
+                "text/".to_owned(),
+                "application/json".to_owned(),
+                "application/x-ndjson".to_owned(),
+                "application/xml".to_owned(),
+                "application/javascript".to_owned(),
+            ],
+            min_size: 1024,
+            level: 6,
+        }
+    }
+}
+
+impl UploadCompression {
+    /// Whether uploads of `content_type` are compressed.
+    pub fn accepts(&self, content_type: Option<&str>) -> bool {
+        let content_type = match content_type {
+            Some(content_type) => content_type,
+            None => return false,
+        };
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        self.content_types.iter().any(|allowed| {
+            let allowed = allowed.to_ascii_lowercase();
+            if allowed.ends_with('/') {
+                essence.starts_with(&allowed)
+            } else {
+                essence == allowed
+            }
+        })
+    }
+
+    /// Compress `data` in one go.
+    pub fn gzip(&self, data: &[u8]) -> io::Result<Bytes> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::new(self.level));
+        encoder.write_all(data)?;
+        Ok(Bytes::from(encoder.finish()?))
+    }
+
+    /// Compress `body` as it is read. The length of the compressed stream isn't known in
+    /// advance, so it can only be uploaded in parts, e.g. with `multipart::upload_stream`.
+    pub fn gzip_stream(&self, body: ByteStream) -> ByteStream {
+        let encoder = GzEncoder::new(Vec::new(), Compression::new(self.level));
+        let chunks = stream::unfold(Some((body, encoder)), |state| async move {
+            let (mut body, mut encoder) = state?;
+            loop {
+                match body.next().await {
+                    Some(Ok(chunk)) => {
+                        if let Err(err) = encoder.write_all(&chunk) {
+                            return Some((Err(err), None));
+                        }
+                        let compressed = std::mem::take(encoder.get_mut());
+                        if !compressed.is_empty() {
+                            return Some((Ok(Bytes::from(compressed)), Some((body, encoder))));
+                        }
+                    }
+                    Some(Err(err)) => return Some((Err(err), None)),
+                    None => {
+                        return match encoder.finish() {
+                            Ok(rest) => Some((Ok(Bytes::from(rest)), None)),
+                            Err(err) => Some((Err(err), None)),
+                        }
+                    }
+                }
+            }
+        });
+        ByteStream::new(chunks)
+    }
+
+    /// `input` with its body compressed, if it's eligible: an in-memory body of at least
+    /// `min_size` bytes and an accepted content type, without a `Content-Encoding` or a
+    /// `Content-MD5` of its own. The body is left as it is when compressing doesn't make it
+    /// smaller.
+    pub(crate) fn compress_request(
+        &self,
+        mut input: PutObjectRequest,
+        infer_content_type: bool,
+    ) -> PutObjectRequest {
+        if input.content_encoding.is_some() || input.content_md5.is_some() {
+            return input;
+        }
+        let content_type = input.content_type.as_deref().or_else(|| {
+            if infer_content_type {
+                mime_type_for_key(&input.key)
+            } else {
+                None
+            }
+        });
+        if !self.accepts(content_type) {
+            return input;
+        }
+        let compressed = match input.body.as_ref().and_then(ByteStream::buffered) {
+            Some(body) if body.len() >= self.min_size => match self.gzip(body) {
+                Ok(compressed) if compressed.len() < body.len() => compressed,
+                _ => return input,
+            },
+            _ => return input,
+        };
+        if input.content_length.is_some() {
+            input.content_length = Some(compressed.len() as i64);
+        }
+        input.body = Some(ByteStream::from(compressed));
+        input.content_encoding = Some(GZIP_ENCODING.to_owned());
+        input
+    }
+}
@@ -0,0 +1,97 @@
+//! Reading an object again only if it changed since a copy was cached locally.
+
+use chrono::{DateTime, Utc};
+
+use crate::core::error::Ks3Error;
+use crate::request::*;
+use crate::s3::{S3Client, S3};
+
+/// The version of an object held in a local cache, as identified by its ETag or its last
+/// modification time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CachedVersion {
+    /// The ETag of the cached copy, sent as `If-None-Match`.
+    pub e_tag: Option<String>,
+    /// When the cached copy was last modified, sent as `If-Modified-Since`. The service only
+    /// considers it when no ETag is sent.
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl From<&GetObjectOutput> for CachedVersion {
+    fn from(output: &GetObjectOutput) -> CachedVersion {
+        CachedVersion {
+            e_tag: output.e_tag.clone(),
+            last_modified: output.last_modified,
+        }
+    }
+}
+
+impl From<&HeadObjectOutput> for CachedVersion {
+    fn from(output: &HeadObjectOutput) -> CachedVersion {
+        CachedVersion {
+            e_tag: output.e_tag.clone(),
+            last_modified: output.last_modified,
+        }
+    }
+}
+
+/// The outcome of `S3Client::get_object_if_modified`.
+#[derive(Debug)]
+pub enum ConditionalGet {
+    /// The object changed; here is its new content.
+    Modified(Box<GetObjectOutput>),
+    /// The cached copy is still current.
+    NotModified,
+}
+
+impl ConditionalGet {
+    /// The new content of the object, if it changed.
+    pub fn modified(self) -> Option<GetObjectOutput> {
+        match self {
+            ConditionalGet::Modified(output) => Some(*output),
+            ConditionalGet::NotModified => None,
+        }
+    }
+
+    /// Whether the cached copy is still current.
+    pub fn is_not_modified(&self) -> bool {
+        matches!(self, ConditionalGet::NotModified)
+    }
+}
+
+/// Whether `err` is the `304 Not Modified` answer to a conditional request.
+fn is_not_modified(err: &Ks3Error<GetObjectError>) -> bool {
+    match *err {
+        Ks3Error::Service(GetObjectError::NotModified { .. }) => true,
+        Ks3Error::Captured { ref error, .. } => is_not_modified(error),
+        _ => false,
+    }
+}
+
+impl S3Client {
+    /// Read the object `key` in `bucket`, unless it still is the version `cached`.
+    ///
+    /// The request is sent with `If-None-Match` and `If-Modified-Since` from `cached`, and the
+    /// `304 Not Modified` response the service answers with when the object didn't change is
+    /// returned as `ConditionalGet::NotModified` rather than as an error. Without an ETag or a
+    /// date in `cached`, the object is always read.
+    pub async fn get_object_if_modified(
+        &self,
+        bucket: &str,
+        key: &str,
+        cached: &CachedVersion,
+    ) -> Result<ConditionalGet, Ks3Error<GetObjectError>> {
+        let input = GetObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            if_none_match: cached.e_tag.clone(),
+            if_modified_since: cached.last_modified,
+            ..Default::default()
+        };
+        match self.get_object(input).await {
+            Ok(output) => Ok(ConditionalGet::Modified(Box::new(output))),
+            Err(ref err) if is_not_modified(err) => Ok(ConditionalGet::NotModified),
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Client-wide settings for `S3Client`, and the settings of single calls overriding them.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::audit::AuditLog;
+use crate::bucket_profile::BucketProfile;
+#[cfg(feature = "encoding")]
+use crate::compression::UploadCompression;
+use crate::core::WireLog;
+use crate::deletion::DeletionProtection;
+use crate::enums::{CannedAcl, ServerSideEncryption, StorageClass};
+use crate::header_dialect::HeaderDialect;
+use crate::hedge::HedgePolicy;
+use crate::metrics::MetricsObserver;
+use crate::object_cache::ObjectCache;
+use crate::signature::{ContentLengthPolicy, DefaultContentType, Region, UriEncoding};
+
+/// Settings applied by `S3Client` to the requests it sends.
+///
+/// Fields named `default_*` fill in the matching request field when a request leaves it unset;
+/// a value set on the request always wins.
+#[derive(Clone, Debug, Default)]
+pub struct Ks3Config {
+    /// Fill in a missing `Content-Type` on uploads from the extension of the object key. When
+    /// disabled, the service stores such objects as `binary/octet-stream`.
+    pub infer_content_type: bool,
+    /// Compute the `Content-MD5` of uploads and parts whose body was created from an in-memory
+    /// buffer, and check the returned ETag against it.
+    pub compute_content_md5: bool,
+    /// The `Content-Type` of requests sending a body without one, filled in before they are
+    /// signed. None is sent by default; some gateways reject bodies without a type, while
+    /// others expect the header absent when checking signatures.
+    pub default_content_type: DefaultContentType,
+    /// When the `Content-Length` header is sent. By default requests with a body carry it, and
+    /// so do `PUT` and `POST` requests without one, while `GET`, `HEAD` and `DELETE` requests
+    /// without a body don't, as some gateways reject them with `Content-Length: 0`.
+    pub content_length_policy: ContentLengthPolicy,
+    /// The canned ACL of uploaded objects, e.g. `private` or `public-read`.
+    pub default_acl: Option<CannedAcl>,
+    /// The storage class of uploaded objects, e.g. `STANDARD_IA`.
+    pub default_storage_class: Option<StorageClass>,
+    /// The server-side encryption algorithm of uploaded objects, e.g. `AES256`.
+    pub default_server_side_encryption: Option<ServerSideEncryption>,
+    /// Build object URLs with the bucket in the host name (`bucket.endpoint/key`) instead of the
+    /// path (`endpoint/bucket/key`). Buckets whose name isn't a valid host label keep the path
+    /// style.
+    pub virtual_hosted_urls: bool,
+    /// A domain bound to the bucket, such as a CDN domain, on which object URLs are built
+    /// instead of the endpoint, e.g. `https://cdn.example.com/key`. Defaults to `https` when no
+    /// scheme is given.
+    pub custom_domain: Option<String>,
+    /// Attach the request to the errors of requests that failed to be sent or got an error
+    /// response, keeping up to this many bytes of its payload. The errors are then wrapped in
+    /// `Ks3Error::Captured`, whose `into_inner` returns the original error. Meant for
+    /// debugging, e.g. comparing a failing request with one that works from another tool.
+    pub capture_failed_requests: Option<usize>,
+    /// Told of every request the client sends, e.g. to export request counts and latencies.
+    pub metrics: Option<Arc<dyn MetricsObserver>>,
+    /// Log every request and response at debug level to the `ks3::wire` target, with their
+    /// secrets redacted.
+    pub wire_log: Option<WireLog>,
+    /// Told of every request that changes a bucket or an object, with the access key ID it was
+    /// signed with and its outcome, e.g. to keep an audit trail.
+    pub audit_log: Option<Arc<dyn AuditLog>>,
+    /// Resume the body of `get_object` from the last byte received when reading it fails, e.g.
+    /// after a connection reset, up to this many times in a row. The rest of the object is
+    /// requested with `If-Match` on its ETag, so the body fails instead if the object was
+    /// replaced in the meantime.
+    pub resume_downloads: Option<u32>,
+    /// Gzip the in-memory bodies of `put_object` whose content type is accepted, storing them
+    /// with `Content-Encoding: gzip`. Streamed bodies are sent as they are, as their compressed
+    /// length isn't known in advance; `transfer::UploadOptions::compression` compresses files
+    /// on the fly instead.
+    #[cfg(feature = "encoding")]
+    pub compress_uploads: Option<UploadCompression>,
+    /// Send `get_object` requests of a `Range` a second time when no response arrived after
+    /// the delay of the policy, and use whichever response arrives first.
+    pub hedge_gets: Option<HedgePolicy>,
+    /// Settings of the requests to some buckets, by bucket name, taking precedence over those
+    /// above. See `S3Client::set_bucket_profile`.
+    pub bucket_profiles: HashMap<String, BucketProfile>,
+    /// Skip or refuse the requests deleting buckets, objects and bucket configurations, e.g. to
+    /// preview what a cleanup would delete. Deletions are sent by default.
+    pub deletion_protection: DeletionProtection,
+    /// Serve `get_object` from an in-memory cache of the objects and ranges read recently, see
+    /// `ObjectCache`.
+    pub object_cache: Option<ObjectCache>,
+    /// Send the extension headers with the `x-kss-` prefix of KS3 instead of the `x-amz-`
+    /// prefix of S3. `x-amz-` headers are sent by default.
+    pub header_dialect: HeaderDialect,
+    /// How the path and the query string of requests are percent-encoded, and the object URLs
+    /// built by `S3Client::object_url`. `UriEncoding::Strict`, following RFC 3986, by
+    /// default; some S3-compatible gateways need another encoding.
+    pub uri_encoding: UriEncoding,
+    /// Fail requests with `Ks3Error::HttpDispatch` when no response arrived after this long.
+    /// Only the wait for the status and headers is covered, not the transfer of the body.
+    /// Requests wait indefinitely by default.
+    pub request_timeout: Option<Duration>,
+    /// The deadline of `complete_multipart_upload`, instead of `request_timeout`. Completing a
+    /// large upload can take minutes, during which the service or a gateway may already answer
+    /// `200 OK` and send whitespace to keep the connection alive, so the deadline covers the
+    /// whole response. Completions wait indefinitely by default.
+    pub complete_multipart_upload_timeout: Option<Duration>,
+    /// Fail the body of a response when no bytes of it arrived for this long, e.g. because the
+    /// connection got stuck, instead of waiting for it indefinitely. Bodies read by the client
+    /// fail with `Ks3Error::Timeout`, and those handed over, such as the body of `get_object`,
+    /// with an `io::ErrorKind::TimedOut` error wrapping `BodyStalled`. Disabled by default.
+    pub response_idle_timeout: Option<Duration>,
+    /// The `Accept-Encoding` header of `get_object` and `head_object` requests. None is sent by
+    /// default, which lets some gateways compress bodies on the fly, so that the `Content-Length`
+    /// and ETag of the object no longer match the bytes received; `AcceptEncoding::Identity`
+    /// asks for the stored bytes. Bodies compressed following `AcceptEncoding::Encodings` are
+    /// returned as received, see `GetObjectOutput::negotiated_encoding`.
+    pub accept_encoding: Option<AcceptEncoding>,
+}
+
+/// The encodings advertised with `Accept-Encoding`, see `Ks3Config::accept_encoding`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AcceptEncoding {
+    /// Ask for the bytes as they are stored, without any encoding applied on the way.
+    Identity,
+    /// Accept bodies in these encodings, e.g. `gzip`, in order of preference, or as stored.
+    Encodings(Vec<String>),
+}
+
+impl AcceptEncoding {
+    /// The value of the `Accept-Encoding` header.
+    pub fn header_value(&self) -> String {
+        match *self {
+            AcceptEncoding::Identity => "identity".to_owned(),
+            AcceptEncoding::Encodings(ref encodings) if encodings.is_empty() => {
+                "identity".to_owned()
+            }
+            AcceptEncoding::Encodings(ref encodings) => encodings.join(", "),
+        }
+    }
+
+    /// The encoding of a response with `content_encoding`, if it's one of those accepted.
+    pub(crate) fn negotiated(&self, content_encoding: Option<&str>) -> Option<String> {
+        let content_encoding = content_encoding?.trim();
+        match *self {
+            AcceptEncoding::Identity => None,
+            AcceptEncoding::Encodings(ref encodings) => encodings
+                .iter()
+                .find(|encoding| encoding.trim().eq_ignore_ascii_case(content_encoding))
+                .map(|_| content_encoding.to_ascii_lowercase()),
+        }
+    }
+}
+
+/// Settings of the requests of some calls, overriding those of the client, e.g. a short deadline
+/// for latency-critical reads and retries for background writes sent with the same client:
+///
+/// ```rust,ignore
+/// let fast = OperationConfig {
+///     timeout: Some(Duration::from_millis(500)),
+///     ..Default::default()
+/// };
+/// let output = client.customize(fast).get_object(input).await?;
+/// ```
+///
+/// See `S3Client::customize`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OperationConfig {
+    /// Fail each request with `Ks3Error::HttpDispatch` when no response arrived after this
+    /// long, instead of `Ks3Config::request_timeout`, or of
+    /// `Ks3Config::complete_multipart_upload_timeout` for completions.
+    pub timeout: Option<Duration>,
+    /// Send a request again, up to this many times, when it couldn't be sent or the service
+    /// answered with a `5xx` status, waiting 200 ms before the first retry and twice as long
+    /// before every further one. Requests with a streaming body are only sent once. Requests
+    /// aren't retried by default.
+    pub retries: Option<u32>,
+    /// Send the requests to this region or endpoint, e.g. a `Region::Custom`, instead of the
+    /// region of the client or of the profile of the bucket.
+    pub endpoint: Option<Region>,
+}
@@ -0,0 +1,125 @@
+//! Snapshots of signed requests, attached to errors when `Ks3Config::capture_failed_requests`
+//! is set.
+
+use std::fmt;
+
+use bytes::Bytes;
+
+use crate::signature::signer::SignedRequestPayload;
+use crate::signature::SignedRequest;
+
+/// The value shown in place of a secret.
+pub const REDACTED: &str = "REDACTED";
+
+/// Request headers whose values are never captured.
+pub(crate) const SECRET_HEADERS: &[&str] = &[
+    "authorization",
+    "x-amz-security-token",
+    "x-amz-server-side-encryption-customer-key",
+    "x-amz-copy-source-server-side-encryption-customer-key",
+];
+
+/// Query parameters whose values are never captured.
+pub(crate) const SECRET_PARAMS: &[&str] = &["KSSAccessKeyId", "Signature", "x-amz-security-token"];
+
+/// Whether the values of the request header `name` are secrets.
+pub(crate) fn is_secret_header(name: &str) -> bool {
+    SECRET_HEADERS
+        .iter()
+        .any(|secret| secret.eq_ignore_ascii_case(name))
+}
+
+/// The encoded query string of `request`, with the values of `SECRET_PARAMS` redacted.
+pub(crate) fn redacted_query(request: &SignedRequest) -> String {
+    request
+        .canonical_query_string()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if SECRET_PARAMS.contains(&name) => format!("{}={}", name, REDACTED),
+            _ => pair.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// A request as it was sent, with its secrets redacted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapturedRequest {
+    /// The HTTP method.
+    pub method: String,
+    /// The URL, including the encoded query string.
+    pub url: String,
+    /// The headers, in the order they were sent.
+    pub headers: Vec<(String, String)>,
+    /// The start of the payload. Streamed payloads aren't captured.
+    pub body: Bytes,
+    /// The length of the payload, if known.
+    pub body_len: Option<usize>,
+}
+
+impl CapturedRequest {
+    /// Capture `request`, keeping up to `max_body` bytes of its payload.
+    pub fn new(request: &SignedRequest, max_body: usize) -> CapturedRequest {
+        let query = redacted_query(request);
+        let mut url = format!(
+            "{}://{}{}",
+            request.scheme(),
+            request.hostname(),
+            request.canonical_uri()
+        );
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query);
+        }
+
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let name = name.as_str();
+                let value = if is_secret_header(name) {
+                    REDACTED.to_owned()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.to_owned(), value)
+            })
+            .collect();
+
+        let (body, body_len) = match request.payload {
+            None => (Bytes::new(), Some(0)),
+            Some(SignedRequestPayload::Buffer(ref payload)) => (
+                payload.slice(..payload.len().min(max_body)),
+                Some(payload.len()),
+            ),
+            Some(SignedRequestPayload::Stream(ref stream)) => (Bytes::new(), stream.size_hint()),
+        };
+
+        CapturedRequest {
+            method: request.method().to_owned(),
+            url,
+            headers,
+            body,
+            body_len,
+        }
+    }
+}
+
+/// Renders the request like an HTTP/1.1 request, with the captured part of the payload.
+impl fmt::Display for CapturedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} {}", self.method, self.url)?;
+        for (name, value) in &self.headers {
+            writeln!(f, "{}: {}", name, value)?;
+        }
+        if !self.body.is_empty() {
+            writeln!(f)?;
+            write!(f, "{}", String::from_utf8_lossy(&self.body))?;
+            if self.body_len.is_none_or(|len| len > self.body.len()) {
+                write!(f, "...")?;
+            }
+        }
+        Ok(())
+    }
+}
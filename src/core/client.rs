@@ -1,16 +1,26 @@
 use async_trait::async_trait;
+#[cfg(not(target_arch = "wasm32"))]
 use lazy_static::lazy_static;
-use tokio::time;
+use tracing::{debug_span, field, Instrument, Span};
 
+use crate::core::capture::CapturedRequest;
+use crate::core::clock::{Clock, Instant, SystemClock};
 use crate::core::encoding::ContentEncoding;
-use crate::core::request::{DispatchSignedRequest, HttpClient, HttpDispatchError, HttpResponse};
-use crate::credential::{
-    Anonymous, CredentialsError, DefaultCredentialsProvider, ProvideAwsCredentials, StaticProvider,
-};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::core::request::HttpClient;
+use crate::core::request::{DispatchSignedRequest, HttpDispatchError, HttpResponse};
+use crate::core::runtime;
+use crate::core::wire_log::WireLog;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::credential::DefaultCredentialsProvider;
+use crate::credential::{Anonymous, CredentialsError, ProvideAwsCredentials, StaticProvider};
 use crate::signature::SignedRequest;
-use std::sync::{Arc, Mutex, Weak};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Mutex, Weak};
 use std::time::Duration;
 
+#[cfg(not(target_arch = "wasm32"))]
 lazy_static! {
     static ref SHARED_CLIENT: Mutex<Weak<ClientInner<DefaultCredentialsProvider, HttpClient>>> =
         Mutex::new(Weak::new());
@@ -24,6 +34,7 @@ pub struct Client {
 
 impl Client {
     /// Return the shared default client.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn shared() -> Self {
         let mut lock = SHARED_CLIENT.lock().unwrap();
         if let Some(inner) = lock.upgrade() {
@@ -103,10 +114,80 @@ impl Client {
         &self,
         request: SignedRequest,
     ) -> Result<HttpResponse, SignAndDispatchError> {
-        self.inner.sign_and_dispatch(request, None).await
+        self.sign_and_dispatch_with_clock(request, Arc::new(SystemClock))
+            .await
+    }
+
+    /// Fetch credentials, sign the request at the time given by `clock` and dispatch it.
+    pub async fn sign_and_dispatch_with_clock(
+        &self,
+        request: SignedRequest,
+        clock: Arc<dyn Clock>,
+    ) -> Result<HttpResponse, SignAndDispatchError> {
+        self.inner
+            .sign_and_dispatch(request, None, clock, DispatchOptions::default())
+            .await
+            .result
+    }
+
+    /// Like `sign_and_dispatch_with_clock`, also returning the request as it was sent, with up
+    /// to `max_body` bytes of its payload. No request is returned when signing failed.
+    pub async fn sign_and_dispatch_captured(
+        &self,
+        request: SignedRequest,
+        clock: Arc<dyn Clock>,
+        max_body: usize,
+    ) -> (
+        Result<HttpResponse, SignAndDispatchError>,
+        Option<CapturedRequest>,
+    ) {
+        let options = DispatchOptions {
+            capture: Some(max_body),
+            ..Default::default()
+        };
+        let outcome = self
+            .sign_and_dispatch_with_options(request, clock, options)
+            .await;
+        (outcome.result, outcome.captured)
+    }
+
+    /// Like `sign_and_dispatch_with_clock`, with the optional behaviors of `options`.
+    pub async fn sign_and_dispatch_with_options(
+        &self,
+        request: SignedRequest,
+        clock: Arc<dyn Clock>,
+        options: DispatchOptions,
+    ) -> DispatchOutcome {
+        let timeout = options.timeout;
+        self.inner
+            .sign_and_dispatch(request, timeout, clock, options)
+            .await
     }
 }
 
+/// Optional behaviors of `Client::sign_and_dispatch_with_options`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DispatchOptions {
+    /// Capture the signed request, keeping up to this many bytes of its payload.
+    pub capture: Option<usize>,
+    /// Log the request and its response.
+    pub wire_log: Option<WireLog>,
+    /// Give up on the request when no response arrived after this long. The body of the
+    /// response isn't covered.
+    pub timeout: Option<Duration>,
+}
+
+/// What `Client::sign_and_dispatch_with_options` did.
+pub struct DispatchOutcome {
+    /// The response, or why none was received.
+    pub result: Result<HttpResponse, SignAndDispatchError>,
+    /// The request as it was sent, when `DispatchOptions::capture` is set and signing
+    /// succeeded.
+    pub captured: Option<CapturedRequest>,
+    /// The access key ID the request was signed with, if it was signed.
+    pub access_key_id: Option<String>,
+}
+
 /// Error that occurs during `sign_and_dispatch`
 #[derive(Debug, PartialEq)]
 pub enum SignAndDispatchError {
@@ -122,7 +203,9 @@ trait SignAndDispatch {
         &self,
         request: SignedRequest,
         timeout: Option<Duration>,
-    ) -> Result<HttpResponse, SignAndDispatchError>;
+        clock: Arc<dyn Clock>,
+        options: DispatchOptions,
+    ) -> DispatchOutcome;
 }
 
 struct ClientInner<P, D> {
@@ -145,39 +228,76 @@ async fn sign_and_dispatch<P, D>(
     client: ClientInner<P, D>,
     mut request: SignedRequest,
     timeout: Option<Duration>,
-) -> Result<HttpResponse, SignAndDispatchError>
+    clock: Arc<dyn Clock>,
+    options: DispatchOptions,
+) -> DispatchOutcome
 where
     P: ProvideAwsCredentials + Send + Sync + 'static,
     D: DispatchSignedRequest + Send + Sync + 'static,
 {
     client.content_encoding.encode(&mut request);
+    let mut access_key_id = None;
     if let Some(provider) = client.credentials_provider {
+        let fetch = provider
+            .credentials()
+            .instrument(debug_span!("credentials"));
         let credentials = if let Some(to) = timeout {
-            time::timeout(to, provider.credentials())
-                .await
-                .map_err(|_| CredentialsError {
+            runtime::timeout(to, fetch).await.unwrap_or_else(|| {
+                Err(CredentialsError {
                     message: "Timeout getting credentials".to_owned(),
                 })
-                .and_then(std::convert::identity)
+            })
         } else {
-            provider.credentials().await
-        }
-        .map_err(SignAndDispatchError::Credentials)?;
+            fetch.await
+        };
+        let credentials = match credentials {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                return DispatchOutcome {
+                    result: Err(SignAndDispatchError::Credentials(err)),
+                    captured: None,
+                    access_key_id: None,
+                }
+            }
+        };
 
         if credentials.is_anonymous() {
             request.complement();
         } else {
-            request.sign(&credentials);
+            request.sign_with_clock(&credentials, &*clock);
+            access_key_id = Some(credentials.aws_access_key_id().to_owned());
         }
     } else {
         request.complement();
     }
 
-    client
+    let captured = options
+        .capture
+        .map(|max_body| CapturedRequest::new(&request, max_body));
+    if let Some(ref wire_log) = options.wire_log {
+        wire_log.log_request(&request);
+    }
+    let started = Instant::now();
+    let result = client
         .dispatcher
         .dispatch(request, timeout)
         .await
-        .map_err(SignAndDispatchError::Dispatch)
+        .map_err(SignAndDispatchError::Dispatch);
+    if let Some(ref wire_log) = options.wire_log {
+        wire_log.log_response(&result, started.elapsed());
+    }
+    if let Ok(ref response) = result {
+        let span = Span::current();
+        span.record("status", response.status.as_u16());
+        if let Some(request_id) = response.request_id() {
+            span.record("request_id", request_id);
+        }
+    }
+    DispatchOutcome {
+        result,
+        captured,
+        access_key_id,
+    }
 }
 
 #[async_trait]
@@ -190,7 +310,21 @@ where
         &self,
         request: SignedRequest,
         timeout: Option<Duration>,
-    ) -> Result<HttpResponse, SignAndDispatchError> {
-        sign_and_dispatch(self.clone(), request, timeout).await
+        clock: Arc<dyn Clock>,
+        options: DispatchOptions,
+    ) -> DispatchOutcome {
+        // Only the method and the path are recorded: the query string of a presigned request
+        // and the headers may hold credentials.
+        let span = debug_span!(
+            "sign_and_dispatch",
+            method = %request.method,
+            host = %request.hostname(),
+            path = %request.path,
+            status = field::Empty,
+            request_id = field::Empty,
+        );
+        sign_and_dispatch(self.clone(), request, timeout, clock, options)
+            .instrument(span)
+            .await
     }
 }
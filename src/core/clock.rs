@@ -0,0 +1,86 @@
+//! Sources of the current time.
+//!
+//! Signing, credential expiry and retry delays read the time through a `Clock`, so tests can
+//! swap `SystemClock` for a `FixedClock` and control time instead of sleeping.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::core::runtime::default_runtime;
+
+/// The monotonic clock the crate times requests with. `std::time::Instant` panics on
+/// `wasm32-unknown-unknown`, which has no clock of its own, so there it reads
+/// `performance.now()` instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// A future that completes after a delay.
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A source of the current time.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The current time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Wait for `duration`, on the default runtime.
+    fn sleep(&self, duration: Duration) -> Sleep {
+        default_runtime().sleep(duration)
+    }
+}
+
+/// The system clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// `sleep` returns immediately after moving the clock forward by the duration, so code that
+/// waits between retries runs without delay while still seeing time pass.
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    /// Create a clock stopped at `now`.
+    pub fn new(now: DateTime<Utc>) -> FixedClock {
+        FixedClock {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Set the time to `now`.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().expect("clock poisoned") = now;
+    }
+
+    /// Move the time forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let duration = chrono::Duration::from_std(duration).expect("duration out of range");
+        *self.now.lock().expect("clock poisoned") += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().expect("clock poisoned")
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.advance(duration);
+        Box::pin(futures::future::ready(()))
+    }
+}
@@ -1,6 +1,6 @@
-use crate::signature::SignedRequest;
 #[cfg(feature = "encoding")]
-use crate::signature::SignedRequestPayload;
+use crate::signature::signer::SignedRequestPayload;
+use crate::signature::SignedRequest;
 #[cfg(feature = "encoding")]
 use flate2::{write::GzEncoder, Compression};
 #[cfg(feature = "encoding")]
@@ -61,6 +61,7 @@ impl ContentEncoding {
                         }
                         let payload_compressed = bytes::Bytes::from(payload_compressed);
                         request.payload = Some(SignedRequestPayload::Buffer(payload_compressed));
+                        request.invalidate();
                     }
                     Some(SignedRequestPayload::Stream(ref stream)) => {
                         // Stream compression is not supported yet
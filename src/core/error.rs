@@ -1,7 +1,9 @@
 use std::error::Error;
 use std::fmt;
 use std::io;
+use std::time::Duration;
 
+use crate::core::capture::CapturedRequest;
 use crate::core::client::SignAndDispatchError;
 use crate::core::proto::xml::util::XmlParseError;
 use crate::core::request::BufferedHttpResponse;
@@ -25,11 +27,60 @@ pub enum Ks3Error<E> {
     Unknown(BufferedHttpResponse),
     /// An error occurred when attempting to run a future as blocking
     Blocking,
+    /// The checksum of the transferred data doesn't match the one reported by the service.
+    IntegrityCheckFailed {
+        /// The checksum reported by the service.
+        expected: String,
+        /// The checksum computed locally.
+        actual: String,
+    },
+    /// No bytes of the response body arrived for this long, see
+    /// `Ks3Config::response_idle_timeout`. The connection was likely stuck, and sending the
+    /// request again is expected to succeed.
+    Timeout(Duration),
+    /// An error along with the request that caused it, returned when
+    /// `Ks3Config::capture_failed_requests` is set.
+    Captured {
+        /// The error.
+        error: Box<Ks3Error<E>>,
+        /// The request, with its secrets redacted.
+        request: CapturedRequest,
+    },
 }
 
 /// Result carrying a generic `Ks3Error`.
 pub type Ks3Result<T, E> = Result<T, Ks3Error<E>>;
 
+impl<E> Ks3Error<E> {
+    /// Attach `request` to the error.
+    pub fn with_request(self, request: CapturedRequest) -> Ks3Error<E> {
+        match self {
+            Ks3Error::Captured { error, .. } => Ks3Error::Captured { error, request },
+            error => Ks3Error::Captured {
+                error: Box::new(error),
+                request,
+            },
+        }
+    }
+
+    /// The request attached to the error, if it was captured.
+    pub fn captured_request(&self) -> Option<&CapturedRequest> {
+        match *self {
+            Ks3Error::Captured { ref request, .. } => Some(request),
+            _ => None,
+        }
+    }
+
+    /// The error without the request attached to it, for matching on the kind of error
+    /// regardless of whether the request was captured.
+    pub fn into_inner(self) -> Ks3Error<E> {
+        match self {
+            Ks3Error::Captured { error, .. } => *error,
+            error => error,
+        }
+    }
+}
+
 /// Header used by AWS on responses to identify the request
 pub const AWS_REQUEST_ID_HEADER: &str = "x-amzn-requestid";
 
@@ -54,7 +105,10 @@ impl<E> From<CredentialsError> for Ks3Error<E> {
 
 impl<E> From<HttpDispatchError> for Ks3Error<E> {
     fn from(err: HttpDispatchError) -> Self {
-        Ks3Error::HttpDispatch(err)
+        match err.stalled() {
+            Some(stalled) => Ks3Error::Timeout(stalled.idle),
+            None => Ks3Error::HttpDispatch(err),
+        }
     }
 }
 
@@ -69,7 +123,7 @@ impl<E> From<SignAndDispatchError> for Ks3Error<E> {
 
 impl<E> From<io::Error> for Ks3Error<E> {
     fn from(err: io::Error) -> Self {
-        Ks3Error::HttpDispatch(HttpDispatchError::from(err))
+        Ks3Error::from(HttpDispatchError::from(err))
     }
 }
 
@@ -81,13 +135,23 @@ impl<E: Error + 'static> fmt::Display for Ks3Error<E> {
             Ks3Error::Credentials(ref err) => write!(f, "{}", err),
             Ks3Error::HttpDispatch(ref dispatch_error) => write!(f, "{}", dispatch_error),
             Ks3Error::ParseError(ref cause) => write!(f, "{}", cause),
-            Ks3Error::Unknown(ref cause) => write!(
+            Ks3Error::Unknown(ref cause) => write!(f, "{}", cause),
+            Ks3Error::Blocking => write!(f, "Failed to run blocking future"),
+            Ks3Error::Timeout(idle) => {
+                write!(f, "No bytes of the response body received for {:?}", idle)
+            }
+            Ks3Error::IntegrityCheckFailed {
+                ref expected,
+                ref actual,
+            } => write!(
                 f,
-                "Request ID: {:?} Body: {}",
-                cause.headers.get(AWS_REQUEST_ID_HEADER),
-                cause.body_as_str()
+                "Integrity check failed: expected checksum {}, computed {}",
+                expected, actual
             ),
-            Ks3Error::Blocking => write!(f, "Failed to run blocking future"),
+            Ks3Error::Captured {
+                ref error,
+                ref request,
+            } => write!(f, "{}\n\nRequest:\n{}", error, request),
         }
     }
 }
@@ -98,6 +162,7 @@ impl<E: Error + 'static> Error for Ks3Error<E> {
             Ks3Error::Service(ref err) => Some(err),
             Ks3Error::Credentials(ref err) => Some(err),
             Ks3Error::HttpDispatch(ref err) => Some(err),
+            Ks3Error::Captured { ref error, .. } => error.source(),
             _ => None,
         }
     }
@@ -0,0 +1,217 @@
+//! Routing requests to a fallback endpoint while the primary one can't be reached.
+//!
+//! `FailoverDispatcher` wraps the dispatcher of a client and sends every request to the first
+//! healthy endpoint of its list, e.g. an internal endpoint, then a public one:
+//!
+//! ```rust,ignore
+//! let dispatcher = FailoverDispatcher::new(
+//!     HttpClient::new()?,
+//!     vec!["http://ks3-internal:8080".to_owned(), "https://ks3-cn-beijing.ksyuncs.com".to_owned()],
+//! )
+//! .with_probe_interval(Duration::from_secs(10));
+//! let client = S3Client::new_with(dispatcher, credentials, region);
+//! ```
+//!
+//! An endpoint that fails to connect is marked unhealthy, and requests go to the next one
+//! instead. Once `probe_interval` has passed, the next request is sent to it again, so the
+//! requests move back as soon as it has recovered. Requests with an in-memory payload that
+//! fail to connect are sent again to the next endpoint right away; requests with a streaming
+//! payload can only be sent once, so they fail, and the requests after them go to the next
+//! endpoint.
+//!
+//! The `Host` header isn't part of the signature, so requests are moved between endpoints
+//! without being signed again.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::FutureExt;
+use tracing::debug;
+
+use crate::core::clock::Instant;
+use crate::core::request::{DispatchSignedRequest, DispatchSignedRequestFuture};
+use crate::signature::SignedRequest;
+
+/// The default time after which an unhealthy endpoint is tried again.
+const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// An endpoint requests can be sent to.
+#[derive(Clone, Debug, PartialEq)]
+struct Endpoint {
+    /// The scheme of the endpoint, if it has one. Requests keep theirs otherwise.
+    scheme: Option<String>,
+    /// The host of the endpoint, with its port.
+    host: String,
+}
+
+impl Endpoint {
+    fn parse(endpoint: &str) -> Endpoint {
+        let (scheme, rest) = match endpoint.find("://") {
+            Some(p) => (Some(endpoint[..p].to_ascii_lowercase()), &endpoint[p + 3..]),
+            None => (None, endpoint),
+        };
+        let host = rest.split('/').next().unwrap_or_default().to_owned();
+        Endpoint { scheme, host }
+    }
+
+    /// Route `request` to the endpoint.
+    fn apply(&self, request: &mut SignedRequest) {
+        if let Some(ref scheme) = self.scheme {
+            request.scheme = Some(scheme.clone());
+        }
+        request.set_hostname(Some(self.host.clone()));
+        request.complement();
+    }
+}
+
+/// When each endpoint last failed to connect, if it hasn't connected since.
+#[derive(Debug)]
+struct Health {
+    failed_at: Vec<Option<Instant>>,
+}
+
+impl Health {
+    /// The indices of the endpoints to try a request with, in order: the healthy endpoints
+    /// and those due to be probed, then the others, the longest unhealthy first.
+    ///
+    /// An endpoint due to be probed is only probed by one request per interval.
+    fn order(&mut self, probe_interval: Duration) -> Vec<usize> {
+        let now = Instant::now();
+        let mut available = Vec::new();
+        let mut unhealthy = Vec::new();
+        for (index, failed_at) in self.failed_at.iter_mut().enumerate() {
+            match *failed_at {
+                None => available.push(index),
+                Some(at) if now.duration_since(at) >= probe_interval => {
+                    *failed_at = Some(now);
+                    available.push(index);
+                }
+                Some(at) => unhealthy.push((at, index)),
+            }
+        }
+        unhealthy.sort();
+        available.extend(unhealthy.into_iter().map(|(_, index)| index));
+        available
+    }
+}
+
+/// A `DispatchSignedRequest` that sends requests to the first healthy endpoint of a list,
+/// failing over to the next one when an endpoint can't be connected to.
+pub struct FailoverDispatcher<D> {
+    inner: Arc<D>,
+    endpoints: Arc<Vec<Endpoint>>,
+    health: Arc<Mutex<Health>>,
+    probe_interval: Duration,
+}
+
+impl<D> FailoverDispatcher<D> {
+    /// Wrap `inner`, sending requests to `endpoints` in order of preference. Endpoints are
+    /// given like those of `Region::Custom`, such as `http://10.0.0.1:8080` or
+    /// `ks3-cn-beijing.ksyuncs.com`.
+    ///
+    /// # Panics
+    ///
+    /// If `endpoints` is empty.
+    pub fn new(inner: D, endpoints: Vec<String>) -> FailoverDispatcher<D> {
+        assert!(
+            !endpoints.is_empty(),
+            "FailoverDispatcher needs at least one endpoint"
+        );
+        let endpoints: Vec<Endpoint> = endpoints.iter().map(|e| Endpoint::parse(e)).collect();
+        FailoverDispatcher {
+            inner: Arc::new(inner),
+            health: Arc::new(Mutex::new(Health {
+                failed_at: vec![None; endpoints.len()],
+            })),
+            endpoints: Arc::new(endpoints),
+            probe_interval: DEFAULT_PROBE_INTERVAL,
+        }
+    }
+
+    /// Try an unhealthy endpoint again once this long has passed since it last failed to
+    /// connect. Defaults to 30 seconds.
+    pub fn with_probe_interval(mut self, probe_interval: Duration) -> FailoverDispatcher<D> {
+        self.probe_interval = probe_interval;
+        self
+    }
+
+    /// Whether each endpoint is healthy, in the order they were given.
+    pub fn health(&self) -> Vec<bool> {
+        let health = self.health.lock().expect("endpoint health poisoned");
+        health.failed_at.iter().map(Option::is_none).collect()
+    }
+
+    /// The host of the endpoint requests are currently sent to first.
+    pub fn active_endpoint(&self) -> String {
+        let health = self.health.lock().expect("endpoint health poisoned");
+        let index = health
+            .failed_at
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or(0);
+        self.endpoints[index].host.clone()
+    }
+}
+
+impl<D> fmt::Debug for FailoverDispatcher<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FailoverDispatcher")
+            .field("endpoints", &self.endpoints)
+            .field("health", &self.health())
+            .field("probe_interval", &self.probe_interval)
+            .finish()
+    }
+}
+
+impl<D> DispatchSignedRequest for FailoverDispatcher<D>
+where
+    D: DispatchSignedRequest + Send + Sync + 'static,
+{
+    fn dispatch(
+        &self,
+        request: SignedRequest,
+        timeout: Option<Duration>,
+    ) -> DispatchSignedRequestFuture {
+        let inner = self.inner.clone();
+        let endpoints = self.endpoints.clone();
+        let health = self.health.clone();
+        let order = health
+            .lock()
+            .expect("endpoint health poisoned")
+            .order(self.probe_interval);
+        async move {
+            let mut request = request;
+            let mut remaining = order.into_iter().peekable();
+            loop {
+                let index = remaining.next().expect("no endpoint left to try");
+                let endpoint = &endpoints[index];
+                let retry = match remaining.peek() {
+                    Some(_) => request.try_clone(),
+                    None => None,
+                };
+                endpoint.apply(&mut request);
+                let result = inner.dispatch(request, timeout).await;
+                let mut health = health.lock().expect("endpoint health poisoned");
+                match result {
+                    Err(err) if err.is_connect() => {
+                        health.failed_at[index] = Some(Instant::now());
+                        drop(health);
+                        debug!(endpoint = %endpoint.host, error = %err, "endpoint unhealthy");
+                        match retry {
+                            Some(next) => request = next,
+                            None => return Err(err),
+                        }
+                    }
+                    result => {
+                        if health.failed_at[index].take().is_some() {
+                            debug!(endpoint = %endpoint.host, "endpoint recovered");
+                        }
+                        return result;
+                    }
+                }
+            }
+        }
+        .boxed()
+    }
+}
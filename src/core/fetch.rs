@@ -0,0 +1,168 @@
+//! Sending requests with `fetch`, for the crate built for `wasm32-unknown-unknown`.
+//!
+//! Browsers and workers such as Cloudflare Workers don't give access to sockets, so
+//! `HttpClient` isn't available there. `FetchDispatcher` sends the requests with the `fetch` of
+//! the JavaScript host instead:
+//!
+//! ```rust,ignore
+//! let credentials = StaticProvider::new_minimal(access_key, secret_key);
+//! let client = S3Client::new_with(FetchDispatcher::new(), credentials, region);
+//! let object = client.get_object(request).await?;
+//! ```
+//!
+//! The body of the response is streamed from the `ReadableStream` of the `fetch` response, see
+//! `ByteStream::from_readable_stream`. Streaming payloads are read into memory before they are
+//! sent, since `fetch` only streams request bodies over HTTP/2 on some hosts, and not at all on
+//! others. Headers the host doesn't let scripts set, such as `Host` and `Content-Length`, are
+//! set by the host itself, and no `User-Agent` is added.
+
+use std::time::Duration;
+
+use bytes::BytesMut;
+use futures::StreamExt;
+use http::header::HeaderName;
+use http::{HeaderMap, StatusCode};
+use js_sys::{Array, Promise, Uint8Array};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortController, Headers, Request, RequestInit, Response};
+
+use crate::core::clock::Instant;
+use crate::core::request::{
+    DispatchSignedRequest, DispatchSignedRequestFuture, HttpDispatchError, HttpResponse,
+};
+use crate::core::runtime;
+use crate::core::timing::RequestTimings;
+use crate::signature::signer::SignedRequestPayload;
+use crate::signature::{ByteStream, SignedRequest};
+
+#[wasm_bindgen]
+extern "C" {
+    // The global `fetch`, the same in windows and workers.
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_request(request: &Request) -> Promise;
+}
+
+/// Sends requests with the `fetch` of the JavaScript host, on `wasm32`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FetchDispatcher;
+
+impl FetchDispatcher {
+    /// A dispatcher sending the requests with the global `fetch`.
+    pub fn new() -> FetchDispatcher {
+        FetchDispatcher
+    }
+}
+
+impl DispatchSignedRequest for FetchDispatcher {
+    fn dispatch(
+        &self,
+        request: SignedRequest,
+        timeout: Option<Duration>,
+    ) -> DispatchSignedRequestFuture {
+        // JavaScript values stay on the thread that made them, the only one on `wasm32`.
+        Box::pin(SendWrapper::new(fetch_dispatch(request, timeout)))
+    }
+}
+
+async fn fetch_dispatch(
+    mut request: SignedRequest,
+    timeout: Option<Duration>,
+) -> Result<HttpResponse, HttpDispatchError> {
+    if let Some(err) = request.header_error() {
+        return Err(HttpDispatchError::new(err.to_owned()));
+    }
+
+    let headers = Headers::new().map_err(|err| js_error("error building request", err))?;
+    for (name, value) in request.headers.iter() {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        headers
+            .append(name.as_str(), &value)
+            .map_err(|err| js_error("error building request", err))?;
+    }
+
+    let init = RequestInit::new();
+    init.set_method(request.method());
+    init.set_headers(&headers);
+    match request.payload.take() {
+        None => {}
+        Some(SignedRequestPayload::Buffer(bytes)) => {
+            init.set_body(&Uint8Array::from(&bytes[..]));
+        }
+        Some(SignedRequestPayload::Stream(mut stream)) => {
+            let mut body = BytesMut::with_capacity(stream.size_hint().unwrap_or(0));
+            while let Some(try_chunk) = stream.next().await {
+                let chunk = try_chunk?;
+                body.extend_from_slice(&chunk);
+            }
+            init.set_body(&Uint8Array::from(&body[..]));
+        }
+    }
+    let abort = AbortController::new().map_err(|err| js_error("error building request", err))?;
+    init.set_signal(Some(&abort.signal()));
+
+    let fetch_request = Request::new_with_str_and_init(&request.url(), &init)
+        .map_err(|err| js_error("error building request", err))?;
+
+    let started = Instant::now();
+    let response = JsFuture::from(fetch_with_request(&fetch_request));
+    let try_resp = match timeout {
+        None => response.await,
+        Some(duration) => match runtime::timeout(duration, response).await {
+            None => {
+                abort.abort();
+                return Err(HttpDispatchError::new(
+                    "Timeout while dispatching request".to_owned(),
+                ));
+            }
+            Some(try_resp) => try_resp,
+        },
+    };
+    let response: Response = try_resp
+        .and_then(|response| response.dyn_into())
+        .map_err(|err| js_error("Error during dispatch", err))?;
+    let time_to_first_byte = started.elapsed();
+
+    let status = StatusCode::from_u16(response.status())
+        .map_err(|err| HttpDispatchError::new(format!("Error during dispatch: {}", err)))?;
+    let headers = response_headers(&response.headers())?;
+    let size_hint = headers
+        .get("Content-Length")
+        .and_then(|len| len.parse::<usize>().ok());
+    let body = match response.body() {
+        Some(body) => ByteStream::from_readable_stream(body, size_hint),
+        None => ByteStream::from(Vec::new()),
+    };
+    let timings = RequestTimings {
+        time_to_first_byte,
+        ..Default::default()
+    };
+    Ok(HttpResponse::new(status, headers, body).with_timings(timings))
+}
+
+/// The headers of a `fetch` response, skipping the ones whose name isn't valid.
+fn response_headers(headers: &Headers) -> Result<HeaderMap<String>, HttpDispatchError> {
+    let entries = js_sys::try_iter(headers)
+        .map_err(|err| js_error("Error reading headers", err))?
+        .ok_or_else(|| HttpDispatchError::new("Error reading headers".to_owned()))?;
+    let mut map = HeaderMap::default();
+    for entry in entries {
+        let entry: Array = entry
+            .map_err(|err| js_error("Error reading headers", err))?
+            .unchecked_into();
+        let (name, value) = match (entry.get(0).as_string(), entry.get(1).as_string()) {
+            (Some(name), Some(value)) => (name, value),
+            _ => continue,
+        };
+        if let Ok(name) = HeaderName::from_bytes(name.as_bytes()) {
+            map.append(name, value);
+        }
+    }
+    Ok(map)
+}
+
+fn js_error(context: &str, err: JsValue) -> HttpDispatchError {
+    HttpDispatchError::new(format!("{}: {:?}", context, err))
+}
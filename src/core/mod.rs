@@ -1,18 +1,41 @@
-#[cfg(feature = "rustls")]
+#[cfg(all(feature = "rustls", not(target_arch = "wasm32")))]
 use hyper_rustls as tls;
-#[cfg(feature = "native-tls")]
+#[cfg(all(feature = "native-tls", not(target_arch = "wasm32")))]
 use hyper_tls as tls;
 
+pub mod capture;
 pub mod client;
+pub mod clock;
 #[doc(hidden)]
 pub mod encoding;
 pub mod error;
+pub mod failover;
+#[cfg(target_arch = "wasm32")]
+pub mod fetch;
+pub mod param;
 #[doc(hidden)]
 pub mod proto;
 pub mod region;
 pub mod request;
+pub mod runtime;
+pub mod timing;
+pub mod wire_log;
 
-pub use crate::core::client::Client;
+pub use crate::core::client::{Client, DispatchOptions, DispatchOutcome};
+pub use crate::core::clock::{Clock, FixedClock, SystemClock};
+pub use crate::core::failover::FailoverDispatcher;
+#[cfg(target_arch = "wasm32")]
+pub use crate::core::fetch::FetchDispatcher;
+pub use crate::core::param::ServiceParams;
 pub use crate::core::region::Region;
+#[cfg(not(target_arch = "wasm32"))]
 pub use crate::core::request::HttpClient;
 pub use crate::core::request::{BufferedHttpResponse, DispatchSignedRequest, HttpResponse};
+pub use crate::core::request::{DispatchSignedRequestFuture, HttpDispatchError};
+pub use crate::core::runtime::{default_runtime, set_default_runtime, Runtime};
+pub use crate::core::timing::RequestTimings;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::core::timing::TimedConnector;
+pub use crate::core::wire_log::WireLog;
+// Re-exported so that custom dispatchers build responses with the same version of `http`.
+pub use http::{HeaderMap, StatusCode};
@@ -0,0 +1,22 @@
+//! Helpers for building the query string parameters of a request.
+
+use crate::signature::signer::Params;
+
+/// Convenience methods for inserting values into `Params`.
+pub trait ServiceParams {
+    /// Insert `key=val` into the parameters.
+    fn put<K: Into<String>, V: ToString>(&mut self, key: K, val: V);
+
+    /// Insert a valueless `key` into the parameters, such as the `uploads` subresource.
+    fn put_key<K: Into<String>>(&mut self, key: K);
+}
+
+impl ServiceParams for Params {
+    fn put<K: Into<String>, V: ToString>(&mut self, key: K, val: V) {
+        self.insert(key.into(), Some(val.to_string()));
+    }
+
+    fn put_key<K: Into<String>>(&mut self, key: K) {
+        self.insert(key.into(), None);
+    }
+}
@@ -3,11 +3,12 @@
 //! Wraps an XML stack via traits.
 //! Also provides a method of supplying an XML stack from a file for testing purposes.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io;
 use std::iter::Peekable;
 use std::num::ParseIntError;
 
+use xml::escape::{escape_str_attribute, escape_str_pcdata};
 use xml::reader::{EventReader, Events, ParserConfig, XmlEvent};
 use xml::writer::EventWriter;
 
@@ -208,6 +209,62 @@ pub fn skip_tree<T: Peek + Next>(stack: &mut T) {
     }
 }
 
+/// consume the element `name`, which the deserializer doesn't know, into `extra` instead of
+/// skipping it, so that the elements a newer version of the service sends can still be read.
+///
+/// A leaf element is kept as its text and an element with children as the markup of its
+/// content, e.g. `<Days>3</Days>`. An element that is repeated keeps its last value. Errors
+/// are ignored, as in `skip_tree`.
+pub fn retain_tree<T: Peek + Next>(
+    name: &str,
+    stack: &mut T,
+    extra: &mut BTreeMap<String, String>,
+) {
+    let mut deep: usize = 0;
+    let mut text = String::new();
+    let mut markup = String::new();
+    let mut has_children = false;
+
+    loop {
+        match stack.next() {
+            None => break,
+            Some(Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            })) => {
+                if deep > 0 {
+                    has_children = true;
+                    markup.push('<');
+                    markup.push_str(&name.local_name);
+                    for attribute in attributes {
+                        markup.push_str(&format!(
+                            " {}=\"{}\"",
+                            attribute.name.local_name,
+                            escape_str_attribute(&attribute.value)
+                        ));
+                    }
+                    markup.push('>');
+                }
+                deep += 1;
+            }
+            Some(Ok(XmlEvent::EndElement { name })) => {
+                if deep > 1 {
+                    deep -= 1;
+                    markup.push_str(&format!("</{}>", name.local_name));
+                } else {
+                    break;
+                }
+            }
+            Some(Ok(XmlEvent::Characters(data))) | Some(Ok(XmlEvent::CData(data))) => {
+                markup.push_str(&escape_str_pcdata(&data));
+                text.push_str(&data);
+            }
+            _ => (),
+        }
+    }
+
+    extra.insert(name.to_owned(), if has_children { markup } else { text });
+}
+
 /// skip all elements until a start element is encountered
 ///
 /// Errors and end-of-stream are ignored.
@@ -263,17 +320,55 @@ pub async fn parse_response<T, E>(
 where
     T: Default,
 {
-    let xml_response = response.buffer().await.map_err(Ks3Error::HttpDispatch)?;
-    if xml_response.body.is_empty() {
+    let body = response.buffer_body().await.map_err(Ks3Error::from)?;
+    Ok(parse_body(&body, deserialize)?)
+}
+
+/// Deserialize a whole response body with `deserialize`, which is given the name of the root
+/// element. An empty body yields the default value.
+pub fn parse_body<T>(
+    body: &[u8],
+    deserialize: fn(&str, &mut XmlResponse<'_>) -> Result<T, XmlParseError>,
+) -> Result<T, XmlParseError>
+where
+    T: Default,
+{
+    let body = trim_leading_whitespace(body);
+    if body.is_empty() {
         Ok(T::default())
     } else {
-        let reader = EventReader::new_with_config(
-            xml_response.body.as_ref(),
-            ParserConfig::new().trim_whitespace(false),
-        );
+        let reader = EventReader::new_with_config(body, ParserConfig::new().trim_whitespace(false));
         let mut stack = XmlResponse::new(reader.into_iter().peekable());
         let _start_document = stack.next();
         let actual_tag_name = peek_at_name(&mut stack)?;
-        Ok(deserialize(&actual_tag_name, &mut stack)?)
+        deserialize(&actual_tag_name, &mut stack)
+    }
+}
+
+/// `body` without the whitespace some gateways send ahead of the document to keep the
+/// connection alive while the service works on a long request, such as completing a large
+/// multipart upload.
+pub fn trim_leading_whitespace(body: &[u8]) -> &[u8] {
+    let start = body
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(body.len());
+    &body[start..]
+}
+
+/// The name of the root element of the document `body`, if it's a well-formed start of a
+/// document.
+pub fn root_element_name(body: &[u8]) -> Option<String> {
+    let reader = EventReader::new(trim_leading_whitespace(body));
+    for event in reader {
+        match event.ok()? {
+            XmlEvent::StartElement { name, .. } => return Some(name.local_name),
+            XmlEvent::StartDocument { .. }
+            | XmlEvent::ProcessingInstruction { .. }
+            | XmlEvent::Comment(_)
+            | XmlEvent::Whitespace(_) => continue,
+            _ => return None,
+        }
     }
+    None
 }
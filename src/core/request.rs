@@ -1,25 +1,35 @@
 //! AWS API requests.
 //!
-//! Wraps the `hyper` library to send PUT, POST, DELETE and GET requests.
+//! Wraps the `hyper` library to send PUT, POST, DELETE and GET requests. On `wasm32`,
+//! where hyper can't open sockets, `FetchDispatcher` sends them instead.
+#[cfg(not(target_arch = "wasm32"))]
 extern crate rustc_version;
 
 use bytes::{Bytes, BytesMut};
-use futures::{FutureExt, StreamExt};
-use http::header::{HeaderName, HeaderValue};
-use http::{HeaderMap, Method, StatusCode};
-use hyper::client::connect::Connect;
-use hyper::client::Builder as HyperBuilder;
-use hyper::client::HttpConnector;
-use hyper::Error as HyperError;
-use hyper::{Body, Client as HyperClient, Request as HyperRequest, Response as HyperResponse};
-use lazy_static::lazy_static;
-use log::{debug, log_enabled, Level::Debug};
-use rustc_version::version;
-use tokio::time;
-
-use crate::core::tls::HttpsConnector;
-use crate::signature::{ByteStream, SignedRequest};
-use std::env;
+use futures::StreamExt;
+use http::{HeaderMap, StatusCode};
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    futures::FutureExt,
+    http::header::HeaderValue,
+    http::Method,
+    hyper::client::connect::Connect,
+    hyper::client::Builder as HyperBuilder,
+    hyper::client::HttpConnector,
+    hyper::Error as HyperError,
+    hyper::{Body, Client as HyperClient, Request as HyperRequest, Response as HyperResponse},
+    lazy_static::lazy_static,
+    rustc_version::version,
+    std::env,
+    std::time::Instant,
+    tokio::time,
+};
+
+use crate::core::timing::RequestTimings;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::core::{timing::TimedConnector, tls::HttpsConnector};
+use crate::signature::{BodyStalled, ByteStream, SignedRequest};
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 use std::future::Future;
@@ -32,6 +42,7 @@ use std::time::Duration;
 
 // Use a lazy static to cache the default User-Agent header
 // because it never changes once it's been computed.
+#[cfg(not(target_arch = "wasm32"))]
 lazy_static! {
     static ref DEFAULT_USER_AGENT: String = format!(
         "rusoto/{} rust/{} {}",
@@ -49,6 +60,8 @@ pub struct HttpResponse {
     pub body: ByteStream,
     /// Response headers
     pub headers: HeaderMap<String>,
+    /// How long the phases of the request took, when measured by the dispatcher.
+    pub timings: Option<RequestTimings>,
 }
 
 /// Stores the buffered response from a HTTP request.
@@ -62,6 +75,19 @@ pub struct BufferedHttpResponse {
     pub headers: HeaderMap<String>,
 }
 
+/// Headers identifying a request in the logs of the service, in order of preference.
+const REQUEST_ID_HEADERS: &[&str] = &["x-kss-request-id", "x-amz-request-id", "x-amzn-requestid"];
+
+/// The first of `REQUEST_ID_HEADERS` in `headers`.
+fn request_id(headers: &HeaderMap<String>) -> Option<&str> {
+    REQUEST_ID_HEADERS
+        .iter()
+        .find_map(|&name| headers.get(name).map(String::as_str))
+}
+
+/// The number of bytes of the body shown by the `Display` of `BufferedHttpResponse`.
+const DISPLAYED_BODY_LEN: usize = 1024;
+
 impl BufferedHttpResponse {
     ///! Best effort to turn response body into more readable &str.
     pub fn body_as_str(&self) -> &str {
@@ -70,6 +96,51 @@ impl BufferedHttpResponse {
             _ => "unknown error",
         }
     }
+
+    /// The start of the body as text, cut to at most `max_len` bytes, with invalid UTF-8
+    /// replaced. `...` is appended when the body was cut.
+    pub fn body_preview(&self, max_len: usize) -> Cow<'_, str> {
+        if self.body.len() <= max_len {
+            return String::from_utf8_lossy(&self.body);
+        }
+        let mut end = max_len;
+        // Don't cut a UTF-8 sequence in the middle.
+        while end > 0 && (self.body[end] & 0xC0) == 0x80 {
+            end -= 1;
+        }
+        let mut preview = String::from_utf8_lossy(&self.body[..end]).into_owned();
+        preview.push_str("...");
+        Cow::Owned(preview)
+    }
+
+    /// The status code.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The value of the header `name`, which is case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// The ID the service assigned to the request, to give to its support.
+    pub fn request_id(&self) -> Option<&str> {
+        request_id(&self.headers)
+    }
+}
+
+/// Renders the status, the request ID and the start of the body.
+impl fmt::Display for BufferedHttpResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HTTP {}", self.status)?;
+        if let Some(request_id) = self.request_id() {
+            write!(f, " (request ID {})", request_id)?;
+        }
+        if !self.body.is_empty() {
+            write!(f, ": {}", self.body_preview(DISPLAYED_BODY_LEN))?;
+        }
+        Ok(())
+    }
 }
 
 /// Best effort based Debug implementation to make generic error's body more readable.
@@ -93,30 +164,89 @@ impl fmt::Debug for BufferedHttpResponse {
 impl HttpResponse {
     /// Buffer the full response body in memory, resulting in a `BufferedHttpResponse`.
     pub async fn buffer(&mut self) -> Result<BufferedHttpResponse, HttpDispatchError> {
-        let mut bytes = BytesMut::new();
-        while let Some(try_chunk) = self.body.next().await {
-            let chunk = try_chunk.map_err(|e| HttpDispatchError {
-                message: format!("Error obtaining body: {}", e),
-            })?;
-            bytes.extend(chunk);
-        }
+        let body = self.buffer_body().await?;
         Ok(BufferedHttpResponse {
             status: self.status,
             headers: self.headers.clone(),
-            body: bytes.freeze(),
+            body,
         })
     }
 
-    async fn from_hyper(hyper_response: HyperResponse<Body>) -> HttpResponse {
+    /// Buffer the full response body in memory like `buffer`, moving the headers into the
+    /// `BufferedHttpResponse` instead of copying them.
+    pub async fn into_buffered(mut self) -> Result<BufferedHttpResponse, HttpDispatchError> {
+        let body = self.buffer_body().await?;
+        Ok(BufferedHttpResponse {
+            status: self.status,
+            headers: self.headers,
+            body,
+        })
+    }
+
+    /// Read the full response body into memory.
+    ///
+    /// Bodies already in memory, or received in a single chunk, are returned as they are
+    /// without being copied.
+    pub async fn buffer_body(&mut self) -> Result<Bytes, HttpDispatchError> {
+        if let Some(body) = ByteStream::buffered(&self.body) {
+            return Ok(body.clone());
+        }
+        let read_error = |e: io::Error| HttpDispatchError {
+            message: format!("Error obtaining body: {}", e),
+            ..HttpDispatchError::from(e)
+        };
+        let first = match self.body.next().await {
+            None => return Ok(Bytes::new()),
+            Some(try_chunk) => try_chunk.map_err(read_error)?,
+        };
+        let second = match self.body.next().await {
+            None => return Ok(first),
+            Some(try_chunk) => try_chunk.map_err(read_error)?,
+        };
+        let received = first.len() + second.len();
+        let capacity = self
+            .headers
+            .get("Content-Length")
+            .and_then(|len| len.parse::<usize>().ok())
+            .map_or(received, |len| len.max(received));
+        let mut bytes = BytesMut::with_capacity(capacity);
+        bytes.extend_from_slice(&first);
+        bytes.extend_from_slice(&second);
+        while let Some(try_chunk) = self.body.next().await {
+            bytes.extend_from_slice(&try_chunk.map_err(read_error)?);
+        }
+        Ok(bytes.freeze())
+    }
+
+    /// The ID the service assigned to the request, to give to its support.
+    pub fn request_id(&self) -> Option<&str> {
+        request_id(&self.headers)
+    }
+
+    /// A response received by a custom dispatcher, without timings.
+    pub fn new(status: StatusCode, headers: HeaderMap<String>, body: ByteStream) -> HttpResponse {
+        HttpResponse {
+            status,
+            body,
+            headers,
+            timings: None,
+        }
+    }
+
+    /// The response with the timings measured by the dispatcher.
+    pub fn with_timings(mut self, timings: RequestTimings) -> HttpResponse {
+        self.timings = Some(timings);
+        self
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn from_hyper(
+        hyper_response: HyperResponse<Body>,
+        time_to_first_byte: Duration,
+    ) -> HttpResponse {
         let status = hyper_response.status();
-        let headers = hyper_response
-            .headers()
-            .iter()
-            .map(|(h, v)| {
-                let value_string = v.to_str().unwrap().to_owned();
-                (h.clone(), value_string)
-            })
-            .collect();
+        let timings = RequestTimings::new(hyper_response.extensions(), time_to_first_byte);
+        let headers = string_headers(hyper_response.headers());
         let body = hyper_response.into_body().map(|try_chunk| {
             try_chunk.map(|c| c).map_err(|e| {
                 IoError::new(
@@ -130,20 +260,69 @@ impl HttpResponse {
             status,
             headers,
             body: ByteStream::new(body),
+            timings: Some(timings),
         }
     }
 }
 
+/// An `http` response whose body was turned into a `ByteStream`, e.g. by a dispatcher built on
+/// another HTTP client.
+impl From<http::Response<ByteStream>> for HttpResponse {
+    fn from(response: http::Response<ByteStream>) -> HttpResponse {
+        let headers = string_headers(response.headers());
+        HttpResponse::new(response.status(), headers, response.into_body())
+    }
+}
+
+/// `headers` with their values as strings, with invalid UTF-8 replaced.
+fn string_headers(headers: &HeaderMap) -> HeaderMap<String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            (name.clone(), value)
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 /// An error produced when sending the request, such as a timeout error.
 pub struct HttpDispatchError {
     message: String,
+    connect: bool,
+    stalled: Option<BodyStalled>,
 }
 
 impl HttpDispatchError {
-    /// Construct a new HttpDispatchError for testing purposes
+    /// Construct a new HttpDispatchError, e.g. from the error of the transport of a custom
+    /// dispatcher.
     pub fn new(message: String) -> HttpDispatchError {
-        HttpDispatchError { message }
+        HttpDispatchError {
+            message,
+            connect: false,
+            stalled: None,
+        }
+    }
+
+    /// Construct a new HttpDispatchError for a request that couldn't connect to the server,
+    /// and so wasn't sent.
+    pub fn connect(message: String) -> HttpDispatchError {
+        HttpDispatchError {
+            message,
+            connect: true,
+            stalled: None,
+        }
+    }
+
+    /// Whether the request failed to connect to the server, and so wasn't sent.
+    pub fn is_connect(&self) -> bool {
+        self.connect
+    }
+
+    /// The stall that failed the body of the response, if no bytes of it arrived for longer
+    /// than `Ks3Config::response_idle_timeout`.
+    pub fn stalled(&self) -> Option<BodyStalled> {
+        self.stalled
     }
 }
 
@@ -155,10 +334,13 @@ impl fmt::Display for HttpDispatchError {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<HyperError> for HttpDispatchError {
     fn from(err: HyperError) -> HttpDispatchError {
-        HttpDispatchError {
-            message: err.to_string(),
+        if err.is_connect() {
+            HttpDispatchError::connect(err.to_string())
+        } else {
+            HttpDispatchError::new(err.to_string())
         }
     }
 }
@@ -166,7 +348,8 @@ impl From<HyperError> for HttpDispatchError {
 impl From<IoError> for HttpDispatchError {
     fn from(err: IoError) -> HttpDispatchError {
         HttpDispatchError {
-            message: err.to_string(),
+            stalled: BodyStalled::from_io_error(&err),
+            ..HttpDispatchError::new(err.to_string())
         }
     }
 }
@@ -176,6 +359,35 @@ pub type DispatchSignedRequestFuture =
     Pin<Box<dyn Future<Output = Result<HttpResponse, HttpDispatchError>> + Send>>;
 
 /// Trait for implementing HTTP Request/Response
+///
+/// Implement it to send the requests of a client over another transport than `HttpClient`,
+/// such as `fetch` in a browser or a proxy listening on a unix socket. The request is signed
+/// already: send it to `request.url()` with its method, headers and payload as they are, and
+/// answer with the response, or with an `HttpDispatchError` when none was received:
+///
+/// ```rust,ignore
+/// impl DispatchSignedRequest for ProxyDispatcher {
+///     fn dispatch(
+///         &self,
+///         mut request: SignedRequest,
+///         timeout: Option<Duration>,
+///     ) -> DispatchSignedRequestFuture {
+///         let proxy = self.proxy.clone();
+///         let url = request.url();
+///         let body = request.payload.take();
+///         Box::pin(async move {
+///             let (status, headers, body) = proxy
+///                 .send(&request.method, &url, &request.headers, body, timeout)
+///                 .await
+///                 .map_err(|err| HttpDispatchError::new(err.to_string()))?;
+///             Ok(HttpResponse::new(status, headers, ByteStream::from(body)))
+///         })
+///     }
+/// }
+/// ```
+///
+/// Errors that mean the request wasn't sent at all, such as a refused connection, are built
+/// with `HttpDispatchError::connect`, so that e.g. `FailoverDispatcher` can tell them apart.
 pub trait DispatchSignedRequest {
     /// Dispatch Request, and then return a Response
     fn dispatch(
@@ -206,11 +418,13 @@ impl<D: DispatchSignedRequest> DispatchSignedRequest for Arc<D> {
 }
 
 /// Http client for use with AWS services.
-pub struct HttpClient<C = HttpsConnector<HttpConnector>> {
+#[cfg(not(target_arch = "wasm32"))]
+pub struct HttpClient<C = TimedConnector<HttpsConnector<HttpConnector>>> {
     inner: HyperClient<C, Body>,
     local_agent: Option<String>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl HttpClient {
     /// Create a tls-enabled http client.
     pub fn new() -> Result<Self, TlsError> {
@@ -220,7 +434,7 @@ impl HttpClient {
         #[cfg(feature = "rustls")]
         let connector = HttpsConnector::new();
 
-        Ok(Self::from_connector(connector))
+        Ok(Self::from_connector(TimedConnector::new(connector)))
     }
 
     /// Create a tls-enabled http client.
@@ -231,7 +445,10 @@ impl HttpClient {
         #[cfg(feature = "rustls")]
         let connector = HttpsConnector::new();
 
-        Ok(Self::from_connector_with_config(connector, config))
+        Ok(Self::from_connector_with_config(
+            TimedConnector::new(connector),
+            config,
+        ))
     }
 
     /// Sets a local agent that is prepended to the default HTTP
@@ -241,6 +458,7 @@ impl HttpClient {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl<C> HttpClient<C>
 where
     C: Connect + Clone + Send + Sync,
@@ -280,10 +498,12 @@ where
 }
 
 /// Configuration options for the HTTP Client
+#[cfg(not(target_arch = "wasm32"))]
 pub struct HttpConfig {
     read_buf_size: Option<usize>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl HttpConfig {
     /// Create a new HttpConfig
     pub fn new() -> HttpConfig {
@@ -299,6 +519,7 @@ impl HttpConfig {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl Default for HttpConfig {
     /// Create a new HttpConfig. Same as HttpConfig::new().
     fn default() -> HttpConfig {
@@ -306,9 +527,10 @@ impl Default for HttpConfig {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 async fn http_client_dispatch<'a, C>(
     client: HyperClient<C, Body>,
-    request: SignedRequest,
+    mut request: SignedRequest,
     timeout: Option<Duration>,
     user_agent: HeaderValue,
 ) -> Result<HttpResponse, HttpDispatchError>
@@ -322,60 +544,24 @@ where
         "GET" => Method::GET,
         "HEAD" => Method::HEAD,
         v => {
-            return Err(HttpDispatchError {
-                message: format!("Unsupported HTTP verb {}", v),
-            });
+            return Err(HttpDispatchError::new(format!(
+                "Unsupported HTTP verb {}",
+                v
+            )));
         }
     };
 
-    // translate the headers map to a format Hyper likes
-    let mut hyper_headers = HeaderMap::new();
-    for h in request.headers().iter() {
-        let header_name = match h.0.parse::<HeaderName>() {
-            Ok(name) => name,
-            Err(err) => {
-                return Err(HttpDispatchError {
-                    message: format!("error parsing header name: {}", err),
-                });
-            }
-        };
-        for v in h.1.iter() {
-            let header_value = match HeaderValue::from_bytes(v) {
-                Ok(value) => value,
-                Err(err) => {
-                    return Err(HttpDispatchError {
-                        message: format!("error parsing header value: {}", err),
-                    });
-                }
-            };
-            hyper_headers.append(&header_name, header_value);
-        }
+    if let Some(err) = request.header_error() {
+        return Err(HttpDispatchError::new(err.to_owned()));
     }
+    let mut hyper_headers = std::mem::take(&mut request.headers);
 
     // Add a default user-agent header if one is not already present.
     if !hyper_headers.contains_key("user-agent") {
         hyper_headers.insert("user-agent", user_agent);
     }
 
-    let mut final_uri = format!(
-        "{}://{}{}",
-        request.scheme(),
-        request.hostname(),
-        request.canonical_path()
-    );
-    if !request.canonical_query_string().is_empty() {
-        final_uri = final_uri + &format!("?{}", request.canonical_query_string());
-    }
-
-    if log_enabled!(Debug) {
-        debug!(
-            "Full request: \n method: {}\n final_uri: {}\nHeaders:\n",
-            hyper_method, final_uri
-        );
-        for (h, v) in hyper_headers.iter() {
-            debug!("{}:{:?}", h.as_str(), v);
-        }
-    }
+    let final_uri = request.url();
 
     let http_request_builder = HyperRequest::builder().method(hyper_method).uri(final_uri);
 
@@ -385,31 +571,37 @@ where
         http_request_builder.body(Body::empty())
     };
 
-    let mut http_request = try_http_request.map_err(|err| HttpDispatchError {
-        message: format!("error building request: {}", err),
-    })?;
+    let mut http_request = try_http_request
+        .map_err(|err| HttpDispatchError::new(format!("error building request: {}", err)))?;
 
     *http_request.headers_mut() = hyper_headers;
 
+    let started = Instant::now();
     let f = client.request(http_request);
 
     let try_resp = match timeout {
         None => f.await,
         Some(duration) => match time::timeout(duration, f).await {
             Err(_e) => {
-                return Err(HttpDispatchError {
-                    message: "Timeout while dispatching request".to_owned(),
-                })
+                return Err(HttpDispatchError::new(
+                    "Timeout while dispatching request".to_owned(),
+                ))
             }
             Ok(try_req) => try_req,
         },
     };
-    let resp = try_resp.map_err(|e| HttpDispatchError {
-        message: format!("Error during dispatch: {}", e),
+    let resp = try_resp.map_err(|e| {
+        let message = format!("Error during dispatch: {}", e);
+        if e.is_connect() {
+            HttpDispatchError::connect(message)
+        } else {
+            HttpDispatchError::new(message)
+        }
     })?;
-    Ok(HttpResponse::from_hyper(resp).await)
+    Ok(HttpResponse::from_hyper(resp, started.elapsed()).await)
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl<C> DispatchSignedRequest for HttpClient<C>
 where
     C: Connect + Clone + Send + Sync + 'static,
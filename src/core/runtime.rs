@@ -0,0 +1,254 @@
+//! The async runtime the crate's own timers, background tasks and blocking file I/O run on.
+//!
+//! Tokio is used by default. With the `runtime-async-std` or `runtime-smol` feature, the
+//! default is async-std or smol instead, so that applications built on them don't need a
+//! Tokio runtime next to their own for timeouts, retry delays, resumed downloads or
+//! `ByteStream::from_path`. Other runtimes can be plugged in with `set_default_runtime`.
+//!
+//! `HttpClient` is built on hyper, whose connections need a Tokio runtime whatever the
+//! runtime set here; on other runtimes, send the requests with a dispatcher of your own, see
+//! `DispatchSignedRequest`.
+//!
+//! On `wasm32`, the default is `WasmRuntime`, which runs tasks on the JavaScript event loop.
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use futures::channel::oneshot;
+use futures::future::{self, Either};
+use lazy_static::lazy_static;
+
+use crate::core::clock::Sleep;
+#[cfg(target_arch = "wasm32")]
+use {js_sys::Promise, send_wrapper::SendWrapper, wasm_bindgen::prelude::*};
+
+/// A task run in the background.
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The pieces of an async runtime the crate needs.
+pub trait Runtime: fmt::Debug + Send + Sync {
+    /// Run `task` in the background.
+    fn spawn(&self, task: Task);
+
+    /// Run `f` on a thread where blocking is allowed. The returned future completes once `f`
+    /// has returned.
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Task;
+
+    /// Wait for `duration`.
+    fn sleep(&self, duration: Duration) -> Sleep;
+
+    /// Run `future` to completion on the current thread, which must not be running a task of
+    /// the runtime.
+    fn block_on<'a>(&self, future: Pin<Box<dyn Future<Output = ()> + 'a>>) -> io::Result<()>;
+}
+
+/// Tokio, the default runtime.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioRuntime;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Runtime for TokioRuntime {
+    fn spawn(&self, task: Task) {
+        tokio::spawn(task);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Task {
+        let handle = tokio::task::spawn_blocking(f);
+        Box::pin(async move {
+            // A panic of `f` is seen by the caller through the result it never receives.
+            let _ = handle.await;
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::delay_for(duration))
+    }
+
+    fn block_on<'a>(&self, future: Pin<Box<dyn Future<Output = ()> + 'a>>) -> io::Result<()> {
+        let mut runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(future);
+        Ok(())
+    }
+}
+
+/// async-std, the default runtime with the `runtime-async-std` feature.
+#[cfg(feature = "runtime-async-std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStdRuntime;
+
+#[cfg(feature = "runtime-async-std")]
+impl Runtime for AsyncStdRuntime {
+    fn spawn(&self, task: Task) {
+        async_std::task::spawn(task);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Task {
+        Box::pin(async_std::task::spawn_blocking(f))
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+
+    fn block_on<'a>(&self, future: Pin<Box<dyn Future<Output = ()> + 'a>>) -> io::Result<()> {
+        async_std::task::block_on(future);
+        Ok(())
+    }
+}
+
+/// smol, the default runtime with the `runtime-smol` feature.
+#[cfg(feature = "runtime-smol")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmolRuntime;
+
+#[cfg(feature = "runtime-smol")]
+impl Runtime for SmolRuntime {
+    fn spawn(&self, task: Task) {
+        smol::spawn(task).detach();
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Task {
+        Box::pin(smol::unblock(f))
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+
+    fn block_on<'a>(&self, future: Pin<Box<dyn Future<Output = ()> + 'a>>) -> io::Result<()> {
+        smol::block_on(future);
+        Ok(())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, millis: i32) -> JsValue;
+}
+
+/// The JavaScript event loop, the default runtime on `wasm32`.
+///
+/// There are no threads to block on: `spawn_blocking` runs the function right away on the
+/// current one, and `block_on` fails.
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WasmRuntime;
+
+#[cfg(target_arch = "wasm32")]
+impl Runtime for WasmRuntime {
+    fn spawn(&self, task: Task) {
+        wasm_bindgen_futures::spawn_local(task);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) -> Task {
+        f();
+        Box::pin(future::ready(()))
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let millis = duration.as_millis().min(i32::MAX as u128) as i32;
+        let timer = Promise::new(&mut |resolve, _reject| {
+            set_timeout(&resolve, millis);
+        });
+        // JavaScript values stay on the thread that made them, the only one on `wasm32`.
+        let timer = SendWrapper::new(wasm_bindgen_futures::JsFuture::from(timer));
+        Box::pin(async move {
+            let _ = timer.await;
+        })
+    }
+
+    fn block_on<'a>(&self, _future: Pin<Box<dyn Future<Output = ()> + 'a>>) -> io::Result<()> {
+        Err(io::Error::other(
+            "blocking on a future is not supported on wasm32",
+        ))
+    }
+}
+
+/// The runtime selected by the enabled features, async-std first.
+fn feature_runtime() -> Arc<dyn Runtime> {
+    #[cfg(target_arch = "wasm32")]
+    return Arc::new(WasmRuntime);
+    #[cfg(all(feature = "runtime-async-std", not(target_arch = "wasm32")))]
+    return Arc::new(AsyncStdRuntime);
+    #[cfg(all(
+        feature = "runtime-smol",
+        not(feature = "runtime-async-std"),
+        not(target_arch = "wasm32")
+    ))]
+    return Arc::new(SmolRuntime);
+    #[cfg(not(any(
+        feature = "runtime-async-std",
+        feature = "runtime-smol",
+        target_arch = "wasm32"
+    )))]
+    return Arc::new(TokioRuntime);
+}
+
+lazy_static! {
+    static ref DEFAULT_RUNTIME: RwLock<Arc<dyn Runtime>> = RwLock::new(feature_runtime());
+}
+
+/// The runtime used by the crate.
+pub fn default_runtime() -> Arc<dyn Runtime> {
+    DEFAULT_RUNTIME
+        .read()
+        .expect("default runtime poisoned")
+        .clone()
+}
+
+/// Use `runtime` from now on, instead of the one selected by the enabled features.
+pub fn set_default_runtime(runtime: Arc<dyn Runtime>) {
+    *DEFAULT_RUNTIME.write().expect("default runtime poisoned") = runtime;
+}
+
+/// Run `future` in the background, receiving its output through the returned channel.
+pub(crate) fn spawn<F>(future: F) -> oneshot::Receiver<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send,
+{
+    let (sender, receiver) = oneshot::channel();
+    default_runtime().spawn(Box::pin(async move {
+        let _ = sender.send(future.await);
+    }));
+    receiver
+}
+
+/// Run `f` on a thread where blocking is allowed, e.g. to read a file, starting right away.
+///
+/// The returned future only holds the channel `f` answers on, so it's `Sync` and can be
+/// polled from a `ByteStream`.
+///
+/// # Panics
+///
+/// The returned future panics if `f` panics.
+pub(crate) fn unblock<F, T>(f: F) -> impl Future<Output = T> + Send + Sync
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let runtime = default_runtime();
+    runtime.spawn(runtime.spawn_blocking(Box::new(move || {
+        let _ = sender.send(f());
+    })));
+    async move { receiver.await.expect("blocking task panicked") }
+}
+
+/// The output of `future`, or `None` if it took longer than `duration`.
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    futures::pin_mut!(future);
+    match future::select(future, default_runtime().sleep(duration)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(((), _)) => None,
+    }
+}
@@ -0,0 +1,171 @@
+//! Timing of the phases of a request, reported by `HttpClient` in `HttpResponse::timings`.
+//!
+//! `HttpClient::new` wraps its connector in a `TimedConnector`, which measures how long new
+//! connections take to establish and tells whether a request reused a pooled connection.
+//! `FetchDispatcher` can't see the connections behind `fetch`, and only reports the time to
+//! the first byte.
+
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    future::Future,
+    io,
+    mem::MaybeUninit,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use http::{Extensions, Uri};
+#[cfg(not(target_arch = "wasm32"))]
+use hyper::client::connect::{Connected, Connection};
+#[cfg(not(target_arch = "wasm32"))]
+use hyper::service::Service;
+#[cfg(not(target_arch = "wasm32"))]
+use pin_project::pin_project;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// How long the phases of a request took.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RequestTimings {
+    /// Whether the request was sent on a new connection rather than one kept from an earlier
+    /// request, when known.
+    pub new_connection: Option<bool>,
+    /// The time it took to resolve the host name and establish the connection, including the
+    /// TLS handshake, when the request was sent on a new connection.
+    pub connect: Option<Duration>,
+    /// The time from sending the request to receiving the headers of the response, including
+    /// `connect`.
+    pub time_to_first_byte: Duration,
+}
+
+impl RequestTimings {
+    /// The timings of a response received `time_to_first_byte` after sending its request, on
+    /// the connection described by `extensions`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn new(extensions: &Extensions, time_to_first_byte: Duration) -> RequestTimings {
+        match extensions.get::<ConnectionTiming>() {
+            Some(timing) => {
+                let new_connection = timing.requests.fetch_add(1, Ordering::Relaxed) == 0;
+                RequestTimings {
+                    new_connection: Some(new_connection),
+                    connect: Some(timing.connect).filter(|_| new_connection),
+                    time_to_first_byte,
+                }
+            }
+            None => RequestTimings {
+                time_to_first_byte,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Attached to the responses received on a connection made by `TimedConnector`.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+struct ConnectionTiming {
+    connect: Duration,
+    /// The number of responses received on the connection so far.
+    requests: Arc<AtomicUsize>,
+}
+
+/// A connector timing the connections made by another.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+pub struct TimedConnector<C> {
+    inner: C,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<C> TimedConnector<C> {
+    /// Time the connections made by `inner`.
+    pub fn new(inner: C) -> TimedConnector<C> {
+        TimedConnector { inner }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<C> Service<Uri> for TimedConnector<C>
+where
+    C: Service<Uri>,
+    C::Future: Send + 'static,
+{
+    type Response = TimedConnection<C::Response>;
+    type Error = C::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        let started = Instant::now();
+        let connecting = self.inner.call(dst);
+        Box::pin(async move {
+            let inner = connecting.await?;
+            Ok(TimedConnection {
+                inner,
+                timing: ConnectionTiming {
+                    connect: started.elapsed(),
+                    requests: Arc::new(AtomicUsize::new(0)),
+                },
+            })
+        })
+    }
+}
+
+/// A connection made by `TimedConnector`.
+#[cfg(not(target_arch = "wasm32"))]
+#[pin_project]
+#[derive(Debug)]
+pub struct TimedConnection<T> {
+    #[pin]
+    inner: T,
+    timing: ConnectionTiming,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Connection> Connection for TimedConnection<T> {
+    fn connected(&self) -> Connected {
+        self.inner.connected().extra(self.timing.clone())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: AsyncRead> AsyncRead for TimedConnection<T> {
+    unsafe fn prepare_uninitialized_buffer(&self, buf: &mut [MaybeUninit<u8>]) -> bool {
+        self.inner.prepare_uninitialized_buffer(buf)
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_read(cx, buf)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: AsyncWrite> AsyncWrite for TimedConnection<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.project().inner.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,153 @@
+//! Logging of the requests sent and the responses received, enabled by `Ks3Config::wire_log`.
+//!
+//! Every request is logged once it is signed, and again when its response arrives or it fails,
+//! as debug events of the `ks3::wire` target. The values of `Authorization`, security tokens,
+//! SSE-C keys and presigning parameters are always replaced with `REDACTED`.
+
+use std::time::Duration;
+
+use http::HeaderMap;
+use tracing::debug;
+
+use crate::core::capture::{is_secret_header, redacted_query, REDACTED};
+use crate::core::client::SignAndDispatchError;
+use crate::core::request::HttpResponse;
+use crate::signature::SignedRequest;
+
+/// The target of the log events.
+pub const WIRE_LOG_TARGET: &str = "ks3::wire";
+
+/// The headers logged by default.
+const DEFAULT_HEADERS: &[&str] = &[
+    "content-length",
+    "content-md5",
+    "content-type",
+    "date",
+    "etag",
+    "range",
+    "x-kss-request-id",
+    "x-amz-request-id",
+];
+
+/// Whether the events would be recorded, by a `tracing` subscriber or, without one, by the
+/// `log` logger.
+fn enabled() -> bool {
+    tracing::enabled!(target: WIRE_LOG_TARGET, tracing::Level::DEBUG)
+        || log::log_enabled!(target: WIRE_LOG_TARGET, log::Level::Debug)
+}
+
+/// Settings of the wire log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WireLog {
+    /// The request and response headers to log, case-insensitive. All headers are logged when
+    /// empty. Defaults to the content headers, `Date`, `ETag`, `Range` and the request IDs.
+    pub headers: Vec<String>,
+    /// Headers whose values are redacted, besides the ones that always are.
+    pub redacted_headers: Vec<String>,
+}
+
+impl Default for WireLog {
+    fn default() -> WireLog {
+        WireLog {
+            headers: DEFAULT_HEADERS
+                .iter()
+                .map(|&name| name.to_owned())
+                .collect(),
+            redacted_headers: Vec::new(),
+        }
+    }
+}
+
+impl WireLog {
+    /// Log all headers, with the default redactions.
+    pub fn all_headers() -> WireLog {
+        WireLog {
+            headers: Vec::new(),
+            redacted_headers: Vec::new(),
+        }
+    }
+
+    /// Add `name: value` to `out` if the header `name` is selected, redacting its value if
+    /// needed.
+    fn push_header(&self, out: &mut String, name: &str, value: &str) {
+        let selected = self.headers.is_empty()
+            || self
+                .headers
+                .iter()
+                .any(|header| header.eq_ignore_ascii_case(name));
+        if !selected {
+            return;
+        }
+        let redacted = is_secret_header(name)
+            || self
+                .redacted_headers
+                .iter()
+                .any(|header| header.eq_ignore_ascii_case(name));
+        if !out.is_empty() {
+            out.push_str(", ");
+        }
+        out.push_str(name);
+        out.push_str(": ");
+        out.push_str(if redacted { REDACTED } else { value });
+    }
+
+    /// Log `request`, which is about to be sent.
+    pub(crate) fn log_request(&self, request: &SignedRequest) {
+        if !enabled() {
+            return;
+        }
+        let mut headers = String::new();
+        for (name, value) in request.headers() {
+            self.push_header(
+                &mut headers,
+                name.as_str(),
+                &String::from_utf8_lossy(value.as_bytes()),
+            );
+        }
+        debug!(
+            target: WIRE_LOG_TARGET,
+            method = %request.method(),
+            host = %request.hostname(),
+            path = %request.canonical_uri(),
+            query = %redacted_query(request),
+            headers = %headers,
+            "request"
+        );
+    }
+
+    /// Log the outcome of a request sent `elapsed` ago.
+    pub(crate) fn log_response(
+        &self,
+        result: &Result<HttpResponse, SignAndDispatchError>,
+        elapsed: Duration,
+    ) {
+        if !enabled() {
+            return;
+        }
+        let elapsed_ms = elapsed.as_millis() as u64;
+        match *result {
+            Ok(ref response) => debug!(
+                target: WIRE_LOG_TARGET,
+                status = response.status.as_u16(),
+                elapsed_ms,
+                request_id = response.request_id().unwrap_or(""),
+                headers = %self.response_headers(&response.headers),
+                "response"
+            ),
+            Err(ref err) => debug!(
+                target: WIRE_LOG_TARGET,
+                elapsed_ms,
+                error = ?err,
+                "request failed"
+            ),
+        }
+    }
+
+    fn response_headers(&self, headers: &HeaderMap<String>) -> String {
+        let mut out = String::new();
+        for (name, value) in headers {
+            self.push_header(&mut out, name.as_str(), value);
+        }
+        out
+    }
+}
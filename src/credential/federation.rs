@@ -0,0 +1,300 @@
+//! Minting temporary credentials scoped to part of a bucket, to hand to clients that can't be
+//! trusted with long-lived ones, such as mobile apps or browsers:
+//!
+//! ```rust,ignore
+//! let minter = TemporaryCredentialsMinter::new(sts_region, long_lived_credentials);
+//! let scope = CredentialScope::new("uploads", "users/42/")
+//!     .with_actions(&[ScopeAction::Read, ScopeAction::Write])
+//!     .with_ttl(Duration::from_secs(3600));
+//! let credentials = minter.mint("user-42", &scope).await?;
+//! ```
+//!
+//! The credentials are requested with `GetFederationToken` from an STS-compatible endpoint,
+//! with a policy limiting them to the scope, and come with a session token that `SignedRequest`
+//! sends along with every request they sign.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac, NewMac};
+use serde_json::json;
+use sha1::Sha1;
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::core::DispatchSignedRequest;
+#[cfg(target_arch = "wasm32")]
+use crate::core::FetchDispatcher as DefaultDispatcher;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::core::HttpClient as DefaultDispatcher;
+use crate::credential::{AwsCredentials, CredentialsError};
+use crate::signature::{Region, SignedRequest};
+
+/// The shortest lifetime of federation tokens.
+pub const MIN_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// The longest lifetime of federation tokens.
+pub const MAX_TTL: Duration = Duration::from_secs(36 * 60 * 60);
+
+/// What the holder of scoped credentials may do with the objects of the scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScopeAction {
+    /// Read objects, `s3:GetObject`.
+    Read,
+    /// Upload objects, in one request or in parts.
+    Write,
+    /// Delete objects, `s3:DeleteObject`.
+    Delete,
+    /// List the keys of the scope, `s3:ListBucket` restricted to its prefix.
+    List,
+}
+
+impl ScopeAction {
+    /// The object actions of the policy granting this action.
+    fn object_actions(self) -> &'static [&'static str] {
+        match self {
+            ScopeAction::Read => &["s3:GetObject"],
+            ScopeAction::Write => &[
+                "s3:PutObject",
+                "s3:AbortMultipartUpload",
+                "s3:ListMultipartUploadParts",
+            ],
+            ScopeAction::Delete => &["s3:DeleteObject"],
+            ScopeAction::List => &[],
+        }
+    }
+}
+
+/// The part of a bucket temporary credentials give access to, what they allow there and how
+/// long they last.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CredentialScope {
+    /// The bucket.
+    pub bucket: String,
+    /// The prefix of the keys, empty for the whole bucket.
+    pub prefix: String,
+    /// What the credentials allow. Reading by default.
+    pub actions: Vec<ScopeAction>,
+    /// How long the credentials last, one hour by default. It's brought within `MIN_TTL` and
+    /// `MAX_TTL` when requesting them.
+    pub ttl: Duration,
+}
+
+impl CredentialScope {
+    /// A scope allowing to read the keys of `bucket` starting with `prefix` for an hour.
+    pub fn new(bucket: &str, prefix: &str) -> CredentialScope {
+        CredentialScope {
+            bucket: bucket.to_owned(),
+            prefix: prefix.to_owned(),
+            actions: vec![ScopeAction::Read],
+            ttl: Duration::from_secs(3600),
+        }
+    }
+
+    /// The scope allowing `actions` instead.
+    pub fn with_actions(mut self, actions: &[ScopeAction]) -> CredentialScope {
+        self.actions = actions.to_vec();
+        self
+    }
+
+    /// The scope lasting `ttl` instead.
+    pub fn with_ttl(mut self, ttl: Duration) -> CredentialScope {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The JSON policy limiting the credentials to the scope.
+    pub fn policy(&self) -> String {
+        let mut object_actions = self
+            .actions
+            .iter()
+            .flat_map(|action| action.object_actions())
+            .collect::<Vec<_>>();
+        object_actions.sort_unstable();
+        object_actions.dedup();
+
+        let mut statements = Vec::new();
+        if !object_actions.is_empty() {
+            statements.push(json!({
+                "Effect": "Allow",
+                "Action": object_actions,
+                "Resource": [format!("arn:aws:s3:::{}/{}*", self.bucket, self.prefix)],
+            }));
+        }
+        if self.actions.contains(&ScopeAction::List) {
+            statements.push(json!({
+                "Effect": "Allow",
+                "Action": ["s3:ListBucket"],
+                "Resource": [format!("arn:aws:s3:::{}", self.bucket)],
+                "Condition": {"StringLike": {"s3:prefix": [format!("{}*", self.prefix)]}},
+            }));
+        }
+        json!({"Version": "2012-10-17", "Statement": statements}).to_string()
+    }
+
+    /// The lifetime requested for the credentials, in seconds.
+    fn duration_seconds(&self) -> u64 {
+        self.ttl.clamp(MIN_TTL, MAX_TTL).as_secs()
+    }
+}
+
+/// Requests temporary credentials limited to a `CredentialScope` from an STS-compatible
+/// endpoint, on behalf of long-lived credentials.
+///
+/// The requests are signed with Signature Version 2 of the query API.
+pub struct TemporaryCredentialsMinter<D = DefaultDispatcher> {
+    dispatcher: D,
+    region: Region,
+    credentials: AwsCredentials,
+}
+
+impl TemporaryCredentialsMinter {
+    /// A minter sending its requests to the STS endpoint of `region`, e.g. a
+    /// `Region::Custom` whose endpoint is the STS-compatible service, with the default TLS
+    /// client, or with `fetch` on `wasm32`.
+    pub fn new(region: Region, credentials: AwsCredentials) -> TemporaryCredentialsMinter {
+        #[cfg(not(target_arch = "wasm32"))]
+        let dispatcher = DefaultDispatcher::new().expect("failed to create request dispatcher");
+        #[cfg(target_arch = "wasm32")]
+        let dispatcher = DefaultDispatcher::new();
+        TemporaryCredentialsMinter::new_with(dispatcher, region, credentials)
+    }
+}
+
+impl<D: DispatchSignedRequest> TemporaryCredentialsMinter<D> {
+    /// A minter sending its requests with `dispatcher`.
+    pub fn new_with(
+        dispatcher: D,
+        region: Region,
+        credentials: AwsCredentials,
+    ) -> TemporaryCredentialsMinter<D> {
+        TemporaryCredentialsMinter {
+            dispatcher,
+            region,
+            credentials,
+        }
+    }
+
+    /// Request credentials limited to `scope` for the federated user `name`, which shows in
+    /// the logs of the requests made with them, e.g. the ID of the user of the app.
+    pub async fn mint(
+        &self,
+        name: &str,
+        scope: &CredentialScope,
+    ) -> Result<AwsCredentials, CredentialsError> {
+        let request = self.signed_request(name, scope, Utc::now());
+        let mut response = self
+            .dispatcher
+            .dispatch(request, None)
+            .await
+            .map_err(|err| CredentialsError::new(format!("Couldn't reach STS: {}", err)))?;
+        let body = response
+            .buffer_body()
+            .await
+            .map_err(|err| CredentialsError::new(format!("Couldn't read STS response: {}", err)))?;
+        if !response.status.is_success() {
+            return Err(CredentialsError::new(format!(
+                "STS answered {}: {}",
+                response.status,
+                error_message(&body)
+            )));
+        }
+        parse_credentials(&body)
+    }
+
+    /// The `GetFederationToken` request for `name` and `scope`, signed as sent at `now`.
+    pub fn signed_request(
+        &self,
+        name: &str,
+        scope: &CredentialScope,
+        now: DateTime<Utc>,
+    ) -> SignedRequest {
+        let mut request = SignedRequest::new("GET", "sts", &self.region, "/");
+        request.add_param("Action", "GetFederationToken");
+        request.add_param("Version", "2011-06-15");
+        request.add_param("Name", name);
+        request.add_param("Policy", &scope.policy());
+        request.add_param("DurationSeconds", &scope.duration_seconds().to_string());
+        request.add_param("AWSAccessKeyId", self.credentials.aws_access_key_id());
+        request.add_param("SignatureVersion", "2");
+        request.add_param("SignatureMethod", "HmacSHA1");
+        request.add_param("Timestamp", &now.format("%Y-%m-%dT%H:%M:%SZ").to_string());
+        request.complement();
+
+        // The parameters are sorted and encoded as in the string to sign.
+        let string_to_sign = format!(
+            "GET\n{}\n{}\n{}",
+            request.hostname(),
+            request.canonical_uri(),
+            request.canonical_query_string()
+        );
+        let mut hmac = Hmac::<Sha1>::new_varkey(self.credentials.aws_secret_access_key().as_ref())
+            .expect("failed to create hmac");
+        hmac.update(string_to_sign.as_bytes());
+        let signature = base64::encode(hmac.finalize().into_bytes());
+        request.add_param("Signature", &signature);
+        request.complement();
+        request
+    }
+}
+
+/// The credentials of a `GetFederationToken` response.
+fn parse_credentials(body: &[u8]) -> Result<AwsCredentials, CredentialsError> {
+    let mut key = None;
+    let mut secret = None;
+    let mut token = None;
+    let mut expiration = None;
+    let mut current = None;
+    for event in EventReader::new(body) {
+        match event
+            .map_err(|err| CredentialsError::new(format!("Invalid STS response: {}", err)))?
+        {
+            XmlEvent::StartElement { name, .. } => current = Some(name.local_name),
+            XmlEvent::Characters(text) => match current.as_deref() {
+                Some("AccessKeyId") => key = Some(text),
+                Some("SecretAccessKey") => secret = Some(text),
+                Some("SessionToken") => token = Some(text),
+                Some("Expiration") => expiration = Some(text),
+                _ => {}
+            },
+            XmlEvent::EndElement { .. } => current = None,
+            _ => {}
+        }
+    }
+    let (key, secret) = match (key, secret) {
+        (Some(key), Some(secret)) => (key, secret),
+        _ => {
+            return Err(CredentialsError::new(
+                "STS response holds no credentials".to_owned(),
+            ))
+        }
+    };
+    let expires_at = match expiration {
+        Some(expiration) => Some(DateTime::parse_from_rfc3339(&expiration)?.with_timezone(&Utc)),
+        None => None,
+    };
+    Ok(AwsCredentials::new(key, secret, token, expires_at))
+}
+
+/// The code and message of an STS error response, or the body itself if it isn't one.
+fn error_message(body: &[u8]) -> String {
+    let mut code = None;
+    let mut message = None;
+    let mut current = None;
+    for event in EventReader::new(body).into_iter().flatten() {
+        match event {
+            XmlEvent::StartElement { name, .. } => current = Some(name.local_name),
+            XmlEvent::Characters(text) => match current.as_deref() {
+                Some("Code") => code = Some(text),
+                Some("Message") => message = Some(text),
+                _ => {}
+            },
+            XmlEvent::EndElement { .. } => current = None,
+            _ => {}
+        }
+    }
+    match (code, message) {
+        (Some(code), Some(message)) => format!("{}: {}", code, message),
+        (Some(code), None) => code,
+        _ => String::from_utf8_lossy(body).into_owned(),
+    }
+}
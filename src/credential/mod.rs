@@ -1,20 +1,34 @@
+#[cfg(not(target_arch = "wasm32"))]
 pub use crate::credential::container::ContainerProvider;
 pub use crate::credential::environment::EnvironmentProvider;
+pub use crate::credential::federation::{
+    CredentialScope, ScopeAction, TemporaryCredentialsMinter, MAX_TTL, MIN_TTL,
+};
+#[cfg(not(target_arch = "wasm32"))]
 pub use crate::credential::instance_metadata::InstanceMetadataProvider;
+#[cfg(not(target_arch = "wasm32"))]
 pub use crate::credential::profile::ProfileProvider;
 pub use crate::credential::static_provider::StaticProvider;
 
+#[cfg(not(target_arch = "wasm32"))]
 mod container;
 mod environment;
+mod federation;
+#[cfg(not(target_arch = "wasm32"))]
 mod instance_metadata;
+#[cfg(not(target_arch = "wasm32"))]
 mod profile;
+#[cfg(not(target_arch = "wasm32"))]
 mod request;
 mod static_provider;
 
 use chrono::{DateTime, Duration as ChronoDuration, ParseError, Utc};
 use serde::Deserialize;
 
+use crate::core::clock::{Clock, SystemClock};
 use async_trait::async_trait;
+use futures::lock::Mutex;
+#[cfg(not(target_arch = "wasm32"))]
 use hyper::Error as HyperError;
 use std::collections::BTreeMap;
 use std::env::{var as env_var, VarError};
@@ -23,8 +37,8 @@ use std::fmt;
 use std::io::Error as IoError;
 use std::string::FromUtf8Error;
 use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
 use std::time::Duration;
-use tokio::sync::Mutex;
 
 /// Representation of anonymity
 pub trait Anonymous {
@@ -104,12 +118,17 @@ impl AwsCredentials {
 
     /// Determine whether or not the credentials are expired.
     pub fn credentials_are_expired(&self) -> bool {
+        self.credentials_are_expired_at(Utc::now())
+    }
+
+    /// Determine whether or not the credentials are expired at `now`.
+    pub fn credentials_are_expired_at(&self, now: DateTime<Utc>) -> bool {
         match self.expires_at {
             Some(ref e) =>
             // This is a rough hack to hopefully avoid someone requesting creds then sitting on them
             // before issuing the request:
             {
-                *e < Utc::now() + ChronoDuration::seconds(20)
+                *e < now + ChronoDuration::seconds(20)
             }
             None => false,
         }
@@ -182,6 +201,7 @@ impl From<IoError> for CredentialsError {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<HyperError> for CredentialsError {
     fn from(err: HyperError) -> CredentialsError {
         CredentialsError::new(format!("Couldn't connect to credentials provider: {}", err))
@@ -230,14 +250,25 @@ impl<P: ProvideAwsCredentials + Send + Sync> ProvideAwsCredentials for Arc<P> {
 pub struct AutoRefreshingProvider<P: ProvideAwsCredentials + 'static> {
     credentials_provider: P,
     current_credentials: Arc<Mutex<Option<Result<AwsCredentials, CredentialsError>>>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl<P: ProvideAwsCredentials + 'static> AutoRefreshingProvider<P> {
     /// Create a new `AutoRefreshingProvider` around the provided base provider.
     pub fn new(provider: P) -> Result<AutoRefreshingProvider<P>, CredentialsError> {
+        AutoRefreshingProvider::with_clock(provider, Arc::new(SystemClock))
+    }
+
+    /// Create a new `AutoRefreshingProvider` that checks the expiry of the cached credentials
+    /// against `clock`.
+    pub fn with_clock(
+        provider: P,
+        clock: Arc<dyn Clock>,
+    ) -> Result<AutoRefreshingProvider<P>, CredentialsError> {
         Ok(AutoRefreshingProvider {
             credentials_provider: provider,
             current_credentials: Arc::new(Mutex::new(None)),
+            clock,
         })
     }
 
@@ -270,7 +301,7 @@ impl<P: ProvideAwsCredentials + Send + Sync + 'static> ProvideAwsCredentials
                 }
                 Some(Err(e)) => return Err(e.clone()),
                 Some(Ok(creds)) => {
-                    if creds.credentials_are_expired() {
+                    if creds.credentials_are_expired_at(self.clock.now()) {
                         *guard = None;
                     } else {
                         return Ok(creds.clone());
@@ -295,9 +326,11 @@ impl<P: ProvideAwsCredentials + Send + Sync + 'static> ProvideAwsCredentials
 /// is as locked down as possible using security best practices for your operating system.
 ///
 /// [credential_process]: https://docs.aws.amazon.com/cli/latest/topic/config-vars.html#sourcing-credentials-from-external-processes
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Clone)]
 pub struct DefaultCredentialsProvider(AutoRefreshingProvider<ChainProvider>);
 
+#[cfg(not(target_arch = "wasm32"))]
 impl DefaultCredentialsProvider {
     /// Creates a new thread-safe `DefaultCredentialsProvider`.
     pub fn new() -> Result<DefaultCredentialsProvider, CredentialsError> {
@@ -306,6 +339,7 @@ impl DefaultCredentialsProvider {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[async_trait]
 impl ProvideAwsCredentials for DefaultCredentialsProvider {
     async fn credentials(&self) -> Result<AwsCredentials, CredentialsError> {
@@ -348,6 +382,7 @@ impl ProvideAwsCredentials for DefaultCredentialsProvider {
 /// is as locked down as possible using security best practices for your operating system.
 ///
 /// [credential_process]: https://docs.aws.amazon.com/cli/latest/topic/config-vars.html#sourcing-credentials-from-external-processes
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, Clone)]
 pub struct ChainProvider {
     environment_provider: EnvironmentProvider,
@@ -356,6 +391,7 @@ pub struct ChainProvider {
     profile_provider: Option<ProfileProvider>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl ChainProvider {
     /// Set the timeout on the provider to the specified duration.
     pub fn set_timeout(&mut self, duration: Duration) {
@@ -364,6 +400,7 @@ impl ChainProvider {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 async fn chain_provider_credentials(
     provider: ChainProvider,
 ) -> Result<AwsCredentials, CredentialsError> {
@@ -386,6 +423,7 @@ async fn chain_provider_credentials(
     ))
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[async_trait]
 impl ProvideAwsCredentials for ChainProvider {
     async fn credentials(&self) -> Result<AwsCredentials, CredentialsError> {
@@ -393,6 +431,7 @@ impl ProvideAwsCredentials for ChainProvider {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl ChainProvider {
     /// Create a new `ChainProvider` using a `ProfileProvider` with the default settings.
     pub fn new() -> ChainProvider {
@@ -415,6 +454,7 @@ impl ChainProvider {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl Default for ChainProvider {
     fn default() -> Self {
         Self::new()
@@ -437,6 +477,7 @@ fn non_empty_env_var(name: &str) -> Option<String> {
 }
 
 /// Parses the response from an AWS Metadata Service, either from an IAM Role, or a Container.
+#[cfg(not(target_arch = "wasm32"))]
 fn parse_credentials_from_aws_service(response: &str) -> Result<AwsCredentials, CredentialsError> {
     Ok(serde_json::from_str::<AwsCredentials>(response)?)
 }
@@ -0,0 +1,34 @@
+//! Formatting and parsing of the dates in headers and XML bodies.
+//!
+//! HTTP headers such as `Expires` and `Last-Modified` use the RFC 1123 format
+//! (`Wed, 28 Mar 2007 21:16:59 GMT`), while XML elements and the object lock headers use
+//! ISO 8601 (`2007-03-28T21:16:59.000Z`).
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Format `date` for an HTTP header.
+pub(crate) fn format_http_date(date: &DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Format `date` as ISO 8601, with milliseconds.
+pub(crate) fn format_iso8601(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Parse an ISO 8601 date.
+pub(crate) fn parse_iso8601(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Parse the date of an HTTP header, accepting ISO 8601 too since some servers send it.
+/// Returns `None` for values that are in neither format, such as the `0` some servers send as
+/// an already expired `Expires`.
+pub(crate) fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+        .or_else(|| parse_iso8601(value))
+}
@@ -0,0 +1,132 @@
+//! Guarding against deletions, e.g. to preview what a cleanup tool would delete:
+//!
+//! ```rust,ignore
+//! let log = DryRunLog::new();
+//! let mut config = client.config().clone();
+//! config.deletion_protection = DeletionProtection::DryRun(log.clone());
+//! client.set_config(config);
+//!
+//! let options = SyncOptions { delete: true, ..Default::default() };
+//! sync_up(&client, "local/", "bucket", "backup/", &options).await?;
+//! for deletion in log.planned() {
+//!     println!("would delete {}/{}", deletion.bucket, deletion.key.unwrap_or_default());
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use http::{HeaderMap, StatusCode};
+use tracing::info;
+
+use crate::audit::bucket_and_key;
+use crate::core::HttpResponse;
+use crate::signature::{ByteStream, SignedRequest};
+
+/// What `S3Client` does with the requests deleting a bucket, an object or a bucket
+/// configuration. Aborting a multipart upload isn't guarded, as it only discards parts that
+/// were never completed into an object.
+#[derive(Clone, Debug, Default)]
+pub enum DeletionProtection {
+    /// Send deletions as usual.
+    #[default]
+    Off,
+    /// Don't send deletions: log them at info level to the `ks3::dry_run` target, record them
+    /// in the log, and report them as successful with an empty `204 No Content` response.
+    DryRun(DryRunLog),
+    /// Don't send deletions, failing them with `Ks3Error::Validation`.
+    Deny,
+}
+
+/// A deletion that wasn't sent because of `DeletionProtection::DryRun`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedDeletion {
+    /// The operation, e.g. `DeleteObject`.
+    pub operation: &'static str,
+    /// The bucket.
+    pub bucket: String,
+    /// The key of the object, for object deletions.
+    pub key: Option<String>,
+    /// The version of the object, for deletions of a given version.
+    pub version_id: Option<String>,
+}
+
+impl fmt::Display for PlannedDeletion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.operation, self.bucket)?;
+        if let Some(ref key) = self.key {
+            write!(f, "/{}", key)?;
+        }
+        if let Some(ref version_id) = self.version_id {
+            write!(f, " (version {})", version_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// The deletions skipped in dry-run mode, shared between the clones of the log.
+#[derive(Clone, Debug, Default)]
+pub struct DryRunLog {
+    planned: Arc<Mutex<Vec<PlannedDeletion>>>,
+}
+
+impl DryRunLog {
+    /// Create an empty log.
+    pub fn new() -> DryRunLog {
+        DryRunLog::default()
+    }
+
+    /// The deletions skipped so far, in the order they were requested.
+    pub fn planned(&self) -> Vec<PlannedDeletion> {
+        self.planned.lock().expect("dry-run log poisoned").clone()
+    }
+
+    /// The deletions skipped so far, emptying the log.
+    pub fn take(&self) -> Vec<PlannedDeletion> {
+        std::mem::take(&mut *self.planned.lock().expect("dry-run log poisoned"))
+    }
+
+    fn push(&self, deletion: PlannedDeletion) {
+        self.planned
+            .lock()
+            .expect("dry-run log poisoned")
+            .push(deletion);
+    }
+}
+
+/// What to do instead of sending `request`, if it's a deletion the protection applies to: the
+/// response to report, or the reason to fail it.
+pub(crate) fn guard(
+    protection: &DeletionProtection,
+    operation: &'static str,
+    request: &SignedRequest,
+) -> Option<Result<HttpResponse, String>> {
+    if let DeletionProtection::Off = *protection {
+        return None;
+    }
+    if request.method != "DELETE" || operation == "AbortMultipartUpload" {
+        return None;
+    }
+    let (bucket, key) = bucket_and_key(&request.path);
+    let deletion = PlannedDeletion {
+        operation,
+        bucket,
+        key,
+        version_id: request.params.get("versionId").cloned().flatten(),
+    };
+    match *protection {
+        DeletionProtection::Off => None,
+        DeletionProtection::DryRun(ref log) => {
+            info!(target: "ks3::dry_run", deletion = %deletion, "deletion skipped");
+            log.push(deletion);
+            Some(Ok(HttpResponse::new(
+                StatusCode::NO_CONTENT,
+                HeaderMap::default(),
+                ByteStream::from(Vec::new()),
+            )))
+        }
+        DeletionProtection::Deny => {
+            Some(Err(format!("{} refused by deletion protection", deletion)))
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Client-side (envelope) encryption of object data.
+//!
+//! Every object is encrypted with its own random 256-bit data key using AES-GCM. The data key is
+//! itself encrypted ("wrapped") with a master key that never leaves the client, and stored next
+//! to the object in `x-amz-meta-*` headers using the V2 layout of the other SDKs' encryption
+//! clients:
+//!
+//! | Metadata key                       | Value                                         |
+//! |------------------------------------|-----------------------------------------------|
+//! | `x-amz-key-v2`                     | base64 of the wrapped data key                |
+//! | `x-amz-iv`                         | base64 of the 96-bit content IV               |
+//! | `x-amz-cek-alg`                    | `AES/GCM/NoPadding`                           |
+//! | `x-amz-wrap-alg`                   | `AES/GCM`                                     |
+//! | `x-amz-tag-len`                    | `128`                                         |
+//! | `x-amz-matdesc`                    | `{}`                                          |
+//! | `x-amz-unencrypted-content-length` | size of the plaintext                         |
+//!
+//! The wrapped key is the 96-bit IV used for wrapping followed by the AES-GCM ciphertext and tag
+//! of the data key. Bodies are encrypted and decrypted in memory.
+
+use std::convert::TryFrom;
+
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::Aes256Gcm;
+use bytes::BytesMut;
+use futures::StreamExt;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::core::error::Ks3Error;
+use crate::metadata::Metadata;
+use crate::request::*;
+use crate::s3::S3;
+
+const KEY_V2_META: &str = "x-amz-key-v2";
+const IV_META: &str = "x-amz-iv";
+const CEK_ALG_META: &str = "x-amz-cek-alg";
+const WRAP_ALG_META: &str = "x-amz-wrap-alg";
+const TAG_LEN_META: &str = "x-amz-tag-len";
+const MATDESC_META: &str = "x-amz-matdesc";
+const UNENCRYPTED_LENGTH_META: &str = "x-amz-unencrypted-content-length";
+
+const CEK_ALG: &str = "AES/GCM/NoPadding";
+const WRAP_ALG: &str = "AES/GCM";
+const IV_LEN: usize = 12;
+
+/// An `S3` client wrapper that encrypts objects before upload and decrypts them after download.
+///
+/// Only `put_object` and `get_object` are wrapped; use `inner` for every other operation.
+pub struct EncryptedS3Client<C> {
+    inner: C,
+    master_key: Aes256Gcm,
+}
+
+impl<C> EncryptedS3Client<C>
+where
+    C: S3 + Sync,
+{
+    /// Wrap `inner`, protecting data keys with the 256-bit `master_key`.
+    pub fn new(inner: C, master_key: [u8; 32]) -> EncryptedS3Client<C> {
+        EncryptedS3Client {
+            inner,
+            master_key: Aes256Gcm::new((&master_key).into()),
+        }
+    }
+
+    /// The wrapped client, which sends requests unchanged.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Encrypt the body of `input` and upload it along with the envelope metadata.
+    ///
+    /// The `Content-Length` and `Content-MD5` of the request are replaced, as they describe the
+    /// plaintext rather than the data that is sent.
+    pub async fn put_object(
+        &self,
+        mut input: PutObjectRequest,
+    ) -> Result<PutObjectOutput, Ks3Error<PutObjectError>> {
+        let mut plaintext = BytesMut::new();
+        if let Some(mut body) = input.body.take() {
+            while let Some(chunk) = body.next().await {
+                plaintext.extend_from_slice(&chunk?);
+            }
+        }
+
+        let mut data_key = [0u8; 32];
+        let mut iv = [0u8; IV_LEN];
+        let mut wrap_iv = [0u8; IV_LEN];
+        OsRng.fill_bytes(&mut data_key);
+        OsRng.fill_bytes(&mut iv);
+        OsRng.fill_bytes(&mut wrap_iv);
+
+        let ciphertext = Aes256Gcm::new((&data_key).into())
+            .encrypt((&iv).into(), plaintext.as_ref())
+            .map_err(|_| Ks3Error::Validation("failed to encrypt object data".to_owned()))?;
+        let mut wrapped_key = wrap_iv.to_vec();
+        wrapped_key.extend(
+            self.master_key
+                .encrypt((&wrap_iv).into(), data_key.as_ref())
+                .map_err(|_| Ks3Error::Validation("failed to wrap data key".to_owned()))?,
+        );
+
+        let metadata = input.metadata.get_or_insert_with(Metadata::new);
+        let envelope = vec![
+            (KEY_V2_META, base64::encode(&wrapped_key)),
+            (IV_META, base64::encode(iv)),
+            (CEK_ALG_META, CEK_ALG.to_owned()),
+            (WRAP_ALG_META, WRAP_ALG.to_owned()),
+            (TAG_LEN_META, "128".to_owned()),
+            (MATDESC_META, "{}".to_owned()),
+            (UNENCRYPTED_LENGTH_META, plaintext.len().to_string()),
+        ];
+        for (key, value) in envelope {
+            metadata
+                .insert(key, value)
+                .map_err(|e| Ks3Error::Validation(e.to_string()))?;
+        }
+        input.content_length = None;
+        input.content_md5 = None;
+        input.body = Some(StreamingBody::from(ciphertext));
+        self.inner.put_object(input).await
+    }
+
+    /// Download an object and decrypt its body.
+    ///
+    /// Objects without envelope metadata are returned as they are. Range requests are rejected,
+    /// as AES-GCM can only authenticate the object as a whole.
+    pub async fn get_object(
+        &self,
+        input: GetObjectRequest,
+    ) -> Result<GetObjectOutput, Ks3Error<GetObjectError>> {
+        if input.range.is_some() || input.part_number.is_some() {
+            return Err(Ks3Error::Validation(
+                "ranged reads of client-side encrypted objects are not supported".to_owned(),
+            ));
+        }
+        let mut output = self.inner.get_object(input).await?;
+        let envelope = match output.metadata.as_ref() {
+            Some(metadata) if metadata.contains_key(KEY_V2_META) => metadata.clone(),
+            _ => return Ok(output),
+        };
+
+        let mut ciphertext = BytesMut::new();
+        if let Some(mut body) = output.body.take() {
+            while let Some(chunk) = body.next().await {
+                ciphertext.extend_from_slice(&chunk?);
+            }
+        }
+        let plaintext = self
+            .decrypt(&envelope, &ciphertext)
+            .map_err(Ks3Error::Validation)?;
+        output.content_length = Some(plaintext.len() as i64);
+        output.body = Some(StreamingBody::from(plaintext));
+        Ok(output)
+    }
+
+    fn decrypt(&self, envelope: &Metadata, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        let field = |name: &str| {
+            envelope
+                .get(name)
+                .ok_or_else(|| format!("missing encryption metadata {}", name))
+        };
+        let cek_alg = field(CEK_ALG_META)?;
+        if cek_alg != CEK_ALG {
+            return Err(format!(
+                "unsupported content encryption algorithm {}",
+                cek_alg
+            ));
+        }
+        let wrap_alg = field(WRAP_ALG_META)?;
+        if wrap_alg != WRAP_ALG {
+            return Err(format!("unsupported key wrapping algorithm {}", wrap_alg));
+        }
+        let decode = |name: &str| {
+            base64::decode(field(name)?).map_err(|e| format!("invalid {}: {}", name, e))
+        };
+        let wrapped_key = decode(KEY_V2_META)?;
+        let iv = decode(IV_META)?;
+        if wrapped_key.len() <= IV_LEN {
+            return Err("malformed encryption metadata".to_owned());
+        }
+        let (wrap_iv, wrapped_key) = wrapped_key.split_at(IV_LEN);
+        let wrap_iv = <[u8; IV_LEN]>::try_from(wrap_iv).expect("split at the IV length");
+        let iv = <[u8; IV_LEN]>::try_from(iv.as_slice())
+            .map_err(|_| "malformed encryption metadata".to_owned())?;
+
+        let data_key = self
+            .master_key
+            .decrypt((&wrap_iv).into(), wrapped_key)
+            .map_err(|_| "failed to unwrap data key".to_owned())?;
+        let data_key = <[u8; 32]>::try_from(data_key.as_slice())
+            .map_err(|_| "malformed encryption metadata".to_owned())?;
+        Aes256Gcm::new((&data_key).into())
+            .decrypt((&iv).into(), ciphertext)
+            .map_err(|_| "failed to decrypt object data".to_owned())
+    }
+}
@@ -0,0 +1,157 @@
+//! Enums for the header and element values that take one of a fixed set of strings.
+//!
+//! Each enum converts from and to the string sent on the wire. Values the SDK doesn't know,
+//! such as a storage class added to the service later, are kept in the `Unknown` variant
+//! instead of failing, so that responses always parse:
+//!
+//! ```rust,ignore
+//! let class: StorageClass = "STANDARD_IA".parse().unwrap();
+//! assert_eq!(class, StorageClass::StandardIa);
+//! assert_eq!(StorageClass::from("GLACIER"), StorageClass::Unknown("GLACIER".to_owned()));
+//! ```
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $value:expr,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+        #[cfg_attr(feature = "deserialize_structs", derive(Deserialize), serde(from = "String"))]
+        #[cfg_attr(feature = "serialize_structs", derive(Serialize), serde(into = "String"))]
+        #[non_exhaustive]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            /// A value the SDK doesn't know.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The value sent on the wire.
+            pub fn as_str(&self) -> &str {
+                match *self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(ref value) => value,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(value: &str) -> Result<$name, Infallible> {
+                Ok(match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(value.to_owned()),
+                })
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> $name {
+                match value.parse() {
+                    Ok(value) => value,
+                    Err(never) => match never {},
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> $name {
+                $name::from(&value[..])
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                match value {
+                    $name::Unknown(value) => value,
+                    value => value.as_str().to_owned(),
+                }
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// A canned ACL, sent in the `x-amz-acl` header of buckets and objects.
+    CannedAcl {
+        /// Only the owner has access.
+        Private => "private",
+        /// Anyone can read.
+        PublicRead => "public-read",
+        /// Anyone can read and write.
+        PublicReadWrite => "public-read-write",
+        /// Authenticated users can read.
+        AuthenticatedRead => "authenticated-read",
+        /// The bucket owner can read the object.
+        BucketOwnerRead => "bucket-owner-read",
+        /// The bucket owner has full control of the object.
+        BucketOwnerFullControl => "bucket-owner-full-control",
+    }
+}
+
+string_enum! {
+    /// The storage class of an object.
+    StorageClass {
+        /// Frequently accessed data. The default.
+        Standard => "STANDARD",
+        /// Infrequently accessed data.
+        StandardIa => "STANDARD_IA",
+        /// Rarely accessed data.
+        DeepIa => "DEEP_IA",
+        /// Archived data, which must be restored before it can be read.
+        Archive => "ARCHIVE",
+    }
+}
+
+string_enum! {
+    /// A server-side encryption algorithm, sent in the `x-amz-server-side-encryption` header.
+    ServerSideEncryption {
+        /// Encryption with keys managed by the service.
+        Aes256 => "AES256",
+        /// Encryption with keys managed by the key management service.
+        AwsKms => "aws:kms",
+    }
+}
+
+string_enum! {
+    /// The retention mode of a locked object.
+    ObjectLockMode {
+        /// Users with special permissions can remove the lock.
+        Governance => "GOVERNANCE",
+        /// Nobody can remove the lock before it expires.
+        Compliance => "COMPLIANCE",
+    }
+}
+
+string_enum! {
+    /// Who pays for a request, sent in the `x-amz-request-payer` header.
+    RequestPayer {
+        /// The requester pays, as required for requester-pays buckets.
+        Requester => "requester",
+    }
+}
@@ -0,0 +1,173 @@
+//! Decoding the event notifications a bucket sends when objects change, e.g. to a queue or a
+//! webhook:
+//!
+//! ```rust,ignore
+//! let notification = EventNotification::from_slice(&payload)?;
+//! for record in &notification.records {
+//!     if record.kind() == EventKind::ObjectCreated {
+//!         let object = record.object_handle(&client);
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! Fields missing from a payload are left empty rather than failing the decoding, as the
+//! fields sent vary with the event and the version of the format.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+
+use crate::resource::ObjectHandle;
+use crate::s3::S3Client;
+
+/// A notification, holding the events it reports.
+///
+/// The test notification sent when notifications are configured has no records.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct EventNotification {
+    /// The events, in the order they were sent.
+    #[serde(rename = "Records", default)]
+    pub records: Vec<EventRecord>,
+}
+
+impl EventNotification {
+    /// Decode the JSON payload of a notification.
+    pub fn from_slice(payload: &[u8]) -> Result<EventNotification, serde_json::Error> {
+        serde_json::from_slice(payload)
+    }
+}
+
+impl FromStr for EventNotification {
+    type Err = serde_json::Error;
+
+    /// Decode the JSON payload of a notification.
+    fn from_str(payload: &str) -> Result<EventNotification, serde_json::Error> {
+        serde_json::from_str(payload)
+    }
+}
+
+/// The kind of an event, from the part of its name before the colon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// An object was uploaded, copied or completed from a multipart upload.
+    ObjectCreated,
+    /// An object was deleted, or a delete marker was created.
+    ObjectRemoved,
+    /// A restore of an archived object was started or completed.
+    ObjectRestore,
+    /// Any other event.
+    Other,
+}
+
+/// An event reported by a notification.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventRecord {
+    /// The version of the format of the record, e.g. `2.1`.
+    pub event_version: Option<String>,
+    /// The service that sent the event.
+    pub event_source: Option<String>,
+    /// The region of the bucket.
+    #[serde(alias = "region")]
+    pub aws_region: Option<String>,
+    /// When the request that caused the event completed.
+    pub event_time: Option<DateTime<Utc>>,
+    /// The name of the event, e.g. `ObjectCreated:Put`.
+    pub event_name: String,
+    /// The bucket and the object the event is about.
+    pub s3: EventEntity,
+}
+
+impl EventRecord {
+    /// The kind of the event, ignoring an `s3:` prefix on its name.
+    pub fn kind(&self) -> EventKind {
+        let name = self.event_name.trim_start_matches("s3:");
+        match name.split(':').next().unwrap_or_default() {
+            "ObjectCreated" => EventKind::ObjectCreated,
+            "ObjectRemoved" => EventKind::ObjectRemoved,
+            "ObjectRestore" => EventKind::ObjectRestore,
+            _ => EventKind::Other,
+        }
+    }
+
+    /// The name of the bucket.
+    pub fn bucket(&self) -> &str {
+        &self.s3.bucket.name
+    }
+
+    /// The key of the object, decoded. See `EventObject::decoded_key`.
+    pub fn key(&self) -> Cow<'_, str> {
+        self.s3.object.decoded_key()
+    }
+
+    /// A handle to the object of the event, backed by `client`.
+    pub fn object_handle(&self, client: &S3Client) -> ObjectHandle {
+        client.bucket(self.bucket()).object(self.key())
+    }
+}
+
+/// The bucket and the object an event is about.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventEntity {
+    /// The ID of the notification configuration that sent the event.
+    pub configuration_id: Option<String>,
+    /// The bucket.
+    pub bucket: EventBucket,
+    /// The object.
+    pub object: EventObject,
+}
+
+/// The bucket an event is about.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EventBucket {
+    /// The name of the bucket.
+    pub name: String,
+    /// The ARN of the bucket.
+    pub arn: Option<String>,
+}
+
+/// The object an event is about.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventObject {
+    /// The key of the object, URL-encoded as in the notification. See `decoded_key`.
+    pub key: String,
+    /// The size of the object in bytes, for the events creating it.
+    pub size: Option<u64>,
+    /// The ETag of the object, without quotes.
+    #[serde(alias = "etag")]
+    pub e_tag: Option<String>,
+    /// The version of the object, in versioned buckets.
+    pub version_id: Option<String>,
+    /// A hexadecimal value ordering the events of the same key, see `is_after`.
+    pub sequencer: Option<String>,
+}
+
+impl EventObject {
+    /// The key of the object, with the URL encoding of the notification undone: `+` stands for
+    /// a space, and other characters are percent-encoded.
+    pub fn decoded_key(&self) -> Cow<'_, str> {
+        if !self.key.contains(['+', '%']) {
+            return Cow::Borrowed(&self.key);
+        }
+        let key = self.key.replace('+', " ");
+        Cow::Owned(percent_decode_str(&key).decode_utf8_lossy().into_owned())
+    }
+
+    /// Whether this event of the object happened after `other`, by comparing their sequencers.
+    /// None if either lacks a sequencer, which events of different keys can't be ordered by
+    /// anyway.
+    pub fn is_after(&self, other: &EventObject) -> Option<bool> {
+        let a = self.sequencer.as_deref()?;
+        let b = other.sequencer.as_deref()?;
+        // Sequencers are hexadecimal numbers of varying lengths: pad the shorter one.
+        let len = a.len().max(b.len());
+        Some(format!("{:0>len$}", a, len = len) > format!("{:0>len$}", b, len = len))
+    }
+}
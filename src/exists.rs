@@ -0,0 +1,83 @@
+//! Existence checks built on the HEAD operations.
+
+use std::error::Error;
+use std::fmt;
+
+use http::StatusCode;
+
+use crate::core::error::Ks3Error;
+use crate::request::*;
+use crate::s3::{S3Client, S3};
+
+/// Errors returned by `S3Client::object_exists` and `S3Client::bucket_exists`.
+#[derive(Debug, PartialEq)]
+pub enum ExistsError {
+    /// The credentials don't allow checking the resource, so its existence is unknown.
+    AccessDenied,
+}
+
+impl fmt::Display for ExistsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ExistsError::AccessDenied => write!(f, "Access denied"),
+        }
+    }
+}
+
+impl Error for ExistsError {}
+
+/// Translate a failed HEAD request, returning `None` when the error means that the resource is
+/// missing. Any service error of the HEAD operations means the resource is missing, as do bare
+/// 404 responses; bare 403 responses become `ExistsError::AccessDenied`.
+pub(crate) fn translate<E>(err: Ks3Error<E>) -> Option<Ks3Error<ExistsError>> {
+    match err {
+        Ks3Error::Service(_) => None,
+        Ks3Error::Unknown(ref res) if res.status == StatusCode::NOT_FOUND => None,
+        Ks3Error::Unknown(ref res) if res.status == StatusCode::FORBIDDEN => {
+            Some(Ks3Error::Service(ExistsError::AccessDenied))
+        }
+        Ks3Error::Unknown(res) => Some(Ks3Error::Unknown(res)),
+        Ks3Error::HttpDispatch(e) => Some(Ks3Error::HttpDispatch(e)),
+        Ks3Error::Credentials(e) => Some(Ks3Error::Credentials(e)),
+        Ks3Error::Validation(e) => Some(Ks3Error::Validation(e)),
+        Ks3Error::ParseError(e) => Some(Ks3Error::ParseError(e)),
+        Ks3Error::Blocking => Some(Ks3Error::Blocking),
+        Ks3Error::Timeout(idle) => Some(Ks3Error::Timeout(idle)),
+        Ks3Error::IntegrityCheckFailed { expected, actual } => {
+            Some(Ks3Error::IntegrityCheckFailed { expected, actual })
+        }
+        Ks3Error::Captured { error, request } => {
+            translate(*error).map(|error| error.with_request(request))
+        }
+    }
+}
+
+impl S3Client {
+    /// Return whether the object `key` exists in `bucket`.
+    pub async fn object_exists(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<bool, Ks3Error<ExistsError>> {
+        let input = HeadObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            ..Default::default()
+        };
+        match self.head_object(input).await {
+            Ok(_) => Ok(true),
+            Err(err) => translate(err).map_or(Ok(false), Err),
+        }
+    }
+
+    /// Return whether `bucket` exists.
+    pub async fn bucket_exists(&self, bucket: &str) -> Result<bool, Ks3Error<ExistsError>> {
+        let input = HeadBucketRequest {
+            bucket: bucket.to_owned(),
+        };
+        match self.head_bucket(input).await {
+            Ok(()) => Ok(true),
+            Err(err) => translate(err).map_or(Ok(false), Err),
+        }
+    }
+}
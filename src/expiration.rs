@@ -0,0 +1,118 @@
+//! The expiration of objects by lifecycle rules, reported in the `x-amz-expiration` header.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+
+use crate::dates::parse_http_date;
+use crate::request::*;
+#[cfg(feature = "multipart")]
+use crate::s3::ops::*;
+
+/// Errors returned when parsing an `x-amz-expiration` header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpirationParseError {
+    value: String,
+}
+
+impl fmt::Display for ExpirationParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid expiration: {:?}", self.value)
+    }
+}
+
+impl Error for ExpirationParseError {}
+
+/// When an object expires and the lifecycle rule expiring it, parsed from a header such as
+/// `expiry-date="Fri, 21 Dec 2012 00:00:00 GMT", rule-id="picture-deletion-rule"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LifecycleExpiration {
+    /// When the object expires.
+    pub expiry_date: DateTime<Utc>,
+    /// The ID of the lifecycle rule, decoded.
+    pub rule_id: String,
+}
+
+impl FromStr for LifecycleExpiration {
+    type Err = ExpirationParseError;
+
+    fn from_str(value: &str) -> Result<LifecycleExpiration, ExpirationParseError> {
+        let error = || ExpirationParseError {
+            value: value.to_owned(),
+        };
+        let mut expiry_date = None;
+        let mut rule_id = None;
+        let mut rest = value;
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+            // A `name="value"` pair, whose value may hold commas.
+            let (name, after) = rest.split_once('=').ok_or_else(error)?;
+            let after = after.strip_prefix('"').ok_or_else(error)?;
+            let (field, after) = after.split_once('"').ok_or_else(error)?;
+            match name.trim() {
+                "expiry-date" => expiry_date = Some(parse_http_date(field).ok_or_else(error)?),
+                "rule-id" => {
+                    rule_id = Some(percent_decode_str(field).decode_utf8_lossy().into_owned())
+                }
+                _ => {}
+            }
+            rest = after;
+        }
+        Ok(LifecycleExpiration {
+            expiry_date: expiry_date.ok_or_else(error)?,
+            rule_id: rule_id.unwrap_or_default(),
+        })
+    }
+}
+
+/// The expiration parsed from `header`, if it's present and valid.
+fn parse(header: Option<&String>) -> Option<LifecycleExpiration> {
+    header.and_then(|value| value.parse().ok())
+}
+
+impl PutObjectOutput {
+    /// The expiration of the object, parsed from `expiration`. None if no lifecycle rule
+    /// expires the object, or if the header can't be parsed.
+    pub fn lifecycle_expiration(&self) -> Option<LifecycleExpiration> {
+        parse(self.expiration.as_ref())
+    }
+}
+
+impl CopyObjectOutput {
+    /// The expiration of the copy, parsed from `expiration`. None if no lifecycle rule
+    /// expires the copy, or if the header can't be parsed.
+    pub fn lifecycle_expiration(&self) -> Option<LifecycleExpiration> {
+        parse(self.expiration.as_ref())
+    }
+}
+
+#[cfg(feature = "multipart")]
+impl CompleteMultipartUploadOutput {
+    /// The expiration of the object, parsed from `expiration`. None if no lifecycle rule
+    /// expires the object, or if the header can't be parsed.
+    pub fn lifecycle_expiration(&self) -> Option<LifecycleExpiration> {
+        parse(self.expiration.as_ref())
+    }
+}
+
+impl HeadObjectOutput {
+    /// The expiration of the object, parsed from `expiration`. None if no lifecycle rule
+    /// expires the object, or if the header can't be parsed.
+    pub fn lifecycle_expiration(&self) -> Option<LifecycleExpiration> {
+        parse(self.expiration.as_ref())
+    }
+}
+
+impl GetObjectOutput {
+    /// The expiration of the object, parsed from `expiration`. None if no lifecycle rule
+    /// expires the object, or if the header can't be parsed.
+    pub fn lifecycle_expiration(&self) -> Option<LifecycleExpiration> {
+        parse(self.expiration.as_ref())
+    }
+}
@@ -0,0 +1,155 @@
+//! Include/exclude filtering of object keys.
+//!
+//! A `KeyFilter` is built from glob and regex `Pattern`s and is shared by the helpers that walk
+//! many keys: the sync helpers, `batch_apply` and `delete_prefix`, and
+//! `S3Client::list_objects_matching`. For example, "everything under logs/ except temporary
+//! files" is written as:
+//!
+//! ```rust,ignore
+//! let filter = KeyFilter::new()
+//!     .include(Pattern::glob("logs/*")?)
+//!     .exclude(Pattern::glob("*.tmp")?);
+//! ```
+
+use std::fmt;
+
+use regex::Regex;
+
+/// A single pattern matched against a whole key.
+#[derive(Clone)]
+pub struct Pattern {
+    source: String,
+    regex: Regex,
+}
+
+impl Pattern {
+    /// Create a pattern from a glob expression.
+    ///
+    /// As with the `--include`/`--exclude` flags of `aws s3`, `*` matches any sequence of
+    /// characters including `/`, `?` matches a single character and `[...]` matches one
+    /// character from a set. Everything else is matched literally.
+    ///
+    /// A set that isn't valid, such as the reversed range `[z-a]`, is an error.
+    pub fn glob(glob: &str) -> Result<Pattern, regex::Error> {
+        Ok(Pattern {
+            source: glob.to_owned(),
+            regex: Regex::new(&glob_to_regex(glob))?,
+        })
+    }
+
+    /// Create a pattern from a regular expression. The expression must match the whole key.
+    pub fn regex(regex: &str) -> Result<Pattern, regex::Error> {
+        Ok(Pattern {
+            source: regex.to_owned(),
+            regex: Regex::new(&format!("^(?:{})$", regex))?,
+        })
+    }
+
+    /// Return true if the pattern matches `key`.
+    pub fn matches(&self, key: &str) -> bool {
+        self.regex.is_match(key)
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Pattern({:?})", self.source)
+    }
+}
+
+/// Translate a glob expression into an anchored regular expression.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                let mut class = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' && !class.is_empty() {
+                        closed = true;
+                        break;
+                    }
+                    class.push(c);
+                }
+                if closed {
+                    regex.push('[');
+                    if let Some(rest) = class.strip_prefix('!') {
+                        regex.push('^');
+                        class = rest.to_owned();
+                    }
+                    regex.push_str(&class.replace('\\', "\\\\").replace('[', "\\["));
+                    regex.push(']');
+                } else {
+                    regex.push_str(&regex::escape(&format!("[{}", class)));
+                }
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// A set of include and exclude patterns.
+///
+/// A key passes the filter when it matches at least one include pattern (or there are no
+/// include patterns) and matches none of the exclude patterns.
+#[derive(Clone, Debug, Default)]
+pub struct KeyFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl KeyFilter {
+    /// Create a filter that accepts every key.
+    pub fn new() -> KeyFilter {
+        KeyFilter::default()
+    }
+
+    /// Only accept keys matching `pattern` (or any other include pattern).
+    pub fn include(mut self, pattern: Pattern) -> KeyFilter {
+        self.include.push(pattern);
+        self
+    }
+
+    /// Reject keys matching `pattern`.
+    pub fn exclude(mut self, pattern: Pattern) -> KeyFilter {
+        self.exclude.push(pattern);
+        self
+    }
+
+    /// Return true if the filter has no patterns and accepts every key.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Return true if `key` passes the filter.
+    pub fn matches(&self, key: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p.matches(key)))
+            && !self.exclude.iter().any(|p| p.matches(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_glob_is_an_error() {
+        assert!(Pattern::glob("[z-a]").is_err());
+    }
+
+    #[test]
+    fn filter_includes_and_excludes() {
+        let filter = KeyFilter::new()
+            .include(Pattern::glob("logs/*").unwrap())
+            .exclude(Pattern::glob("*.tmp").unwrap());
+        assert!(filter.matches("logs/2020/a.log"));
+        assert!(!filter.matches("logs/a.tmp"));
+        assert!(!filter.matches("data/a.log"));
+    }
+}
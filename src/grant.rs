@@ -0,0 +1,153 @@
+//! Typed grantees for the `x-amz-grant-*` headers.
+//!
+//! The `grant_*` fields of `CreateBucketRequest`, `PutObjectRequest`,
+//! `CreateMultipartUploadRequest`, `PutObjectAclRequest` and `CopyObjectRequest` take a list of
+//! grantees formatted as `type="value"` pairs. `GrantList` renders that list:
+//!
+//! ```rust,ignore
+//! let request = PutObjectAclRequest::builder()
+//!     .bucket("photos")
+//!     .key("puppy.jpg")
+//!     .grant_read(GrantList::new().grantee(AclGrantee::AllUsers).canonical_id("1234"))
+//!     .build()?;
+//! ```
+
+use std::fmt;
+
+use crate::request::Grantee;
+
+/// The URI of the group of all users, including anonymous ones.
+pub const ALL_USERS_URI: &str = "http://acs.amazonaws.com/groups/global/AllUsers";
+/// The URI of the group of all authenticated users.
+pub const AUTHENTICATED_USERS_URI: &str =
+    "http://acs.amazonaws.com/groups/global/AuthenticatedUsers";
+
+/// Someone permissions are granted to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum AclGrantee {
+    /// A user, by canonical user ID.
+    CanonicalId(String),
+    /// A group, by URI.
+    Uri(String),
+    /// A user, by the email address of their account.
+    Email(String),
+    /// Everyone, including anonymous users.
+    AllUsers,
+    /// Every authenticated user.
+    AuthenticatedUsers,
+}
+
+impl AclGrantee {
+    /// The type and value of the grantee in the `type="value"` syntax of the grant headers.
+    fn pair(&self) -> (&str, &str) {
+        match *self {
+            AclGrantee::CanonicalId(ref id) => ("id", id),
+            AclGrantee::Uri(ref uri) => ("uri", uri),
+            AclGrantee::Email(ref email) => ("emailAddress", email),
+            AclGrantee::AllUsers => ("uri", ALL_USERS_URI),
+            AclGrantee::AuthenticatedUsers => ("uri", AUTHENTICATED_USERS_URI),
+        }
+    }
+}
+
+/// Renders the grantee as it appears in a grant header, e.g. `id="1234"`.
+impl fmt::Display for AclGrantee {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (kind, value) = self.pair();
+        write!(f, "{}=\"{}\"", kind, value)
+    }
+}
+
+/// The grantee of an access control policy, e.g. to build a `PutObjectAclRequest` with an
+/// explicit `AccessControlPolicy`.
+impl From<AclGrantee> for Grantee {
+    fn from(grantee: AclGrantee) -> Grantee {
+        match grantee {
+            AclGrantee::CanonicalId(id) => Grantee {
+                type_: "CanonicalUser".to_owned(),
+                id: Some(id),
+                ..Default::default()
+            },
+            AclGrantee::Email(email) => Grantee {
+                type_: "AmazonCustomerByEmail".to_owned(),
+                email_address: Some(email),
+                ..Default::default()
+            },
+            group => Grantee {
+                type_: "Group".to_owned(),
+                uri: Some(group.pair().1.to_owned()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// The value of a grant header: the grantees a permission is granted to.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GrantList {
+    grantees: Vec<AclGrantee>,
+}
+
+impl GrantList {
+    /// Create an empty list.
+    pub fn new() -> GrantList {
+        GrantList::default()
+    }
+
+    /// Add `grantee`.
+    pub fn grantee(mut self, grantee: AclGrantee) -> GrantList {
+        self.grantees.push(grantee);
+        self
+    }
+
+    /// Add the user with the canonical user ID `id`.
+    pub fn canonical_id<S: Into<String>>(self, id: S) -> GrantList {
+        self.grantee(AclGrantee::CanonicalId(id.into()))
+    }
+
+    /// Add the group identified by `uri`.
+    pub fn uri<S: Into<String>>(self, uri: S) -> GrantList {
+        self.grantee(AclGrantee::Uri(uri.into()))
+    }
+
+    /// Add the user whose account has the email address `email`.
+    pub fn email<S: Into<String>>(self, email: S) -> GrantList {
+        self.grantee(AclGrantee::Email(email.into()))
+    }
+
+    /// The grantees, in the order they were added.
+    pub fn grantees(&self) -> &[AclGrantee] {
+        &self.grantees
+    }
+}
+
+/// Renders the list as the value of a grant header, e.g. `id="1234", uri="http://..."`.
+impl fmt::Display for GrantList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, grantee) in self.grantees.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", grantee)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<AclGrantee> for GrantList {
+    fn from(grantee: AclGrantee) -> GrantList {
+        GrantList::new().grantee(grantee)
+    }
+}
+
+impl From<GrantList> for String {
+    fn from(grants: GrantList) -> String {
+        grants.to_string()
+    }
+}
+
+impl From<AclGrantee> for String {
+    fn from(grantee: AclGrantee) -> String {
+        grantee.to_string()
+    }
+}
@@ -0,0 +1,93 @@
+//! The prefix of the extension headers sent to the service.
+//!
+//! KS3 accepts the `x-amz-` headers of S3, and its own `x-kss-` headers with the same meaning,
+//! e.g. `x-kss-acl` or `x-kss-storage-class`. Some deployments, such as gateways checking
+//! headers against an allow-list, only accept the latter:
+//!
+//! ```rust,ignore
+//! let mut config = client.config().clone();
+//! config.header_dialect = HeaderDialect::Kss;
+//! client.set_config(config);
+//! ```
+//!
+//! Responses are read the same way whatever the dialect: an `x-kss-` header the service answers
+//! with is also made available under its `x-amz-` name, so the fields of the outputs are filled
+//! in from either.
+
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::signature::SignedRequest;
+
+const AMZ_PREFIX: &str = "x-amz-";
+const KSS_PREFIX: &str = "x-kss-";
+
+/// The prefix of the extension headers `S3Client` sends, set with `Ks3Config::header_dialect`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HeaderDialect {
+    /// Send `x-amz-` headers, as S3 does.
+    #[default]
+    Amz,
+    /// Send `x-kss-` headers.
+    Kss,
+}
+
+impl HeaderDialect {
+    /// The prefix of the extension headers, `x-amz-` or `x-kss-`.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            HeaderDialect::Amz => AMZ_PREFIX,
+            HeaderDialect::Kss => KSS_PREFIX,
+        }
+    }
+
+    /// The name of the extension header `name`, given with either prefix, in this dialect, e.g.
+    /// `x-kss-acl` for `x-amz-acl`. Other headers are returned as they are.
+    pub fn header_name(self, name: &str) -> String {
+        match name
+            .strip_prefix(AMZ_PREFIX)
+            .or_else(|| name.strip_prefix(KSS_PREFIX))
+        {
+            Some(suffix) => format!("{}{}", self.prefix(), suffix),
+            None => name.to_owned(),
+        }
+    }
+}
+
+/// Rename the `x-amz-` headers of `request` to `x-kss-` ones when the dialect is `Kss`.
+pub(crate) fn apply_header_dialect(dialect: HeaderDialect, request: &mut SignedRequest) {
+    if dialect == HeaderDialect::Amz {
+        return;
+    }
+    let renamed: Vec<HeaderName> = request
+        .headers
+        .keys()
+        .filter(|name| name.as_str().starts_with(AMZ_PREFIX))
+        .cloned()
+        .collect();
+    for name in renamed {
+        let values: Vec<HeaderValue> = request.headers.get_all(&name).iter().cloned().collect();
+        request.headers.remove(&name);
+        let kss = HeaderName::from_bytes(dialect.header_name(name.as_str()).as_bytes())
+            .expect("renamed header name");
+        for value in values {
+            request.headers.append(&kss, value);
+        }
+    }
+}
+
+/// Make every `x-kss-` header of a response also available under its `x-amz-` name, unless the
+/// response has that header already.
+pub(crate) fn alias_kss_headers(headers: &mut HeaderMap<String>) {
+    let aliases: Vec<(HeaderName, String)> = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let suffix = name.as_str().strip_prefix(KSS_PREFIX)?;
+            let alias = HeaderName::from_bytes(format!("{}{}", AMZ_PREFIX, suffix).as_bytes());
+            Some((alias.ok()?, value.clone()))
+        })
+        .filter(|(alias, _)| !headers.contains_key(alias))
+        .collect();
+    for (alias, value) in aliases {
+        headers.append(alias, value);
+    }
+}
@@ -0,0 +1,219 @@
+//! Hedging `get_object` requests that take unusually long.
+//!
+//! With `Ks3Config::hedge_gets` set, a `get_object` of a `Range` whose response hasn't arrived
+//! after the delay of the `HedgePolicy` is sent a second time, and whichever response arrives
+//! first is used. The delay follows a percentile of the latencies of the first requests, so
+//! that only the slowest ones are hedged. A first request that loses is left to get its
+//! response, for its latency to be observed too, and its body is then dropped unread, which
+//! closes its connection.
+//!
+//! Requests of whole objects aren't hedged, as a second one could transfer the whole object
+//! again: read large objects by ranges to hedge them.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::{self, Either};
+use tracing::debug;
+
+use crate::core::clock::Instant;
+use crate::core::error::Ks3Error;
+use crate::core::runtime::default_runtime;
+use crate::request::{GetObjectError, GetObjectOutput, GetObjectRequest};
+use crate::s3::S3Client;
+
+/// The number of latencies the delay is computed from.
+const MAX_SAMPLES: usize = 256;
+
+/// The number of latencies to observe before the delay follows their percentile.
+const MIN_SAMPLES: usize = 20;
+
+/// When to send a second `get_object` request.
+///
+/// Clones share the latencies they observe.
+#[derive(Clone)]
+pub struct HedgePolicy {
+    percentile: f64,
+    initial_delay: Duration,
+    min_delay: Duration,
+    samples: Arc<Mutex<VecDeque<Duration>>>,
+}
+
+impl HedgePolicy {
+    /// A policy hedging requests slower than the 99th percentile of the latencies observed, and
+    /// slower than `initial_delay` until enough of them were observed.
+    pub fn new(initial_delay: Duration) -> HedgePolicy {
+        HedgePolicy {
+            percentile: 0.99,
+            initial_delay,
+            min_delay: Duration::from_millis(5),
+            samples: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_SAMPLES))),
+        }
+    }
+
+    /// Hedge requests slower than this percentile of the latencies observed, between 0 and 1.
+    pub fn with_percentile(mut self, percentile: f64) -> HedgePolicy {
+        self.percentile = percentile.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Never hedge requests faster than `min_delay`, however fast the others were.
+    pub fn with_min_delay(mut self, min_delay: Duration) -> HedgePolicy {
+        self.min_delay = min_delay;
+        self
+    }
+
+    /// How long to wait for a response before sending the request again.
+    pub fn delay(&self) -> Duration {
+        let samples = self.samples.lock().unwrap();
+        if samples.len() < MIN_SAMPLES {
+            return self.initial_delay.max(self.min_delay);
+        }
+        let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+        drop(samples);
+        sorted.sort_unstable();
+        let index = ((sorted.len() - 1) as f64 * self.percentile).round() as usize;
+        sorted[index].max(self.min_delay)
+    }
+
+    fn record(&self, latency: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+    }
+}
+
+impl fmt::Debug for HedgePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HedgePolicy")
+            .field("percentile", &self.percentile)
+            .field("initial_delay", &self.initial_delay)
+            .field("min_delay", &self.min_delay)
+            .field("delay", &self.delay())
+            .finish()
+    }
+}
+
+/// Whether the request failed to get a response at all, in which case the other one is
+/// waited for.
+fn is_dispatch_error(result: &Result<GetObjectOutput, Ks3Error<GetObjectError>>) -> bool {
+    matches!(result, Err(Ks3Error::HttpDispatch(_)))
+}
+
+/// `get_object` with `input`, sent a second time if it has a `Range` and no response arrived
+/// after the delay of `policy`.
+pub(crate) async fn hedged_get_object(
+    client: &S3Client,
+    input: GetObjectRequest,
+    policy: &HedgePolicy,
+) -> Result<GetObjectOutput, Ks3Error<GetObjectError>> {
+    if input.range.is_none() {
+        return client.send_get_object(input).await;
+    }
+
+    // Only the latencies of the first requests are observed: those of the requests that won
+    // a race would pull the delay down.
+    let primary = {
+        let client = client.clone();
+        let input = input.clone();
+        let policy = policy.clone();
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = client.send_get_object(input).await;
+            policy.record(started.elapsed());
+            result
+        })
+    };
+    let started = Instant::now();
+    let delay = default_runtime().sleep(policy.delay());
+    let primary = match future::select(primary, delay).await {
+        Either::Left((result, _)) => return result,
+        Either::Right(((), primary)) => primary,
+    };
+
+    debug!(
+        bucket = %input.bucket,
+        key = %input.key,
+        elapsed = ?started.elapsed(),
+        "hedging get_object"
+    );
+    let hedge = Box::pin(client.send_get_object(input));
+    match future::select(primary, hedge).await {
+        Either::Left((result, hedge)) if is_dispatch_error(&result) => hedge.await,
+        Either::Right((result, primary)) if is_dispatch_error(&result) => primary.await,
+        Either::Left((result, _)) => result,
+        Either::Right((result, primary)) => {
+            default_runtime().spawn(Box::pin(async move {
+                let _ = primary.await;
+            }));
+            result
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::config::Ks3Config;
+    use crate::credential::StaticProvider;
+    use crate::range::Range;
+    use crate::s3::S3;
+    use crate::signature::Region;
+    use crate::test::{ChaosDispatcher, MockRequestDispatcher};
+
+    /// A client hedging after 5 ms whose requests all take 50 ms, and the number of requests
+    /// it sent.
+    fn client() -> (S3Client, Arc<AtomicUsize>) {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let counted = sent.clone();
+        let dispatcher = MockRequestDispatcher::with_status(200)
+            .with_body("data")
+            .with_request_checker(move |_| {
+                counted.fetch_add(1, Ordering::SeqCst);
+            });
+        let dispatcher =
+            ChaosDispatcher::new(dispatcher).with_latency(1.0, Duration::from_millis(50));
+        let mut client = S3Client::new_with(
+            dispatcher,
+            StaticProvider::new_minimal("a".to_owned(), "b".to_owned()),
+            Region::default(),
+        );
+        client.set_config(Ks3Config {
+            hedge_gets: Some(HedgePolicy::new(Duration::from_millis(5))),
+            ..Default::default()
+        });
+        (client, sent)
+    }
+
+    fn request(range: Option<Range>) -> GetObjectRequest {
+        GetObjectRequest {
+            bucket: "bucket".to_owned(),
+            key: "key".to_owned(),
+            range,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn whole_objects_are_not_hedged() {
+        let (client, sent) = client();
+        client.get_object(request(None)).await.unwrap();
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn slow_ranges_are_hedged() {
+        let (client, sent) = client();
+        client
+            .get_object(request(Some(Range::bytes(0..=1))))
+            .await
+            .unwrap();
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,74 @@
+//! Reading objects as HTTP responses, for services proxying objects to their own clients.
+
+use http::header::{self, HeaderName, HeaderValue};
+use http::{Response, StatusCode};
+use hyper::Body;
+
+use crate::core::error::Ks3Error;
+use crate::dates::format_http_date;
+use crate::range::Range;
+use crate::request::*;
+use crate::s3::{S3Client, S3};
+
+impl S3Client {
+    /// Read the object `key` in `bucket`, or the part of it in `range`, as a response that a
+    /// `hyper` service, or any framework built on `hyper::Body`, can send as is.
+    ///
+    /// The response is `206 Partial Content` with a `Content-Range` header when a range was
+    /// read, and `200 OK` otherwise. It carries the `Content-Type`, `Content-Length`, `ETag`
+    /// and `Last-Modified` of the object, so clients can cache it and revalidate it, as well as
+    /// its `Cache-Control`, `Content-Encoding`, `Content-Disposition` and `Expires` if it has
+    /// them. The body is streamed from the service as the response is sent.
+    pub async fn get_object_http_body(
+        &self,
+        bucket: &str,
+        key: &str,
+        range: Option<Range>,
+    ) -> Result<Response<Body>, Ks3Error<GetObjectError>> {
+        let input = GetObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            range,
+            ..Default::default()
+        };
+        let output = self.get_object(input).await?;
+
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = if output.content_range.is_some() {
+            StatusCode::PARTIAL_CONTENT
+        } else {
+            StatusCode::OK
+        };
+        let headers = response.headers_mut();
+        let mut insert = |name: HeaderName, value: Option<String>| {
+            // Values the service sent back are valid header values; skip the odd one that isn't.
+            if let Some(value) = value.and_then(|value| HeaderValue::from_str(&value).ok()) {
+                headers.insert(name, value);
+            }
+        };
+        insert(header::CONTENT_TYPE, output.content_type);
+        insert(
+            header::CONTENT_LENGTH,
+            output.content_length.map(|len| len.to_string()),
+        );
+        insert(header::CONTENT_RANGE, output.content_range);
+        insert(header::ACCEPT_RANGES, output.accept_ranges);
+        insert(header::ETAG, output.e_tag);
+        insert(
+            header::LAST_MODIFIED,
+            output.last_modified.as_ref().map(format_http_date),
+        );
+        insert(header::CACHE_CONTROL, output.cache_control);
+        insert(header::CONTENT_ENCODING, output.content_encoding);
+        insert(header::CONTENT_DISPOSITION, output.content_disposition);
+        insert(
+            header::EXPIRES,
+            output.expires.as_ref().map(format_http_date),
+        );
+
+        if let Some(body) = output.body {
+            *response.body_mut() = Body::wrap_stream(body);
+        }
+        Ok(response)
+    }
+}
@@ -0,0 +1,128 @@
+//! End-to-end integrity checks of downloaded objects.
+//!
+//! `S3Client::get_object_verified` reads the whole body of an object and checks it against the
+//! ETag, for objects that weren't uploaded in parts, and against the CRC64 checksum that KS3
+//! reports in the `x-kss-checksum-crc64ecma` header. The computed checksums are returned whether
+//! or not the service reported anything to compare them with.
+
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use lazy_static::lazy_static;
+
+use crate::core::error::Ks3Error;
+use crate::enums::ServerSideEncryption;
+use crate::request::*;
+use crate::s3::{S3Client, S3};
+
+/// Checksums computed over a downloaded body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checksums {
+    /// The hex-encoded MD5 digest.
+    pub md5: String,
+    /// The CRC64 checksum, using the ECMA-182 polynomial as KS3 does.
+    pub crc64: u64,
+}
+
+impl Checksums {
+    /// Compute the checksums of `data`.
+    pub fn compute(data: &[u8]) -> Checksums {
+        Checksums {
+            md5: format!("{:x}", md5::compute(data)),
+            crc64: crc64(0, data),
+        }
+    }
+}
+
+/// An object whose body was read in full and verified.
+#[derive(Debug)]
+pub struct VerifiedObject {
+    /// The response of `get_object`, with the body taken out.
+    pub output: GetObjectOutput,
+    /// The content of the object.
+    pub body: Bytes,
+    /// The checksums computed over `body`.
+    pub checksums: Checksums,
+}
+
+/// Check `checksums` against those reported in `output`, returning the expected and actual
+/// values of the first mismatch.
+///
+/// Range requests are not checked, as the reported checksums cover the whole object. Neither
+/// are multipart ETags and ETags of objects encrypted with SSE-KMS or SSE-C, which aren't the
+/// MD5 of the content.
+pub fn find_mismatch(output: &GetObjectOutput, checksums: &Checksums) -> Option<(String, String)> {
+    if output.content_range.is_some() {
+        return None;
+    }
+    if let Some(expected) = output.checksum_crc64ecma.as_deref() {
+        if expected.parse::<u64>().ok() != Some(checksums.crc64) {
+            return Some((expected.to_owned(), checksums.crc64.to_string()));
+        }
+    }
+    let encrypted = output.sse_customer_algorithm.is_some()
+        || output.server_side_encryption == Some(ServerSideEncryption::AwsKms);
+    let e_tag = output.e_tag.as_deref().map(|e| e.trim_matches('"'))?;
+    if !encrypted && !e_tag.contains('-') && !e_tag.eq_ignore_ascii_case(&checksums.md5) {
+        return Some((e_tag.to_owned(), checksums.md5.clone()));
+    }
+    None
+}
+
+impl S3Client {
+    /// Download an object into memory and verify its content.
+    ///
+    /// Returns `Ks3Error::IntegrityCheckFailed` if the content doesn't match the checksums
+    /// reported by the service; see `find_mismatch` for the cases that can't be checked.
+    pub async fn get_object_verified(
+        &self,
+        input: GetObjectRequest,
+    ) -> Result<VerifiedObject, Ks3Error<GetObjectError>> {
+        let mut output = self.get_object(input).await?;
+        let mut body = BytesMut::new();
+        if let Some(mut stream) = output.body.take() {
+            while let Some(chunk) = stream.next().await {
+                body.extend_from_slice(&chunk?);
+            }
+        }
+        let body = body.freeze();
+        let checksums = Checksums::compute(&body);
+        if let Some((expected, actual)) = find_mismatch(&output, &checksums) {
+            return Err(Ks3Error::IntegrityCheckFailed { expected, actual });
+        }
+        Ok(VerifiedObject {
+            output,
+            body,
+            checksums,
+        })
+    }
+}
+
+/// The reflected ECMA-182 polynomial.
+const CRC64_POLY: u64 = 0xc96c_5795_d787_0f42;
+
+lazy_static! {
+    static ref CRC64_TABLE: [u64; 256] = {
+        let mut table = [0; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut crc = i as u64;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ CRC64_POLY
+                } else {
+                    crc >> 1
+                };
+            }
+            *entry = crc;
+        }
+        table
+    };
+}
+
+/// Continue the CRC64 checksum `crc` over `data`. Start with a `crc` of 0.
+pub fn crc64(crc: u64, data: &[u8]) -> u64 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc = CRC64_TABLE[((crc ^ u64::from(byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -0,0 +1,364 @@
+//! Reading inventory reports, enabled by the `inventory` feature.
+//!
+//! An inventory report is a `manifest.json` listing the data files of the report, all stored in
+//! the destination bucket of the inventory configuration. `read_manifest` fetches the manifest,
+//! `read_inventory_file` fetches one data file and parses it into `InventoryRecord`s, and
+//! `analyze_storage_classes` sums the objects and bytes held in each storage class over the
+//! whole report:
+//!
+//! ```rust,ignore
+//! let manifest = read_manifest(&client, "reports", "photos/daily/2020-06-01T00-00Z/manifest.json").await?;
+//! let report = analyze_storage_classes(&client, "reports", &manifest).await?;
+//! for (class, usage) in &report.by_storage_class {
+//!     println!("{}: {} objects, {} bytes", class, usage.objects, usage.bytes);
+//! }
+//! ```
+//!
+//! Only reports in the gzipped CSV format can be parsed; ORC and Parquet data files are
+//! rejected with `InventoryError::UnsupportedFormat`.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use flate2::read::MultiGzDecoder;
+use futures::StreamExt;
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+
+use crate::core::error::Ks3Error;
+use crate::dates::parse_iso8601;
+use crate::enums::StorageClass;
+use crate::request::*;
+use crate::s3::S3;
+pub use crate::storage_class::{StorageClassReport, StorageClassUsage};
+
+/// The only data file format that can be parsed.
+const CSV_FORMAT: &str = "CSV";
+
+/// Errors returned while reading an inventory report.
+#[derive(Debug)]
+pub enum InventoryError {
+    /// The manifest or a data file couldn't be downloaded.
+    Get(Ks3Error<GetObjectError>),
+    /// Reading or decompressing a downloaded file failed.
+    Io(io::Error),
+    /// The manifest isn't valid.
+    Manifest(serde_json::Error),
+    /// A data file doesn't match the checksum the manifest lists for it.
+    ChecksumMismatch {
+        /// The key of the data file.
+        key: String,
+    },
+    /// The report is in a format other than CSV.
+    UnsupportedFormat(String),
+    /// A row of a data file doesn't match the schema of the report.
+    Malformed {
+        /// The key of the data file.
+        key: String,
+        /// What is wrong with the row.
+        message: String,
+    },
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InventoryError::Get(ref err) => write!(f, "{}", err),
+            InventoryError::Io(ref err) => write!(f, "{}", err),
+            InventoryError::Manifest(ref err) => write!(f, "Invalid inventory manifest: {}", err),
+            InventoryError::ChecksumMismatch { ref key } => {
+                write!(f, "Inventory file {} doesn't match its checksum", key)
+            }
+            InventoryError::UnsupportedFormat(ref format) => {
+                write!(
+                    f,
+                    "Inventory reports in the {} format aren't supported",
+                    format
+                )
+            }
+            InventoryError::Malformed {
+                ref key,
+                ref message,
+            } => write!(f, "Malformed inventory file {}: {}", key, message),
+        }
+    }
+}
+
+impl Error for InventoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            InventoryError::Get(ref err) => Some(err),
+            InventoryError::Io(ref err) => Some(err),
+            InventoryError::Manifest(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The `manifest.json` of an inventory report.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryManifest {
+    /// The bucket the report lists.
+    pub source_bucket: String,
+    /// The ARN of the bucket the report is stored in.
+    pub destination_bucket: String,
+    /// The version of the manifest format.
+    pub version: String,
+    /// When the listing started, in milliseconds since the epoch.
+    pub creation_timestamp: String,
+    /// The format of the data files: `CSV`, `ORC` or `Parquet`.
+    pub file_format: String,
+    /// The fields of each record, in order, e.g. `Bucket, Key, Size, LastModifiedDate`.
+    pub file_schema: String,
+    /// The data files of the report.
+    pub files: Vec<InventoryFile>,
+}
+
+/// A data file of an inventory report.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct InventoryFile {
+    /// The key of the file in the destination bucket.
+    pub key: String,
+    /// The size of the file, compressed.
+    pub size: u64,
+    /// The hex MD5 of the file, compressed.
+    #[serde(rename = "MD5checksum")]
+    pub md5_checksum: String,
+}
+
+/// An object or object version listed by an inventory report.
+///
+/// The fields the report wasn't configured to include are `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InventoryRecord {
+    /// The bucket of the object.
+    pub bucket: String,
+    /// The key of the object, decoded.
+    pub key: String,
+    /// The version of the object, for reports listing all versions.
+    pub version_id: Option<String>,
+    /// Whether the version is the current one, for reports listing all versions.
+    pub is_latest: Option<bool>,
+    /// Whether the version is a delete marker, for reports listing all versions.
+    pub is_delete_marker: Option<bool>,
+    /// The size of the object in bytes.
+    pub size: Option<u64>,
+    /// When the object was last modified.
+    pub last_modified: Option<DateTime<Utc>>,
+    /// The entity tag of the object.
+    pub e_tag: Option<String>,
+    /// The storage class of the object.
+    pub storage_class: Option<StorageClass>,
+}
+
+impl StorageClassReport {
+    /// Count `record` in the usage of its storage class.
+    ///
+    /// Delete markers hold no data and are skipped. Records without a storage class, from
+    /// reports not configured to include it, are counted as `StorageClass::Standard`.
+    pub fn add(&mut self, record: &InventoryRecord) {
+        if record.is_delete_marker == Some(true) {
+            return;
+        }
+        self.record(record.storage_class.clone(), record.size.unwrap_or(0));
+    }
+}
+
+/// Download and parse the manifest stored under `key` in `bucket`.
+pub async fn read_manifest<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+) -> Result<InventoryManifest, InventoryError>
+where
+    C: S3 + Sync,
+{
+    let data = get_object(client, bucket, key).await?;
+    serde_json::from_slice(&data).map_err(InventoryError::Manifest)
+}
+
+/// Download the data file `file` of the report described by `manifest`, stored in `bucket`, and
+/// parse its records.
+///
+/// The file is checked against the MD5 listed in the manifest before it is decompressed.
+pub async fn read_inventory_file<C>(
+    client: &C,
+    bucket: &str,
+    manifest: &InventoryManifest,
+    file: &InventoryFile,
+) -> Result<Vec<InventoryRecord>, InventoryError>
+where
+    C: S3 + Sync,
+{
+    if !manifest.file_format.eq_ignore_ascii_case(CSV_FORMAT) {
+        return Err(InventoryError::UnsupportedFormat(
+            manifest.file_format.clone(),
+        ));
+    }
+    let data = get_object(client, bucket, &file.key).await?;
+    if !file
+        .md5_checksum
+        .eq_ignore_ascii_case(&format!("{:x}", md5::compute(&data)))
+    {
+        return Err(InventoryError::ChecksumMismatch {
+            key: file.key.clone(),
+        });
+    }
+    let mut csv = Vec::new();
+    MultiGzDecoder::new(&data[..])
+        .read_to_end(&mut csv)
+        .map_err(InventoryError::Io)?;
+    parse_csv(&manifest.file_schema, &csv).map_err(|message| InventoryError::Malformed {
+        key: file.key.clone(),
+        message,
+    })
+}
+
+/// Sum the usage of each storage class over all the data files of the report described by
+/// `manifest`, stored in `bucket`.
+///
+/// The files are read one at a time, so only the records of one file are held in memory.
+pub async fn analyze_storage_classes<C>(
+    client: &C,
+    bucket: &str,
+    manifest: &InventoryManifest,
+) -> Result<StorageClassReport, InventoryError>
+where
+    C: S3 + Sync,
+{
+    let mut report = StorageClassReport::default();
+    for file in &manifest.files {
+        for record in read_inventory_file(client, bucket, manifest, file).await? {
+            report.add(&record);
+        }
+    }
+    Ok(report)
+}
+
+async fn get_object<C>(client: &C, bucket: &str, key: &str) -> Result<Bytes, InventoryError>
+where
+    C: S3 + Sync,
+{
+    let output = client
+        .get_object(GetObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            ..Default::default()
+        })
+        .await
+        .map_err(InventoryError::Get)?;
+    let mut data = BytesMut::new();
+    if let Some(mut body) = output.body {
+        while let Some(chunk) = body.next().await {
+            data.extend_from_slice(&chunk.map_err(InventoryError::Io)?);
+        }
+    }
+    Ok(data.freeze())
+}
+
+/// Parse the rows of a CSV data file whose columns are the fields of `schema`.
+fn parse_csv(schema: &str, csv: &[u8]) -> Result<Vec<InventoryRecord>, String> {
+    let fields = schema.split(',').map(str::trim).collect::<Vec<_>>();
+    let csv = std::str::from_utf8(csv).map_err(|err| err.to_string())?;
+    split_rows(csv)?
+        .into_iter()
+        .enumerate()
+        .map(|(line, row)| {
+            if row.len() != fields.len() {
+                return Err(format!(
+                    "row {} has {} fields, the schema {}",
+                    line + 1,
+                    row.len(),
+                    fields.len()
+                ));
+            }
+            let mut record = InventoryRecord::default();
+            for (field, value) in fields.iter().zip(row) {
+                set_field(&mut record, field, value)
+                    .map_err(|message| format!("row {}: {}", line + 1, message))?;
+            }
+            Ok(record)
+        })
+        .collect()
+}
+
+/// Set the field `field` of `record` from its CSV value. Unknown fields are ignored.
+fn set_field(record: &mut InventoryRecord, field: &str, value: String) -> Result<(), String> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    let parse_bool = |value: &str| match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("invalid {}: {}", field, value)),
+    };
+    match field {
+        "Bucket" => record.bucket = value,
+        "Key" => {
+            // Keys are form-encoded, with spaces as `+`.
+            let key = value.replace('+', " ");
+            record.key = percent_decode_str(&key).decode_utf8_lossy().into_owned();
+        }
+        "VersionId" => record.version_id = Some(value),
+        "IsLatest" => record.is_latest = Some(parse_bool(&value)?),
+        "IsDeleteMarker" => record.is_delete_marker = Some(parse_bool(&value)?),
+        "Size" => {
+            record.size = Some(
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid Size: {}", value))?,
+            )
+        }
+        "LastModifiedDate" => {
+            record.last_modified = Some(
+                parse_iso8601(&value)
+                    .ok_or_else(|| format!("invalid LastModifiedDate: {}", value))?,
+            )
+        }
+        "ETag" => record.e_tag = Some(value),
+        "StorageClass" => record.storage_class = Some(StorageClass::from(value.as_str())),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Split CSV text into rows of unquoted fields.
+fn split_rows(csv: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_owned());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
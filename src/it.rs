@@ -0,0 +1,258 @@
+//! End-to-end tests against a local S3-compatible server, such as MinIO.
+//!
+//! `with_bucket` runs a test in a bucket of its own and removes the bucket afterwards, whether
+//! the test passed, failed or panicked, so test runs don't leak buckets into the server:
+//!
+//! ```rust,ignore
+//! #[tokio::test]
+//! async fn round_trip() {
+//!     let client = match it::client_from_env() {
+//!         Some(client) => client,
+//!         None => return, // no server configured
+//!     };
+//!     it::with_bucket(&client, "round-trip", |bucket| async move {
+//!         // ... use `bucket` ...
+//!     })
+//!     .await
+//!     .unwrap();
+//! }
+//! ```
+//!
+//! Enabled by the `testing` feature.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::panic::{resume_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::FutureExt;
+
+use crate::core::error::Ks3Error;
+use crate::core::request::HttpClient;
+use crate::core::Region;
+use crate::credential::StaticProvider;
+use crate::request::*;
+#[cfg(feature = "multipart")]
+use crate::s3::ops::*;
+use crate::s3::{S3Client, S3};
+
+/// The endpoint of the server, e.g. `http://localhost:9000`.
+pub const ENDPOINT_VAR: &str = "KS3_IT_ENDPOINT";
+/// The access key ID to use with the server.
+pub const ACCESS_KEY_VAR: &str = "KS3_IT_ACCESS_KEY_ID";
+/// The secret access key to use with the server.
+pub const SECRET_KEY_VAR: &str = "KS3_IT_SECRET_ACCESS_KEY";
+
+/// Errors returned by `with_bucket`.
+#[derive(Debug)]
+pub enum HarnessError {
+    /// The bucket couldn't be created.
+    CreateBucket(Ks3Error<CreateBucketError>),
+    /// The multipart uploads left in the bucket couldn't be listed.
+    #[cfg(feature = "multipart")]
+    ListMultipartUploads(Ks3Error<ListMultipartUploadsError>),
+    /// A multipart upload left in the bucket couldn't be aborted.
+    #[cfg(feature = "multipart")]
+    AbortMultipartUpload(Ks3Error<AbortMultipartUploadError>),
+    /// The objects left in the bucket couldn't be listed.
+    ListObjects(Ks3Error<ListObjectsError>),
+    /// An object left in the bucket couldn't be deleted.
+    DeleteObject(Ks3Error<DeleteObjectError>),
+    /// The emptied bucket couldn't be deleted.
+    DeleteBucket(Ks3Error<DeleteBucketError>),
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HarnessError::CreateBucket(ref err) => write!(f, "{}", err),
+            #[cfg(feature = "multipart")]
+            HarnessError::ListMultipartUploads(ref err) => write!(f, "{}", err),
+            #[cfg(feature = "multipart")]
+            HarnessError::AbortMultipartUpload(ref err) => write!(f, "{}", err),
+            HarnessError::ListObjects(ref err) => write!(f, "{}", err),
+            HarnessError::DeleteObject(ref err) => write!(f, "{}", err),
+            HarnessError::DeleteBucket(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for HarnessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            HarnessError::CreateBucket(ref err) => Some(err),
+            #[cfg(feature = "multipart")]
+            HarnessError::ListMultipartUploads(ref err) => Some(err),
+            #[cfg(feature = "multipart")]
+            HarnessError::AbortMultipartUpload(ref err) => Some(err),
+            HarnessError::ListObjects(ref err) => Some(err),
+            HarnessError::DeleteObject(ref err) => Some(err),
+            HarnessError::DeleteBucket(ref err) => Some(err),
+        }
+    }
+}
+
+/// A client for the server configured by `KS3_IT_ENDPOINT`, `KS3_IT_ACCESS_KEY_ID` and
+/// `KS3_IT_SECRET_ACCESS_KEY`, or `None` if they aren't all set, so that tests can be skipped
+/// when no server is available.
+///
+/// Requests use path-style URLs, which local servers support without DNS setup.
+pub fn client_from_env() -> Option<S3Client> {
+    let endpoint = env::var(ENDPOINT_VAR).ok()?;
+    let access_key = env::var(ACCESS_KEY_VAR).ok()?;
+    let secret_key = env::var(SECRET_KEY_VAR).ok()?;
+    let dispatcher = HttpClient::new().expect("failed to create request dispatcher");
+    let region = Region::Custom {
+        name: "local".to_owned(),
+        endpoint,
+    };
+    Some(S3Client::new_with(
+        dispatcher,
+        StaticProvider::new_minimal(access_key, secret_key),
+        region,
+    ))
+}
+
+/// A bucket name starting with `prefix` that no other call returns, in this process or in
+/// concurrent ones.
+///
+/// The prefix is lowercased and cut so that the name stays a valid bucket name.
+pub fn unique_bucket_name(prefix: &str) -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let suffix = format!(
+        "{:x}-{:x}-{:x}",
+        nanos & 0xffff_ffff_ffff,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    );
+    let prefix: String = prefix
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .take(62 - suffix.len())
+        .collect();
+    let prefix = prefix.trim_matches('-');
+    if prefix.is_empty() {
+        format!("it-{}", suffix)
+    } else {
+        format!("{}-{}", prefix, suffix)
+    }
+}
+
+/// Create a bucket with a unique name starting with `prefix`, run `test` with its name, then
+/// delete the bucket.
+///
+/// Before the bucket is deleted, the multipart uploads left in it are aborted and its objects
+/// are deleted. This runs even if `test` panics, after which the panic is resumed. Object
+/// versions and delete markers aren't listed, so tests should leave versioning disabled on the
+/// bucket.
+pub async fn with_bucket<C, F, Fut, T>(client: &C, prefix: &str, test: F) -> Result<T, HarnessError>
+where
+    C: S3 + Sync,
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let bucket = unique_bucket_name(prefix);
+    client
+        .create_bucket(CreateBucketRequest {
+            bucket: bucket.clone(),
+            ..Default::default()
+        })
+        .await
+        .map_err(HarnessError::CreateBucket)?;
+
+    let outcome = AssertUnwindSafe(test(bucket.clone())).catch_unwind().await;
+    let cleanup = remove_bucket(client, &bucket).await;
+    match outcome {
+        Ok(output) => cleanup.map(|_| output),
+        Err(panic) => resume_unwind(panic),
+    }
+}
+
+/// Abort the multipart uploads in `bucket`, delete its objects, then delete it.
+pub async fn remove_bucket<C>(client: &C, bucket: &str) -> Result<(), HarnessError>
+where
+    C: S3 + Sync,
+{
+    #[cfg(feature = "multipart")]
+    abort_uploads(client, bucket).await?;
+
+    // Deleting objects while listing would shift the markers, so list from the start every time.
+    loop {
+        let output = client
+            .list_objects(ListObjectsRequest {
+                bucket: bucket.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .map_err(HarnessError::ListObjects)?;
+        let objects = output.contents.unwrap_or_default();
+        if objects.is_empty() {
+            break;
+        }
+        for key in objects.into_iter().filter_map(|object| object.key) {
+            client
+                .delete_object(DeleteObjectRequest {
+                    bucket: bucket.to_owned(),
+                    key,
+                    ..Default::default()
+                })
+                .await
+                .map_err(HarnessError::DeleteObject)?;
+        }
+    }
+
+    client
+        .delete_bucket(DeleteBucketRequest {
+            bucket: bucket.to_owned(),
+        })
+        .await
+        .map_err(HarnessError::DeleteBucket)
+}
+
+/// Abort the multipart uploads in `bucket`.
+#[cfg(feature = "multipart")]
+async fn abort_uploads<C>(client: &C, bucket: &str) -> Result<(), HarnessError>
+where
+    C: S3 + Sync,
+{
+    let mut key_marker = None;
+    let mut upload_id_marker = None;
+    loop {
+        let output = client
+            .list_multipart_uploads(ListMultipartUploadsRequest {
+                bucket: bucket.to_owned(),
+                key_marker: key_marker.take(),
+                upload_id_marker: upload_id_marker.take(),
+                ..Default::default()
+            })
+            .await
+            .map_err(HarnessError::ListMultipartUploads)?;
+        for upload in output.uploads.unwrap_or_default() {
+            if let (Some(key), Some(upload_id)) = (upload.key, upload.upload_id) {
+                client
+                    .abort_multipart_upload(AbortMultipartUploadRequest {
+                        bucket: bucket.to_owned(),
+                        key,
+                        upload_id,
+                        ..Default::default()
+                    })
+                    .await
+                    .map_err(HarnessError::AbortMultipartUpload)?;
+            }
+        }
+        if !output.is_truncated.unwrap_or(false) || output.next_key_marker.is_none() {
+            break;
+        }
+        key_marker = output.next_key_marker;
+        upload_id_marker = output.next_upload_id_marker;
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Validation and normalization of object keys.
+//!
+//! Every operation on an object checks its key before sending the request, so that a key the
+//! service would reject fails with `Ks3Error::Validation` instead of a round trip. Operations
+//! writing a new object, `put_object`, `copy_object`, `create_multipart_upload` and
+//! `append_object`, also reject the keys `validate_key` rejects, which can't be sent unchanged.
+//! The other operations only check that the key isn't empty or too long, so that objects
+//! written with such keys by other clients can still be read, restored and deleted.
+//!
+//! Keys built from file paths can be cleaned up first with `Key::normalize`:
+//!
+//! ```rust,ignore
+//! let key = Key::normalize("/photos//2020/puppy.jpg")?;
+//! assert_eq!(key.as_str(), "photos/2020/puppy.jpg");
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::core::error::Ks3Error;
+use crate::signature::signer::encode_uri_path;
+
+/// The longest key allowed, in bytes of UTF-8.
+pub const MAX_KEY_LEN: usize = 1024;
+
+/// Errors returned for keys that can't be used.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyError {
+    /// The key is empty.
+    Empty,
+    /// The key is longer than `MAX_KEY_LEN` bytes.
+    TooLong(usize),
+    /// The key starts with `/`, which proxies and servers may merge with the `/` before the key
+    /// in the request path, addressing another object.
+    LeadingSlash,
+    /// The key has a control character, which object listings can't represent in XML.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KeyError::Empty => write!(f, "Object key is empty"),
+            KeyError::TooLong(len) => write!(
+                f,
+                "Object key is {} bytes long, more than the limit of {}",
+                len, MAX_KEY_LEN
+            ),
+            KeyError::LeadingSlash => write!(f, "Object key starts with '/'"),
+            KeyError::InvalidCharacter(c) => {
+                write!(f, "Object key has the invalid character {:?}", c)
+            }
+        }
+    }
+}
+
+impl Error for KeyError {}
+
+impl<E> From<KeyError> for Ks3Error<E> {
+    fn from(err: KeyError) -> Ks3Error<E> {
+        Ks3Error::Validation(err.to_string())
+    }
+}
+
+/// Check that `key` can be used as an object key.
+pub fn validate_key(key: &str) -> Result<(), KeyError> {
+    validate_key_length(key)?;
+    if key.starts_with('/') {
+        return Err(KeyError::LeadingSlash);
+    }
+    match key.chars().find(|c| c.is_control()) {
+        Some(c) => Err(KeyError::InvalidCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+/// Check that `key` isn't empty or longer than `MAX_KEY_LEN`, the keys the service rejects.
+pub(crate) fn validate_key_length(key: &str) -> Result<(), KeyError> {
+    if key.is_empty() {
+        return Err(KeyError::Empty);
+    }
+    if key.len() > MAX_KEY_LEN {
+        return Err(KeyError::TooLong(key.len()));
+    }
+    Ok(())
+}
+
+/// A valid object key.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Key(String);
+
+impl Key {
+    /// Check `key` and wrap it.
+    pub fn new<S: Into<String>>(key: S) -> Result<Key, KeyError> {
+        let key = key.into();
+        validate_key(&key)?;
+        Ok(Key(key))
+    }
+
+    /// Remove the leading slashes of `key` and merge repeated slashes, then check it. A
+    /// trailing slash, which marks a "directory" placeholder, is kept.
+    pub fn normalize(key: &str) -> Result<Key, KeyError> {
+        let mut normalized = String::with_capacity(key.len());
+        for c in key.chars() {
+            if c == '/' && (normalized.is_empty() || normalized.ends_with('/')) {
+                continue;
+            }
+            normalized.push(c);
+        }
+        Key::new(normalized)
+    }
+
+    /// The key.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The key percent-encoded for a URL path, keeping the slashes.
+    pub fn url_encoded(&self) -> String {
+        encode_uri_path(&self.0)
+    }
+}
+
+impl Deref for Key {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> String {
+        key.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn existing_keys_only_need_a_valid_length() {
+        assert_eq!(validate_key_length("/a\tb"), Ok(()));
+        assert_eq!(validate_key_length(""), Err(KeyError::Empty));
+        let long = "a".repeat(MAX_KEY_LEN + 1);
+        assert_eq!(
+            validate_key_length(&long),
+            Err(KeyError::TooLong(long.len()))
+        );
+    }
+
+    #[test]
+    fn new_keys_are_checked_fully() {
+        assert_eq!(Key::new("/a").unwrap_err(), KeyError::LeadingSlash);
+        assert_eq!(
+            Key::new("a\nb").unwrap_err(),
+            KeyError::InvalidCharacter('\n')
+        );
+        assert_eq!(Key::normalize("//a//b").unwrap().as_str(), "a/b");
+    }
+}
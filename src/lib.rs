@@ -1,8 +1,119 @@
+#[cfg(feature = "multipart")]
+pub mod archive;
+mod audit;
+pub mod batch;
+mod bucket_name;
+mod bucket_profile;
+pub mod buckets;
+mod builder;
+#[cfg(feature = "multipart")]
+pub mod checkpoint;
+#[cfg(all(feature = "bucket-lifecycle", feature = "restore-object"))]
+pub mod cold_archive;
+#[cfg(feature = "encoding")]
+pub mod compression;
+mod conditional;
+pub mod config;
 pub mod core;
 pub mod credential;
+mod dates;
+mod deletion;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+mod enums;
+pub mod events;
+mod exists;
+mod expiration;
+pub mod filter;
+mod grant;
+mod header_dialect;
+mod hedge;
+#[cfg(not(target_arch = "wasm32"))]
+mod http_body;
+pub mod integrity;
+#[cfg(feature = "inventory")]
+pub mod inventory;
+#[cfg(all(feature = "testing", not(target_arch = "wasm32")))]
+pub mod it;
+mod key;
+mod listing;
+#[cfg(feature = "append-object")]
+pub mod log_appender;
+mod metadata;
+mod metrics;
+pub mod mime;
+#[cfg(feature = "multipart")]
+pub mod mirror;
+#[cfg(feature = "multipart")]
+pub mod multipart;
+mod object_cache;
+mod object_metadata;
+#[cfg(feature = "otel")]
+mod otel;
+mod page_token;
+pub mod prelude;
+pub mod progress;
+mod public_url;
+mod range;
 mod request;
+mod resource;
+mod resume;
+pub mod retry;
 mod s3;
-mod signature;
+mod select;
+pub mod sharding;
+pub mod signature;
+pub mod storage_class;
+mod summary;
+pub mod sync;
+mod tagging;
+#[cfg(feature = "testing")]
+pub mod test;
+#[cfg(feature = "multipart")]
+pub mod transfer;
+pub mod versions;
+#[cfg(all(feature = "multipart", not(target_arch = "wasm32")))]
+mod writer;
 
+pub use crate::audit::{AuditLog, AuditRecord};
+pub use crate::bucket_name::{
+    validate_bucket_name, BucketNameError, MAX_BUCKET_NAME_LEN, MIN_BUCKET_NAME_LEN,
+};
+pub use crate::bucket_profile::BucketProfile;
+pub use crate::builder::*;
+pub use crate::conditional::{CachedVersion, ConditionalGet};
+pub use crate::config::{AcceptEncoding, Ks3Config, OperationConfig};
+pub use crate::deletion::{DeletionProtection, DryRunLog, PlannedDeletion};
+pub use crate::enums::*;
+pub use crate::exists::ExistsError;
+pub use crate::expiration::{ExpirationParseError, LifecycleExpiration};
+pub use crate::grant::{AclGrantee, GrantList, ALL_USERS_URI, AUTHENTICATED_USERS_URI};
+pub use crate::header_dialect::HeaderDialect;
+pub use crate::hedge::HedgePolicy;
+pub use crate::key::{validate_key, Key, KeyError, MAX_KEY_LEN};
+pub use crate::listing::ListObjectsStream;
+pub use crate::metadata::{Metadata, MetadataError, METADATA_SIZE_LIMIT};
+#[cfg(feature = "metrics")]
+pub use crate::metrics::PrometheusMetrics;
+pub use crate::metrics::{MetricsObserver, RequestMetrics};
+pub use crate::object_cache::ObjectCache;
+pub use crate::object_metadata::ObjectMetadata;
+pub use crate::page_token::{PageToken, PageTokenError};
+pub use crate::range::{Range, RangeParseError};
 pub use crate::request::*;
+pub use crate::resource::{BucketHandle, ObjectHandle};
+#[cfg(any(
+    feature = "append-object",
+    feature = "bucket-analytics",
+    feature = "bucket-lifecycle",
+    feature = "bucket-metrics",
+    feature = "multipart",
+    feature = "restore-object"
+))]
+pub use crate::s3::ops::*;
 pub use crate::s3::{S3Client, S3};
+pub use crate::select::{SelectEvent, SelectObjectContentEventStream, SelectStreamError};
+pub use crate::summary::ObjectSummary;
+pub use crate::tagging::{TagSet, TagSetParseError};
+#[cfg(all(feature = "multipart", not(target_arch = "wasm32")))]
+pub use crate::writer::{DropPolicy, UploadWriter};
@@ -0,0 +1,222 @@
+//! Listing objects without buffering whole `ListObjects` responses.
+//!
+//! A page of a listing holds up to a thousand `Contents` elements. `ListObjectsStream` parses
+//! each one as soon as it has arrived, so the first objects are available before the rest of
+//! the page is received, and the page is never held in memory as a whole:
+//!
+//! ```rust,ignore
+//! let mut objects = client.list_all_objects(ListObjectsRequest {
+//!     bucket: "bucket".to_owned(),
+//!     ..Default::default()
+//! });
+//! while let Some(object) = objects.next().await {
+//!     println!("{:?}", object?.key);
+//! }
+//! ```
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::BytesMut;
+use futures::{stream, Stream, StreamExt};
+use http::HeaderMap;
+
+use crate::core::error::Ks3Error;
+use crate::core::proto::xml::util::{self as xml_util, XmlParseError};
+use crate::request::{
+    ListObjectsError, ListObjectsOutput, ListObjectsOutputDeserializer, ListObjectsRequest, Object,
+    ObjectDeserializer, StreamingBody,
+};
+use crate::s3::S3Client;
+
+const CONTENTS_START: &[u8] = b"<Contents>";
+const CONTENTS_END: &[u8] = b"</Contents>";
+
+/// The objects of a page of a `ListObjects` response, parsed from its body as it arrives.
+///
+/// The other fields of the page, such as `is_truncated` and `next_marker`, are available from
+/// `output` once the stream has ended.
+pub struct ListObjectsStream {
+    body: StreamingBody,
+    /// Received bytes not parsed yet.
+    buffer: BytesMut,
+    /// The response without its `Contents` elements, parsed once the body has ended.
+    skeleton: BytesMut,
+    raw_headers: HeaderMap<String>,
+    last_key: Option<String>,
+    output: Option<ListObjectsOutput>,
+    done: bool,
+}
+
+impl ListObjectsStream {
+    pub(crate) fn new(body: StreamingBody, raw_headers: HeaderMap<String>) -> ListObjectsStream {
+        ListObjectsStream {
+            body,
+            buffer: BytesMut::new(),
+            skeleton: BytesMut::new(),
+            raw_headers,
+            last_key: None,
+            output: None,
+            done: false,
+        }
+    }
+
+    /// The page without its objects, once all of them were read. `contents` is always `None`.
+    pub fn output(&self) -> Option<&ListObjectsOutput> {
+        self.output.as_ref()
+    }
+
+    /// The key of the last object read so far.
+    pub fn last_key(&self) -> Option<&str> {
+        self.last_key.as_deref()
+    }
+
+    /// The marker to list the next page from, once all the objects were read and if the
+    /// listing is truncated.
+    pub fn next_marker(&self) -> Option<String> {
+        let output = self.output.as_ref()?;
+        if !output.is_truncated.unwrap_or(false) {
+            return None;
+        }
+        output.next_marker.clone().or_else(|| self.last_key.clone())
+    }
+
+    /// The next object in the buffered bytes, if they hold a whole `Contents` element. Bytes
+    /// outside of `Contents` elements are moved to the skeleton.
+    fn decode(&mut self) -> Result<Option<Object>, XmlParseError> {
+        let start = match find(&self.buffer, CONTENTS_START) {
+            Some(start) => start,
+            None => {
+                // Keep what could be the start of a `<Contents>` tag cut by the chunk boundary.
+                let keep = (CONTENTS_START.len() - 1).min(self.buffer.len());
+                let outside = self.buffer.split_to(self.buffer.len() - keep);
+                self.skeleton.extend_from_slice(&outside);
+                return Ok(None);
+            }
+        };
+        let outside = self.buffer.split_to(start);
+        self.skeleton.extend_from_slice(&outside);
+        let end = match find(&self.buffer, CONTENTS_END) {
+            Some(end) => end + CONTENTS_END.len(),
+            None => return Ok(None),
+        };
+        let element = self.buffer.split_to(end);
+        let object = xml_util::parse_body(&element, |name, stack| {
+            ObjectDeserializer::deserialize(name, stack)
+        })?;
+        if object.key.is_some() {
+            self.last_key = object.key.clone();
+        }
+        Ok(Some(object))
+    }
+
+    /// Parse the skeleton of the response once the body has ended.
+    fn finish(&mut self) -> Result<(), XmlParseError> {
+        if find(&self.buffer, CONTENTS_START).is_some() {
+            return Err(XmlParseError::new(
+                "ListObjects response ended within a Contents element",
+            ));
+        }
+        let rest = self.buffer.split();
+        self.skeleton.extend_from_slice(&rest);
+        let mut output = xml_util::parse_body(&self.skeleton, |name, stack| {
+            ListObjectsOutputDeserializer::deserialize(name, stack)
+        })?;
+        output.raw_headers = self.raw_headers.clone();
+        self.output = Some(output);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ListObjectsStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ListObjectsStream")
+            .field("buffered", &self.buffer.len())
+            .field("last_key", &self.last_key)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl Stream for ListObjectsStream {
+    type Item = Result<Object, Ks3Error<ListObjectsError>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if self.done {
+                return Poll::Ready(None);
+            }
+            match self.decode() {
+                Ok(Some(object)) => return Poll::Ready(Some(Ok(object))),
+                Ok(None) => {}
+                Err(err) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+            }
+            match futures::ready!(Pin::new(&mut self.body).poll_next(cx)) {
+                Some(Ok(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(Ks3Error::from(err))));
+                }
+                None => {
+                    self.done = true;
+                    if let Err(err) = self.finish() {
+                        return Poll::Ready(Some(Err(err.into())));
+                    }
+                    return Poll::Ready(None);
+                }
+            }
+        }
+    }
+}
+
+/// The offset of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+enum Page {
+    Next(ListObjectsRequest),
+    Reading(Box<ListObjectsStream>, ListObjectsRequest),
+    Done,
+}
+
+/// All the objects listed by `input` across pages, each page requested once the objects of
+/// the previous one were read.
+pub(crate) fn list_all_objects(
+    client: S3Client,
+    input: ListObjectsRequest,
+) -> impl Stream<Item = Result<Object, Ks3Error<ListObjectsError>>> + Send {
+    stream::unfold(Page::Next(input), move |mut page| {
+        let client = client.clone();
+        async move {
+            loop {
+                page = match page {
+                    Page::Done => return None,
+                    Page::Next(input) => match client.list_objects_streaming(input.clone()).await {
+                        Ok(objects) => Page::Reading(Box::new(objects), input),
+                        Err(err) => return Some((Err(err), Page::Done)),
+                    },
+                    Page::Reading(mut objects, input) => match objects.next().await {
+                        Some(Ok(object)) => {
+                            return Some((Ok(object), Page::Reading(objects, input)))
+                        }
+                        Some(Err(err)) => return Some((Err(err), Page::Done)),
+                        None => match objects.next_marker() {
+                            Some(marker) => Page::Next(ListObjectsRequest {
+                                marker: Some(marker),
+                                ..input
+                            }),
+                            None => Page::Done,
+                        },
+                    },
+                };
+            }
+        }
+    })
+}
@@ -0,0 +1,327 @@
+//! A log sink appending records to objects with `AppendObject`, moving to a new object once the
+//! current one is large enough:
+//!
+//! ```rust,ignore
+//! let mut log = LogAppender::new(Arc::new(client), "logs", "orders/")
+//!     .with_max_size(64 * 1024 * 1024);
+//! log.append(b"order 42 shipped").await?;
+//! ```
+//!
+//! Each object, a segment of the log, is named after the prefix, the time it was started at and
+//! its sequence number, e.g. `orders/20200601T120000.000Z-000001`, so that listing the prefix
+//! returns the segments in order. Every record of a segment is readable as soon as its append
+//! has returned.
+//!
+//! An appender writes to its segments alone: appenders running at once need prefixes of their
+//! own, e.g. ending with the name of the host.
+
+use std::fmt;
+use std::sync::Arc;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::core::clock::{Clock, SystemClock};
+use crate::core::error::Ks3Error;
+use crate::s3::ops::{AppendObjectError, AppendObjectRequest};
+use crate::s3::S3;
+use crate::signature::ByteStream;
+
+/// The size `LogAppender` rotates at by default.
+pub const DEFAULT_MAX_SEGMENT_SIZE: u64 = 128 * 1024 * 1024;
+
+/// How `LogAppender` delimits the records of a segment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecordFraming {
+    /// Each record is followed by a newline, for text records without newlines of their own.
+    #[default]
+    Newline,
+    /// Each record is preceded by its length, as a 32-bit big-endian integer, for records of
+    /// any content.
+    LengthPrefixed,
+}
+
+impl RecordFraming {
+    /// The number of bytes `record` takes once framed.
+    fn framed_len(self, record: &[u8]) -> usize {
+        match self {
+            RecordFraming::Newline => record.len() + 1,
+            RecordFraming::LengthPrefixed => record.len() + 4,
+        }
+    }
+
+    /// Write `record`, framed, to `buffer`.
+    fn frame(self, record: &[u8], buffer: &mut BytesMut) {
+        match self {
+            RecordFraming::Newline => {
+                buffer.extend_from_slice(record);
+                buffer.put_u8(b'\n');
+            }
+            RecordFraming::LengthPrefixed => {
+                buffer.put_u32(record.len() as u32);
+                buffer.extend_from_slice(record);
+            }
+        }
+    }
+}
+
+/// Where a record was written by `LogAppender`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppendedRecord {
+    /// The key of the segment holding the record.
+    pub key: String,
+    /// The offset of the framed record in the segment.
+    pub position: u64,
+    /// The size of the framed record.
+    pub len: u64,
+}
+
+/// The segment being appended to.
+#[derive(Debug)]
+struct Segment {
+    key: String,
+    position: u64,
+}
+
+/// Appends framed records to segments of a log stored under a prefix of a bucket, starting a
+/// new segment when the next records would take the current one past the maximum size.
+///
+/// A failed append keeps its segment, and the next records are appended to it at the same
+/// position. Records that were stored after all aren't appended twice: the service then
+/// refuses the position, and the appender leaves the segment behind for a new one.
+pub struct LogAppender<C: S3 + Send + Sync> {
+    client: Arc<C>,
+    bucket: String,
+    prefix: String,
+    max_size: u64,
+    framing: RecordFraming,
+    content_type: Option<String>,
+    clock: Arc<dyn Clock>,
+    segment: Option<Segment>,
+    sequence: u64,
+}
+
+impl<C: S3 + Send + Sync> LogAppender<C> {
+    /// An appender writing to segments of `bucket` under `prefix` with `client`, rotating at
+    /// `DEFAULT_MAX_SEGMENT_SIZE`, with newline-delimited records. Nothing is sent until the
+    /// first record.
+    pub fn new(client: Arc<C>, bucket: &str, prefix: &str) -> LogAppender<C> {
+        LogAppender {
+            client,
+            bucket: bucket.to_owned(),
+            prefix: prefix.to_owned(),
+            max_size: DEFAULT_MAX_SEGMENT_SIZE,
+            framing: RecordFraming::default(),
+            content_type: None,
+            clock: Arc::new(SystemClock),
+            segment: None,
+            sequence: 0,
+        }
+    }
+
+    /// The appender rotating at `max_size` bytes instead. A segment is never empty: records
+    /// larger than `max_size` are written to a segment of their own.
+    pub fn with_max_size(mut self, max_size: u64) -> LogAppender<C> {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The appender delimiting the records with `framing` instead.
+    pub fn with_framing(mut self, framing: RecordFraming) -> LogAppender<C> {
+        self.framing = framing;
+        self
+    }
+
+    /// The appender creating its segments with `content_type`.
+    pub fn with_content_type(mut self, content_type: &str) -> LogAppender<C> {
+        self.content_type = Some(content_type.to_owned());
+        self
+    }
+
+    /// The appender naming its segments after the time given by `clock` instead of the system
+    /// clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> LogAppender<C> {
+        self.clock = clock;
+        self
+    }
+
+    /// The key of the segment being appended to, if any.
+    pub fn current_key(&self) -> Option<&str> {
+        self.segment.as_ref().map(|segment| &segment.key[..])
+    }
+
+    /// The size of the segment being appended to, which is where the next record goes unless
+    /// it makes the appender rotate. 0 before the first record of a segment.
+    pub fn position(&self) -> u64 {
+        self.segment.as_ref().map_or(0, |segment| segment.position)
+    }
+
+    /// Write the next records to a new segment, whatever the size of the current one.
+    pub fn rotate(&mut self) {
+        self.segment = None;
+    }
+
+    /// Append `record`.
+    pub async fn append(
+        &mut self,
+        record: &[u8],
+    ) -> Result<AppendedRecord, Ks3Error<AppendObjectError>> {
+        let mut records = self.append_batch(&[record]).await?;
+        Ok(records.remove(0))
+    }
+
+    /// Append `records` with a single request, to the same segment, in order. Nothing is sent
+    /// when `records` is empty.
+    pub async fn append_batch<R: AsRef<[u8]>>(
+        &mut self,
+        records: &[R],
+    ) -> Result<Vec<AppendedRecord>, Ks3Error<AppendObjectError>> {
+        if records.is_empty() {
+            return Ok(Vec::new());
+        }
+        let size = records
+            .iter()
+            .map(|record| self.framing.framed_len(record.as_ref()))
+            .sum::<usize>();
+        let mut buffer = BytesMut::with_capacity(size);
+        for record in records {
+            self.framing.frame(record.as_ref(), &mut buffer);
+        }
+
+        if let Some(ref segment) = self.segment {
+            if segment.position > 0 && segment.position + size as u64 > self.max_size {
+                self.rotate();
+            }
+        }
+        let segment = match self.segment.take() {
+            Some(segment) => segment,
+            None => self.next_segment(),
+        };
+        let output = match self
+            .client
+            .append_object(self.request(&segment, buffer.freeze()))
+            .await
+        {
+            Ok(output) => output,
+            Err(err) => {
+                // Once the segment isn't where the appender thinks, it can't be appended to.
+                if !matches!(
+                    err,
+                    Ks3Error::Service(AppendObjectError::PositionNotEqualToLength(_))
+                        | Ks3Error::Service(AppendObjectError::ObjectNotAppendable(_))
+                ) {
+                    self.segment = Some(segment);
+                }
+                return Err(err);
+            }
+        };
+
+        let mut appended = Vec::with_capacity(records.len());
+        let mut position = segment.position;
+        for record in records {
+            let len = self.framing.framed_len(record.as_ref()) as u64;
+            appended.push(AppendedRecord {
+                key: segment.key.clone(),
+                position,
+                len,
+            });
+            position += len;
+        }
+        self.segment = Some(Segment {
+            position: output
+                .next_append_position
+                .map_or(position, |next| next.max(0) as u64),
+            key: segment.key,
+        });
+        Ok(appended)
+    }
+
+    /// A new segment, named after the current time and the next sequence number.
+    fn next_segment(&mut self) -> Segment {
+        self.sequence += 1;
+        Segment {
+            key: format!(
+                "{}{}-{:06}",
+                self.prefix,
+                self.clock.now().format("%Y%m%dT%H%M%S%.3fZ"),
+                self.sequence
+            ),
+            position: 0,
+        }
+    }
+
+    /// The request appending `data` to `segment`.
+    fn request(&self, segment: &Segment, data: Bytes) -> AppendObjectRequest {
+        AppendObjectRequest {
+            bucket: self.bucket.clone(),
+            key: segment.key.clone(),
+            position: segment.position as i64,
+            content_type: if segment.position == 0 {
+                self.content_type.clone()
+            } else {
+                None
+            },
+            body: Some(ByteStream::from(data)),
+            ..Default::default()
+        }
+    }
+}
+
+impl<C: S3 + Send + Sync> fmt::Debug for LogAppender<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LogAppender")
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("max_size", &self.max_size)
+            .field("framing", &self.framing)
+            .field("segment", &self.segment)
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::request::{CreateBucketRequest, PutObjectRequest};
+    use crate::test::FakeS3;
+
+    #[tokio::test]
+    async fn failed_append_keeps_the_segment() {
+        let client = Arc::new(FakeS3::new());
+        let mut log = LogAppender::new(client.clone(), "logs", "app/");
+        assert!(log.append(b"a").await.is_err());
+        let key = log.current_key().unwrap().to_owned();
+        assert_eq!(log.position(), 0);
+
+        client
+            .create_bucket(CreateBucketRequest {
+                bucket: "logs".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let record = log.append(b"a").await.unwrap();
+        assert_eq!(record.key, key);
+        assert_eq!(record.position, 0);
+        assert_eq!(log.position(), 2);
+    }
+
+    #[tokio::test]
+    async fn refused_position_rotates() {
+        let client = Arc::new(FakeS3::new().with_bucket("logs"));
+        let mut log = LogAppender::new(client.clone(), "logs", "app/");
+        let first = log.append(b"a").await.unwrap();
+        client
+            .put_object(PutObjectRequest {
+                bucket: "logs".to_owned(),
+                key: first.key.clone(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert!(log.append(b"b").await.is_err());
+        assert_eq!(log.current_key(), None);
+        let second = log.append(b"b").await.unwrap();
+        assert_ne!(second.key, first.key);
+    }
+}
@@ -0,0 +1,221 @@
+//! User-defined object metadata, sent and returned in `x-amz-meta-*` headers.
+
+use std::collections::{btree_map, BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::request::{GetObjectOutput, HeadObjectOutput};
+
+/// The largest total size of the keys and values of the metadata of an object, in bytes.
+pub const METADATA_SIZE_LIMIT: usize = 2048;
+
+/// Errors returned when metadata is rejected before being sent.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetadataError {
+    /// The key is empty or has characters that can't appear in a header name.
+    InvalidKey(String),
+    /// The value of the key has non-ASCII or control characters, which headers can't carry.
+    InvalidValue(String),
+    /// The keys and values would add up to more than `METADATA_SIZE_LIMIT` bytes.
+    TooLarge(usize),
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MetadataError::InvalidKey(ref key) => write!(f, "Invalid metadata key: {:?}", key),
+            MetadataError::InvalidValue(ref key) => {
+                write!(f, "Invalid value for metadata key: {:?}", key)
+            }
+            MetadataError::TooLarge(size) => write!(
+                f,
+                "Metadata is {} bytes, more than the limit of {}",
+                size, METADATA_SIZE_LIMIT
+            ),
+        }
+    }
+}
+
+impl Error for MetadataError {}
+
+/// The user-defined metadata of an object.
+///
+/// Keys are case-insensitive: they are lowercased on insertion and lookup, the way the service
+/// returns them, so a key reads back the same way it was written. Entries are checked when
+/// inserted, so that metadata the service would reject or mangle fails before the request is
+/// sent:
+///
+/// ```rust,ignore
+/// let mut metadata = Metadata::new();
+/// metadata.insert("Author", "alice")?;
+/// assert_eq!(metadata.get("author"), Some("alice"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "deserialize_structs",
+    derive(Deserialize),
+    serde(try_from = "BTreeMap<String, String>")
+)]
+#[cfg_attr(
+    feature = "serialize_structs",
+    derive(Serialize),
+    serde(into = "BTreeMap<String, String>")
+)]
+pub struct Metadata {
+    entries: BTreeMap<String, String>,
+}
+
+impl Metadata {
+    /// Create empty metadata.
+    pub fn new() -> Metadata {
+        Metadata::default()
+    }
+
+    /// Set `key` to `value`, returning the previous value.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Result<Option<String>, MetadataError>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let key = key.into().to_ascii_lowercase();
+        let value = value.into();
+        if key.is_empty() || !key.bytes().all(is_token_byte) {
+            return Err(MetadataError::InvalidKey(key));
+        }
+        if !value
+            .bytes()
+            .all(|b| b == b'\t' || (b' '..=b'~').contains(&b))
+        {
+            return Err(MetadataError::InvalidValue(key));
+        }
+        let replaced = self.get(&key).map_or(0, |old| key.len() + old.len());
+        let size = self.size() - replaced + key.len() + value.len();
+        if size > METADATA_SIZE_LIMIT {
+            return Err(MetadataError::TooLarge(size));
+        }
+        Ok(self.entries.insert(key, value))
+    }
+
+    /// Set `key` to `value` as returned by the service, without checking them.
+    pub(crate) fn insert_unchecked(&mut self, key: &str, value: String) {
+        self.entries.insert(key.to_ascii_lowercase(), value);
+    }
+
+    /// The value of `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .get(&key.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Whether `key` is set.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(&key.to_ascii_lowercase())
+    }
+
+    /// Remove `key`, returning its value.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.entries.remove(&key.to_ascii_lowercase())
+    }
+
+    /// The entries, ordered by key.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, String> {
+        self.entries.iter()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The total size of the keys and values in bytes, which the service limits to
+    /// `METADATA_SIZE_LIMIT`.
+    pub fn size(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|(key, value)| key.len() + value.len())
+            .sum()
+    }
+}
+
+/// Whether `b` can appear in a header name.
+fn is_token_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+impl<'a> IntoIterator for &'a Metadata {
+    type Item = (&'a String, &'a String);
+    type IntoIter = btree_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Metadata {
+    type Item = (String, String);
+    type IntoIter = btree_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl From<Metadata> for BTreeMap<String, String> {
+    fn from(metadata: Metadata) -> BTreeMap<String, String> {
+        metadata.entries
+    }
+}
+
+impl TryFrom<BTreeMap<String, String>> for Metadata {
+    type Error = MetadataError;
+
+    fn try_from(entries: BTreeMap<String, String>) -> Result<Metadata, MetadataError> {
+        let mut metadata = Metadata::new();
+        for (key, value) in entries {
+            metadata.insert(key, value)?;
+        }
+        Ok(metadata)
+    }
+}
+
+impl TryFrom<HashMap<String, String>> for Metadata {
+    type Error = MetadataError;
+
+    fn try_from(entries: HashMap<String, String>) -> Result<Metadata, MetadataError> {
+        let mut metadata = Metadata::new();
+        for (key, value) in entries {
+            metadata.insert(key, value)?;
+        }
+        Ok(metadata)
+    }
+}
+
+impl GetObjectOutput {
+    /// The value of the metadata `key` of the object.
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(key))
+    }
+}
+
+impl HeadObjectOutput {
+    /// The value of the metadata `key` of the object.
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(key))
+    }
+}
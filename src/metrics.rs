@@ -0,0 +1,394 @@
+//! Hooks for monitoring the requests sent by `S3Client`.
+//!
+//! Set `Ks3Config::metrics` to an implementation of `MetricsObserver` to be told of every
+//! request the client sends. With the `metrics` feature, `PrometheusMetrics` aggregates them
+//! into counters and histograms rendered in the Prometheus text format:
+//!
+//! ```rust,ignore
+//! let metrics = Arc::new(PrometheusMetrics::new());
+//! client.set_config(Ks3Config {
+//!     metrics: Some(metrics.clone()),
+//!     ..Default::default()
+//! });
+//! // In the handler of the `/metrics` endpoint:
+//! let body = metrics.render();
+//! ```
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::Stream;
+use http::StatusCode;
+use pin_project::pin_project;
+
+use crate::core::clock::Instant;
+use crate::core::RequestTimings;
+use crate::signature::ByteStream;
+
+/// What happened to a request sent by `S3Client`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestMetrics {
+    /// The operation, e.g. `PutObject`.
+    pub operation: &'static str,
+    /// The status code of the response, or `None` if no response was received.
+    pub status: Option<StatusCode>,
+    /// The time from sending the request to receiving the headers of the response. The body of
+    /// a `GetObject` response is still to be read at that point.
+    pub latency: Duration,
+    /// The size of the payload of the request, when known.
+    pub bytes_sent: Option<u64>,
+    /// The `Content-Length` of the response, when present.
+    pub bytes_received: Option<u64>,
+    /// The breakdown of `latency`, when measured by the dispatcher.
+    pub timings: Option<RequestTimings>,
+}
+
+impl RequestMetrics {
+    /// Whether the service asked the client to slow down, with a `503 Service Unavailable` or
+    /// `429 Too Many Requests` response.
+    pub fn is_throttled(&self) -> bool {
+        self.status == Some(StatusCode::SERVICE_UNAVAILABLE)
+            || self.status == Some(StatusCode::TOO_MANY_REQUESTS)
+    }
+}
+
+/// Receives the requests sent by `S3Client`. Every method does nothing by default.
+///
+/// The methods are called on the task sending the request, so they should be quick, e.g. only
+/// update counters.
+pub trait MetricsObserver: fmt::Debug + Send + Sync {
+    /// `operation` is about to be sent.
+    fn request_started(&self, _operation: &'static str) {}
+
+    /// A request got a response or failed to be sent.
+    fn request_completed(&self, _metrics: &RequestMetrics) {}
+
+    /// `operation` failed and is attempted again, for the `attempt`th time.
+    fn request_retried(&self, _operation: &'static str, _attempt: u32) {}
+
+    /// The body of the response to `operation`, of `bytes` bytes, was read to the end
+    /// `elapsed` after its headers were received. Not called for bodies that aren't read to the
+    /// end, or that fail.
+    fn transfer_completed(&self, _operation: &'static str, _bytes: u64, _elapsed: Duration) {}
+}
+
+/// Wrap `body` to call `MetricsObserver::transfer_completed` once it is read to the end.
+pub(crate) fn observe_transfer(
+    body: ByteStream,
+    observer: Arc<dyn MetricsObserver>,
+    operation: &'static str,
+) -> ByteStream {
+    body.map_stream(|body| ObservedTransfer {
+        inner: body,
+        observer: Some(observer),
+        operation,
+        started: Instant::now(),
+        bytes: 0,
+    })
+}
+
+#[pin_project]
+struct ObservedTransfer {
+    #[pin]
+    inner: ByteStream,
+    /// Taken when the observer is called.
+    observer: Option<Arc<dyn MetricsObserver>>,
+    operation: &'static str,
+    started: Instant,
+    bytes: u64,
+}
+
+impl Stream for ObservedTransfer {
+    type Item = Result<Bytes, std::io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let item = futures::ready!(this.inner.poll_next(cx));
+        match item {
+            Some(Ok(ref chunk)) => *this.bytes += chunk.len() as u64,
+            Some(Err(_)) => *this.observer = None,
+            None => {
+                if let Some(observer) = this.observer.take() {
+                    observer.transfer_completed(
+                        this.operation,
+                        *this.bytes,
+                        this.started.elapsed(),
+                    );
+                }
+            }
+        }
+        Poll::Ready(item)
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub use self::prometheus::PrometheusMetrics;
+
+#[cfg(feature = "metrics")]
+mod prometheus {
+    use std::collections::BTreeMap;
+    use std::fmt::{self, Write};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use super::{MetricsObserver, RequestMetrics};
+
+    /// Upper bounds of the buckets of the duration histograms, in seconds.
+    const LATENCY_BUCKETS: &[f64] = &[
+        0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+    ];
+
+    #[derive(Debug, Default)]
+    struct Histogram {
+        counts: Vec<u64>,
+        sum: f64,
+        count: u64,
+    }
+
+    impl Histogram {
+        fn observe(&mut self, value: f64) {
+            if self.counts.is_empty() {
+                self.counts = vec![0; LATENCY_BUCKETS.len()];
+            }
+            for (count, &bound) in self.counts.iter_mut().zip(LATENCY_BUCKETS) {
+                if value <= bound {
+                    *count += 1;
+                }
+            }
+            self.sum += value;
+            self.count += 1;
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct State {
+        in_flight: BTreeMap<&'static str, i64>,
+        requests: BTreeMap<(&'static str, String), u64>,
+        latency: BTreeMap<&'static str, Histogram>,
+        bytes_sent: BTreeMap<&'static str, u64>,
+        bytes_received: BTreeMap<&'static str, u64>,
+        retries: BTreeMap<&'static str, u64>,
+        throttles: BTreeMap<&'static str, u64>,
+        new_connections: BTreeMap<&'static str, u64>,
+        connect: BTreeMap<&'static str, Histogram>,
+        transfer: BTreeMap<&'static str, Histogram>,
+    }
+
+    /// A `MetricsObserver` aggregating the requests by operation, rendered by `render` in the
+    /// Prometheus text exposition format:
+    ///
+    /// * `ks3_requests_in_flight`: requests sent and not completed yet.
+    /// * `ks3_requests_total`: completed requests, also by `status` (`error` when no response
+    ///   was received).
+    /// * `ks3_request_duration_seconds`: histogram of the latency of the requests.
+    /// * `ks3_sent_bytes_total` and `ks3_received_bytes_total`: payload sizes.
+    /// * `ks3_retries_total`: retried requests.
+    /// * `ks3_throttled_requests_total`: requests the service throttled.
+    /// * `ks3_new_connections_total`: requests sent on a new connection.
+    /// * `ks3_connect_duration_seconds`: histogram of the time to establish new connections.
+    /// * `ks3_transfer_duration_seconds`: histogram of the time to read response bodies.
+    #[derive(Debug, Default)]
+    pub struct PrometheusMetrics {
+        state: Mutex<State>,
+    }
+
+    impl PrometheusMetrics {
+        /// Create a collector with no requests recorded.
+        pub fn new() -> PrometheusMetrics {
+            PrometheusMetrics::default()
+        }
+
+        /// The metrics in the Prometheus text exposition format.
+        pub fn render(&self) -> String {
+            let state = self.state.lock().unwrap();
+            let mut out = String::new();
+            // Writing to a `String` can't fail.
+            let _ = write_metrics(&mut out, &state);
+            out
+        }
+    }
+
+    impl MetricsObserver for PrometheusMetrics {
+        fn request_started(&self, operation: &'static str) {
+            let mut state = self.state.lock().unwrap();
+            *state.in_flight.entry(operation).or_default() += 1;
+        }
+
+        fn request_completed(&self, metrics: &RequestMetrics) {
+            let operation = metrics.operation;
+            let status = match metrics.status {
+                Some(status) => status.as_u16().to_string(),
+                None => "error".to_owned(),
+            };
+            let mut state = self.state.lock().unwrap();
+            *state.in_flight.entry(operation).or_default() -= 1;
+            *state.requests.entry((operation, status)).or_default() += 1;
+            state
+                .latency
+                .entry(operation)
+                .or_default()
+                .observe(metrics.latency.as_secs_f64());
+            if let Some(bytes) = metrics.bytes_sent {
+                *state.bytes_sent.entry(operation).or_default() += bytes;
+            }
+            if let Some(bytes) = metrics.bytes_received {
+                *state.bytes_received.entry(operation).or_default() += bytes;
+            }
+            if metrics.is_throttled() {
+                *state.throttles.entry(operation).or_default() += 1;
+            }
+            if let Some(connect) = metrics.timings.and_then(|timings| timings.connect) {
+                *state.new_connections.entry(operation).or_default() += 1;
+                state
+                    .connect
+                    .entry(operation)
+                    .or_default()
+                    .observe(connect.as_secs_f64());
+            }
+        }
+
+        fn request_retried(&self, operation: &'static str, _attempt: u32) {
+            let mut state = self.state.lock().unwrap();
+            *state.retries.entry(operation).or_default() += 1;
+        }
+
+        fn transfer_completed(&self, operation: &'static str, _bytes: u64, elapsed: Duration) {
+            let mut state = self.state.lock().unwrap();
+            state
+                .transfer
+                .entry(operation)
+                .or_default()
+                .observe(elapsed.as_secs_f64());
+        }
+    }
+
+    fn write_header(out: &mut String, name: &str, kind: &str, help: &str) -> fmt::Result {
+        writeln!(out, "# HELP {} {}", name, help)?;
+        writeln!(out, "# TYPE {} {}", name, kind)
+    }
+
+    fn write_counters<V: fmt::Display>(
+        out: &mut String,
+        name: &str,
+        kind: &str,
+        help: &str,
+        values: &BTreeMap<&'static str, V>,
+    ) -> fmt::Result {
+        write_header(out, name, kind, help)?;
+        for (operation, value) in values {
+            writeln!(out, "{}{{operation=\"{}\"}} {}", name, operation, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_histograms(
+        out: &mut String,
+        name: &str,
+        help: &str,
+        histograms: &BTreeMap<&'static str, Histogram>,
+    ) -> fmt::Result {
+        write_header(out, name, "histogram", help)?;
+        for (operation, histogram) in histograms {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&histogram.counts) {
+                writeln!(
+                    out,
+                    "{}_bucket{{operation=\"{}\",le=\"{}\"}} {}",
+                    name, operation, bound, count
+                )?;
+            }
+            writeln!(
+                out,
+                "{}_bucket{{operation=\"{}\",le=\"+Inf\"}} {}",
+                name, operation, histogram.count
+            )?;
+            writeln!(
+                out,
+                "{}_sum{{operation=\"{}\"}} {}",
+                name, operation, histogram.sum
+            )?;
+            writeln!(
+                out,
+                "{}_count{{operation=\"{}\"}} {}",
+                name, operation, histogram.count
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_metrics(out: &mut String, state: &State) -> fmt::Result {
+        write_counters(
+            out,
+            "ks3_requests_in_flight",
+            "gauge",
+            "Requests sent and not completed yet.",
+            &state.in_flight,
+        )?;
+
+        write_header(out, "ks3_requests_total", "counter", "Completed requests.")?;
+        for ((operation, status), value) in &state.requests {
+            writeln!(
+                out,
+                "ks3_requests_total{{operation=\"{}\",status=\"{}\"}} {}",
+                operation, status, value
+            )?;
+        }
+
+        write_histograms(
+            out,
+            "ks3_request_duration_seconds",
+            "Time to the response headers.",
+            &state.latency,
+        )?;
+        write_counters(
+            out,
+            "ks3_sent_bytes_total",
+            "counter",
+            "Bytes of request payloads.",
+            &state.bytes_sent,
+        )?;
+        write_counters(
+            out,
+            "ks3_received_bytes_total",
+            "counter",
+            "Bytes of response bodies.",
+            &state.bytes_received,
+        )?;
+        write_counters(
+            out,
+            "ks3_retries_total",
+            "counter",
+            "Retried requests.",
+            &state.retries,
+        )?;
+        write_counters(
+            out,
+            "ks3_throttled_requests_total",
+            "counter",
+            "Requests the service throttled.",
+            &state.throttles,
+        )?;
+        write_counters(
+            out,
+            "ks3_new_connections_total",
+            "counter",
+            "Requests sent on a new connection.",
+            &state.new_connections,
+        )?;
+        write_histograms(
+            out,
+            "ks3_connect_duration_seconds",
+            "Time to establish new connections.",
+            &state.connect,
+        )?;
+        write_histograms(
+            out,
+            "ks3_transfer_duration_seconds",
+            "Time to read response bodies.",
+            &state.transfer,
+        )
+    }
+}
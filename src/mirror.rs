@@ -0,0 +1,280 @@
+//! Writing the same stream to two destinations at once, e.g. buckets in two regions, for
+//! durability in both before server-side replication catches up:
+//!
+//! ```rust,ignore
+//! let upload = ReplicatedUploader::new(&beijing, beijing_input, &shanghai, shanghai_input)
+//!     .with_policy(MirrorPolicy::BestEffort)
+//!     .upload(body)
+//!     .await;
+//! if !upload.is_complete() {
+//!     // Queue the missing copy for later.
+//! }
+//! ```
+//!
+//! The body is read once, a part at a time, and every part is sent to both destinations
+//! concurrently as a part of a multipart upload, so only one part is held in memory.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use bytes::{Bytes, BytesMut};
+use futures::{future, StreamExt};
+
+use crate::multipart::{MultipartError, MultipartUpload, MIN_PART_SIZE};
+use crate::request::*;
+use crate::s3::ops::*;
+use crate::s3::S3;
+
+/// What `ReplicatedUploader` does when one of the destinations fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MirrorPolicy {
+    /// Abort the upload to the other destination as well, so that the object is written to
+    /// both or, barring a failure while completing, to none.
+    #[default]
+    FailFast,
+    /// Keep uploading to the other destination, so that the object is written to at least
+    /// one of them whenever possible.
+    BestEffort,
+}
+
+impl MirrorPolicy {
+    /// Whether the upload has to stop, given which destinations have failed.
+    fn stops(self, primary_failed: bool, secondary_failed: bool) -> bool {
+        match self {
+            MirrorPolicy::FailFast => primary_failed || secondary_failed,
+            MirrorPolicy::BestEffort => primary_failed && secondary_failed,
+        }
+    }
+}
+
+/// Why the object wasn't written to a destination.
+#[derive(Debug)]
+pub enum MirrorFailure {
+    /// The upload to this destination failed, and was aborted.
+    Upload(MultipartError),
+    /// The upload to this destination was aborted because the other one failed, following
+    /// `MirrorPolicy::FailFast`.
+    Cancelled,
+}
+
+impl fmt::Display for MirrorFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MirrorFailure::Upload(ref err) => write!(f, "{}", err),
+            MirrorFailure::Cancelled => write!(f, "cancelled after the other destination failed"),
+        }
+    }
+}
+
+impl Error for MirrorFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            MirrorFailure::Upload(ref err) => Some(err),
+            MirrorFailure::Cancelled => None,
+        }
+    }
+}
+
+/// The outcome of `ReplicatedUploader::upload` at each destination.
+#[derive(Debug)]
+pub struct MirroredUpload {
+    /// The outcome at the primary destination.
+    pub primary: Result<CompleteMultipartUploadOutput, MirrorFailure>,
+    /// The outcome at the secondary destination.
+    pub secondary: Result<CompleteMultipartUploadOutput, MirrorFailure>,
+}
+
+impl MirroredUpload {
+    /// Whether the object was written to both destinations.
+    pub fn is_complete(&self) -> bool {
+        self.primary.is_ok() && self.secondary.is_ok()
+    }
+
+    /// Whether the object was written to at least one destination.
+    pub fn is_written(&self) -> bool {
+        self.primary.is_ok() || self.secondary.is_ok()
+    }
+}
+
+/// Uploads a stream to two destinations concurrently, each described by its client and the
+/// `CreateMultipartUploadRequest` of the object there. The clients may be the same, or
+/// address different regions or services.
+pub struct ReplicatedUploader<'a, A, B> {
+    primary: &'a A,
+    primary_input: CreateMultipartUploadRequest,
+    secondary: &'a B,
+    secondary_input: CreateMultipartUploadRequest,
+    policy: MirrorPolicy,
+    part_size: usize,
+}
+
+impl<'a, A, B> ReplicatedUploader<'a, A, B>
+where
+    A: S3 + Sync,
+    B: S3 + Sync,
+{
+    /// An uploader writing to `primary_input` with `primary` and to `secondary_input` with
+    /// `secondary`, failing fast, in parts of 8 MiB.
+    pub fn new(
+        primary: &'a A,
+        primary_input: CreateMultipartUploadRequest,
+        secondary: &'a B,
+        secondary_input: CreateMultipartUploadRequest,
+    ) -> ReplicatedUploader<'a, A, B> {
+        ReplicatedUploader {
+            primary,
+            primary_input,
+            secondary,
+            secondary_input,
+            policy: MirrorPolicy::FailFast,
+            part_size: 8 * 1024 * 1024,
+        }
+    }
+
+    /// The uploader following `policy` instead.
+    pub fn with_policy(mut self, policy: MirrorPolicy) -> ReplicatedUploader<'a, A, B> {
+        self.policy = policy;
+        self
+    }
+
+    /// The uploader sending parts of `part_size` bytes instead, raised to `MIN_PART_SIZE` if
+    /// needed.
+    pub fn with_part_size(mut self, part_size: usize) -> ReplicatedUploader<'a, A, B> {
+        self.part_size = part_size;
+        self
+    }
+
+    /// Upload `body` to both destinations.
+    ///
+    /// Each part is sent to both destinations before the next one is read. Once a destination
+    /// fails, its upload is aborted and, following the policy, either the other one is aborted
+    /// too or the body keeps being sent to it alone. A failure to read the body fails both.
+    /// Completing the uploads can't be undone, so a destination may end up with the object
+    /// even under `MirrorPolicy::FailFast` when the other fails to complete.
+    pub async fn upload(self, mut body: StreamingBody) -> MirroredUpload {
+        let part_size = self.part_size.max(MIN_PART_SIZE);
+        let policy = self.policy;
+        let (primary, secondary) = future::join(
+            MultipartUpload::start(self.primary, self.primary_input),
+            MultipartUpload::start(self.secondary, self.secondary_input),
+        )
+        .await;
+        let mut primary = Destination::from(primary);
+        let mut secondary = Destination::from(secondary);
+
+        let mut buffer = BytesMut::new();
+        let mut done = false;
+        let mut index = 0;
+        while !policy.stops(primary.has_failed(), secondary.has_failed()) {
+            while !done && buffer.len() < part_size {
+                match body.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(err)) => {
+                        let copy = io::Error::new(err.kind(), err.to_string());
+                        primary.fail(MultipartError::Body(err)).await;
+                        secondary.fail(MultipartError::Body(copy)).await;
+                        return MirroredUpload::new(primary, secondary);
+                    }
+                    None => done = true,
+                }
+            }
+            // Every upload has at least one part, even if the body is empty.
+            if done && buffer.is_empty() && index > 0 {
+                break;
+            }
+            let len = buffer.len().min(part_size);
+            let data = buffer.split_to(len).freeze();
+            let (primary_part, secondary_part) = future::join(
+                primary.upload_part(index, data.clone()),
+                secondary.upload_part(index, data),
+            )
+            .await;
+            if let Err(err) = primary_part {
+                primary.fail(err).await;
+            }
+            if let Err(err) = secondary_part {
+                secondary.fail(err).await;
+            }
+            index += 1;
+        }
+
+        if policy.stops(primary.has_failed(), secondary.has_failed()) {
+            future::join(primary.cancel(), secondary.cancel()).await;
+        } else {
+            future::join(primary.complete(), secondary.complete()).await;
+        }
+        MirroredUpload::new(primary, secondary)
+    }
+}
+
+/// The upload to one destination, until it's completed or fails.
+struct Destination<'a, C> {
+    upload: Option<MultipartUpload<'a, C>>,
+    outcome: Option<Result<CompleteMultipartUploadOutput, MirrorFailure>>,
+}
+
+impl<'a, C> From<Result<MultipartUpload<'a, C>, MultipartError>> for Destination<'a, C> {
+    fn from(upload: Result<MultipartUpload<'a, C>, MultipartError>) -> Destination<'a, C> {
+        match upload {
+            Ok(upload) => Destination {
+                upload: Some(upload),
+                outcome: None,
+            },
+            Err(err) => Destination {
+                upload: None,
+                outcome: Some(Err(MirrorFailure::Upload(err))),
+            },
+        }
+    }
+}
+
+impl<'a, C> Destination<'a, C>
+where
+    C: S3 + Sync,
+{
+    fn has_failed(&self) -> bool {
+        matches!(self.outcome, Some(Err(_)))
+    }
+
+    /// Upload `data` as the part at `index`, unless the upload has already failed.
+    async fn upload_part(&self, index: usize, data: Bytes) -> Result<(), MultipartError> {
+        match self.upload {
+            Some(ref upload) => upload.upload_part(index, data).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Abort the upload after `err`.
+    async fn fail(&mut self, err: MultipartError) {
+        if let Some(upload) = self.upload.take() {
+            upload.abort().await;
+            self.outcome = Some(Err(MirrorFailure::Upload(err)));
+        }
+    }
+
+    /// Abort the upload if it's still going, after the other destination failed.
+    async fn cancel(&mut self) {
+        if let Some(upload) = self.upload.take() {
+            upload.abort().await;
+            self.outcome = Some(Err(MirrorFailure::Cancelled));
+        }
+    }
+
+    /// Complete the upload if it's still going.
+    async fn complete(&mut self) {
+        if let Some(upload) = self.upload.take() {
+            self.outcome = Some(upload.complete().await.map_err(MirrorFailure::Upload));
+        }
+    }
+}
+
+impl MirroredUpload {
+    /// The outcomes of two finished destinations.
+    fn new<A, B>(primary: Destination<'_, A>, secondary: Destination<'_, B>) -> MirroredUpload {
+        MirroredUpload {
+            primary: primary.outcome.unwrap_or(Err(MirrorFailure::Cancelled)),
+            secondary: secondary.outcome.unwrap_or(Err(MirrorFailure::Cancelled)),
+        }
+    }
+}
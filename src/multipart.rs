@@ -0,0 +1,596 @@
+//! Multipart uploads of streams whose size isn't known in advance, and multipart copies of
+//! objects too large for `copy_object`.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt, TryStreamExt};
+
+use crate::core::error::Ks3Error;
+use crate::progress::{report_outcome, ProgressEvent, ProgressReporter};
+use crate::range::Range;
+use crate::request::*;
+use crate::s3::ops::*;
+use crate::s3::S3;
+
+/// The smallest part size accepted by the service, except for the last part.
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// The largest number of parts in a multipart upload.
+pub const MAX_PARTS: i64 = 10_000;
+
+/// Errors returned by the multipart upload helpers. The helpers abort the upload before returning
+/// any of them, except for `Create` where there is nothing to abort; errors of `MultipartUpload`
+/// leave that decision to the caller.
+#[derive(Debug)]
+pub enum MultipartError {
+    /// The multipart upload couldn't be initiated.
+    Create(Ks3Error<CreateMultipartUploadError>),
+    /// The part with the given number couldn't be uploaded.
+    UploadPart(i64, Ks3Error<UploadPartError>),
+    /// The part with the given number couldn't be copied from the source object.
+    UploadPartCopy(i64, Ks3Error<UploadPartCopyError>),
+    /// The multipart upload couldn't be completed.
+    Complete(Ks3Error<CompleteMultipartUploadError>),
+    /// Reading the body failed.
+    Body(io::Error),
+    /// The body needs more than `MAX_PARTS` parts of the chosen size.
+    TooManyParts,
+    /// The part with the given number is missing from an upload being completed.
+    MissingPart(i64),
+    /// The parts of an upload being resumed couldn't be listed.
+    ListParts(Ks3Error<ListPartsError>),
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MultipartError::Create(ref err) => write!(f, "{}", err),
+            MultipartError::UploadPart(part_number, ref err) => {
+                write!(f, "part {}: {}", part_number, err)
+            }
+            MultipartError::UploadPartCopy(part_number, ref err) => {
+                write!(f, "part {}: {}", part_number, err)
+            }
+            MultipartError::Complete(ref err) => write!(f, "{}", err),
+            MultipartError::Body(ref err) => write!(f, "{}", err),
+            MultipartError::TooManyParts => {
+                write!(f, "the body needs more than {} parts", MAX_PARTS)
+            }
+            MultipartError::MissingPart(part_number) => {
+                write!(f, "part {} was never uploaded", part_number)
+            }
+            MultipartError::ListParts(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for MultipartError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            MultipartError::Create(ref err) => Some(err),
+            MultipartError::UploadPart(_, ref err) => Some(err),
+            MultipartError::UploadPartCopy(_, ref err) => Some(err),
+            MultipartError::Complete(ref err) => Some(err),
+            MultipartError::Body(ref err) => Some(err),
+            MultipartError::TooManyParts => None,
+            MultipartError::MissingPart(_) => None,
+            MultipartError::ListParts(ref err) => Some(err),
+        }
+    }
+}
+
+/// Upload `body` as the object described by `input`, in parts of `part_size` bytes.
+///
+/// Only one part is held in memory at a time, so the body can be produced on the fly and be
+/// larger than the available memory or disk. `part_size` is raised to `MIN_PART_SIZE` if needed.
+pub async fn upload_stream<C>(
+    client: &C,
+    input: CreateMultipartUploadRequest,
+    body: StreamingBody,
+    part_size: usize,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+{
+    upload_stream_reported(client, input, body, part_size, None).await
+}
+
+/// `upload_stream`, reporting the start and outcome of the upload and of every part to
+/// `progress`. Bytes are reported as parts are stored, against the size hint of `body`.
+pub async fn upload_stream_with_progress<C>(
+    client: &C,
+    input: CreateMultipartUploadRequest,
+    body: StreamingBody,
+    part_size: usize,
+    progress: &ProgressReporter,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+{
+    let progress = UploadProgress::new(
+        progress.clone(),
+        &input.key,
+        body.size_hint().map(|len| len as u64),
+    );
+    let result = upload_stream_reported(client, input, body, part_size, Some(&progress)).await;
+    progress.finished(&result);
+    result
+}
+
+async fn upload_stream_reported<C>(
+    client: &C,
+    input: CreateMultipartUploadRequest,
+    body: StreamingBody,
+    part_size: usize,
+    progress: Option<&UploadProgress>,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+{
+    let bucket = input.bucket.clone();
+    let key = input.key.clone();
+    let upload_id = client
+        .create_multipart_upload(input)
+        .await
+        .map_err(MultipartError::Create)?
+        .upload_id
+        .unwrap_or_default();
+
+    let result = upload_parts(
+        client,
+        &bucket,
+        &key,
+        &upload_id,
+        body,
+        part_size.max(MIN_PART_SIZE),
+        progress,
+    )
+    .await;
+    if result.is_err() {
+        abort(client, &bucket, &key, &upload_id).await;
+    }
+    result
+}
+
+/// The progress of a multipart upload, reported to a `ProgressReporter`.
+struct UploadProgress {
+    reporter: ProgressReporter,
+    key: String,
+    total_bytes: Option<u64>,
+    /// The number of bytes in the parts stored so far.
+    uploaded: AtomicU64,
+}
+
+impl UploadProgress {
+    /// Start reporting the upload of the `total_bytes` bytes of `key`.
+    fn new(reporter: ProgressReporter, key: &str, total_bytes: Option<u64>) -> UploadProgress {
+        reporter.emit(ProgressEvent::TransferStarted {
+            key: key.to_owned(),
+            total_bytes,
+        });
+        UploadProgress {
+            reporter,
+            key: key.to_owned(),
+            total_bytes,
+            uploaded: AtomicU64::new(0),
+        }
+    }
+
+    /// Upload `data` as part `part_number` like `upload_part`, reporting it.
+    async fn upload_part<C>(
+        &self,
+        client: &C,
+        bucket: &str,
+        upload_id: &str,
+        part_number: i64,
+        data: Bytes,
+    ) -> Result<CompletedPart, MultipartError>
+    where
+        C: S3 + Sync,
+    {
+        let bytes = data.len() as u64;
+        self.reporter.emit(ProgressEvent::PartStarted {
+            key: self.key.clone(),
+            part_number,
+            bytes,
+        });
+        let result = upload_part(client, bucket, &self.key, upload_id, part_number, data).await;
+        match result {
+            Ok(_) => {
+                self.reporter.emit(ProgressEvent::PartCompleted {
+                    key: self.key.clone(),
+                    part_number,
+                    bytes,
+                });
+                let transferred = self.uploaded.fetch_add(bytes, Ordering::Relaxed) + bytes;
+                self.reporter.emit(ProgressEvent::BytesTransferred {
+                    key: self.key.clone(),
+                    transferred,
+                    total_bytes: self.total_bytes,
+                });
+            }
+            Err(ref err) => self.reporter.emit(ProgressEvent::PartFailed {
+                key: self.key.clone(),
+                part_number,
+                error: err.to_string(),
+            }),
+        }
+        result
+    }
+
+    /// Report the outcome of the upload.
+    fn finished<T>(&self, result: &Result<T, MultipartError>) {
+        report_outcome(Some(&self.reporter), &self.key, result)
+    }
+}
+
+async fn upload_parts<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    mut body: StreamingBody,
+    part_size: usize,
+    progress: Option<&UploadProgress>,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+{
+    let mut parts = Vec::new();
+    let mut buffer = BytesMut::new();
+    let mut done = false;
+    loop {
+        while !done && buffer.len() < part_size {
+            match body.next().await {
+                Some(chunk) => buffer.extend_from_slice(&chunk.map_err(MultipartError::Body)?),
+                None => done = true,
+            }
+        }
+        // Every upload has at least one part, even if the body is empty.
+        if done && buffer.is_empty() && !parts.is_empty() {
+            break;
+        }
+        let part_number = parts.len() as i64 + 1;
+        if part_number > MAX_PARTS {
+            return Err(MultipartError::TooManyParts);
+        }
+        let len = buffer.len().min(part_size);
+        let data = buffer.split_to(len).freeze();
+        let part = match progress {
+            Some(progress) => {
+                progress
+                    .upload_part(client, bucket, upload_id, part_number, data)
+                    .await?
+            }
+            None => upload_part(client, bucket, key, upload_id, part_number, data).await?,
+        };
+        parts.push(part);
+    }
+
+    complete(client, bucket, key, upload_id, parts).await
+}
+
+/// A multipart upload fed with parts by the application, for data produced by a parallel
+/// computation rather than read from a single stream.
+///
+/// Parts are identified by their zero-based position in the object and can be uploaded in any
+/// order, concurrently, and more than once (the last upload of a position wins). Every part but
+/// the last must hold at least `MIN_PART_SIZE` bytes. The upload has to be finished with
+/// `complete` or `abort`; dropping the handle leaves the parts stored until the upload is
+/// aborted or expires.
+///
+/// ```rust,ignore
+/// let upload = MultipartUpload::start(&client, input).await?;
+/// stream::iter(chunks)
+///     .map(|(index, data)| upload.upload_part(index, data))
+///     .buffer_unordered(4)
+///     .try_collect::<Vec<_>>()
+///     .await?;
+/// upload.complete().await?;
+/// ```
+pub struct MultipartUpload<'a, C> {
+    client: &'a C,
+    bucket: String,
+    key: String,
+    upload_id: String,
+    parts: Mutex<BTreeMap<i64, CompletedPart>>,
+    progress: Option<UploadProgress>,
+}
+
+impl<'a, C> MultipartUpload<'a, C>
+where
+    C: S3 + Sync,
+{
+    /// Initiate the multipart upload of the object described by `input`.
+    pub async fn start(
+        client: &'a C,
+        input: CreateMultipartUploadRequest,
+    ) -> Result<MultipartUpload<'a, C>, MultipartError> {
+        let bucket = input.bucket.clone();
+        let key = input.key.clone();
+        let upload_id = client
+            .create_multipart_upload(input)
+            .await
+            .map_err(MultipartError::Create)?
+            .upload_id
+            .unwrap_or_default();
+        Ok(MultipartUpload {
+            client,
+            bucket,
+            key,
+            upload_id,
+            parts: Mutex::new(BTreeMap::new()),
+            progress: None,
+        })
+    }
+
+    /// Report the parts and the outcome of the upload to `progress`, against `total_bytes`,
+    /// the size of the object when known. The outcome is reported by `complete` and `abort`.
+    /// Parts uploaded more than once count towards the bytes transferred every time.
+    pub fn with_progress(
+        mut self,
+        progress: &ProgressReporter,
+        total_bytes: Option<u64>,
+    ) -> MultipartUpload<'a, C> {
+        self.progress = Some(UploadProgress::new(
+            progress.clone(),
+            &self.key,
+            total_bytes,
+        ));
+        self
+    }
+
+    /// The ID of the upload.
+    pub fn upload_id(&self) -> &str {
+        &self.upload_id
+    }
+
+    /// Upload `data` as the part at the zero-based position `index`.
+    pub async fn upload_part(&self, index: usize, data: Bytes) -> Result<(), MultipartError> {
+        let part_number = index as i64 + 1;
+        if part_number > MAX_PARTS {
+            return Err(MultipartError::TooManyParts);
+        }
+        let part = match self.progress {
+            Some(ref progress) => {
+                progress
+                    .upload_part(
+                        self.client,
+                        &self.bucket,
+                        &self.upload_id,
+                        part_number,
+                        data,
+                    )
+                    .await?
+            }
+            None => {
+                upload_part(
+                    self.client,
+                    &self.bucket,
+                    &self.key,
+                    &self.upload_id,
+                    part_number,
+                    data,
+                )
+                .await?
+            }
+        };
+        self.parts
+            .lock()
+            .expect("parts lock poisoned")
+            .insert(part_number, part);
+        Ok(())
+    }
+
+    /// Assemble the uploaded parts into the object. The parts must cover every position from
+    /// zero up to the last one.
+    pub async fn complete(self) -> Result<CompleteMultipartUploadOutput, MultipartError> {
+        let result = self.finish().await;
+        if let Some(ref progress) = self.progress {
+            progress.finished(&result);
+        }
+        result
+    }
+
+    async fn finish(&self) -> Result<CompleteMultipartUploadOutput, MultipartError> {
+        let parts = self.parts.lock().expect("parts lock poisoned").clone();
+        let last = parts.keys().next_back().copied().unwrap_or(0);
+        if parts.is_empty() || last != parts.len() as i64 {
+            let missing = (1..).find(|n| !parts.contains_key(n)).unwrap_or(1);
+            return Err(MultipartError::MissingPart(missing));
+        }
+        let parts = parts.into_values().collect();
+        complete(self.client, &self.bucket, &self.key, &self.upload_id, parts).await
+    }
+
+    /// Abort the upload, discarding the uploaded parts.
+    pub async fn abort(self) {
+        abort(self.client, &self.bucket, &self.key, &self.upload_id).await;
+        if let Some(ref progress) = self.progress {
+            progress.reporter.emit(ProgressEvent::TransferFailed {
+                key: self.key.clone(),
+                error: "the upload was aborted".to_owned(),
+            });
+        }
+    }
+}
+
+/// Upload the object described by `input` from a stream of `(index, data)` parts, running up to
+/// `concurrency` part uploads at once. See `MultipartUpload` for the requirements on the parts.
+pub async fn upload_parts_from<C, S>(
+    client: &C,
+    input: CreateMultipartUploadRequest,
+    parts: S,
+    concurrency: usize,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+    S: Stream<Item = io::Result<(usize, Bytes)>>,
+{
+    let upload = MultipartUpload::start(client, input).await?;
+    let result = parts
+        .map_err(MultipartError::Body)
+        .map_ok(|(index, data)| upload.upload_part(index, data))
+        .try_buffer_unordered(concurrency.max(1))
+        .try_collect::<Vec<_>>()
+        .await;
+    let result = match result {
+        Ok(_) => upload.finish().await,
+        Err(err) => Err(err),
+    };
+    if result.is_err() {
+        upload.abort().await;
+    }
+    result
+}
+
+/// The largest object that can be copied with a single `copy_object` request.
+pub const MAX_COPY_SIZE: i64 = 5 * 1024 * 1024 * 1024;
+
+/// The part size used by `copy_multipart`, unless the object needs larger parts.
+const COPY_PART_SIZE: i64 = 512 * 1024 * 1024;
+
+/// Copy the `size` bytes of the existing object `copy_source` into the object described by
+/// `input`, one part at a time.
+///
+/// This is the only way to copy objects larger than `MAX_COPY_SIZE`. Unlike `copy_object`,
+/// nothing is taken over from the source object: its metadata has to be set on `input`.
+pub async fn copy_multipart<C>(
+    client: &C,
+    input: CreateMultipartUploadRequest,
+    copy_source: &str,
+    size: i64,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+{
+    let bucket = input.bucket.clone();
+    let key = input.key.clone();
+    let upload_id = client
+        .create_multipart_upload(input)
+        .await
+        .map_err(MultipartError::Create)?
+        .upload_id
+        .unwrap_or_default();
+
+    let result = copy_parts(client, &bucket, &key, &upload_id, copy_source, size).await;
+    if result.is_err() {
+        abort(client, &bucket, &key, &upload_id).await;
+    }
+    result
+}
+
+async fn copy_parts<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    copy_source: &str,
+    size: i64,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+{
+    let part_size = COPY_PART_SIZE.max((size + MAX_PARTS - 1) / MAX_PARTS);
+    let mut parts = Vec::new();
+    let mut offset = 0;
+    while offset < size || parts.is_empty() {
+        let part_number = parts.len() as i64 + 1;
+        let last = (offset + part_size).min(size) - 1;
+        let output = client
+            .upload_part_copy(UploadPartCopyRequest {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                upload_id: upload_id.to_owned(),
+                part_number,
+                copy_source: copy_source.to_owned(),
+                copy_source_range: Some(Range::bytes(offset as u64..=last as u64))
+                    .filter(|_| size > 0),
+                ..Default::default()
+            })
+            .await
+            .map_err(|err| MultipartError::UploadPartCopy(part_number, err))?;
+        parts.push(CompletedPart {
+            e_tag: output.copy_part_result.and_then(|result| result.e_tag),
+            part_number: Some(part_number),
+        });
+        offset += part_size;
+    }
+
+    complete(client, bucket, key, upload_id, parts).await
+}
+
+/// Complete a multipart upload with `parts`, given in ascending order.
+pub(crate) async fn complete<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    parts: Vec<CompletedPart>,
+) -> Result<CompleteMultipartUploadOutput, MultipartError>
+where
+    C: S3 + Sync,
+{
+    client
+        .complete_multipart_upload(CompleteMultipartUploadRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            upload_id: upload_id.to_owned(),
+            multipart_upload: Some(CompletedMultipartUpload { parts: Some(parts) }),
+            ..Default::default()
+        })
+        .await
+        .map_err(MultipartError::Complete)
+}
+
+/// Upload `data` as part `part_number`, returning the entry of the part in the completion
+/// request.
+pub(crate) async fn upload_part<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    upload_id: &str,
+    part_number: i64,
+    data: Bytes,
+) -> Result<CompletedPart, MultipartError>
+where
+    C: S3 + Sync,
+{
+    let output = client
+        .upload_part(UploadPartRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            upload_id: upload_id.to_owned(),
+            part_number,
+            content_length: Some(data.len() as i64),
+            body: Some(StreamingBody::from(data)),
+            ..Default::default()
+        })
+        .await
+        .map_err(|err| MultipartError::UploadPart(part_number, err))?;
+    Ok(CompletedPart {
+        e_tag: output.e_tag,
+        part_number: Some(part_number),
+    })
+}
+
+/// Abort a multipart upload on a best-effort basis, after another error.
+pub(crate) async fn abort<C>(client: &C, bucket: &str, key: &str, upload_id: &str)
+where
+    C: S3 + Sync,
+{
+    let _ = client
+        .abort_multipart_upload(AbortMultipartUploadRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            upload_id: upload_id.to_owned(),
+            ..Default::default()
+        })
+        .await;
+}
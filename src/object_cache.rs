@@ -0,0 +1,345 @@
+//! Caching the bodies of small objects read again and again.
+//!
+//! With `Ks3Config::object_cache` set, `get_object` first looks for the object, or the range
+//! of it requested, in an in-memory cache, and only sends the request when it isn't there.
+//! The cache holds up to a total size of bodies, dropping the least recently used ones first,
+//! and entries expire after a time to live, after which the object is requested again:
+//!
+//! ```rust,ignore
+//! let mut config = client.config().clone();
+//! config.object_cache = Some(
+//!     ObjectCache::new(64 * 1024 * 1024)
+//!         .with_ttl(Duration::from_secs(30))
+//!         .with_max_object_size(256 * 1024),
+//! );
+//! client.set_config(config);
+//! ```
+//!
+//! Objects changed through the same client are dropped from the cache right away; changes made
+//! elsewhere are seen once the entries expire. Conditional requests, requests with
+//! customer-provided keys, part numbers or `response-*` overrides bypass the cache.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+
+use crate::config::Ks3Config;
+use crate::core::clock::Instant;
+use crate::core::error::Ks3Error;
+use crate::range::Range;
+use crate::request::{GetObjectError, GetObjectOutput, GetObjectRequest};
+use crate::s3::{S3Client, S3};
+use crate::signature::ByteStream;
+
+/// The default time to live of the entries.
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// The default size of the largest body cached.
+const DEFAULT_MAX_OBJECT_SIZE: usize = 1024 * 1024;
+
+/// What an entry is looked up by.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct CacheKey {
+    bucket: String,
+    key: String,
+    version_id: Option<String>,
+    range: Option<Range>,
+}
+
+impl CacheKey {
+    /// The key of the response to `input`, if it may be served from the cache.
+    fn for_request(input: &GetObjectRequest) -> Option<CacheKey> {
+        let bypass = input.if_modified_since.is_some()
+            || input.if_none_match.is_some()
+            || input.if_unmodified_since.is_some()
+            || input.part_number.is_some()
+            || input.sse_customer_key.is_some()
+            || input.response_cache_control.is_some()
+            || input.response_content_disposition.is_some()
+            || input.response_content_encoding.is_some()
+            || input.response_content_language.is_some()
+            || input.response_content_type.is_some()
+            || input.response_expires.is_some();
+        if bypass {
+            return None;
+        }
+        Some(CacheKey {
+            bucket: input.bucket.clone(),
+            key: input.key.clone(),
+            version_id: input.version_id.clone(),
+            range: input.range,
+        })
+    }
+}
+
+struct Entry {
+    /// The response, without its body.
+    output: GetObjectOutput,
+    body: Bytes,
+    stored_at: Instant,
+    /// The position of the entry in `Inner::recency`.
+    tick: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<CacheKey, Entry>,
+    /// The entries by the tick of their last use, the least recently used first.
+    recency: BTreeMap<u64, CacheKey>,
+    next_tick: u64,
+    size: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl Inner {
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.tick);
+            self.size -= entry.body.len();
+        }
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.tick);
+            entry.tick = tick;
+            self.recency.insert(tick, key.clone());
+        }
+    }
+}
+
+/// An in-memory LRU cache of `get_object` responses, set with `Ks3Config::object_cache`.
+///
+/// Clones share the cached entries.
+#[derive(Clone)]
+pub struct ObjectCache {
+    max_size: usize,
+    max_object_size: usize,
+    ttl: Duration,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ObjectCache {
+    /// A cache holding up to `max_size` bytes of bodies.
+    pub fn new(max_size: usize) -> ObjectCache {
+        ObjectCache {
+            max_size,
+            max_object_size: DEFAULT_MAX_OBJECT_SIZE.min(max_size),
+            ttl: DEFAULT_TTL,
+            inner: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+
+    /// Request objects again once this long has passed since they were cached. Defaults to 60
+    /// seconds.
+    pub fn with_ttl(mut self, ttl: Duration) -> ObjectCache {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Only cache bodies of up to `max_object_size` bytes. Defaults to 1 MiB, or the size of
+    /// the cache if smaller.
+    pub fn with_max_object_size(mut self, max_object_size: usize) -> ObjectCache {
+        self.max_object_size = max_object_size.min(self.max_size);
+        self
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.inner().entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total size of the cached bodies, in bytes.
+    pub fn size(&self) -> usize {
+        self.inner().size
+    }
+
+    /// The number of `get_object` calls served from the cache, and of those that weren't.
+    pub fn hits_and_misses(&self) -> (u64, u64) {
+        let inner = self.inner();
+        (inner.hits, inner.misses)
+    }
+
+    /// Drop every entry of the object `key` in `bucket`, whatever its version or range.
+    pub fn invalidate(&self, bucket: &str, key: &str) {
+        let mut inner = self.inner();
+        let stale: Vec<CacheKey> = inner
+            .entries
+            .keys()
+            .filter(|k| k.bucket == bucket && k.key == key)
+            .cloned()
+            .collect();
+        for k in stale {
+            inner.remove(&k);
+        }
+    }
+
+    /// Drop every entry.
+    pub fn clear(&self) {
+        let mut inner = self.inner();
+        inner.entries.clear();
+        inner.recency.clear();
+        inner.size = 0;
+    }
+
+    fn get(&self, key: &CacheKey, if_match: Option<&str>) -> Option<GetObjectOutput> {
+        let mut inner = self.inner();
+        let fresh = match inner.entries.get(key) {
+            Some(entry) => entry.stored_at.elapsed() < self.ttl,
+            None => {
+                inner.misses += 1;
+                return None;
+            }
+        };
+        if !fresh {
+            inner.remove(key);
+            inner.misses += 1;
+            return None;
+        }
+        let entry = &inner.entries[key];
+        if if_match.is_some() && if_match != entry.output.e_tag.as_deref() {
+            // Let the service answer with its precondition error.
+            inner.misses += 1;
+            return None;
+        }
+        let mut output = without_body(&entry.output);
+        output.body = Some(ByteStream::from(entry.body.clone()));
+        inner.hits += 1;
+        inner.touch(key);
+        Some(output)
+    }
+
+    fn insert(&self, key: CacheKey, output: &GetObjectOutput, body: Bytes) {
+        if body.len() > self.max_object_size {
+            return;
+        }
+        let mut inner = self.inner();
+        inner.remove(&key);
+        while inner.size + body.len() > self.max_size {
+            let oldest = match inner.recency.values().next() {
+                Some(oldest) => oldest.clone(),
+                None => break,
+            };
+            inner.remove(&oldest);
+        }
+        inner.size += body.len();
+        inner.entries.insert(
+            key.clone(),
+            Entry {
+                output: without_body(output),
+                body,
+                stored_at: Instant::now(),
+                tick: 0,
+            },
+        );
+        inner.touch(&key);
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("object cache poisoned")
+    }
+}
+
+impl fmt::Debug for ObjectCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ObjectCache")
+            .field("max_size", &self.max_size)
+            .field("max_object_size", &self.max_object_size)
+            .field("ttl", &self.ttl)
+            .field("len", &self.len())
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+/// A copy of `output` without its body.
+fn without_body(output: &GetObjectOutput) -> GetObjectOutput {
+    GetObjectOutput {
+        accept_ranges: output.accept_ranges.clone(),
+        body: None,
+        cache_control: output.cache_control.clone(),
+        checksum_crc64ecma: output.checksum_crc64ecma.clone(),
+        content_disposition: output.content_disposition.clone(),
+        content_encoding: output.content_encoding.clone(),
+        content_language: output.content_language.clone(),
+        content_length: output.content_length,
+        content_range: output.content_range.clone(),
+        content_type: output.content_type.clone(),
+        delete_marker: output.delete_marker,
+        e_tag: output.e_tag.clone(),
+        expiration: output.expiration.clone(),
+        expires: output.expires,
+        last_modified: output.last_modified,
+        metadata: output.metadata.clone(),
+        missing_meta: output.missing_meta,
+        negotiated_encoding: output.negotiated_encoding.clone(),
+        object_lock_legal_hold_status: output.object_lock_legal_hold_status.clone(),
+        object_lock_mode: output.object_lock_mode.clone(),
+        object_lock_retain_until_date: output.object_lock_retain_until_date,
+        parts_count: output.parts_count,
+        replication_status: output.replication_status.clone(),
+        request_charged: output.request_charged.clone(),
+        restore: output.restore.clone(),
+        sse_customer_algorithm: output.sse_customer_algorithm.clone(),
+        sse_customer_key_md5: output.sse_customer_key_md5.clone(),
+        ssekms_key_id: output.ssekms_key_id.clone(),
+        server_side_encryption: output.server_side_encryption.clone(),
+        storage_class: output.storage_class.clone(),
+        tag_count: output.tag_count,
+        version_id: output.version_id.clone(),
+        website_redirect_location: output.website_redirect_location.clone(),
+        raw_headers: output.raw_headers.clone(),
+    }
+}
+
+/// `get_object` with `input`, served from `cache` when possible.
+pub(crate) async fn cached_get_object(
+    client: &S3Client,
+    input: GetObjectRequest,
+    cache: &ObjectCache,
+) -> Result<GetObjectOutput, Ks3Error<GetObjectError>> {
+    // The request itself must not go through the cache.
+    let mut client = client.clone();
+    client.set_config(Ks3Config {
+        object_cache: None,
+        ..client.config().clone()
+    });
+
+    let key = match CacheKey::for_request(&input) {
+        Some(key) => key,
+        None => return client.get_object(input).await,
+    };
+    if let Some(output) = cache.get(&key, input.if_match.as_deref()) {
+        return Ok(output);
+    }
+
+    let mut output = client.get_object(input).await?;
+    let cacheable = output
+        .content_length
+        .is_some_and(|len| len >= 0 && len as usize <= cache.max_object_size);
+    if !cacheable {
+        return Ok(output);
+    }
+    let mut body = BytesMut::new();
+    if let Some(mut stream) = output.body.take() {
+        while let Some(chunk) = stream.next().await {
+            body.extend_from_slice(&chunk?);
+        }
+    }
+    let body = body.freeze();
+    cache.insert(key, &output, body.clone());
+    output.body = Some(ByteStream::from(body));
+    Ok(output)
+}
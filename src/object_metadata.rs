@@ -0,0 +1,184 @@
+//! The properties of an object as one value, to read them, change some and write them back.
+//!
+//! `HeadObject` and `GetObject` return the content headers, user metadata and encryption
+//! settings of an object as separate fields, which `PutObject` and `CopyObject` take back as
+//! yet other fields. `ObjectMetadata` gathers them, so that updating the metadata of an object
+//! in place is a matter of a few lines:
+//!
+//! ```rust,ignore
+//! let head = client.head_object(HeadObjectRequest {
+//!     bucket: "bucket".to_owned(),
+//!     key: "report.csv".to_owned(),
+//!     ..Default::default()
+//! }).await?;
+//! let mut metadata = ObjectMetadata::from(&head);
+//! metadata.cache_control = Some("max-age=3600".to_owned());
+//! metadata.metadata.insert("reviewed", "yes")?;
+//! client.copy_object(metadata.replace_request("bucket", "report.csv")).await?;
+//! ```
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::enums::{ServerSideEncryption, StorageClass};
+use crate::metadata::Metadata;
+use crate::request::{CopyObjectRequest, GetObjectOutput, HeadObjectOutput, PutObjectRequest};
+use crate::storage_class::copy_source;
+
+/// The properties of an object, from the headers of a `head_object` or `get_object` response.
+///
+/// The first fields are written back by `apply_to_put` and `apply_to_copy`; the others are
+/// set by the service and only read. Customer-provided encryption keys aren't returned by the
+/// service, so objects encrypted with one need the key set on the request separately.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ObjectMetadata {
+    /// The `Cache-Control` header.
+    pub cache_control: Option<String>,
+    /// The `Content-Disposition` header.
+    pub content_disposition: Option<String>,
+    /// The `Content-Encoding` header.
+    pub content_encoding: Option<String>,
+    /// The `Content-Language` header.
+    pub content_language: Option<String>,
+    /// The `Content-Type` header.
+    pub content_type: Option<String>,
+    /// The `Expires` header.
+    pub expires: Option<DateTime<Utc>>,
+    /// The user-defined metadata, sent in `x-amz-meta-*` headers.
+    pub metadata: Metadata,
+    /// The server-side encryption algorithm, e.g. `AES256`.
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// The ID of the KMS key the object is encrypted with.
+    pub ssekms_key_id: Option<String>,
+    /// The storage class. The service leaves it out for objects of the standard class.
+    pub storage_class: Option<StorageClass>,
+    /// Where requests for the object are redirected to when the bucket is a website.
+    pub website_redirect_location: Option<String>,
+    /// The size of the object in bytes. Only that of the returned range for a ranged
+    /// `get_object`.
+    pub content_length: Option<i64>,
+    /// The ETag of the object.
+    pub e_tag: Option<String>,
+    /// When the object was last modified.
+    pub last_modified: Option<DateTime<Utc>>,
+    /// The version of the object, in a versioned bucket.
+    pub version_id: Option<String>,
+    /// The algorithm of the customer-provided key the object is encrypted with.
+    pub sse_customer_algorithm: Option<String>,
+    /// The MD5 digest of the customer-provided key the object is encrypted with.
+    pub sse_customer_key_md5: Option<String>,
+}
+
+impl ObjectMetadata {
+    /// Set the writable properties on `input`, replacing those it had.
+    pub fn apply_to_put(&self, input: &mut PutObjectRequest) {
+        input.cache_control = self.cache_control.clone();
+        input.content_disposition = self.content_disposition.clone();
+        input.content_encoding = self.content_encoding.clone();
+        input.content_language = self.content_language.clone();
+        input.content_type = self.content_type.clone();
+        input.expires = self.expires;
+        input.metadata = self.user_metadata();
+        input.server_side_encryption = self.server_side_encryption.clone();
+        input.ssekms_key_id = self.ssekms_key_id.clone();
+        input.storage_class = self.storage_class.clone();
+        input.website_redirect_location = self.website_redirect_location.clone();
+    }
+
+    /// Set the writable properties on `input`, replacing those it had, with the `REPLACE`
+    /// metadata directive so that they are used instead of those of the source object.
+    pub fn apply_to_copy(&self, input: &mut CopyObjectRequest) {
+        input.cache_control = self.cache_control.clone();
+        input.content_disposition = self.content_disposition.clone();
+        input.content_encoding = self.content_encoding.clone();
+        input.content_language = self.content_language.clone();
+        input.content_type = self.content_type.clone();
+        input.expires = self.expires;
+        input.metadata = self.user_metadata();
+        input.metadata_directive = Some("REPLACE".to_owned());
+        input.server_side_encryption = self.server_side_encryption.clone();
+        input.ssekms_key_id = self.ssekms_key_id.clone();
+        input.storage_class = self.storage_class.clone();
+        input.website_redirect_location = self.website_redirect_location.clone();
+    }
+
+    /// A request copying the object `key` in `bucket` onto itself with these properties.
+    ///
+    /// The copy only happens if the object still has the ETag read, when known, so that an
+    /// object replaced in the meantime doesn't get the properties of the previous one. The ACL
+    /// of the object is reset, as with any copy.
+    pub fn replace_request(&self, bucket: &str, key: &str) -> CopyObjectRequest {
+        let mut input = CopyObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            copy_source: copy_source(bucket, key),
+            copy_source_if_match: self.e_tag.clone(),
+            ..Default::default()
+        };
+        self.apply_to_copy(&mut input);
+        input
+    }
+
+    fn user_metadata(&self) -> Option<Metadata> {
+        Some(self.metadata.clone()).filter(|metadata| !metadata.is_empty())
+    }
+}
+
+impl From<&HeadObjectOutput> for ObjectMetadata {
+    fn from(output: &HeadObjectOutput) -> ObjectMetadata {
+        ObjectMetadata {
+            cache_control: output.cache_control.clone(),
+            content_disposition: output.content_disposition.clone(),
+            content_encoding: output.content_encoding.clone(),
+            content_language: output.content_language.clone(),
+            content_type: output.content_type.clone(),
+            expires: output.expires,
+            metadata: output.metadata.clone().unwrap_or_default(),
+            server_side_encryption: output.server_side_encryption.clone(),
+            ssekms_key_id: output.ssekms_key_id.clone(),
+            storage_class: output.storage_class.clone(),
+            website_redirect_location: output.website_redirect_location.clone(),
+            content_length: output.content_length,
+            e_tag: output.e_tag.clone(),
+            last_modified: output.last_modified,
+            version_id: output.version_id.clone(),
+            sse_customer_algorithm: output.sse_customer_algorithm.clone(),
+            sse_customer_key_md5: output.sse_customer_key_md5.clone(),
+        }
+    }
+}
+
+impl From<HeadObjectOutput> for ObjectMetadata {
+    fn from(output: HeadObjectOutput) -> ObjectMetadata {
+        ObjectMetadata::from(&output)
+    }
+}
+
+impl From<&GetObjectOutput> for ObjectMetadata {
+    fn from(output: &GetObjectOutput) -> ObjectMetadata {
+        ObjectMetadata {
+            cache_control: output.cache_control.clone(),
+            content_disposition: output.content_disposition.clone(),
+            content_encoding: output.content_encoding.clone(),
+            content_language: output.content_language.clone(),
+            content_type: output.content_type.clone(),
+            expires: output.expires,
+            metadata: output.metadata.clone().unwrap_or_default(),
+            server_side_encryption: output.server_side_encryption.clone(),
+            ssekms_key_id: output.ssekms_key_id.clone(),
+            storage_class: output.storage_class.clone(),
+            website_redirect_location: output.website_redirect_location.clone(),
+            content_length: output.content_length,
+            e_tag: output.e_tag.clone(),
+            last_modified: output.last_modified,
+            version_id: output.version_id.clone(),
+            sse_customer_algorithm: output.sse_customer_algorithm.clone(),
+            sse_customer_key_md5: output.sse_customer_key_md5.clone(),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! OpenTelemetry spans of the requests sent by `S3Client`, enabled by the `otel` feature.
+//!
+//! Every request gets a client span named after its operation, e.g. `S3.PutObject`, started
+//! from the global tracer as a child of the current OpenTelemetry context. The trace context of
+//! the span is injected into the headers of the request with the global propagator, so that
+//! nothing is sent until the application installs one, e.g. `TraceContextPropagator` for the
+//! W3C `traceparent` header. Attributes follow the semantic conventions for the AWS SDK:
+//! `rpc.system`, `rpc.service`, `rpc.method`, `aws.s3.bucket`, `aws.s3.key`,
+//! `aws.request_id`, `http.request.method`, `server.address`, `http.response.status_code` and
+//! `error.type`.
+
+use std::error::Error;
+
+use http::StatusCode;
+use opentelemetry::propagation::Injector;
+use opentelemetry::trace::{SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::{global, Context, KeyValue};
+
+use crate::audit::bucket_and_key;
+use crate::core::error::Ks3Error;
+use crate::signature::SignedRequest;
+
+/// The name of the tracer the spans are created with.
+const TRACER_NAME: &str = "ks3";
+
+/// The span of a request, ended once its outcome is known.
+pub(crate) struct ClientSpan {
+    cx: Context,
+    status: Option<StatusCode>,
+}
+
+impl ClientSpan {
+    /// Start the span of `request`, a call of `operation`, and inject its context into the
+    /// headers of the request.
+    pub(crate) fn start(operation: &'static str, request: &mut SignedRequest) -> ClientSpan {
+        let (bucket, key) = bucket_and_key(&request.path);
+        let mut attributes = vec![
+            KeyValue::new("rpc.system", "aws-api"),
+            KeyValue::new("rpc.service", "S3"),
+            KeyValue::new("rpc.method", operation),
+            KeyValue::new("http.request.method", request.method.clone()),
+            KeyValue::new("server.address", request.hostname()),
+            KeyValue::new("aws.s3.bucket", bucket),
+        ];
+        if let Some(key) = key {
+            attributes.push(KeyValue::new("aws.s3.key", key));
+        }
+        let tracer = global::tracer(TRACER_NAME);
+        let span = tracer
+            .span_builder(format!("S3.{}", operation))
+            .with_kind(SpanKind::Client)
+            .with_attributes(attributes)
+            .start_with_context(&tracer, &Context::current());
+        let cx = Context::current_with_span(span);
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, &mut HeaderInjector(request))
+        });
+        ClientSpan { cx, status: None }
+    }
+
+    /// Record the response to the request, if one was received.
+    pub(crate) fn record_response(&mut self, status: Option<StatusCode>, request_id: Option<&str>) {
+        self.status = status;
+        let span = self.cx.span();
+        if let Some(status) = status {
+            span.set_attribute(KeyValue::new(
+                "http.response.status_code",
+                i64::from(status.as_u16()),
+            ));
+        }
+        if let Some(request_id) = request_id {
+            span.set_attribute(KeyValue::new("aws.request_id", request_id.to_owned()));
+        }
+    }
+
+    /// End the span of a request that succeeded.
+    pub(crate) fn succeed(&self) {
+        self.cx.span().end();
+    }
+
+    /// End the span of a request that failed with `err`.
+    pub(crate) fn fail<E: Error + 'static>(&self, err: &Ks3Error<E>) {
+        let span = self.cx.span();
+        span.set_attribute(KeyValue::new("error.type", error_type(err, self.status)));
+        span.set_status(Status::error(err.to_string()));
+        span.end();
+    }
+}
+
+/// The class of `err` for `error.type`: the status code of error responses, or the kind of
+/// error when no response was received.
+fn error_type<E>(err: &Ks3Error<E>, status: Option<StatusCode>) -> String {
+    let kind = match *err {
+        Ks3Error::Captured { ref error, .. } => return error_type(error, status),
+        Ks3Error::Service(_) | Ks3Error::Unknown(_) => {
+            return status.map_or_else(|| "_OTHER".to_owned(), |status| status.as_str().to_owned())
+        }
+        Ks3Error::HttpDispatch(_) => "HttpDispatch",
+        Ks3Error::Credentials(_) => "Credentials",
+        Ks3Error::Validation(_) => "Validation",
+        Ks3Error::ParseError(_) => "ParseError",
+        Ks3Error::Blocking => "Blocking",
+        Ks3Error::Timeout(_) => "Timeout",
+        Ks3Error::IntegrityCheckFailed { .. } => "IntegrityCheckFailed",
+    };
+    kind.to_owned()
+}
+
+struct HeaderInjector<'a>(&'a mut SignedRequest);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.add_header(key, &value);
+    }
+}
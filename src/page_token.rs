@@ -0,0 +1,312 @@
+//! Tokens recording where a listing stopped, to resume it later, e.g. in another process.
+//!
+//! A `PageToken` is taken from a truncated page and turns into an opaque string, or a serde
+//! value, that a job scheduler can checkpoint along with its own state:
+//!
+//! ```rust,ignore
+//! let output = client.list_objects(input.clone()).await?;
+//! if let Some(token) = PageToken::after_objects(&input, &output) {
+//!     save(token.with_max_age(Duration::from_secs(24 * 3600)).to_string());
+//! }
+//!
+//! // After a restart:
+//! let token: PageToken = load().parse()?;
+//! token.resume_objects(&mut input)?;
+//! let output = client.list_objects(input).await?;
+//! ```
+//!
+//! The token remembers the listing it was taken from, and is checked against the request it
+//! resumes: a token of another bucket, prefix or delimiter, or of another kind of listing, is
+//! refused rather than silently skipping or repeating entries.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::listing::ListObjectsStream;
+use crate::request::*;
+#[cfg(feature = "multipart")]
+use crate::s3::ops::*;
+
+/// The kinds of listings a `PageToken` resumes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum Listing {
+    Objects,
+    ObjectVersions,
+    MultipartUploads,
+}
+
+/// Errors returned when parsing a `PageToken` or resuming a listing from it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PageTokenError {
+    /// The string isn't a page token.
+    Malformed(String),
+    /// The token expired at this time.
+    Expired(DateTime<Utc>),
+    /// The token was taken from another listing, as told by the field that differs, one of
+    /// `"listing"`, `"bucket"`, `"prefix"` and `"delimiter"`.
+    Mismatch(&'static str),
+}
+
+impl fmt::Display for PageTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PageTokenError::Malformed(ref cause) => write!(f, "Malformed page token: {}", cause),
+            PageTokenError::Expired(at) => write!(f, "Page token expired at {}", at),
+            PageTokenError::Mismatch(field) => {
+                write!(f, "Page token of another listing: its {} differs", field)
+            }
+        }
+    }
+}
+
+impl Error for PageTokenError {}
+
+/// Where a listing stopped: the markers of its next page, along with the listing they belong
+/// to and when they were taken.
+///
+/// `to_string` and `parse` turn the token into an opaque, URL-safe string and back.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageToken {
+    listing: Listing,
+    bucket: String,
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    /// The key marker of the next page.
+    marker: String,
+    /// The version ID or upload ID marker going with `marker`, if any.
+    id_marker: Option<String>,
+    issued_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl PageToken {
+    fn new(
+        listing: Listing,
+        bucket: &str,
+        prefix: &Option<String>,
+        delimiter: &Option<String>,
+        marker: String,
+        id_marker: Option<String>,
+    ) -> PageToken {
+        PageToken {
+            listing,
+            bucket: bucket.to_owned(),
+            prefix: prefix.clone(),
+            delimiter: delimiter.clone(),
+            marker,
+            id_marker,
+            issued_at: Utc::now(),
+            expires_at: None,
+        }
+    }
+
+    /// The token of the page following `output`, the response to `input`, if it's truncated.
+    ///
+    /// Without a `NextMarker`, which the service only sends with a delimiter, the listing
+    /// resumes after the last key of the page.
+    pub fn after_objects(
+        input: &ListObjectsRequest,
+        output: &ListObjectsOutput,
+    ) -> Option<PageToken> {
+        if !output.is_truncated.unwrap_or(false) {
+            return None;
+        }
+        let marker = output.next_marker.clone().or_else(|| {
+            let contents = output.contents.as_ref()?;
+            contents.last()?.key.clone()
+        })?;
+        Some(PageToken::new(
+            Listing::Objects,
+            &input.bucket,
+            &input.prefix,
+            &input.delimiter,
+            marker,
+            None,
+        ))
+    }
+
+    /// The token of the page following the one read by `objects`, the streamed response to
+    /// `input`, once all its objects were read and if it's truncated.
+    pub fn after_object_stream(
+        input: &ListObjectsRequest,
+        objects: &ListObjectsStream,
+    ) -> Option<PageToken> {
+        let marker = objects.next_marker()?;
+        Some(PageToken::new(
+            Listing::Objects,
+            &input.bucket,
+            &input.prefix,
+            &input.delimiter,
+            marker,
+            None,
+        ))
+    }
+
+    /// The token of the page following `output`, the response to `input`, if it's truncated.
+    pub fn after_object_versions(
+        input: &ListObjectVersionsRequest,
+        output: &ListObjectVersionsOutput,
+    ) -> Option<PageToken> {
+        if !output.is_truncated.unwrap_or(false) {
+            return None;
+        }
+        Some(PageToken::new(
+            Listing::ObjectVersions,
+            &input.bucket,
+            &input.prefix,
+            &input.delimiter,
+            output.next_key_marker.clone()?,
+            output.next_version_id_marker.clone(),
+        ))
+    }
+
+    /// The token of the page following `output`, the response to `input`, if it's truncated.
+    #[cfg(feature = "multipart")]
+    pub fn after_multipart_uploads(
+        input: &ListMultipartUploadsRequest,
+        output: &ListMultipartUploadsOutput,
+    ) -> Option<PageToken> {
+        if !output.is_truncated.unwrap_or(false) {
+            return None;
+        }
+        Some(PageToken::new(
+            Listing::MultipartUploads,
+            &input.bucket,
+            &input.prefix,
+            &input.delimiter,
+            output.next_key_marker.clone()?,
+            output.next_upload_id_marker.clone(),
+        ))
+    }
+
+    /// The token expiring `max_age` after it was taken.
+    ///
+    /// The markers themselves stay valid, but the objects listed change meanwhile: schedulers
+    /// checkpointing long scans can bound how stale a resumed listing may be, rather than
+    /// resume a scan started days ago.
+    pub fn with_max_age(mut self, max_age: Duration) -> PageToken {
+        self.expires_at = chrono::Duration::from_std(max_age)
+            .ok()
+            .and_then(|max_age| self.issued_at.checked_add_signed(max_age));
+        self
+    }
+
+    /// When the token was taken.
+    pub fn issued_at(&self) -> DateTime<Utc> {
+        self.issued_at
+    }
+
+    /// When the token expires, if it does.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// Whether the token expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|at| at <= Utc::now())
+    }
+
+    /// The bucket of the listing.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// The key marker the listing resumes from.
+    pub fn marker(&self) -> &str {
+        &self.marker
+    }
+
+    /// Check that the token hasn't expired and was taken from a listing of `listing` with the
+    /// same bucket, prefix and delimiter.
+    fn validate(
+        &self,
+        listing: Listing,
+        bucket: &str,
+        prefix: &Option<String>,
+        delimiter: &Option<String>,
+    ) -> Result<(), PageTokenError> {
+        if self.listing != listing {
+            return Err(PageTokenError::Mismatch("listing"));
+        }
+        if self.bucket != bucket {
+            return Err(PageTokenError::Mismatch("bucket"));
+        }
+        if self.prefix != *prefix {
+            return Err(PageTokenError::Mismatch("prefix"));
+        }
+        if self.delimiter != *delimiter {
+            return Err(PageTokenError::Mismatch("delimiter"));
+        }
+        match self.expires_at {
+            Some(at) if at <= Utc::now() => Err(PageTokenError::Expired(at)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the marker of `input` to resume the listing, after checking the token.
+    pub fn resume_objects(&self, input: &mut ListObjectsRequest) -> Result<(), PageTokenError> {
+        self.validate(
+            Listing::Objects,
+            &input.bucket,
+            &input.prefix,
+            &input.delimiter,
+        )?;
+        input.marker = Some(self.marker.clone());
+        Ok(())
+    }
+
+    /// Set the markers of `input` to resume the listing, after checking the token.
+    pub fn resume_object_versions(
+        &self,
+        input: &mut ListObjectVersionsRequest,
+    ) -> Result<(), PageTokenError> {
+        self.validate(
+            Listing::ObjectVersions,
+            &input.bucket,
+            &input.prefix,
+            &input.delimiter,
+        )?;
+        input.key_marker = Some(self.marker.clone());
+        input.version_id_marker = self.id_marker.clone();
+        Ok(())
+    }
+
+    /// Set the markers of `input` to resume the listing, after checking the token.
+    #[cfg(feature = "multipart")]
+    pub fn resume_multipart_uploads(
+        &self,
+        input: &mut ListMultipartUploadsRequest,
+    ) -> Result<(), PageTokenError> {
+        self.validate(
+            Listing::MultipartUploads,
+            &input.bucket,
+            &input.prefix,
+            &input.delimiter,
+        )?;
+        input.key_marker = Some(self.marker.clone());
+        input.upload_id_marker = self.id_marker.clone();
+        Ok(())
+    }
+}
+
+impl fmt::Display for PageToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let json = serde_json::to_vec(self).map_err(|_| fmt::Error)?;
+        f.write_str(&base64::encode_config(json, base64::URL_SAFE_NO_PAD))
+    }
+}
+
+impl FromStr for PageToken {
+    type Err = PageTokenError;
+
+    fn from_str(s: &str) -> Result<PageToken, PageTokenError> {
+        let json = base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+            .map_err(|e| PageTokenError::Malformed(e.to_string()))?;
+        serde_json::from_slice(&json).map_err(|e| PageTokenError::Malformed(e.to_string()))
+    }
+}
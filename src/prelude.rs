@@ -0,0 +1,43 @@
+//! The types most applications need, in a single import:
+//!
+//! ```rust,ignore
+//! use ks3::prelude::*;
+//!
+//! let client = S3Client::new(Region::default());
+//! let object = client
+//!     .get_object(GetObjectRequest::builder().bucket("photos").key("puppy.jpg").build()?)
+//!     .await?;
+//! ```
+//!
+//! Only the client, its configuration and credentials, and the requests, outputs and errors of
+//! the object and multipart operations are exported, the latter with the `multipart` feature.
+//! The other operations, and the helpers of the modules such as `ks3::multipart` or
+//! `ks3::sync`, are imported from the crate root or their module.
+
+pub use crate::builder::BuildError;
+pub use crate::config::{AcceptEncoding, Ks3Config, OperationConfig};
+pub use crate::core::error::{Ks3Error, Ks3Result};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::credential::DefaultCredentialsProvider;
+pub use crate::credential::{
+    AwsCredentials, CredentialsError, ProvideAwsCredentials, StaticProvider,
+};
+pub use crate::enums::{CannedAcl, ServerSideEncryption, StorageClass};
+pub use crate::request::{
+    CopyObjectError, CopyObjectOutput, CopyObjectRequest, DeleteObjectError, DeleteObjectOutput,
+    DeleteObjectRequest, GetObjectError, GetObjectOutput, GetObjectRequest, HeadObjectError,
+    HeadObjectOutput, HeadObjectRequest, ListObjectsError, ListObjectsOutput, ListObjectsRequest,
+    Object, PutObjectError, PutObjectOutput, PutObjectRequest, StreamingBody,
+};
+pub use crate::resource::{BucketHandle, ObjectHandle};
+#[cfg(feature = "multipart")]
+pub use crate::s3::ops::{
+    AbortMultipartUploadError, AbortMultipartUploadOutput, AbortMultipartUploadRequest,
+    CompleteMultipartUploadError, CompleteMultipartUploadOutput, CompleteMultipartUploadRequest,
+    CompletedMultipartUpload, CompletedPart, CreateMultipartUploadError,
+    CreateMultipartUploadOutput, CreateMultipartUploadRequest, UploadPartError, UploadPartOutput,
+    UploadPartRequest,
+};
+pub use crate::s3::{S3Client, S3};
+pub use crate::signature::{ByteStream, Region};
+pub use crate::summary::ObjectSummary;
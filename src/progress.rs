@@ -0,0 +1,234 @@
+//! Progress events of the transfer helpers, for showing the state of running transfers.
+//!
+//! `upload_file`, `upload_stream_with_progress`, `MultipartUpload`, `sync_up` and `sync_down`
+//! report their progress to a `ProgressReporter` when given one, either through a callback or a
+//! channel:
+//!
+//! ```rust,ignore
+//! let (progress, mut events) = ProgressReporter::channel();
+//! let options = SyncOptions {
+//!     progress: Some(progress),
+//!     ..Default::default()
+//! };
+//! tokio::spawn(async move {
+//!     while let Some(event) = events.next().await {
+//!         if let ProgressEvent::BytesTransferred { ref key, .. } = event {
+//!             println!("{}: {:?}%", key, event.percent());
+//!         }
+//!     }
+//! });
+//! sync_down(&client, "photos", "2020/", "/tmp/photos", &options).await?;
+//! ```
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures::channel::mpsc::{self, UnboundedReceiver};
+use futures::Stream;
+use pin_project::pin_project;
+
+use crate::signature::ByteStream;
+
+/// Something that happened to a transfer, identified by the key of its object.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgressEvent {
+    /// The transfer of an object started.
+    TransferStarted {
+        /// The key of the object.
+        key: String,
+        /// The size of the object, when known.
+        total_bytes: Option<u64>,
+    },
+    /// A part of a multipart upload started to be sent.
+    PartStarted {
+        /// The key of the object.
+        key: String,
+        /// The number of the part.
+        part_number: i64,
+        /// The size of the part.
+        bytes: u64,
+    },
+    /// A part of a multipart upload was stored.
+    PartCompleted {
+        /// The key of the object.
+        key: String,
+        /// The number of the part.
+        part_number: i64,
+        /// The size of the part.
+        bytes: u64,
+    },
+    /// A part of a multipart upload failed.
+    PartFailed {
+        /// The key of the object.
+        key: String,
+        /// The number of the part.
+        part_number: i64,
+        /// The error.
+        error: String,
+    },
+    /// More of an object was transferred.
+    BytesTransferred {
+        /// The key of the object.
+        key: String,
+        /// The number of bytes transferred so far.
+        transferred: u64,
+        /// The size of the object, when known.
+        total_bytes: Option<u64>,
+    },
+    /// The transfer of an object succeeded.
+    TransferCompleted {
+        /// The key of the object.
+        key: String,
+    },
+    /// The transfer of an object failed.
+    TransferFailed {
+        /// The key of the object.
+        key: String,
+        /// The error.
+        error: String,
+    },
+}
+
+impl ProgressEvent {
+    /// The key of the object the event is about.
+    pub fn key(&self) -> &str {
+        match *self {
+            ProgressEvent::TransferStarted { ref key, .. }
+            | ProgressEvent::PartStarted { ref key, .. }
+            | ProgressEvent::PartCompleted { ref key, .. }
+            | ProgressEvent::PartFailed { ref key, .. }
+            | ProgressEvent::BytesTransferred { ref key, .. }
+            | ProgressEvent::TransferCompleted { ref key }
+            | ProgressEvent::TransferFailed { ref key, .. } => key,
+        }
+    }
+
+    /// The share of the object transferred so far, from 0 to 100, for `BytesTransferred`
+    /// events of objects of known size.
+    pub fn percent(&self) -> Option<f64> {
+        match *self {
+            ProgressEvent::BytesTransferred {
+                transferred,
+                total_bytes: Some(total),
+                ..
+            } => Some(if total == 0 {
+                100.0
+            } else {
+                transferred as f64 * 100.0 / total as f64
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Where progress events are sent. Cloning a reporter sends the events of both clones to the
+/// same place.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    callback: Arc<dyn Fn(ProgressEvent) + Send + Sync>,
+}
+
+impl ProgressReporter {
+    /// Call `callback` with every event. It is called on the task running the transfer, so it
+    /// should return quickly.
+    pub fn new<F>(callback: F) -> ProgressReporter
+    where
+        F: Fn(ProgressEvent) + Send + Sync + 'static,
+    {
+        ProgressReporter {
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Send the events to the returned receiver. Events are dropped once the receiver is.
+    pub fn channel() -> (ProgressReporter, UnboundedReceiver<ProgressEvent>) {
+        let (sender, receiver) = mpsc::unbounded();
+        let reporter = ProgressReporter::new(move |event| {
+            let _ = sender.unbounded_send(event);
+        });
+        (reporter, receiver)
+    }
+
+    pub(crate) fn emit(&self, event: ProgressEvent) {
+        (self.callback)(event)
+    }
+
+    /// Wrap `body`, the `total_bytes` bytes of the object `key`, to report `BytesTransferred`
+    /// events as it is read.
+    pub(crate) fn track(
+        &self,
+        key: &str,
+        body: ByteStream,
+        total_bytes: Option<u64>,
+    ) -> ByteStream {
+        body.map_stream(|body| Tracked {
+            inner: body,
+            reporter: self.clone(),
+            key: key.to_owned(),
+            transferred: 0,
+            total_bytes,
+        })
+    }
+}
+
+impl fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ProgressReporter")
+    }
+}
+
+/// Emit `event` to `reporter`, if any.
+pub(crate) fn emit(reporter: Option<&ProgressReporter>, event: impl FnOnce() -> ProgressEvent) {
+    if let Some(reporter) = reporter {
+        reporter.emit(event());
+    }
+}
+
+/// Emit the `TransferCompleted` or `TransferFailed` event matching `result`, the outcome of the
+/// transfer of `key`, to `reporter`, if any.
+pub(crate) fn report_outcome<T, E: fmt::Display>(
+    reporter: Option<&ProgressReporter>,
+    key: &str,
+    result: &Result<T, E>,
+) {
+    emit(reporter, || match *result {
+        Ok(_) => ProgressEvent::TransferCompleted {
+            key: key.to_owned(),
+        },
+        Err(ref err) => ProgressEvent::TransferFailed {
+            key: key.to_owned(),
+            error: err.to_string(),
+        },
+    })
+}
+
+#[pin_project]
+struct Tracked {
+    #[pin]
+    inner: ByteStream,
+    reporter: ProgressReporter,
+    key: String,
+    transferred: u64,
+    total_bytes: Option<u64>,
+}
+
+impl Stream for Tracked {
+    type Item = Result<Bytes, std::io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let item = futures::ready!(this.inner.poll_next(cx));
+        if let Some(Ok(ref chunk)) = item {
+            *this.transferred += chunk.len() as u64;
+            this.reporter.emit(ProgressEvent::BytesTransferred {
+                key: this.key.clone(),
+                transferred: *this.transferred,
+                total_bytes: *this.total_bytes,
+            });
+        }
+        Poll::Ready(item)
+    }
+}
@@ -0,0 +1,60 @@
+//! Unauthenticated URLs of objects, for objects readable by everyone.
+
+use crate::bucket_name::validate_bucket_name;
+use crate::s3::S3Client;
+use crate::signature::signer::extract_endpoint_path;
+use crate::signature::{Region, SignedRequest};
+
+impl S3Client {
+    /// The URL at which the object `key` in `bucket` can be read without signing, if its ACL
+    /// allows public reads.
+    ///
+    /// The URL is built on `Ks3Config::custom_domain` when it is set, and on the endpoint of the
+    /// client's region otherwise, following `Ks3Config::virtual_hosted_urls`; the region and
+    /// addressing style of the bucket's profile take precedence, if it has one. The key is
+    /// percent-encoded following `Ks3Config::uri_encoding`, except for its `/` separators.
+    pub fn object_url(&self, bucket: &str, key: &str) -> String {
+        let encoding = self.config().uri_encoding;
+        let key = encoding.encode_path(key);
+        if let Some(domain) = self.config().custom_domain.as_deref() {
+            let domain = domain.trim_end_matches('/');
+            return if domain.contains("://") {
+                format!("{}/{}", domain, key)
+            } else {
+                format!("https://{}/{}", domain, key)
+            };
+        }
+
+        let region = self
+            .config()
+            .bucket_profile(bucket)
+            .and_then(|profile| profile.region.as_ref())
+            .unwrap_or_else(|| self.region());
+        let request = SignedRequest::new("GET", "s3", region, "/");
+        let base_path = match region {
+            Region::Custom { ref endpoint, .. } => extract_endpoint_path(endpoint)
+                .unwrap_or("")
+                .trim_end_matches('/'),
+            _ => "",
+        };
+        if self.config().virtual_hosted_urls_for(bucket) && validate_bucket_name(bucket).is_ok() {
+            format!(
+                "{}://{}.{}{}/{}",
+                request.scheme(),
+                bucket,
+                request.hostname(),
+                base_path,
+                key
+            )
+        } else {
+            format!(
+                "{}://{}{}/{}/{}",
+                request.scheme(),
+                request.hostname(),
+                base_path,
+                encoding.encode_path(bucket),
+                key
+            )
+        }
+    }
+}
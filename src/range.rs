@@ -0,0 +1,150 @@
+//! Byte ranges, for reading or copying part of an object.
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+/// Errors returned when parsing a range header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeParseError {
+    value: String,
+}
+
+impl fmt::Display for RangeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid byte range: {:?}", self.value)
+    }
+}
+
+impl Error for RangeParseError {}
+
+/// A range of bytes of an object, sent in the `Range` header of `get_object` and
+/// `head_object` and the `x-amz-copy-source-range` header of `upload_part_copy`.
+///
+/// Bounds are inclusive, as in the header: `Range::bytes(0..=99)` is the first 100 bytes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "deserialize_structs",
+    derive(Deserialize),
+    serde(try_from = "String")
+)]
+#[cfg_attr(
+    feature = "serialize_structs",
+    derive(Serialize),
+    serde(into = "String")
+)]
+pub enum Range {
+    /// The bytes from `first` to `last`, both included.
+    Bytes {
+        /// The offset of the first byte.
+        first: u64,
+        /// The offset of the last byte.
+        last: u64,
+    },
+    /// The bytes from an offset to the end of the object.
+    FromOffset(u64),
+    /// The last bytes of the object.
+    Last(u64),
+}
+
+impl Range {
+    /// The bytes in `range`, e.g. `Range::bytes(0..=99)` for the first 100 bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is empty.
+    pub fn bytes(range: RangeInclusive<u64>) -> Range {
+        let (first, last) = range.into_inner();
+        assert!(first <= last, "empty byte range {}..={}", first, last);
+        Range::Bytes { first, last }
+    }
+
+    /// The bytes from `offset` to the end of the object.
+    pub fn from_offset(offset: u64) -> Range {
+        Range::FromOffset(offset)
+    }
+
+    /// The last `count` bytes of the object.
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero.
+    pub fn last(count: u64) -> Range {
+        assert!(count > 0, "empty byte range");
+        Range::Last(count)
+    }
+
+    /// The offsets of the first and last bytes of the range in an object of `len` bytes, or
+    /// `None` if the range doesn't overlap the object. Ranges extending past the end of the
+    /// object are cut to it, as the service does.
+    pub fn resolve(&self, len: u64) -> Option<(u64, u64)> {
+        let end = len.checked_sub(1)?;
+        let (first, last) = match *self {
+            Range::Bytes { first, last } => (first, last.min(end)),
+            Range::FromOffset(first) => (first, end),
+            Range::Last(count) => (len - count.min(len), end),
+        };
+        if first > last {
+            return None;
+        }
+        Some((first, last))
+    }
+}
+
+/// Renders the range as the value of a range header, e.g. `bytes=0-99`.
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Range::Bytes { first, last } => write!(f, "bytes={}-{}", first, last),
+            Range::FromOffset(first) => write!(f, "bytes={}-", first),
+            Range::Last(count) => write!(f, "bytes=-{}", count),
+        }
+    }
+}
+
+/// Parses the value of a range header with a single range.
+impl FromStr for Range {
+    type Err = RangeParseError;
+
+    fn from_str(value: &str) -> Result<Range, RangeParseError> {
+        let parse = || {
+            let (first, last) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+            match (first.trim(), last.trim()) {
+                ("", count) => count
+                    .parse()
+                    .ok()
+                    .filter(|&count| count > 0)
+                    .map(Range::Last),
+                (first, "") => Some(Range::FromOffset(first.parse().ok()?)),
+                (first, last) => {
+                    let (first, last) = (first.parse().ok()?, last.parse().ok()?);
+                    Some(Range::Bytes { first, last }).filter(|_| first <= last)
+                }
+            }
+        };
+        parse().ok_or_else(|| RangeParseError {
+            value: value.to_owned(),
+        })
+    }
+}
+
+impl TryFrom<String> for Range {
+    type Error = RangeParseError;
+
+    fn try_from(value: String) -> Result<Range, RangeParseError> {
+        value.parse()
+    }
+}
+
+impl From<Range> for String {
+    fn from(range: Range) -> String {
+        range.to_string()
+    }
+}
@@ -1,26 +1,39 @@
+use std::collections::BTreeMap;
 use std::default::Default;
 use std::error::Error;
 use std::fmt;
 use std::io::Write;
+use std::str::FromStr;
 
+use chrono::{DateTime, Utc};
+use http::{HeaderMap, StatusCode};
 #[cfg(feature = "deserialize_structs")]
 use serde::Deserialize;
 #[cfg(feature = "serialize_structs")]
 use serde::Serialize;
+use xml::reader::XmlEvent;
 use xml::EventReader;
 use xml::EventWriter;
 
 use crate::core::error::Ks3Error;
 use crate::core::proto::xml::error::{XmlError, XmlErrorDeserializer};
 use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError, XmlResponse};
-use crate::core::proto::xml::util::{find_start_element, write_characters_element};
+use crate::core::proto::xml::util::{
+    deserialize_elements, find_start_element, retain_tree, skip_tree, write_characters_element,
+};
 use crate::core::request::BufferedHttpResponse;
+use crate::dates::parse_iso8601;
+use crate::enums::{CannedAcl, ObjectLockMode, RequestPayer, ServerSideEncryption, StorageClass};
+use crate::metadata::Metadata;
+use crate::range::Range;
+use crate::select::SelectObjectContentEventStream;
 
 pub type StreamingBody = crate::signature::ByteStream;
 
 /// <p>The configuration information for the bucket.</p>
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
 pub struct CreateBucketConfiguration {
     /// <p>Specifies the Region where the bucket will be created. If you don't specify a Region, the bucket is created in the US East (N. Virginia) Region (us-east-1).</p>
     pub location_constraint: Option<String>,
@@ -46,10 +59,17 @@ impl CreateBucketConfigurationSerializer {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
 #[cfg_attr(feature = "serialize_structs", derive(Serialize))]
 pub struct CreateBucketOutput {
     /// <p>Specifies the Region where the bucket will be created. If you are creating a bucket on the US East (N. Virginia) Region (us-east-1), you do not need to specify the location.</p>
     pub location: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
 }
 
 #[allow(dead_code)]
@@ -72,9 +92,10 @@ impl CreateBucketOutputDeserializer {
 
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
 pub struct CreateBucketRequest {
     /// <p>The canned ACL to apply to the bucket.</p>
-    pub acl: Option<String>,
+    pub acl: Option<CannedAcl>,
     /// <p>The name of the bucket to create.</p>
     pub bucket: String,
     /// <p>The configuration information for the bucket.</p>
@@ -148,8 +169,11 @@ impl fmt::Display for CreateBucketError {
 impl Error for CreateBucketError {}
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
 #[cfg_attr(feature = "serialize_structs", derive(Serialize))]
 pub struct PutObjectOutput {
+    /// <p>The CRC64 (ECMA-182) checksum of the whole object, as reported by KS3.</p>
+    pub checksum_crc64ecma: Option<String>,
     /// <p>Entity tag for the uploaded object.</p>
     pub e_tag: Option<String>,
     /// <p> If the expiration is configured for the object (see <a>PutBucketLifecycleConfiguration</a>), the response includes this header. It includes the expiry-date and rule-id key-value pairs that provide information about object expiration. The value of the rule-id is URL encoded.</p>
@@ -164,9 +188,15 @@ pub struct PutObjectOutput {
     /// <p>If <code>x-amz-server-side-encryption</code> is present and has the value of <code>aws:kms</code>, this header specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object. </p>
     pub ssekms_key_id: Option<String>,
     /// <p>If you specified server-side encryption either with an AWS KMS customer master key (CMK) or Amazon S3-managed encryption key in your PUT request, the response includes this header. It confirms the encryption algorithm that Amazon S3 used to encrypt the object.</p>
-    pub server_side_encryption: Option<String>,
+    pub server_side_encryption: Option<ServerSideEncryption>,
     /// <p>Version of the object.</p>
     pub version_id: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
 }
 
 #[allow(dead_code)]
@@ -188,10 +218,16 @@ impl PutObjectOutputDeserializer {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
 pub struct PutObjectRequest {
     /// <p>The canned ACL to apply to the object. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html#CannedACL">Canned ACL</a>.</p>
-    pub acl: Option<String>,
+    pub acl: Option<CannedAcl>,
     /// <p>Object data.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
     pub body: Option<StreamingBody>,
     /// <p>Bucket name to which the PUT operation was initiated. </p> <p>When using this API with an access point, you must direct requests to the access point hostname. The access point hostname takes the form <i>AccessPointName</i>-<i>AccountId</i>.s3-accesspoint.<i>Region</i>.amazonaws.com. When using this operation using an access point through the AWS SDKs, you provide the access point ARN in place of the bucket name. For more information about access point ARNs, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/using-access-points.html">Using Access Points</a> in the <i>Amazon Simple Storage Service Developer Guide</i>.</p>
     pub bucket: String,
@@ -210,7 +246,7 @@ pub struct PutObjectRequest {
     /// <p>A standard MIME type describing the format of the contents. For more information, see <a href="http://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.17">http://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.17</a>.</p>
     pub content_type: Option<String>,
     /// <p>The date and time at which the object is no longer cacheable. For more information, see <a href="http://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.21">http://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.21</a>.</p>
-    pub expires: Option<String>,
+    pub expires: Option<DateTime<Utc>>,
     /// <p>Gives the grantee READ, READ_ACP, and WRITE_ACP permissions on the object.</p>
     pub grant_full_control: Option<String>,
     /// <p>Allows grantee to read the object data and its metadata.</p>
@@ -222,14 +258,14 @@ pub struct PutObjectRequest {
     /// <p>Object key for which the PUT operation was initiated.</p>
     pub key: String,
     /// <p>A map of metadata to store with the object in S3.</p>
-    pub metadata: Option<::std::collections::HashMap<String, String>>,
+    pub metadata: Option<Metadata>,
     /// <p>Specifies whether a legal hold will be applied to this object. For more information about S3 Object Lock, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/object-lock.html">Object Lock</a>.</p>
     pub object_lock_legal_hold_status: Option<String>,
     /// <p>The Object Lock mode that you want to apply to this object.</p>
-    pub object_lock_mode: Option<String>,
+    pub object_lock_mode: Option<ObjectLockMode>,
     /// <p>The date and time when you want this object's Object Lock to expire.</p>
-    pub object_lock_retain_until_date: Option<String>,
-    pub request_payer: Option<String>,
+    pub object_lock_retain_until_date: Option<DateTime<Utc>>,
+    pub request_payer: Option<RequestPayer>,
     /// <p>Specifies the algorithm to use to when encrypting the object (for example, AES256).</p>
     pub sse_customer_algorithm: Option<String>,
     /// <p>Specifies the customer-provided encryption key for Amazon S3 to use in encrypting data. This value is used to store the object and then it is discarded; Amazon S3 does not store the encryption key. The key must be appropriate for use with the algorithm specified in the <code>x-amz-server-side​-encryption​-customer-algorithm</code> header.</p>
@@ -241,15 +277,66 @@ pub struct PutObjectRequest {
     /// <p>If <code>x-amz-server-side-encryption</code> is present and has the value of <code>aws:kms</code>, this header specifies the ID of the AWS Key Management Service (AWS KMS) symmetrical customer managed customer master key (CMK) that was used for the object.</p> <p> If the value of <code>x-amz-server-side-encryption</code> is <code>aws:kms</code>, this header specifies the ID of the symmetric customer managed AWS KMS CMK that will be used for the object. If you specify <code>x-amz-server-side-encryption:aws:kms</code>, but do not provide<code> x-amz-server-side-encryption-aws-kms-key-id</code>, Amazon S3 uses the AWS managed CMK in AWS to protect the data.</p>
     pub ssekms_key_id: Option<String>,
     /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
-    pub server_side_encryption: Option<String>,
+    pub server_side_encryption: Option<ServerSideEncryption>,
     /// <p>If you don't specify, S3 Standard is the default storage class. Amazon S3 supports other storage classes.</p>
-    pub storage_class: Option<String>,
+    pub storage_class: Option<StorageClass>,
     /// <p>The tag-set for the object. The tag-set must be encoded as URL Query parameters. (For example, "Key1=Value1")</p>
     pub tagging: Option<String>,
     /// <p>If the bucket is configured as a website, redirects requests for this object to another object in the same bucket or to an external URL. Amazon S3 stores the value of this header in the object metadata. For information about object metadata, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingMetadata.html">Object Key and Metadata</a>.</p> <p>In the following example, the request header sets the redirect to an object (anotherPage.html) in the same bucket:</p> <p> <code>x-amz-website-redirect-location: /anotherPage.html</code> </p> <p>In the following example, the request header sets the object redirect to another website:</p> <p> <code>x-amz-website-redirect-location: http://www.example.com/</code> </p> <p>For more information about website hosting in Amazon S3, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/WebsiteHosting.html">Hosting Websites on Amazon S3</a> and <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/how-to-page-redirect.html">How to Configure Website Page Redirects</a>. </p>
     pub website_redirect_location: Option<String>,
 }
 
+impl PutObjectRequest {
+    /// A copy of the request without its body, e.g. to send the same request again with a new
+    /// body. The request isn't `Clone` because a streaming body can only be read once.
+    pub fn clone_without_body(&self) -> PutObjectRequest {
+        PutObjectRequest {
+            acl: self.acl.clone(),
+            body: None,
+            bucket: self.bucket.clone(),
+            cache_control: self.cache_control.clone(),
+            content_disposition: self.content_disposition.clone(),
+            content_encoding: self.content_encoding.clone(),
+            content_language: self.content_language.clone(),
+            content_length: self.content_length,
+            content_md5: self.content_md5.clone(),
+            content_type: self.content_type.clone(),
+            expires: self.expires,
+            grant_full_control: self.grant_full_control.clone(),
+            grant_read: self.grant_read.clone(),
+            grant_read_acp: self.grant_read_acp.clone(),
+            grant_write_acp: self.grant_write_acp.clone(),
+            key: self.key.clone(),
+            metadata: self.metadata.clone(),
+            object_lock_legal_hold_status: self.object_lock_legal_hold_status.clone(),
+            object_lock_mode: self.object_lock_mode.clone(),
+            object_lock_retain_until_date: self.object_lock_retain_until_date,
+            request_payer: self.request_payer.clone(),
+            sse_customer_algorithm: self.sse_customer_algorithm.clone(),
+            sse_customer_key: self.sse_customer_key.clone(),
+            sse_customer_key_md5: self.sse_customer_key_md5.clone(),
+            ssekms_encryption_context: self.ssekms_encryption_context.clone(),
+            ssekms_key_id: self.ssekms_key_id.clone(),
+            server_side_encryption: self.server_side_encryption.clone(),
+            storage_class: self.storage_class.clone(),
+            tagging: self.tagging.clone(),
+            website_redirect_location: self.website_redirect_location.clone(),
+        }
+    }
+
+    /// A copy of the request, if its body is absent or was created from an in-memory buffer.
+    pub fn try_clone(&self) -> Option<PutObjectRequest> {
+        let body = match self.body {
+            Some(ref body) => Some(body.try_clone()?),
+            None => None,
+        };
+        Some(PutObjectRequest {
+            body,
+            ..self.clone_without_body()
+        })
+    }
+}
+
 /// Errors returned by PutObject
 #[derive(Debug, PartialEq)]
 pub enum PutObjectError {}
@@ -285,3 +372,2416 @@ impl fmt::Display for PutObjectError {
 }
 
 impl Error for PutObjectError {}
+
+#[allow(dead_code)]
+pub(crate) struct BooleanDeserializer;
+impl BooleanDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<bool, XmlParseError> {
+        xml_util::deserialize_primitive(tag_name, stack, |s| {
+            bool::from_str(&s).map_err(|e| XmlParseError::new(&e.to_string()))
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct LongDeserializer;
+impl LongDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<i64, XmlParseError> {
+        xml_util::deserialize_primitive(tag_name, stack, |s| Ok(i64::from_str(&s)?))
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct DateTimeDeserializer;
+impl DateTimeDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<DateTime<Utc>, XmlParseError> {
+        xml_util::deserialize_primitive(tag_name, stack, |s| {
+            parse_iso8601(&s).ok_or_else(|| XmlParseError::new(&format!("Invalid date: {}", s)))
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct StringDeserializer;
+impl StringDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<String, XmlParseError> {
+        xml_util::deserialize_primitive(tag_name, stack, Ok)
+    }
+}
+
+/// <p>Container for all (if there are any) keys between Prefix and the next occurrence of the string specified by a delimiter. CommonPrefixes lists keys that act like subdirectories in the directory specified by Prefix. For example, if the prefix is notes/ and the delimiter is a slash (/) as in notes/summer/july, the common prefix is notes/summer/. </p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CommonPrefix {
+    /// <p>Container for the specified common prefix.</p>
+    pub prefix: Option<String>,
+}
+
+#[allow(dead_code)]
+struct CommonPrefixDeserializer;
+impl CommonPrefixDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<CommonPrefix, XmlParseError> {
+        deserialize_elements::<_, CommonPrefix, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct CommonPrefixListDeserializer;
+impl CommonPrefixListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<CommonPrefix>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(CommonPrefixDeserializer::deserialize(tag_name, stack)?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+/// <p>Container for the owner's display name and ID.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Owner {
+    /// <p>Container for the display name of the owner.</p>
+    pub display_name: Option<String>,
+    /// <p>Container for the ID of the owner.</p>
+    pub id: Option<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct OwnerDeserializer;
+impl OwnerDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Owner, XmlParseError> {
+        deserialize_elements::<_, Owner, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "DisplayName" => {
+                    obj.display_name = Some(StringDeserializer::deserialize("DisplayName", stack)?);
+                }
+                "ID" => {
+                    obj.id = Some(StringDeserializer::deserialize("ID", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+/// <p>An object consists of data and its descriptive metadata.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Object {
+    /// <p>The entity tag is an MD5 hash of the object. ETag reflects only changes to the contents of an object, not its metadata.</p>
+    pub e_tag: Option<String>,
+    /// <p>The name that you assign to an object. You use the object key to retrieve the object.</p>
+    pub key: Option<String>,
+    /// <p>The date the Object was Last Modified</p>
+    pub last_modified: Option<DateTime<Utc>>,
+    /// <p>The owner of the object</p>
+    pub owner: Option<Owner>,
+    /// <p>Size in bytes of the object</p>
+    pub size: Option<i64>,
+    /// <p>The class of storage used to store the object.</p>
+    pub storage_class: Option<StorageClass>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct ObjectDeserializer;
+impl ObjectDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Object, XmlParseError> {
+        deserialize_elements::<_, Object, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "ETag" => {
+                    obj.e_tag = Some(StringDeserializer::deserialize("ETag", stack)?);
+                }
+                "Key" => {
+                    obj.key = Some(StringDeserializer::deserialize("Key", stack)?);
+                }
+                "LastModified" => {
+                    obj.last_modified =
+                        Some(DateTimeDeserializer::deserialize("LastModified", stack)?);
+                }
+                "Owner" => {
+                    obj.owner = Some(OwnerDeserializer::deserialize("Owner", stack)?);
+                }
+                "Size" => {
+                    obj.size = Some(LongDeserializer::deserialize("Size", stack)?);
+                }
+                "StorageClass" => {
+                    obj.storage_class =
+                        Some(StringDeserializer::deserialize("StorageClass", stack)?.into());
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+struct ObjectListDeserializer;
+impl ObjectListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<Object>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(ObjectDeserializer::deserialize(tag_name, stack)?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListObjectsOutput {
+    /// <p>All of the keys rolled up in a common prefix count as a single return when calculating the number of returns. </p> <p>A response can contain CommonPrefixes only if you specify a delimiter.</p> <p>CommonPrefixes contains all (if there are any) keys between Prefix and the next occurrence of the string specified by the delimiter.</p> <p> CommonPrefixes lists keys that act like subdirectories in the directory specified by Prefix.</p> <p>For example, if the prefix is notes/ and the delimiter is a slash (/) as in notes/summer/july, the common prefix is notes/summer/. All of the keys that roll up into a common prefix count as a single return when calculating the number of returns.</p>
+    pub common_prefixes: Option<Vec<CommonPrefix>>,
+    /// <p>Metadata about each object returned.</p>
+    pub contents: Option<Vec<Object>>,
+    /// <p>Causes keys that contain the same string between the prefix and the first occurrence of the delimiter to be rolled up into a single result element in the <code>CommonPrefixes</code> collection. These rolled-up keys are not returned elsewhere in the response. Each rolled-up result counts as only one return against the <code>MaxKeys</code> value.</p>
+    pub delimiter: Option<String>,
+    /// <p>Encoding type used by Amazon S3 to encode object keys in the response.</p>
+    pub encoding_type: Option<String>,
+    /// <p>A flag that indicates whether Amazon S3 returned all of the results that satisfied the search criteria.</p>
+    pub is_truncated: Option<bool>,
+    /// <p>Indicates where in the bucket listing begins. Marker is included in the response if it was sent with the request.</p>
+    pub marker: Option<String>,
+    /// <p>The maximum number of keys returned in the response body.</p>
+    pub max_keys: Option<i64>,
+    /// <p>The bucket name.</p>
+    pub name: Option<String>,
+    /// <p>When response is truncated (the IsTruncated element value in the response is true), you can use the key name in this field as marker in the subsequent request to get next set of objects. Amazon S3 lists objects in alphabetical order Note: This element is returned only if you have delimiter request parameter specified. If response does not include the NextMaker and it is truncated, you can use the value of the last Key in the response as the marker in the subsequent request to get the next set of object keys.</p>
+    pub next_marker: Option<String>,
+    /// <p>Keys that begin with the indicated prefix.</p>
+    pub prefix: Option<String>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct ListObjectsOutputDeserializer;
+impl ListObjectsOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<ListObjectsOutput, XmlParseError> {
+        deserialize_elements::<_, ListObjectsOutput, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "CommonPrefixes" => {
+                    obj.common_prefixes.get_or_insert(vec![]).extend(
+                        CommonPrefixListDeserializer::deserialize("CommonPrefixes", stack)?,
+                    );
+                }
+                "Contents" => {
+                    obj.contents
+                        .get_or_insert(vec![])
+                        .extend(ObjectListDeserializer::deserialize("Contents", stack)?);
+                }
+                "Delimiter" => {
+                    obj.delimiter = Some(StringDeserializer::deserialize("Delimiter", stack)?);
+                }
+                "EncodingType" => {
+                    obj.encoding_type =
+                        Some(StringDeserializer::deserialize("EncodingType", stack)?);
+                }
+                "IsTruncated" => {
+                    obj.is_truncated =
+                        Some(BooleanDeserializer::deserialize("IsTruncated", stack)?);
+                }
+                "Marker" => {
+                    obj.marker = Some(StringDeserializer::deserialize("Marker", stack)?);
+                }
+                "MaxKeys" => {
+                    obj.max_keys = Some(LongDeserializer::deserialize("MaxKeys", stack)?);
+                }
+                "Name" => {
+                    obj.name = Some(StringDeserializer::deserialize("Name", stack)?);
+                }
+                "NextMarker" => {
+                    obj.next_marker = Some(StringDeserializer::deserialize("NextMarker", stack)?);
+                }
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                _ => retain_tree(name, stack, &mut obj.extra),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListObjectsRequest {
+    /// <p>The name of the bucket containing the objects.</p>
+    pub bucket: String,
+    /// <p>A delimiter is a character you use to group keys.</p>
+    pub delimiter: Option<String>,
+    pub encoding_type: Option<String>,
+    /// <p>Specifies the key to start with when listing objects in a bucket.</p>
+    pub marker: Option<String>,
+    /// <p>Sets the maximum number of keys returned in the response. By default the API returns up to 1,000 key names. The response might contain fewer keys but will never contain more. </p>
+    pub max_keys: Option<i64>,
+    /// <p>Limits the response to keys that begin with the specified prefix.</p>
+    pub prefix: Option<String>,
+    /// <p>Confirms that the requester knows that she or he will be charged for the list objects request. Bucket owners need not specify this parameter in their requests.</p>
+    pub request_payer: Option<RequestPayer>,
+}
+
+/// Errors returned by ListObjects
+#[derive(Debug, PartialEq)]
+pub enum ListObjectsError {
+    /// <p>The specified bucket does not exist.</p>
+    NoSuchBucket(String),
+}
+
+impl ListObjectsError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<ListObjectsError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "NoSuchBucket" {
+                    return Ks3Error::Service(ListObjectsError::NoSuchBucket(parsed_error.message));
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for ListObjectsError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ListObjectsError::NoSuchBucket(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for ListObjectsError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct DeleteObjectOutput {
+    /// <p>Specifies whether the versioned object that was permanently deleted was (true) or was not (false) a delete marker.</p>
+    pub delete_marker: Option<bool>,
+    pub request_charged: Option<String>,
+    /// <p>Returns the version ID of the delete marker created as a result of the DELETE operation.</p>
+    pub version_id: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct DeleteObjectRequest {
+    /// <p>The bucket name of the bucket containing the object. </p>
+    pub bucket: String,
+    /// <p>Key name of the object to delete.</p>
+    pub key: String,
+    /// <p>The concatenation of the authentication device's serial number, a space, and the value that is displayed on your authentication device. Required to permanently delete a versioned object if versioning is configured with MFA delete enabled.</p>
+    pub mfa: Option<String>,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>VersionId used to reference a specific version of the object.</p>
+    pub version_id: Option<String>,
+}
+
+/// Errors returned by DeleteObject
+#[derive(Debug, PartialEq)]
+pub enum DeleteObjectError {}
+
+impl DeleteObjectError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<DeleteObjectError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for DeleteObjectError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for DeleteObjectError {}
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetObjectOutput {
+    /// <p>Indicates that a range of bytes was specified.</p>
+    pub accept_ranges: Option<String>,
+    /// <p>Object data.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub body: Option<StreamingBody>,
+    /// <p>Specifies caching behavior along the request/reply chain.</p>
+    pub cache_control: Option<String>,
+    /// <p>The CRC64 (ECMA-182) checksum of the whole object, as reported by KS3.</p>
+    pub checksum_crc64ecma: Option<String>,
+    /// <p>Specifies presentational information for the object.</p>
+    pub content_disposition: Option<String>,
+    /// <p>Specifies what content encodings have been applied to the object and thus what decoding mechanisms must be applied to obtain the media-type referenced by the Content-Type header field.</p>
+    pub content_encoding: Option<String>,
+    /// <p>The language the content is in.</p>
+    pub content_language: Option<String>,
+    /// <p>Size of the body in bytes.</p>
+    pub content_length: Option<i64>,
+    /// <p>The portion of the object returned in the response.</p>
+    pub content_range: Option<String>,
+    /// <p>A standard MIME type describing the format of the object data.</p>
+    pub content_type: Option<String>,
+    /// <p>Specifies whether the object retrieved was (true) or was not (false) a Delete Marker. If false, this response header does not appear in the response.</p>
+    pub delete_marker: Option<bool>,
+    /// <p>An ETag is an opaque identifier assigned by a web server to a specific version of a resource found at a URL.</p>
+    pub e_tag: Option<String>,
+    /// <p>If the object expiration is configured (see PUT Bucket lifecycle), the response includes this header. It includes the expiry-date and rule-id key-value pairs providing object expiration information. The value of the rule-id is URL encoded.</p>
+    pub expiration: Option<String>,
+    /// <p>The date and time at which the object is no longer cacheable.</p>
+    pub expires: Option<DateTime<Utc>>,
+    /// <p>Last modified date of the object</p>
+    pub last_modified: Option<DateTime<Utc>>,
+    /// <p>A map of metadata to store with the object in S3.</p>
+    pub metadata: Option<Metadata>,
+    /// <p>This is set to the number of metadata entries not returned in <code>x-amz-meta</code> headers. This can happen if you create metadata using an API like SOAP that supports more flexible metadata than the REST API. For example, using SOAP, you can create metadata whose values are not legal HTTP headers.</p>
+    pub missing_meta: Option<i64>,
+    /// <p>The encoding the body was sent in following <code>Ks3Config::accept_encoding</code>, e.g. <code>gzip</code>, when it's one of the advertised encodings. The body is returned as received, still encoded: its length and digest are those of the encoded bytes, not those of the object.</p>
+    pub negotiated_encoding: Option<String>,
+    /// <p>Indicates whether this object has an active legal hold. This field is only returned if you have permission to view an object's legal hold status. </p>
+    pub object_lock_legal_hold_status: Option<String>,
+    /// <p>The Object Lock mode currently in place for this object.</p>
+    pub object_lock_mode: Option<ObjectLockMode>,
+    /// <p>The date and time when this object's Object Lock will expire.</p>
+    pub object_lock_retain_until_date: Option<DateTime<Utc>>,
+    /// <p>The count of parts this object has.</p>
+    pub parts_count: Option<i64>,
+    /// <p>Amazon S3 can return this if your request involves a bucket that is either a source or destination in a replication rule.</p>
+    pub replication_status: Option<String>,
+    pub request_charged: Option<String>,
+    /// <p>Provides information about object restoration operation and expiration time of the restored object copy.</p>
+    pub restore: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header confirming the encryption algorithm used.</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header to provide round-trip message integrity verification of the customer-provided encryption key.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>Provides storage class information of the object. Amazon S3 returns this header for all objects except for S3 Standard storage class objects.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>The number of tags, if any, on the object.</p>
+    pub tag_count: Option<i64>,
+    /// <p>Version of the object.</p>
+    pub version_id: Option<String>,
+    /// <p>If the bucket is configured as a website, redirects requests for this object to another object in the same bucket or to an external URL. Amazon S3 stores the value of this header in the object metadata.</p>
+    pub website_redirect_location: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetObjectRequest {
+    /// <p>The bucket name containing the object. </p>
+    pub bucket: String,
+    /// <p>Return the object only if its entity tag (ETag) is the same as the one specified, otherwise return a 412 (precondition failed).</p>
+    pub if_match: Option<String>,
+    /// <p>Return the object only if it has been modified since the specified time, otherwise return a 304 (not modified).</p>
+    pub if_modified_since: Option<DateTime<Utc>>,
+    /// <p>Return the object only if its entity tag (ETag) is different from the one specified, otherwise return a 304 (not modified).</p>
+    pub if_none_match: Option<String>,
+    /// <p>Return the object only if it has not been modified since the specified time, otherwise return a 412 (precondition failed).</p>
+    pub if_unmodified_since: Option<DateTime<Utc>>,
+    /// <p>Key of the object to get.</p>
+    pub key: String,
+    /// <p>Part number of the object being read. This is a positive integer between 1 and 10,000. Effectively performs a 'ranged' GET request for the part specified. Useful for downloading just a part of an object.</p>
+    pub part_number: Option<i64>,
+    /// <p>Downloads the specified range bytes of an object. For more information about the HTTP Range header, see <a href="https://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.35">https://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.35</a>.</p> <note> <p>Amazon S3 doesn't support retrieving multiple ranges of data per <code>GET</code> request.</p> </note>
+    pub range: Option<Range>,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>Sets the <code>Cache-Control</code> header of the response.</p>
+    pub response_cache_control: Option<String>,
+    /// <p>Sets the <code>Content-Disposition</code> header of the response</p>
+    pub response_content_disposition: Option<String>,
+    /// <p>Sets the <code>Content-Encoding</code> header of the response.</p>
+    pub response_content_encoding: Option<String>,
+    /// <p>Sets the <code>Content-Language</code> header of the response.</p>
+    pub response_content_language: Option<String>,
+    /// <p>Sets the <code>Content-Type</code> header of the response.</p>
+    pub response_content_type: Option<String>,
+    /// <p>Sets the <code>Expires</code> header of the response.</p>
+    pub response_expires: Option<DateTime<Utc>>,
+    /// <p>Specifies the algorithm to use to when encrypting the object (for example, AES256).</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use in encrypting data. This value is used to store the object and then it is discarded; Amazon S3 does not store the encryption key. The key must be appropriate for use with the algorithm specified in the <code>x-amz-server-side​-encryption​-customer-algorithm</code> header.</p>
+    pub sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321. Amazon S3 uses this header for a message integrity check to ensure that the encryption key was transmitted without error.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>VersionId used to reference a specific version of the object.</p>
+    pub version_id: Option<String>,
+}
+
+/// Errors returned by GetObject
+#[derive(Debug, PartialEq)]
+pub enum GetObjectError {
+    /// <p>Object is archived and inaccessible until restored.</p>
+    InvalidObjectState(String),
+    /// <p>The specified key does not exist.</p>
+    NoSuchKey(String),
+    /// <p>The object wasn't modified since the time or entity tag of the conditions of the request (<code>304 Not Modified</code>).</p>
+    NotModified {
+        /// The current entity tag of the object, when the response carries it.
+        e_tag: Option<String>,
+    },
+    /// <p>A condition of the request, <code>If-Match</code> or <code>If-Unmodified-Since</code>, doesn't hold (<code>412 Precondition Failed</code>).</p>
+    PreconditionFailed {
+        /// The current entity tag of the object, when the response carries it.
+        e_tag: Option<String>,
+    },
+}
+
+impl GetObjectError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<GetObjectError> {
+        let e_tag = res.headers.get("ETag").cloned();
+        match res.status {
+            StatusCode::NOT_MODIFIED => {
+                return Ks3Error::Service(GetObjectError::NotModified { e_tag })
+            }
+            StatusCode::PRECONDITION_FAILED => {
+                return Ks3Error::Service(GetObjectError::PreconditionFailed { e_tag })
+            }
+            _ => {}
+        }
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                match &parsed_error.code[..] {
+                    "InvalidObjectState" => {
+                        return Ks3Error::Service(GetObjectError::InvalidObjectState(
+                            parsed_error.message,
+                        ))
+                    }
+                    "NoSuchKey" => {
+                        return Ks3Error::Service(GetObjectError::NoSuchKey(parsed_error.message))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for GetObjectError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GetObjectError::InvalidObjectState(ref cause) => write!(f, "{}", cause),
+            GetObjectError::NoSuchKey(ref cause) => write!(f, "{}", cause),
+            GetObjectError::NotModified { ref e_tag } => not_modified(f, e_tag.as_deref()),
+            GetObjectError::PreconditionFailed { ref e_tag } => {
+                precondition_failed(f, e_tag.as_deref())
+            }
+        }
+    }
+}
+
+impl Error for GetObjectError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct HeadBucketRequest {
+    /// <p>The bucket name.</p>
+    pub bucket: String,
+}
+
+/// Errors returned by HeadBucket
+#[derive(Debug, PartialEq)]
+pub enum HeadBucketError {
+    /// <p>The specified bucket does not exist.</p>
+    NoSuchBucket(String),
+}
+
+impl HeadBucketError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<HeadBucketError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "NoSuchBucket" {
+                    return Ks3Error::Service(HeadBucketError::NoSuchBucket(parsed_error.message));
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for HeadBucketError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HeadBucketError::NoSuchBucket(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for HeadBucketError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct HeadObjectOutput {
+    /// <p>Indicates that a range of bytes was specified.</p>
+    pub accept_ranges: Option<String>,
+    /// <p>Specifies caching behavior along the request/reply chain.</p>
+    pub cache_control: Option<String>,
+    /// <p>The CRC64 (ECMA-182) checksum of the whole object, as reported by KS3.</p>
+    pub checksum_crc64ecma: Option<String>,
+    /// <p>Specifies presentational information for the object.</p>
+    pub content_disposition: Option<String>,
+    /// <p>Specifies what content encodings have been applied to the object and thus what decoding mechanisms must be applied to obtain the media-type referenced by the Content-Type header field.</p>
+    pub content_encoding: Option<String>,
+    /// <p>The language the content is in.</p>
+    pub content_language: Option<String>,
+    /// <p>Size of the body in bytes.</p>
+    pub content_length: Option<i64>,
+    /// <p>A standard MIME type describing the format of the object data.</p>
+    pub content_type: Option<String>,
+    /// <p>Specifies whether the object retrieved was (true) or was not (false) a Delete Marker. If false, this response header does not appear in the response.</p>
+    pub delete_marker: Option<bool>,
+    /// <p>An ETag is an opaque identifier assigned by a web server to a specific version of a resource found at a URL.</p>
+    pub e_tag: Option<String>,
+    /// <p>If the object expiration is configured (see PUT Bucket lifecycle), the response includes this header. It includes the expiry-date and rule-id key-value pairs providing object expiration information. The value of the rule-id is URL encoded.</p>
+    pub expiration: Option<String>,
+    /// <p>The date and time at which the object is no longer cacheable.</p>
+    pub expires: Option<DateTime<Utc>>,
+    /// <p>Last modified date of the object</p>
+    pub last_modified: Option<DateTime<Utc>>,
+    /// <p>A map of metadata to store with the object in S3.</p>
+    pub metadata: Option<Metadata>,
+    /// <p>This is set to the number of metadata entries not returned in <code>x-amz-meta</code> headers. This can happen if you create metadata using an API like SOAP that supports more flexible metadata than the REST API. For example, using SOAP, you can create metadata whose values are not legal HTTP headers.</p>
+    pub missing_meta: Option<i64>,
+    /// <p>Specifies whether a legal hold is in effect for this object. This header is only returned if the requester has the <code>s3:GetObjectLegalHold</code> permission. This header is not returned if the specified version of this object has never had a legal hold applied.</p>
+    pub object_lock_legal_hold_status: Option<String>,
+    /// <p>The Object Lock mode, if any, that's in effect for this object. This header is only returned if the requester has the <code>s3:GetObjectRetention</code> permission.</p>
+    pub object_lock_mode: Option<ObjectLockMode>,
+    /// <p>The date and time when the Object Lock retention period expires. This header is only returned if the requester has the <code>s3:GetObjectRetention</code> permission.</p>
+    pub object_lock_retain_until_date: Option<DateTime<Utc>>,
+    /// <p>The count of parts this object has.</p>
+    pub parts_count: Option<i64>,
+    /// <p>Amazon S3 can return this header if your request involves a bucket that is either a source or destination in a replication rule.</p>
+    pub replication_status: Option<String>,
+    pub request_charged: Option<String>,
+    /// <p>If the object is an archived object (an object whose storage class is GLACIER), the response includes this header if either the archive restoration is in progress or an archive copy is already restored.</p>
+    pub restore: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header confirming the encryption algorithm used.</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header to provide round-trip message integrity verification of the customer-provided encryption key.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>If the object is stored using server-side encryption either with an AWS KMS customer master key (CMK) or an Amazon S3-managed encryption key, the response includes this header with the value of the server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>Provides storage class information of the object. Amazon S3 returns this header for all objects except for S3 Standard storage class objects.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>Version of the object.</p>
+    pub version_id: Option<String>,
+    /// <p>If the bucket is configured as a website, redirects requests for this object to another object in the same bucket or to an external URL. Amazon S3 stores the value of this header in the object metadata.</p>
+    pub website_redirect_location: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct HeadObjectRequest {
+    /// <p>The name of the bucket containing the object.</p>
+    pub bucket: String,
+    /// <p>Return the object only if its entity tag (ETag) is the same as the one specified, otherwise return a 412 (precondition failed).</p>
+    pub if_match: Option<String>,
+    /// <p>Return the object only if it has been modified since the specified time, otherwise return a 304 (not modified).</p>
+    pub if_modified_since: Option<DateTime<Utc>>,
+    /// <p>Return the object only if its entity tag (ETag) is different from the one specified, otherwise return a 304 (not modified).</p>
+    pub if_none_match: Option<String>,
+    /// <p>Return the object only if it has not been modified since the specified time, otherwise return a 412 (precondition failed).</p>
+    pub if_unmodified_since: Option<DateTime<Utc>>,
+    /// <p>The object key.</p>
+    pub key: String,
+    /// <p>Part number of the object being read. This is a positive integer between 1 and 10,000. Effectively performs a 'ranged' HEAD request for the part specified. Useful querying about the size of the part and the number of parts in this object.</p>
+    pub part_number: Option<i64>,
+    /// <p>Downloads the specified range bytes of an object. For more information about the HTTP Range header, see <a href="http://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.35">http://www.w3.org/Protocols/rfc2616/rfc2616-sec14.html#sec14.35</a>.</p>
+    pub range: Option<Range>,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>Specifies the algorithm to use to when encrypting the object (for example, AES256).</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use in encrypting data. This value is used to store the object and then it is discarded; Amazon S3 does not store the encryption key. The key must be appropriate for use with the algorithm specified in the <code>x-amz-server-side​-encryption​-customer-algorithm</code> header.</p>
+    pub sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321. Amazon S3 uses this header for a message integrity check to ensure that the encryption key was transmitted without error.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>VersionId used to reference a specific version of the object.</p>
+    pub version_id: Option<String>,
+}
+
+/// Errors returned by HeadObject
+#[derive(Debug, PartialEq)]
+pub enum HeadObjectError {
+    /// <p>The specified key does not exist.</p>
+    NoSuchKey(String),
+    /// <p>The object wasn't modified since the time or entity tag of the conditions of the request (<code>304 Not Modified</code>).</p>
+    NotModified {
+        /// The current entity tag of the object, when the response carries it.
+        e_tag: Option<String>,
+    },
+    /// <p>A condition of the request, <code>If-Match</code> or <code>If-Unmodified-Since</code>, doesn't hold (<code>412 Precondition Failed</code>).</p>
+    PreconditionFailed {
+        /// The current entity tag of the object, when the response carries it.
+        e_tag: Option<String>,
+    },
+}
+
+impl HeadObjectError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<HeadObjectError> {
+        let e_tag = res.headers.get("ETag").cloned();
+        match res.status {
+            StatusCode::NOT_MODIFIED => {
+                return Ks3Error::Service(HeadObjectError::NotModified { e_tag })
+            }
+            StatusCode::PRECONDITION_FAILED => {
+                return Ks3Error::Service(HeadObjectError::PreconditionFailed { e_tag })
+            }
+            _ => {}
+        }
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "NoSuchKey" {
+                    return Ks3Error::Service(HeadObjectError::NoSuchKey(parsed_error.message));
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for HeadObjectError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HeadObjectError::NoSuchKey(ref cause) => write!(f, "{}", cause),
+            HeadObjectError::NotModified { ref e_tag } => not_modified(f, e_tag.as_deref()),
+            HeadObjectError::PreconditionFailed { ref e_tag } => {
+                precondition_failed(f, e_tag.as_deref())
+            }
+        }
+    }
+}
+
+impl Error for HeadObjectError {}
+
+/// Describe a `304 Not Modified` answer to a request for the object with `e_tag`.
+fn not_modified(f: &mut fmt::Formatter, e_tag: Option<&str>) -> fmt::Result {
+    match e_tag {
+        Some(e_tag) => write!(f, "The object was not modified (ETag {})", e_tag),
+        None => write!(f, "The object was not modified"),
+    }
+}
+
+/// Describe a `412 Precondition Failed` answer to a request for the object with `e_tag`.
+fn precondition_failed(f: &mut fmt::Formatter, e_tag: Option<&str>) -> fmt::Result {
+    match e_tag {
+        Some(e_tag) => write!(f, "A precondition of the request failed (ETag {})", e_tag),
+        None => write!(f, "A precondition of the request failed"),
+    }
+}
+
+/// <p>A container of a key value name pair.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Tag {
+    /// <p>Name of the object key.</p>
+    pub key: String,
+    /// <p>Value of the tag.</p>
+    pub value: String,
+}
+
+pub struct TagSerializer;
+impl TagSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Tag,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        write_characters_element(writer, "Key", &obj.key.to_string())?;
+        write_characters_element(writer, "Value", &obj.value.to_string())?;
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+pub struct TagSetSerializer;
+impl TagSetSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Vec<Tag>,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        for element in obj {
+            TagSerializer::serialize(writer, "Tag", element)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())?;
+        Ok(())
+    }
+}
+
+/// <p>Container for <code>TagSet</code> elements.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Tagging {
+    /// <p>A collection for a set of tags</p>
+    pub tag_set: Vec<Tag>,
+}
+
+pub struct TaggingSerializer;
+impl TaggingSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Tagging,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        TagSetSerializer::serialize(&mut writer, "TagSet", &obj.tag_set)?;
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct PutObjectTaggingOutput {
+    /// <p>The versionId of the object the tag-set was added to.</p>
+    pub version_id: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct PutObjectTaggingRequest {
+    /// <p>The bucket name containing the object. </p>
+    pub bucket: String,
+    /// <p>The MD5 hash for the request body.</p>
+    pub content_md5: Option<String>,
+    /// <p>Name of the object key.</p>
+    pub key: String,
+    /// <p>Container for the <code>TagSet</code> and <code>Tag</code> elements</p>
+    pub tagging: Tagging,
+    /// <p>The versionId of the object that the tag-set will be added to.</p>
+    pub version_id: Option<String>,
+}
+
+/// Errors returned by PutObjectTagging
+#[derive(Debug, PartialEq)]
+pub enum PutObjectTaggingError {}
+
+impl PutObjectTaggingError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<PutObjectTaggingError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for PutObjectTaggingError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for PutObjectTaggingError {}
+
+pub struct OwnerSerializer;
+impl OwnerSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Owner,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.display_name {
+            write_characters_element(writer, "DisplayName", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.id {
+            write_characters_element(writer, "ID", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Container for the person being granted permissions.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Grantee {
+    /// <p>Screen name of the grantee.</p>
+    pub display_name: Option<String>,
+    /// <p>Email address of the grantee.</p>
+    pub email_address: Option<String>,
+    /// <p>The canonical user ID of the grantee.</p>
+    pub id: Option<String>,
+    /// <p>Type of grantee</p>
+    pub type_: String,
+    /// <p>URI of the grantee group.</p>
+    pub uri: Option<String>,
+}
+
+pub struct GranteeSerializer;
+impl GranteeSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Grantee,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(
+            xml::writer::XmlEvent::start_element(name)
+                .ns("xsi", "http://www.w3.org/2001/XMLSchema-instance")
+                .attr("xsi:type", &obj.type_),
+        )?;
+        if let Some(ref value) = obj.display_name {
+            write_characters_element(writer, "DisplayName", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.email_address {
+            write_characters_element(writer, "EmailAddress", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.id {
+            write_characters_element(writer, "ID", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.uri {
+            write_characters_element(writer, "URI", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Container for grant information.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Grant {
+    /// <p>The person being granted permissions.</p>
+    pub grantee: Option<Grantee>,
+    /// <p>Specifies the permission given to the grantee.</p>
+    pub permission: Option<String>,
+}
+
+pub struct GrantSerializer;
+impl GrantSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Grant,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.grantee {
+            GranteeSerializer::serialize(&mut writer, "Grantee", value)?;
+        }
+        if let Some(ref value) = obj.permission {
+            write_characters_element(writer, "Permission", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+pub struct GrantsSerializer;
+impl GrantsSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Vec<Grant>,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        for element in obj {
+            GrantSerializer::serialize(writer, "Grant", element)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())?;
+        Ok(())
+    }
+}
+
+/// <p>Contains the elements that set the ACL permissions for an object per grantee.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AccessControlPolicy {
+    /// <p>A list of grants.</p>
+    pub grants: Option<Vec<Grant>>,
+    /// <p>Container for the bucket owner's display name and ID.</p>
+    pub owner: Option<Owner>,
+}
+
+pub struct AccessControlPolicySerializer;
+impl AccessControlPolicySerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &AccessControlPolicy,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.grants {
+            GrantsSerializer::serialize(&mut writer, "AccessControlList", value)?;
+        }
+        if let Some(ref value) = obj.owner {
+            OwnerSerializer::serialize(&mut writer, "Owner", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct PutObjectAclOutput {
+    pub request_charged: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct PutObjectAclRequest {
+    /// <p>The canned ACL to apply to the object.</p>
+    pub acl: Option<CannedAcl>,
+    /// <p>Contains the elements that set the ACL permissions for an object per grantee.</p>
+    pub access_control_policy: Option<AccessControlPolicy>,
+    /// <p>The bucket name that contains the object to which you want to attach the ACL. </p>
+    pub bucket: String,
+    /// <p>The base64-encoded 128-bit MD5 digest of the data.</p>
+    pub content_md5: Option<String>,
+    /// <p>Allows grantee the read, write, read ACP, and write ACP permissions on the bucket.</p>
+    pub grant_full_control: Option<String>,
+    /// <p>Allows grantee to list the objects in the bucket.</p>
+    pub grant_read: Option<String>,
+    /// <p>Allows grantee to read the bucket ACL.</p>
+    pub grant_read_acp: Option<String>,
+    /// <p>Allows grantee to create, overwrite, and delete any object in the bucket.</p>
+    pub grant_write: Option<String>,
+    /// <p>Allows grantee to write the ACL for the applicable bucket.</p>
+    pub grant_write_acp: Option<String>,
+    /// <p>Key for which the PUT operation was initiated.</p>
+    pub key: String,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>VersionId used to reference a specific version of the object.</p>
+    pub version_id: Option<String>,
+}
+
+/// Errors returned by PutObjectAcl
+#[derive(Debug, PartialEq)]
+pub enum PutObjectAclError {
+    /// <p>The specified key does not exist.</p>
+    NoSuchKey(String),
+}
+
+impl PutObjectAclError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<PutObjectAclError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "NoSuchKey" {
+                    return Ks3Error::Service(PutObjectAclError::NoSuchKey(parsed_error.message));
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for PutObjectAclError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PutObjectAclError::NoSuchKey(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for PutObjectAclError {}
+
+/// <p>Container for all response elements.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CopyObjectResult {
+    /// <p>Returns the ETag of the new object. The ETag reflects only changes to the contents of an object, not its metadata. The source and destination ETag is identical for a successfully copied object.</p>
+    pub e_tag: Option<String>,
+    /// <p>Returns the date that the object was last modified.</p>
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct CopyObjectResultDeserializer;
+impl CopyObjectResultDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<CopyObjectResult, XmlParseError> {
+        deserialize_elements::<_, CopyObjectResult, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "ETag" => {
+                    obj.e_tag = Some(StringDeserializer::deserialize("ETag", stack)?);
+                }
+                "LastModified" => {
+                    obj.last_modified =
+                        Some(DateTimeDeserializer::deserialize("LastModified", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CopyObjectOutput {
+    /// <p>Container for all response elements.</p>
+    pub copy_object_result: Option<CopyObjectResult>,
+    /// <p>Version of the copied object in the destination bucket.</p>
+    pub copy_source_version_id: Option<String>,
+    /// <p>If the object expiration is configured, the response includes this header.</p>
+    pub expiration: Option<String>,
+    pub request_charged: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header confirming the encryption algorithm used.</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header to provide round-trip message integrity verification of the customer-provided encryption key.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>Version ID of the newly created copy.</p>
+    pub version_id: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct CopyObjectOutputDeserializer;
+impl CopyObjectOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<CopyObjectOutput, XmlParseError> {
+        Ok(CopyObjectOutput {
+            copy_object_result: Some(CopyObjectResultDeserializer::deserialize(
+                "CopyObjectResult",
+                stack,
+            )?),
+            ..CopyObjectOutput::default()
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CopyObjectRequest {
+    /// <p>The canned ACL to apply to the object.</p>
+    pub acl: Option<CannedAcl>,
+    /// <p>The name of the destination bucket.</p>
+    pub bucket: String,
+    /// <p>Specifies caching behavior along the request/reply chain.</p>
+    pub cache_control: Option<String>,
+    /// <p>Specifies presentational information for the object.</p>
+    pub content_disposition: Option<String>,
+    /// <p>Specifies what content encodings have been applied to the object and thus what decoding mechanisms must be applied to obtain the media-type referenced by the Content-Type header field.</p>
+    pub content_encoding: Option<String>,
+    /// <p>The language the content is in.</p>
+    pub content_language: Option<String>,
+    /// <p>A standard MIME type describing the format of the object data.</p>
+    pub content_type: Option<String>,
+    /// <p>The name of the source bucket and key name of the source object, separated by a slash (/), e.g. <code>/bucket/key</code>. Must be URL-encoded.</p>
+    pub copy_source: String,
+    /// <p>Copies the object if its entity tag (ETag) matches the specified tag.</p>
+    pub copy_source_if_match: Option<String>,
+    /// <p>Copies the object if it has been modified since the specified time.</p>
+    pub copy_source_if_modified_since: Option<DateTime<Utc>>,
+    /// <p>Copies the object if its entity tag (ETag) is different than the specified ETag.</p>
+    pub copy_source_if_none_match: Option<String>,
+    /// <p>Copies the object if it hasn't been modified since the specified time.</p>
+    pub copy_source_if_unmodified_since: Option<DateTime<Utc>>,
+    /// <p>Specifies the algorithm to use when decrypting the source object (for example, AES256).</p>
+    pub copy_source_sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use to decrypt the source object. The encryption key provided in this header must be one that was used when the source object was created.</p>
+    pub copy_source_sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321.</p>
+    pub copy_source_sse_customer_key_md5: Option<String>,
+    /// <p>The date and time at which the object is no longer cacheable.</p>
+    pub expires: Option<DateTime<Utc>>,
+    /// <p>Gives the grantee READ, READ_ACP, and WRITE_ACP permissions on the object.</p>
+    pub grant_full_control: Option<String>,
+    /// <p>Allows grantee to read the object data and its metadata.</p>
+    pub grant_read: Option<String>,
+    /// <p>Allows grantee to read the object ACL.</p>
+    pub grant_read_acp: Option<String>,
+    /// <p>Allows grantee to write the ACL for the applicable object.</p>
+    pub grant_write_acp: Option<String>,
+    /// <p>The key of the destination object.</p>
+    pub key: String,
+    /// <p>A map of metadata to store with the object in S3.</p>
+    pub metadata: Option<Metadata>,
+    /// <p>Specifies whether the metadata is copied from the source object or replaced with metadata provided in the request.</p>
+    pub metadata_directive: Option<String>,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>Specifies the algorithm to use to when encrypting the object (for example, AES256).</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use in encrypting data.</p>
+    pub sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>Specifies the AWS KMS key ID to use for object encryption.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>The type of storage to use for the object. Defaults to 'STANDARD'.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>The tag-set for the object destination object this value must be used in conjunction with the <code>TaggingDirective</code>. The tag-set must be encoded as URL Query parameters.</p>
+    pub tagging: Option<String>,
+    /// <p>Specifies whether the object tag-set are copied from the source object or replaced with tag-set provided in the request.</p>
+    pub tagging_directive: Option<String>,
+    /// <p>If the bucket is configured as a website, redirects requests for this object to another object in the same bucket or to an external URL.</p>
+    pub website_redirect_location: Option<String>,
+}
+
+/// Errors returned by CopyObject
+#[derive(Debug, PartialEq)]
+pub enum CopyObjectError {
+    /// <p>The source object of the COPY operation is not in the active tier and is only stored in Amazon S3 Glacier.</p>
+    ObjectNotInActiveTierError(String),
+}
+
+impl CopyObjectError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<CopyObjectError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "ObjectNotInActiveTierError" {
+                    return Ks3Error::Service(CopyObjectError::ObjectNotInActiveTierError(
+                        parsed_error.message,
+                    ));
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for CopyObjectError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CopyObjectError::ObjectNotInActiveTierError(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for CopyObjectError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct DeleteBucketRequest {
+    /// <p>Specifies the bucket being deleted.</p>
+    pub bucket: String,
+}
+
+/// Errors returned by DeleteBucket
+#[derive(Debug, PartialEq)]
+pub enum DeleteBucketError {}
+
+impl DeleteBucketError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<DeleteBucketError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for DeleteBucketError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for DeleteBucketError {}
+
+/// <p> In terms of implementation, a Bucket is a resource. An Amazon S3 bucket name is globally unique, and the namespace is shared by all AWS accounts. </p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Bucket {
+    /// <p>Date the bucket was created.</p>
+    pub creation_date: Option<DateTime<Utc>>,
+    /// <p>The name of the bucket.</p>
+    pub name: Option<String>,
+}
+
+#[allow(dead_code)]
+struct BucketDeserializer;
+impl BucketDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(tag_name: &str, stack: &mut T) -> Result<Bucket, XmlParseError> {
+        deserialize_elements::<_, Bucket, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "CreationDate" => {
+                    obj.creation_date =
+                        Some(DateTimeDeserializer::deserialize("CreationDate", stack)?);
+                }
+                "Name" => {
+                    obj.name = Some(StringDeserializer::deserialize("Name", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+struct BucketsDeserializer;
+impl BucketsDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<Bucket>, XmlParseError> {
+        deserialize_elements::<_, Vec<_>, _>(tag_name, stack, |name, stack, obj| {
+            if name == "Bucket" {
+                obj.push(BucketDeserializer::deserialize("Bucket", stack)?);
+            } else {
+                skip_tree(stack);
+            }
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListBucketsOutput {
+    /// <p>The list of buckets owned by the requestor.</p>
+    pub buckets: Option<Vec<Bucket>>,
+    /// <p>The owner of the buckets listed.</p>
+    pub owner: Option<Owner>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct ListBucketsOutputDeserializer;
+impl ListBucketsOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<ListBucketsOutput, XmlParseError> {
+        deserialize_elements::<_, ListBucketsOutput, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Buckets" => {
+                    obj.buckets
+                        .get_or_insert(vec![])
+                        .extend(BucketsDeserializer::deserialize("Buckets", stack)?);
+                }
+                "Owner" => {
+                    obj.owner = Some(OwnerDeserializer::deserialize("Owner", stack)?);
+                }
+                _ => retain_tree(name, stack, &mut obj.extra),
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Errors returned by ListBuckets
+#[derive(Debug, PartialEq)]
+pub enum ListBucketsError {}
+
+impl ListBucketsError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<ListBucketsError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for ListBucketsError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for ListBucketsError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketLocationOutput {
+    /// <p>Specifies the Region where the bucket resides. Buckets in the default region of the endpoint have a null <code>LocationConstraint</code>.</p>
+    pub location_constraint: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct GetBucketLocationOutputDeserializer;
+impl GetBucketLocationOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<GetBucketLocationOutput, XmlParseError> {
+        let location = StringDeserializer::deserialize(tag_name, stack)?;
+        Ok(GetBucketLocationOutput {
+            location_constraint: Some(location).filter(|location| !location.is_empty()),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketLocationRequest {
+    /// <p>The name of the bucket for which to get the location.</p>
+    pub bucket: String,
+}
+
+/// Errors returned by GetBucketLocation
+#[derive(Debug, PartialEq)]
+pub enum GetBucketLocationError {}
+
+impl GetBucketLocationError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<GetBucketLocationError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for GetBucketLocationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for GetBucketLocationError {}
+
+/// <p>A version of an object.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ObjectVersion {
+    /// <p>The entity tag is an MD5 hash of that version of the object.</p>
+    pub e_tag: Option<String>,
+    /// <p>Specifies whether the object is (true) or is not (false) the latest version of an object.</p>
+    pub is_latest: Option<bool>,
+    /// <p>The object key.</p>
+    pub key: Option<String>,
+    /// <p>Date and time the object was last modified.</p>
+    pub last_modified: Option<DateTime<Utc>>,
+    /// <p>Specifies the owner of the object.</p>
+    pub owner: Option<Owner>,
+    /// <p>Size in bytes of the object.</p>
+    pub size: Option<i64>,
+    /// <p>The class of storage used to store the object.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>Version ID of an object.</p>
+    pub version_id: Option<String>,
+}
+
+#[allow(dead_code)]
+struct ObjectVersionDeserializer;
+impl ObjectVersionDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<ObjectVersion, XmlParseError> {
+        deserialize_elements::<_, ObjectVersion, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "ETag" => {
+                    obj.e_tag = Some(StringDeserializer::deserialize("ETag", stack)?);
+                }
+                "IsLatest" => {
+                    obj.is_latest = Some(BooleanDeserializer::deserialize("IsLatest", stack)?);
+                }
+                "Key" => {
+                    obj.key = Some(StringDeserializer::deserialize("Key", stack)?);
+                }
+                "LastModified" => {
+                    obj.last_modified =
+                        Some(DateTimeDeserializer::deserialize("LastModified", stack)?);
+                }
+                "Owner" => {
+                    obj.owner = Some(OwnerDeserializer::deserialize("Owner", stack)?);
+                }
+                "Size" => {
+                    obj.size = Some(LongDeserializer::deserialize("Size", stack)?);
+                }
+                "StorageClass" => {
+                    obj.storage_class =
+                        Some(StringDeserializer::deserialize("StorageClass", stack)?.into());
+                }
+                "VersionId" => {
+                    obj.version_id = Some(StringDeserializer::deserialize("VersionId", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+/// <p>Information about the delete marker.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct DeleteMarkerEntry {
+    /// <p>Specifies whether the object is (true) or is not (false) the latest version of an object.</p>
+    pub is_latest: Option<bool>,
+    /// <p>The object key.</p>
+    pub key: Option<String>,
+    /// <p>Date and time the object was last modified.</p>
+    pub last_modified: Option<DateTime<Utc>>,
+    /// <p>The account that created the delete marker.</p>
+    pub owner: Option<Owner>,
+    /// <p>Version ID of an object.</p>
+    pub version_id: Option<String>,
+}
+
+#[allow(dead_code)]
+struct DeleteMarkerEntryDeserializer;
+impl DeleteMarkerEntryDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<DeleteMarkerEntry, XmlParseError> {
+        deserialize_elements::<_, DeleteMarkerEntry, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "IsLatest" => {
+                    obj.is_latest = Some(BooleanDeserializer::deserialize("IsLatest", stack)?);
+                }
+                "Key" => {
+                    obj.key = Some(StringDeserializer::deserialize("Key", stack)?);
+                }
+                "LastModified" => {
+                    obj.last_modified =
+                        Some(DateTimeDeserializer::deserialize("LastModified", stack)?);
+                }
+                "Owner" => {
+                    obj.owner = Some(OwnerDeserializer::deserialize("Owner", stack)?);
+                }
+                "VersionId" => {
+                    obj.version_id = Some(StringDeserializer::deserialize("VersionId", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListObjectVersionsOutput {
+    /// <p>All of the keys rolled up into a common prefix count as a single return when calculating the number of returns.</p>
+    pub common_prefixes: Option<Vec<CommonPrefix>>,
+    /// <p>Container for an object that is a delete marker.</p>
+    pub delete_markers: Option<Vec<DeleteMarkerEntry>>,
+    /// <p>The delimiter grouping the included keys.</p>
+    pub delimiter: Option<String>,
+    /// <p>Encoding type used by Amazon S3 to encode object key names in the response.</p>
+    pub encoding_type: Option<String>,
+    /// <p>A flag that indicates whether Amazon S3 returned all of the results that satisfied the search criteria. If your results were truncated, you can make a follow-up paginated request using the NextKeyMarker and NextVersionIdMarker response parameters as a starting place in another request to return the rest of the results.</p>
+    pub is_truncated: Option<bool>,
+    /// <p>Marks the last key returned in a truncated response.</p>
+    pub key_marker: Option<String>,
+    /// <p>Specifies the maximum number of objects to return.</p>
+    pub max_keys: Option<i64>,
+    /// <p>The bucket name.</p>
+    pub name: Option<String>,
+    /// <p>When the number of responses exceeds the value of <code>MaxKeys</code>, <code>NextKeyMarker</code> specifies the first key not returned that satisfies the search criteria. Use this value for the key-marker request parameter in a subsequent request.</p>
+    pub next_key_marker: Option<String>,
+    /// <p>When the number of responses exceeds the value of <code>MaxKeys</code>, <code>NextVersionIdMarker</code> specifies the first object version not returned that satisfies the search criteria. Use this value for the version-id-marker request parameter in a subsequent request.</p>
+    pub next_version_id_marker: Option<String>,
+    /// <p>Selects objects that start with the value supplied by this parameter.</p>
+    pub prefix: Option<String>,
+    /// <p>Marks the last version of the key returned in a truncated response.</p>
+    pub version_id_marker: Option<String>,
+    /// <p>Container for version information.</p>
+    pub versions: Option<Vec<ObjectVersion>>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct ListObjectVersionsOutputDeserializer;
+impl ListObjectVersionsOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<ListObjectVersionsOutput, XmlParseError> {
+        deserialize_elements::<_, ListObjectVersionsOutput, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "CommonPrefixes" => {
+                        obj.common_prefixes.get_or_insert(vec![]).extend(
+                            CommonPrefixListDeserializer::deserialize("CommonPrefixes", stack)?,
+                        );
+                    }
+                    // Versions and delete markers are interleaved in key order.
+                    "DeleteMarker" => {
+                        obj.delete_markers.get_or_insert(vec![]).push(
+                            DeleteMarkerEntryDeserializer::deserialize("DeleteMarker", stack)?,
+                        );
+                    }
+                    "Delimiter" => {
+                        obj.delimiter = Some(StringDeserializer::deserialize("Delimiter", stack)?);
+                    }
+                    "EncodingType" => {
+                        obj.encoding_type =
+                            Some(StringDeserializer::deserialize("EncodingType", stack)?);
+                    }
+                    "IsTruncated" => {
+                        obj.is_truncated =
+                            Some(BooleanDeserializer::deserialize("IsTruncated", stack)?);
+                    }
+                    "KeyMarker" => {
+                        obj.key_marker = Some(StringDeserializer::deserialize("KeyMarker", stack)?);
+                    }
+                    "MaxKeys" => {
+                        obj.max_keys = Some(LongDeserializer::deserialize("MaxKeys", stack)?);
+                    }
+                    "Name" => {
+                        obj.name = Some(StringDeserializer::deserialize("Name", stack)?);
+                    }
+                    "NextKeyMarker" => {
+                        obj.next_key_marker =
+                            Some(StringDeserializer::deserialize("NextKeyMarker", stack)?);
+                    }
+                    "NextVersionIdMarker" => {
+                        obj.next_version_id_marker = Some(StringDeserializer::deserialize(
+                            "NextVersionIdMarker",
+                            stack,
+                        )?);
+                    }
+                    "Prefix" => {
+                        obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                    }
+                    "Version" => {
+                        obj.versions
+                            .get_or_insert(vec![])
+                            .push(ObjectVersionDeserializer::deserialize("Version", stack)?);
+                    }
+                    "VersionIdMarker" => {
+                        obj.version_id_marker =
+                            Some(StringDeserializer::deserialize("VersionIdMarker", stack)?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListObjectVersionsRequest {
+    /// <p>The bucket name that contains the objects. </p>
+    pub bucket: String,
+    /// <p>A delimiter is a character that you specify to group keys. All keys that contain the same string between the <code>prefix</code> and the first occurrence of the delimiter are grouped under a single result element in CommonPrefixes.</p>
+    pub delimiter: Option<String>,
+    pub encoding_type: Option<String>,
+    /// <p>Specifies the key to start with when listing objects in a bucket.</p>
+    pub key_marker: Option<String>,
+    /// <p>Sets the maximum number of keys returned in the response. By default the API returns up to 1,000 key names.</p>
+    pub max_keys: Option<i64>,
+    /// <p>Use this parameter to select only those keys that begin with the specified prefix.</p>
+    pub prefix: Option<String>,
+    /// <p>Specifies the object version you want to start listing from.</p>
+    pub version_id_marker: Option<String>,
+}
+
+/// Errors returned by ListObjectVersions
+#[derive(Debug, PartialEq)]
+pub enum ListObjectVersionsError {}
+
+impl ListObjectVersionsError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<ListObjectVersionsError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for ListObjectVersionsError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for ListObjectVersionsError {}
+
+/// <p>Describes how an uncompressed comma-separated values (CSV)-formatted input object is formatted.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CSVInput {
+    /// <p>Specifies that CSV field values may contain quoted record delimiters and such records should be allowed. Default value is FALSE. Setting this value to TRUE may lower performance.</p>
+    pub allow_quoted_record_delimiter: Option<bool>,
+    /// <p>A single character used to indicate that a row should be ignored when the character is present at the start of that row. You can specify any character to indicate a comment line.</p>
+    pub comments: Option<String>,
+    /// <p>A single character used to separate individual fields in a record. You can specify an arbitrary delimiter.</p>
+    pub field_delimiter: Option<String>,
+    /// <p>Describes the first line of input. Valid values are:</p> <ul> <li> <p> <code>NONE</code>: First line is not a header.</p> </li> <li> <p> <code>IGNORE</code>: First line is a header, but you can't use the header values to indicate the column in an expression. You can use column position (such as _1, _2, …) to indicate the column (<code>SELECT s._1 FROM OBJECT s</code>).</p> </li> <li> <p> <code>Use</code>: First line is a header, and you can use the header value to identify a column in an expression (<code>SELECT "name" FROM OBJECT</code>). </p> </li> </ul>
+    pub file_header_info: Option<String>,
+    /// <p>A single character used for escaping when the field delimiter is part of the value. For example, if the value is <code>a, b</code>, Amazon S3 wraps this field value in quotation marks, as follows: <code>" a , b "</code>.</p>
+    pub quote_character: Option<String>,
+    /// <p>A single character used for escaping the quotation mark character inside an already escaped value. For example, the value """ a , b """ is parsed as " a , b ".</p>
+    pub quote_escape_character: Option<String>,
+    /// <p>A single character used to separate individual records in the input. Instead of the default value, you can specify an arbitrary delimiter.</p>
+    pub record_delimiter: Option<String>,
+}
+
+pub struct CSVInputSerializer;
+impl CSVInputSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &CSVInput,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.allow_quoted_record_delimiter {
+            write_characters_element(writer, "AllowQuotedRecordDelimiter", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.comments {
+            write_characters_element(writer, "Comments", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.field_delimiter {
+            write_characters_element(writer, "FieldDelimiter", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.file_header_info {
+            write_characters_element(writer, "FileHeaderInfo", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.quote_character {
+            write_characters_element(writer, "QuoteCharacter", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.quote_escape_character {
+            write_characters_element(writer, "QuoteEscapeCharacter", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.record_delimiter {
+            write_characters_element(writer, "RecordDelimiter", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Specifies JSON as object's input serialization format.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct JSONInput {
+    /// <p>The type of JSON. Valid values: Document, Lines.</p>
+    pub type_: Option<String>,
+}
+
+pub struct JSONInputSerializer;
+impl JSONInputSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &JSONInput,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.type_ {
+            write_characters_element(writer, "Type", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Container for Parquet.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ParquetInput {}
+
+pub struct ParquetInputSerializer;
+impl ParquetInputSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &ParquetInput,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Describes the serialization format of the object.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct InputSerialization {
+    /// <p>Describes the serialization of a CSV-encoded object.</p>
+    pub csv: Option<CSVInput>,
+    /// <p>Specifies object's compression format. Valid values: NONE, GZIP, BZIP2. Default Value: NONE.</p>
+    pub compression_type: Option<String>,
+    /// <p>Specifies JSON as object's input serialization format.</p>
+    pub json: Option<JSONInput>,
+    /// <p>Specifies Parquet as object's input serialization format.</p>
+    pub parquet: Option<ParquetInput>,
+}
+
+pub struct InputSerializationSerializer;
+impl InputSerializationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &InputSerialization,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.csv {
+            CSVInputSerializer::serialize(writer, "CSV", value)?;
+        }
+        if let Some(ref value) = obj.compression_type {
+            write_characters_element(writer, "CompressionType", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.json {
+            JSONInputSerializer::serialize(writer, "JSON", value)?;
+        }
+        if let Some(ref value) = obj.parquet {
+            ParquetInputSerializer::serialize(writer, "Parquet", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Describes how uncompressed comma-separated values (CSV)-formatted results are formatted.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CSVOutput {
+    /// <p>The value used to separate individual fields in a record. You can specify an arbitrary delimiter.</p>
+    pub field_delimiter: Option<String>,
+    /// <p>A single character used for escaping when the field delimiter is part of the value. For example, if the value is <code>a, b</code>, Amazon S3 wraps this field value in quotation marks, as follows: <code>" a , b "</code>.</p>
+    pub quote_character: Option<String>,
+    /// <p>The single character used for escaping the quote character inside an already escaped value.</p>
+    pub quote_escape_character: Option<String>,
+    /// <p>Indicates whether to use quotation marks around output fields. </p> <ul> <li> <p> <code>ALWAYS</code>: Always use quotation marks for output fields.</p> </li> <li> <p> <code>ASNEEDED</code>: Use quotation marks for output fields when needed.</p> </li> </ul>
+    pub quote_fields: Option<String>,
+    /// <p>A single character used to separate individual records in the output. Instead of the default value, you can specify an arbitrary delimiter.</p>
+    pub record_delimiter: Option<String>,
+}
+
+pub struct CSVOutputSerializer;
+impl CSVOutputSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &CSVOutput,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.field_delimiter {
+            write_characters_element(writer, "FieldDelimiter", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.quote_character {
+            write_characters_element(writer, "QuoteCharacter", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.quote_escape_character {
+            write_characters_element(writer, "QuoteEscapeCharacter", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.quote_fields {
+            write_characters_element(writer, "QuoteFields", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.record_delimiter {
+            write_characters_element(writer, "RecordDelimiter", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Specifies JSON as request's output serialization format.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct JSONOutput {
+    /// <p>The value used to separate individual records in the output. If no value is specified, Amazon S3 uses a newline character ('\n').</p>
+    pub record_delimiter: Option<String>,
+}
+
+pub struct JSONOutputSerializer;
+impl JSONOutputSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &JSONOutput,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.record_delimiter {
+            write_characters_element(writer, "RecordDelimiter", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Describes how results of the Select job are serialized.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct OutputSerialization {
+    /// <p>Describes the serialization of CSV-encoded Select results.</p>
+    pub csv: Option<CSVOutput>,
+    /// <p>Specifies JSON as request's output serialization format.</p>
+    pub json: Option<JSONOutput>,
+}
+
+pub struct OutputSerializationSerializer;
+impl OutputSerializationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &OutputSerialization,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.csv {
+            CSVOutputSerializer::serialize(writer, "CSV", value)?;
+        }
+        if let Some(ref value) = obj.json {
+            JSONOutputSerializer::serialize(writer, "JSON", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Container for specifying if periodic <code>QueryProgress</code> messages should be sent.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct RequestProgress {
+    /// <p>Specifies whether periodic QueryProgress frames should be sent. Valid values: TRUE, FALSE. Default value: FALSE.</p>
+    pub enabled: Option<bool>,
+}
+
+pub struct RequestProgressSerializer;
+impl RequestProgressSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &RequestProgress,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.enabled {
+            write_characters_element(writer, "Enabled", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Specifies the byte range of the object to get the records from. A record is processed when its first byte is contained by the range. This parameter is optional, but when specified, it must not be empty.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ScanRange {
+    /// <p>Specifies the end of the byte range. This parameter is optional. Valid values: non-negative integers. The default value is one less than the size of the object being queried. If only the End parameter is supplied, it is interpreted to mean scan the last N bytes of the file.</p>
+    pub end: Option<i64>,
+    /// <p>Specifies the start of the byte range. This parameter is optional. Valid values: non-negative integers. The default value is 0. If only start is supplied, it means scan from that point to the end of the file.</p>
+    pub start: Option<i64>,
+}
+
+pub struct ScanRangeSerializer;
+impl ScanRangeSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &ScanRange,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.end {
+            write_characters_element(writer, "End", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.start {
+            write_characters_element(writer, "Start", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Request to filter the contents of an Amazon S3 object based on a simple Structured Query Language (SQL) statement. In the request, along with the SQL expression, you must specify a data serialization format (JSON or CSV) of the object. Amazon S3 uses this to parse object data into records. It returns only records that match the specified SQL expression. You must also specify the data serialization format for the response.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct SelectObjectContentRequest {
+    /// <p>The S3 bucket.</p>
+    pub bucket: String,
+    /// <p>The expression that is used to query the object.</p>
+    pub expression: String,
+    /// <p>The type of the provided expression (for example, SQL).</p>
+    pub expression_type: String,
+    /// <p>Describes the format of the data in the object that is being queried.</p>
+    pub input_serialization: InputSerialization,
+    /// <p>The object key.</p>
+    pub key: String,
+    /// <p>Describes the format of the data that you want Amazon S3 to return in response.</p>
+    pub output_serialization: OutputSerialization,
+    /// <p>Specifies if periodic request progress information should be enabled.</p>
+    pub request_progress: Option<RequestProgress>,
+    /// <p>The SSE Algorithm used to encrypt the object.</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>The SSE Customer Key.</p>
+    pub sse_customer_key: Option<String>,
+    /// <p>The SSE Customer Key MD5.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>Specifies the byte range of the object to get the records from. A record is processed when its first byte is contained by the range. This parameter is optional, but when specified, it must not be empty.</p>
+    pub scan_range: Option<ScanRange>,
+}
+
+pub struct SelectObjectContentRequestSerializer;
+impl SelectObjectContentRequestSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &SelectObjectContentRequest,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        write_characters_element(writer, "Expression", &obj.expression.to_string())?;
+        write_characters_element(writer, "ExpressionType", &obj.expression_type.to_string())?;
+        InputSerializationSerializer::serialize(
+            &mut writer,
+            "InputSerialization",
+            &obj.input_serialization,
+        )?;
+        OutputSerializationSerializer::serialize(
+            &mut writer,
+            "OutputSerialization",
+            &obj.output_serialization,
+        )?;
+        if let Some(ref value) = obj.request_progress {
+            RequestProgressSerializer::serialize(&mut writer, "RequestProgress", value)?;
+        }
+        if let Some(ref value) = obj.scan_range {
+            ScanRangeSerializer::serialize(&mut writer, "ScanRange", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Container for the stats details.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Stats {
+    /// <p>The total number of uncompressed object bytes processed.</p>
+    pub bytes_processed: Option<i64>,
+    /// <p>The total number of bytes of records payload data returned.</p>
+    pub bytes_returned: Option<i64>,
+    /// <p>The total number of object bytes scanned.</p>
+    pub bytes_scanned: Option<i64>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct StatsDeserializer;
+impl StatsDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Stats, XmlParseError> {
+        deserialize_elements::<_, Stats, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "BytesProcessed" => {
+                    obj.bytes_processed =
+                        Some(LongDeserializer::deserialize("BytesProcessed", stack)?);
+                }
+                "BytesReturned" => {
+                    obj.bytes_returned =
+                        Some(LongDeserializer::deserialize("BytesReturned", stack)?);
+                }
+                "BytesScanned" => {
+                    obj.bytes_scanned = Some(LongDeserializer::deserialize("BytesScanned", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Parse the payload of a `Stats` or `Progress` event, whose root element is `tag_name`.
+pub(crate) fn parse_stats(tag_name: &str, payload: &[u8]) -> Result<Stats, XmlParseError> {
+    let reader = EventReader::new(payload);
+    let mut stack = XmlResponse::new(reader.into_iter().peekable());
+    find_start_element(&mut stack);
+    StatsDeserializer::deserialize(tag_name, &mut stack)
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct SelectObjectContentOutput {
+    /// <p>The events of the response, decoded as they are received.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub payload: Option<SelectObjectContentEventStream>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+/// Errors returned by SelectObjectContent
+#[derive(Debug, PartialEq)]
+pub enum SelectObjectContentError {}
+
+impl SelectObjectContentError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<SelectObjectContentError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for SelectObjectContentError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for SelectObjectContentError {}
+
+#[allow(dead_code)]
+pub(crate) struct TagDeserializer;
+impl TagDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Tag, XmlParseError> {
+        deserialize_elements::<_, Tag, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Key" => {
+                    obj.key = StringDeserializer::deserialize("Key", stack)?;
+                }
+                "Value" => {
+                    obj.value = StringDeserializer::deserialize("Value", stack)?;
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct TagListDeserializer;
+impl TagListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<Tag>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(TagDeserializer::deserialize(tag_name, stack)?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+pub struct TagListSerializer;
+impl TagListSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Vec<Tag>,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        for element in obj {
+            TagSerializer::serialize(writer, name, element)?;
+        }
+        Ok(())
+    }
+}
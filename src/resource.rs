@@ -0,0 +1,124 @@
+//! Resource-oriented handles over the low-level `S3` operations.
+//!
+//! ```rust,ignore
+//! let client = S3Client::new(Region::default());
+//! let bucket = client.bucket("photos");
+//! bucket.object("a.jpg").put(stream).await?;
+//! ```
+//!
+//! The handles only carry names and a cheap clone of the client; every call is translated into
+//! the matching request struct and sent through the `S3` trait.
+
+use crate::core::error::Ks3Error;
+use crate::exists::ExistsError;
+use crate::request::*;
+use crate::s3::{S3Client, S3};
+
+/// A handle to a single bucket.
+#[derive(Clone)]
+pub struct BucketHandle {
+    client: S3Client,
+    name: String,
+}
+
+impl BucketHandle {
+    /// Create a handle to the bucket `name` backed by `client`.
+    pub fn new<N: Into<String>>(client: S3Client, name: N) -> BucketHandle {
+        BucketHandle {
+            client,
+            name: name.into(),
+        }
+    }
+
+    /// The bucket name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The client used by this handle.
+    pub fn client(&self) -> &S3Client {
+        &self.client
+    }
+
+    /// Return a handle to the object `key` in this bucket.
+    pub fn object<K: Into<String>>(&self, key: K) -> ObjectHandle {
+        ObjectHandle {
+            client: self.client.clone(),
+            bucket: self.name.clone(),
+            key: key.into(),
+        }
+    }
+
+    /// Return whether the bucket exists.
+    pub async fn exists(&self) -> Result<bool, Ks3Error<ExistsError>> {
+        self.client.bucket_exists(&self.name).await
+    }
+
+    /// Create the bucket with default settings.
+    pub async fn create(&self) -> Result<CreateBucketOutput, Ks3Error<CreateBucketError>> {
+        self.create_with(CreateBucketRequest::default()).await
+    }
+
+    /// Create the bucket using `input` for everything but the bucket name.
+    pub async fn create_with(
+        &self,
+        mut input: CreateBucketRequest,
+    ) -> Result<CreateBucketOutput, Ks3Error<CreateBucketError>> {
+        input.bucket = self.name.clone();
+        self.client.create_bucket(input).await
+    }
+}
+
+/// A handle to a single object key inside a bucket.
+#[derive(Clone)]
+pub struct ObjectHandle {
+    client: S3Client,
+    bucket: String,
+    key: String,
+}
+
+impl ObjectHandle {
+    /// The name of the bucket containing this object.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// The object key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Return whether the object exists.
+    pub async fn exists(&self) -> Result<bool, Ks3Error<ExistsError>> {
+        self.client.object_exists(&self.bucket, &self.key).await
+    }
+
+    /// Upload `body` as the object's content.
+    pub async fn put<B: Into<StreamingBody>>(
+        &self,
+        body: B,
+    ) -> Result<PutObjectOutput, Ks3Error<PutObjectError>> {
+        self.put_with(PutObjectRequest {
+            body: Some(body.into()),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Upload the object using `input` for everything but the bucket and key.
+    pub async fn put_with(
+        &self,
+        mut input: PutObjectRequest,
+    ) -> Result<PutObjectOutput, Ks3Error<PutObjectError>> {
+        input.bucket = self.bucket.clone();
+        input.key = self.key.clone();
+        self.client.put_object(input).await
+    }
+}
+
+impl S3Client {
+    /// Return a handle to the bucket `name`.
+    pub fn bucket<N: Into<String>>(&self, name: N) -> BucketHandle {
+        BucketHandle::new(self.clone(), name)
+    }
+}
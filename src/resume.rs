@@ -0,0 +1,226 @@
+//! Resuming `get_object` bodies whose connection fails partway.
+//!
+//! With `Ks3Config::resume_downloads` set, the body returned by `get_object` picks up where it
+//! stopped when reading it fails: it requests the remaining bytes with a `Range` header, pinned
+//! to the ETag (and version) of the first response with `If-Match` so that bytes of a replaced
+//! object are never spliced in, and carries on with the new response. Readers only see an error
+//! once the resumes in a row run out, or if the object changed in the meantime.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures::channel::oneshot;
+use futures::{Future, Stream};
+use tracing::debug;
+
+use crate::config::Ks3Config;
+use crate::core::error::Ks3Error;
+use crate::core::runtime;
+use crate::range::Range;
+use crate::request::*;
+use crate::s3::{S3Client, S3};
+
+type Resumed = Result<GetObjectOutput, Ks3Error<GetObjectError>>;
+
+/// Wrap the body of the `get_object` response `output` to `input`, so that it resumes up to
+/// `max_resumes` times in a row. Bodies of responses without an ETag are left as they are.
+pub(crate) fn resuming_body(
+    client: &S3Client,
+    input: &GetObjectRequest,
+    output: &GetObjectOutput,
+    body: StreamingBody,
+    max_resumes: u32,
+) -> StreamingBody {
+    let e_tag = match output.e_tag {
+        Some(ref e_tag) if max_resumes > 0 => e_tag.clone(),
+        _ => return body,
+    };
+    let (start, end) = match output
+        .content_range
+        .as_deref()
+        .and_then(parse_content_range)
+    {
+        Some((first, last)) => (first, Some(last)),
+        None => (
+            0,
+            output
+                .content_length
+                .filter(|len| *len > 0)
+                .map(|len| len as u64 - 1),
+        ),
+    };
+
+    // Resumed requests must not resume themselves.
+    let mut client = client.clone();
+    client.set_config(Ks3Config {
+        resume_downloads: None,
+        ..client.config().clone()
+    });
+    let input = GetObjectRequest {
+        if_match: Some(e_tag),
+        if_none_match: None,
+        if_modified_since: None,
+        if_unmodified_since: None,
+        // The range of the part is requested instead.
+        part_number: None,
+        version_id: output
+            .version_id
+            .clone()
+            .or_else(|| input.version_id.clone()),
+        ..input.clone()
+    };
+    let size_hint = body.size_hint();
+    let body = ResumingBody {
+        client,
+        input,
+        start,
+        end,
+        received: 0,
+        resumes: 0,
+        max_resumes,
+        state: State::Reading(body),
+    };
+    match size_hint {
+        Some(size) => StreamingBody::new_with_size(body, size),
+        None => StreamingBody::new(body),
+    }
+}
+
+/// Parse the first and last offsets out of a `Content-Range` header, e.g. `bytes 0-99/1000`.
+fn parse_content_range(value: &str) -> Option<(u64, u64)> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let range = range.split('/').next()?;
+    let mut bounds = range.splitn(2, '-');
+    let first = bounds.next()?.trim().parse().ok()?;
+    let last = bounds.next()?.trim().parse().ok()?;
+    Some((first, last))
+}
+
+enum State {
+    Reading(StreamingBody),
+    Resuming(oneshot::Receiver<Resumed>),
+    Done,
+}
+
+struct ResumingBody {
+    client: S3Client,
+    input: GetObjectRequest,
+    /// The offset of the first byte of the body in the object.
+    start: u64,
+    /// The offset of the last byte of the body in the object, if known.
+    end: Option<u64>,
+    received: u64,
+    /// The resumes since bytes were last received.
+    resumes: u32,
+    max_resumes: u32,
+    state: State,
+}
+
+impl ResumingBody {
+    /// Request the rest of the body after reading it failed with `err`, or give up with `err`.
+    fn resume(&mut self, err: io::Error) -> Result<(), io::Error> {
+        if self.resumes >= self.max_resumes {
+            return Err(err);
+        }
+        self.resumes += 1;
+        let offset = self.start + self.received;
+        let range = match self.end {
+            Some(end) => Range::Bytes {
+                first: offset,
+                last: end,
+            },
+            None => Range::FromOffset(offset),
+        };
+        debug!(
+            bucket = %self.input.bucket,
+            key = %self.input.key,
+            offset,
+            attempt = self.resumes,
+            "resuming download after error: {}",
+            err
+        );
+        let client = self.client.clone();
+        let input = GetObjectRequest {
+            range: Some(range),
+            ..self.input.clone()
+        };
+        self.state = State::Resuming(runtime::spawn(
+            async move { client.get_object(input).await },
+        ));
+        Ok(())
+    }
+
+    fn is_complete(&self) -> bool {
+        self.end.is_some_and(|end| self.start + self.received > end)
+    }
+}
+
+impl Stream for ResumingBody {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let this = &mut *self;
+            match this.state {
+                State::Reading(ref mut body) => match Pin::new(body).poll_next(cx) {
+                    Poll::Ready(Some(Ok(chunk))) => {
+                        this.received += chunk.len() as u64;
+                        this.resumes = 0;
+                        return Poll::Ready(Some(Ok(chunk)));
+                    }
+                    Poll::Ready(Some(Err(err))) => {
+                        if this.is_complete() {
+                            this.state = State::Done;
+                            return Poll::Ready(None);
+                        }
+                        if let Err(err) = this.resume(err) {
+                            this.state = State::Done;
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                    }
+                    Poll::Ready(None) => {
+                        this.state = State::Done;
+                        return Poll::Ready(None);
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+                State::Resuming(ref mut handle) => {
+                    let resumed = match futures::ready!(Pin::new(handle).poll(cx)) {
+                        Ok(resumed) => resumed,
+                        Err(err) => {
+                            this.state = State::Done;
+                            return Poll::Ready(Some(Err(io::Error::other(err))));
+                        }
+                    };
+                    match resumed {
+                        Ok(output) => {
+                            this.state = State::Reading(
+                                output
+                                    .body
+                                    .unwrap_or_else(|| StreamingBody::from(Bytes::new())),
+                            )
+                        }
+                        // The connection failed again: try once more if allowed.
+                        Err(Ks3Error::HttpDispatch(err)) => {
+                            let err = io::Error::other(err.to_string());
+                            if let Err(err) = this.resume(err) {
+                                this.state = State::Done;
+                                return Poll::Ready(Some(Err(err)));
+                            }
+                        }
+                        Err(err) => {
+                            this.state = State::Done;
+                            return Poll::Ready(Some(Err(io::Error::other(format!(
+                                "resuming the download failed: {}",
+                                err
+                            )))));
+                        }
+                    }
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! A retry budget shared by the operations of a group, e.g. all the deletions of a batch and
+//! the transfers of a sync.
+//!
+//! Retrying each operation a few times is cheap while failures are rare, but when an endpoint
+//! is degraded every operation fails, and the retries multiply the load on it. A `RetryBudget`
+//! caps the retries of the whole group instead: once it is spent, failing operations are
+//! reported as failed on their first attempt.
+//!
+//! ```rust,ignore
+//! let budget = RetryBudget::new(50);
+//! let options = BatchOptions {
+//!     retry_budget: Some(budget.clone()),
+//!     ..Default::default()
+//! };
+//! let report = batch_apply(&client, "logs", "2020/", &options, |object| delete(object)).await?;
+//! println!("{} retries left", budget.remaining());
+//! ```
+//!
+//! Operations retried by code of your own can draw from the same budget with `try_acquire`.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::core::clock::Clock;
+use crate::metrics::MetricsObserver;
+
+/// A number of retries shared between the clones of the budget.
+#[derive(Clone, Debug)]
+pub struct RetryBudget {
+    limit: u32,
+    spent: Arc<AtomicU32>,
+}
+
+impl RetryBudget {
+    /// A budget of `limit` retries.
+    pub fn new(limit: u32) -> RetryBudget {
+        RetryBudget {
+            limit,
+            spent: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Take one retry from the budget, returning false if none is left.
+    pub fn try_acquire(&self) -> bool {
+        self.spent
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |spent| {
+                Some(spent + 1).filter(|&spent| spent <= self.limit)
+            })
+            .is_ok()
+    }
+
+    /// The retries taken so far.
+    pub fn spent(&self) -> u32 {
+        self.spent.load(Ordering::Acquire)
+    }
+
+    /// The retries left.
+    pub fn remaining(&self) -> u32 {
+        self.limit - self.spent()
+    }
+
+    /// Whether no retry is left.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+/// How the operations of a helper are retried.
+pub(crate) struct Retries<'a> {
+    /// The retries of each operation.
+    pub max_retries: u32,
+    /// The delay before the first retry of an operation, doubled for every further retry.
+    pub delay: Duration,
+    /// The clock the delays are waited on.
+    pub clock: &'a dyn Clock,
+    /// The budget every retry is taken from, if any.
+    pub budget: Option<&'a RetryBudget>,
+    /// Told of every retry, as `operation`.
+    pub metrics: Option<&'a dyn MetricsObserver>,
+    /// The name the retries are reported under.
+    pub operation: &'static str,
+}
+
+impl Retries<'_> {
+    /// Run `op` until it succeeds, `max_retries` retries were made, or the budget is spent,
+    /// returning the number of attempts along with the result of the last one.
+    pub async fn run<F, Fut, T, E>(&self, mut op: F) -> (u32, Result<T, E>)
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempts = 0;
+        let mut delay = self.delay;
+        loop {
+            attempts += 1;
+            let result = op(attempts).await;
+            if result.is_ok() || attempts > self.max_retries {
+                return (attempts, result);
+            }
+            if !self.budget.is_none_or(RetryBudget::try_acquire) {
+                return (attempts, result);
+            }
+            self.clock.sleep(delay).await;
+            delay *= 2;
+            if let Some(metrics) = self.metrics {
+                metrics.request_retried(self.operation, attempts + 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,2061 @@
+pub(crate) mod ops;
+
+use crate::audit::{bucket_and_key, is_mutating, AuditRecord};
+use crate::bucket_name::validate_bucket_name;
+use crate::bucket_profile::{apply_bucket_profile, BucketProfile};
+use crate::config::{AcceptEncoding, Ks3Config, OperationConfig};
+use crate::core::client::SignAndDispatchError;
+use crate::core::clock::{Clock, Instant, SystemClock};
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util as xml_util;
+use crate::core::{BufferedHttpResponse, DispatchSignedRequest, HttpResponse};
+use crate::core::{Client, DispatchOptions, DispatchOutcome};
+use crate::credential::ProvideAwsCredentials;
+use crate::dates::{format_http_date, format_iso8601, parse_http_date, parse_iso8601};
+use crate::deletion::guard;
+use crate::enums::ServerSideEncryption;
+use crate::filter::KeyFilter;
+use crate::header_dialect::{alias_kss_headers, apply_header_dialect};
+use crate::hedge::hedged_get_object;
+use crate::key::{validate_key, validate_key_length};
+use crate::listing::{list_all_objects, ListObjectsStream};
+use crate::metadata::Metadata;
+use crate::metrics::{observe_transfer, RequestMetrics};
+use crate::mime::mime_type_for_key;
+use crate::object_cache::cached_get_object;
+#[cfg(feature = "otel")]
+use crate::otel::ClientSpan;
+use crate::request::*;
+use crate::resume::resuming_body;
+use crate::retry::Retries;
+use crate::select::SelectObjectContentEventStream;
+use crate::signature::signer::Params;
+use crate::signature::{ByteStream, Region, SignedRequest};
+
+#[cfg(any(
+    feature = "append-object",
+    feature = "bucket-analytics",
+    feature = "bucket-lifecycle",
+    feature = "bucket-metrics",
+    feature = "multipart",
+    feature = "restore-object"
+))]
+use self::ops::*;
+use async_trait::async_trait;
+use futures::{future, Stream};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::instrument;
+use xml::EventWriter;
+
+/// Trait representing the capabilities of the Amazon S3 API. Amazon S3 clients implement this trait.
+#[async_trait]
+pub trait S3 {
+    /// <p><p>Creates a new bucket. To create a bucket, you must register with Amazon S3 and have a valid AWS Access Key ID to authenticate requests. Anonymous requests are never allowed to create buckets. By creating the bucket, you become the bucket owner.</p> <p>Not every string is an acceptable bucket name. For information on bucket naming restrictions, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingBucket.html">Working with Amazon S3 Buckets</a>.</p> <p>By default, the bucket is created in the US East (N. Virginia) Region. You can optionally specify a Region in the request body. You might choose a Region to optimize latency, minimize costs, or address regulatory requirements. For example, if you reside in Europe, you will probably find it advantageous to create buckets in the Europe (Ireland) Region. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingBucket.html#access-bucket-intro">How to Select a Region for Your Buckets</a>.</p> <note> <p>If you send your create bucket request to the <code>s3.amazonaws.com</code> endpoint, the request goes to the us-east-1 Region. Accordingly, the signature calculations in Signature Version 4 must use us-east-1 as the Region, even if the location constraint in the request specifies another Region where the bucket is to be created. If you create a bucket in a Region other than US East (N. Virginia), your application must be able to handle 307 redirect. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/VirtualHosting.html">Virtual Hosting of Buckets</a>.</p> </note> <p>When creating a bucket using this operation, you can optionally specify the accounts or groups that should be granted specific permissions on the bucket. There are two ways to grant the appropriate permissions using the request headers.</p> <ul> <li> <p>Specify a canned ACL using the <code>x-amz-acl</code> request header. Amazon S3 supports a set of predefined ACLs, known as <i>canned ACLs</i>. Each canned ACL has a predefined set of grantees and permissions. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html#CannedACL">Canned ACL</a>.</p> </li> <li> <p>Specify access permissions explicitly using the <code>x-amz-grant-read</code>, <code>x-amz-grant-write</code>, <code>x-amz-grant-read-acp</code>, <code>x-amz-grant-write-acp</code>, and <code>x-amz-grant-full-control</code> headers. These headers map to the set of permissions Amazon S3 supports in an ACL. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html">Access Control List (ACL) Overview</a>.</p> <p>You specify each grantee as a type=value pair, where the type is one of the following:</p> <ul> <li> <p> <code>id</code> – if the value specified is the canonical user ID of an AWS account</p> </li> <li> <p> <code>uri</code> – if you are granting permissions to a predefined group</p> </li> <li> <p> <code>emailAddress</code> – if the value specified is the email address of an AWS account</p> <note> <p>Using email addresses to specify a grantee is only supported in the following AWS Regions: </p> <ul> <li> <p>US East (N. Virginia)</p> </li> <li> <p>US West (N. California)</p> </li> <li> <p> US West (Oregon)</p> </li> <li> <p> Asia Pacific (Singapore)</p> </li> <li> <p>Asia Pacific (Sydney)</p> </li> <li> <p>Asia Pacific (Tokyo)</p> </li> <li> <p>Europe (Ireland)</p> </li> <li> <p>South America (São Paulo)</p> </li> </ul> <p>For a list of all the Amazon S3 supported Regions and endpoints, see <a href="https://docs.aws.amazon.com/general/latest/gr/rande.html#s3_region">Regions and Endpoints</a> in the AWS General Reference.</p> </note> </li> </ul> <p>For example, the following <code>x-amz-grant-read</code> header grants the AWS accounts identified by account IDs permissions to read object data and its metadata:</p> <p> <code>x-amz-grant-read: id=&quot;11112222333&quot;, id=&quot;444455556666&quot; </code> </p> </li> </ul> <note> <p>You can use either a canned ACL or specify access permissions explicitly. You cannot do both.</p> </note> <p>The following operations are related to <code>CreateBucket</code>:</p> <ul> <li> <p> <a>PutObject</a> </p> </li> <li> <p> <a>DeleteBucket</a> </p> </li> </ul></p>
+    async fn create_bucket(
+        &self,
+        input: CreateBucketRequest,
+    ) -> Result<CreateBucketOutput, Ks3Error<CreateBucketError>>;
+
+    /// <p><p>Adds an object to a bucket. You must have WRITE permissions on a bucket to add an object to it.</p> <p>Amazon S3 never adds partial objects; if you receive a success response, Amazon S3 added the entire object to the bucket.</p> <p>Amazon S3 is a distributed system. If it receives multiple write requests for the same object simultaneously, it overwrites all but the last object written. Amazon S3 does not provide object locking; if you need this, make sure to build it into your application layer or use versioning instead.</p> <p>To ensure that data is not corrupted traversing the network, use the <code>Content-MD5</code> header. When you use this header, Amazon S3 checks the object against the provided MD5 value and, if they do not match, returns an error. Additionally, you can calculate the MD5 while putting an object to Amazon S3 and compare the returned ETag to the calculated MD5 value.</p> <note> <p> The <code>Content-MD5</code> header is required for any request to upload an object with a retention period configured using Amazon S3 Object Lock. For more information about Amazon S3 Object Lock, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/object-lock-overview.html">Amazon S3 Object Lock Overview</a> in the <i>Amazon Simple Storage Service Developer Guide</i>. </p> </note> <p> <b>Server-side Encryption</b> </p> <p>You can optionally request server-side encryption. With server-side encryption, Amazon S3 encrypts your data as it writes it to disks in its data centers and decrypts the data when you access it. You have the option to provide your own encryption key or use AWS managed encryption keys. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingServerSideEncryption.html">Using Server-Side Encryption</a>.</p> <p> <b>Access Control List (ACL)-Specific Request Headers</b> </p> <p>You can use headers to grant ACL- based permissions. By default, all objects are private. Only the owner has full access control. When adding a new object, you can grant permissions to individual AWS accounts or to predefined groups defined by Amazon S3. These permissions are then added to the ACL on the object. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html">Access Control List (ACL) Overview</a> and <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-using-rest-api.html">Managing ACLs Using the REST API</a>. </p> <p> <b>Storage Class Options</b> </p> <p>By default, Amazon S3 uses the STANDARD storage class to store newly created objects. The STANDARD storage class provides high durability and high availability. Depending on performance needs, you can specify a different storage class. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/storage-class-intro.html">Storage Classes</a> in the <i>Amazon S3 Service Developer Guide</i>.</p> <p> <b>Versioning</b> </p> <p>If you enable versioning for a bucket, Amazon S3 automatically generates a unique version ID for the object being stored. Amazon S3 returns this ID in the response. When you enable versioning for a bucket, if Amazon S3 receives multiple write requests for the same object simultaneously, it stores all of the objects.</p> <p>For more information about versioning, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/AddingObjectstoVersioningEnabledBuckets.html">Adding Objects to Versioning Enabled Buckets</a>. For information about returning the versioning state of a bucket, see <a>GetBucketVersioning</a>. </p> <p class="title"> <b>Related Resources</b> </p> <ul> <li> <p> <a>CopyObject</a> </p> </li> <li> <p> <a>DeleteObject</a> </p> </li> </ul></p>
+    async fn put_object(
+        &self,
+        input: PutObjectRequest,
+    ) -> Result<PutObjectOutput, Ks3Error<PutObjectError>>;
+
+    /// <p><p>Returns some or all (up to 1,000) of the objects in a bucket. You can use the request parameters as selection criteria to return a subset of the objects in a bucket. A 200 OK response can contain valid or invalid XML. Be sure to design your application to parse the contents of the response and handle it appropriately.</p> <p>The following operations are related to <code>ListObjects</code>:</p> <ul> <li> <p> <a>GetObject</a> </p> </li> <li> <p> <a>PutObject</a> </p> </li> <li> <p> <a>CreateBucket</a> </p> </li> </ul></p>
+    async fn list_objects(
+        &self,
+        input: ListObjectsRequest,
+    ) -> Result<ListObjectsOutput, Ks3Error<ListObjectsError>>;
+
+    /// <p><p>Removes the null version (if there is one) of an object and inserts a delete marker, which becomes the latest version of the object. If there isn't a null version, Amazon S3 does not remove any objects.</p> <p>To remove a specific version, you must be the bucket owner and you must use the version Id subresource. Using this subresource permanently deletes the version. If the object deleted is a delete marker, Amazon S3 sets the response header, <code>x-amz-delete-marker</code>, to true. </p> <p>The following operation is related to <code>DeleteObject</code>:</p> <ul> <li> <p> <a>PutObject</a> </p> </li> </ul></p>
+    async fn delete_object(
+        &self,
+        input: DeleteObjectRequest,
+    ) -> Result<DeleteObjectOutput, Ks3Error<DeleteObjectError>>;
+
+    /// <p><p>Retrieves objects from Amazon S3. To use <code>GET</code>, you must have <code>READ</code> access to the object. If you grant <code>READ</code> access to the anonymous user, you can return the object without using an authorization header.</p> <p>To distribute large files to many people, you can save bandwidth costs by using BitTorrent. </p> <p> <b>Versioning</b> </p> <p>By default, the GET operation returns the current version of an object. To return a different version, use the <code>versionId</code> subresource.</p> <p> <b>Overriding Response Header Values</b> </p> <p>There are times when you want to override certain response header values in a GET response. For example, you might override the Content-Disposition response header value in your GET request. You can override values for a set of response headers using the <code>response-*</code> query parameters.</p> <p>The following operations are related to <code>GetObject</code>:</p> <ul> <li> <p> <a>ListObjects</a> </p> </li> <li> <p> <a>PutObject</a> </p> </li> </ul></p>
+    async fn get_object(
+        &self,
+        input: GetObjectRequest,
+    ) -> Result<GetObjectOutput, Ks3Error<GetObjectError>>;
+
+    /// <p>This operation is useful to determine if a bucket exists and you have permission to access it. The operation returns a <code>200 OK</code> if the bucket exists and you have permission to access it. Otherwise, the operation might return responses such as <code>404 Not Found</code> and <code>403 Forbidden</code>. </p> <p>To use this operation, you must have permissions to perform the <code>s3:ListBucket</code> action. The bucket owner has this permission by default and can grant this permission to others.</p>
+    async fn head_bucket(&self, input: HeadBucketRequest) -> Result<(), Ks3Error<HeadBucketError>>;
+
+    /// <p><p>The HEAD operation retrieves metadata from an object without returning the object itself. This operation is useful if you're only interested in an object's metadata. To use HEAD, you must have READ access to the object.</p> <p>A <code>HEAD</code> request has the same options as a <code>GET</code> operation on an object. The response is identical to the <code>GET</code> response except that there is no response body.</p> <p>If the object you request does not exist, the error Amazon S3 returns depends on whether you also have the s3:ListBucket permission.</p> <ul> <li> <p>If you have the <code>s3:ListBucket</code> permission on the bucket, Amazon S3 returns an HTTP status code 404 (&quot;no such key&quot;) error.</p> </li> <li> <p>If you don’t have the <code>s3:ListBucket</code> permission, Amazon S3 returns an HTTP status code 403 (&quot;access denied&quot;) error.</p> </li> </ul> <p>The following operation is related to <code>HeadObject</code>:</p> <ul> <li> <p> <a>GetObject</a> </p> </li> </ul></p>
+    async fn head_object(
+        &self,
+        input: HeadObjectRequest,
+    ) -> Result<HeadObjectOutput, Ks3Error<HeadObjectError>>;
+
+    #[cfg(feature = "multipart")]
+    /// <p><p>This operation initiates a multipart upload and returns an upload ID. This upload ID is used to associate all of the parts in the specific multipart upload. You specify this upload ID in each of your subsequent upload part requests (see <a>UploadPart</a>). You also include this upload ID in the final request to either complete or abort the multipart upload request.</p> <p>The following operations are related to <code>CreateMultipartUpload</code>:</p> <ul> <li> <p> <a>UploadPart</a> </p> </li> <li> <p> <a>CompleteMultipartUpload</a> </p> </li> <li> <p> <a>AbortMultipartUpload</a> </p> </li> </ul></p>
+    async fn create_multipart_upload(
+        &self,
+        input: CreateMultipartUploadRequest,
+    ) -> Result<CreateMultipartUploadOutput, Ks3Error<CreateMultipartUploadError>>;
+
+    #[cfg(feature = "multipart")]
+    /// <p><p>Uploads a part in a multipart upload.</p> <note> <p>In this operation, you provide part data in your request. However, you have an option to specify your existing Amazon S3 object as a data source for the part you are uploading.</p> </note> <p>You must initiate a multipart upload (see <a>CreateMultipartUpload</a>) before you can upload any part. In response to your initiate request, Amazon S3 returns an upload ID, a unique identifier, that you must include in your upload part request.</p> <p>Part numbers can be any number from 1 to 10,000, inclusive. A part number uniquely identifies a part and also defines its position within the object being created. If you upload a new part using the same part number that was used with a previous part, the previously uploaded part is overwritten. Each part must be at least 5 MB in size, except the last part.</p></p>
+    async fn upload_part(
+        &self,
+        input: UploadPartRequest,
+    ) -> Result<UploadPartOutput, Ks3Error<UploadPartError>>;
+
+    #[cfg(feature = "multipart")]
+    /// <p><p>Completes a multipart upload by assembling previously uploaded parts.</p> <p>You first initiate the multipart upload and then upload all parts using the <a>UploadPart</a> operation. After successfully uploading all relevant parts of an upload, you call this operation to complete the upload. Upon receiving this request, Amazon S3 concatenates all the parts in ascending order by part number to create a new object. In the Complete Multipart Upload request, you must provide the parts list. You must ensure that the parts list is complete.</p> <p>Processing of a Complete Multipart Upload request could take several minutes to complete. While processing is in progress, Amazon S3 periodically sends white space characters to keep the connection from timing out. Because a request could fail after the initial 200 OK response has been sent, it is important that you check the response body to determine whether the request succeeded.</p></p>
+    async fn complete_multipart_upload(
+        &self,
+        input: CompleteMultipartUploadRequest,
+    ) -> Result<CompleteMultipartUploadOutput, Ks3Error<CompleteMultipartUploadError>>;
+
+    #[cfg(feature = "multipart")]
+    /// <p><p>This operation aborts a multipart upload. After a multipart upload is aborted, no additional parts can be uploaded using that upload ID. The storage consumed by any previously uploaded parts will be freed. However, if any part uploads are currently in progress, those part uploads might or might not succeed. As a result, it might be necessary to abort a given multipart upload multiple times in order to completely free all storage consumed by all parts.</p></p>
+    async fn abort_multipart_upload(
+        &self,
+        input: AbortMultipartUploadRequest,
+    ) -> Result<AbortMultipartUploadOutput, Ks3Error<AbortMultipartUploadError>>;
+    /// <p><p>Sets the supplied tag-set to an object that already exists in a bucket.</p> <p>A tag is a key-value pair. You can associate tags with an object by sending a PUT request against the tagging subresource that is associated with the object. To replace the tag-set of an object, send the complete new set; the existing one is overwritten.</p></p>
+    async fn put_object_tagging(
+        &self,
+        input: PutObjectTaggingRequest,
+    ) -> Result<PutObjectTaggingOutput, Ks3Error<PutObjectTaggingError>>;
+
+    /// <p><p>Uses the <code>acl</code> subresource to set the access control list (ACL) permissions for an object that already exists in a bucket. You must have <code>WRITE_ACP</code> permission to set the ACL of an object.</p> <p>Depending on your application needs, you can choose to set the ACL on an object using either the request body or the headers, e.g. a canned ACL with the <code>x-amz-acl</code> header. You cannot do both.</p></p>
+    async fn put_object_acl(
+        &self,
+        input: PutObjectAclRequest,
+    ) -> Result<PutObjectAclOutput, Ks3Error<PutObjectAclError>>;
+
+    /// <p><p>Creates a copy of an object that is already stored in Amazon S3.</p> <p>Copying an object onto itself is allowed as long as something else changes at the same time, e.g. the storage class, the metadata (with a <code>REPLACE</code> metadata directive) or the encryption settings. A copy request might return an error after the initial <code>200 OK</code> response has been sent, in which case the error is carried in the response body.</p></p>
+    async fn copy_object(
+        &self,
+        input: CopyObjectRequest,
+    ) -> Result<CopyObjectOutput, Ks3Error<CopyObjectError>>;
+    #[cfg(feature = "multipart")]
+    /// <p><p>Uploads a part by copying data from an existing object as data source. You specify the data source by adding the request header <code>x-amz-copy-source</code> in your request and a byte range by adding the request header <code>x-amz-copy-source-range</code> in your request.</p> <p>The minimum allowable part size for a multipart upload is 5 MB, and copying is the only way to build objects larger than 5 GB from existing ones.</p></p>
+    async fn upload_part_copy(
+        &self,
+        input: UploadPartCopyRequest,
+    ) -> Result<UploadPartCopyOutput, Ks3Error<UploadPartCopyError>>;
+
+    /// <p><p>Deletes the bucket. All objects (including all object versions and delete markers) in the bucket must be deleted before the bucket itself can be deleted.</p></p>
+    async fn delete_bucket(
+        &self,
+        input: DeleteBucketRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketError>>;
+
+    /// <p>Returns a list of all buckets owned by the authenticated sender of the request.</p>
+    async fn list_buckets(&self) -> Result<ListBucketsOutput, Ks3Error<ListBucketsError>>;
+
+    /// <p><p>Returns the Region the bucket resides in. You set the bucket's Region using the <code>LocationConstraint</code> request parameter in a <code>CreateBucket</code> request.</p> <p>To use this operation, you must be the bucket owner.</p></p>
+    async fn get_bucket_location(
+        &self,
+        input: GetBucketLocationRequest,
+    ) -> Result<GetBucketLocationOutput, Ks3Error<GetBucketLocationError>>;
+
+    #[cfg(feature = "multipart")]
+    /// <p><p>This operation lists in-progress multipart uploads. An in-progress multipart upload is a multipart upload that has been initiated using the Initiate Multipart Upload request, but has not yet been completed or aborted.</p> <p>This operation returns at most 1,000 multipart uploads in the response. 1,000 multipart uploads is the maximum number of uploads a response can include, which is also the default value. You can further limit the number of uploads in a response by specifying the <code>max-uploads</code> parameter in the response. If additional multipart uploads satisfy the list criteria, the response will contain an <code>IsTruncated</code> element with the value true. To list the additional multipart uploads, use the <code>key-marker</code> and <code>upload-id-marker</code> request parameters.</p></p>
+    async fn list_multipart_uploads(
+        &self,
+        input: ListMultipartUploadsRequest,
+    ) -> Result<ListMultipartUploadsOutput, Ks3Error<ListMultipartUploadsError>>;
+
+    #[cfg(feature = "multipart")]
+    /// <p><p>Lists the parts that have been uploaded for a specific multipart upload. This operation must include the upload ID, which you obtain by sending the initiate multipart upload request (see <a>CreateMultipartUpload</a>).</p> <p>This request returns a maximum of 1,000 uploaded parts. If the upload has more parts, the response is truncated, with <code>IsTruncated</code> set to true and a <code>NextPartNumberMarker</code> to send as the <code>part-number-marker</code> of the next request.</p></p>
+    async fn list_parts(
+        &self,
+        input: ListPartsRequest,
+    ) -> Result<ListPartsOutput, Ks3Error<ListPartsError>>;
+
+    /// <p><p>Returns metadata about all of the versions of objects in a bucket. You can also use request parameters as selection criteria to return metadata about a subset of all the object versions. A 200 OK response can contain valid or invalid XML. Make sure to design your application to parse the contents of the response and handle it appropriately.</p> <p>To use this operation, you must have READ access to the bucket.</p> <p>The following operations are related to <code>ListObjectVersions</code>:</p> <ul> <li> <p> <a>ListObjects</a> </p> </li> <li> <p> <a>GetObject</a> </p> </li> <li> <p> <a>DeleteObject</a> </p> </li> </ul></p>
+    async fn list_object_versions(
+        &self,
+        input: ListObjectVersionsRequest,
+    ) -> Result<ListObjectVersionsOutput, Ks3Error<ListObjectVersionsError>>;
+
+    /// <p><p>This operation filters the contents of an Amazon S3 object based on a simple structured query language (SQL) statement. In the request, along with the SQL expression, you must also specify a data serialization format (JSON, CSV, or Apache Parquet) of the object. Amazon S3 uses this format to parse object data into records, and returns only records that match the specified SQL expression. You must also specify the data serialization format for the response.</p> <p>The response is a stream of events: batches of records, progress and statistics messages, and a final end event. An error found while scanning the object is reported as an event of the stream rather than as an error of the request.</p></p>
+    async fn select_object_content(
+        &self,
+        input: SelectObjectContentRequest,
+    ) -> Result<SelectObjectContentOutput, Ks3Error<SelectObjectContentError>>;
+
+    #[cfg(feature = "bucket-metrics")]
+    /// <p><p>Sets a metrics configuration (specified by the metrics configuration ID) for the bucket. You can have up to 1,000 metrics configurations per bucket. If you're updating an existing metrics configuration, note that this is a full replacement of the existing metrics configuration. If you don't include the elements you want to keep, they are erased.</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutMetricsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    async fn put_bucket_metrics_configuration(
+        &self,
+        input: PutBucketMetricsConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketMetricsConfigurationError>>;
+
+    #[cfg(feature = "bucket-metrics")]
+    /// <p><p>Gets a metrics configuration (specified by the metrics configuration ID) from the bucket. Note that this doesn't include the daily storage metrics.</p> <p>To use this operation, you must have permissions to perform the <code>s3:GetMetricsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    async fn get_bucket_metrics_configuration(
+        &self,
+        input: GetBucketMetricsConfigurationRequest,
+    ) -> Result<GetBucketMetricsConfigurationOutput, Ks3Error<GetBucketMetricsConfigurationError>>;
+
+    #[cfg(feature = "bucket-metrics")]
+    /// <p><p>Deletes a metrics configuration for the request metrics (specified by the metrics configuration ID) from the bucket. Note that this doesn't include the daily storage metrics.</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutMetricsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    async fn delete_bucket_metrics_configuration(
+        &self,
+        input: DeleteBucketMetricsConfigurationRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketMetricsConfigurationError>>;
+
+    #[cfg(feature = "bucket-metrics")]
+    /// <p><p>Lists the metrics configurations for the bucket. The metrics configurations are only for the request metrics of the bucket and do not provide information on daily storage metrics. You can have up to 1,000 configurations per bucket.</p> <p>This operation supports list pagination and does not return more than 100 configurations at a time. Always check the <code>IsTruncated</code> element in the response. If there are no more configurations to list, <code>IsTruncated</code> is set to false. If there are more configurations to list, <code>IsTruncated</code> is set to true, and there is a value in <code>NextContinuationToken</code>. You use the <code>NextContinuationToken</code> value to continue the pagination of the list by passing the value in <code>continuation-token</code> in the request to <code>GET</code> the next page.</p></p>
+    async fn list_bucket_metrics_configurations(
+        &self,
+        input: ListBucketMetricsConfigurationsRequest,
+    ) -> Result<ListBucketMetricsConfigurationsOutput, Ks3Error<ListBucketMetricsConfigurationsError>>;
+
+    #[cfg(feature = "bucket-analytics")]
+    /// <p><p>Sets an analytics configuration for the bucket (specified by the analytics configuration ID). You can have up to 1,000 analytics configurations per bucket.</p> <p>You can choose to have storage class analysis export analysis reports sent to a comma-separated values (CSV) flat file. Reports are updated daily and are based on the object filters that you configure. When selecting data export, you specify a destination bucket and an optional destination prefix where the file is written.</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutAnalyticsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    async fn put_bucket_analytics_configuration(
+        &self,
+        input: PutBucketAnalyticsConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketAnalyticsConfigurationError>>;
+
+    #[cfg(feature = "bucket-analytics")]
+    /// <p><p>This operation returns an analytics configuration (identified by the analytics configuration ID) from the bucket.</p> <p>To use this operation, you must have permissions to perform the <code>s3:GetAnalyticsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    async fn get_bucket_analytics_configuration(
+        &self,
+        input: GetBucketAnalyticsConfigurationRequest,
+    ) -> Result<GetBucketAnalyticsConfigurationOutput, Ks3Error<GetBucketAnalyticsConfigurationError>>;
+
+    #[cfg(feature = "bucket-analytics")]
+    /// <p><p>Deletes an analytics configuration for the bucket (specified by the analytics configuration ID).</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutAnalyticsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    async fn delete_bucket_analytics_configuration(
+        &self,
+        input: DeleteBucketAnalyticsConfigurationRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketAnalyticsConfigurationError>>;
+
+    #[cfg(feature = "bucket-analytics")]
+    /// <p><p>Lists the analytics configurations for the bucket. You can have up to 1,000 analytics configurations per bucket.</p> <p>This operation supports list pagination and does not return more than 100 configurations at a time. You should always check the <code>IsTruncated</code> element in the response. If there are no more configurations to list, <code>IsTruncated</code> is set to false. If there are more configurations to list, <code>IsTruncated</code> is set to true, and there will be a value in <code>NextContinuationToken</code>. You use the <code>NextContinuationToken</code> value to continue the pagination of the list by passing the value in continuation-token in the request to <code>GET</code> the next page.</p></p>
+    async fn list_bucket_analytics_configurations(
+        &self,
+        input: ListBucketAnalyticsConfigurationsRequest,
+    ) -> Result<
+        ListBucketAnalyticsConfigurationsOutput,
+        Ks3Error<ListBucketAnalyticsConfigurationsError>,
+    >;
+
+    #[cfg(feature = "bucket-lifecycle")]
+    /// <p><p>Sets the lifecycle configuration of the bucket, replacing the current one as a whole: rules left out of the new configuration are removed. Its rules move objects to other storage classes, such as <code>ARCHIVE</code>, and delete them as they age.</p> <p>It can take a while for a new configuration to apply to every object of the bucket.</p></p>
+    async fn put_bucket_lifecycle_configuration(
+        &self,
+        input: PutBucketLifecycleConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketLifecycleConfigurationError>>;
+
+    #[cfg(feature = "bucket-lifecycle")]
+    /// <p>Returns the lifecycle configuration of the bucket, failing with <code>NoSuchLifecycleConfiguration</code> when it has none.</p>
+    async fn get_bucket_lifecycle_configuration(
+        &self,
+        input: GetBucketLifecycleConfigurationRequest,
+    ) -> Result<GetBucketLifecycleConfigurationOutput, Ks3Error<GetBucketLifecycleConfigurationError>>;
+
+    #[cfg(feature = "restore-object")]
+    /// <p><p>Restores a temporary copy of an archived object, readable for the number of days of the request. The restore completes in the background; until then, the <code>restore</code> header of <code>HeadObject</code> reports <code>ongoing-request="true"</code>.</p> <p>Sending the request again for an object already restored extends the life of the restored copy.</p></p>
+    async fn restore_object(
+        &self,
+        input: RestoreObjectRequest,
+    ) -> Result<RestoreObjectOutput, Ks3Error<RestoreObjectError>>;
+
+    #[cfg(feature = "append-object")]
+    /// <p><p>Appends bytes to an appendable object, creating it with the first append at position 0. Each append has to be sent at the current length of the object, returned as the next append position of the previous one, so concurrent writers to the same object fail rather than overwrite each other.</p> <p>An object created by appending can't be uploaded to in parts, and an object uploaded with <code>PutObject</code> can't be appended to.</p></p>
+    async fn append_object(
+        &self,
+        input: AppendObjectRequest,
+    ) -> Result<AppendObjectOutput, Ks3Error<AppendObjectError>>;
+}
+
+/// The delay before the first retry of a request, see `OperationConfig::retries`.
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// A client for the Amazon S3 API.
+#[derive(Clone)]
+pub struct S3Client {
+    client: Client,
+    region: Region,
+    /// Shared by the copies of the client until one of them changes it.
+    config: Arc<Ks3Config>,
+    /// The settings of the calls of this client, see `customize`.
+    operation: OperationConfig,
+    clock: Arc<dyn Clock>,
+}
+
+impl S3Client {
+    /// Creates a client backed by the default tokio event loop.
+    ///
+    /// The client will use the default credentials provider and tls client. On `wasm32`, build
+    /// the client with `new_with`, a `FetchDispatcher` and the credentials instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(region: Region) -> Self {
+        S3Client {
+            client: Client::shared(),
+            region,
+            config: Arc::new(Ks3Config::default()),
+            operation: OperationConfig::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn new_with<P, D>(request_dispatcher: D, credentials_provider: P, region: Region) -> Self
+    where
+        P: ProvideAwsCredentials + Send + Sync + 'static,
+        D: DispatchSignedRequest + Send + Sync + 'static,
+    {
+        S3Client {
+            client: Client::new_with(credentials_provider, request_dispatcher),
+            region,
+            config: Arc::new(Ks3Config::default()),
+            operation: OperationConfig::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn new_with_client(client: Client, region: Region) -> S3Client {
+        S3Client {
+            client,
+            region,
+            config: Arc::new(Ks3Config::default()),
+            operation: OperationConfig::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Sets whether `put_object` fills in a missing `Content-Type` from the extension of the
+    /// object key. Disabled by default, in which case the service stores such objects as
+    /// `binary/octet-stream`.
+    pub fn infer_content_type(&mut self, enabled: bool) {
+        Arc::make_mut(&mut self.config).infer_content_type = enabled;
+    }
+
+    /// Sets whether `put_object` and `upload_part` compute the `Content-MD5` header of bodies
+    /// created from an in-memory buffer when the request doesn't carry one, and check the
+    /// returned ETag against that digest. A mismatch is reported as
+    /// `Ks3Error::IntegrityCheckFailed`. Disabled by default.
+    pub fn compute_content_md5(&mut self, enabled: bool) {
+        Arc::make_mut(&mut self.config).compute_content_md5 = enabled;
+    }
+
+    /// Sets the settings of the requests to `bucket`, replacing its previous profile: the
+    /// region they are sent to, whether they pay for a requester-pays bucket, and the
+    /// server-side encryption of uploads. See `BucketProfile`.
+    pub fn set_bucket_profile(&mut self, bucket: &str, profile: BucketProfile) {
+        Arc::make_mut(&mut self.config)
+            .bucket_profiles
+            .insert(bucket.to_owned(), profile);
+    }
+
+    /// Removes the profile of `bucket`, returning it, so that its requests use the settings of
+    /// the client again.
+    pub fn remove_bucket_profile(&mut self, bucket: &str) -> Option<BucketProfile> {
+        Arc::make_mut(&mut self.config)
+            .bucket_profiles
+            .remove(bucket)
+    }
+
+    /// Replaces the client-wide settings.
+    pub fn set_config(&mut self, config: Ks3Config) {
+        self.config = Arc::new(config);
+    }
+
+    /// The client-wide settings.
+    pub fn config(&self) -> &Ks3Config {
+        &self.config
+    }
+
+    /// A copy of the client whose calls follow `operation`, overriding the settings of the
+    /// client:
+    ///
+    /// ```rust,ignore
+    /// let background = client.customize(OperationConfig {
+    ///     retries: Some(5),
+    ///     ..Default::default()
+    /// });
+    /// background.put_object(input).await?;
+    /// ```
+    ///
+    /// The copy shares the connections and credentials of the client, and replaces the
+    /// overrides of a client already customized.
+    pub fn customize(&self, operation: OperationConfig) -> S3Client {
+        S3Client {
+            operation,
+            ..self.clone()
+        }
+    }
+
+    /// The settings overriding those of the client, see `customize`.
+    pub fn operation_config(&self) -> &OperationConfig {
+        &self.operation
+    }
+
+    /// The region requests are sent to.
+    pub fn region(&self) -> &Region {
+        &self.region
+    }
+
+    /// Sets the clock requests are signed with, and the expiry of credentials checked with.
+    /// Defaults to the system clock.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// The clock requests are signed with.
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Opens up to `connections` connections to the endpoint ahead of traffic, so that a burst
+    /// of requests, e.g. for a batch report, doesn't wait for TCP and TLS handshakes.
+    ///
+    /// As many signed `HEAD /` requests are sent at once, each on a connection of its own, and
+    /// the connections are kept in the pool of the dispatcher for later requests. Idle
+    /// connections are closed by the pool after a while, 90 seconds with `HttpClient`, so this
+    /// is best called shortly before the burst. Returns the number of requests that got a
+    /// response, whatever their status, or the last error when none did.
+    pub async fn preconnect(&self, connections: usize) -> Result<usize, SignAndDispatchError> {
+        let requests = (0..connections).map(|_| async move {
+            let request = SignedRequest::new("HEAD", "s3", &self.region, "/");
+            let mut response = self
+                .client
+                .sign_and_dispatch_with_clock(request, self.clock.clone())
+                .await?;
+            // The connection goes back to the pool once the body has been read.
+            response
+                .buffer_body()
+                .await
+                .map_err(SignAndDispatchError::Dispatch)?;
+            Ok(())
+        });
+        let mut connected = 0;
+        let mut last_error = None;
+        for result in future::join_all(requests).await {
+            match result {
+                Ok(()) => connected += 1,
+                Err(err) => last_error = Some(err),
+            }
+        }
+        match last_error {
+            Some(err) if connected == 0 => Err(err),
+            _ => Ok(connected),
+        }
+    }
+
+    /// Lists a page of objects like `list_objects`, but yields the objects as they are parsed
+    /// from the response body instead of buffering the whole page first.
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    pub async fn list_objects_streaming(
+        &self,
+        input: ListObjectsRequest,
+    ) -> Result<ListObjectsStream, Ks3Error<ListObjectsError>> {
+        let request = list_objects_request(&self.region, &input);
+        let response = self
+            .sign_and_dispatch("ListObjects", request, ListObjectsError::from_response)
+            .await?;
+        Ok(ListObjectsStream::new(response.body, response.headers))
+    }
+
+    /// All the objects listed by `input`, following the markers from page to page. Each page
+    /// is streamed with `list_objects_streaming`, and requested once the objects of the
+    /// previous one were read.
+    pub fn list_all_objects(
+        &self,
+        input: ListObjectsRequest,
+    ) -> impl Stream<Item = Result<Object, Ks3Error<ListObjectsError>>> + Send {
+        list_all_objects(self.clone(), input)
+    }
+
+    /// The objects listed by `input` whose whole key passes `filter`, following the markers
+    /// from page to page as `list_all_objects` does. Errors are passed on.
+    pub fn list_objects_matching(
+        &self,
+        input: ListObjectsRequest,
+        filter: KeyFilter,
+    ) -> impl Stream<Item = Result<Object, Ks3Error<ListObjectsError>>> + Send {
+        // `StreamExt` isn't imported here, as `ByteStream::buffered` would clash with it.
+        futures::StreamExt::filter(self.list_all_objects(input), move |object| {
+            future::ready(match object {
+                Ok(object) => object.key.as_deref().is_some_and(|key| filter.matches(key)),
+                Err(_) => true,
+            })
+        })
+    }
+}
+
+impl S3Client {
+    async fn sign_and_dispatch<E: std::error::Error + 'static>(
+        &self,
+        operation: &'static str,
+        request: SignedRequest,
+        from_response: fn(BufferedHttpResponse) -> Ks3Error<E>,
+    ) -> Result<HttpResponse, Ks3Error<E>> {
+        if let Some(outcome) = guard(&self.config.deletion_protection, operation, &request) {
+            return outcome.map_err(Ks3Error::Validation);
+        }
+        let metrics = self.config.metrics.as_ref();
+        if let Some(metrics) = metrics {
+            metrics.request_started(operation);
+        }
+        let bytes_sent = request
+            .payload
+            .as_ref()
+            .and_then(|payload| payload.size_hint())
+            .map(|len| len as u64);
+        let mut audit = self
+            .config
+            .audit_log
+            .as_ref()
+            .filter(|_| is_mutating(operation, &request.method))
+            .map(|audit_log| {
+                let (bucket, key) = bucket_and_key(&request.path);
+                // Completed once the outcome of the request is known.
+                let record = AuditRecord {
+                    operation,
+                    method: request.method.clone(),
+                    bucket,
+                    key,
+                    access_key_id: None,
+                    time: self.clock.now(),
+                    status: None,
+                    request_id: None,
+                    error: None,
+                };
+                (audit_log, record)
+            });
+        // Dropped from the cache once the request completed, whatever its outcome.
+        let changed_object = self
+            .config
+            .object_cache
+            .as_ref()
+            .filter(|_| is_mutating(operation, &request.method))
+            .and_then(|cache| match bucket_and_key(&request.path) {
+                (bucket, Some(key)) => Some((cache, bucket, key)),
+                (_, None) => None,
+            });
+        let mut request = request;
+        request.set_default_content_type(self.config.default_content_type.clone());
+        request.set_content_length_policy(self.config.content_length_policy);
+        request.set_uri_encoding(self.config.uri_encoding);
+        apply_bucket_profile(&self.config, &mut request);
+        if let Some(ref endpoint) = self.operation.endpoint {
+            request.region = endpoint.clone();
+            request.invalidate();
+        }
+        apply_header_dialect(self.config.header_dialect, &mut request);
+        #[cfg(feature = "otel")]
+        let mut span = ClientSpan::start(operation, &mut request);
+        let started = Instant::now();
+
+        let options = DispatchOptions {
+            capture: self.config.capture_failed_requests,
+            wire_log: self.config.wire_log.clone(),
+            // Completions are bounded by their own deadline, see `complete_multipart_upload`.
+            timeout: match operation {
+                "CompleteMultipartUpload" => None,
+                _ => self.operation.timeout.or(self.config.request_timeout),
+            },
+        };
+        let DispatchOutcome {
+            result,
+            captured,
+            access_key_id,
+        } = self
+            .dispatch_with_retries(operation, request, options)
+            .await;
+        if let Some((cache, bucket, key)) = changed_object {
+            cache.invalidate(&bucket, &key);
+        }
+        if let Some(metrics) = metrics {
+            let response = result.as_ref().ok();
+            metrics.request_completed(&RequestMetrics {
+                operation,
+                status: response.map(|response| response.status),
+                latency: started.elapsed(),
+                bytes_sent,
+                bytes_received: response
+                    .and_then(|response| response.headers.get("Content-Length"))
+                    .and_then(|len| len.parse().ok()),
+                timings: response.and_then(|response| response.timings),
+            });
+        }
+        let status = result.as_ref().ok().map(|response| response.status);
+        let request_id = result
+            .as_ref()
+            .ok()
+            .and_then(|response| response.request_id());
+        #[cfg(feature = "otel")]
+        span.record_response(status, request_id);
+        if let Some((_, ref mut record)) = audit {
+            record.access_key_id = access_key_id;
+            record.status = status;
+            record.request_id = request_id.map(str::to_owned);
+        }
+        let fail = |err: Ks3Error<E>| {
+            #[cfg(feature = "otel")]
+            span.fail(&err);
+            if let Some((audit_log, ref record)) = audit {
+                audit_log.record(&AuditRecord {
+                    error: Some(err.to_string()),
+                    ..record.clone()
+                });
+            }
+            match captured.clone() {
+                Some(request) => err.with_request(request),
+                None => err,
+            }
+        };
+
+        let mut response = result.map_err(|err| fail(err.into()))?;
+        alias_kss_headers(&mut response.headers);
+        if let Some(idle) = self.config.response_idle_timeout {
+            response.body = response.body.with_idle_timeout(idle);
+        }
+        if !response.status.is_success() {
+            let err = match response.into_buffered().await {
+                Ok(response) => from_response(response),
+                Err(err) => Ks3Error::from(err),
+            };
+            return Err(fail(err));
+        }
+        if answers_errors_with_ok(operation) {
+            let body = match response.buffer_body().await {
+                Ok(body) => body,
+                Err(err) => return Err(fail(Ks3Error::from(err))),
+            };
+            let start = body.len() - xml_util::trim_leading_whitespace(&body).len();
+            let body = body.slice(start..);
+            if xml_util::root_element_name(&body).as_deref() == Some("Error") {
+                return Err(fail(from_response(BufferedHttpResponse {
+                    status: response.status,
+                    headers: response.headers,
+                    body,
+                })));
+            }
+            response.body = ByteStream::from(body);
+        }
+        if let Some((audit_log, ref record)) = audit {
+            audit_log.record(record);
+        }
+        #[cfg(feature = "otel")]
+        span.succeed();
+
+        if let Some(metrics) = metrics {
+            response.body = observe_transfer(response.body, metrics.clone(), operation);
+        }
+        Ok(response)
+    }
+}
+
+impl S3Client {
+    /// Sign and send `request`, sending it again as many times as `OperationConfig::retries`
+    /// allows while it fails to be sent or gets a `5xx` response, and its payload can be sent
+    /// again.
+    async fn dispatch_with_retries(
+        &self,
+        operation: &'static str,
+        request: SignedRequest,
+        options: DispatchOptions,
+    ) -> DispatchOutcome {
+        let max_retries = match self.operation.retries {
+            Some(retries) if retries > 0 => retries,
+            _ => {
+                return self
+                    .client
+                    .sign_and_dispatch_with_options(request, self.clock.clone(), options)
+                    .await
+            }
+        };
+        let retries = Retries {
+            max_retries,
+            delay: RETRY_DELAY,
+            clock: &*self.clock,
+            budget: None,
+            metrics: self.config.metrics.as_deref(),
+            operation,
+        };
+        let mut next = Some(request);
+        let (_, outcome) = retries
+            .run(|_| {
+                let request = next.take().expect("retried a request that can't be copied");
+                next = request.try_clone();
+                let retryable = next.is_some();
+                let options = options.clone();
+                async move {
+                    let outcome = self
+                        .client
+                        .sign_and_dispatch_with_options(request, self.clock.clone(), options)
+                        .await;
+                    let failed = match outcome.result {
+                        Ok(ref response) => response.status.is_server_error(),
+                        Err(SignAndDispatchError::Dispatch(_)) => true,
+                        Err(SignAndDispatchError::Credentials(_)) => false,
+                    };
+                    if retryable && failed {
+                        Err(outcome)
+                    } else {
+                        Ok(outcome)
+                    }
+                }
+            })
+            .await;
+        outcome.unwrap_or_else(|outcome| outcome)
+    }
+}
+
+/// Whether the operation may fail after the service answered `200 OK`, in which case the body
+/// of the response is an `<Error>` document instead of the result, possibly after whitespace
+/// sent to keep the connection alive.
+fn answers_errors_with_ok(operation: &str) -> bool {
+    matches!(
+        operation,
+        "CompleteMultipartUpload" | "CopyObject" | "UploadPartCopy"
+    )
+}
+
+/// The request of `list_objects` and `list_objects_streaming`.
+fn list_objects_request(region: &Region, input: &ListObjectsRequest) -> SignedRequest {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("GET", "s3", region, &request_uri);
+
+    request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+    let mut params = Params::new();
+    if let Some(ref x) = input.delimiter {
+        params.put("delimiter", x);
+    }
+    if let Some(ref x) = input.encoding_type {
+        params.put("encoding-type", x);
+    }
+    if let Some(ref x) = input.marker {
+        params.put("marker", x);
+    }
+    if let Some(ref x) = input.max_keys {
+        params.put("max-keys", x);
+    }
+    if let Some(ref x) = input.prefix {
+        params.put("prefix", x);
+    }
+    request.set_params(params);
+    request
+}
+
+/// Why the length of the body of `put_object` is wrong, if it is: unknown, as the service
+/// rejects bodies sent chunked, or other than the `content_length` of the request.
+fn body_length_error(body: &ByteStream, content_length: Option<i64>) -> Option<String> {
+    match (body.exact_len(), content_length) {
+        (Some(len), Some(content_length)) if len as i64 != content_length => Some(format!(
+            "the content length of the request is {} but the body is {} bytes long",
+            content_length, len
+        )),
+        (None, None) if body.size_hint().is_none() => Some(
+            "PutObject needs the length of the body: set `content_length` or create the body \
+             with a size, or upload a stream of unknown length with a multipart upload, e.g. \
+             with `UploadWriter` or `multipart::MultipartUpload`"
+                .to_owned(),
+        ),
+        _ => None,
+    }
+}
+
+/// Check the ETag returned by an upload against the MD5 `digest` of the uploaded body,
+/// returning the ETag if they differ.
+///
+/// Objects encrypted with SSE-KMS or SSE-C don't use the MD5 of their content as the ETag, so
+/// they can't be checked this way.
+fn mismatched_etag(
+    e_tag: Option<&str>,
+    sse_customer_algorithm: Option<&str>,
+    server_side_encryption: Option<&ServerSideEncryption>,
+    digest: &str,
+) -> Option<String> {
+    if sse_customer_algorithm.is_some()
+        || server_side_encryption == Some(&ServerSideEncryption::AwsKms)
+    {
+        return None;
+    }
+    e_tag
+        .map(|e_tag| e_tag.trim_matches('"'))
+        .filter(|e_tag| !e_tag.eq_ignore_ascii_case(digest))
+        .map(str::to_owned)
+}
+
+impl S3Client {
+    /// `get_object` with `input`, neither cached nor hedged.
+    #[allow(unused_variables, warnings)]
+    pub(crate) async fn send_get_object(
+        &self,
+        input: GetObjectRequest,
+    ) -> Result<GetObjectOutput, Ks3Error<GetObjectError>> {
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("GET", "s3", &self.region, &request_uri);
+
+        request.add_optional_header("If-Match", input.if_match.as_ref());
+        request.add_optional_header(
+            "If-Modified-Since",
+            input.if_modified_since.as_ref().map(format_http_date),
+        );
+        request.add_optional_header("If-None-Match", input.if_none_match.as_ref());
+        request.add_optional_header(
+            "If-Unmodified-Since",
+            input.if_unmodified_since.as_ref().map(format_http_date),
+        );
+        request.add_optional_header("Range", input.range.as_ref());
+        request.add_optional_header(
+            "Accept-Encoding",
+            self.config
+                .accept_encoding
+                .as_ref()
+                .map(AcceptEncoding::header_value),
+        );
+        request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-algorithm",
+            input.sse_customer_algorithm.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key",
+            input.sse_customer_key.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key-MD5",
+            input.sse_customer_key_md5.as_ref(),
+        );
+        let mut params = Params::new();
+        if let Some(ref x) = input.part_number {
+            params.put("partNumber", x);
+        }
+        if let Some(ref x) = input.response_cache_control {
+            params.put("response-cache-control", x);
+        }
+        if let Some(ref x) = input.response_content_disposition {
+            params.put("response-content-disposition", x);
+        }
+        if let Some(ref x) = input.response_content_encoding {
+            params.put("response-content-encoding", x);
+        }
+        if let Some(ref x) = input.response_content_language {
+            params.put("response-content-language", x);
+        }
+        if let Some(ref x) = input.response_content_type {
+            params.put("response-content-type", x);
+        }
+        if let Some(ref x) = input.response_expires {
+            params.put("response-expires", format_http_date(x));
+        }
+        if let Some(ref x) = input.version_id {
+            params.put("versionId", x);
+        }
+        request.set_params(params);
+
+        let mut response = self
+            .sign_and_dispatch("GetObject", request, GetObjectError::from_response)
+            .await?;
+
+        let mut result = GetObjectOutput::default();
+        result.raw_headers = response.headers.clone();
+        result.body = Some(response.body);
+
+        result.accept_ranges = response.headers.remove("accept-ranges");
+        result.cache_control = response.headers.remove("Cache-Control");
+        result.checksum_crc64ecma = response.headers.remove("x-kss-checksum-crc64ecma");
+        result.content_disposition = response.headers.remove("Content-Disposition");
+        result.content_encoding = response.headers.remove("Content-Encoding");
+        result.content_language = response.headers.remove("Content-Language");
+        result.content_length = response
+            .headers
+            .remove("Content-Length")
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        result.content_range = response.headers.remove("Content-Range");
+        result.content_type = response.headers.remove("Content-Type");
+        result.delete_marker = response
+            .headers
+            .remove("x-amz-delete-marker")
+            .and_then(|value| value.trim().parse::<bool>().ok());
+        result.e_tag = response.headers.remove("ETag");
+        result.expiration = response.headers.remove("x-amz-expiration");
+        result.expires = response
+            .headers
+            .remove("Expires")
+            .and_then(|value| parse_http_date(&value));
+        result.last_modified = response
+            .headers
+            .remove("Last-Modified")
+            .and_then(|value| parse_http_date(&value));
+        let mut values = Metadata::new();
+        for (key, value) in response.headers.iter() {
+            if key.as_str().starts_with("x-amz-meta-") {
+                values.insert_unchecked(&key.as_str()["x-amz-meta-".len()..], value.to_owned());
+            }
+        }
+        result.metadata = Some(values);
+        result.missing_meta = response
+            .headers
+            .remove("x-amz-missing-meta")
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        result.negotiated_encoding = self
+            .config
+            .accept_encoding
+            .as_ref()
+            .and_then(|accept| accept.negotiated(result.content_encoding.as_deref()));
+        result.object_lock_legal_hold_status =
+            response.headers.remove("x-amz-object-lock-legal-hold");
+        result.object_lock_mode = response
+            .headers
+            .remove("x-amz-object-lock-mode")
+            .map(Into::into);
+        result.object_lock_retain_until_date = response
+            .headers
+            .remove("x-amz-object-lock-retain-until-date")
+            .and_then(|value| parse_iso8601(&value));
+        result.parts_count = response
+            .headers
+            .remove("x-amz-mp-parts-count")
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        result.replication_status = response.headers.remove("x-amz-replication-status");
+        result.request_charged = response.headers.remove("x-amz-request-charged");
+        result.restore = response.headers.remove("x-amz-restore");
+        result.sse_customer_algorithm = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-algorithm");
+        result.sse_customer_key_md5 = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-key-MD5");
+        result.ssekms_key_id = response
+            .headers
+            .remove("x-amz-server-side-encryption-aws-kms-key-id");
+        result.server_side_encryption = response
+            .headers
+            .remove("x-amz-server-side-encryption")
+            .map(Into::into);
+        result.storage_class = response
+            .headers
+            .remove("x-amz-storage-class")
+            .map(Into::into);
+        result.tag_count = response
+            .headers
+            .remove("x-amz-tagging-count")
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        result.version_id = response.headers.remove("x-amz-version-id");
+        result.website_redirect_location =
+            response.headers.remove("x-amz-website-redirect-location"); // parse non-payload
+
+        // The ranges of an encoded body don't match those of the object.
+        if let Some(max_resumes) = self
+            .config
+            .resume_downloads
+            .filter(|_| result.negotiated_encoding.is_none())
+        {
+            if let Some(body) = result.body.take() {
+                result.body = Some(resuming_body(self, &input, &result, body, max_resumes));
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl S3 for S3Client {
+    /// <p><p>Creates a new bucket. To create a bucket, you must register with Amazon S3 and have a valid AWS Access Key ID to authenticate requests. Anonymous requests are never allowed to create buckets. By creating the bucket, you become the bucket owner.</p> <p>Not every string is an acceptable bucket name. For information on bucket naming restrictions, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingBucket.html">Working with Amazon S3 Buckets</a>.</p> <p>By default, the bucket is created in the US East (N. Virginia) Region. You can optionally specify a Region in the request body. You might choose a Region to optimize latency, minimize costs, or address regulatory requirements. For example, if you reside in Europe, you will probably find it advantageous to create buckets in the Europe (Ireland) Region. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingBucket.html#access-bucket-intro">How to Select a Region for Your Buckets</a>.</p> <note> <p>If you send your create bucket request to the <code>s3.amazonaws.com</code> endpoint, the request goes to the us-east-1 Region. Accordingly, the signature calculations in Signature Version 4 must use us-east-1 as the Region, even if the location constraint in the request specifies another Region where the bucket is to be created. If you create a bucket in a Region other than US East (N. Virginia), your application must be able to handle 307 redirect. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/VirtualHosting.html">Virtual Hosting of Buckets</a>.</p> </note> <p>When creating a bucket using this operation, you can optionally specify the accounts or groups that should be granted specific permissions on the bucket. There are two ways to grant the appropriate permissions using the request headers.</p> <ul> <li> <p>Specify a canned ACL using the <code>x-amz-acl</code> request header. Amazon S3 supports a set of predefined ACLs, known as <i>canned ACLs</i>. Each canned ACL has a predefined set of grantees and permissions. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html#CannedACL">Canned ACL</a>.</p> </li> <li> <p>Specify access permissions explicitly using the <code>x-amz-grant-read</code>, <code>x-amz-grant-write</code>, <code>x-amz-grant-read-acp</code>, <code>x-amz-grant-write-acp</code>, and <code>x-amz-grant-full-control</code> headers. These headers map to the set of permissions Amazon S3 supports in an ACL. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html">Access Control List (ACL) Overview</a>.</p> <p>You specify each grantee as a type=value pair, where the type is one of the following:</p> <ul> <li> <p> <code>id</code> – if the value specified is the canonical user ID of an AWS account</p> </li> <li> <p> <code>uri</code> – if you are granting permissions to a predefined group</p> </li> <li> <p> <code>emailAddress</code> – if the value specified is the email address of an AWS account</p> <note> <p>Using email addresses to specify a grantee is only supported in the following AWS Regions: </p> <ul> <li> <p>US East (N. Virginia)</p> </li> <li> <p>US West (N. California)</p> </li> <li> <p> US West (Oregon)</p> </li> <li> <p> Asia Pacific (Singapore)</p> </li> <li> <p>Asia Pacific (Sydney)</p> </li> <li> <p>Asia Pacific (Tokyo)</p> </li> <li> <p>Europe (Ireland)</p> </li> <li> <p>South America (São Paulo)</p> </li> </ul> <p>For a list of all the Amazon S3 supported Regions and endpoints, see <a href="https://docs.aws.amazon.com/general/latest/gr/rande.html#s3_region">Regions and Endpoints</a> in the AWS General Reference.</p> </note> </li> </ul> <p>For example, the following <code>x-amz-grant-read</code> header grants the AWS accounts identified by account IDs permissions to read object data and its metadata:</p> <p> <code>x-amz-grant-read: id=&quot;11112222333&quot;, id=&quot;444455556666&quot; </code> </p> </li> </ul> <note> <p>You can use either a canned ACL or specify access permissions explicitly. You cannot do both.</p> </note> <p>The following operations are related to <code>CreateBucket</code>:</p> <ul> <li> <p> <a>PutObject</a> </p> </li> <li> <p> <a>DeleteBucket</a> </p> </li> </ul></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn create_bucket(
+        &self,
+        input: CreateBucketRequest,
+    ) -> Result<CreateBucketOutput, Ks3Error<CreateBucketError>> {
+        validate_bucket_name(&input.bucket)?;
+        let request_uri = format!("/{bucket}", bucket = input.bucket);
+        let mut request = SignedRequest::new("PUT", "s3", &self.region, &request_uri);
+
+        request.add_optional_header("x-amz-acl", input.acl.as_ref());
+        request.add_optional_header(
+            "x-amz-grant-full-control",
+            input.grant_full_control.as_ref(),
+        );
+        request.add_optional_header("x-amz-grant-read", input.grant_read.as_ref());
+        request.add_optional_header("x-amz-grant-read-acp", input.grant_read_acp.as_ref());
+        request.add_optional_header("x-amz-grant-write", input.grant_write.as_ref());
+        request.add_optional_header("x-amz-grant-write-acp", input.grant_write_acp.as_ref());
+        request.add_optional_header(
+            "x-amz-bucket-object-lock-enabled",
+            input.object_lock_enabled_for_bucket.as_ref(),
+        );
+
+        if input.create_bucket_configuration.is_some() {
+            let mut writer = EventWriter::new(Vec::new());
+            CreateBucketConfigurationSerializer::serialize(
+                &mut writer,
+                "CreateBucketConfiguration",
+                input.create_bucket_configuration.as_ref().unwrap(),
+            );
+            request.set_payload(Some(writer.into_inner()));
+        } else {
+            request.set_payload(Some(Vec::new()));
+        }
+
+        let mut response = self
+            .sign_and_dispatch("CreateBucket", request, CreateBucketError::from_response)
+            .await?;
+
+        let result = CreateBucketOutput::default();
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        result.location = response.headers.remove("Location"); // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>Adds an object to a bucket. You must have WRITE permissions on a bucket to add an object to it.</p> <p>Amazon S3 never adds partial objects; if you receive a success response, Amazon S3 added the entire object to the bucket.</p> <p>Amazon S3 is a distributed system. If it receives multiple write requests for the same object simultaneously, it overwrites all but the last object written. Amazon S3 does not provide object locking; if you need this, make sure to build it into your application layer or use versioning instead.</p> <p>To ensure that data is not corrupted traversing the network, use the <code>Content-MD5</code> header. When you use this header, Amazon S3 checks the object against the provided MD5 value and, if they do not match, returns an error. Additionally, you can calculate the MD5 while putting an object to Amazon S3 and compare the returned ETag to the calculated MD5 value.</p> <note> <p> The <code>Content-MD5</code> header is required for any request to upload an object with a retention period configured using Amazon S3 Object Lock. For more information about Amazon S3 Object Lock, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/object-lock-overview.html">Amazon S3 Object Lock Overview</a> in the <i>Amazon Simple Storage Service Developer Guide</i>. </p> </note> <p> <b>Server-side Encryption</b> </p> <p>You can optionally request server-side encryption. With server-side encryption, Amazon S3 encrypts your data as it writes it to disks in its data centers and decrypts the data when you access it. You have the option to provide your own encryption key or use AWS managed encryption keys. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/UsingServerSideEncryption.html">Using Server-Side Encryption</a>.</p> <p> <b>Access Control List (ACL)-Specific Request Headers</b> </p> <p>You can use headers to grant ACL- based permissions. By default, all objects are private. Only the owner has full access control. When adding a new object, you can grant permissions to individual AWS accounts or to predefined groups defined by Amazon S3. These permissions are then added to the ACL on the object. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-overview.html">Access Control List (ACL) Overview</a> and <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/acl-using-rest-api.html">Managing ACLs Using the REST API</a>. </p> <p> <b>Storage Class Options</b> </p> <p>By default, Amazon S3 uses the STANDARD storage class to store newly created objects. The STANDARD storage class provides high durability and high availability. Depending on performance needs, you can specify a different storage class. For more information, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/storage-class-intro.html">Storage Classes</a> in the <i>Amazon S3 Service Developer Guide</i>.</p> <p> <b>Versioning</b> </p> <p>If you enable versioning for a bucket, Amazon S3 automatically generates a unique version ID for the object being stored. Amazon S3 returns this ID in the response. When you enable versioning for a bucket, if Amazon S3 receives multiple write requests for the same object simultaneously, it stores all of the objects.</p> <p>For more information about versioning, see <a href="https://docs.aws.amazon.com/AmazonS3/latest/dev/AddingObjectstoVersioningEnabledBuckets.html">Adding Objects to Versioning Enabled Buckets</a>. For information about returning the versioning state of a bucket, see <a>GetBucketVersioning</a>. </p> <p class="title"> <b>Related Resources</b> </p> <ul> <li> <p> <a>CopyObject</a> </p> </li> <li> <p> <a>DeleteObject</a> </p> </li> </ul></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn put_object(
+        &self,
+        input: PutObjectRequest,
+    ) -> Result<PutObjectOutput, Ks3Error<PutObjectError>> {
+        validate_key(&input.key)?;
+        #[cfg(feature = "encoding")]
+        let input = match self.config.compress_uploads {
+            Some(ref compression) => {
+                compression.compress_request(input, self.config.infer_content_type)
+            }
+            None => input,
+        };
+        if let Some(ref body) = input.body {
+            if let Some(err) = body_length_error(body, input.content_length) {
+                return Err(Ks3Error::Validation(err));
+            }
+        }
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("PUT", "s3", &self.region, &request_uri);
+
+        request.add_optional_header(
+            "x-amz-acl",
+            input.acl.as_ref().or(self.config.default_acl.as_ref()),
+        );
+        request.add_optional_header("Cache-Control", input.cache_control.as_ref());
+        request.add_optional_header("Content-Disposition", input.content_disposition.as_ref());
+        request.add_optional_header("Content-Encoding", input.content_encoding.as_ref());
+        request.add_optional_header("Content-Language", input.content_language.as_ref());
+        request.add_optional_header("Content-Length", input.content_length.as_ref());
+        request.add_optional_header("Content-MD5", input.content_md5.as_ref());
+        let content_type = input.content_type.as_deref().or_else(|| {
+            if self.config.infer_content_type {
+                mime_type_for_key(&input.key)
+            } else {
+                None
+            }
+        });
+        request.add_optional_header("Content-Type", content_type);
+        request.add_optional_header("Expires", input.expires.as_ref().map(format_http_date));
+        request.add_optional_header(
+            "x-amz-grant-full-control",
+            input.grant_full_control.as_ref(),
+        );
+        request.add_optional_header("x-amz-grant-read", input.grant_read.as_ref());
+        request.add_optional_header("x-amz-grant-read-acp", input.grant_read_acp.as_ref());
+        request.add_optional_header("x-amz-grant-write-acp", input.grant_write_acp.as_ref());
+
+        if let Some(ref metadata) = input.metadata {
+            for (header_name, header_value) in metadata.iter() {
+                let header = format!("x-amz-meta-{}", header_name);
+                request.add_header(header, header_value);
+            }
+        }
+        request.add_optional_header(
+            "x-amz-object-lock-legal-hold",
+            input.object_lock_legal_hold_status.as_ref(),
+        );
+        request.add_optional_header("x-amz-object-lock-mode", input.object_lock_mode.as_ref());
+        request.add_optional_header(
+            "x-amz-object-lock-retain-until-date",
+            input
+                .object_lock_retain_until_date
+                .as_ref()
+                .map(format_iso8601),
+        );
+        request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-algorithm",
+            input.sse_customer_algorithm.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key",
+            input.sse_customer_key.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key-MD5",
+            input.sse_customer_key_md5.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-context",
+            input.ssekms_encryption_context.as_ref(),
+        );
+        let server_side_encryption = input
+            .server_side_encryption
+            .as_ref()
+            .or_else(|| self.config.server_side_encryption_for(&input.bucket));
+        request.add_optional_header(
+            "x-amz-server-side-encryption-aws-kms-key-id",
+            input.ssekms_key_id.as_ref().or_else(|| {
+                self.config
+                    .ssekms_key_id_for(&input.bucket, server_side_encryption)
+            }),
+        );
+        request.add_optional_header("x-amz-server-side-encryption", server_side_encryption);
+        request.add_optional_header(
+            "x-amz-storage-class",
+            input
+                .storage_class
+                .as_ref()
+                .or(self.config.default_storage_class.as_ref()),
+        );
+        request.add_optional_header("x-amz-tagging", input.tagging.as_ref());
+        request.add_optional_header(
+            "x-amz-website-redirect-location",
+            input.website_redirect_location.as_ref(),
+        );
+
+        let mut digest = None;
+        if let Some(__body) = input.body {
+            match __body.buffered() {
+                Some(buffer) if self.config.compute_content_md5 && input.content_md5.is_none() => {
+                    digest = Some(format!("{:x}", md5::compute(buffer)));
+                    request.set_payload(Some(buffer.clone()));
+                    request.set_content_md5_header();
+                }
+                _ => request.set_payload_stream(__body),
+            }
+        }
+
+        let mut response = self
+            .sign_and_dispatch("PutObject", request, PutObjectError::from_response)
+            .await?;
+
+        let result = PutObjectOutput::default();
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        result.checksum_crc64ecma = response.headers.remove("x-kss-checksum-crc64ecma");
+        result.e_tag = response.headers.remove("ETag");
+        result.expiration = response.headers.remove("x-amz-expiration");
+        result.request_charged = response.headers.remove("x-amz-request-charged");
+        result.sse_customer_algorithm = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-algorithm");
+        result.sse_customer_key_md5 = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-key-MD5");
+        result.ssekms_encryption_context = response
+            .headers
+            .remove("x-amz-server-side-encryption-context");
+        result.ssekms_key_id = response
+            .headers
+            .remove("x-amz-server-side-encryption-aws-kms-key-id");
+        result.server_side_encryption = response
+            .headers
+            .remove("x-amz-server-side-encryption")
+            .map(Into::into);
+        result.version_id = response.headers.remove("x-amz-version-id"); // parse non-payload
+        if let Some(digest) = digest {
+            if let Some(e_tag) = mismatched_etag(
+                result.e_tag.as_deref(),
+                result.sse_customer_algorithm.as_deref(),
+                result.server_side_encryption.as_ref(),
+                &digest,
+            ) {
+                return Err(Ks3Error::IntegrityCheckFailed {
+                    expected: e_tag,
+                    actual: digest,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// <p><p>Returns some or all (up to 1,000) of the objects in a bucket. You can use the request parameters as selection criteria to return a subset of the objects in a bucket. A 200 OK response can contain valid or invalid XML. Be sure to design your application to parse the contents of the response and handle it appropriately.</p> <p>The following operations are related to <code>ListObjects</code>:</p> <ul> <li> <p> <a>GetObject</a> </p> </li> <li> <p> <a>PutObject</a> </p> </li> <li> <p> <a>CreateBucket</a> </p> </li> </ul></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn list_objects(
+        &self,
+        input: ListObjectsRequest,
+    ) -> Result<ListObjectsOutput, Ks3Error<ListObjectsError>> {
+        let request = list_objects_request(&self.region, &input);
+
+        let mut response = self
+            .sign_and_dispatch("ListObjects", request, ListObjectsError::from_response)
+            .await?;
+
+        let mut response = response;
+        let result = xml_util::parse_response(&mut response, |name, stack| {
+            ListObjectsOutputDeserializer::deserialize(name, stack)
+        })
+        .await?;
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>Removes the null version (if there is one) of an object and inserts a delete marker, which becomes the latest version of the object. If there isn't a null version, Amazon S3 does not remove any objects.</p> <p>To remove a specific version, you must be the bucket owner and you must use the version Id subresource. Using this subresource permanently deletes the version. If the object deleted is a delete marker, Amazon S3 sets the response header, <code>x-amz-delete-marker</code>, to true. </p> <p>The following operation is related to <code>DeleteObject</code>:</p> <ul> <li> <p> <a>PutObject</a> </p> </li> </ul></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn delete_object(
+        &self,
+        input: DeleteObjectRequest,
+    ) -> Result<DeleteObjectOutput, Ks3Error<DeleteObjectError>> {
+        validate_key_length(&input.key)?;
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("DELETE", "s3", &self.region, &request_uri);
+
+        request.add_optional_header("x-amz-mfa", input.mfa.as_ref());
+        request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+        let mut params = Params::new();
+        if let Some(ref x) = input.version_id {
+            params.put("versionId", x);
+        }
+        request.set_params(params);
+
+        let mut response = self
+            .sign_and_dispatch("DeleteObject", request, DeleteObjectError::from_response)
+            .await?;
+
+        let result = DeleteObjectOutput::default();
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        result.delete_marker = response
+            .headers
+            .remove("x-amz-delete-marker")
+            .map(|value| value.parse::<bool>().unwrap_or_default());
+        result.request_charged = response.headers.remove("x-amz-request-charged");
+        result.version_id = response.headers.remove("x-amz-version-id"); // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>Retrieves objects from Amazon S3. To use <code>GET</code>, you must have <code>READ</code> access to the object. If you grant <code>READ</code> access to the anonymous user, you can return the object without using an authorization header.</p> <p>To distribute large files to many people, you can save bandwidth costs by using BitTorrent. </p> <p> <b>Versioning</b> </p> <p>By default, the GET operation returns the current version of an object. To return a different version, use the <code>versionId</code> subresource.</p> <p> <b>Overriding Response Header Values</b> </p> <p>There are times when you want to override certain response header values in a GET response. For example, you might override the Content-Disposition response header value in your GET request. You can override values for a set of response headers using the <code>response-*</code> query parameters.</p> <p>The following operations are related to <code>GetObject</code>:</p> <ul> <li> <p> <a>ListObjects</a> </p> </li> <li> <p> <a>PutObject</a> </p> </li> </ul></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn get_object(
+        &self,
+        input: GetObjectRequest,
+    ) -> Result<GetObjectOutput, Ks3Error<GetObjectError>> {
+        validate_key_length(&input.key)?;
+        if let Some(ref cache) = self.config.object_cache {
+            return cached_get_object(self, input, cache).await;
+        }
+        if let Some(ref policy) = self.config.hedge_gets {
+            return hedged_get_object(self, input, policy).await;
+        }
+        self.send_get_object(input).await
+    }
+
+    /// <p>This operation is useful to determine if a bucket exists and you have permission to access it. The operation returns a <code>200 OK</code> if the bucket exists and you have permission to access it. Otherwise, the operation might return responses such as <code>404 Not Found</code> and <code>403 Forbidden</code>. </p> <p>To use this operation, you must have permissions to perform the <code>s3:ListBucket</code> action. The bucket owner has this permission by default and can grant this permission to others.</p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn head_bucket(&self, input: HeadBucketRequest) -> Result<(), Ks3Error<HeadBucketError>> {
+        let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+        let mut request = SignedRequest::new("HEAD", "s3", &self.region, &request_uri);
+
+        let mut response = self
+            .sign_and_dispatch("HeadBucket", request, HeadBucketError::from_response)
+            .await?;
+
+        std::mem::drop(response);
+        Ok(())
+    }
+
+    /// <p><p>The HEAD operation retrieves metadata from an object without returning the object itself. This operation is useful if you're only interested in an object's metadata. To use HEAD, you must have READ access to the object.</p> <p>A <code>HEAD</code> request has the same options as a <code>GET</code> operation on an object. The response is identical to the <code>GET</code> response except that there is no response body.</p> <p>If the object you request does not exist, the error Amazon S3 returns depends on whether you also have the s3:ListBucket permission.</p> <ul> <li> <p>If you have the <code>s3:ListBucket</code> permission on the bucket, Amazon S3 returns an HTTP status code 404 (&quot;no such key&quot;) error.</p> </li> <li> <p>If you don’t have the <code>s3:ListBucket</code> permission, Amazon S3 returns an HTTP status code 403 (&quot;access denied&quot;) error.</p> </li> </ul> <p>The following operation is related to <code>HeadObject</code>:</p> <ul> <li> <p> <a>GetObject</a> </p> </li> </ul></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn head_object(
+        &self,
+        input: HeadObjectRequest,
+    ) -> Result<HeadObjectOutput, Ks3Error<HeadObjectError>> {
+        validate_key_length(&input.key)?;
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("HEAD", "s3", &self.region, &request_uri);
+
+        request.add_optional_header("If-Match", input.if_match.as_ref());
+        request.add_optional_header(
+            "If-Modified-Since",
+            input.if_modified_since.as_ref().map(format_http_date),
+        );
+        request.add_optional_header("If-None-Match", input.if_none_match.as_ref());
+        request.add_optional_header(
+            "If-Unmodified-Since",
+            input.if_unmodified_since.as_ref().map(format_http_date),
+        );
+        request.add_optional_header("Range", input.range.as_ref());
+        request.add_optional_header(
+            "Accept-Encoding",
+            self.config
+                .accept_encoding
+                .as_ref()
+                .map(AcceptEncoding::header_value),
+        );
+        request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-algorithm",
+            input.sse_customer_algorithm.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key",
+            input.sse_customer_key.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key-MD5",
+            input.sse_customer_key_md5.as_ref(),
+        );
+        let mut params = Params::new();
+        if let Some(ref x) = input.part_number {
+            params.put("partNumber", x);
+        }
+        if let Some(ref x) = input.version_id {
+            params.put("versionId", x);
+        }
+        request.set_params(params);
+
+        let mut response = self
+            .sign_and_dispatch("HeadObject", request, HeadObjectError::from_response)
+            .await?;
+
+        let result = HeadObjectOutput::default();
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        result.accept_ranges = response.headers.remove("accept-ranges");
+        result.cache_control = response.headers.remove("Cache-Control");
+        result.checksum_crc64ecma = response.headers.remove("x-kss-checksum-crc64ecma");
+        result.content_disposition = response.headers.remove("Content-Disposition");
+        result.content_encoding = response.headers.remove("Content-Encoding");
+        result.content_language = response.headers.remove("Content-Language");
+        result.content_length = response
+            .headers
+            .remove("Content-Length")
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        result.content_type = response.headers.remove("Content-Type");
+        result.delete_marker = response
+            .headers
+            .remove("x-amz-delete-marker")
+            .and_then(|value| value.trim().parse::<bool>().ok());
+        result.e_tag = response.headers.remove("ETag");
+        result.expiration = response.headers.remove("x-amz-expiration");
+        result.expires = response
+            .headers
+            .remove("Expires")
+            .and_then(|value| parse_http_date(&value));
+        result.last_modified = response
+            .headers
+            .remove("Last-Modified")
+            .and_then(|value| parse_http_date(&value));
+        let mut values = Metadata::new();
+        for (key, value) in response.headers.iter() {
+            if key.as_str().starts_with("x-amz-meta-") {
+                values.insert_unchecked(&key.as_str()["x-amz-meta-".len()..], value.to_owned());
+            }
+        }
+        result.metadata = Some(values);
+        result.missing_meta = response
+            .headers
+            .remove("x-amz-missing-meta")
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        result.object_lock_legal_hold_status =
+            response.headers.remove("x-amz-object-lock-legal-hold");
+        result.object_lock_mode = response
+            .headers
+            .remove("x-amz-object-lock-mode")
+            .map(Into::into);
+        result.object_lock_retain_until_date = response
+            .headers
+            .remove("x-amz-object-lock-retain-until-date")
+            .and_then(|value| parse_iso8601(&value));
+        result.parts_count = response
+            .headers
+            .remove("x-amz-mp-parts-count")
+            .and_then(|value| value.trim().parse::<i64>().ok());
+        result.replication_status = response.headers.remove("x-amz-replication-status");
+        result.request_charged = response.headers.remove("x-amz-request-charged");
+        result.restore = response.headers.remove("x-amz-restore");
+        result.sse_customer_algorithm = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-algorithm");
+        result.sse_customer_key_md5 = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-key-MD5");
+        result.ssekms_key_id = response
+            .headers
+            .remove("x-amz-server-side-encryption-aws-kms-key-id");
+        result.server_side_encryption = response
+            .headers
+            .remove("x-amz-server-side-encryption")
+            .map(Into::into);
+        result.storage_class = response
+            .headers
+            .remove("x-amz-storage-class")
+            .map(Into::into);
+        result.version_id = response.headers.remove("x-amz-version-id");
+        result.website_redirect_location =
+            response.headers.remove("x-amz-website-redirect-location"); // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>This operation initiates a multipart upload and returns an upload ID. This upload ID is used to associate all of the parts in the specific multipart upload. You specify this upload ID in each of your subsequent upload part requests (see <a>UploadPart</a>). You also include this upload ID in the final request to either complete or abort the multipart upload request.</p> <p>The following operations are related to <code>CreateMultipartUpload</code>:</p> <ul> <li> <p> <a>UploadPart</a> </p> </li> <li> <p> <a>CompleteMultipartUpload</a> </p> </li> <li> <p> <a>AbortMultipartUpload</a> </p> </li> </ul></p>
+    #[cfg(feature = "multipart")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn create_multipart_upload(
+        &self,
+        input: CreateMultipartUploadRequest,
+    ) -> Result<CreateMultipartUploadOutput, Ks3Error<CreateMultipartUploadError>> {
+        ops::create_multipart_upload::send(self, input).await
+    }
+
+    /// <p><p>Uploads a part in a multipart upload.</p> <note> <p>In this operation, you provide part data in your request. However, you have an option to specify your existing Amazon S3 object as a data source for the part you are uploading.</p> </note> <p>You must initiate a multipart upload (see <a>CreateMultipartUpload</a>) before you can upload any part. In response to your initiate request, Amazon S3 returns an upload ID, a unique identifier, that you must include in your upload part request.</p> <p>Part numbers can be any number from 1 to 10,000, inclusive. A part number uniquely identifies a part and also defines its position within the object being created. If you upload a new part using the same part number that was used with a previous part, the previously uploaded part is overwritten. Each part must be at least 5 MB in size, except the last part.</p></p>
+    #[cfg(feature = "multipart")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn upload_part(
+        &self,
+        input: UploadPartRequest,
+    ) -> Result<UploadPartOutput, Ks3Error<UploadPartError>> {
+        ops::upload_part::send(self, input).await
+    }
+
+    /// <p><p>Completes a multipart upload by assembling previously uploaded parts.</p> <p>You first initiate the multipart upload and then upload all parts using the <a>UploadPart</a> operation. After successfully uploading all relevant parts of an upload, you call this operation to complete the upload. Upon receiving this request, Amazon S3 concatenates all the parts in ascending order by part number to create a new object. In the Complete Multipart Upload request, you must provide the parts list. You must ensure that the parts list is complete.</p> <p>Processing of a Complete Multipart Upload request could take several minutes to complete. While processing is in progress, Amazon S3 periodically sends white space characters to keep the connection from timing out. Because a request could fail after the initial 200 OK response has been sent, it is important that you check the response body to determine whether the request succeeded.</p></p>
+    #[cfg(feature = "multipart")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn complete_multipart_upload(
+        &self,
+        input: CompleteMultipartUploadRequest,
+    ) -> Result<CompleteMultipartUploadOutput, Ks3Error<CompleteMultipartUploadError>> {
+        ops::complete_multipart_upload::send(self, input).await
+    }
+
+    /// <p><p>This operation aborts a multipart upload. After a multipart upload is aborted, no additional parts can be uploaded using that upload ID. The storage consumed by any previously uploaded parts will be freed. However, if any part uploads are currently in progress, those part uploads might or might not succeed. As a result, it might be necessary to abort a given multipart upload multiple times in order to completely free all storage consumed by all parts.</p></p>
+    #[cfg(feature = "multipart")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn abort_multipart_upload(
+        &self,
+        input: AbortMultipartUploadRequest,
+    ) -> Result<AbortMultipartUploadOutput, Ks3Error<AbortMultipartUploadError>> {
+        ops::abort_multipart_upload::send(self, input).await
+    }
+
+    /// <p><p>Sets the supplied tag-set to an object that already exists in a bucket.</p> <p>A tag is a key-value pair. You can associate tags with an object by sending a PUT request against the tagging subresource that is associated with the object. To replace the tag-set of an object, send the complete new set; the existing one is overwritten.</p></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn put_object_tagging(
+        &self,
+        input: PutObjectTaggingRequest,
+    ) -> Result<PutObjectTaggingOutput, Ks3Error<PutObjectTaggingError>> {
+        validate_key_length(&input.key)?;
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("PUT", "s3", &self.region, &request_uri);
+
+        request.add_optional_header("Content-MD5", input.content_md5.as_ref());
+        let mut params = Params::new();
+        if let Some(ref x) = input.version_id {
+            params.put("versionId", x);
+        }
+        params.put_key("tagging");
+        request.set_params(params);
+        let mut writer = EventWriter::new(Vec::new());
+        TaggingSerializer::serialize(&mut writer, "Tagging", &input.tagging);
+        request.set_payload(Some(writer.into_inner()));
+        request.set_content_md5_header();
+
+        let mut response = self
+            .sign_and_dispatch(
+                "PutObjectTagging",
+                request,
+                PutObjectTaggingError::from_response,
+            )
+            .await?;
+
+        let result = PutObjectTaggingOutput::default();
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        result.version_id = response.headers.remove("x-amz-version-id"); // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>Uses the <code>acl</code> subresource to set the access control list (ACL) permissions for an object that already exists in a bucket. You must have <code>WRITE_ACP</code> permission to set the ACL of an object.</p> <p>Depending on your application needs, you can choose to set the ACL on an object using either the request body or the headers, e.g. a canned ACL with the <code>x-amz-acl</code> header. You cannot do both.</p></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn put_object_acl(
+        &self,
+        input: PutObjectAclRequest,
+    ) -> Result<PutObjectAclOutput, Ks3Error<PutObjectAclError>> {
+        validate_key_length(&input.key)?;
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("PUT", "s3", &self.region, &request_uri);
+
+        request.add_optional_header("x-amz-acl", input.acl.as_ref());
+        request.add_optional_header("Content-MD5", input.content_md5.as_ref());
+        request.add_optional_header(
+            "x-amz-grant-full-control",
+            input.grant_full_control.as_ref(),
+        );
+        request.add_optional_header("x-amz-grant-read", input.grant_read.as_ref());
+        request.add_optional_header("x-amz-grant-read-acp", input.grant_read_acp.as_ref());
+        request.add_optional_header("x-amz-grant-write", input.grant_write.as_ref());
+        request.add_optional_header("x-amz-grant-write-acp", input.grant_write_acp.as_ref());
+        request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+        let mut params = Params::new();
+        if let Some(ref x) = input.version_id {
+            params.put("versionId", x);
+        }
+        params.put_key("acl");
+        request.set_params(params);
+        if input.access_control_policy.is_some() {
+            let mut writer = EventWriter::new(Vec::new());
+            AccessControlPolicySerializer::serialize(
+                &mut writer,
+                "AccessControlPolicy",
+                input.access_control_policy.as_ref().unwrap(),
+            );
+            request.set_payload(Some(writer.into_inner()));
+        } else {
+            request.set_payload(Some(Vec::new()));
+        }
+
+        let mut response = self
+            .sign_and_dispatch("PutObjectAcl", request, PutObjectAclError::from_response)
+            .await?;
+
+        let result = PutObjectAclOutput::default();
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        result.request_charged = response.headers.remove("x-amz-request-charged"); // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>Creates a copy of an object that is already stored in Amazon S3.</p> <p>Copying an object onto itself is allowed as long as something else changes at the same time, e.g. the storage class, the metadata (with a <code>REPLACE</code> metadata directive) or the encryption settings. A copy request might return an error after the initial <code>200 OK</code> response has been sent, in which case the error is carried in the response body.</p></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn copy_object(
+        &self,
+        input: CopyObjectRequest,
+    ) -> Result<CopyObjectOutput, Ks3Error<CopyObjectError>> {
+        validate_key(&input.key)?;
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("PUT", "s3", &self.region, &request_uri);
+
+        request.add_optional_header("x-amz-acl", input.acl.as_ref());
+        request.add_optional_header("Cache-Control", input.cache_control.as_ref());
+        request.add_optional_header("Content-Disposition", input.content_disposition.as_ref());
+        request.add_optional_header("Content-Encoding", input.content_encoding.as_ref());
+        request.add_optional_header("Content-Language", input.content_language.as_ref());
+        request.add_optional_header("Content-Type", input.content_type.as_ref());
+        request.add_header("x-amz-copy-source", &input.copy_source.to_string());
+        request.add_optional_header(
+            "x-amz-copy-source-if-match",
+            input.copy_source_if_match.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-copy-source-if-modified-since",
+            input
+                .copy_source_if_modified_since
+                .as_ref()
+                .map(format_http_date),
+        );
+        request.add_optional_header(
+            "x-amz-copy-source-if-none-match",
+            input.copy_source_if_none_match.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-copy-source-if-unmodified-since",
+            input
+                .copy_source_if_unmodified_since
+                .as_ref()
+                .map(format_http_date),
+        );
+        request.add_optional_header(
+            "x-amz-copy-source-server-side-encryption-customer-algorithm",
+            input.copy_source_sse_customer_algorithm.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-copy-source-server-side-encryption-customer-key",
+            input.copy_source_sse_customer_key.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-copy-source-server-side-encryption-customer-key-MD5",
+            input.copy_source_sse_customer_key_md5.as_ref(),
+        );
+        request.add_optional_header("Expires", input.expires.as_ref().map(format_http_date));
+        request.add_optional_header(
+            "x-amz-grant-full-control",
+            input.grant_full_control.as_ref(),
+        );
+        request.add_optional_header("x-amz-grant-read", input.grant_read.as_ref());
+        request.add_optional_header("x-amz-grant-read-acp", input.grant_read_acp.as_ref());
+        request.add_optional_header("x-amz-grant-write-acp", input.grant_write_acp.as_ref());
+
+        if let Some(ref metadata) = input.metadata {
+            for (header_name, header_value) in metadata.iter() {
+                let header = format!("x-amz-meta-{}", header_name);
+                request.add_header(header, header_value);
+            }
+        }
+        request.add_optional_header(
+            "x-amz-metadata-directive",
+            input.metadata_directive.as_ref(),
+        );
+        request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-algorithm",
+            input.sse_customer_algorithm.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key",
+            input.sse_customer_key.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key-MD5",
+            input.sse_customer_key_md5.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-aws-kms-key-id",
+            input.ssekms_key_id.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption",
+            input.server_side_encryption.as_ref(),
+        );
+        request.add_optional_header("x-amz-storage-class", input.storage_class.as_ref());
+        request.add_optional_header("x-amz-tagging", input.tagging.as_ref());
+        request.add_optional_header("x-amz-tagging-directive", input.tagging_directive.as_ref());
+        request.add_optional_header(
+            "x-amz-website-redirect-location",
+            input.website_redirect_location.as_ref(),
+        );
+
+        let mut response = self
+            .sign_and_dispatch("CopyObject", request, CopyObjectError::from_response)
+            .await?;
+
+        let mut response = response;
+        let result = xml_util::parse_response(&mut response, |name, stack| {
+            CopyObjectOutputDeserializer::deserialize(name, stack)
+        })
+        .await?;
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        result.copy_source_version_id = response.headers.remove("x-amz-copy-source-version-id");
+        result.expiration = response.headers.remove("x-amz-expiration");
+        result.request_charged = response.headers.remove("x-amz-request-charged");
+        result.sse_customer_algorithm = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-algorithm");
+        result.sse_customer_key_md5 = response
+            .headers
+            .remove("x-amz-server-side-encryption-customer-key-MD5");
+        result.ssekms_key_id = response
+            .headers
+            .remove("x-amz-server-side-encryption-aws-kms-key-id");
+        result.server_side_encryption = response
+            .headers
+            .remove("x-amz-server-side-encryption")
+            .map(Into::into);
+        result.version_id = response.headers.remove("x-amz-version-id"); // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>Uploads a part by copying data from an existing object as data source. You specify the data source by adding the request header <code>x-amz-copy-source</code> in your request and a byte range by adding the request header <code>x-amz-copy-source-range</code> in your request.</p> <p>The minimum allowable part size for a multipart upload is 5 MB, and copying is the only way to build objects larger than 5 GB from existing ones.</p></p>
+    #[cfg(feature = "multipart")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn upload_part_copy(
+        &self,
+        input: UploadPartCopyRequest,
+    ) -> Result<UploadPartCopyOutput, Ks3Error<UploadPartCopyError>> {
+        ops::upload_part_copy::send(self, input).await
+    }
+
+    /// <p><p>Deletes the bucket. All objects (including all object versions and delete markers) in the bucket must be deleted before the bucket itself can be deleted.</p></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn delete_bucket(
+        &self,
+        input: DeleteBucketRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketError>> {
+        let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+        let mut request = SignedRequest::new("DELETE", "s3", &self.region, &request_uri);
+
+        let mut response = self
+            .sign_and_dispatch("DeleteBucket", request, DeleteBucketError::from_response)
+            .await?;
+
+        std::mem::drop(response);
+        Ok(())
+    }
+
+    /// <p>Returns a list of all buckets owned by the authenticated sender of the request.</p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self))]
+    async fn list_buckets(&self) -> Result<ListBucketsOutput, Ks3Error<ListBucketsError>> {
+        let request_uri = "/";
+
+        let mut request = SignedRequest::new("GET", "s3", &self.region, &request_uri);
+
+        let mut response = self
+            .sign_and_dispatch("ListBuckets", request, ListBucketsError::from_response)
+            .await?;
+
+        let mut response = response;
+        let result = xml_util::parse_response(&mut response, |name, stack| {
+            ListBucketsOutputDeserializer::deserialize(name, stack)
+        })
+        .await?;
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>Returns the Region the bucket resides in. You set the bucket's Region using the <code>LocationConstraint</code> request parameter in a <code>CreateBucket</code> request.</p> <p>To use this operation, you must be the bucket owner.</p></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn get_bucket_location(
+        &self,
+        input: GetBucketLocationRequest,
+    ) -> Result<GetBucketLocationOutput, Ks3Error<GetBucketLocationError>> {
+        let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+        let mut request = SignedRequest::new("GET", "s3", &self.region, &request_uri);
+
+        let mut params = Params::new();
+        params.put_key("location");
+        request.set_params(params);
+
+        let mut response = self
+            .sign_and_dispatch(
+                "GetBucketLocation",
+                request,
+                GetBucketLocationError::from_response,
+            )
+            .await?;
+
+        let mut response = response;
+        let result = xml_util::parse_response(&mut response, |name, stack| {
+            GetBucketLocationOutputDeserializer::deserialize(name, stack)
+        })
+        .await?;
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>This operation lists in-progress multipart uploads. An in-progress multipart upload is a multipart upload that has been initiated using the Initiate Multipart Upload request, but has not yet been completed or aborted.</p> <p>This operation returns at most 1,000 multipart uploads in the response. 1,000 multipart uploads is the maximum number of uploads a response can include, which is also the default value. You can further limit the number of uploads in a response by specifying the <code>max-uploads</code> parameter in the response. If additional multipart uploads satisfy the list criteria, the response will contain an <code>IsTruncated</code> element with the value true. To list the additional multipart uploads, use the <code>key-marker</code> and <code>upload-id-marker</code> request parameters.</p></p>
+    #[cfg(feature = "multipart")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn list_multipart_uploads(
+        &self,
+        input: ListMultipartUploadsRequest,
+    ) -> Result<ListMultipartUploadsOutput, Ks3Error<ListMultipartUploadsError>> {
+        ops::list_multipart_uploads::send(self, input).await
+    }
+
+    /// <p><p>Lists the parts that have been uploaded for a specific multipart upload. This operation must include the upload ID, which you obtain by sending the initiate multipart upload request (see <a>CreateMultipartUpload</a>).</p> <p>This request returns a maximum of 1,000 uploaded parts. If the upload has more parts, the response is truncated, with <code>IsTruncated</code> set to true and a <code>NextPartNumberMarker</code> to send as the <code>part-number-marker</code> of the next request.</p></p>
+    #[cfg(feature = "multipart")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn list_parts(
+        &self,
+        input: ListPartsRequest,
+    ) -> Result<ListPartsOutput, Ks3Error<ListPartsError>> {
+        ops::list_parts::send(self, input).await
+    }
+
+    /// <p><p>Returns metadata about all of the versions of objects in a bucket. You can also use request parameters as selection criteria to return metadata about a subset of all the object versions. A 200 OK response can contain valid or invalid XML. Make sure to design your application to parse the contents of the response and handle it appropriately.</p> <p>To use this operation, you must have READ access to the bucket.</p> <p>The following operations are related to <code>ListObjectVersions</code>:</p> <ul> <li> <p> <a>ListObjects</a> </p> </li> <li> <p> <a>GetObject</a> </p> </li> <li> <p> <a>DeleteObject</a> </p> </li> </ul></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn list_object_versions(
+        &self,
+        input: ListObjectVersionsRequest,
+    ) -> Result<ListObjectVersionsOutput, Ks3Error<ListObjectVersionsError>> {
+        let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+        let mut request = SignedRequest::new("GET", "s3", &self.region, &request_uri);
+
+        let mut params = Params::new();
+        if let Some(ref x) = input.delimiter {
+            params.put("delimiter", x);
+        }
+        if let Some(ref x) = input.encoding_type {
+            params.put("encoding-type", x);
+        }
+        if let Some(ref x) = input.key_marker {
+            params.put("key-marker", x);
+        }
+        if let Some(ref x) = input.max_keys {
+            params.put("max-keys", x);
+        }
+        if let Some(ref x) = input.prefix {
+            params.put("prefix", x);
+        }
+        if let Some(ref x) = input.version_id_marker {
+            params.put("version-id-marker", x);
+        }
+        params.put_key("versions");
+        request.set_params(params);
+
+        let mut response = self
+            .sign_and_dispatch(
+                "ListObjectVersions",
+                request,
+                ListObjectVersionsError::from_response,
+            )
+            .await?;
+
+        let mut response = response;
+        let result = xml_util::parse_response(&mut response, |name, stack| {
+            ListObjectVersionsOutputDeserializer::deserialize(name, stack)
+        })
+        .await?;
+        let mut result = result;
+        result.raw_headers = response.headers.clone();
+        // parse non-payload
+        Ok(result)
+    }
+
+    /// <p><p>This operation filters the contents of an Amazon S3 object based on a simple structured query language (SQL) statement. In the request, along with the SQL expression, you must also specify a data serialization format (JSON, CSV, or Apache Parquet) of the object. Amazon S3 uses this format to parse object data into records, and returns only records that match the specified SQL expression. You must also specify the data serialization format for the response.</p> <p>The response is a stream of events: batches of records, progress and statistics messages, and a final end event. An error found while scanning the object is reported as an event of the stream rather than as an error of the request.</p></p>
+    #[allow(unused_variables, warnings)]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn select_object_content(
+        &self,
+        input: SelectObjectContentRequest,
+    ) -> Result<SelectObjectContentOutput, Ks3Error<SelectObjectContentError>> {
+        validate_key_length(&input.key)?;
+        let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+        let mut request = SignedRequest::new("POST", "s3", &self.region, &request_uri);
+
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-algorithm",
+            input.sse_customer_algorithm.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key",
+            input.sse_customer_key.as_ref(),
+        );
+        request.add_optional_header(
+            "x-amz-server-side-encryption-customer-key-MD5",
+            input.sse_customer_key_md5.as_ref(),
+        );
+        let mut params = Params::new();
+        params.put_key("select");
+        params.put("select-type", "2");
+        request.set_params(params);
+        let mut writer = EventWriter::new(Vec::new());
+        SelectObjectContentRequestSerializer::serialize(
+            &mut writer,
+            "SelectObjectContentRequest",
+            &input,
+        );
+        request.set_payload(Some(writer.into_inner()));
+
+        let mut response = self
+            .sign_and_dispatch(
+                "SelectObjectContent",
+                request,
+                SelectObjectContentError::from_response,
+            )
+            .await?;
+
+        let mut result = SelectObjectContentOutput::default();
+        result.raw_headers = response.headers.clone();
+        result.payload = Some(SelectObjectContentEventStream::new(response.body));
+        Ok(result)
+    }
+
+    /// <p><p>Sets a metrics configuration (specified by the metrics configuration ID) for the bucket. You can have up to 1,000 metrics configurations per bucket. If you're updating an existing metrics configuration, note that this is a full replacement of the existing metrics configuration. If you don't include the elements you want to keep, they are erased.</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutMetricsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    #[cfg(feature = "bucket-metrics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, id = %input.id))]
+    async fn put_bucket_metrics_configuration(
+        &self,
+        input: PutBucketMetricsConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketMetricsConfigurationError>> {
+        ops::put_bucket_metrics_configuration::send(self, input).await
+    }
+
+    /// <p><p>Gets a metrics configuration (specified by the metrics configuration ID) from the bucket. Note that this doesn't include the daily storage metrics.</p> <p>To use this operation, you must have permissions to perform the <code>s3:GetMetricsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    #[cfg(feature = "bucket-metrics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, id = %input.id))]
+    async fn get_bucket_metrics_configuration(
+        &self,
+        input: GetBucketMetricsConfigurationRequest,
+    ) -> Result<GetBucketMetricsConfigurationOutput, Ks3Error<GetBucketMetricsConfigurationError>>
+    {
+        ops::get_bucket_metrics_configuration::send(self, input).await
+    }
+
+    /// <p><p>Deletes a metrics configuration for the request metrics (specified by the metrics configuration ID) from the bucket. Note that this doesn't include the daily storage metrics.</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutMetricsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    #[cfg(feature = "bucket-metrics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, id = %input.id))]
+    async fn delete_bucket_metrics_configuration(
+        &self,
+        input: DeleteBucketMetricsConfigurationRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketMetricsConfigurationError>> {
+        ops::delete_bucket_metrics_configuration::send(self, input).await
+    }
+
+    /// <p><p>Lists the metrics configurations for the bucket. The metrics configurations are only for the request metrics of the bucket and do not provide information on daily storage metrics. You can have up to 1,000 configurations per bucket.</p> <p>This operation supports list pagination and does not return more than 100 configurations at a time. Always check the <code>IsTruncated</code> element in the response. If there are no more configurations to list, <code>IsTruncated</code> is set to false. If there are more configurations to list, <code>IsTruncated</code> is set to true, and there is a value in <code>NextContinuationToken</code>. You use the <code>NextContinuationToken</code> value to continue the pagination of the list by passing the value in <code>continuation-token</code> in the request to <code>GET</code> the next page.</p></p>
+    #[cfg(feature = "bucket-metrics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn list_bucket_metrics_configurations(
+        &self,
+        input: ListBucketMetricsConfigurationsRequest,
+    ) -> Result<ListBucketMetricsConfigurationsOutput, Ks3Error<ListBucketMetricsConfigurationsError>>
+    {
+        ops::list_bucket_metrics_configurations::send(self, input).await
+    }
+
+    /// <p><p>Sets an analytics configuration for the bucket (specified by the analytics configuration ID). You can have up to 1,000 analytics configurations per bucket.</p> <p>You can choose to have storage class analysis export analysis reports sent to a comma-separated values (CSV) flat file. Reports are updated daily and are based on the object filters that you configure. When selecting data export, you specify a destination bucket and an optional destination prefix where the file is written.</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutAnalyticsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    #[cfg(feature = "bucket-analytics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, id = %input.id))]
+    async fn put_bucket_analytics_configuration(
+        &self,
+        input: PutBucketAnalyticsConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketAnalyticsConfigurationError>> {
+        ops::put_bucket_analytics_configuration::send(self, input).await
+    }
+
+    /// <p><p>This operation returns an analytics configuration (identified by the analytics configuration ID) from the bucket.</p> <p>To use this operation, you must have permissions to perform the <code>s3:GetAnalyticsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    #[cfg(feature = "bucket-analytics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, id = %input.id))]
+    async fn get_bucket_analytics_configuration(
+        &self,
+        input: GetBucketAnalyticsConfigurationRequest,
+    ) -> Result<GetBucketAnalyticsConfigurationOutput, Ks3Error<GetBucketAnalyticsConfigurationError>>
+    {
+        ops::get_bucket_analytics_configuration::send(self, input).await
+    }
+
+    /// <p><p>Deletes an analytics configuration for the bucket (specified by the analytics configuration ID).</p> <p>To use this operation, you must have permissions to perform the <code>s3:PutAnalyticsConfiguration</code> action. The bucket owner has this permission by default.</p></p>
+    #[cfg(feature = "bucket-analytics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, id = %input.id))]
+    async fn delete_bucket_analytics_configuration(
+        &self,
+        input: DeleteBucketAnalyticsConfigurationRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketAnalyticsConfigurationError>> {
+        ops::delete_bucket_analytics_configuration::send(self, input).await
+    }
+
+    /// <p><p>Lists the analytics configurations for the bucket. You can have up to 1,000 analytics configurations per bucket.</p> <p>This operation supports list pagination and does not return more than 100 configurations at a time. You should always check the <code>IsTruncated</code> element in the response. If there are no more configurations to list, <code>IsTruncated</code> is set to false. If there are more configurations to list, <code>IsTruncated</code> is set to true, and there will be a value in <code>NextContinuationToken</code>. You use the <code>NextContinuationToken</code> value to continue the pagination of the list by passing the value in continuation-token in the request to <code>GET</code> the next page.</p></p>
+    #[cfg(feature = "bucket-analytics")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn list_bucket_analytics_configurations(
+        &self,
+        input: ListBucketAnalyticsConfigurationsRequest,
+    ) -> Result<
+        ListBucketAnalyticsConfigurationsOutput,
+        Ks3Error<ListBucketAnalyticsConfigurationsError>,
+    > {
+        ops::list_bucket_analytics_configurations::send(self, input).await
+    }
+
+    /// <p><p>Sets the lifecycle configuration of the bucket, replacing the current one as a whole: rules left out of the new configuration are removed. Its rules move objects to other storage classes, such as <code>ARCHIVE</code>, and delete them as they age.</p> <p>It can take a while for a new configuration to apply to every object of the bucket.</p></p>
+    #[cfg(feature = "bucket-lifecycle")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn put_bucket_lifecycle_configuration(
+        &self,
+        input: PutBucketLifecycleConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketLifecycleConfigurationError>> {
+        ops::put_bucket_lifecycle_configuration::send(self, input).await
+    }
+
+    /// <p>Returns the lifecycle configuration of the bucket, failing with <code>NoSuchLifecycleConfiguration</code> when it has none.</p>
+    #[cfg(feature = "bucket-lifecycle")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket))]
+    async fn get_bucket_lifecycle_configuration(
+        &self,
+        input: GetBucketLifecycleConfigurationRequest,
+    ) -> Result<GetBucketLifecycleConfigurationOutput, Ks3Error<GetBucketLifecycleConfigurationError>>
+    {
+        ops::get_bucket_lifecycle_configuration::send(self, input).await
+    }
+
+    /// <p><p>Restores a temporary copy of an archived object, readable for the number of days of the request. The restore completes in the background; until then, the <code>restore</code> header of <code>HeadObject</code> reports <code>ongoing-request="true"</code>.</p> <p>Sending the request again for an object already restored extends the life of the restored copy.</p></p>
+    #[cfg(feature = "restore-object")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key))]
+    async fn restore_object(
+        &self,
+        input: RestoreObjectRequest,
+    ) -> Result<RestoreObjectOutput, Ks3Error<RestoreObjectError>> {
+        ops::restore_object::send(self, input).await
+    }
+
+    /// <p><p>Appends bytes to an appendable object, creating it with the first append at position 0. Each append has to be sent at the current length of the object, returned as the next append position of the previous one, so concurrent writers to the same object fail rather than overwrite each other.</p> <p>An object created by appending can't be uploaded to in parts, and an object uploaded with <code>PutObject</code> can't be appended to.</p></p>
+    #[cfg(feature = "append-object")]
+    #[instrument(skip(self, input), fields(bucket = %input.bucket, key = %input.key, position = input.position))]
+    async fn append_object(
+        &self,
+        input: AppendObjectRequest,
+    ) -> Result<AppendObjectOutput, Ks3Error<AppendObjectError>> {
+        ops::append_object::send(self, input).await
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::credential::StaticProvider;
+    use crate::test::MockRequestDispatcher;
+
+    fn client(dispatcher: MockRequestDispatcher) -> S3Client {
+        S3Client::new_with(
+            dispatcher,
+            StaticProvider::new_minimal("a".to_owned(), "b".to_owned()),
+            Region::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn get_object_skips_malformed_headers() {
+        let client = client(
+            MockRequestDispatcher::with_status(200)
+                .with_header("x-amz-delete-marker", "yes")
+                .with_header("x-amz-missing-meta", "many")
+                .with_header("x-amz-mp-parts-count", "-")
+                .with_body("data"),
+        );
+        let output = client
+            .get_object(GetObjectRequest {
+                bucket: "bucket".to_owned(),
+                key: "key".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.delete_marker, None);
+        assert_eq!(output.missing_meta, None);
+        assert_eq!(output.parts_count, None);
+    }
+
+    #[tokio::test]
+    async fn head_object_skips_malformed_headers() {
+        let client = client(
+            MockRequestDispatcher::with_status(200)
+                .with_header("x-amz-delete-marker", "yes")
+                .with_header("x-amz-missing-meta", "many")
+                .with_header("x-amz-mp-parts-count", "-"),
+        );
+        let output = client
+            .head_object(HeadObjectRequest {
+                bucket: "bucket".to_owned(),
+                key: "key".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.delete_marker, None);
+        assert_eq!(output.missing_meta, None);
+        assert_eq!(output.parts_count, None);
+    }
+}
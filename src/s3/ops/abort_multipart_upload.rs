@@ -0,0 +1,123 @@
+//! The `AbortMultipartUpload` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventReader;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::error::{XmlError, XmlErrorDeserializer};
+use crate::core::proto::xml::util::find_start_element;
+use crate::core::proto::xml::util::{Next, Peek, XmlParseError, XmlResponse};
+use crate::core::request::BufferedHttpResponse;
+use crate::enums::RequestPayer;
+use crate::key::validate_key_length;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AbortMultipartUploadOutput {
+    pub request_charged: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AbortMultipartUploadRequest {
+    /// <p>The bucket name to which the upload was taking place. </p>
+    pub bucket: String,
+    /// <p>Key of the object for which the multipart upload was initiated.</p>
+    pub key: String,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>Upload ID that identifies the multipart upload.</p>
+    pub upload_id: String,
+}
+
+/// Errors returned by AbortMultipartUpload
+#[derive(Debug, PartialEq)]
+pub enum AbortMultipartUploadError {
+    /// <p>The specified multipart upload does not exist.</p>
+    NoSuchUpload(String),
+}
+
+impl AbortMultipartUploadError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<AbortMultipartUploadError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "NoSuchUpload" {
+                    return Ks3Error::Service(AbortMultipartUploadError::NoSuchUpload(
+                        parsed_error.message,
+                    ));
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for AbortMultipartUploadError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AbortMultipartUploadError::NoSuchUpload(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for AbortMultipartUploadError {}
+
+/// Send a `AbortMultipartUpload` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: AbortMultipartUploadRequest,
+) -> Result<AbortMultipartUploadOutput, Ks3Error<AbortMultipartUploadError>> {
+    validate_key_length(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("DELETE", "s3", &client.region, &request_uri);
+
+    request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+    let mut params = Params::new();
+    params.put("uploadId", &input.upload_id);
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "AbortMultipartUpload",
+            request,
+            AbortMultipartUploadError::from_response,
+        )
+        .await?;
+
+    let result = AbortMultipartUploadOutput::default();
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    result.request_charged = response.headers.remove("x-amz-request-charged"); // parse non-payload
+    Ok(result)
+}
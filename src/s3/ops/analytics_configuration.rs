@@ -0,0 +1,481 @@
+//! The analytics configurations of buckets, shared by the operations managing them.
+
+use std::io::Write;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::reader::XmlEvent;
+use xml::EventWriter;
+
+use crate::core::proto::xml::util::{deserialize_elements, skip_tree, write_characters_element};
+use crate::core::proto::xml::util::{Next, Peek, XmlParseError};
+use crate::request::*;
+
+/// <p>A conjunction (logical AND) of predicates, which is used in evaluating a metrics filter. The operator must have at least two predicates in any combination, and an object must match all of the predicates for the filter to apply.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AnalyticsAndOperator {
+    /// <p>The prefix to use when evaluating an AND predicate: The prefix that an object must have to be included in the metrics results.</p>
+    pub prefix: Option<String>,
+    /// <p>The list of tags to use when evaluating an AND predicate.</p>
+    pub tags: Option<Vec<Tag>>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct AnalyticsAndOperatorDeserializer;
+impl AnalyticsAndOperatorDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<AnalyticsAndOperator, XmlParseError> {
+        deserialize_elements::<_, AnalyticsAndOperator, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                "Tag" => {
+                    obj.tags
+                        .get_or_insert(vec![])
+                        .extend(TagListDeserializer::deserialize("Tag", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct AnalyticsAndOperatorSerializer;
+impl AnalyticsAndOperatorSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &AnalyticsAndOperator,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.tags {
+            TagListSerializer::serialize(&mut writer, "Tag", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>The filter used to describe a set of objects for analyses. A filter must have exactly one prefix, one tag, or one conjunction (AnalyticsAndOperator). If no filter is provided, all objects will be considered in any analysis.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AnalyticsFilter {
+    /// <p>A conjunction (logical AND) of predicates, which is used in evaluating an analytics filter. The operator must have at least two predicates.</p>
+    pub and: Option<AnalyticsAndOperator>,
+    /// <p>The prefix to use when evaluating an analytics filter.</p>
+    pub prefix: Option<String>,
+    /// <p>The tag to use when evaluating an analytics filter.</p>
+    pub tag: Option<Tag>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct AnalyticsFilterDeserializer;
+impl AnalyticsFilterDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<AnalyticsFilter, XmlParseError> {
+        deserialize_elements::<_, AnalyticsFilter, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "And" => {
+                    obj.and = Some(AnalyticsAndOperatorDeserializer::deserialize("And", stack)?);
+                }
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                "Tag" => {
+                    obj.tag = Some(TagDeserializer::deserialize("Tag", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct AnalyticsFilterSerializer;
+impl AnalyticsFilterSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &AnalyticsFilter,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.and {
+            AnalyticsAndOperatorSerializer::serialize(&mut writer, "And", value)?;
+        }
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.tag {
+            TagSerializer::serialize(&mut writer, "Tag", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Contains information about where to publish the analytics results.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AnalyticsS3BucketDestination {
+    /// <p>The name of the bucket to which data is exported.</p>
+    pub bucket: String,
+    /// <p>The account ID that owns the destination bucket. If no account ID is provided, the owner will not be validated prior to exporting data.</p>
+    pub bucket_account_id: Option<String>,
+    /// <p>Specifies the file format used when exporting data to Amazon S3.</p>
+    pub format: String,
+    /// <p>The prefix to use when exporting data. The prefix is prepended to all results.</p>
+    pub prefix: Option<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct AnalyticsS3BucketDestinationDeserializer;
+impl AnalyticsS3BucketDestinationDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<AnalyticsS3BucketDestination, XmlParseError> {
+        deserialize_elements::<_, AnalyticsS3BucketDestination, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Bucket" => {
+                        obj.bucket = StringDeserializer::deserialize("Bucket", stack)?;
+                    }
+                    "BucketAccountId" => {
+                        obj.bucket_account_id =
+                            Some(StringDeserializer::deserialize("BucketAccountId", stack)?);
+                    }
+                    "Format" => {
+                        obj.format = StringDeserializer::deserialize("Format", stack)?;
+                    }
+                    "Prefix" => {
+                        obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                    }
+                    _ => skip_tree(stack),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct AnalyticsS3BucketDestinationSerializer;
+impl AnalyticsS3BucketDestinationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &AnalyticsS3BucketDestination,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        write_characters_element(writer, "Bucket", &obj.bucket.to_string())?;
+        if let Some(ref value) = obj.bucket_account_id {
+            write_characters_element(writer, "BucketAccountId", &value.to_string())?;
+        }
+        write_characters_element(writer, "Format", &obj.format.to_string())?;
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Where to publish the analytics results.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AnalyticsExportDestination {
+    /// <p>A destination signifying output to an S3 bucket.</p>
+    pub s3_bucket_destination: AnalyticsS3BucketDestination,
+}
+
+#[allow(dead_code)]
+pub(crate) struct AnalyticsExportDestinationDeserializer;
+impl AnalyticsExportDestinationDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<AnalyticsExportDestination, XmlParseError> {
+        deserialize_elements::<_, AnalyticsExportDestination, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "S3BucketDestination" => {
+                        obj.s3_bucket_destination =
+                            AnalyticsS3BucketDestinationDeserializer::deserialize(
+                                "S3BucketDestination",
+                                stack,
+                            )?;
+                    }
+                    _ => skip_tree(stack),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct AnalyticsExportDestinationSerializer;
+impl AnalyticsExportDestinationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &AnalyticsExportDestination,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        AnalyticsS3BucketDestinationSerializer::serialize(
+            &mut writer,
+            "S3BucketDestination",
+            &obj.s3_bucket_destination,
+        )?;
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Container for data related to the storage class analysis for an Amazon S3 bucket for export.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct StorageClassAnalysisDataExport {
+    /// <p>The place to store the data for an analysis.</p>
+    pub destination: AnalyticsExportDestination,
+    /// <p>The version of the output schema to use when exporting data. Must be <code>V_1</code>.</p>
+    pub output_schema_version: String,
+}
+
+#[allow(dead_code)]
+pub(crate) struct StorageClassAnalysisDataExportDeserializer;
+impl StorageClassAnalysisDataExportDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<StorageClassAnalysisDataExport, XmlParseError> {
+        deserialize_elements::<_, StorageClassAnalysisDataExport, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Destination" => {
+                        obj.destination = AnalyticsExportDestinationDeserializer::deserialize(
+                            "Destination",
+                            stack,
+                        )?;
+                    }
+                    "OutputSchemaVersion" => {
+                        obj.output_schema_version =
+                            StringDeserializer::deserialize("OutputSchemaVersion", stack)?;
+                    }
+                    _ => skip_tree(stack),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct StorageClassAnalysisDataExportSerializer;
+impl StorageClassAnalysisDataExportSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &StorageClassAnalysisDataExport,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        AnalyticsExportDestinationSerializer::serialize(
+            &mut writer,
+            "Destination",
+            &obj.destination,
+        )?;
+        write_characters_element(
+            writer,
+            "OutputSchemaVersion",
+            &obj.output_schema_version.to_string(),
+        )?;
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Specifies data related to access patterns to be collected and made available to analyze the tradeoffs between different storage classes for an Amazon S3 bucket.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct StorageClassAnalysis {
+    /// <p>Specifies how data related to the storage class analysis for an Amazon S3 bucket should be exported.</p>
+    pub data_export: Option<StorageClassAnalysisDataExport>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct StorageClassAnalysisDeserializer;
+impl StorageClassAnalysisDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<StorageClassAnalysis, XmlParseError> {
+        deserialize_elements::<_, StorageClassAnalysis, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "DataExport" => {
+                    obj.data_export =
+                        Some(StorageClassAnalysisDataExportDeserializer::deserialize(
+                            "DataExport",
+                            stack,
+                        )?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct StorageClassAnalysisSerializer;
+impl StorageClassAnalysisSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &StorageClassAnalysis,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.data_export {
+            StorageClassAnalysisDataExportSerializer::serialize(&mut writer, "DataExport", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Specifies the configuration and any analyses for the analytics filter of an Amazon S3 bucket.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AnalyticsConfiguration {
+    /// <p>The filter used to describe a set of objects for analyses. A filter must have exactly one prefix, one tag, or one conjunction (AnalyticsAndOperator). If no filter is provided, all objects will be considered in any analysis.</p>
+    pub filter: Option<AnalyticsFilter>,
+    /// <p>The ID that identifies the analytics configuration.</p>
+    pub id: String,
+    /// <p> Contains data related to access patterns to be collected and made available to analyze the tradeoffs between different storage classes. </p>
+    pub storage_class_analysis: StorageClassAnalysis,
+}
+
+#[allow(dead_code)]
+pub(crate) struct AnalyticsConfigurationDeserializer;
+impl AnalyticsConfigurationDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<AnalyticsConfiguration, XmlParseError> {
+        deserialize_elements::<_, AnalyticsConfiguration, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Filter" => {
+                    obj.filter = Some(AnalyticsFilterDeserializer::deserialize("Filter", stack)?);
+                }
+                "Id" => {
+                    obj.id = StringDeserializer::deserialize("Id", stack)?;
+                }
+                "StorageClassAnalysis" => {
+                    obj.storage_class_analysis = StorageClassAnalysisDeserializer::deserialize(
+                        "StorageClassAnalysis",
+                        stack,
+                    )?;
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct AnalyticsConfigurationListDeserializer;
+impl AnalyticsConfigurationListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<AnalyticsConfiguration>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(AnalyticsConfigurationDeserializer::deserialize(
+                    tag_name, stack,
+                )?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+pub struct AnalyticsConfigurationSerializer;
+impl AnalyticsConfigurationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &AnalyticsConfiguration,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.filter {
+            AnalyticsFilterSerializer::serialize(&mut writer, "Filter", value)?;
+        }
+        write_characters_element(writer, "Id", &obj.id.to_string())?;
+        StorageClassAnalysisSerializer::serialize(
+            &mut writer,
+            "StorageClassAnalysis",
+            &obj.storage_class_analysis,
+        )?;
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
@@ -0,0 +1,192 @@
+//! The `AppendObject` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventReader;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::error::{XmlError, XmlErrorDeserializer};
+use crate::core::proto::xml::util::find_start_element;
+use crate::core::proto::xml::util::{Next, Peek, XmlParseError, XmlResponse};
+use crate::core::request::BufferedHttpResponse;
+use crate::dates::format_http_date;
+use crate::enums::{CannedAcl, StorageClass};
+use crate::key::validate_key;
+use crate::metadata::Metadata;
+use crate::request::StreamingBody;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AppendObjectRequest {
+    /// <p>The canned ACL to apply to the object, when the append creates it.</p>
+    pub acl: Option<CannedAcl>,
+    /// <p>The bytes to append.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub body: Option<StreamingBody>,
+    /// <p>The bucket holding the object.</p>
+    pub bucket: String,
+    /// <p>Caching behavior of the object, when the append creates it.</p>
+    pub cache_control: Option<String>,
+    /// <p>Presentational information for the object, when the append creates it.</p>
+    pub content_disposition: Option<String>,
+    /// <p>The content encodings applied to the object, when the append creates it.</p>
+    pub content_encoding: Option<String>,
+    /// <p>Size of the appended bytes. Required when the size of the body can't be determined automatically.</p>
+    pub content_length: Option<i64>,
+    /// <p>The base64-encoded 128-bit MD5 digest of the appended bytes according to RFC 1864.</p>
+    pub content_md5: Option<String>,
+    /// <p>A standard MIME type describing the format of the object, when the append creates it.</p>
+    pub content_type: Option<String>,
+    /// <p>The date and time at which the object is no longer cacheable, when the append creates it.</p>
+    pub expires: Option<DateTime<Utc>>,
+    /// <p>The key of the object. The object is created by the first append, at position 0.</p>
+    pub key: String,
+    /// <p>The metadata of the object, when the append creates it.</p>
+    pub metadata: Option<Metadata>,
+    /// <p>Where to append the bytes, which has to be the current length of the object: 0 for the first append, then the <code>next_append_position</code> of the previous one.</p>
+    pub position: i64,
+    /// <p>The storage class of the object, when the append creates it.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>The tag-set of the object, when the append creates it, encoded as URL query parameters (for example, "Key1=Value1").</p>
+    pub tagging: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AppendObjectOutput {
+    /// <p>The CRC64 (ECMA-182) checksum of the whole object after the append, as reported by KS3.</p>
+    pub checksum_crc64ecma: Option<String>,
+    /// <p>Entity tag of the object after the append.</p>
+    pub e_tag: Option<String>,
+    /// <p>The position to send with the next append to the object, which is its length after this one.</p>
+    pub next_append_position: Option<i64>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+/// Errors returned by AppendObject
+#[derive(Debug, PartialEq)]
+pub enum AppendObjectError {
+    /// <p>The object exists but wasn't created by appending to it, e.g. it was uploaded with PutObject.</p>
+    ObjectNotAppendable(String),
+    /// <p>The position of the request isn't the current length of the object.</p>
+    PositionNotEqualToLength(String),
+}
+
+impl AppendObjectError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<AppendObjectError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                match &parsed_error.code[..] {
+                    "ObjectNotAppendable" => {
+                        return Ks3Error::Service(AppendObjectError::ObjectNotAppendable(
+                            parsed_error.message,
+                        ))
+                    }
+                    "PositionNotEqualToLength" => {
+                        return Ks3Error::Service(AppendObjectError::PositionNotEqualToLength(
+                            parsed_error.message,
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for AppendObjectError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AppendObjectError::ObjectNotAppendable(ref cause) => write!(f, "{}", cause),
+            AppendObjectError::PositionNotEqualToLength(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for AppendObjectError {}
+
+/// Send an `AppendObject` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: AppendObjectRequest,
+) -> Result<AppendObjectOutput, Ks3Error<AppendObjectError>> {
+    validate_key(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("POST", "s3", &client.region, &request_uri);
+
+    request.add_optional_header("x-amz-acl", input.acl.as_ref());
+    request.add_optional_header("Cache-Control", input.cache_control.as_ref());
+    request.add_optional_header("Content-Disposition", input.content_disposition.as_ref());
+    request.add_optional_header("Content-Encoding", input.content_encoding.as_ref());
+    request.add_optional_header("Content-Length", input.content_length.as_ref());
+    request.add_optional_header("Content-MD5", input.content_md5.as_ref());
+    request.add_optional_header("Content-Type", input.content_type.as_ref());
+    request.add_optional_header("Expires", input.expires.as_ref().map(format_http_date));
+
+    if let Some(ref metadata) = input.metadata {
+        for (header_name, header_value) in metadata.iter() {
+            let header = format!("x-amz-meta-{}", header_name);
+            request.add_header(header, header_value);
+        }
+    }
+    request.add_optional_header("x-amz-storage-class", input.storage_class.as_ref());
+    request.add_optional_header("x-amz-tagging", input.tagging.as_ref());
+
+    let mut params = Params::new();
+    params.put_key("append");
+    params.put("position", &input.position);
+    request.set_params(params);
+
+    if let Some(__body) = input.body {
+        request.set_payload_stream(__body);
+    }
+
+    let mut response = client
+        .sign_and_dispatch("AppendObject", request, AppendObjectError::from_response)
+        .await?;
+
+    let mut result = AppendObjectOutput::default();
+    result.raw_headers = response.headers.clone();
+    result.checksum_crc64ecma = response.headers.remove("x-kss-checksum-crc64ecma");
+    result.e_tag = response.headers.remove("ETag");
+    result.next_append_position = response
+        .headers
+        .remove("x-amz-next-append-position")
+        .and_then(|position| position.parse().ok());
+    Ok(result)
+}
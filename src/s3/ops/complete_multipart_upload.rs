@@ -0,0 +1,276 @@
+//! The `CompleteMultipartUpload` operation.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventWriter;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError};
+use crate::core::proto::xml::util::{deserialize_elements, retain_tree, write_characters_element};
+use crate::core::request::BufferedHttpResponse;
+use crate::core::request::HttpDispatchError;
+use crate::core::runtime;
+use crate::enums::{RequestPayer, ServerSideEncryption};
+use crate::key::validate_key_length;
+use crate::request::*;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+/// <p>Details of the parts that were uploaded.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CompletedPart {
+    /// <p>Entity tag returned when the part was uploaded.</p>
+    pub e_tag: Option<String>,
+    /// <p>Part number that identifies the part. This is a positive integer between 1 and 10,000.</p>
+    pub part_number: Option<i64>,
+}
+
+pub struct CompletedPartSerializer;
+impl CompletedPartSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &CompletedPart,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.e_tag {
+            write_characters_element(writer, "ETag", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.part_number {
+            write_characters_element(writer, "PartNumber", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+pub struct CompletedPartListSerializer;
+impl CompletedPartListSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Vec<CompletedPart>,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        for element in obj {
+            CompletedPartSerializer::serialize(writer, name, element)?;
+        }
+        Ok(())
+    }
+}
+
+/// <p>The container for the completed multipart upload details.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CompletedMultipartUpload {
+    /// <p>Array of CompletedPart data types.</p>
+    pub parts: Option<Vec<CompletedPart>>,
+}
+
+pub struct CompletedMultipartUploadSerializer;
+impl CompletedMultipartUploadSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &CompletedMultipartUpload,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.parts {
+            CompletedPartListSerializer::serialize(writer, "Part", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CompleteMultipartUploadOutput {
+    /// <p>The name of the bucket that contains the newly created object.</p>
+    pub bucket: Option<String>,
+    /// <p>The CRC64 (ECMA-182) checksum of the whole object, as reported by KS3.</p>
+    pub checksum_crc64ecma: Option<String>,
+    /// <p>Entity tag that identifies the newly created object's data. Objects with different object data will have different entity tags.</p>
+    pub e_tag: Option<String>,
+    /// <p>If the object expiration is configured, this will contain the expiration date (expiry-date) and rule ID (rule-id). The value of rule-id is URL encoded.</p>
+    pub expiration: Option<String>,
+    /// <p>The object key of the newly created object.</p>
+    pub key: Option<String>,
+    /// <p>The URI that identifies the newly created object.</p>
+    pub location: Option<String>,
+    pub request_charged: Option<String>,
+    /// <p>If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>If you specified server-side encryption either with an Amazon S3-managed encryption key or an AWS KMS customer master key (CMK) in your initiate multipart upload request, the response includes this header. It confirms the encryption algorithm that Amazon S3 used to encrypt the object.</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>Version ID of the newly created object, in case the bucket has versioning turned on.</p>
+    pub version_id: Option<String>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct CompleteMultipartUploadOutputDeserializer;
+impl CompleteMultipartUploadOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<CompleteMultipartUploadOutput, XmlParseError> {
+        deserialize_elements::<_, CompleteMultipartUploadOutput, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Bucket" => {
+                        obj.bucket = Some(StringDeserializer::deserialize("Bucket", stack)?);
+                    }
+                    "ETag" => {
+                        obj.e_tag = Some(StringDeserializer::deserialize("ETag", stack)?);
+                    }
+                    "Key" => {
+                        obj.key = Some(StringDeserializer::deserialize("Key", stack)?);
+                    }
+                    "Location" => {
+                        obj.location = Some(StringDeserializer::deserialize("Location", stack)?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CompleteMultipartUploadRequest {
+    /// <p>Name of the bucket to which the multipart upload was initiated.</p>
+    pub bucket: String,
+    /// <p>Object key for which the multipart upload was initiated.</p>
+    pub key: String,
+    /// <p>The container for the multipart upload request information.</p>
+    pub multipart_upload: Option<CompletedMultipartUpload>,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>ID for the initiated multipart upload.</p>
+    pub upload_id: String,
+}
+
+/// Errors returned by CompleteMultipartUpload
+#[derive(Debug, PartialEq)]
+pub enum CompleteMultipartUploadError {}
+
+impl CompleteMultipartUploadError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<CompleteMultipartUploadError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for CompleteMultipartUploadError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for CompleteMultipartUploadError {}
+
+/// Send a `CompleteMultipartUpload` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: CompleteMultipartUploadRequest,
+) -> Result<CompleteMultipartUploadOutput, Ks3Error<CompleteMultipartUploadError>> {
+    validate_key_length(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("POST", "s3", &client.region, &request_uri);
+
+    request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+    let mut params = Params::new();
+    params.put("uploadId", &input.upload_id);
+    request.set_params(params);
+    if input.multipart_upload.is_some() {
+        let mut writer = EventWriter::new(Vec::new());
+        CompletedMultipartUploadSerializer::serialize(
+            &mut writer,
+            "CompleteMultipartUpload",
+            input.multipart_upload.as_ref().unwrap(),
+        );
+        request.set_payload(Some(writer.into_inner()));
+    } else {
+        request.set_payload(Some(Vec::new()));
+    }
+
+    let dispatch = client.sign_and_dispatch(
+        "CompleteMultipartUpload",
+        request,
+        CompleteMultipartUploadError::from_response,
+    );
+    let deadline = client
+        .operation
+        .timeout
+        .or(client.config.complete_multipart_upload_timeout);
+    let mut response = match deadline {
+        Some(deadline) => runtime::timeout(deadline, dispatch)
+            .await
+            .unwrap_or_else(|| {
+                Err(Ks3Error::HttpDispatch(HttpDispatchError::new(format!(
+                    "CompleteMultipartUpload didn't complete within {:?}",
+                    deadline
+                ))))
+            })?,
+        None => dispatch.await?,
+    };
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        CompleteMultipartUploadOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    result.checksum_crc64ecma = response.headers.remove("x-kss-checksum-crc64ecma");
+    result.expiration = response.headers.remove("x-amz-expiration");
+    result.request_charged = response.headers.remove("x-amz-request-charged");
+    result.ssekms_key_id = response
+        .headers
+        .remove("x-amz-server-side-encryption-aws-kms-key-id");
+    result.server_side_encryption = response
+        .headers
+        .remove("x-amz-server-side-encryption")
+        .map(Into::into);
+    result.version_id = response.headers.remove("x-amz-version-id"); // parse non-payload
+    Ok(result)
+}
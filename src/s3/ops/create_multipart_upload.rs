@@ -0,0 +1,273 @@
+//! The `CreateMultipartUpload` operation.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError};
+use crate::core::proto::xml::util::{deserialize_elements, retain_tree};
+use crate::core::request::BufferedHttpResponse;
+use crate::dates::format_http_date;
+use crate::enums::{CannedAcl, RequestPayer, ServerSideEncryption, StorageClass};
+use crate::key::validate_key;
+use crate::metadata::Metadata;
+use crate::mime::mime_type_for_key;
+use crate::request::*;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CreateMultipartUploadOutput {
+    /// <p>The name of the bucket to which the multipart upload was initiated. </p>
+    pub bucket: Option<String>,
+    /// <p>Object key for which the multipart upload was initiated.</p>
+    pub key: Option<String>,
+    pub request_charged: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header confirming the encryption algorithm used.</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header to provide round-trip message integrity verification of the customer-provided encryption key.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>ID for the initiated multipart upload.</p>
+    pub upload_id: Option<String>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct CreateMultipartUploadOutputDeserializer;
+impl CreateMultipartUploadOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<CreateMultipartUploadOutput, XmlParseError> {
+        deserialize_elements::<_, CreateMultipartUploadOutput, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Bucket" => {
+                        obj.bucket = Some(StringDeserializer::deserialize("Bucket", stack)?);
+                    }
+                    "Key" => {
+                        obj.key = Some(StringDeserializer::deserialize("Key", stack)?);
+                    }
+                    "UploadId" => {
+                        obj.upload_id = Some(StringDeserializer::deserialize("UploadId", stack)?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CreateMultipartUploadRequest {
+    /// <p>The canned ACL to apply to the object.</p>
+    pub acl: Option<CannedAcl>,
+    /// <p>The name of the bucket to which to initiate the upload</p>
+    pub bucket: String,
+    /// <p>Specifies caching behavior along the request/reply chain.</p>
+    pub cache_control: Option<String>,
+    /// <p>Specifies presentational information for the object.</p>
+    pub content_disposition: Option<String>,
+    /// <p>Specifies what content encodings have been applied to the object and thus what decoding mechanisms must be applied to obtain the media-type referenced by the Content-Type header field.</p>
+    pub content_encoding: Option<String>,
+    /// <p>The language the content is in.</p>
+    pub content_language: Option<String>,
+    /// <p>A standard MIME type describing the format of the object data.</p>
+    pub content_type: Option<String>,
+    /// <p>The date and time at which the object is no longer cacheable.</p>
+    pub expires: Option<DateTime<Utc>>,
+    /// <p>Gives the grantee READ, READ_ACP, and WRITE_ACP permissions on the object.</p>
+    pub grant_full_control: Option<String>,
+    /// <p>Allows grantee to read the object data and its metadata.</p>
+    pub grant_read: Option<String>,
+    /// <p>Allows grantee to read the object ACL.</p>
+    pub grant_read_acp: Option<String>,
+    /// <p>Allows grantee to write the ACL for the applicable object.</p>
+    pub grant_write_acp: Option<String>,
+    /// <p>Object key for which the multipart upload is to be initiated.</p>
+    pub key: String,
+    /// <p>A map of metadata to store with the object in S3.</p>
+    pub metadata: Option<Metadata>,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>Specifies the algorithm to use to when encrypting the object (for example, AES256).</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use in encrypting data.</p>
+    pub sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>Specifies the ID of the symmetric customer managed AWS KMS CMK to use for object encryption.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>The type of storage to use for the object. Defaults to 'STANDARD'.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>The tag-set for the object. The tag-set must be encoded as URL Query parameters.</p>
+    pub tagging: Option<String>,
+    /// <p>If the bucket is configured as a website, redirects requests for this object to another object in the same bucket or to an external URL.</p>
+    pub website_redirect_location: Option<String>,
+}
+
+/// Errors returned by CreateMultipartUpload
+#[derive(Debug, PartialEq)]
+pub enum CreateMultipartUploadError {}
+
+impl CreateMultipartUploadError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<CreateMultipartUploadError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for CreateMultipartUploadError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for CreateMultipartUploadError {}
+
+/// Send a `CreateMultipartUpload` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: CreateMultipartUploadRequest,
+) -> Result<CreateMultipartUploadOutput, Ks3Error<CreateMultipartUploadError>> {
+    validate_key(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("POST", "s3", &client.region, &request_uri);
+
+    request.add_optional_header(
+        "x-amz-acl",
+        input.acl.as_ref().or(client.config.default_acl.as_ref()),
+    );
+    request.add_optional_header("Cache-Control", input.cache_control.as_ref());
+    request.add_optional_header("Content-Disposition", input.content_disposition.as_ref());
+    request.add_optional_header("Content-Encoding", input.content_encoding.as_ref());
+    request.add_optional_header("Content-Language", input.content_language.as_ref());
+    let content_type = input.content_type.as_deref().or_else(|| {
+        if client.config.infer_content_type {
+            mime_type_for_key(&input.key)
+        } else {
+            None
+        }
+    });
+    request.add_optional_header("Content-Type", content_type);
+    request.add_optional_header("Expires", input.expires.as_ref().map(format_http_date));
+    request.add_optional_header(
+        "x-amz-grant-full-control",
+        input.grant_full_control.as_ref(),
+    );
+    request.add_optional_header("x-amz-grant-read", input.grant_read.as_ref());
+    request.add_optional_header("x-amz-grant-read-acp", input.grant_read_acp.as_ref());
+    request.add_optional_header("x-amz-grant-write-acp", input.grant_write_acp.as_ref());
+
+    if let Some(ref metadata) = input.metadata {
+        for (header_name, header_value) in metadata.iter() {
+            let header = format!("x-amz-meta-{}", header_name);
+            request.add_header(header, header_value);
+        }
+    }
+    request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-algorithm",
+        input.sse_customer_algorithm.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-key",
+        input.sse_customer_key.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-key-MD5",
+        input.sse_customer_key_md5.as_ref(),
+    );
+    let server_side_encryption = input
+        .server_side_encryption
+        .as_ref()
+        .or_else(|| client.config.server_side_encryption_for(&input.bucket));
+    request.add_optional_header(
+        "x-amz-server-side-encryption-aws-kms-key-id",
+        input.ssekms_key_id.as_ref().or_else(|| {
+            client
+                .config
+                .ssekms_key_id_for(&input.bucket, server_side_encryption)
+        }),
+    );
+    request.add_optional_header("x-amz-server-side-encryption", server_side_encryption);
+    request.add_optional_header(
+        "x-amz-storage-class",
+        input
+            .storage_class
+            .as_ref()
+            .or(client.config.default_storage_class.as_ref()),
+    );
+    request.add_optional_header("x-amz-tagging", input.tagging.as_ref());
+    request.add_optional_header(
+        "x-amz-website-redirect-location",
+        input.website_redirect_location.as_ref(),
+    );
+    let mut params = Params::new();
+    params.put_key("uploads");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "CreateMultipartUpload",
+            request,
+            CreateMultipartUploadError::from_response,
+        )
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        CreateMultipartUploadOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    result.request_charged = response.headers.remove("x-amz-request-charged");
+    result.sse_customer_algorithm = response
+        .headers
+        .remove("x-amz-server-side-encryption-customer-algorithm");
+    result.sse_customer_key_md5 = response
+        .headers
+        .remove("x-amz-server-side-encryption-customer-key-MD5");
+    result.ssekms_key_id = response
+        .headers
+        .remove("x-amz-server-side-encryption-aws-kms-key-id");
+    result.server_side_encryption = response
+        .headers
+        .remove("x-amz-server-side-encryption")
+        .map(Into::into); // parse non-payload
+    Ok(result)
+}
@@ -0,0 +1,74 @@
+//! The `DeleteBucketAnalyticsConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct DeleteBucketAnalyticsConfigurationRequest {
+    /// <p>The name of the bucket from which an analytics configuration is deleted.</p>
+    pub bucket: String,
+    /// <p>The ID that identifies the analytics configuration.</p>
+    pub id: String,
+}
+
+/// Errors returned by DeleteBucketAnalyticsConfiguration
+#[derive(Debug, PartialEq)]
+pub enum DeleteBucketAnalyticsConfigurationError {}
+
+impl DeleteBucketAnalyticsConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<DeleteBucketAnalyticsConfigurationError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for DeleteBucketAnalyticsConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for DeleteBucketAnalyticsConfigurationError {}
+
+/// Send a `DeleteBucketAnalyticsConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: DeleteBucketAnalyticsConfigurationRequest,
+) -> Result<(), Ks3Error<DeleteBucketAnalyticsConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("DELETE", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    params.put("id", &input.id);
+    params.put_key("analytics");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "DeleteBucketAnalyticsConfiguration",
+            request,
+            DeleteBucketAnalyticsConfigurationError::from_response,
+        )
+        .await?;
+
+    std::mem::drop(response);
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! The `DeleteBucketMetricsConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct DeleteBucketMetricsConfigurationRequest {
+    /// <p>The name of the bucket containing the metrics configuration to delete.</p>
+    pub bucket: String,
+    /// <p>The ID used to identify the metrics configuration.</p>
+    pub id: String,
+}
+
+/// Errors returned by DeleteBucketMetricsConfiguration
+#[derive(Debug, PartialEq)]
+pub enum DeleteBucketMetricsConfigurationError {}
+
+impl DeleteBucketMetricsConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<DeleteBucketMetricsConfigurationError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for DeleteBucketMetricsConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for DeleteBucketMetricsConfigurationError {}
+
+/// Send a `DeleteBucketMetricsConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: DeleteBucketMetricsConfigurationRequest,
+) -> Result<(), Ks3Error<DeleteBucketMetricsConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("DELETE", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    params.put("id", &input.id);
+    params.put_key("metrics");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "DeleteBucketMetricsConfiguration",
+            request,
+            DeleteBucketMetricsConfigurationError::from_response,
+        )
+        .await?;
+
+    std::mem::drop(response);
+    Ok(())
+}
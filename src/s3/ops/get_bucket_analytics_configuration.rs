@@ -0,0 +1,117 @@
+//! The `GetBucketAnalyticsConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use super::analytics_configuration::*;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError};
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketAnalyticsConfigurationOutput {
+    /// <p>The configuration and any analyses for the analytics filter.</p>
+    pub analytics_configuration: Option<AnalyticsConfiguration>,
+    /// <p>The entity tag of the configuration, to send as the <code>if_match</code> of <code>PutBucketAnalyticsConfiguration</code> when replacing it.</p>
+    pub e_tag: Option<String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct GetBucketAnalyticsConfigurationOutputDeserializer;
+impl GetBucketAnalyticsConfigurationOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<GetBucketAnalyticsConfigurationOutput, XmlParseError> {
+        Ok(GetBucketAnalyticsConfigurationOutput {
+            analytics_configuration: Some(AnalyticsConfigurationDeserializer::deserialize(
+                tag_name, stack,
+            )?),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketAnalyticsConfigurationRequest {
+    /// <p>The name of the bucket from which an analytics configuration is retrieved.</p>
+    pub bucket: String,
+    /// <p>The ID that identifies the analytics configuration.</p>
+    pub id: String,
+}
+
+/// Errors returned by GetBucketAnalyticsConfiguration
+#[derive(Debug, PartialEq)]
+pub enum GetBucketAnalyticsConfigurationError {}
+
+impl GetBucketAnalyticsConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<GetBucketAnalyticsConfigurationError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for GetBucketAnalyticsConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for GetBucketAnalyticsConfigurationError {}
+
+/// Send a `GetBucketAnalyticsConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: GetBucketAnalyticsConfigurationRequest,
+) -> Result<GetBucketAnalyticsConfigurationOutput, Ks3Error<GetBucketAnalyticsConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("GET", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    params.put("id", &input.id);
+    params.put_key("analytics");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "GetBucketAnalyticsConfiguration",
+            request,
+            GetBucketAnalyticsConfigurationError::from_response,
+        )
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        GetBucketAnalyticsConfigurationOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    result.e_tag = response.headers.remove("ETag"); // parse non-payload
+    Ok(result)
+}
@@ -0,0 +1,151 @@
+//! The `GetBucketLifecycleConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventReader;
+
+use super::lifecycle_configuration::*;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::error::{XmlError, XmlErrorDeserializer};
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError, XmlResponse};
+use crate::core::proto::xml::util::{deserialize_elements, find_start_element, skip_tree};
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketLifecycleConfigurationOutput {
+    /// <p>The rules of the lifecycle configuration.</p>
+    pub rules: Option<Vec<LifecycleRule>>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct GetBucketLifecycleConfigurationOutputDeserializer;
+impl GetBucketLifecycleConfigurationOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<GetBucketLifecycleConfigurationOutput, XmlParseError> {
+        deserialize_elements::<_, GetBucketLifecycleConfigurationOutput, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Rule" => {
+                        obj.rules
+                            .get_or_insert(vec![])
+                            .extend(LifecycleRuleListDeserializer::deserialize("Rule", stack)?);
+                    }
+                    _ => skip_tree(stack),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketLifecycleConfigurationRequest {
+    /// <p>The name of the bucket for which to get the configuration.</p>
+    pub bucket: String,
+}
+
+/// Errors returned by GetBucketLifecycleConfiguration
+#[derive(Debug, PartialEq)]
+pub enum GetBucketLifecycleConfigurationError {
+    /// <p>The bucket has no lifecycle configuration.</p>
+    NoSuchLifecycleConfiguration(String),
+}
+
+impl GetBucketLifecycleConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<GetBucketLifecycleConfigurationError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "NoSuchLifecycleConfiguration" {
+                    return Ks3Error::Service(
+                        GetBucketLifecycleConfigurationError::NoSuchLifecycleConfiguration(
+                            parsed_error.message,
+                        ),
+                    );
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for GetBucketLifecycleConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GetBucketLifecycleConfigurationError::NoSuchLifecycleConfiguration(ref cause) => {
+                write!(f, "{}", cause)
+            }
+        }
+    }
+}
+
+impl Error for GetBucketLifecycleConfigurationError {}
+
+/// Send a `GetBucketLifecycleConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: GetBucketLifecycleConfigurationRequest,
+) -> Result<GetBucketLifecycleConfigurationOutput, Ks3Error<GetBucketLifecycleConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("GET", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    params.put_key("lifecycle");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "GetBucketLifecycleConfiguration",
+            request,
+            GetBucketLifecycleConfigurationError::from_response,
+        )
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        GetBucketLifecycleConfigurationOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    Ok(result)
+}
@@ -0,0 +1,117 @@
+//! The `GetBucketMetricsConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use super::metrics_configuration::*;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError};
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketMetricsConfigurationOutput {
+    /// <p>The entity tag of the configuration, to send as the <code>if_match</code> of <code>PutBucketMetricsConfiguration</code> when replacing it.</p>
+    pub e_tag: Option<String>,
+    /// <p>Specifies the metrics configuration.</p>
+    pub metrics_configuration: Option<MetricsConfiguration>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct GetBucketMetricsConfigurationOutputDeserializer;
+impl GetBucketMetricsConfigurationOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<GetBucketMetricsConfigurationOutput, XmlParseError> {
+        Ok(GetBucketMetricsConfigurationOutput {
+            metrics_configuration: Some(MetricsConfigurationDeserializer::deserialize(
+                tag_name, stack,
+            )?),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct GetBucketMetricsConfigurationRequest {
+    /// <p>The name of the bucket containing the metrics configuration to retrieve.</p>
+    pub bucket: String,
+    /// <p>The ID used to identify the metrics configuration.</p>
+    pub id: String,
+}
+
+/// Errors returned by GetBucketMetricsConfiguration
+#[derive(Debug, PartialEq)]
+pub enum GetBucketMetricsConfigurationError {}
+
+impl GetBucketMetricsConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<GetBucketMetricsConfigurationError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for GetBucketMetricsConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for GetBucketMetricsConfigurationError {}
+
+/// Send a `GetBucketMetricsConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: GetBucketMetricsConfigurationRequest,
+) -> Result<GetBucketMetricsConfigurationOutput, Ks3Error<GetBucketMetricsConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("GET", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    params.put("id", &input.id);
+    params.put_key("metrics");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "GetBucketMetricsConfiguration",
+            request,
+            GetBucketMetricsConfigurationError::from_response,
+        )
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        GetBucketMetricsConfigurationOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    result.e_tag = response.headers.remove("ETag"); // parse non-payload
+    Ok(result)
+}
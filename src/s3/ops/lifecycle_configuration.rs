@@ -0,0 +1,854 @@
+//! The lifecycle configurations of buckets, shared by the operations managing them.
+//!
+//! Every element of a lifecycle rule is modeled, so that a configuration read with
+//! `get_bucket_lifecycle_configuration` can be written back with
+//! `put_bucket_lifecycle_configuration` unchanged. Elements a newer version of the service
+//! sends are kept in the `extra` fields, and aren't written back: `unknown_elements` lists
+//! them, to refuse to rewrite a configuration that would lose them.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::reader::XmlEvent;
+use xml::EventWriter;
+
+use crate::core::proto::xml::util::{deserialize_elements, retain_tree, write_characters_element};
+use crate::core::proto::xml::util::{Next, Peek, XmlParseError};
+use crate::enums::StorageClass;
+use crate::request::*;
+
+/// <p>When the objects a lifecycle rule applies to are deleted.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct LifecycleRuleExpiration {
+    /// <p>The date the objects are deleted on, in ISO 8601 format at midnight UTC, e.g. <code>2021-01-01T00:00:00+08:00</code>.</p>
+    pub date: Option<String>,
+    /// <p>The number of days after their creation the objects are deleted.</p>
+    pub days: Option<i64>,
+    /// <p>Whether delete markers without noncurrent versions left are removed, in versioned buckets.</p>
+    pub expired_object_delete_marker: Option<bool>,
+    /// <p>The elements of the expiration none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct LifecycleRuleExpirationDeserializer;
+impl LifecycleRuleExpirationDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<LifecycleRuleExpiration, XmlParseError> {
+        deserialize_elements::<_, LifecycleRuleExpiration, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Date" => {
+                        obj.date = Some(StringDeserializer::deserialize("Date", stack)?);
+                    }
+                    "Days" => {
+                        obj.days = Some(LongDeserializer::deserialize("Days", stack)?);
+                    }
+                    "ExpiredObjectDeleteMarker" => {
+                        obj.expired_object_delete_marker = Some(BooleanDeserializer::deserialize(
+                            "ExpiredObjectDeleteMarker",
+                            stack,
+                        )?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct LifecycleRuleExpirationSerializer;
+impl LifecycleRuleExpirationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &LifecycleRuleExpiration,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.date {
+            write_characters_element(writer, "Date", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.days {
+            write_characters_element(writer, "Days", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.expired_object_delete_marker {
+            write_characters_element(writer, "ExpiredObjectDeleteMarker", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>The conjunction of conditions of a lifecycle rule filter: the objects it applies to have a key starting with the prefix and carry all the tags.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct LifecycleRuleAndOperator {
+    /// <p>The prefix of the keys of the objects the rule applies to.</p>
+    pub prefix: Option<String>,
+    /// <p>The tags all the objects the rule applies to carry.</p>
+    pub tags: Option<Vec<Tag>>,
+    /// <p>The elements of the conjunction none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct LifecycleRuleAndOperatorDeserializer;
+impl LifecycleRuleAndOperatorDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<LifecycleRuleAndOperator, XmlParseError> {
+        deserialize_elements::<_, LifecycleRuleAndOperator, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Prefix" => {
+                        obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                    }
+                    "Tag" => {
+                        obj.tags
+                            .get_or_insert(vec![])
+                            .extend(TagListDeserializer::deserialize("Tag", stack)?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct LifecycleRuleAndOperatorSerializer;
+impl LifecycleRuleAndOperatorSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &LifecycleRuleAndOperator,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.tags {
+            for tag in value {
+                TagSerializer::serialize(&mut writer, "Tag", tag)?;
+            }
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>The objects a lifecycle rule applies to: those with a key starting with the prefix, carrying the tag, or matching all the conditions of <code>And</code>. A filter without any applies to every object of the bucket.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct LifecycleRuleFilter {
+    /// <p>The conditions the objects the rule applies to all match.</p>
+    pub and: Option<LifecycleRuleAndOperator>,
+    /// <p>The prefix of the keys of the objects the rule applies to.</p>
+    pub prefix: Option<String>,
+    /// <p>The tag of the objects the rule applies to.</p>
+    pub tag: Option<Tag>,
+    /// <p>The elements of the filter none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct LifecycleRuleFilterDeserializer;
+impl LifecycleRuleFilterDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<LifecycleRuleFilter, XmlParseError> {
+        deserialize_elements::<_, LifecycleRuleFilter, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "And" => {
+                    obj.and = Some(LifecycleRuleAndOperatorDeserializer::deserialize(
+                        "And", stack,
+                    )?);
+                }
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                "Tag" => {
+                    obj.tag = Some(TagDeserializer::deserialize("Tag", stack)?);
+                }
+                _ => retain_tree(name, stack, &mut obj.extra),
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct LifecycleRuleFilterSerializer;
+impl LifecycleRuleFilterSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &LifecycleRuleFilter,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.tag {
+            TagSerializer::serialize(&mut writer, "Tag", value)?;
+        }
+        if let Some(ref value) = obj.and {
+            LifecycleRuleAndOperatorSerializer::serialize(&mut writer, "And", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>When the objects a lifecycle rule applies to move to another storage class.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Transition {
+    /// <p>The date the objects move on, in ISO 8601 format at midnight UTC, e.g. <code>2021-01-01T00:00:00+08:00</code>.</p>
+    pub date: Option<String>,
+    /// <p>The number of days after their creation the objects move.</p>
+    pub days: Option<i64>,
+    /// <p>The storage class the objects move to, e.g. <code>ARCHIVE</code>.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>The elements of the transition none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct TransitionDeserializer;
+impl TransitionDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Transition, XmlParseError> {
+        deserialize_elements::<_, Transition, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Date" => {
+                    obj.date = Some(StringDeserializer::deserialize("Date", stack)?);
+                }
+                "Days" => {
+                    obj.days = Some(LongDeserializer::deserialize("Days", stack)?);
+                }
+                "StorageClass" => {
+                    obj.storage_class =
+                        Some(StringDeserializer::deserialize("StorageClass", stack)?.into());
+                }
+                _ => retain_tree(name, stack, &mut obj.extra),
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct TransitionSerializer;
+impl TransitionSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &Transition,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.date {
+            write_characters_element(writer, "Date", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.days {
+            write_characters_element(writer, "Days", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.storage_class {
+            write_characters_element(writer, "StorageClass", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct TransitionListDeserializer;
+impl TransitionListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<Transition>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(TransitionDeserializer::deserialize(tag_name, stack)?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+/// <p>When the noncurrent versions of the objects a lifecycle rule applies to move to another storage class, in versioned buckets.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct NoncurrentVersionTransition {
+    /// <p>The number of days after they became noncurrent the versions move.</p>
+    pub noncurrent_days: Option<i64>,
+    /// <p>The storage class the versions move to.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>The elements of the transition none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct NoncurrentVersionTransitionDeserializer;
+impl NoncurrentVersionTransitionDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<NoncurrentVersionTransition, XmlParseError> {
+        deserialize_elements::<_, NoncurrentVersionTransition, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "NoncurrentDays" => {
+                        obj.noncurrent_days =
+                            Some(LongDeserializer::deserialize("NoncurrentDays", stack)?);
+                    }
+                    "StorageClass" => {
+                        obj.storage_class =
+                            Some(StringDeserializer::deserialize("StorageClass", stack)?.into());
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct NoncurrentVersionTransitionSerializer;
+impl NoncurrentVersionTransitionSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &NoncurrentVersionTransition,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.noncurrent_days {
+            write_characters_element(writer, "NoncurrentDays", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.storage_class {
+            write_characters_element(writer, "StorageClass", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct NoncurrentVersionTransitionListDeserializer;
+impl NoncurrentVersionTransitionListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<NoncurrentVersionTransition>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(NoncurrentVersionTransitionDeserializer::deserialize(
+                    tag_name, stack,
+                )?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+/// <p>When the noncurrent versions of the objects a lifecycle rule applies to are deleted, in versioned buckets.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct NoncurrentVersionExpiration {
+    /// <p>The number of days after they became noncurrent the versions are deleted.</p>
+    pub noncurrent_days: Option<i64>,
+    /// <p>The elements of the expiration none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct NoncurrentVersionExpirationDeserializer;
+impl NoncurrentVersionExpirationDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<NoncurrentVersionExpiration, XmlParseError> {
+        deserialize_elements::<_, NoncurrentVersionExpiration, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "NoncurrentDays" => {
+                        obj.noncurrent_days =
+                            Some(LongDeserializer::deserialize("NoncurrentDays", stack)?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct NoncurrentVersionExpirationSerializer;
+impl NoncurrentVersionExpirationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &NoncurrentVersionExpiration,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.noncurrent_days {
+            write_characters_element(writer, "NoncurrentDays", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>When the multipart uploads left incomplete under the objects a lifecycle rule applies to are aborted.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct AbortIncompleteMultipartUpload {
+    /// <p>The number of days after they were initiated the uploads are aborted.</p>
+    pub days_after_initiation: Option<i64>,
+    /// <p>The elements of the element none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct AbortIncompleteMultipartUploadDeserializer;
+impl AbortIncompleteMultipartUploadDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<AbortIncompleteMultipartUpload, XmlParseError> {
+        deserialize_elements::<_, AbortIncompleteMultipartUpload, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "DaysAfterInitiation" => {
+                        obj.days_after_initiation =
+                            Some(LongDeserializer::deserialize("DaysAfterInitiation", stack)?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+pub struct AbortIncompleteMultipartUploadSerializer;
+impl AbortIncompleteMultipartUploadSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &AbortIncompleteMultipartUpload,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.days_after_initiation {
+            write_characters_element(writer, "DaysAfterInitiation", &value.to_string())?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>A rule of the lifecycle configuration of a bucket, moving the objects it applies to between storage classes and deleting them as they age.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct LifecycleRule {
+    /// <p>When the multipart uploads left incomplete are aborted.</p>
+    pub abort_incomplete_multipart_upload: Option<AbortIncompleteMultipartUpload>,
+    /// <p>When the objects are deleted.</p>
+    pub expiration: Option<LifecycleRuleExpiration>,
+    /// <p>The objects the rule applies to.</p>
+    pub filter: Option<LifecycleRuleFilter>,
+    /// <p>The unique identifier of the rule, up to 255 characters.</p>
+    pub id: Option<String>,
+    /// <p>When the noncurrent versions of the objects are deleted.</p>
+    pub noncurrent_version_expiration: Option<NoncurrentVersionExpiration>,
+    /// <p>When the noncurrent versions of the objects move to other storage classes.</p>
+    pub noncurrent_version_transitions: Option<Vec<NoncurrentVersionTransition>>,
+    /// <p>The prefix of the keys of the objects the rule applies to, in rules written before filters existed. Rules with a filter go without it.</p>
+    pub prefix: Option<String>,
+    /// <p>Whether the rule is applied: <code>Enabled</code> or <code>Disabled</code>.</p>
+    pub status: String,
+    /// <p>When the objects move to other storage classes.</p>
+    pub transitions: Option<Vec<Transition>>,
+    /// <p>The elements of the rule none of the other fields are parsed from, by name.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl LifecycleRule {
+    /// The elements of the rule that aren't modeled, and would be lost when writing it back,
+    /// by path, e.g. `Filter/Or`.
+    pub fn unknown_elements(&self) -> Vec<String> {
+        fn collect(path: &str, extra: &BTreeMap<String, String>, names: &mut Vec<String>) {
+            for name in extra.keys() {
+                names.push(if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}/{}", path, name)
+                });
+            }
+        }
+
+        let mut names = Vec::new();
+        collect("", &self.extra, &mut names);
+        if let Some(ref upload) = self.abort_incomplete_multipart_upload {
+            collect("AbortIncompleteMultipartUpload", &upload.extra, &mut names);
+        }
+        if let Some(ref expiration) = self.expiration {
+            collect("Expiration", &expiration.extra, &mut names);
+        }
+        if let Some(ref filter) = self.filter {
+            collect("Filter", &filter.extra, &mut names);
+            if let Some(ref and) = filter.and {
+                collect("Filter/And", &and.extra, &mut names);
+            }
+        }
+        if let Some(ref expiration) = self.noncurrent_version_expiration {
+            collect("NoncurrentVersionExpiration", &expiration.extra, &mut names);
+        }
+        for transition in self.noncurrent_version_transitions.iter().flatten() {
+            collect("NoncurrentVersionTransition", &transition.extra, &mut names);
+        }
+        for transition in self.transitions.iter().flatten() {
+            collect("Transition", &transition.extra, &mut names);
+        }
+        names
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct LifecycleRuleDeserializer;
+impl LifecycleRuleDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<LifecycleRule, XmlParseError> {
+        deserialize_elements::<_, LifecycleRule, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "AbortIncompleteMultipartUpload" => {
+                    obj.abort_incomplete_multipart_upload =
+                        Some(AbortIncompleteMultipartUploadDeserializer::deserialize(
+                            "AbortIncompleteMultipartUpload",
+                            stack,
+                        )?);
+                }
+                "Expiration" => {
+                    obj.expiration = Some(LifecycleRuleExpirationDeserializer::deserialize(
+                        "Expiration",
+                        stack,
+                    )?);
+                }
+                "Filter" => {
+                    obj.filter = Some(LifecycleRuleFilterDeserializer::deserialize(
+                        "Filter", stack,
+                    )?);
+                }
+                "ID" => {
+                    obj.id = Some(StringDeserializer::deserialize("ID", stack)?);
+                }
+                "NoncurrentVersionExpiration" => {
+                    obj.noncurrent_version_expiration =
+                        Some(NoncurrentVersionExpirationDeserializer::deserialize(
+                            "NoncurrentVersionExpiration",
+                            stack,
+                        )?);
+                }
+                "NoncurrentVersionTransition" => {
+                    obj.noncurrent_version_transitions
+                        .get_or_insert(vec![])
+                        .extend(NoncurrentVersionTransitionListDeserializer::deserialize(
+                            "NoncurrentVersionTransition",
+                            stack,
+                        )?);
+                }
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                "Status" => {
+                    obj.status = StringDeserializer::deserialize("Status", stack)?;
+                }
+                "Transition" => {
+                    obj.transitions.get_or_insert(vec![]).extend(
+                        TransitionListDeserializer::deserialize("Transition", stack)?,
+                    );
+                }
+                _ => retain_tree(name, stack, &mut obj.extra),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct LifecycleRuleListDeserializer;
+impl LifecycleRuleListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<LifecycleRule>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(LifecycleRuleDeserializer::deserialize(tag_name, stack)?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+pub struct LifecycleRuleSerializer;
+impl LifecycleRuleSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &LifecycleRule,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.id {
+            write_characters_element(writer, "ID", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.filter {
+            LifecycleRuleFilterSerializer::serialize(&mut writer, "Filter", value)?;
+        }
+        write_characters_element(writer, "Status", &obj.status.to_string())?;
+        if let Some(ref value) = obj.transitions {
+            for transition in value {
+                TransitionSerializer::serialize(&mut writer, "Transition", transition)?;
+            }
+        }
+        if let Some(ref value) = obj.expiration {
+            LifecycleRuleExpirationSerializer::serialize(&mut writer, "Expiration", value)?;
+        }
+        if let Some(ref value) = obj.noncurrent_version_transitions {
+            for transition in value {
+                NoncurrentVersionTransitionSerializer::serialize(
+                    &mut writer,
+                    "NoncurrentVersionTransition",
+                    transition,
+                )?;
+            }
+        }
+        if let Some(ref value) = obj.noncurrent_version_expiration {
+            NoncurrentVersionExpirationSerializer::serialize(
+                &mut writer,
+                "NoncurrentVersionExpiration",
+                value,
+            )?;
+        }
+        if let Some(ref value) = obj.abort_incomplete_multipart_upload {
+            AbortIncompleteMultipartUploadSerializer::serialize(
+                &mut writer,
+                "AbortIncompleteMultipartUpload",
+                value,
+            )?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>The lifecycle configuration of a bucket: its rules, replacing the previous ones as a whole.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct BucketLifecycleConfiguration {
+    /// <p>The rules of the configuration, up to 1,000.</p>
+    pub rules: Vec<LifecycleRule>,
+}
+
+pub struct BucketLifecycleConfigurationSerializer;
+impl BucketLifecycleConfigurationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &BucketLifecycleConfiguration,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        for rule in &obj.rules {
+            LifecycleRuleSerializer::serialize(&mut writer, "Rule", rule)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::proto::xml::util as xml_util;
+    use crate::s3::ops::get_bucket_lifecycle_configuration::GetBucketLifecycleConfigurationOutputDeserializer;
+
+    const CONFIGURATION: &str = "<LifecycleConfiguration>\
+        <Rule><ID>tmp</ID>\
+        <Filter><And><Prefix>tmp/</Prefix>\
+        <Tag><Key>kind</Key><Value>scratch</Value></Tag>\
+        <Tag><Key>team</Key><Value>data</Value></Tag></And></Filter>\
+        <Status>Enabled</Status>\
+        <Expiration><Days>1</Days></Expiration>\
+        <AbortIncompleteMultipartUpload><DaysAfterInitiation>2</DaysAfterInitiation></AbortIncompleteMultipartUpload>\
+        </Rule>\
+        <Rule><ID>legacy</ID><Prefix>old/</Prefix><Status>Disabled</Status>\
+        <Transition><Days>30</Days><StorageClass>STANDARD_IA</StorageClass></Transition>\
+        <Transition><Days>90</Days><StorageClass>ARCHIVE</StorageClass></Transition>\
+        <Expiration><ExpiredObjectDeleteMarker>true</ExpiredObjectDeleteMarker></Expiration>\
+        <NoncurrentVersionTransition><NoncurrentDays>10</NoncurrentDays><StorageClass>ARCHIVE</StorageClass></NoncurrentVersionTransition>\
+        <NoncurrentVersionExpiration><NoncurrentDays>60</NoncurrentDays></NoncurrentVersionExpiration>\
+        </Rule>\
+        <Rule><ID>tagged</ID><Filter><Tag><Key>k</Key><Value>v</Value></Tag></Filter>\
+        <Status>Enabled</Status><Expiration><Date>2030-01-01T00:00:00+08:00</Date></Expiration></Rule>\
+        </LifecycleConfiguration>";
+
+    fn parse(xml: &[u8]) -> Vec<LifecycleRule> {
+        xml_util::parse_body(xml, |name, stack| {
+            GetBucketLifecycleConfigurationOutputDeserializer::deserialize(name, stack)
+        })
+        .unwrap()
+        .rules
+        .unwrap()
+    }
+
+    fn write(rules: Vec<LifecycleRule>) -> Vec<u8> {
+        let mut writer = EventWriter::new(Vec::new());
+        BucketLifecycleConfigurationSerializer::serialize(
+            &mut writer,
+            "LifecycleConfiguration",
+            &BucketLifecycleConfiguration { rules },
+        )
+        .unwrap();
+        writer.into_inner()
+    }
+
+    #[test]
+    fn configuration_round_trips() {
+        let rules = parse(CONFIGURATION.as_bytes());
+        assert_eq!(rules.len(), 3);
+        let and = rules[0].filter.as_ref().unwrap().and.as_ref().unwrap();
+        assert_eq!(and.prefix.as_deref(), Some("tmp/"));
+        assert_eq!(and.tags.as_ref().unwrap().len(), 2);
+        assert_eq!(rules[1].prefix.as_deref(), Some("old/"));
+        assert_eq!(rules[1].transitions.as_ref().unwrap().len(), 2);
+        assert!(rules.iter().all(|rule| rule.unknown_elements().is_empty()));
+
+        let written = write(rules.clone());
+        let text = String::from_utf8(written.clone()).unwrap();
+        assert!(
+            text.contains("<Filter><And><Prefix>tmp/</Prefix>"),
+            "{}",
+            text
+        );
+        assert_eq!(parse(&written), rules);
+    }
+
+    #[test]
+    fn unknown_elements_are_reported() {
+        let rules = parse(
+            b"<LifecycleConfiguration><Rule><ID>x</ID>\
+            <Filter><Or><Prefix>a/</Prefix></Or></Filter><Status>Enabled</Status>\
+            <Expiration><Days>1</Days><Hours>3</Hours></Expiration>\
+            <Replicate>yes</Replicate></Rule></LifecycleConfiguration>",
+        );
+        assert_eq!(
+            rules[0].unknown_elements(),
+            vec!["Replicate", "Expiration/Hours", "Filter/Or"]
+        );
+    }
+}
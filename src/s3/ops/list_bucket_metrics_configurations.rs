@@ -0,0 +1,155 @@
+//! The `ListBucketMetricsConfigurations` operation.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use super::metrics_configuration::*;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError};
+use crate::core::proto::xml::util::{deserialize_elements, retain_tree};
+use crate::core::request::BufferedHttpResponse;
+use crate::request::*;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListBucketMetricsConfigurationsOutput {
+    /// <p>The marker that is used as a starting point for this metrics configuration list response. This value is present if it was sent in the request.</p>
+    pub continuation_token: Option<String>,
+    /// <p>Indicates whether the returned list of metrics configurations is complete. A value of true indicates that the list is not complete and the NextContinuationToken will be provided for a subsequent request.</p>
+    pub is_truncated: Option<bool>,
+    /// <p>The list of metrics configurations for a bucket.</p>
+    pub metrics_configuration_list: Option<Vec<MetricsConfiguration>>,
+    /// <p>The marker used to continue a metrics configuration listing that has been truncated. Use the <code>NextContinuationToken</code> from a previously truncated list response to continue the listing. The continuation token is an opaque value that Amazon S3 understands.</p>
+    pub next_continuation_token: Option<String>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct ListBucketMetricsConfigurationsOutputDeserializer;
+impl ListBucketMetricsConfigurationsOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<ListBucketMetricsConfigurationsOutput, XmlParseError> {
+        deserialize_elements::<_, ListBucketMetricsConfigurationsOutput, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "ContinuationToken" => {
+                        obj.continuation_token =
+                            Some(StringDeserializer::deserialize("ContinuationToken", stack)?);
+                    }
+                    "IsTruncated" => {
+                        obj.is_truncated =
+                            Some(BooleanDeserializer::deserialize("IsTruncated", stack)?);
+                    }
+                    "MetricsConfiguration" => {
+                        obj.metrics_configuration_list.get_or_insert(vec![]).extend(
+                            MetricsConfigurationListDeserializer::deserialize(
+                                "MetricsConfiguration",
+                                stack,
+                            )?,
+                        );
+                    }
+                    "NextContinuationToken" => {
+                        obj.next_continuation_token = Some(StringDeserializer::deserialize(
+                            "NextContinuationToken",
+                            stack,
+                        )?);
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListBucketMetricsConfigurationsRequest {
+    /// <p>The name of the bucket containing the metrics configurations to retrieve.</p>
+    pub bucket: String,
+    /// <p>The marker that is used to continue a metrics configuration listing that has been truncated. Use the NextContinuationToken from a previously truncated list response to continue the listing. The continuation token is an opaque value that Amazon S3 understands.</p>
+    pub continuation_token: Option<String>,
+}
+
+/// Errors returned by ListBucketMetricsConfigurations
+#[derive(Debug, PartialEq)]
+pub enum ListBucketMetricsConfigurationsError {}
+
+impl ListBucketMetricsConfigurationsError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<ListBucketMetricsConfigurationsError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for ListBucketMetricsConfigurationsError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for ListBucketMetricsConfigurationsError {}
+
+/// Send a `ListBucketMetricsConfigurations` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: ListBucketMetricsConfigurationsRequest,
+) -> Result<ListBucketMetricsConfigurationsOutput, Ks3Error<ListBucketMetricsConfigurationsError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("GET", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    if let Some(ref x) = input.continuation_token {
+        params.put("continuation-token", x);
+    }
+    params.put_key("metrics");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "ListBucketMetricsConfigurations",
+            request,
+            ListBucketMetricsConfigurationsError::from_response,
+        )
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        ListBucketMetricsConfigurationsOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    // parse non-payload
+    Ok(result)
+}
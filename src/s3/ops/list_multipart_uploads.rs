@@ -0,0 +1,303 @@
+//! The `ListMultipartUploads` operation.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::reader::XmlEvent;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError};
+use crate::core::proto::xml::util::{deserialize_elements, retain_tree, skip_tree};
+use crate::core::request::BufferedHttpResponse;
+use crate::enums::StorageClass;
+use crate::request::*;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+/// Who initiated a multipart upload, with the same fields as the owner of an object.
+pub type Initiator = Owner;
+
+/// <p>Container for the <code>MultipartUpload</code> for the Amazon S3 object.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct MultipartUpload {
+    /// <p>Date and time at which the multipart upload was initiated.</p>
+    pub initiated: Option<DateTime<Utc>>,
+    /// <p>Identifies who initiated the multipart upload.</p>
+    pub initiator: Option<Initiator>,
+    /// <p>Key of the object for which the multipart upload was initiated.</p>
+    pub key: Option<String>,
+    /// <p>Specifies the owner of the object that is part of the multipart upload. </p>
+    pub owner: Option<Owner>,
+    /// <p>The class of storage used to store the object.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>Upload ID that identifies the multipart upload.</p>
+    pub upload_id: Option<String>,
+}
+
+#[allow(dead_code)]
+struct MultipartUploadDeserializer;
+impl MultipartUploadDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<MultipartUpload, XmlParseError> {
+        deserialize_elements::<_, MultipartUpload, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Initiated" => {
+                    obj.initiated = Some(DateTimeDeserializer::deserialize("Initiated", stack)?);
+                }
+                "Initiator" => {
+                    obj.initiator = Some(OwnerDeserializer::deserialize("Initiator", stack)?);
+                }
+                "Key" => {
+                    obj.key = Some(StringDeserializer::deserialize("Key", stack)?);
+                }
+                "Owner" => {
+                    obj.owner = Some(OwnerDeserializer::deserialize("Owner", stack)?);
+                }
+                "StorageClass" => {
+                    obj.storage_class =
+                        Some(StringDeserializer::deserialize("StorageClass", stack)?.into());
+                }
+                "UploadId" => {
+                    obj.upload_id = Some(StringDeserializer::deserialize("UploadId", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+struct MultipartUploadListDeserializer;
+impl MultipartUploadListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<MultipartUpload>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(MultipartUploadDeserializer::deserialize(tag_name, stack)?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListMultipartUploadsOutput {
+    /// <p>The name of the bucket to which the multipart upload was initiated.</p>
+    pub bucket: Option<String>,
+    /// <p>If you specify a delimiter in the request, then the result returns each distinct key prefix containing the delimiter in a <code>CommonPrefixes</code> element. The distinct key prefixes are returned in the <code>Prefix</code> child element.</p>
+    pub common_prefixes: Option<Vec<CommonPrefix>>,
+    /// <p>Contains the delimiter you specified in the request. If you don't specify a delimiter in your request, this element is absent from the response.</p>
+    pub delimiter: Option<String>,
+    /// <p>Encoding type used by Amazon S3 to encode object keys in the response.</p>
+    pub encoding_type: Option<String>,
+    /// <p>Indicates whether the returned list of multipart uploads is truncated. A value of true indicates that the list was truncated. The list can be truncated if the number of multipart uploads exceeds the limit allowed or specified by max uploads.</p>
+    pub is_truncated: Option<bool>,
+    /// <p>The key at or after which the listing began.</p>
+    pub key_marker: Option<String>,
+    /// <p>Maximum number of multipart uploads that could have been included in the response.</p>
+    pub max_uploads: Option<i64>,
+    /// <p>When a list is truncated, this element specifies the value that should be used for the key-marker request parameter in a subsequent request.</p>
+    pub next_key_marker: Option<String>,
+    /// <p>When a list is truncated, this element specifies the value that should be used for the <code>upload-id-marker</code> request parameter in a subsequent request.</p>
+    pub next_upload_id_marker: Option<String>,
+    /// <p>When a prefix is provided in the request, this field contains the specified prefix. The result contains only keys starting with the specified prefix.</p>
+    pub prefix: Option<String>,
+    /// <p>Upload ID after which listing began.</p>
+    pub upload_id_marker: Option<String>,
+    /// <p>Container for elements related to a particular multipart upload. A response can contain zero or more <code>Upload</code> elements.</p>
+    pub uploads: Option<Vec<MultipartUpload>>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct ListMultipartUploadsOutputDeserializer;
+impl ListMultipartUploadsOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<ListMultipartUploadsOutput, XmlParseError> {
+        deserialize_elements::<_, ListMultipartUploadsOutput, _>(
+            tag_name,
+            stack,
+            |name, stack, obj| {
+                match name {
+                    "Bucket" => {
+                        obj.bucket = Some(StringDeserializer::deserialize("Bucket", stack)?);
+                    }
+                    "CommonPrefixes" => {
+                        obj.common_prefixes.get_or_insert(vec![]).extend(
+                            CommonPrefixListDeserializer::deserialize("CommonPrefixes", stack)?,
+                        );
+                    }
+                    "Delimiter" => {
+                        obj.delimiter = Some(StringDeserializer::deserialize("Delimiter", stack)?);
+                    }
+                    "EncodingType" => {
+                        obj.encoding_type =
+                            Some(StringDeserializer::deserialize("EncodingType", stack)?);
+                    }
+                    "IsTruncated" => {
+                        obj.is_truncated =
+                            Some(BooleanDeserializer::deserialize("IsTruncated", stack)?);
+                    }
+                    "KeyMarker" => {
+                        obj.key_marker = Some(StringDeserializer::deserialize("KeyMarker", stack)?);
+                    }
+                    "MaxUploads" => {
+                        obj.max_uploads = Some(LongDeserializer::deserialize("MaxUploads", stack)?);
+                    }
+                    "NextKeyMarker" => {
+                        obj.next_key_marker =
+                            Some(StringDeserializer::deserialize("NextKeyMarker", stack)?);
+                    }
+                    "NextUploadIdMarker" => {
+                        obj.next_upload_id_marker = Some(StringDeserializer::deserialize(
+                            "NextUploadIdMarker",
+                            stack,
+                        )?);
+                    }
+                    "Prefix" => {
+                        obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                    }
+                    "UploadIdMarker" => {
+                        obj.upload_id_marker =
+                            Some(StringDeserializer::deserialize("UploadIdMarker", stack)?);
+                    }
+                    "Upload" => {
+                        obj.uploads.get_or_insert(vec![]).extend(
+                            MultipartUploadListDeserializer::deserialize("Upload", stack)?,
+                        );
+                    }
+                    _ => retain_tree(name, stack, &mut obj.extra),
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListMultipartUploadsRequest {
+    /// <p>The name of the bucket to which the multipart upload was initiated. </p>
+    pub bucket: String,
+    /// <p>Character you use to group keys.</p> <p>All keys that contain the same string between the prefix, if specified, and the first occurrence of the delimiter after the prefix are grouped under a single result element, <code>CommonPrefixes</code>. </p>
+    pub delimiter: Option<String>,
+    pub encoding_type: Option<String>,
+    /// <p>Together with upload-id-marker, this parameter specifies the multipart upload after which listing should begin.</p>
+    pub key_marker: Option<String>,
+    /// <p>Sets the maximum number of multipart uploads, from 1 to 1,000, to return in the response body. 1,000 is the maximum number of uploads that can be returned in a response.</p>
+    pub max_uploads: Option<i64>,
+    /// <p>Lists in-progress uploads only for those keys that begin with the specified prefix.</p>
+    pub prefix: Option<String>,
+    /// <p>Together with key-marker, specifies the multipart upload after which listing should begin. If key-marker is not specified, the upload-id-marker parameter is ignored.</p>
+    pub upload_id_marker: Option<String>,
+}
+
+/// Errors returned by ListMultipartUploads
+#[derive(Debug, PartialEq)]
+pub enum ListMultipartUploadsError {}
+
+impl ListMultipartUploadsError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<ListMultipartUploadsError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for ListMultipartUploadsError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for ListMultipartUploadsError {}
+
+/// Send a `ListMultipartUploads` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: ListMultipartUploadsRequest,
+) -> Result<ListMultipartUploadsOutput, Ks3Error<ListMultipartUploadsError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("GET", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    if let Some(ref x) = input.delimiter {
+        params.put("delimiter", x);
+    }
+    if let Some(ref x) = input.encoding_type {
+        params.put("encoding-type", x);
+    }
+    if let Some(ref x) = input.key_marker {
+        params.put("key-marker", x);
+    }
+    if let Some(ref x) = input.max_uploads {
+        params.put("max-uploads", x);
+    }
+    if let Some(ref x) = input.prefix {
+        params.put("prefix", x);
+    }
+    if let Some(ref x) = input.upload_id_marker {
+        params.put("upload-id-marker", x);
+    }
+    params.put_key("uploads");
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "ListMultipartUploads",
+            request,
+            ListMultipartUploadsError::from_response,
+        )
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        ListMultipartUploadsOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    // parse non-payload
+    Ok(result)
+}
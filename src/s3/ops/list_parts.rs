@@ -0,0 +1,285 @@
+//! The `ListParts` operation.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::reader::XmlEvent;
+use xml::EventReader;
+
+use super::list_multipart_uploads::Initiator;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::error::{XmlError, XmlErrorDeserializer};
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError, XmlResponse};
+use crate::core::proto::xml::util::{
+    deserialize_elements, find_start_element, retain_tree, skip_tree,
+};
+use crate::core::request::BufferedHttpResponse;
+use crate::enums::StorageClass;
+use crate::key::validate_key_length;
+use crate::request::*;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+/// <p>Container for elements related to a part.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct Part {
+    /// <p>Entity tag returned when the part was uploaded.</p>
+    pub e_tag: Option<String>,
+    /// <p>Date and time at which the part was uploaded.</p>
+    pub last_modified: Option<DateTime<Utc>>,
+    /// <p>Part number identifying the part. This is a positive integer between 1 and 10,000.</p>
+    pub part_number: Option<i64>,
+    /// <p>Size in bytes of the uploaded part data.</p>
+    pub size: Option<i64>,
+}
+
+#[allow(dead_code)]
+struct PartDeserializer;
+impl PartDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(tag_name: &str, stack: &mut T) -> Result<Part, XmlParseError> {
+        deserialize_elements::<_, Part, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "ETag" => {
+                    obj.e_tag = Some(StringDeserializer::deserialize("ETag", stack)?);
+                }
+                "LastModified" => {
+                    obj.last_modified =
+                        Some(DateTimeDeserializer::deserialize("LastModified", stack)?);
+                }
+                "PartNumber" => {
+                    obj.part_number = Some(LongDeserializer::deserialize("PartNumber", stack)?);
+                }
+                "Size" => {
+                    obj.size = Some(LongDeserializer::deserialize("Size", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+struct PartListDeserializer;
+impl PartListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<Part>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(PartDeserializer::deserialize(tag_name, stack)?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListPartsOutput {
+    /// <p>The name of the bucket to which the multipart upload was initiated.</p>
+    pub bucket: Option<String>,
+    /// <p>Identifies who initiated the multipart upload.</p>
+    pub initiator: Option<Initiator>,
+    /// <p> Indicates whether the returned list of parts is truncated. A true value indicates that the list was truncated. A list can be truncated if the number of parts exceeds the limit returned in the MaxParts element.</p>
+    pub is_truncated: Option<bool>,
+    /// <p>Object key for which the multipart upload was initiated.</p>
+    pub key: Option<String>,
+    /// <p>Maximum number of parts that were allowed in the response.</p>
+    pub max_parts: Option<i64>,
+    /// <p>When a list is truncated, this element specifies the last part in the list, as well as the value to use for the part-number-marker request parameter in a subsequent request.</p>
+    pub next_part_number_marker: Option<i64>,
+    /// <p> Container element that identifies the object owner, after the object is created.</p>
+    pub owner: Option<Owner>,
+    /// <p>The part number after which listing began.</p>
+    pub part_number_marker: Option<i64>,
+    /// <p> Container for elements related to a particular part. A response can contain zero or more <code>Part</code> elements.</p>
+    pub parts: Option<Vec<Part>>,
+    /// <p>Class of storage used to store the uploaded object.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>Upload ID identifying the multipart upload whose parts are being listed.</p>
+    pub upload_id: Option<String>,
+    /// <p>The elements of the response none of the other fields are parsed from, by name, e.g. ones a newer version of the service sends.</p>
+    #[cfg_attr(feature = "deserialize_structs", serde(default))]
+    pub extra: BTreeMap<String, String>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct ListPartsOutputDeserializer;
+impl ListPartsOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<ListPartsOutput, XmlParseError> {
+        deserialize_elements::<_, ListPartsOutput, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Bucket" => {
+                    obj.bucket = Some(StringDeserializer::deserialize("Bucket", stack)?);
+                }
+                "Initiator" => {
+                    obj.initiator = Some(OwnerDeserializer::deserialize("Initiator", stack)?);
+                }
+                "IsTruncated" => {
+                    obj.is_truncated =
+                        Some(BooleanDeserializer::deserialize("IsTruncated", stack)?);
+                }
+                "Key" => {
+                    obj.key = Some(StringDeserializer::deserialize("Key", stack)?);
+                }
+                "MaxParts" => {
+                    obj.max_parts = Some(LongDeserializer::deserialize("MaxParts", stack)?);
+                }
+                "NextPartNumberMarker" => {
+                    obj.next_part_number_marker = Some(LongDeserializer::deserialize(
+                        "NextPartNumberMarker",
+                        stack,
+                    )?);
+                }
+                "Owner" => {
+                    obj.owner = Some(OwnerDeserializer::deserialize("Owner", stack)?);
+                }
+                "PartNumberMarker" => {
+                    obj.part_number_marker =
+                        Some(LongDeserializer::deserialize("PartNumberMarker", stack)?);
+                }
+                "Part" => {
+                    obj.parts
+                        .get_or_insert(vec![])
+                        .extend(PartListDeserializer::deserialize("Part", stack)?);
+                }
+                "StorageClass" => {
+                    obj.storage_class =
+                        Some(StringDeserializer::deserialize("StorageClass", stack)?.into());
+                }
+                "UploadId" => {
+                    obj.upload_id = Some(StringDeserializer::deserialize("UploadId", stack)?);
+                }
+                _ => retain_tree(name, stack, &mut obj.extra),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ListPartsRequest {
+    /// <p>The name of the bucket to which the parts are being uploaded. </p>
+    pub bucket: String,
+    /// <p>Object key for which the multipart upload was initiated.</p>
+    pub key: String,
+    /// <p>Sets the maximum number of parts to return, at most 1,000, the default.</p>
+    pub max_parts: Option<i64>,
+    /// <p>Specifies the part after which listing should begin. Only parts with higher part numbers will be listed.</p>
+    pub part_number_marker: Option<i64>,
+    /// <p>Upload ID identifying the multipart upload whose parts are being listed.</p>
+    pub upload_id: String,
+}
+
+/// Errors returned by ListParts
+#[derive(Debug, PartialEq)]
+pub enum ListPartsError {
+    /// <p>The specified multipart upload does not exist.</p>
+    NoSuchUpload(String),
+}
+
+impl ListPartsError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<ListPartsError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                if parsed_error.code == "NoSuchUpload" {
+                    return Ks3Error::Service(ListPartsError::NoSuchUpload(parsed_error.message));
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for ListPartsError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ListPartsError::NoSuchUpload(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for ListPartsError {}
+
+/// Send a `ListParts` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: ListPartsRequest,
+) -> Result<ListPartsOutput, Ks3Error<ListPartsError>> {
+    validate_key_length(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("GET", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    if let Some(ref x) = input.max_parts {
+        params.put("max-parts", x);
+    }
+    if let Some(ref x) = input.part_number_marker {
+        params.put("part-number-marker", x);
+    }
+    params.put("uploadId", &input.upload_id);
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch("ListParts", request, ListPartsError::from_response)
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        ListPartsOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    // parse non-payload
+    Ok(result)
+}
@@ -0,0 +1,217 @@
+//! The metrics configurations of buckets, shared by the operations managing them.
+
+use std::io::Write;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::reader::XmlEvent;
+use xml::EventWriter;
+
+use crate::core::proto::xml::util::{deserialize_elements, skip_tree, write_characters_element};
+use crate::core::proto::xml::util::{Next, Peek, XmlParseError};
+use crate::request::*;
+
+/// <p>A conjunction (logical AND) of predicates, which is used in evaluating a metrics filter. The operator must have at least two predicates, and an object must match all of the predicates in order for the filter to apply.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct MetricsAndOperator {
+    /// <p>The prefix used when evaluating an AND predicate.</p>
+    pub prefix: Option<String>,
+    /// <p>The list of tags used when evaluating an AND predicate.</p>
+    pub tags: Option<Vec<Tag>>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct MetricsAndOperatorDeserializer;
+impl MetricsAndOperatorDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<MetricsAndOperator, XmlParseError> {
+        deserialize_elements::<_, MetricsAndOperator, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                "Tag" => {
+                    obj.tags
+                        .get_or_insert(vec![])
+                        .extend(TagListDeserializer::deserialize("Tag", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct MetricsAndOperatorSerializer;
+impl MetricsAndOperatorSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &MetricsAndOperator,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.tags {
+            TagListSerializer::serialize(&mut writer, "Tag", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Specifies a metrics configuration filter. The metrics configuration only includes objects that meet the filter's criteria. A filter must be a prefix, a tag, or a conjunction (MetricsAndOperator).</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct MetricsFilter {
+    /// <p>A conjunction (logical AND) of predicates, which is used in evaluating a metrics filter. The operator must have at least two predicates, and an object must match all of the predicates in order for the filter to apply.</p>
+    pub and: Option<MetricsAndOperator>,
+    /// <p>The prefix used when evaluating a metrics filter.</p>
+    pub prefix: Option<String>,
+    /// <p>The tag used when evaluating a metrics filter.</p>
+    pub tag: Option<Tag>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct MetricsFilterDeserializer;
+impl MetricsFilterDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<MetricsFilter, XmlParseError> {
+        deserialize_elements::<_, MetricsFilter, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "And" => {
+                    obj.and = Some(MetricsAndOperatorDeserializer::deserialize("And", stack)?);
+                }
+                "Prefix" => {
+                    obj.prefix = Some(StringDeserializer::deserialize("Prefix", stack)?);
+                }
+                "Tag" => {
+                    obj.tag = Some(TagDeserializer::deserialize("Tag", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct MetricsFilterSerializer;
+impl MetricsFilterSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &MetricsFilter,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.and {
+            MetricsAndOperatorSerializer::serialize(&mut writer, "And", value)?;
+        }
+        if let Some(ref value) = obj.prefix {
+            write_characters_element(writer, "Prefix", &value.to_string())?;
+        }
+        if let Some(ref value) = obj.tag {
+            TagSerializer::serialize(&mut writer, "Tag", value)?;
+        }
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
+
+/// <p>Specifies a metrics configuration for the CloudWatch request metrics (specified by the metrics configuration ID) from an Amazon S3 bucket. If you're updating an existing metrics configuration, note that this is a full replacement of the existing metrics configuration. If you don't include the elements you want to keep, they are erased.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct MetricsConfiguration {
+    /// <p>Specifies a metrics configuration filter. The metrics configuration will only include objects that meet the filter's criteria. A filter must be a prefix, a tag, or a conjunction (MetricsAndOperator).</p>
+    pub filter: Option<MetricsFilter>,
+    /// <p>The ID used to identify the metrics configuration.</p>
+    pub id: String,
+}
+
+#[allow(dead_code)]
+pub(crate) struct MetricsConfigurationDeserializer;
+impl MetricsConfigurationDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<MetricsConfiguration, XmlParseError> {
+        deserialize_elements::<_, MetricsConfiguration, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "Filter" => {
+                    obj.filter = Some(MetricsFilterDeserializer::deserialize("Filter", stack)?);
+                }
+                "Id" => {
+                    obj.id = StringDeserializer::deserialize("Id", stack)?;
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct MetricsConfigurationListDeserializer;
+impl MetricsConfigurationListDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<Vec<MetricsConfiguration>, XmlParseError> {
+        let mut obj = vec![];
+        loop {
+            let consume_next_tag = match stack.peek() {
+                Some(&Ok(XmlEvent::StartElement { ref name, .. })) => name.local_name == tag_name,
+                _ => false,
+            };
+
+            if consume_next_tag {
+                obj.push(MetricsConfigurationDeserializer::deserialize(
+                    tag_name, stack,
+                )?);
+            } else {
+                break;
+            }
+        }
+        Ok(obj)
+    }
+}
+
+pub struct MetricsConfigurationSerializer;
+impl MetricsConfigurationSerializer {
+    #[allow(unused_variables, warnings)]
+    pub fn serialize<W>(
+        mut writer: &mut EventWriter<W>,
+        name: &str,
+        obj: &MetricsConfiguration,
+    ) -> Result<(), xml::writer::Error>
+    where
+        W: Write,
+    {
+        writer.write(xml::writer::XmlEvent::start_element(name))?;
+        if let Some(ref value) = obj.filter {
+            MetricsFilterSerializer::serialize(&mut writer, "Filter", value)?;
+        }
+        write_characters_element(writer, "Id", &obj.id.to_string())?;
+        writer.write(xml::writer::XmlEvent::end_element())
+    }
+}
@@ -0,0 +1,96 @@
+//! The operations compiled in only with their Cargo feature, each in its own module with its
+//! request, output and error types and the sending of its request by `S3Client`.
+//!
+//! Their types are exported from the root of the crate like those of the other operations.
+//! The object and bucket operations the rest of the crate is built on, such as `get_object`,
+//! `put_object` or `list_objects`, stay in `request.rs` and are always compiled in.
+
+#[cfg(feature = "multipart")]
+pub(super) mod abort_multipart_upload;
+#[cfg(feature = "bucket-analytics")]
+pub(super) mod analytics_configuration;
+#[cfg(feature = "append-object")]
+pub(super) mod append_object;
+#[cfg(feature = "multipart")]
+pub(super) mod complete_multipart_upload;
+#[cfg(feature = "multipart")]
+pub(super) mod create_multipart_upload;
+#[cfg(feature = "bucket-analytics")]
+pub(super) mod delete_bucket_analytics_configuration;
+#[cfg(feature = "bucket-metrics")]
+pub(super) mod delete_bucket_metrics_configuration;
+#[cfg(feature = "bucket-analytics")]
+pub(super) mod get_bucket_analytics_configuration;
+#[cfg(feature = "bucket-lifecycle")]
+pub(super) mod get_bucket_lifecycle_configuration;
+#[cfg(feature = "bucket-metrics")]
+pub(super) mod get_bucket_metrics_configuration;
+#[cfg(feature = "bucket-lifecycle")]
+pub(super) mod lifecycle_configuration;
+#[cfg(feature = "bucket-analytics")]
+pub(super) mod list_bucket_analytics_configurations;
+#[cfg(feature = "bucket-metrics")]
+pub(super) mod list_bucket_metrics_configurations;
+#[cfg(feature = "multipart")]
+pub(super) mod list_multipart_uploads;
+#[cfg(feature = "multipart")]
+pub(super) mod list_parts;
+#[cfg(feature = "bucket-metrics")]
+pub(super) mod metrics_configuration;
+#[cfg(feature = "bucket-analytics")]
+pub(super) mod put_bucket_analytics_configuration;
+#[cfg(feature = "bucket-lifecycle")]
+pub(super) mod put_bucket_lifecycle_configuration;
+#[cfg(feature = "bucket-metrics")]
+pub(super) mod put_bucket_metrics_configuration;
+#[cfg(feature = "restore-object")]
+pub(super) mod restore_object;
+#[cfg(feature = "multipart")]
+pub(super) mod upload_part;
+#[cfg(feature = "multipart")]
+pub(super) mod upload_part_copy;
+
+#[cfg(feature = "multipart")]
+pub use self::abort_multipart_upload::*;
+#[cfg(feature = "bucket-analytics")]
+pub use self::analytics_configuration::*;
+#[cfg(feature = "append-object")]
+pub use self::append_object::*;
+#[cfg(feature = "multipart")]
+pub use self::complete_multipart_upload::*;
+#[cfg(feature = "multipart")]
+pub use self::create_multipart_upload::*;
+#[cfg(feature = "bucket-analytics")]
+pub use self::delete_bucket_analytics_configuration::*;
+#[cfg(feature = "bucket-metrics")]
+pub use self::delete_bucket_metrics_configuration::*;
+#[cfg(feature = "bucket-analytics")]
+pub use self::get_bucket_analytics_configuration::*;
+#[cfg(feature = "bucket-lifecycle")]
+pub use self::get_bucket_lifecycle_configuration::*;
+#[cfg(feature = "bucket-metrics")]
+pub use self::get_bucket_metrics_configuration::*;
+#[cfg(feature = "bucket-lifecycle")]
+pub use self::lifecycle_configuration::*;
+#[cfg(feature = "bucket-analytics")]
+pub use self::list_bucket_analytics_configurations::*;
+#[cfg(feature = "bucket-metrics")]
+pub use self::list_bucket_metrics_configurations::*;
+#[cfg(feature = "multipart")]
+pub use self::list_multipart_uploads::*;
+#[cfg(feature = "multipart")]
+pub use self::list_parts::*;
+#[cfg(feature = "bucket-metrics")]
+pub use self::metrics_configuration::*;
+#[cfg(feature = "bucket-analytics")]
+pub use self::put_bucket_analytics_configuration::*;
+#[cfg(feature = "bucket-lifecycle")]
+pub use self::put_bucket_lifecycle_configuration::*;
+#[cfg(feature = "bucket-metrics")]
+pub use self::put_bucket_metrics_configuration::*;
+#[cfg(feature = "restore-object")]
+pub use self::restore_object::*;
+#[cfg(feature = "multipart")]
+pub use self::upload_part::*;
+#[cfg(feature = "multipart")]
+pub use self::upload_part_copy::*;
@@ -0,0 +1,115 @@
+//! The `PutBucketAnalyticsConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::StatusCode;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventWriter;
+
+use super::analytics_configuration::*;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct PutBucketAnalyticsConfigurationRequest {
+    /// <p>The configuration and any analyses for the analytics filter.</p>
+    pub analytics_configuration: AnalyticsConfiguration,
+    /// <p>The name of the bucket to which an analytics configuration is stored.</p>
+    pub bucket: String,
+    /// <p>The ID that identifies the analytics configuration.</p>
+    pub id: String,
+    /// <p>A token identifying this change of the configuration, sent as <code>x-amz-idempotency-token</code>. A request sent again with the same token, e.g. when retried after its response was lost, is answered as the first one was instead of being applied twice or failing <code>if_match</code>.</p>
+    pub idempotency_token: Option<String>,
+    /// <p>Replace the configuration only if its entity tag is this one, as returned by <code>GetBucketAnalyticsConfiguration</code>, so that a change made by another writer since it was read isn't overwritten. Otherwise the request fails with <code>PreconditionFailed</code>.</p>
+    pub if_match: Option<String>,
+}
+
+/// Errors returned by PutBucketAnalyticsConfiguration
+#[derive(Debug, PartialEq)]
+pub enum PutBucketAnalyticsConfigurationError {
+    /// <p>The entity tag of the configuration isn't the one of <code>if_match</code>, as another writer changed it (<code>412 Precondition Failed</code>).</p>
+    PreconditionFailed {
+        /// The current entity tag of the configuration, when the response carries it.
+        e_tag: Option<String>,
+    },
+}
+
+impl PutBucketAnalyticsConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<PutBucketAnalyticsConfigurationError> {
+        if res.status == StatusCode::PRECONDITION_FAILED {
+            return Ks3Error::Service(PutBucketAnalyticsConfigurationError::PreconditionFailed {
+                e_tag: res.headers.get("ETag").cloned(),
+            });
+        }
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for PutBucketAnalyticsConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PutBucketAnalyticsConfigurationError::PreconditionFailed { ref e_tag } => {
+                match *e_tag {
+                    Some(ref e_tag) => write!(
+                        f,
+                        "The configuration was changed by another writer (ETag {})",
+                        e_tag
+                    ),
+                    None => write!(f, "The configuration was changed by another writer"),
+                }
+            }
+        }
+    }
+}
+
+impl Error for PutBucketAnalyticsConfigurationError {}
+
+/// Send a `PutBucketAnalyticsConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: PutBucketAnalyticsConfigurationRequest,
+) -> Result<(), Ks3Error<PutBucketAnalyticsConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("PUT", "s3", &client.region, &request_uri);
+
+    request.add_optional_header("If-Match", input.if_match.as_ref());
+    request.add_optional_header("x-amz-idempotency-token", input.idempotency_token.as_ref());
+
+    let mut params = Params::new();
+    params.put("id", &input.id);
+    params.put_key("analytics");
+    request.set_params(params);
+    let mut writer = EventWriter::new(Vec::new());
+    AnalyticsConfigurationSerializer::serialize(
+        &mut writer,
+        "AnalyticsConfiguration",
+        &input.analytics_configuration,
+    );
+    request.set_payload(Some(writer.into_inner()));
+
+    let mut response = client
+        .sign_and_dispatch(
+            "PutBucketAnalyticsConfiguration",
+            request,
+            PutBucketAnalyticsConfigurationError::from_response,
+        )
+        .await?;
+
+    std::mem::drop(response);
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! The `PutBucketLifecycleConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventWriter;
+
+use super::lifecycle_configuration::*;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct PutBucketLifecycleConfigurationRequest {
+    /// <p>The name of the bucket for which to set the configuration.</p>
+    pub bucket: String,
+    /// <p>The lifecycle configuration, replacing the current one as a whole.</p>
+    pub lifecycle_configuration: BucketLifecycleConfiguration,
+}
+
+/// Errors returned by PutBucketLifecycleConfiguration
+#[derive(Debug, PartialEq)]
+pub enum PutBucketLifecycleConfigurationError {}
+
+impl PutBucketLifecycleConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<PutBucketLifecycleConfigurationError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for PutBucketLifecycleConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for PutBucketLifecycleConfigurationError {}
+
+/// Send a `PutBucketLifecycleConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: PutBucketLifecycleConfigurationRequest,
+) -> Result<(), Ks3Error<PutBucketLifecycleConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("PUT", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    params.put_key("lifecycle");
+    request.set_params(params);
+    let mut writer = EventWriter::new(Vec::new());
+    BucketLifecycleConfigurationSerializer::serialize(
+        &mut writer,
+        "LifecycleConfiguration",
+        &input.lifecycle_configuration,
+    );
+    request.set_payload(Some(writer.into_inner()));
+    // The service only accepts lifecycle configurations with their digest.
+    request.set_content_md5_header();
+
+    let mut response = client
+        .sign_and_dispatch(
+            "PutBucketLifecycleConfiguration",
+            request,
+            PutBucketLifecycleConfigurationError::from_response,
+        )
+        .await?;
+
+    std::mem::drop(response);
+    Ok(())
+}
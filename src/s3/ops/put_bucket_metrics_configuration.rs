@@ -0,0 +1,113 @@
+//! The `PutBucketMetricsConfiguration` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::StatusCode;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventWriter;
+
+use super::metrics_configuration::*;
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::request::BufferedHttpResponse;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct PutBucketMetricsConfigurationRequest {
+    /// <p>The name of the bucket for which the metrics configuration is set.</p>
+    pub bucket: String,
+    /// <p>The ID used to identify the metrics configuration.</p>
+    pub id: String,
+    /// <p>A token identifying this change of the configuration, sent as <code>x-amz-idempotency-token</code>. A request sent again with the same token, e.g. when retried after its response was lost, is answered as the first one was instead of being applied twice or failing <code>if_match</code>.</p>
+    pub idempotency_token: Option<String>,
+    /// <p>Replace the configuration only if its entity tag is this one, as returned by <code>GetBucketMetricsConfiguration</code>, so that a change made by another writer since it was read isn't overwritten. Otherwise the request fails with <code>PreconditionFailed</code>.</p>
+    pub if_match: Option<String>,
+    /// <p>Specifies the metrics configuration.</p>
+    pub metrics_configuration: MetricsConfiguration,
+}
+
+/// Errors returned by PutBucketMetricsConfiguration
+#[derive(Debug, PartialEq)]
+pub enum PutBucketMetricsConfigurationError {
+    /// <p>The entity tag of the configuration isn't the one of <code>if_match</code>, as another writer changed it (<code>412 Precondition Failed</code>).</p>
+    PreconditionFailed {
+        /// The current entity tag of the configuration, when the response carries it.
+        e_tag: Option<String>,
+    },
+}
+
+impl PutBucketMetricsConfigurationError {
+    pub fn from_response(
+        res: BufferedHttpResponse,
+    ) -> Ks3Error<PutBucketMetricsConfigurationError> {
+        if res.status == StatusCode::PRECONDITION_FAILED {
+            return Ks3Error::Service(PutBucketMetricsConfigurationError::PreconditionFailed {
+                e_tag: res.headers.get("ETag").cloned(),
+            });
+        }
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for PutBucketMetricsConfigurationError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PutBucketMetricsConfigurationError::PreconditionFailed { ref e_tag } => match *e_tag {
+                Some(ref e_tag) => write!(
+                    f,
+                    "The configuration was changed by another writer (ETag {})",
+                    e_tag
+                ),
+                None => write!(f, "The configuration was changed by another writer"),
+            },
+        }
+    }
+}
+
+impl Error for PutBucketMetricsConfigurationError {}
+
+/// Send a `PutBucketMetricsConfiguration` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: PutBucketMetricsConfigurationRequest,
+) -> Result<(), Ks3Error<PutBucketMetricsConfigurationError>> {
+    let request_uri = format!("/{bucket}", bucket = input.bucket);
+
+    let mut request = SignedRequest::new("PUT", "s3", &client.region, &request_uri);
+
+    request.add_optional_header("If-Match", input.if_match.as_ref());
+    request.add_optional_header("x-amz-idempotency-token", input.idempotency_token.as_ref());
+
+    let mut params = Params::new();
+    params.put("id", &input.id);
+    params.put_key("metrics");
+    request.set_params(params);
+    let mut writer = EventWriter::new(Vec::new());
+    MetricsConfigurationSerializer::serialize(
+        &mut writer,
+        "MetricsConfiguration",
+        &input.metrics_configuration,
+    );
+    request.set_payload(Some(writer.into_inner()));
+
+    let mut response = client
+        .sign_and_dispatch(
+            "PutBucketMetricsConfiguration",
+            request,
+            PutBucketMetricsConfigurationError::from_response,
+        )
+        .await?;
+
+    std::mem::drop(response);
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! The `RestoreObject` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::{HeaderMap, StatusCode};
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+use xml::EventReader;
+use xml::EventWriter;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::error::{XmlError, XmlErrorDeserializer};
+use crate::core::proto::xml::util::{find_start_element, write_characters_element};
+use crate::core::proto::xml::util::{Next, Peek, XmlParseError, XmlResponse};
+use crate::core::request::BufferedHttpResponse;
+use crate::key::validate_key_length;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct RestoreObjectRequest {
+    /// <p>The bucket holding the archived object.</p>
+    pub bucket: String,
+    /// <p>The number of days the restored copy stays readable before the object is archived again. The service's default applies when unset.</p>
+    pub days: Option<i64>,
+    /// <p>The key of the archived object.</p>
+    pub key: String,
+    /// <p>The version of the object to restore, the latest one when unset.</p>
+    pub version_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct RestoreObjectOutput {
+    /// <p>Whether the object was already restored (<code>200 OK</code>), in which case the request only extended the life of the restored copy, rather than starting a restore (<code>202 Accepted</code>).</p>
+    pub already_restored: bool,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+/// Errors returned by RestoreObject
+#[derive(Debug, PartialEq)]
+pub enum RestoreObjectError {
+    /// <p>The object isn't archived, so there is nothing to restore.</p>
+    InvalidObjectState(String),
+    /// <p>The specified key does not exist.</p>
+    NoSuchKey(String),
+    /// <p>A restore of the object was already started and hasn't completed yet.</p>
+    RestoreAlreadyInProgress(String),
+}
+
+impl RestoreObjectError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<RestoreObjectError> {
+        {
+            let reader = EventReader::new(res.body.as_ref());
+            let mut stack = XmlResponse::new(reader.into_iter().peekable());
+            find_start_element(&mut stack);
+            if let Ok(parsed_error) = Self::deserialize(&mut stack) {
+                match &parsed_error.code[..] {
+                    "InvalidObjectState" => {
+                        return Ks3Error::Service(RestoreObjectError::InvalidObjectState(
+                            parsed_error.message,
+                        ))
+                    }
+                    "NoSuchKey" => {
+                        return Ks3Error::Service(RestoreObjectError::NoSuchKey(
+                            parsed_error.message,
+                        ))
+                    }
+                    "RestoreAlreadyInProgress" => {
+                        return Ks3Error::Service(RestoreObjectError::RestoreAlreadyInProgress(
+                            parsed_error.message,
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ks3Error::Unknown(res)
+    }
+
+    fn deserialize<T>(stack: &mut T) -> Result<XmlError, XmlParseError>
+    where
+        T: Peek + Next,
+    {
+        XmlErrorDeserializer::deserialize("Error", stack)
+    }
+}
+
+impl fmt::Display for RestoreObjectError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RestoreObjectError::InvalidObjectState(ref cause) => write!(f, "{}", cause),
+            RestoreObjectError::NoSuchKey(ref cause) => write!(f, "{}", cause),
+            RestoreObjectError::RestoreAlreadyInProgress(ref cause) => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl Error for RestoreObjectError {}
+
+/// Send a `RestoreObject` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: RestoreObjectRequest,
+) -> Result<RestoreObjectOutput, Ks3Error<RestoreObjectError>> {
+    validate_key_length(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("POST", "s3", &client.region, &request_uri);
+
+    let mut params = Params::new();
+    params.put_key("restore");
+    if let Some(ref x) = input.version_id {
+        params.put("versionId", x);
+    }
+    request.set_params(params);
+    if let Some(days) = input.days {
+        let mut writer = EventWriter::new(Vec::new());
+        writer.write(xml::writer::XmlEvent::start_element("RestoreRequest"));
+        write_characters_element(&mut writer, "Days", &days.to_string());
+        writer.write(xml::writer::XmlEvent::end_element());
+        request.set_payload(Some(writer.into_inner()));
+    }
+
+    let mut response = client
+        .sign_and_dispatch("RestoreObject", request, RestoreObjectError::from_response)
+        .await?;
+
+    let mut result = RestoreObjectOutput::default();
+    result.already_restored = response.status == StatusCode::OK;
+    result.raw_headers = response.headers.clone();
+    Ok(result)
+}
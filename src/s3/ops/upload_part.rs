@@ -0,0 +1,210 @@
+//! The `UploadPart` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::request::BufferedHttpResponse;
+use crate::enums::{RequestPayer, ServerSideEncryption};
+use crate::key::validate_key_length;
+use crate::request::*;
+use crate::s3::mismatched_etag;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct UploadPartOutput {
+    /// <p>The CRC64 (ECMA-182) checksum of the part, as reported by KS3.</p>
+    pub checksum_crc64ecma: Option<String>,
+    /// <p>Entity tag for the uploaded object.</p>
+    pub e_tag: Option<String>,
+    pub request_charged: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header confirming the encryption algorithm used.</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header to provide round-trip message integrity verification of the customer-provided encryption key.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) was used for the object.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct UploadPartRequest {
+    /// <p>Object data.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub body: Option<StreamingBody>,
+    /// <p>The name of the bucket to which the multipart upload was initiated.</p>
+    pub bucket: String,
+    /// <p>Size of the body in bytes. This parameter is useful when the size of the body cannot be determined automatically.</p>
+    pub content_length: Option<i64>,
+    /// <p>The base64-encoded 128-bit MD5 digest of the part data. This parameter is auto-populated when using the command from the CLI. This parameter is required if object lock parameters are specified.</p>
+    pub content_md5: Option<String>,
+    /// <p>Object key for which the multipart upload was initiated.</p>
+    pub key: String,
+    /// <p>Part number of part being uploaded. This is a positive integer between 1 and 10,000.</p>
+    pub part_number: i64,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>Specifies the algorithm to use to when encrypting the object (for example, AES256).</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use in encrypting data. This must be the same encryption key specified in the initiate multipart upload request.</p>
+    pub sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>Upload ID identifying the multipart upload whose part is being uploaded.</p>
+    pub upload_id: String,
+}
+
+impl UploadPartRequest {
+    /// A copy of the request without its body, e.g. to send the same request again with a new
+    /// body. The request isn't `Clone` because a streaming body can only be read once.
+    pub fn clone_without_body(&self) -> UploadPartRequest {
+        UploadPartRequest {
+            body: None,
+            bucket: self.bucket.clone(),
+            content_length: self.content_length,
+            content_md5: self.content_md5.clone(),
+            key: self.key.clone(),
+            part_number: self.part_number,
+            request_payer: self.request_payer.clone(),
+            sse_customer_algorithm: self.sse_customer_algorithm.clone(),
+            sse_customer_key: self.sse_customer_key.clone(),
+            sse_customer_key_md5: self.sse_customer_key_md5.clone(),
+            upload_id: self.upload_id.clone(),
+        }
+    }
+
+    /// A copy of the request, if its body is absent or was created from an in-memory buffer.
+    pub fn try_clone(&self) -> Option<UploadPartRequest> {
+        let body = match self.body {
+            Some(ref body) => Some(body.try_clone()?),
+            None => None,
+        };
+        Some(UploadPartRequest {
+            body,
+            ..self.clone_without_body()
+        })
+    }
+}
+
+/// Errors returned by UploadPart
+#[derive(Debug, PartialEq)]
+pub enum UploadPartError {}
+
+impl UploadPartError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<UploadPartError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for UploadPartError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for UploadPartError {}
+
+/// Send a `UploadPart` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: UploadPartRequest,
+) -> Result<UploadPartOutput, Ks3Error<UploadPartError>> {
+    validate_key_length(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("PUT", "s3", &client.region, &request_uri);
+
+    request.add_optional_header("Content-Length", input.content_length.as_ref());
+    request.add_optional_header("Content-MD5", input.content_md5.as_ref());
+    request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-algorithm",
+        input.sse_customer_algorithm.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-key",
+        input.sse_customer_key.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-key-MD5",
+        input.sse_customer_key_md5.as_ref(),
+    );
+    let mut params = Params::new();
+    params.put("partNumber", &input.part_number);
+    params.put("uploadId", &input.upload_id);
+    request.set_params(params);
+
+    let mut digest = None;
+    if let Some(__body) = input.body {
+        match __body.buffered() {
+            Some(buffer) if client.config.compute_content_md5 && input.content_md5.is_none() => {
+                digest = Some(format!("{:x}", md5::compute(buffer)));
+                request.set_payload(Some(buffer.clone()));
+                request.set_content_md5_header();
+            }
+            _ => request.set_payload_stream(__body),
+        }
+    }
+
+    let mut response = client
+        .sign_and_dispatch("UploadPart", request, UploadPartError::from_response)
+        .await?;
+
+    let result = UploadPartOutput::default();
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    result.checksum_crc64ecma = response.headers.remove("x-kss-checksum-crc64ecma");
+    result.e_tag = response.headers.remove("ETag");
+    result.request_charged = response.headers.remove("x-amz-request-charged");
+    result.sse_customer_algorithm = response
+        .headers
+        .remove("x-amz-server-side-encryption-customer-algorithm");
+    result.sse_customer_key_md5 = response
+        .headers
+        .remove("x-amz-server-side-encryption-customer-key-MD5");
+    result.ssekms_key_id = response
+        .headers
+        .remove("x-amz-server-side-encryption-aws-kms-key-id");
+    result.server_side_encryption = response
+        .headers
+        .remove("x-amz-server-side-encryption")
+        .map(Into::into); // parse non-payload
+    if let Some(digest) = digest {
+        if let Some(e_tag) = mismatched_etag(
+            result.e_tag.as_deref(),
+            result.sse_customer_algorithm.as_deref(),
+            result.server_side_encryption.as_ref(),
+            &digest,
+        ) {
+            return Err(Ks3Error::IntegrityCheckFailed {
+                expected: e_tag,
+                actual: digest,
+            });
+        }
+    }
+    Ok(result)
+}
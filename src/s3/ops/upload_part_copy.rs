@@ -0,0 +1,259 @@
+//! The `UploadPartCopy` operation.
+
+use std::error::Error;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use http::HeaderMap;
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::core::error::Ks3Error;
+use crate::core::param::ServiceParams;
+use crate::core::proto::xml::util::{self as xml_util, Next, Peek, XmlParseError};
+use crate::core::proto::xml::util::{deserialize_elements, skip_tree};
+use crate::core::request::BufferedHttpResponse;
+use crate::dates::format_http_date;
+use crate::enums::{RequestPayer, ServerSideEncryption};
+use crate::key::validate_key_length;
+use crate::range::Range;
+use crate::request::*;
+use crate::s3::S3Client;
+use crate::signature::signer::Params;
+use crate::signature::SignedRequest;
+
+/// <p>Container for all response elements.</p>
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct CopyPartResult {
+    /// <p>Entity tag of the object.</p>
+    pub e_tag: Option<String>,
+    /// <p>Date and time at which the object was uploaded.</p>
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct CopyPartResultDeserializer;
+impl CopyPartResultDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<CopyPartResult, XmlParseError> {
+        deserialize_elements::<_, CopyPartResult, _>(tag_name, stack, |name, stack, obj| {
+            match name {
+                "ETag" => {
+                    obj.e_tag = Some(StringDeserializer::deserialize("ETag", stack)?);
+                }
+                "LastModified" => {
+                    obj.last_modified =
+                        Some(DateTimeDeserializer::deserialize("LastModified", stack)?);
+                }
+                _ => skip_tree(stack),
+            }
+            Ok(())
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct UploadPartCopyOutput {
+    /// <p>Container for all response elements.</p>
+    pub copy_part_result: Option<CopyPartResult>,
+    /// <p>The version of the source object that was copied, if you have enabled versioning on the source bucket.</p>
+    pub copy_source_version_id: Option<String>,
+    pub request_charged: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header confirming the encryption algorithm used.</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>If server-side encryption with a customer-provided encryption key was requested, the response will include this header to provide round-trip message integrity verification of the customer-provided encryption key.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>If present, specifies the ID of the AWS Key Management Service (AWS KMS) symmetric customer managed customer master key (CMK) that was used for the object.</p>
+    pub ssekms_key_id: Option<String>,
+    /// <p>The server-side encryption algorithm used when storing this object in Amazon S3 (for example, AES256, aws:kms).</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// <p>All the headers of the response, including the ones parsed into the other fields, e.g. to read KS3 extension headers the fields don't cover.</p>
+    #[cfg_attr(
+        any(feature = "serialize_structs", feature = "deserialize_structs"),
+        serde(skip)
+    )]
+    pub raw_headers: HeaderMap<String>,
+}
+
+#[allow(dead_code)]
+pub(crate) struct UploadPartCopyOutputDeserializer;
+impl UploadPartCopyOutputDeserializer {
+    #[allow(dead_code, unused_variables)]
+    pub(crate) fn deserialize<T: Peek + Next>(
+        tag_name: &str,
+        stack: &mut T,
+    ) -> Result<UploadPartCopyOutput, XmlParseError> {
+        Ok(UploadPartCopyOutput {
+            copy_part_result: Some(CopyPartResultDeserializer::deserialize(
+                "CopyPartResult",
+                stack,
+            )?),
+            ..UploadPartCopyOutput::default()
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct UploadPartCopyRequest {
+    /// <p>The bucket name.</p>
+    pub bucket: String,
+    /// <p>The name of the source bucket and key name of the source object, separated by a slash (/), e.g. <code>/bucket/key</code>. Must be URL-encoded.</p>
+    pub copy_source: String,
+    /// <p>Copies the object if its entity tag (ETag) matches the specified tag.</p>
+    pub copy_source_if_match: Option<String>,
+    /// <p>Copies the object if it has been modified since the specified time.</p>
+    pub copy_source_if_modified_since: Option<DateTime<Utc>>,
+    /// <p>Copies the object if its entity tag (ETag) is different than the specified ETag.</p>
+    pub copy_source_if_none_match: Option<String>,
+    /// <p>Copies the object if it hasn't been modified since the specified time.</p>
+    pub copy_source_if_unmodified_since: Option<DateTime<Utc>>,
+    /// <p>The range of bytes to copy from the source object. The range value must use the form bytes=first-last, where the first and last are the zero-based byte offsets to copy. For example, bytes=0-9 indicates that you want to copy the first 10 bytes of the source. You can copy a range only if the source object is greater than 5 MB.</p>
+    pub copy_source_range: Option<Range>,
+    /// <p>Specifies the algorithm to use when decrypting the source object (for example, AES256).</p>
+    pub copy_source_sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use to decrypt the source object. The encryption key provided in this header must be one that was used when the source object was created.</p>
+    pub copy_source_sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321.</p>
+    pub copy_source_sse_customer_key_md5: Option<String>,
+    /// <p>Object key for which the multipart upload was initiated.</p>
+    pub key: String,
+    /// <p>Part number of part being copied. This is a positive integer between 1 and 10,000.</p>
+    pub part_number: i64,
+    pub request_payer: Option<RequestPayer>,
+    /// <p>Specifies the algorithm to use to when encrypting the object (for example, AES256).</p>
+    pub sse_customer_algorithm: Option<String>,
+    /// <p>Specifies the customer-provided encryption key for Amazon S3 to use in encrypting data. This must be the same encryption key specified in the initiate multipart upload request.</p>
+    pub sse_customer_key: Option<String>,
+    /// <p>Specifies the 128-bit MD5 digest of the encryption key according to RFC 1321.</p>
+    pub sse_customer_key_md5: Option<String>,
+    /// <p>Upload ID identifying the multipart upload whose part is being copied.</p>
+    pub upload_id: String,
+}
+
+/// Errors returned by UploadPartCopy
+#[derive(Debug, PartialEq)]
+pub enum UploadPartCopyError {}
+
+impl UploadPartCopyError {
+    pub fn from_response(res: BufferedHttpResponse) -> Ks3Error<UploadPartCopyError> {
+        Ks3Error::Unknown(res)
+    }
+}
+
+impl fmt::Display for UploadPartCopyError {
+    #[allow(unused_variables)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl Error for UploadPartCopyError {}
+
+/// Send a `UploadPartCopy` request with `client`.
+#[allow(unused_variables, warnings)]
+pub(crate) async fn send(
+    client: &S3Client,
+    input: UploadPartCopyRequest,
+) -> Result<UploadPartCopyOutput, Ks3Error<UploadPartCopyError>> {
+    validate_key_length(&input.key)?;
+    let request_uri = format!("/{bucket}/{key}", bucket = input.bucket, key = input.key);
+
+    let mut request = SignedRequest::new("PUT", "s3", &client.region, &request_uri);
+
+    request.add_header("x-amz-copy-source", &input.copy_source.to_string());
+    request.add_optional_header(
+        "x-amz-copy-source-if-match",
+        input.copy_source_if_match.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-copy-source-if-modified-since",
+        input
+            .copy_source_if_modified_since
+            .as_ref()
+            .map(format_http_date),
+    );
+    request.add_optional_header(
+        "x-amz-copy-source-if-none-match",
+        input.copy_source_if_none_match.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-copy-source-if-unmodified-since",
+        input
+            .copy_source_if_unmodified_since
+            .as_ref()
+            .map(format_http_date),
+    );
+    request.add_optional_header("x-amz-copy-source-range", input.copy_source_range.as_ref());
+    request.add_optional_header(
+        "x-amz-copy-source-server-side-encryption-customer-algorithm",
+        input.copy_source_sse_customer_algorithm.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-copy-source-server-side-encryption-customer-key",
+        input.copy_source_sse_customer_key.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-copy-source-server-side-encryption-customer-key-MD5",
+        input.copy_source_sse_customer_key_md5.as_ref(),
+    );
+    request.add_optional_header("x-amz-request-payer", input.request_payer.as_ref());
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-algorithm",
+        input.sse_customer_algorithm.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-key",
+        input.sse_customer_key.as_ref(),
+    );
+    request.add_optional_header(
+        "x-amz-server-side-encryption-customer-key-MD5",
+        input.sse_customer_key_md5.as_ref(),
+    );
+    let mut params = Params::new();
+    params.put("partNumber", &input.part_number);
+    params.put("uploadId", &input.upload_id);
+    request.set_params(params);
+
+    let mut response = client
+        .sign_and_dispatch(
+            "UploadPartCopy",
+            request,
+            UploadPartCopyError::from_response,
+        )
+        .await?;
+
+    let mut response = response;
+    let result = xml_util::parse_response(&mut response, |name, stack| {
+        UploadPartCopyOutputDeserializer::deserialize(name, stack)
+    })
+    .await?;
+    let mut result = result;
+    result.raw_headers = response.headers.clone();
+    result.copy_source_version_id = response.headers.remove("x-amz-copy-source-version-id");
+    result.request_charged = response.headers.remove("x-amz-request-charged");
+    result.sse_customer_algorithm = response
+        .headers
+        .remove("x-amz-server-side-encryption-customer-algorithm");
+    result.sse_customer_key_md5 = response
+        .headers
+        .remove("x-amz-server-side-encryption-customer-key-MD5");
+    result.ssekms_key_id = response
+        .headers
+        .remove("x-amz-server-side-encryption-aws-kms-key-id");
+    result.server_side_encryption = response
+        .headers
+        .remove("x-amz-server-side-encryption")
+        .map(Into::into); // parse non-payload
+    Ok(result)
+}
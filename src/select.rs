@@ -0,0 +1,333 @@
+//! Decoding of the response of `select_object_content`.
+//!
+//! The response body is a sequence of messages in the binary event stream format: a prelude
+//! with the lengths of the message and of its headers, the headers, the payload, and CRC32
+//! checksums of the prelude and of the whole message. `SelectObjectContentEventStream` decodes
+//! them as they arrive:
+//!
+//! ```rust,ignore
+//! let output = client.select_object_content(input).await?;
+//! let mut records = output.payload.expect("select response has a payload").records();
+//! while let Some(batch) = records.next().await {
+//!     io::stdout().write_all(&batch?)?;
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{future, Stream, StreamExt};
+use lazy_static::lazy_static;
+
+use crate::request::{parse_stats, Stats, StreamingBody};
+
+/// The length of the prelude of a message: its total length, the length of its headers and
+/// the checksum of both.
+const PRELUDE_LEN: usize = 12;
+
+/// The length of the checksum ending a message.
+const MESSAGE_CRC_LEN: usize = 4;
+
+/// An event of a `select_object_content` response.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectEvent {
+    /// A batch of records matching the query, in the output serialization of the request.
+    /// Batches don't necessarily end on a record boundary.
+    Records(Bytes),
+    /// The statistics of the query, sent once before `End`.
+    Stats(Stats),
+    /// The progress of the query so far, sent periodically when requested.
+    Progress(Stats),
+    /// Sent periodically to keep the connection alive.
+    Cont,
+    /// The query completed, and no more events follow.
+    End,
+}
+
+/// Errors returned by `SelectObjectContentEventStream`.
+#[derive(Debug)]
+pub enum SelectStreamError {
+    /// Reading the response body failed.
+    Io(io::Error),
+    /// The response isn't a valid event stream, or one of its checksums doesn't match.
+    Malformed(String),
+    /// The service failed the query after sending the response headers.
+    Service {
+        /// The error code, e.g. `CSVParsingError`.
+        code: String,
+        /// The description of the error.
+        message: String,
+    },
+    /// The response ended before the `End` event, so the records received may be incomplete.
+    Truncated,
+}
+
+impl fmt::Display for SelectStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SelectStreamError::Io(ref err) => write!(f, "{}", err),
+            SelectStreamError::Malformed(ref cause) => {
+                write!(f, "Malformed event stream: {}", cause)
+            }
+            SelectStreamError::Service {
+                ref code,
+                ref message,
+            } => write!(f, "{}: {}", code, message),
+            SelectStreamError::Truncated => {
+                write!(f, "The event stream ended before the End event")
+            }
+        }
+    }
+}
+
+impl Error for SelectStreamError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SelectStreamError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The events of a `select_object_content` response, decoded from its body as they arrive.
+///
+/// The stream ends after the `End` event, or after the first error.
+pub struct SelectObjectContentEventStream {
+    body: StreamingBody,
+    buffer: BytesMut,
+    done: bool,
+}
+
+impl SelectObjectContentEventStream {
+    pub(crate) fn new(body: StreamingBody) -> SelectObjectContentEventStream {
+        SelectObjectContentEventStream {
+            body,
+            buffer: BytesMut::new(),
+            done: false,
+        }
+    }
+
+    /// Only the batches of records, ending with an error if the query fails or the response is
+    /// cut short.
+    pub fn records(self) -> impl Stream<Item = Result<Bytes, SelectStreamError>> {
+        self.filter_map(|event| {
+            future::ready(match event {
+                Ok(SelectEvent::Records(records)) => Some(Ok(records)),
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+        })
+    }
+
+    /// The next event in the buffered bytes, if they hold a whole message.
+    fn decode(&mut self) -> Result<Option<SelectEvent>, SelectStreamError> {
+        while let Some(message) = decode_message(&mut self.buffer)? {
+            if let Some(event) = message.into_event()? {
+                return Ok(Some(event));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl fmt::Debug for SelectObjectContentEventStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SelectObjectContentEventStream")
+            .field("buffered", &self.buffer.len())
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl Stream for SelectObjectContentEventStream {
+    type Item = Result<SelectEvent, SelectStreamError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if self.done {
+                return Poll::Ready(None);
+            }
+            match self.decode() {
+                Ok(Some(event)) => {
+                    self.done = event == SelectEvent::End;
+                    return Poll::Ready(Some(Ok(event)));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+            match futures::ready!(Pin::new(&mut self.body).poll_next(cx)) {
+                Some(Ok(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(SelectStreamError::Io(err))));
+                }
+                None => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(SelectStreamError::Truncated)));
+                }
+            }
+        }
+    }
+}
+
+/// A decoded message: its string headers and its payload.
+struct Message {
+    headers: HashMap<String, String>,
+    payload: Bytes,
+}
+
+impl Message {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// The event carried by the message, or `None` for events of unknown types.
+    fn into_event(self) -> Result<Option<SelectEvent>, SelectStreamError> {
+        match self.header(":message-type") {
+            Some("event") => {}
+            Some("error") => {
+                return Err(SelectStreamError::Service {
+                    code: self.header(":error-code").unwrap_or_default().to_owned(),
+                    message: self.header(":error-message").unwrap_or_default().to_owned(),
+                })
+            }
+            other => {
+                return Err(SelectStreamError::Malformed(format!(
+                    "unexpected message type {:?}",
+                    other
+                )))
+            }
+        }
+        let stats = |tag_name| {
+            parse_stats(tag_name, &self.payload).map_err(|err| SelectStreamError::Malformed(err.0))
+        };
+        let event = match self.header(":event-type") {
+            Some("Records") => SelectEvent::Records(self.payload.clone()),
+            Some("Stats") => SelectEvent::Stats(stats("Stats")?),
+            Some("Progress") => SelectEvent::Progress(stats("Progress")?),
+            Some("Cont") => SelectEvent::Cont,
+            Some("End") => SelectEvent::End,
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+}
+
+/// Take the first message out of `buffer`, if it holds all of it.
+fn decode_message(buffer: &mut BytesMut) -> Result<Option<Message>, SelectStreamError> {
+    if buffer.len() < PRELUDE_LEN {
+        return Ok(None);
+    }
+    let mut prelude = &buffer[..PRELUDE_LEN];
+    let total_len = prelude.get_u32() as usize;
+    let headers_len = prelude.get_u32() as usize;
+    if prelude.get_u32() != crc32(&buffer[..8]) {
+        return Err(malformed("prelude checksum mismatch"));
+    }
+    if total_len < PRELUDE_LEN + MESSAGE_CRC_LEN + headers_len {
+        return Err(malformed("message shorter than its headers"));
+    }
+    if buffer.len() < total_len {
+        return Ok(None);
+    }
+
+    let frame = buffer.split_to(total_len).freeze();
+    let payload_end = total_len - MESSAGE_CRC_LEN;
+    if (&frame[payload_end..]).get_u32() != crc32(&frame[..payload_end]) {
+        return Err(malformed("message checksum mismatch"));
+    }
+    let headers = decode_headers(&frame[PRELUDE_LEN..PRELUDE_LEN + headers_len])?;
+    Ok(Some(Message {
+        headers,
+        payload: frame.slice(PRELUDE_LEN + headers_len..payload_end),
+    }))
+}
+
+/// Decode the headers of a message, keeping the ones with string values.
+fn decode_headers(mut data: &[u8]) -> Result<HashMap<String, String>, SelectStreamError> {
+    let mut headers = HashMap::new();
+    while data.has_remaining() {
+        let name_len = usize::from(data.get_u8());
+        let name = take(&mut data, name_len)?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        if !data.has_remaining() {
+            return Err(malformed("header without a value"));
+        }
+        let value_len = match data.get_u8() {
+            // Boolean true and false, with no value bytes.
+            0 | 1 => 0,
+            // Byte, short, integer, long and timestamp.
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            // Byte array and string, preceded by their length.
+            6 | 7 => {
+                if data.remaining() < 2 {
+                    return Err(malformed("truncated header value"));
+                }
+                let len = usize::from(data.get_u16());
+                let value = take(&mut data, len)?;
+                headers.insert(name, String::from_utf8_lossy(value).into_owned());
+                continue;
+            }
+            // UUID.
+            9 => 16,
+            other => return Err(malformed(&format!("unknown header type {}", other))),
+        };
+        take(&mut data, value_len)?;
+    }
+    Ok(headers)
+}
+
+/// Take the next `len` bytes of `data`.
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], SelectStreamError> {
+    if data.len() < len {
+        return Err(malformed("truncated header"));
+    }
+    let (taken, rest) = data.split_at(len);
+    *data = rest;
+    Ok(taken)
+}
+
+fn malformed(cause: &str) -> SelectStreamError {
+    SelectStreamError::Malformed(cause.to_owned())
+}
+
+/// The reflected IEEE 802.3 polynomial.
+const CRC32_POLY: u32 = 0xedb8_8320;
+
+lazy_static! {
+    static ref CRC32_TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut crc = i as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ CRC32_POLY
+                } else {
+                    crc >> 1
+                };
+            }
+            *entry = crc;
+        }
+        table
+    };
+}
+
+/// The CRC32 checksum of `data`, as used by the event stream format.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc = CRC32_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -0,0 +1,132 @@
+//! Spreading keys over partitions of a bucket by prefixing them with a hash, for very high
+//! write rates.
+//!
+//! The service partitions a bucket by key ranges, so keys sharing a long prefix, such as
+//! `logs/2020-06-01/...`, are all written to the same partition. A `KeyTransformer` maps the
+//! keys seen by the application to the keys stored in the bucket and back; with
+//! `HashPrefixSharding`, `logs/2020-06-01/a.gz` is stored as e.g. `7f/logs/2020-06-01/a.gz`.
+//! The transfer and sync helpers take a transformer in their options, and `list_sharded` lists
+//! a prefix of the application keys across all the shards:
+//!
+//! ```rust,ignore
+//! let sharding: Arc<dyn KeyTransformer> = Arc::new(HashPrefixSharding::new(2));
+//! let options = UploadOptions {
+//!     key_transformer: Some(sharding.clone()),
+//!     ..Default::default()
+//! };
+//! upload_file(&client, "logs", "2020-06-01/a.gz", "a.gz", &options).await?;
+//! let objects = list_sharded(&client, "logs", &*sharding, "2020-06-01/").await?;
+//! ```
+//!
+//! The transformation has to stay the same for the life of the bucket: changing it leaves the
+//! keys stored before out of reach.
+
+use std::fmt;
+
+use futures::{stream, StreamExt, TryStreamExt};
+
+use crate::core::error::Ks3Error;
+use crate::request::*;
+use crate::s3::S3;
+use crate::sync::list_prefix;
+
+/// The number of shard listings `list_sharded` runs at once.
+const LIST_CONCURRENCY: usize = 8;
+
+/// Maps the keys of the application to the keys stored in the bucket, and back.
+pub trait KeyTransformer: fmt::Debug + Send + Sync {
+    /// The key `key` is stored under.
+    fn to_stored(&self, key: &str) -> String;
+
+    /// The key of the application stored as `stored`, or None if `stored` isn't the result of
+    /// the transformation, e.g. an object written without it.
+    fn to_logical(&self, stored: &str) -> Option<String>;
+
+    /// The prefixes of the stored keys to list for the keys of the application starting with
+    /// `prefix`. Listing them may return other keys as well, which `to_logical` maps back to
+    /// keys not starting with `prefix`.
+    fn listing_prefixes(&self, prefix: &str) -> Vec<String>;
+}
+
+/// Stores every key under a prefix made of the first hexadecimal digits of its MD5 digest,
+/// spreading them over up to `16^digits` partitions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashPrefixSharding {
+    digits: usize,
+}
+
+impl HashPrefixSharding {
+    /// Sharding with prefixes of `digits` hexadecimal digits, brought between 1 and 4.
+    pub fn new(digits: usize) -> HashPrefixSharding {
+        HashPrefixSharding {
+            digits: digits.clamp(1, 4),
+        }
+    }
+
+    /// The number of shards.
+    pub fn shards(&self) -> usize {
+        1 << (4 * self.digits)
+    }
+
+    /// The shard `key` is stored in, as its prefix without the `/`.
+    pub fn shard(&self, key: &str) -> String {
+        let digest = format!("{:x}", md5::compute(key));
+        digest[..self.digits].to_owned()
+    }
+}
+
+impl KeyTransformer for HashPrefixSharding {
+    fn to_stored(&self, key: &str) -> String {
+        format!("{}/{}", self.shard(key), key)
+    }
+
+    fn to_logical(&self, stored: &str) -> Option<String> {
+        let (shard, key) = stored.split_once('/')?;
+        if shard == self.shard(key) {
+            Some(key.to_owned())
+        } else {
+            None
+        }
+    }
+
+    fn listing_prefixes(&self, prefix: &str) -> Vec<String> {
+        (0..self.shards())
+            .map(|shard| format!("{:0width$x}/{}", shard, prefix, width = self.digits))
+            .collect()
+    }
+}
+
+/// Every object of `bucket` whose application key starts with `prefix`, with `key` set to the
+/// application key, sorted by key.
+///
+/// The prefixes given by `transformer` are listed a few at a time. Stored keys that
+/// `transformer` doesn't map back are left out.
+pub async fn list_sharded<C>(
+    client: &C,
+    bucket: &str,
+    transformer: &dyn KeyTransformer,
+    prefix: &str,
+) -> Result<Vec<Object>, Ks3Error<ListObjectsError>>
+where
+    C: S3 + Sync,
+{
+    let shards = stream::iter(transformer.listing_prefixes(prefix))
+        .map(|shard| async move { list_prefix(client, bucket, &shard).await })
+        .buffer_unordered(LIST_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+    let mut objects = shards
+        .into_iter()
+        .flat_map(|shard| shard.into_values())
+        .filter_map(|mut object| {
+            let key = transformer.to_logical(object.key.as_deref()?)?;
+            if !key.starts_with(prefix) {
+                return None;
+            }
+            object.key = Some(key);
+            Some(object)
+        })
+        .collect::<Vec<_>>();
+    objects.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(objects)
+}
@@ -0,0 +1,56 @@
+//! Normalization of the host requests are sent to, shared by the `Host` header and the URL.
+//!
+//! Both are built from `SignedRequest::hostname`, which normalizes the host of the region or
+//! of `set_hostname` with `normalize_host`. The URL also keys the connection pool of the HTTP
+//! client, so `S3.Example.com.`, `s3.example.com:443` and `s3.example.com` over HTTPS all
+//! reuse the same connections and are all sent with the same `Host` header.
+
+/// The default port of `scheme`, if it has one.
+fn default_port(scheme: &str) -> Option<&'static str> {
+    if scheme.eq_ignore_ascii_case("https") {
+        Some("443")
+    } else if scheme.eq_ignore_ascii_case("http") {
+        Some("80")
+    } else {
+        None
+    }
+}
+
+/// `host`, with an optional port, as sent to with `scheme`: lowercase, without the trailing dot
+/// of a fully qualified name and without the port when it's the default one of `scheme`.
+///
+/// ```rust
+/// use ks3::signature::host::normalize_host;
+///
+/// assert_eq!(normalize_host("https", "KS3-CN-Beijing.ksyun.com."), "ks3-cn-beijing.ksyun.com");
+/// assert_eq!(normalize_host("https", "localhost:443"), "localhost");
+/// assert_eq!(normalize_host("http", "localhost:9000"), "localhost:9000");
+/// assert_eq!(normalize_host("http", "[::1]:80"), "[::1]");
+/// ```
+pub fn normalize_host(scheme: &str, host: &str) -> String {
+    let host = host.trim();
+    let (name, port) = if host.starts_with('[') {
+        // An IPv6 literal, whose colons aren't port separators.
+        match host.find(']') {
+            Some(end) => (&host[..=end], host[end + 1..].strip_prefix(':')),
+            None => (host, None),
+        }
+    } else {
+        match host.rfind(':') {
+            Some(colon) if !host[..colon].contains(':') => {
+                (&host[..colon], Some(&host[colon + 1..]))
+            }
+            _ => (host, None),
+        }
+    };
+
+    let mut normalized = name.trim_end_matches('.').to_ascii_lowercase();
+    match port {
+        Some(port) if !port.is_empty() && Some(port) != default_port(scheme) => {
+            normalized.push(':');
+            normalized.push_str(port);
+        }
+        _ => {}
+    }
+    normalized
+}
@@ -1,8 +1,13 @@
+pub mod host;
 pub mod region;
 pub mod signer;
 pub mod stream;
+pub mod vectors;
 pub use region::Region;
-pub use signer::SignedRequest;
-pub use stream::ByteStream;
+pub use signer::{
+    ContentLengthPolicy, DefaultContentType, SignedRequest, UriEncoding,
+    DEFAULT_CANONICAL_HEADER_PREFIXES,
+};
+pub use stream::{BodyStalled, ByteStream};
 
 mod ks_time;
@@ -4,6 +4,7 @@
 //!
 //! For example: `UsEast1` to "us-east-1"
 
+#[cfg(not(target_arch = "wasm32"))]
 use crate::credential::ProfileProvider;
 use serde::ser::SerializeTuple;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
@@ -287,10 +288,14 @@ impl Default for Region {
     fn default() -> Region {
         match std::env::var("AWS_DEFAULT_REGION").or_else(|_| std::env::var("AWS_REGION")) {
             Ok(ref v) => Region::from_str(v).unwrap_or(Region::UsEast1),
+            #[cfg(not(target_arch = "wasm32"))]
             Err(_) => match ProfileProvider::region() {
                 Ok(Some(region)) => Region::from_str(&region).unwrap_or(Region::UsEast1),
                 _ => Region::UsEast1,
             },
+            // There's no profile to read the region from in a browser or a worker.
+            #[cfg(target_arch = "wasm32")]
+            Err(_) => Region::UsEast1,
         }
     }
 }
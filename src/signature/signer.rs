@@ -11,22 +11,29 @@
 
 use bytes::Bytes;
 use hmac::{Hmac, Mac, NewMac};
+use http::header::{GetAll, HeaderMap, HeaderName, HeaderValue};
+#[cfg(not(target_arch = "wasm32"))]
 use hyper::Body;
 use log::{debug, log_enabled, Level::Debug};
 use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use sha1::Sha1;
 use time::OffsetDateTime;
 
+use crate::core::clock::{Clock, SystemClock};
 use crate::credential::AwsCredentials;
+use crate::signature::host::normalize_host;
 use crate::signature::ks_time::rfc1123;
 use crate::signature::ByteStream;
 use crate::signature::Region;
 use std::collections::BTreeMap;
 use std::fmt;
-use std::str;
 
 pub type Params = BTreeMap<String, Option<String>>;
 
+/// The prefixes of the headers included in the string to sign by default: the `x-amz-`
+/// headers of S3 and the `x-kss-` headers of KS3, which the service signs alike.
+pub const DEFAULT_CANONICAL_HEADER_PREFIXES: &[&str] = &["x-amz-", "x-kss-"];
+
 /// Possible payloads included in a `SignedRequest`.
 pub enum SignedRequestPayload {
     /// Transfer payload in a single chunk
@@ -37,12 +44,21 @@ pub enum SignedRequestPayload {
 
 impl SignedRequestPayload {
     /// Convert `SignedRequestPayload` into a hyper `Body`
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn into_body(self) -> Body {
         match self {
             SignedRequestPayload::Buffer(bytes) => Body::from(bytes),
             SignedRequestPayload::Stream(stream) => Body::wrap_stream(stream),
         }
     }
+
+    /// The size of the payload, if known.
+    pub fn size_hint(&self) -> Option<usize> {
+        match *self {
+            SignedRequestPayload::Buffer(ref bytes) => Some(bytes.len()),
+            SignedRequestPayload::Stream(ref stream) => stream.size_hint(),
+        }
+    }
 }
 
 impl fmt::Debug for SignedRequestPayload {
@@ -60,6 +76,107 @@ impl fmt::Debug for SignedRequestPayload {
     }
 }
 
+/// The `Content-Type` sent with requests that carry a payload without one.
+///
+/// The content type is part of the string to sign, so it's filled in before the request is
+/// signed, and sent as it was signed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum DefaultContentType {
+    /// Leave the header out, for gateways that expect it absent when checking signatures.
+    #[default]
+    None,
+    /// `application/octet-stream`, for gateways that reject bodies without a type.
+    OctetStream,
+    /// The given content type.
+    Custom(String),
+}
+
+impl DefaultContentType {
+    /// The value of the header, if one is sent.
+    pub fn content_type(&self) -> Option<&str> {
+        match *self {
+            DefaultContentType::None => None,
+            DefaultContentType::OctetStream => Some("application/octet-stream"),
+            DefaultContentType::Custom(ref content_type) => Some(content_type),
+        }
+    }
+}
+
+/// When `complement` sets the `Content-Length` header of a request.
+///
+/// The header isn't part of the string to sign, so this only changes what is sent. A streamed
+/// payload is sent with its exact length when it was created from a buffer or a file, and
+/// otherwise with the `Content-Length` set on the request or else its size hint. One of unknown
+/// size gets none, and is sent chunked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ContentLengthPolicy {
+    /// Set it on requests with a payload, and to `0` on the requests without one whose method
+    /// usually carries one, such as `PUT` and `POST`. `GET`, `HEAD`, `DELETE` and `OPTIONS`
+    /// requests without a payload go without it, as some gateways reject them otherwise.
+    #[default]
+    ByMethod,
+    /// Set it on every request, to `0` on the ones without a payload.
+    Always,
+    /// Only set it on requests with a payload.
+    PayloadOnly,
+}
+
+impl ContentLengthPolicy {
+    /// Whether a request with `method` and no payload gets `Content-Length: 0`.
+    pub fn sends_zero_length(self, method: &str) -> bool {
+        match self {
+            ContentLengthPolicy::ByMethod => !matches!(
+                &*method.to_ascii_uppercase(),
+                "GET" | "HEAD" | "DELETE" | "OPTIONS"
+            ),
+            ContentLengthPolicy::Always => true,
+            ContentLengthPolicy::PayloadOnly => false,
+        }
+    }
+}
+
+/// How `complement` percent-encodes the path and the query string of a request, which are
+/// signed as they are sent.
+///
+/// KS3 follows RFC 3986, but some S3-compatible gateways decode or sign the URI differently:
+/// older ones expect `~` encoded and spaces in the query string as `+`, and keys that are
+/// already percent-encoded must be sent as they are to some.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UriEncoding {
+    /// Encode every character but the unreserved ones of RFC 3986, letters, digits, `-`, `.`,
+    /// `_` and `~`, as well as `/` in the path. Spaces become `%20`.
+    #[default]
+    Strict,
+    /// Encode `~` as `%7E` too, and spaces in the query string as `+`, as form encoding does.
+    Legacy,
+    /// Keep the `%XY` escapes already in the path and the parameters, and encode the other
+    /// characters as `Strict` does, e.g. for keys stored percent-encoded.
+    Passthrough,
+}
+
+impl UriEncoding {
+    /// `path`, with its `/` separators, encoded for the path of a request.
+    pub fn encode_path(self, path: &str) -> String {
+        match self {
+            UriEncoding::Strict => encode_uri_path(path),
+            UriEncoding::Legacy => utf8_percent_encode(path, &LEGACY_PATH_ENCODE_SET).collect(),
+            UriEncoding::Passthrough => encode_keeping_escapes(path, &STRICT_PATH_ENCODE_SET),
+        }
+    }
+
+    /// `value`, the name or the value of a query parameter, encoded for the query string.
+    pub fn encode_query(self, value: &str) -> String {
+        match self {
+            UriEncoding::Strict => encode_uri_strict(value),
+            // `+` itself is encoded, so every `+` left stands for a space.
+            UriEncoding::Legacy => utf8_percent_encode(value, &LEGACY_ENCODE_SET)
+                .collect::<String>()
+                .replace("%20", "+"),
+            UriEncoding::Passthrough => encode_keeping_escapes(value, &STRICT_ENCODE_SET),
+        }
+    }
+}
+
 /// A data structure for all the elements of an HTTP request that are involved in
 /// the Amazon Signature Version 2 signing process
 #[derive(Debug)]
@@ -68,24 +185,41 @@ pub struct SignedRequest {
     pub method: String,
     /// The AWS Service
     pub service: String,
-    /// The AWS Region
+    /// The AWS Region. Call `invalidate` after changing it directly.
     pub region: Region,
-    /// The HTTP request path
+    /// The HTTP request path. Call `invalidate` after changing it directly.
     pub path: String,
-    /// The HTTP Request Headers
-    pub headers: BTreeMap<String, Vec<Vec<u8>>>,
-    /// The HTTP request paramaters
+    /// The HTTP Request Headers, sent as they are. The canonical headers are sorted when the
+    /// request is signed.
+    pub headers: HeaderMap,
+    /// The HTTP request paramaters. Call `invalidate` after changing them directly.
     pub params: Params,
     /// The HTTP/HTTPS protocol
     pub scheme: Option<String>,
-    /// The AWS hostname
+    /// The AWS hostname. Call `invalidate` after changing it directly.
     pub hostname: Option<String>,
-    /// The HTTP Content
+    /// The HTTP Content. Call `invalidate` after changing it directly.
     pub payload: Option<SignedRequestPayload>,
     /// The Standardised query string
     pub canonical_query_string: String,
     /// The Standardised URI
     pub canonical_uri: String,
+    /// The `Content-Type` filled in by `complement` when the request has a payload without one.
+    default_content_type: DefaultContentType,
+    /// When `complement` sets the `Content-Length` header.
+    content_length_policy: ContentLengthPolicy,
+    /// How `complement` encodes the canonical URI and query string.
+    uri_encoding: UriEncoding,
+    /// The prefixes of the headers included in the string to sign.
+    canonical_header_prefixes: &'static [&'static str],
+    /// Why the first header that isn't a valid HTTP header was left out, reported when the
+    /// request is dispatched.
+    header_error: Option<String>,
+    /// The resource part of the string to sign, computed by `complement`.
+    canonical_resource: String,
+    /// Whether the canonical parts of the request are up to date, so that signing it again,
+    /// e.g. to retry it, only refreshes the `Date` and `Authorization` headers.
+    complete: bool,
 }
 
 impl SignedRequest {
@@ -96,24 +230,95 @@ impl SignedRequest {
             service: service.to_string(),
             region: region.clone(),
             path: path.to_string(),
-            headers: BTreeMap::new(),
+            headers: HeaderMap::new(),
             params: Params::new(),
             scheme: None,
             hostname: None,
             payload: None,
             canonical_query_string: String::new(),
             canonical_uri: String::new(),
+            default_content_type: DefaultContentType::None,
+            content_length_policy: ContentLengthPolicy::ByMethod,
+            uri_encoding: UriEncoding::Strict,
+            canonical_header_prefixes: DEFAULT_CANONICAL_HEADER_PREFIXES,
+            header_error: None,
+            canonical_resource: String::new(),
+            complete: false,
         }
     }
 
+    /// A copy of the request, if its payload is in memory. Requests with a streaming payload can
+    /// only be sent once.
+    pub fn try_clone(&self) -> Option<SignedRequest> {
+        let payload = match self.payload {
+            None => None,
+            Some(SignedRequestPayload::Buffer(ref bytes)) => {
+                Some(SignedRequestPayload::Buffer(bytes.clone()))
+            }
+            Some(SignedRequestPayload::Stream(ref stream)) => {
+                Some(SignedRequestPayload::Stream(stream.try_clone()?))
+            }
+        };
+        Some(SignedRequest {
+            method: self.method.clone(),
+            service: self.service.clone(),
+            region: self.region.clone(),
+            path: self.path.clone(),
+            headers: self.headers.clone(),
+            params: self.params.clone(),
+            scheme: self.scheme.clone(),
+            hostname: self.hostname.clone(),
+            payload,
+            canonical_query_string: self.canonical_query_string.clone(),
+            canonical_uri: self.canonical_uri.clone(),
+            default_content_type: self.default_content_type.clone(),
+            content_length_policy: self.content_length_policy,
+            uri_encoding: self.uri_encoding,
+            canonical_header_prefixes: self.canonical_header_prefixes,
+            header_error: self.header_error.clone(),
+            canonical_resource: self.canonical_resource.clone(),
+            complete: self.complete,
+        })
+    }
+
     /// Sets the value of the "content-type" header.
     pub fn set_content_type(&mut self, content_type: String) {
         self.add_header("content-type", &content_type);
     }
 
+    /// Sets the `Content-Type` sent when the request has a payload but no such header. None is
+    /// sent by default.
+    pub fn set_default_content_type(&mut self, default_content_type: DefaultContentType) {
+        self.default_content_type = default_content_type;
+        self.complete = false;
+    }
+
+    /// Sets when the `Content-Length` header is sent, `ContentLengthPolicy::ByMethod` by
+    /// default.
+    pub fn set_content_length_policy(&mut self, policy: ContentLengthPolicy) {
+        self.content_length_policy = policy;
+        self.complete = false;
+    }
+
+    /// Sets how the path and the query string are percent-encoded, `UriEncoding::Strict` by
+    /// default.
+    pub fn set_uri_encoding(&mut self, encoding: UriEncoding) {
+        self.uri_encoding = encoding;
+        self.complete = false;
+    }
+
+    /// Sets the prefixes of the headers included in the string to sign, matched against the
+    /// lowercase header names. Defaults to `DEFAULT_CANONICAL_HEADER_PREFIXES`, e.g. a gateway
+    /// that only signs `x-kss-` headers needs `&["x-kss-"]`.
+    pub fn set_canonical_header_prefixes(&mut self, prefixes: &'static [&'static str]) {
+        self.canonical_header_prefixes = prefixes;
+        self.complete = false;
+    }
+
     /// Sets the target hostname
     pub fn set_hostname(&mut self, hostname: Option<String>) {
         self.hostname = hostname;
+        self.complete = false;
     }
 
     /// Sets the target hostname using the current service type and region
@@ -121,16 +326,19 @@ impl SignedRequest {
     /// See the implementation of build_hostname to see how this is done
     pub fn set_endpoint_prefix(&mut self, endpoint_prefix: String) {
         self.hostname = Some(build_hostname(&endpoint_prefix, &self.region));
+        self.complete = false;
     }
 
     /// Sets the new body (payload)
     pub fn set_payload<B: Into<Bytes>>(&mut self, payload: Option<B>) {
         self.payload = payload.map(|chunk| SignedRequestPayload::Buffer(chunk.into()));
+        self.complete = false;
     }
 
     /// Sets the new body (payload) as a stream
     pub fn set_payload_stream(&mut self, stream: ByteStream) {
         self.payload = Some(SignedRequestPayload::Stream(stream));
+        self.complete = false;
     }
 
     /// Computes and sets the Content-MD5 header based on the current payload.
@@ -161,7 +369,7 @@ impl SignedRequest {
 
     /// Invokes `canonical_uri(path)` to return a canonical path
     pub fn canonical_path(&self) -> String {
-        canonical_uri(&self.path, &self.region)
+        canonical_uri(&self.path, &self.region, self.uri_encoding)
     }
 
     /// Returns the current canonical URI
@@ -177,10 +385,16 @@ impl SignedRequest {
     }
 
     /// Returns the current headers
-    pub fn headers(&self) -> &BTreeMap<String, Vec<Vec<u8>>> {
+    pub fn headers(&self) -> &HeaderMap {
         &self.headers
     }
 
+    /// Why a header added to the request was left out, as its name or value isn't valid in
+    /// HTTP. Such requests can't be sent.
+    pub fn header_error(&self) -> Option<&str> {
+        self.header_error.as_deref()
+    }
+
     /// Returns the current http scheme (https or http)
     pub fn scheme(&self) -> String {
         match self.scheme {
@@ -217,44 +431,74 @@ impl SignedRequest {
         }
     }
 
-    /// Converts hostname to String if it exists, else it invokes build_hostname()
+    /// The host the request is sent to, with its port: the one set with `set_hostname` or the
+    /// one of the region, normalized with `normalize_host` for the scheme of the request. Both
+    /// the `Host` header and the URL use it.
     pub fn hostname(&self) -> String {
+        let scheme = self.scheme();
         // hostname may be already set by an endpoint prefix
         match self.hostname {
-            Some(ref h) => h.to_string(),
-            None => build_hostname(&self.service, &self.region),
+            Some(ref h) => normalize_host(&scheme, h),
+            None => normalize_host(&scheme, &build_hostname(&self.service, &self.region)),
         }
     }
 
-    // gets the value associated with the given key
-    pub fn get_header<K: ToString>(&self, key: K) -> Vec<Vec<u8>> {
-        let mut key_lower = key.to_string();
-        key_lower.make_ascii_lowercase();
-
-        match self.headers.get(&key_lower) {
-            None => vec![],
-            Some(v) => v.to_owned(),
+    /// The URL the request is sent to: its scheme, host name, canonical path and canonical
+    /// query string.
+    pub fn url(&self) -> String {
+        let mut url = format!(
+            "{}://{}{}",
+            self.scheme(),
+            self.hostname(),
+            self.canonical_path()
+        );
+        if !self.canonical_query_string.is_empty() {
+            url.push('?');
+            url.push_str(&self.canonical_query_string);
         }
+        url
+    }
+
+    // gets the values associated with the given key, case-insensitively
+    pub fn get_header(&self, key: &str) -> GetAll<'_, HeaderValue> {
+        self.headers.get_all(key)
+    }
+
+    /// The first value of the header `key`, if it's set.
+    fn first_header(&self, key: &str) -> Option<&[u8]> {
+        self.headers.get(key).map(HeaderValue::as_bytes)
     }
 
     /// If the key exists in headers, set it to blank/unoccupied:
     pub fn remove_header(&mut self, key: &str) {
-        let key_lower = key.to_ascii_lowercase();
-        self.headers.remove(&key_lower);
+        self.headers.remove(key);
     }
 
     /// Add a value to the array of headers for the specified key.
-    /// Headers are kept sorted by key name for use at signing (BTreeMap)
-    pub fn add_header<K: ToString>(&mut self, key: K, value: &str) {
-        let mut key_lower = key.to_string();
-        key_lower.make_ascii_lowercase();
-
-        let value_vec = value.as_bytes().to_vec();
-
-        self.headers.entry(key_lower).or_default().push(value_vec);
+    ///
+    /// Headers that aren't valid in HTTP are left out, and make dispatching the request fail.
+    pub fn add_header<K: AsRef<str>>(&mut self, key: K, value: &str) {
+        let key = key.as_ref();
+        let name = match HeaderName::from_bytes(key.as_bytes()) {
+            Ok(name) => name,
+            Err(err) => {
+                self.header_error
+                    .get_or_insert_with(|| format!("error parsing header name: {}", err));
+                return;
+            }
+        };
+        match HeaderValue::from_str(value) {
+            Ok(value) => {
+                self.headers.append(name, value);
+            }
+            Err(err) => {
+                self.header_error
+                    .get_or_insert_with(|| format!("error parsing header value: {}", err));
+            }
+        }
     }
 
-    pub fn add_optional_header<K: ToString, V: ToString>(&mut self, key: K, value: Option<V>) {
+    pub fn add_optional_header<K: AsRef<str>, V: ToString>(&mut self, key: K, value: Option<V>) {
         if let Some(ref value) = value {
             self.add_header(key, &value.to_string());
         }
@@ -266,61 +510,167 @@ impl SignedRequest {
         S: Into<String>,
     {
         self.params.insert(key.into(), Some(value.into()));
+        self.complete = false;
     }
 
     /// Sets paramaters with a given variable of `Params` type
     pub fn set_params(&mut self, params: Params) {
         self.params = params;
+        self.complete = false;
+    }
+
+    /// Make the next `complement` compute the canonical parts of the request again, after
+    /// `region`, `path`, `params`, `hostname` or `payload` were changed directly instead of
+    /// through the setters. Until then, signing the request again reuses the canonical URI,
+    /// query string and resource computed before the change.
+    pub fn invalidate(&mut self) {
+        self.complete = false;
     }
 
     /// Complement SignedRequest by ensuring the following HTTP headers are set accordingly:
     /// - host
     /// - content-type
-    /// - content-length (if applicable)
+    /// - content-length (if applicable, see `ContentLengthPolicy`)
+    ///
+    /// The canonical URI, query string and resource are computed along the way. They are only
+    /// computed again once the request was changed through its setters or `invalidate`.
     pub fn complement(&mut self) {
+        if self.complete {
+            return;
+        }
         // build the canonical request
         self.canonical_uri = self.canonical_path();
-        self.canonical_query_string = build_canonical_query_string(&self.params);
+        self.canonical_query_string = build_canonical_query_string(&self.params, self.uri_encoding);
+        self.canonical_resource = self.build_canonical_resource();
         // Gotta remove and re-add headers since by default they append the value.  If we're following
         // a 307 redirect we end up with Three Stooges in the headers with duplicate values.
         self.remove_header("Host");
         self.add_header("Host", &self.hostname());
         // if there's no content-type header set, set it to the default value
-        // if let Entry::Vacant(entry) = self.headers.entry("Content-Type".to_owned()) {
-        //     let mut values = Vec::new();
-        //     values.push(b"application/octet-stream".to_vec());
-        //     entry.insert(values);
-        // }
+        if self.payload.is_some() && !self.headers.contains_key("content-type") {
+            if let Some(content_type) = self.default_content_type.content_type() {
+                let content_type = content_type.to_owned();
+                self.add_header("content-type", &content_type);
+            }
+        }
         let len = match self.payload {
-            None => Some(0),
+            None if self.content_length_policy.sends_zero_length(&self.method) => Some(0),
+            None => {
+                self.remove_header("Content-Length");
+                None
+            }
             Some(SignedRequestPayload::Buffer(ref payload)) => Some(payload.len()),
-            Some(SignedRequestPayload::Stream(ref stream)) => stream.size_hint(),
+            Some(SignedRequestPayload::Stream(ref stream)) => match stream.exact_len() {
+                // The length of buffers and files is known, whatever the request says.
+                Some(len) => Some(len),
+                // Otherwise a length set on the request wins over the hint of the stream.
+                None if self.headers.contains_key("Content-Length") => None,
+                None => stream.size_hint(),
+            },
         };
         if let Some(len) = len {
             self.remove_header("Content-Length");
             self.add_header("Content-Length", &format!("{}", len));
         }
+        self.complete = true;
     }
 
     /// Signs the request using Amazon Signature version 2 to verify identity.
     pub fn sign(&mut self, creds: &AwsCredentials) {
+        self.sign_with_clock(creds, &SystemClock);
+    }
+
+    /// Signs the request like `sign`, reading the date of the request and checking the expiry
+    /// of the credentials with `clock`.
+    ///
+    /// Signing a request again, e.g. to retry it, refreshes its `Date` and `Authorization`
+    /// headers and reuses the rest. Presigned requests are left as they are while the
+    /// credentials haven't expired.
+    pub fn sign_with_clock(&mut self, creds: &AwsCredentials, clock: &dyn Clock) {
         self.complement();
-        if self.is_request_signed() && !creds.credentials_are_expired() {
-            // If the request is already signed, and the credentials have not
-            // expired yet ignore the signing request.
+        let now = clock.now();
+        if self.is_presigned() && !creds.credentials_are_expired_at(now) {
             return;
         }
 
-        // build time
-        let date = OffsetDateTime::now_utc();
+        let date = OffsetDateTime::from_unix_timestamp(now.timestamp());
+        self.sign_with_date(creds, &rfc1123(&date));
+    }
+
+    /// Signs the request as if it was sent at `date`, the value of its `Date` header, e.g.
+    /// `Tue, 27 Mar 2007 19:36:42 GMT`.
+    ///
+    /// Unlike `sign`, this always signs, so the same request, credentials and date give the same
+    /// `Authorization` header. This is meant to reproduce the signature of a request that failed
+    /// with `SignatureDoesNotMatch`, to compare with the string to sign returned by the service.
+    pub fn sign_with_date(&mut self, creds: &AwsCredentials, date: &str) {
+        self.complement();
         self.remove_header("Date");
-        let formatted_time = rfc1123(&date);
-        self.add_header("Date", &formatted_time);
+        self.add_header("Date", date);
+        // Temporary credentials are only valid along with their session token, which is signed
+        // as an `x-amz-` header.
+        if let Some(token) = creds.token() {
+            self.remove_header("x-amz-security-token");
+            self.add_header("x-amz-security-token", token);
+        }
+
+        let canonical_request = self.string_to_sign();
+        if log_enabled!(Debug) {
+            debug!("String to sign: {}", canonical_request)
+        }
+
+        let signature = sign_string(&canonical_request, creds.aws_secret_access_key());
+        let auth_header = format!("AWS {}:{}", &creds.aws_access_key_id(), signature);
+        self.remove_header("Authorization");
+        self.add_header("Authorization", &auth_header);
+    }
+
+    /// The string to sign of the request: the method, `Content-MD5`, `Content-Type` and `Date`
+    /// headers, the `x-amz-` and `x-kss-` headers, or those of the prefixes set with
+    /// `set_canonical_header_prefixes`, and the resource, one per line.
+    ///
+    /// The request must have been completed by `complement` or signed first, as this reads the
+    /// canonical URI and query string and the `Date` header they set.
+    pub fn string_to_sign(&self) -> String {
+        let formatted_time = self.first_header("Date").map(String::from_utf8_lossy);
 
         // build canonical headers
-        let canonical_headers = canonical_headers(&self.headers);
+        let canonical_headers = canonical_headers(&self.headers, self.canonical_header_prefixes);
+
+        let built_resource;
+        let canonical_resource = if self.complete {
+            &self.canonical_resource
+        } else {
+            built_resource = self.build_canonical_resource();
+            &built_resource
+        };
+
+        let md5_str = self
+            .first_header("Content-Md5")
+            .map(String::from_utf8_lossy);
+        let type_str = self
+            .first_header("Content-Type")
+            .map(String::from_utf8_lossy);
+
+        let mut canonical_request = format!(
+            "{}\n{}\n{}\n{}",
+            &self.method,
+            md5_str.unwrap_or_default(),
+            type_str.unwrap_or_default(),
+            formatted_time.unwrap_or_default()
+        );
+        if !canonical_headers.is_empty() {
+            canonical_request.push('\n');
+            canonical_request.push_str(&canonical_headers);
+        }
+        canonical_request.push('\n');
+        canonical_request.push_str(canonical_resource);
+        canonical_request
+    }
 
-        // build canonical resource
+    /// The resource part of the string to sign: the canonical URI, with a `/` appended to bare
+    /// bucket names, and the canonical query string.
+    fn build_canonical_resource(&self) -> String {
         let mut uri = self.canonical_uri().to_owned();
         if !uri.is_empty() {
             uri = uri.strip_prefix("/").unwrap().to_owned();
@@ -339,58 +689,16 @@ impl SignedRequest {
             uri = String::from("/");
         }
 
-        let canonical_resource = if self.canonical_query_string.is_empty() {
+        if self.canonical_query_string.is_empty() {
             uri
         } else {
             format!("{}?{}", &uri, &self.canonical_query_string)
-        };
-
-        let md5_list = self.get_header("Content-Md5");
-        let mut md5_str = String::new();
-        if !md5_list.is_empty() {
-            md5_str = String::from_utf8(md5_list[0].clone()).unwrap();
-        }
-
-        let type_list = self.get_header("Content-Type");
-        let mut type_str = String::new();
-        if !type_list.is_empty() {
-            type_str = String::from_utf8(type_list[0].clone()).unwrap();
-        }
-
-        let mut canonical_request = format!(
-            "{}\n{}\n{}\n{}",
-            &self.method, md5_str, type_str, formatted_time
-        );
-        if !canonical_headers.is_empty() {
-            canonical_request.push('\n');
-            canonical_request.push_str(&canonical_headers);
-        }
-        canonical_request.push('\n');
-        canonical_request.push_str(&canonical_resource);
-
-        if log_enabled!(Debug) {
-            debug!("String to sign: {}", canonical_request)
-        }
-
-        let signature = sign_string(&canonical_request, creds.aws_secret_access_key());
-        let auth_header = format!("AWS {}:{}", &creds.aws_access_key_id(), signature);
-        self.remove_header("Authorization");
-        self.add_header("Authorization", &auth_header);
-
-        if log_enabled!(Debug) {
-            debug!("Authorization: {}", auth_header);
         }
     }
 
-    /// is_request_signed returns if the request is currently signed or presigned
-    fn is_request_signed(&self) -> bool {
-        if self.params.get("signature").is_some() {
-            return true;
-        }
-        if self.headers.get("authorization").is_some() {
-            return true;
-        }
-        false
+    /// Whether the request is presigned, with its signature in the query string.
+    fn is_presigned(&self) -> bool {
+        self.params.contains_key("signature")
     }
 }
 
@@ -411,7 +719,7 @@ fn hmac(secret: &[u8], message: &[u8]) -> Hmac<Sha1> {
 }
 
 /// Returns standardised URI
-fn canonical_uri(path: &str, region: &Region) -> String {
+fn canonical_uri(path: &str, region: &Region, encoding: UriEncoding) -> String {
     let endpoint_path = match region {
         Region::Custom { ref endpoint, .. } => extract_endpoint_path(endpoint),
         _ => None,
@@ -420,13 +728,13 @@ fn canonical_uri(path: &str, region: &Region) -> String {
     match (endpoint_path, path) {
         (Some(prefix), "") => prefix.to_string(),
         (None, "") => "/".to_string(),
-        (Some(prefix), _) => encode_uri_path(&(prefix.to_owned() + path)),
-        _ => encode_uri_path(path),
+        (Some(prefix), _) => encoding.encode_path(&(prefix.to_owned() + path)),
+        _ => encoding.encode_path(path),
     }
 }
 
 /// Canonicalizes query while iterating through the given paramaters
-fn build_canonical_query_string(params: &Params) -> String {
+fn build_canonical_query_string(params: &Params, encoding: UriEncoding) -> String {
     if params.is_empty() {
         return String::new();
     }
@@ -436,11 +744,11 @@ fn build_canonical_query_string(params: &Params) -> String {
         if !output.is_empty() {
             output.push('&');
         }
-        output.push_str(&encode_uri_strict(&key));
+        output.push_str(&encoding.encode_query(key));
         output.push('=');
 
         if let Some(ref unwrapped_val) = *val {
-            output.push_str(&encode_uri_strict(&unwrapped_val));
+            output.push_str(&encoding.encode_query(unwrapped_val));
         }
     }
 
@@ -475,38 +783,66 @@ fn encode_uri_strict(uri: &str) -> String {
     utf8_percent_encode(uri, &STRICT_ENCODE_SET).collect::<String>()
 }
 
-fn extract_endpoint_path(endpoint: &str) -> Option<&str> {
+/// The encode set of `UriEncoding::Legacy`, which also encodes `~`.
+const LEGACY_ENCODE_SET: AsciiSet = STRICT_ENCODE_SET.add(b'~');
+
+const LEGACY_PATH_ENCODE_SET: AsciiSet = LEGACY_ENCODE_SET.remove(b'/');
+
+/// `uri` encoded with `set`, except for the `%XY` escapes it already holds.
+fn encode_keeping_escapes(uri: &str, set: &'static AsciiSet) -> String {
+    let bytes = uri.as_bytes();
+    let mut output = String::with_capacity(uri.len());
+    let (mut start, mut i) = (0, 0);
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            output.extend(utf8_percent_encode(&uri[start..i], set));
+            output.push_str(&uri[i..i + 3]);
+            i += 3;
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    output.extend(utf8_percent_encode(&uri[start..], set));
+    output
+}
+
+pub(crate) fn extract_endpoint_path(endpoint: &str) -> Option<&str> {
     extract_endpoint_components(endpoint).1
 }
 
-/// Canonicalizes headers into the AWS Canonical Form.
-fn canonical_headers(headers: &BTreeMap<String, Vec<Vec<u8>>>) -> String {
-    let mut canonical = String::new();
+/// Canonicalizes headers into the AWS Canonical Form: the headers starting with one of
+/// `prefixes` sorted by name, one per line, with their values joined by commas.
+fn canonical_headers(headers: &HeaderMap, prefixes: &[&str]) -> String {
+    let mut names: Vec<&HeaderName> = headers
+        .keys()
+        .filter(|name| {
+            prefixes
+                .iter()
+                .any(|prefix| name.as_str().starts_with(prefix))
+        })
+        .collect();
+    names.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
 
-    for (key, value) in headers.iter() {
-        if !key.starts_with("x-amz-") {
-            continue;
+    let mut canonical = String::new();
+    for name in names {
+        if !canonical.is_empty() {
+            canonical.push('\n');
         }
-
-        canonical.push_str(format!("{}:{}\n", key, canonical_values(value)).as_ref());
-    }
-    if !canonical.is_empty() {
-        canonical.remove(canonical.len() - 1);
-    }
-    canonical
-}
-
-/// Canonicalizes values into the AWS Canonical Form.
-fn canonical_values(values: &[Vec<u8>]) -> String {
-    let mut st = String::new();
-    for v in values {
-        let s = str::from_utf8(v).unwrap();
-        if !st.is_empty() {
-            st.push(',');
+        canonical.push_str(name.as_str());
+        canonical.push(':');
+        for (i, value) in headers.get_all(name).iter().enumerate() {
+            if i > 0 {
+                canonical.push(',');
+            }
+            canonical.push_str(&String::from_utf8_lossy(value.as_bytes()));
         }
-        st.push_str(s);
     }
-    st
+    canonical
 }
 
 fn extract_endpoint_components(endpoint: &str) -> (&str, Option<&str>) {
@@ -585,3 +921,126 @@ fn build_hostname(service: &str, region: &Region) -> String {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> SignedRequest {
+        let mut request = SignedRequest::new("PUT", "s3", &Region::default(), "/bucket/a key");
+        request.add_param("uploadId", "1");
+        request.add_header("x-kss-meta-a", "b");
+        request.set_payload(Some(&b"payload"[..]));
+        request
+    }
+
+    #[test]
+    fn signing_again_only_changes_date_and_authorization() {
+        let creds = AwsCredentials::new("a", "b", None, None);
+        let mut request = request();
+        request.sign_with_date(&creds, "Tue, 27 Mar 2007 19:36:42 GMT");
+        let headers = request.headers().clone();
+        let uri = request.canonical_uri.clone();
+        let query = request.canonical_query_string.clone();
+        let resource = request.canonical_resource.clone();
+
+        request.sign_with_date(&creds, "Tue, 27 Mar 2007 19:40:00 GMT");
+        assert_eq!(request.canonical_uri, uri);
+        assert_eq!(request.canonical_query_string, query);
+        assert_eq!(request.canonical_resource, resource);
+        assert_eq!(request.headers().len(), headers.len());
+        for (name, value) in request.headers() {
+            let changed = headers.get_all(name).iter().all(|v| v != value);
+            assert_eq!(
+                changed,
+                name == "date" || name == "authorization",
+                "header {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn invalidate_recomputes_the_canonical_parts() {
+        let creds = AwsCredentials::new("a", "b", None, None);
+        let mut request = request();
+        request.sign_with_date(&creds, "Tue, 27 Mar 2007 19:36:42 GMT");
+        assert_eq!(request.canonical_resource, "/bucket/a%20key?uploadId=1");
+
+        request.path = "/bucket/b".to_owned();
+        request.invalidate();
+        request.sign_with_date(&creds, "Tue, 27 Mar 2007 19:36:42 GMT");
+        assert_eq!(request.canonical_uri, "/bucket/b");
+        assert_eq!(request.canonical_resource, "/bucket/b?uploadId=1");
+    }
+
+    const DATE: &str = "Tue, 27 Mar 2007 19:36:42 GMT";
+
+    fn signed(headers: &[(&str, &str)], prefixes: Option<&'static [&'static str]>) -> String {
+        let mut request = SignedRequest::new("GET", "s3", &Region::default(), "/bucket/key");
+        if let Some(prefixes) = prefixes {
+            request.set_canonical_header_prefixes(prefixes);
+        }
+        for &(name, value) in headers {
+            request.add_header(name, value);
+        }
+        request.sign_with_date(&AwsCredentials::new("a", "b", None, None), DATE);
+        request.string_to_sign()
+    }
+
+    #[test]
+    fn string_to_sign_includes_kss_headers() {
+        assert_eq!(
+            signed(&[("x-kss-acl", "private"), ("x-kss-meta-a", "b")], None),
+            format!(
+                "GET\n\n\n{}\nx-kss-acl:private\nx-kss-meta-a:b\n/bucket/key",
+                DATE
+            )
+        );
+    }
+
+    #[test]
+    fn custom_prefixes_select_the_signed_headers() {
+        let headers = [("x-amz-meta-a", "1"), ("x-kss-meta-b", "2")];
+        assert_eq!(
+            signed(&headers, Some(&["x-kss-"])),
+            format!("GET\n\n\n{}\nx-kss-meta-b:2\n/bucket/key", DATE)
+        );
+        // Prefixes are matched against the lowercase names.
+        assert_eq!(
+            signed(&[("X-Kss-Meta-B", "2")], Some(&["x-kss-"])),
+            format!("GET\n\n\n{}\nx-kss-meta-b:2\n/bucket/key", DATE)
+        );
+        // Without prefixes, only the method, the standard headers and the resource are signed.
+        assert_eq!(
+            signed(&headers, Some(&[])),
+            format!("GET\n\n\n{}\n/bucket/key", DATE)
+        );
+    }
+
+    #[test]
+    fn headers_with_several_values_are_joined() {
+        let headers = [
+            ("x-kss-meta-b", "3"),
+            ("x-amz-meta-a", "1"),
+            ("x-kss-meta-b", "4"),
+            ("x-amz-meta-a", "2"),
+            ("x-other", "5"),
+        ];
+        let mut request = SignedRequest::new("GET", "s3", &Region::default(), "/");
+        for &(name, value) in &headers {
+            request.add_header(name, value);
+        }
+        assert_eq!(
+            canonical_headers(&request.headers, DEFAULT_CANONICAL_HEADER_PREFIXES),
+            "x-amz-meta-a:1,2\nx-kss-meta-b:3,4"
+        );
+        assert_eq!(
+            signed(&headers, None),
+            format!(
+                "GET\n\n\n{}\nx-amz-meta-a:1,2\nx-kss-meta-b:3,4\n/bucket/key",
+                DATE
+            )
+        );
+    }
+}
@@ -1,17 +1,40 @@
-use bytes::{BufMut, Bytes, BytesMut};
-use futures::{future, stream, Stream, StreamExt};
+use bytes::Bytes;
+#[cfg(not(target_arch = "wasm32"))]
+use bytes::{BufMut, BytesMut};
+#[cfg(not(target_arch = "wasm32"))]
+use futures::future;
+use futures::{stream, Stream, StreamExt};
 use pin_project::pin_project;
-use tokio::io::AsyncRead;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::{AsyncRead, AsyncReadExt};
+#[cfg(target_arch = "wasm32")]
+use {js_sys::Uint8Array, send_wrapper::SendWrapper, wasm_bindgen::JsCast};
 
+use crate::core::clock::Sleep;
+use crate::core::runtime::{self, default_runtime};
+
+use std::error::Error;
 use std::fmt;
 use std::io;
+use std::path::Path;
 use std::pin::Pin;
+use std::sync::Mutex;
 use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Size of the chunks produced by `ByteStream::from_file`.
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Stream of bytes.
 #[pin_project]
 pub struct ByteStream {
     size_hint: Option<usize>,
+    /// Whether the stream yields exactly `size_hint` bytes, as for in-memory buffers and files,
+    /// rather than the number given by the creator of the stream.
+    exact: bool,
+    buffer: Option<Bytes>,
     #[pin]
     inner: Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send + Sync + 'static>>,
 }
@@ -24,6 +47,8 @@ impl ByteStream {
     {
         ByteStream {
             size_hint: None,
+            exact: false,
+            buffer: None,
             inner: Box::pin(stream),
         }
     }
@@ -36,20 +61,200 @@ impl ByteStream {
     {
         ByteStream {
             size_hint: Some(size_hint),
+            exact: false,
+            buffer: None,
             inner: Box::pin(stream),
         }
     }
 
+    /// Create a new `ByteStream` that reads `file` in fixed-size chunks. `len` is used as the
+    /// size hint and should be the number of bytes left to read.
+    ///
+    /// The stream yields exactly `len` bytes, so that it matches the `Content-Length` it is sent
+    /// with: bytes past `len` are left out, and the stream fails with an
+    /// `io::ErrorKind::UnexpectedEof` error if the file ends before.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(file: File, len: usize) -> ByteStream {
+        let chunks = stream::unfold(Some(file), |state| async move {
+            let mut file = state?;
+            let mut buf = vec![0; FILE_CHUNK_SIZE];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(Bytes::from(buf)), Some(file)))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        ByteStream::new_with_exact_size(chunks, len)
+    }
+
+    /// Create a new `ByteStream` that reads the chunks of a JavaScript `ReadableStream`, e.g.
+    /// the body of a `fetch` response or of a `File` picked in a browser. The chunks must be
+    /// `Uint8Array`s; a stream failing, or yielding anything else, fails the `ByteStream`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn from_readable_stream(
+        stream: web_sys::ReadableStream,
+        size_hint: Option<usize>,
+    ) -> ByteStream {
+        let chunks = wasm_streams::ReadableStream::from_raw(stream)
+            .into_stream()
+            .map(|chunk| {
+                let chunk =
+                    chunk.map_err(|err| io::Error::other(format!("stream failed: {:?}", err)))?;
+                let chunk = chunk.dyn_into::<Uint8Array>().map_err(|chunk| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("stream yielded a chunk that isn't bytes: {:?}", chunk),
+                    )
+                })?;
+                Ok(Bytes::from(chunk.to_vec()))
+            });
+        // JavaScript values stay on the thread that made them, the only one on `wasm32`.
+        let chunks = SendWrapper::new(chunks);
+        match size_hint {
+            Some(size_hint) => ByteStream::new_with_size(chunks, size_hint),
+            None => ByteStream::new(chunks),
+        }
+    }
+
+    /// Create a new `ByteStream` that reads the file at `path` in fixed-size chunks, with the
+    /// length of the file as the size hint. Like `from_file`, the stream yields exactly that
+    /// many bytes, even if the file grows or shrinks while it is read.
+    ///
+    /// Unlike `from_file`, which reads a Tokio file, the file is read on the blocking threads of
+    /// the default runtime, so this works the same on every runtime, see `core::runtime`.
+    pub async fn from_path<P: AsRef<Path>>(path: P) -> io::Result<ByteStream> {
+        let path = path.as_ref().to_owned();
+        let (file, len) = runtime::unblock(move || -> io::Result<_> {
+            let file = std::fs::File::open(path)?;
+            let len = file.metadata()?.len() as usize;
+            Ok((file, len))
+        })
+        .await?;
+        Ok(ByteStream::from_std_file(file, len))
+    }
+
+    /// Like `from_path`, but the stream yields exactly `len` bytes, as with `from_file`, e.g.
+    /// the length of the file when it was listed.
+    pub(crate) async fn from_path_with_len<P: AsRef<Path>>(
+        path: P,
+        len: usize,
+    ) -> io::Result<ByteStream> {
+        let path = path.as_ref().to_owned();
+        let file = runtime::unblock(move || std::fs::File::open(path)).await?;
+        Ok(ByteStream::from_std_file(file, len))
+    }
+
+    /// A stream yielding exactly `len` bytes of `file`, read on the blocking threads of the
+    /// default runtime.
+    fn from_std_file(file: std::fs::File, len: usize) -> ByteStream {
+        let chunks = stream::unfold(Some(file), |state| async move {
+            let mut file = state?;
+            let (file, read) = runtime::unblock(move || {
+                let mut buf = vec![0; FILE_CHUNK_SIZE];
+                let read = io::Read::read(&mut file, &mut buf).map(|n| {
+                    buf.truncate(n);
+                    buf
+                });
+                (file, read)
+            })
+            .await;
+            match read {
+                Ok(buf) if buf.is_empty() => None,
+                Ok(buf) => Some((Ok(Bytes::from(buf)), Some(file))),
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        ByteStream::new_with_exact_size(chunks, len)
+    }
+
+    /// A stream yielding exactly `len` bytes of `chunks`, leaving out the bytes past `len` and
+    /// failing if `chunks` ends before.
+    fn new_with_exact_size<S>(chunks: S, len: usize) -> ByteStream
+    where
+        S: Stream<Item = Result<Bytes, io::Error>> + Send + Sync + 'static,
+    {
+        ByteStream {
+            size_hint: Some(len),
+            exact: true,
+            buffer: None,
+            inner: Box::pin(ExactLength {
+                inner: Box::pin(chunks),
+                len,
+                remaining: len,
+                done: false,
+            }),
+        }
+    }
+
     pub(crate) fn size_hint(&self) -> Option<usize> {
         self.size_hint
     }
 
+    /// The number of bytes the stream yields, if it is known exactly: for streams created from
+    /// an in-memory buffer or a file, and those wrapping them with `map_stream`.
+    pub(crate) fn exact_len(&self) -> Option<usize> {
+        self.size_hint.filter(|_| self.exact)
+    }
+
+    /// The stream made of `f(self)`, with the size hint of `self`, e.g. to observe the chunks
+    /// of a body as they are read.
+    pub(crate) fn map_stream<F, S>(self, f: F) -> ByteStream
+    where
+        F: FnOnce(ByteStream) -> S,
+        S: Stream<Item = Result<Bytes, io::Error>> + Send + Sync + 'static,
+    {
+        let size_hint = self.size_hint;
+        let exact = self.exact;
+        ByteStream {
+            size_hint,
+            exact,
+            buffer: None,
+            inner: Box::pin(f(self)),
+        }
+    }
+
+    /// The whole content of the stream, if it was created from an in-memory buffer.
+    pub(crate) fn buffered(&self) -> Option<&Bytes> {
+        self.buffer.as_ref()
+    }
+
+    /// A copy of the stream, if it was created from an in-memory buffer. Other streams can
+    /// only be read once.
+    pub fn try_clone(&self) -> Option<ByteStream> {
+        self.buffer.clone().map(ByteStream::from)
+    }
+
+    /// The stream failing with an `io::ErrorKind::TimedOut` error wrapping `BodyStalled` when
+    /// no chunk arrived for `idle`, e.g. because the connection got stuck. Streams created from
+    /// an in-memory buffer are returned as they are.
+    pub fn with_idle_timeout(self, idle: Duration) -> ByteStream {
+        if self.buffer.is_some() {
+            return self;
+        }
+        ByteStream {
+            size_hint: self.size_hint,
+            exact: self.exact,
+            buffer: None,
+            inner: Box::pin(IdleTimeout {
+                inner: self.inner,
+                idle,
+                timer: Mutex::new(None),
+                done: false,
+            }),
+        }
+    }
+
     /// Return an implementation of `AsyncRead` that uses async i/o to consume the stream.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn into_async_read(self) -> impl AsyncRead + Send + Sync {
         ImplAsyncRead::new(self.inner)
     }
 
     /// Return an implementation of `Read` that uses blocking i/o to consume the stream.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn into_blocking_read(self) -> impl io::Read + Send + Sync {
         ImplBlockingRead::new(self.inner)
     }
@@ -57,9 +262,18 @@ impl ByteStream {
 
 impl From<Vec<u8>> for ByteStream {
     fn from(buf: Vec<u8>) -> ByteStream {
+        ByteStream::from(Bytes::from(buf))
+    }
+}
+
+impl From<Bytes> for ByteStream {
+    fn from(buf: Bytes) -> ByteStream {
+        let chunk = buf.clone();
         ByteStream {
             size_hint: Some(buf.len()),
-            inner: Box::pin(stream::once(async move { Ok(Bytes::from(buf)) })),
+            exact: true,
+            buffer: Some(buf),
+            inner: Box::pin(stream::once(async move { Ok(chunk) })),
         }
     }
 }
@@ -79,6 +293,114 @@ impl Stream for ByteStream {
     }
 }
 
+/// The error of a stream that received no chunk for too long, see
+/// `ByteStream::with_idle_timeout`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BodyStalled {
+    /// How long the stream waited for a chunk.
+    pub idle: Duration,
+}
+
+impl BodyStalled {
+    /// The stall behind `err`, if it's the error of a stream that received no chunk for too
+    /// long.
+    pub fn from_io_error(err: &io::Error) -> Option<BodyStalled> {
+        err.get_ref()
+            .and_then(|inner| inner.downcast_ref::<BodyStalled>())
+            .copied()
+    }
+}
+
+impl fmt::Display for BodyStalled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "No bytes of the body received for {:?}", self.idle)
+    }
+}
+
+impl Error for BodyStalled {}
+
+/// A stream failing once no chunk arrived for `idle`, and ending after that.
+struct IdleTimeout {
+    inner: Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send + Sync + 'static>>,
+    idle: Duration,
+    /// The wait for the next chunk, started when the stream first has none ready. In a mutex
+    /// only to keep the stream `Sync`, as it's always accessed mutably.
+    timer: Mutex<Option<Sleep>>,
+    done: bool,
+}
+
+impl Stream for IdleTimeout {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        let timer = this.timer.get_mut().expect("idle timer poisoned");
+        if let Poll::Ready(item) = this.inner.as_mut().poll_next(cx) {
+            *timer = None;
+            this.done = item.is_none();
+            return Poll::Ready(item);
+        }
+        let idle = this.idle;
+        let timer = timer.get_or_insert_with(|| default_runtime().sleep(idle));
+        futures::ready!(timer.as_mut().poll(cx));
+        this.done = true;
+        Poll::Ready(Some(Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            BodyStalled { idle },
+        ))))
+    }
+}
+
+/// A stream yielding exactly `len` bytes of `inner`, see `ByteStream::new_with_exact_size`.
+struct ExactLength {
+    inner: Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send + Sync + 'static>>,
+    len: usize,
+    remaining: usize,
+    done: bool,
+}
+
+impl Stream for ExactLength {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        if this.remaining == 0 {
+            this.done = true;
+            return Poll::Ready(None);
+        }
+        let item = match futures::ready!(this.inner.as_mut().poll_next(cx)) {
+            Some(Ok(mut chunk)) => {
+                chunk.truncate(this.remaining);
+                this.remaining -= chunk.len();
+                Ok(chunk)
+            }
+            Some(Err(err)) => {
+                this.done = true;
+                Err(err)
+            }
+            None => {
+                this.done = true;
+                Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "the file ended after {} of the {} bytes expected",
+                        this.len - this.remaining,
+                        this.len
+                    ),
+                ))
+            }
+        };
+        Poll::Ready(Some(item))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 #[pin_project]
 struct ImplAsyncRead {
     buffer: BytesMut,
@@ -87,6 +409,7 @@ struct ImplAsyncRead {
         futures::stream::Fuse<Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send + Sync>>>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl ImplAsyncRead {
     fn new(stream: Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send + Sync>>) -> Self {
         ImplAsyncRead {
@@ -96,6 +419,7 @@ impl ImplAsyncRead {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl AsyncRead for ImplAsyncRead {
     fn poll_read(
         self: Pin<&mut Self>,
@@ -120,12 +444,14 @@ impl AsyncRead for ImplAsyncRead {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 #[pin_project]
 struct ImplBlockingRead {
     #[pin]
     inner: ImplAsyncRead,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl ImplBlockingRead {
     fn new(stream: Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send + Sync>>) -> Self {
         ImplBlockingRead {
@@ -134,11 +460,16 @@ impl ImplBlockingRead {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl io::Read for ImplBlockingRead {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let mut rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(future::poll_fn(|cx| {
-            tokio::io::AsyncRead::poll_read(Pin::new(&mut self.inner), cx, buf)
-        }))
+        let inner = &mut self.inner;
+        let mut read = None;
+        default_runtime().block_on(Box::pin(async {
+            let poll_read =
+                |cx: &mut Context<'_>| AsyncRead::poll_read(Pin::new(&mut *inner), cx, buf);
+            read = Some(future::poll_fn(poll_read).await);
+        }))?;
+        read.expect("blocking read didn't complete")
     }
 }
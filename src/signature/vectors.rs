@@ -0,0 +1,167 @@
+//! Known-good signatures, to check the signer against and to compare a failing request with.
+//!
+//! Each vector is a request, the date it is signed at and the string to sign and
+//! `Authorization` header the signer is expected to produce for it with `ACCESS_KEY_ID` and
+//! `SECRET_ACCESS_KEY`. When the service answers `SignatureDoesNotMatch`, the string to sign it
+//! returns can be compared with `SignedRequest::string_to_sign` of the same request signed with
+//! `SignedRequest::sign_with_date`.
+
+use crate::credential::AwsCredentials;
+use crate::signature::{Region, SignedRequest};
+
+/// The access key ID the vectors are signed with.
+pub const ACCESS_KEY_ID: &str = "AKLTEXAMPLEACCESSKEYID";
+
+/// The secret access key the vectors are signed with.
+pub const SECRET_ACCESS_KEY: &str = "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY";
+
+/// A request along with its expected signature.
+#[derive(Clone, Copy, Debug)]
+pub struct SignatureVector {
+    /// What the vector covers.
+    pub name: &'static str,
+    pub method: &'static str,
+    /// The path of the request, before encoding.
+    pub path: &'static str,
+    pub params: &'static [(&'static str, &'static str)],
+    pub headers: &'static [(&'static str, &'static str)],
+    /// The `Date` header the request is signed with.
+    pub date: &'static str,
+    pub string_to_sign: &'static str,
+    pub authorization: &'static str,
+}
+
+/// The published vectors.
+pub const VECTORS: &[SignatureVector] = &[
+    SignatureVector {
+        name: "get_object",
+        method: "GET",
+        path: "/examplebucket/photos/puppy.jpg",
+        params: &[],
+        headers: &[],
+        date: "Tue, 27 Mar 2007 19:36:42 GMT",
+        string_to_sign: "GET\n\n\nTue, 27 Mar 2007 19:36:42 GMT\n/examplebucket/photos/puppy.jpg",
+        authorization: "AWS AKLTEXAMPLEACCESSKEYID:/8qYTA+R3EvtIy+BN0pN7KHE4G4=",
+    },
+    SignatureVector {
+        name: "put_object_with_metadata_and_encoded_key",
+        method: "PUT",
+        path: "/examplebucket/my photos/été.jpg",
+        params: &[],
+        headers: &[
+            ("Content-Type", "image/jpeg"),
+            ("Content-MD5", "4gJE4saaMU4BqNR0kLY+lw=="),
+            ("x-amz-acl", "public-read"),
+            ("x-amz-meta-author", "alice"),
+        ],
+        date: "Wed, 28 Mar 2007 21:16:59 GMT",
+        string_to_sign:
+            "PUT\n4gJE4saaMU4BqNR0kLY+lw==\nimage/jpeg\nWed, 28 Mar 2007 21:16:59 GMT\n\
+             x-amz-acl:public-read\nx-amz-meta-author:alice\n\
+             /examplebucket/my%20photos/%C3%A9t%C3%A9.jpg",
+        authorization: "AWS AKLTEXAMPLEACCESSKEYID:tFp/meWUOFtE14cLf0bYU7UI3TY=",
+    },
+    SignatureVector {
+        name: "list_objects_with_params",
+        method: "GET",
+        path: "/examplebucket",
+        params: &[
+            ("prefix", "logs/2020/"),
+            ("max-keys", "100"),
+            ("delimiter", "/"),
+        ],
+        headers: &[],
+        date: "Thu, 29 Oct 2020 10:15:30 GMT",
+        string_to_sign: "GET\n\n\nThu, 29 Oct 2020 10:15:30 GMT\n\
+                         /examplebucket/?delimiter=%2F&max-keys=100&prefix=logs%2F2020%2F",
+        authorization: "AWS AKLTEXAMPLEACCESSKEYID:04BAjBE/8VPSI3pIHJyXJjF5AOE=",
+    },
+    SignatureVector {
+        name: "upload_part",
+        method: "PUT",
+        path: "/examplebucket/videos/big.mp4",
+        params: &[("partNumber", "12"), ("uploadId", "a1b2c3d4")],
+        headers: &[("Content-MD5", "1B2M2Y8AsgTpgAmY7PhCfg==")],
+        date: "Fri, 13 Nov 2020 12:34:56 GMT",
+        string_to_sign: "PUT\n1B2M2Y8AsgTpgAmY7PhCfg==\n\nFri, 13 Nov 2020 12:34:56 GMT\n\
+                         /examplebucket/videos/big.mp4?partNumber=12&uploadId=a1b2c3d4",
+        authorization: "AWS AKLTEXAMPLEACCESSKEYID:0l+ZqBotYN9SES3DHN7NhJA0/mA=",
+    },
+    SignatureVector {
+        name: "put_object_with_kss_headers",
+        method: "PUT",
+        path: "/examplebucket/reports/q3.txt",
+        params: &[],
+        headers: &[
+            ("Content-Type", "text/plain"),
+            ("x-kss-storage-class", "STANDARD_IA"),
+            ("x-kss-acl", "private"),
+            ("x-amz-meta-owner", "ops"),
+        ],
+        date: "Sat, 14 Nov 2020 08:00:00 GMT",
+        string_to_sign: "PUT\n\ntext/plain\nSat, 14 Nov 2020 08:00:00 GMT\n\
+                         x-amz-meta-owner:ops\nx-kss-acl:private\nx-kss-storage-class:STANDARD_IA\n\
+                         /examplebucket/reports/q3.txt",
+        authorization: "AWS AKLTEXAMPLEACCESSKEYID:4shUCwKpgGD+X6DqfkImee5F3D8=",
+    },
+];
+
+impl SignatureVector {
+    /// Build the request of the vector and sign it.
+    pub fn signed_request(&self) -> SignedRequest {
+        let region = Region::Custom {
+            name: "BEIJING".to_owned(),
+            endpoint: "ks3-cn-beijing.ksyuncs.com".to_owned(),
+        };
+        let mut request = SignedRequest::new(self.method, "s3", &region, self.path);
+        for &(name, value) in self.params {
+            request.add_param(name, value);
+        }
+        for &(name, value) in self.headers {
+            request.add_header(name, value);
+        }
+        let credentials = AwsCredentials::new(ACCESS_KEY_ID, SECRET_ACCESS_KEY, None, None);
+        request.sign_with_date(&credentials, self.date);
+        request
+    }
+
+    /// Check that the signer produces the expected string to sign and `Authorization` header,
+    /// describing the difference if it doesn't.
+    pub fn check(&self) -> Result<(), String> {
+        let request = self.signed_request();
+        let string_to_sign = request.string_to_sign();
+        if string_to_sign != self.string_to_sign {
+            return Err(format!(
+                "{}: expected string to sign {:?}, got {:?}",
+                self.name, self.string_to_sign, string_to_sign
+            ));
+        }
+        let authorization = request
+            .get_header("Authorization")
+            .iter()
+            .next()
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            .unwrap_or_default();
+        if authorization != self.authorization {
+            return Err(format!(
+                "{}: expected authorization {:?}, got {:?}",
+                self.name, self.authorization, authorization
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signer_matches_every_vector() {
+        let failures: Vec<String> = VECTORS
+            .iter()
+            .filter_map(|vector| vector.check().err())
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,225 @@
+//! Moving objects between storage classes.
+//!
+//! KS3 has no operation to change the storage class of an object; instead the object is copied
+//! onto itself with the new class. `S3Client::change_storage_class`, enabled by the `multipart`
+//! feature, does so, switching to a multipart copy for objects too large for a single copy
+//! request.
+//!
+//! The objects of a prefix can also be counted by storage class from a listing, e.g. to feed a
+//! capacity or cost dashboard without setting up inventory reports:
+//!
+//! ```rust,ignore
+//! let report = client.summarize_prefix("photos", "2020/").await?;
+//! for (class, usage) in &report.by_storage_class {
+//!     println!("{}: {} objects, {} bytes", class, usage.objects, usage.bytes);
+//! }
+//! ```
+
+use std::collections::HashMap;
+#[cfg(feature = "multipart")]
+use std::error::Error;
+#[cfg(feature = "multipart")]
+use std::fmt;
+
+use futures::{future, Stream, StreamExt};
+
+use crate::core::error::Ks3Error;
+use crate::enums::StorageClass;
+#[cfg(feature = "multipart")]
+use crate::multipart::{copy_multipart, MultipartError, MAX_COPY_SIZE};
+use crate::request::*;
+#[cfg(feature = "multipart")]
+use crate::s3::ops::*;
+use crate::s3::S3Client;
+#[cfg(feature = "multipart")]
+use crate::s3::S3;
+use crate::signature::signer::encode_uri_path;
+
+/// Errors returned by `S3Client::change_storage_class`.
+#[cfg(feature = "multipart")]
+#[derive(Debug)]
+pub enum StorageClassError {
+    /// The object couldn't be inspected.
+    Head(Ks3Error<HeadObjectError>),
+    /// The single-request copy failed.
+    Copy(Ks3Error<CopyObjectError>),
+    /// The multipart copy failed.
+    Multipart(MultipartError),
+}
+
+#[cfg(feature = "multipart")]
+impl fmt::Display for StorageClassError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StorageClassError::Head(ref err) => write!(f, "{}", err),
+            StorageClassError::Copy(ref err) => write!(f, "{}", err),
+            StorageClassError::Multipart(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(feature = "multipart")]
+impl Error for StorageClassError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            StorageClassError::Head(ref err) => Some(err),
+            StorageClassError::Copy(ref err) => Some(err),
+            StorageClassError::Multipart(ref err) => Some(err),
+        }
+    }
+}
+
+/// The number and total size of the objects in a storage class.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StorageClassUsage {
+    /// The number of objects.
+    pub objects: u64,
+    /// Their total size in bytes.
+    pub bytes: u64,
+}
+
+impl StorageClassUsage {
+    fn add(&mut self, size: u64) {
+        self.objects += 1;
+        self.bytes += size;
+    }
+}
+
+/// The usage of each storage class in a listing or an inventory report.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageClassReport {
+    /// The usage of each storage class holding objects.
+    pub by_storage_class: HashMap<StorageClass, StorageClassUsage>,
+    /// The usage of all the storage classes together.
+    pub total: StorageClassUsage,
+}
+
+impl StorageClassReport {
+    /// Count an object of `size` bytes in `storage_class`, `StorageClass::Standard` if unknown.
+    pub(crate) fn record(&mut self, storage_class: Option<StorageClass>, size: u64) {
+        let class = storage_class.unwrap_or(StorageClass::Standard);
+        self.by_storage_class.entry(class).or_default().add(size);
+        self.total.add(size);
+    }
+
+    /// Count `object`, as listed by `ListObjects`, in the usage of its storage class.
+    ///
+    /// Listings leave out the storage class of objects in `StorageClass::Standard`.
+    pub fn add_object(&mut self, object: &Object) {
+        let size = object.size.unwrap_or(0).max(0) as u64;
+        self.record(object.storage_class.clone(), size);
+    }
+}
+
+/// The `x-amz-copy-source` value naming `key` in `bucket`.
+pub(crate) fn copy_source(bucket: &str, key: &str) -> String {
+    format!("/{}/{}", bucket, encode_uri_path(key))
+}
+
+impl S3Client {
+    /// Move the object `key` in `bucket` to `storage_class`, e.g. `StorageClass::StandardIa` or
+    /// `StorageClass::Archive`, by copying it onto itself.
+    ///
+    /// The content, metadata and content headers of the object are kept. Objects larger than
+    /// `MAX_COPY_SIZE` are copied in parts, which keeps the content headers and metadata but
+    /// not the tags. In both cases the ACL of the object is reset, as with any copy. Nothing is
+    /// sent when the object is already in `storage_class`.
+    #[cfg(feature = "multipart")]
+    pub async fn change_storage_class(
+        &self,
+        bucket: &str,
+        key: &str,
+        storage_class: StorageClass,
+    ) -> Result<(), StorageClassError> {
+        let head = self
+            .head_object(HeadObjectRequest {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                ..Default::default()
+            })
+            .await
+            .map_err(StorageClassError::Head)?;
+        if head.storage_class.unwrap_or(StorageClass::Standard) == storage_class {
+            return Ok(());
+        }
+
+        let source = copy_source(bucket, key);
+        let size = head.content_length.unwrap_or(0);
+        if size <= MAX_COPY_SIZE {
+            self.copy_object(CopyObjectRequest {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                copy_source: source,
+                copy_source_if_match: head.e_tag,
+                metadata_directive: Some("COPY".to_owned()),
+                storage_class: Some(storage_class),
+                ..Default::default()
+            })
+            .await
+            .map_err(StorageClassError::Copy)?;
+            return Ok(());
+        }
+
+        let input = CreateMultipartUploadRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            cache_control: head.cache_control,
+            content_disposition: head.content_disposition,
+            content_encoding: head.content_encoding,
+            content_language: head.content_language,
+            content_type: head.content_type,
+            expires: head.expires,
+            metadata: head.metadata,
+            server_side_encryption: head.server_side_encryption,
+            ssekms_key_id: head.ssekms_key_id,
+            storage_class: Some(storage_class),
+            website_redirect_location: head.website_redirect_location,
+            ..Default::default()
+        };
+        copy_multipart(self, input, &source, size)
+            .await
+            .map_err(StorageClassError::Multipart)?;
+        Ok(())
+    }
+
+    /// The objects listed by `input` that are in `storage_class`, following the markers from
+    /// page to page as `list_all_objects` does. Objects listed without a storage class are
+    /// taken to be in `StorageClass::Standard`.
+    pub fn list_objects_in_storage_class(
+        &self,
+        input: ListObjectsRequest,
+        storage_class: StorageClass,
+    ) -> impl Stream<Item = Result<Object, Ks3Error<ListObjectsError>>> + Send {
+        self.list_all_objects(input).filter(move |object| {
+            future::ready(match object {
+                Ok(object) => {
+                    object
+                        .storage_class
+                        .as_ref()
+                        .unwrap_or(&StorageClass::Standard)
+                        == &storage_class
+                }
+                Err(_) => true,
+            })
+        })
+    }
+
+    /// Count the objects of `bucket` whose key starts with `prefix`, and sum their sizes, for
+    /// each storage class. The whole prefix is listed, a page at a time.
+    pub async fn summarize_prefix(
+        &self,
+        bucket: &str,
+        prefix: &str,
+    ) -> Result<StorageClassReport, Ks3Error<ListObjectsError>> {
+        let mut objects = Box::pin(self.list_all_objects(ListObjectsRequest {
+            bucket: bucket.to_owned(),
+            prefix: Some(prefix.to_owned()).filter(|prefix| !prefix.is_empty()),
+            ..Default::default()
+        }));
+        let mut report = StorageClassReport::default();
+        while let Some(object) = objects.next().await {
+            report.add_object(&object?);
+        }
+        Ok(report)
+    }
+}
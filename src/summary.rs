@@ -0,0 +1,120 @@
+//! The objects and common prefixes of listings, with the same types whatever the operation.
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::enums::StorageClass;
+use crate::request::*;
+#[cfg(feature = "multipart")]
+use crate::s3::ops::*;
+
+/// An object of a listing, as returned by `ListObjects` or `ListObjectVersions`.
+///
+/// Unlike `Object` and `ObjectVersion`, which keep the fields as they were received, the size
+/// is unsigned and the fields the service leaves out have their implied value.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+pub struct ObjectSummary {
+    /// The key of the object.
+    pub key: String,
+    /// The size of the object in bytes.
+    pub size: u64,
+    /// When the object was last modified.
+    pub last_modified: Option<DateTime<Utc>>,
+    /// The entity tag of the object.
+    pub e_tag: Option<String>,
+    /// The storage class of the object, `StorageClass::Standard` when the listing leaves it
+    /// out.
+    pub storage_class: StorageClass,
+    /// The owner of the object, when listed.
+    pub owner: Option<Owner>,
+    /// The ID of the version, for the versions of a versioned bucket.
+    pub version_id: Option<String>,
+}
+
+impl From<&Object> for ObjectSummary {
+    fn from(object: &Object) -> ObjectSummary {
+        ObjectSummary {
+            key: object.key.clone().unwrap_or_default(),
+            size: object.size.unwrap_or(0).max(0) as u64,
+            last_modified: object.last_modified,
+            e_tag: object.e_tag.clone(),
+            storage_class: object
+                .storage_class
+                .clone()
+                .unwrap_or(StorageClass::Standard),
+            owner: object.owner.clone(),
+            version_id: None,
+        }
+    }
+}
+
+impl From<&ObjectVersion> for ObjectSummary {
+    fn from(version: &ObjectVersion) -> ObjectSummary {
+        ObjectSummary {
+            key: version.key.clone().unwrap_or_default(),
+            size: version.size.unwrap_or(0).max(0) as u64,
+            last_modified: version.last_modified,
+            e_tag: version.e_tag.clone(),
+            storage_class: version
+                .storage_class
+                .clone()
+                .unwrap_or(StorageClass::Standard),
+            owner: version.owner.clone(),
+            version_id: version.version_id.clone(),
+        }
+    }
+}
+
+/// The prefixes of `common_prefixes`, in the order they were listed.
+fn prefixes(common_prefixes: &Option<Vec<CommonPrefix>>) -> Vec<&str> {
+    common_prefixes
+        .iter()
+        .flatten()
+        .filter_map(|common_prefix| common_prefix.prefix.as_deref())
+        .collect()
+}
+
+impl ListObjectsOutput {
+    /// The objects of the page.
+    pub fn summaries(&self) -> Vec<ObjectSummary> {
+        self.contents
+            .iter()
+            .flatten()
+            .map(ObjectSummary::from)
+            .collect()
+    }
+
+    /// The common prefixes of the page, when listing with a delimiter.
+    pub fn prefixes(&self) -> Vec<&str> {
+        prefixes(&self.common_prefixes)
+    }
+}
+
+impl ListObjectVersionsOutput {
+    /// The versions of the page, leaving out the delete markers.
+    pub fn summaries(&self) -> Vec<ObjectSummary> {
+        self.versions
+            .iter()
+            .flatten()
+            .map(ObjectSummary::from)
+            .collect()
+    }
+
+    /// The common prefixes of the page, when listing with a delimiter.
+    pub fn prefixes(&self) -> Vec<&str> {
+        prefixes(&self.common_prefixes)
+    }
+}
+
+#[cfg(feature = "multipart")]
+impl ListMultipartUploadsOutput {
+    /// The common prefixes of the page, when listing with a delimiter.
+    pub fn prefixes(&self) -> Vec<&str> {
+        prefixes(&self.common_prefixes)
+    }
+}
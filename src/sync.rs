@@ -0,0 +1,667 @@
+//! Directory synchronisation helpers, the equivalent of `aws s3 sync` in both directions.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt};
+
+use crate::batch::{BatchFailure, BatchResult};
+use crate::core::clock::SystemClock;
+use crate::core::error::Ks3Error;
+use crate::core::runtime;
+use crate::filter::KeyFilter;
+use crate::progress::{emit, report_outcome, ProgressEvent, ProgressReporter};
+use crate::request::*;
+use crate::retry::{Retries, RetryBudget};
+use crate::s3::S3;
+use crate::sharding::{list_sharded, KeyTransformer};
+use crate::signature::ByteStream;
+
+/// Options shared by the sync helpers.
+#[derive(Clone, Debug)]
+pub struct SyncOptions {
+    /// Maximum number of transfers in flight at once.
+    pub concurrency: usize,
+    /// Remove files from the destination that don't exist in the source.
+    pub delete: bool,
+    /// Compare the MD5 digest of local files with the remote ETag instead of relying on
+    /// modification times. Multipart ETags can't be compared and fall back to the time check.
+    pub compare_etag: bool,
+    /// Restrict the sync to paths, relative to the local directory and remote prefix, that
+    /// pass this filter. Files rejected by the filter are neither transferred nor deleted.
+    pub filter: KeyFilter,
+    /// Report the start, bytes and outcome of every transfer here. Skipped and deleted files
+    /// aren't reported.
+    pub progress: Option<ProgressReporter>,
+    /// How many times a failed transfer or deletion is retried before the key is reported as
+    /// failed. None by default.
+    pub max_retries: u32,
+    /// The delay before the first retry of a key, doubled for every further retry.
+    pub retry_delay: Duration,
+    /// Take every retry from this budget, shared with other syncs or batches, and stop
+    /// retrying once it is spent.
+    pub retry_budget: Option<RetryBudget>,
+    /// Store the objects under the keys this gives for the keys of the sync, e.g.
+    /// `HashPrefixSharding`, and list them with `list_sharded`. Keys are reported untransformed.
+    pub key_transformer: Option<Arc<dyn KeyTransformer>>,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions {
+            concurrency: 8,
+            delete: false,
+            compare_etag: false,
+            filter: KeyFilter::new(),
+            progress: None,
+            max_retries: 0,
+            retry_delay: Duration::from_millis(200),
+            retry_budget: None,
+            key_transformer: None,
+        }
+    }
+}
+
+/// The outcome of a sync, keyed by object key.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// The transfers of the keys that were missing or out of date in the destination.
+    pub transferred: BatchResult<SyncFailure>,
+    /// Keys that were already up to date.
+    pub skipped: Vec<String>,
+    /// The deletions of the keys missing from the source, with `SyncOptions::delete`.
+    pub deleted: BatchResult<SyncFailure>,
+}
+
+impl SyncReport {
+    /// Whether every transfer and deletion succeeded.
+    pub fn is_success(&self) -> bool {
+        self.transferred.is_success() && self.deleted.is_success()
+    }
+
+    /// The transfers and deletions that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &BatchFailure<SyncFailure>> {
+        self.transferred.failed.iter().chain(&self.deleted.failed)
+    }
+}
+
+/// Why the transfer or deletion of a key failed.
+#[derive(Debug)]
+pub enum SyncFailure {
+    /// A local file couldn't be read, written or removed.
+    Io(io::Error),
+    /// The upload of a file failed.
+    Upload(Ks3Error<PutObjectError>),
+    /// The download of an object failed.
+    Download(Ks3Error<GetObjectError>),
+    /// The deletion of an object failed.
+    Delete(Ks3Error<DeleteObjectError>),
+    /// The key of an object doesn't map to a local path, e.g. because it holds `..`.
+    InvalidKey,
+}
+
+impl fmt::Display for SyncFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SyncFailure::Io(ref err) => write!(f, "{}", err),
+            SyncFailure::Upload(ref err) => write!(f, "{}", err),
+            SyncFailure::Download(ref err) => write!(f, "{}", err),
+            SyncFailure::Delete(ref err) => write!(f, "{}", err),
+            SyncFailure::InvalidKey => write!(f, "key does not map to a local path"),
+        }
+    }
+}
+
+impl Error for SyncFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SyncFailure::Io(ref err) => Some(err),
+            SyncFailure::Upload(ref err) => Some(err),
+            SyncFailure::Download(ref err) => Some(err),
+            SyncFailure::Delete(ref err) => Some(err),
+            SyncFailure::InvalidKey => None,
+        }
+    }
+}
+
+impl From<io::Error> for SyncFailure {
+    fn from(err: io::Error) -> Self {
+        SyncFailure::Io(err)
+    }
+}
+
+/// Errors that abort a sync before any per-file work is done.
+#[derive(Debug)]
+pub enum SyncError {
+    /// The local directory couldn't be read.
+    Io(io::Error),
+    /// The remote listing failed.
+    List(Ks3Error<ListObjectsError>),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SyncError::Io(ref err) => write!(f, "{}", err),
+            SyncError::List(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for SyncError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SyncError::Io(ref err) => Some(err),
+            SyncError::List(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for SyncError {
+    fn from(err: io::Error) -> Self {
+        SyncError::Io(err)
+    }
+}
+
+impl From<Ks3Error<ListObjectsError>> for SyncError {
+    fn from(err: Ks3Error<ListObjectsError>) -> Self {
+        SyncError::List(err)
+    }
+}
+
+struct LocalFile {
+    path: PathBuf,
+    size: u64,
+    modified: Option<DateTime<Utc>>,
+}
+
+/// Upload the contents of `local_dir` to `bucket` under `prefix`, skipping files that are
+/// already up to date.
+///
+/// A local file is uploaded when the remote object is missing, the sizes differ, or the local
+/// file is newer than the remote one (or its MD5 differs from the ETag when
+/// `SyncOptions::compare_etag` is set). With `SyncOptions::delete`, remote keys under `prefix`
+/// that have no local counterpart are removed.
+pub async fn sync_up<C, P>(
+    client: &C,
+    local_dir: P,
+    bucket: &str,
+    prefix: &str,
+    options: &SyncOptions,
+) -> Result<SyncReport, SyncError>
+where
+    C: S3 + Sync,
+    P: AsRef<Path>,
+{
+    let mut local = walk_dir(local_dir.as_ref()).await?;
+    let mut remote = list_remote(client, bucket, prefix, options).await?;
+    let base = dir_prefix(prefix);
+    local.retain(|(relative, _)| options.filter.matches(relative));
+    remote.retain(|key, _| options.filter.matches(relative_key(key, &base)));
+    let mut report = SyncReport::default();
+
+    let mut uploads = Vec::new();
+    for (relative, file) in local {
+        let key = join_key(prefix, &relative);
+        let changed = match remote.remove(&key) {
+            None => true,
+            Some(object) => is_changed(&file, &object, options, Direction::Up).await,
+        };
+        if changed {
+            uploads.push((key, file));
+        } else {
+            report.skipped.push(key);
+        }
+    }
+
+    let results = stream::iter(uploads)
+        .map(|(key, file)| async move {
+            let progress = options.progress.as_ref();
+            emit(progress, || ProgressEvent::TransferStarted {
+                key: key.clone(),
+                total_bytes: Some(file.size),
+            });
+            let stored_key = stored_key(options, &key);
+            let (attempts, result) = retries(options, "SyncUpload")
+                .run(|_| upload_file(client, bucket, &key, &stored_key, &file, progress))
+                .await;
+            report_outcome(progress, &key, &result);
+            (key, attempts, result)
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    for (key, attempts, result) in results {
+        report.transferred.record(key, attempts, result);
+    }
+
+    if options.delete {
+        let results = stream::iter(remote.into_keys())
+            .map(|key| async move {
+                let stored_key = stored_key(options, &key);
+                let (attempts, result) = retries(options, "SyncDelete")
+                    .run(|_| {
+                        client.delete_object(DeleteObjectRequest {
+                            bucket: bucket.to_owned(),
+                            key: stored_key.clone(),
+                            ..Default::default()
+                        })
+                    })
+                    .await;
+                (key, attempts, result.map_err(SyncFailure::Delete))
+            })
+            .buffer_unordered(options.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        for (key, attempts, result) in results {
+            report.deleted.record(key, attempts, result);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Download the objects in `bucket` under `prefix` into `local_dir`, skipping files that are
+/// already up to date.
+///
+/// Keys are mapped onto the local file system by splitting them on `/`, creating intermediate
+/// directories as needed; keys ending in `/` are treated as directory markers and skipped. An
+/// object is downloaded when the local file is missing, the sizes differ, or the remote object
+/// is newer (or its ETag differs from the local MD5 when `SyncOptions::compare_etag` is set).
+/// With `SyncOptions::delete`, local files with no remote counterpart are removed.
+pub async fn sync_down<C, P>(
+    client: &C,
+    bucket: &str,
+    prefix: &str,
+    local_dir: P,
+    options: &SyncOptions,
+) -> Result<SyncReport, SyncError>
+where
+    C: S3 + Sync,
+    P: AsRef<Path>,
+{
+    let root = local_dir.as_ref();
+    let owned = root.to_owned();
+    runtime::unblock(move || std::fs::create_dir_all(owned)).await?;
+    let base = dir_prefix(prefix);
+    let mut remote = list_remote(client, bucket, prefix, options).await?;
+    remote.retain(|key, _| options.filter.matches(relative_key(key, &base)));
+    let mut local = walk_dir(root)
+        .await?
+        .into_iter()
+        .filter(|(relative, _)| options.filter.matches(relative))
+        .collect::<HashMap<_, _>>();
+    let mut report = SyncReport::default();
+
+    let mut downloads = Vec::new();
+    let mut remote = remote.into_iter().collect::<Vec<_>>();
+    remote.sort_by(|a, b| a.0.cmp(&b.0));
+    for (key, object) in remote {
+        let relative = match key.strip_prefix(base.as_str()) {
+            Some(relative) if !relative.is_empty() && !relative.ends_with('/') => relative,
+            _ => continue,
+        };
+        if relative
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == "..")
+        {
+            report
+                .transferred
+                .record(key, 1, Err::<(), _>(SyncFailure::InvalidKey));
+            continue;
+        }
+        let path = relative
+            .split('/')
+            .fold(root.to_path_buf(), |path, part| path.join(part));
+        let changed = match local.remove(relative) {
+            None => true,
+            Some(file) => is_changed(&file, &object, options, Direction::Down).await,
+        };
+        if changed {
+            downloads.push((key, path, object.size.map(|size| size as u64)));
+        } else {
+            report.skipped.push(key);
+        }
+    }
+
+    let results = stream::iter(downloads)
+        .map(|(key, path, size)| async move {
+            let progress = options.progress.as_ref();
+            emit(progress, || ProgressEvent::TransferStarted {
+                key: key.clone(),
+                total_bytes: size,
+            });
+            let stored_key = stored_key(options, &key);
+            let (attempts, result) = retries(options, "SyncDownload")
+                .run(|_| download_file(client, bucket, &key, &stored_key, &path, progress, size))
+                .await;
+            report_outcome(progress, &key, &result);
+            (key, attempts, result)
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    for (key, attempts, result) in results {
+        report.transferred.record(key, attempts, result);
+    }
+
+    if options.delete {
+        for (relative, file) in local {
+            let key = join_key(prefix, &relative);
+            let path = file.path;
+            let result = runtime::unblock(move || std::fs::remove_file(path)).await;
+            report
+                .deleted
+                .record(key, 1, result.map_err(SyncFailure::Io));
+        }
+    }
+
+    Ok(report)
+}
+
+/// The retries of the transfers and deletions of a sync.
+fn retries<'a>(options: &'a SyncOptions, operation: &'static str) -> Retries<'a> {
+    Retries {
+        max_retries: options.max_retries,
+        delay: options.retry_delay,
+        clock: &SystemClock,
+        budget: options.retry_budget.as_ref(),
+        metrics: None,
+        operation,
+    }
+}
+
+/// List every object under `prefix`, keyed by the keys of the sync.
+async fn list_remote<C>(
+    client: &C,
+    bucket: &str,
+    prefix: &str,
+    options: &SyncOptions,
+) -> Result<HashMap<String, Object>, Ks3Error<ListObjectsError>>
+where
+    C: S3 + Sync,
+{
+    match options.key_transformer {
+        Some(ref transformer) => {
+            let objects = list_sharded(client, bucket, &**transformer, &dir_prefix(prefix)).await?;
+            Ok(objects
+                .into_iter()
+                .filter_map(|object| Some((object.key.clone()?, object)))
+                .collect())
+        }
+        None => list_all(client, bucket, prefix).await,
+    }
+}
+
+/// The key `key` is stored under.
+fn stored_key(options: &SyncOptions, key: &str) -> String {
+    match options.key_transformer {
+        Some(ref transformer) => transformer.to_stored(key),
+        None => key.to_owned(),
+    }
+}
+
+/// List every object under `prefix`, following the listing markers.
+pub(crate) async fn list_all<C>(
+    client: &C,
+    bucket: &str,
+    prefix: &str,
+) -> Result<HashMap<String, Object>, Ks3Error<ListObjectsError>>
+where
+    C: S3 + Sync,
+{
+    list_prefix(client, bucket, &dir_prefix(prefix)).await
+}
+
+/// List every object whose key starts with `prefix`, following the listing markers.
+pub(crate) async fn list_prefix<C>(
+    client: &C,
+    bucket: &str,
+    prefix: &str,
+) -> Result<HashMap<String, Object>, Ks3Error<ListObjectsError>>
+where
+    C: S3 + Sync,
+{
+    let mut objects = HashMap::new();
+    let mut marker = None;
+    loop {
+        let output = client
+            .list_objects(ListObjectsRequest {
+                bucket: bucket.to_owned(),
+                prefix: Some(prefix.to_owned()).filter(|p| !p.is_empty()),
+                marker: marker.take(),
+                ..Default::default()
+            })
+            .await?;
+        let mut last_key = None;
+        for object in output.contents.unwrap_or_default() {
+            if let Some(key) = object.key.clone() {
+                last_key = Some(key.clone());
+                objects.insert(key, object);
+            }
+        }
+        if !output.is_truncated.unwrap_or(false) {
+            break;
+        }
+        marker = output.next_marker.or(last_key);
+        if marker.is_none() {
+            break;
+        }
+    }
+    Ok(objects)
+}
+
+/// Normalise `prefix` so that it names a "directory", e.g. `logs` becomes `logs/`.
+pub(crate) fn dir_prefix(prefix: &str) -> String {
+    let prefix = prefix.trim_start_matches('/');
+    if prefix.is_empty() || prefix.ends_with('/') {
+        prefix.to_owned()
+    } else {
+        format!("{}/", prefix)
+    }
+}
+
+/// Strip the normalised `base` prefix from `key`, the inverse of `join_key`.
+fn relative_key<'a>(key: &'a str, base: &str) -> &'a str {
+    key.strip_prefix(base).unwrap_or(key)
+}
+
+fn join_key(prefix: &str, relative: &str) -> String {
+    format!("{}{}", dir_prefix(prefix), relative)
+}
+
+/// Recursively collect the regular files below `root`, keyed by their `/`-separated path
+/// relative to `root`.
+async fn walk_dir(root: &Path) -> io::Result<Vec<(String, LocalFile)>> {
+    let root = root.to_owned();
+    runtime::unblock(move || walk_dir_blocking(&root)).await
+}
+
+fn walk_dir_blocking(root: &Path) -> io::Result<Vec<(String, LocalFile)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let metadata = std::fs::metadata(&path)?;
+            if metadata.is_dir() {
+                pending.push(path);
+            } else if metadata.is_file() {
+                let relative = path
+                    .strip_prefix(root)
+                    .expect("walked path is below the root")
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((
+                    relative,
+                    LocalFile {
+                        path,
+                        size: metadata.len(),
+                        modified: metadata.modified().ok().map(to_datetime),
+                    },
+                ));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+fn to_datetime(time: SystemTime) -> DateTime<Utc> {
+    DateTime::<Utc>::from(time)
+}
+
+/// Which side of the sync is the source.
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Up,
+    Down,
+}
+
+async fn is_changed(
+    file: &LocalFile,
+    object: &Object,
+    options: &SyncOptions,
+    direction: Direction,
+) -> bool {
+    if object.size != Some(file.size as i64) {
+        return true;
+    }
+    if options.compare_etag {
+        if let Some(etag) = object.e_tag.as_ref().map(|e| e.trim_matches('"')) {
+            if !etag.contains('-') {
+                return match file_md5(&file.path).await {
+                    Ok(digest) => digest != etag,
+                    Err(_) => true,
+                };
+            }
+        }
+    }
+    let remote_modified = object.last_modified;
+    match (file.modified, remote_modified) {
+        (Some(local), Some(remote)) => match direction {
+            Direction::Up => local > remote,
+            Direction::Down => remote > local,
+        },
+        _ => true,
+    }
+}
+
+/// Compute the hex-encoded MD5 digest of the file at `path`.
+pub(crate) async fn file_md5(path: &Path) -> io::Result<String> {
+    let path = path.to_owned();
+    runtime::unblock(move || {
+        let mut file = std::fs::File::open(path)?;
+        let mut context = md5::Context::new();
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let n = io::Read::read(&mut file, &mut buf)?;
+            if n == 0 {
+                break;
+            }
+            context.consume(&buf[..n]);
+        }
+        Ok(format!("{:x}", context.compute()))
+    })
+    .await
+}
+
+/// Upload `file` under `stored_key`, reporting it to `progress` as `key`.
+async fn upload_file<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    stored_key: &str,
+    file: &LocalFile,
+    progress: Option<&ProgressReporter>,
+) -> Result<(), SyncFailure>
+where
+    C: S3 + Sync,
+{
+    let mut body = ByteStream::from_path_with_len(&file.path, file.size as usize).await?;
+    if let Some(progress) = progress {
+        body = progress.track(key, body, Some(file.size));
+    }
+    client
+        .put_object(PutObjectRequest {
+            bucket: bucket.to_owned(),
+            key: stored_key.to_owned(),
+            body: Some(body),
+            content_length: Some(file.size as i64),
+            ..Default::default()
+        })
+        .await
+        .map(|_| ())
+        .map_err(SyncFailure::Upload)
+}
+
+/// Stream the object stored under `stored_key` into `path`, going through a temporary file so
+/// that a failed transfer never leaves a truncated file behind. The `size` bytes of the object
+/// are reported to `progress` as they are written, as `key`.
+async fn download_file<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    stored_key: &str,
+    path: &Path,
+    progress: Option<&ProgressReporter>,
+    size: Option<u64>,
+) -> Result<(), SyncFailure>
+where
+    C: S3 + Sync,
+{
+    if let Some(parent) = path.parent() {
+        let parent = parent.to_owned();
+        runtime::unblock(move || std::fs::create_dir_all(parent)).await?;
+    }
+    let output = client
+        .get_object(GetObjectRequest {
+            bucket: bucket.to_owned(),
+            key: stored_key.to_owned(),
+            ..Default::default()
+        })
+        .await
+        .map_err(SyncFailure::Download)?;
+
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    let body = match progress {
+        Some(progress) => output.body.map(|body| progress.track(key, body, size)),
+        None => output.body,
+    };
+    let result = write_body(body, partial.clone()).await;
+    let path = path.to_owned();
+    match result {
+        Ok(()) => Ok(runtime::unblock(move || std::fs::rename(partial, path)).await?),
+        Err(err) => {
+            let _ = runtime::unblock(move || std::fs::remove_file(partial)).await;
+            Err(SyncFailure::Io(err))
+        }
+    }
+}
+
+async fn write_body(body: Option<StreamingBody>, path: PathBuf) -> io::Result<()> {
+    let mut file = runtime::unblock(move || std::fs::File::create(path)).await?;
+    if let Some(mut body) = body {
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk?;
+            let (written, result) = runtime::unblock(move || {
+                let result = io::Write::write_all(&mut file, &chunk);
+                (file, result)
+            })
+            .await;
+            file = written;
+            result?;
+        }
+    }
+    runtime::unblock(move || io::Write::flush(&mut file)).await
+}
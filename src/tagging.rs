@@ -0,0 +1,187 @@
+//! Object tags.
+//!
+//! Uploads and copies take their tags as a URL-encoded query string in the `x-amz-tagging`
+//! header, while `put_object_tagging` takes a `Tagging` document. `TagSet` converts to both, so
+//! the same tags can be used with either:
+//!
+//! ```rust,ignore
+//! let mut tags = TagSet::new();
+//! tags.insert("project", "blue & green");
+//! let request = PutObjectRequest::builder()
+//!     .bucket("photos")
+//!     .key("puppy.jpg")
+//!     .tagging(tags.clone()) // project=blue%20%26%20green
+//!     .build()?;
+//! ```
+
+use std::collections::{btree_map, BTreeMap};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode};
+#[cfg(feature = "deserialize_structs")]
+use serde::Deserialize;
+#[cfg(feature = "serialize_structs")]
+use serde::Serialize;
+
+use crate::request::{Tag, Tagging};
+use crate::signature::signer::STRICT_ENCODE_SET;
+
+/// Errors returned when parsing a tagging query string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagSetParseError {
+    pair: String,
+}
+
+impl fmt::Display for TagSetParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid tag: {:?}", self.pair)
+    }
+}
+
+impl Error for TagSetParseError {}
+
+/// A set of tags, ordered by key.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
+#[cfg_attr(feature = "serialize_structs", derive(Serialize))]
+#[cfg_attr(
+    any(feature = "serialize_structs", feature = "deserialize_structs"),
+    serde(transparent)
+)]
+pub struct TagSet {
+    tags: BTreeMap<String, String>,
+}
+
+impl TagSet {
+    /// Create an empty set.
+    pub fn new() -> TagSet {
+        TagSet::default()
+    }
+
+    /// Set the tag `key` to `value`, returning the previous value.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Option<String>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.tags.insert(key.into(), value.into())
+    }
+
+    /// The value of the tag `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.tags.get(key).map(String::as_str)
+    }
+
+    /// Remove the tag `key`, returning its value.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.tags.remove(key)
+    }
+
+    /// The tags, ordered by key.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, String> {
+        self.tags.iter()
+    }
+
+    /// The number of tags.
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Whether there are no tags.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+/// Renders the set as the query string of the `x-amz-tagging` header, with keys and values
+/// percent-encoded.
+impl fmt::Display for TagSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (key, value)) in self.tags.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            write!(
+                f,
+                "{}={}",
+                utf8_percent_encode(key, &STRICT_ENCODE_SET),
+                utf8_percent_encode(value, &STRICT_ENCODE_SET)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the query string of the `x-amz-tagging` header. `+` is read as a space, as in forms.
+impl FromStr for TagSet {
+    type Err = TagSetParseError;
+
+    fn from_str(query: &str) -> Result<TagSet, TagSetParseError> {
+        let decode = |value: &str, pair: &str| {
+            percent_decode_str(&value.replace('+', " "))
+                .decode_utf8()
+                .map(|value| value.into_owned())
+                .map_err(|_| TagSetParseError {
+                    pair: pair.to_owned(),
+                })
+        };
+        let mut tags = TagSet::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            tags.insert(decode(key, pair)?, decode(value, pair)?);
+        }
+        Ok(tags)
+    }
+}
+
+impl From<TagSet> for String {
+    fn from(tags: TagSet) -> String {
+        tags.to_string()
+    }
+}
+
+impl From<TagSet> for Tagging {
+    fn from(tags: TagSet) -> Tagging {
+        Tagging {
+            tag_set: tags
+                .tags
+                .into_iter()
+                .map(|(key, value)| Tag { key, value })
+                .collect(),
+        }
+    }
+}
+
+impl From<Tagging> for TagSet {
+    fn from(tagging: Tagging) -> TagSet {
+        TagSet {
+            tags: tagging
+                .tag_set
+                .into_iter()
+                .map(|tag| (tag.key, tag.value))
+                .collect(),
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> std::iter::FromIterator<(K, V)> for TagSet {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> TagSet {
+        TagSet {
+            tags: iter
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TagSet {
+    type Item = (&'a String, &'a String);
+    type IntoIter = btree_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::FutureExt;
+use http::header::HeaderName;
+use http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::core::capture::{is_secret_header, REDACTED, SECRET_PARAMS};
+use crate::core::request::{DispatchSignedRequestFuture, HttpDispatchError};
+use crate::core::{DispatchSignedRequest, HttpResponse};
+use crate::signature::signer::{Params, SignedRequestPayload};
+use crate::signature::{ByteStream, SignedRequest};
+
+/// A request as written to a cassette.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub params: Params,
+    pub headers: BTreeMap<String, Vec<String>>,
+    /// The base64 of the payload, unless it was streamed.
+    pub body: Option<String>,
+}
+
+/// A response as written to a cassette.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// The base64 of the body.
+    pub body: String,
+}
+
+/// A request and the response it got.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// The interactions recorded by a `RecordingDispatcher`, stored as JSON.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Read the cassette at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Cassette> {
+        let json = fs::read(path)?;
+        serde_json::from_slice(&json).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Write the cassette to `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, json)
+    }
+}
+
+fn record_request(request: &SignedRequest, redacted_headers: &[String]) -> RecordedRequest {
+    let headers = request
+        .headers()
+        .keys()
+        .map(|name| {
+            let name = name.as_str();
+            let secret = is_secret_header(name)
+                || redacted_headers
+                    .iter()
+                    .any(|h| h.eq_ignore_ascii_case(name));
+            let values = request
+                .get_header(name)
+                .iter()
+                .map(|value| {
+                    if secret {
+                        REDACTED.to_owned()
+                    } else {
+                        String::from_utf8_lossy(value.as_bytes()).into_owned()
+                    }
+                })
+                .collect();
+            (name.to_owned(), values)
+        })
+        .collect();
+    let params = request
+        .params
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET_PARAMS.contains(&name.as_str()) {
+                value.as_ref().map(|_| REDACTED.to_owned())
+            } else {
+                value.clone()
+            };
+            (name.clone(), value)
+        })
+        .collect();
+    let body = match request.payload {
+        Some(SignedRequestPayload::Buffer(ref payload)) => Some(base64::encode(payload)),
+        _ => None,
+    };
+    RecordedRequest {
+        method: request.method().to_owned(),
+        path: request.path().to_owned(),
+        params,
+        headers,
+        body,
+    }
+}
+
+/// Whether `request` is the one `recorded` was made of. Headers are ignored, as they hold
+/// dates and signatures that change on every run.
+fn matches(recorded: &RecordedRequest, request: &SignedRequest) -> bool {
+    recorded.method == request.method()
+        && recorded.path == request.path()
+        && recorded.params.len() == request.params.len()
+        && recorded.params.iter().all(|(name, value)| {
+            SECRET_PARAMS.contains(&name.as_str()) || request.params.get(name) == Some(value)
+        })
+}
+
+/// A `DispatchSignedRequest` that sends requests through another dispatcher and records them,
+/// along with their responses, to a cassette file that a `ReplayDispatcher` can play back.
+///
+/// ```rust,ignore
+/// let dispatcher = RecordingDispatcher::new(HttpClient::new()?, "tests/cassettes/upload.json");
+/// let client = S3Client::new_with(dispatcher, credentials, Region::default());
+/// ```
+///
+/// The cassette is rewritten after every response, so it is complete whenever the client is
+/// dropped. Signatures, session tokens and SSE-C keys are replaced by `REDACTED`; further
+/// headers can be redacted with `redact_header`. Response bodies are buffered in memory to be
+/// recorded, and streamed request payloads are recorded without their content.
+pub struct RecordingDispatcher<D> {
+    inner: D,
+    path: PathBuf,
+    redacted_headers: Vec<String>,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl<D> RecordingDispatcher<D> {
+    /// Record the requests sent through `inner` to the cassette at `path`, replacing it.
+    pub fn new<P: Into<PathBuf>>(inner: D, path: P) -> RecordingDispatcher<D> {
+        RecordingDispatcher {
+            inner,
+            path: path.into(),
+            redacted_headers: Vec::new(),
+            cassette: Arc::new(Mutex::new(Cassette::default())),
+        }
+    }
+
+    /// Also redact the values of the request header `name`.
+    pub fn redact_header(mut self, name: &str) -> RecordingDispatcher<D> {
+        self.redacted_headers.push(name.to_owned());
+        self
+    }
+}
+
+impl<D: DispatchSignedRequest> DispatchSignedRequest for RecordingDispatcher<D> {
+    fn dispatch(
+        &self,
+        request: SignedRequest,
+        timeout: Option<Duration>,
+    ) -> DispatchSignedRequestFuture {
+        let recorded = record_request(&request, &self.redacted_headers);
+        let response = self.inner.dispatch(request, timeout);
+        let path = self.path.clone();
+        let cassette = self.cassette.clone();
+        async move {
+            let mut response = response.await?;
+            let buffered = response.buffer().await?;
+            let interaction = Interaction {
+                request: recorded,
+                response: RecordedResponse {
+                    status: buffered.status.as_u16(),
+                    headers: buffered
+                        .headers
+                        .iter()
+                        .map(|(name, value)| (name.as_str().to_owned(), value.clone()))
+                        .collect(),
+                    body: base64::encode(&buffered.body),
+                },
+            };
+            {
+                let mut cassette = cassette.lock().expect("cassette poisoned");
+                cassette.interactions.push(interaction);
+                cassette.save(&path).map_err(|err| {
+                    HttpDispatchError::new(format!(
+                        "Error writing cassette {}: {}",
+                        path.display(),
+                        err
+                    ))
+                })?;
+            }
+            Ok(HttpResponse {
+                status: buffered.status,
+                body: ByteStream::from(buffered.body),
+                headers: buffered.headers,
+                timings: None,
+            })
+        }
+        .boxed()
+    }
+}
+
+/// A `DispatchSignedRequest` that answers requests from a cassette written by a
+/// `RecordingDispatcher`, without sending anything.
+///
+/// Each request is answered with the response of the first unused interaction with the same
+/// method, path and query parameters, so requests repeated during a run get their responses in
+/// the order they were recorded. A request with no such interaction fails with a dispatch
+/// error naming it.
+pub struct ReplayDispatcher {
+    interactions: Mutex<Vec<Option<Interaction>>>,
+}
+
+impl ReplayDispatcher {
+    /// Replay the cassette at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<ReplayDispatcher> {
+        Ok(ReplayDispatcher::new(Cassette::load(path)?))
+    }
+
+    /// Replay `cassette`.
+    pub fn new(cassette: Cassette) -> ReplayDispatcher {
+        ReplayDispatcher {
+            interactions: Mutex::new(cassette.interactions.into_iter().map(Some).collect()),
+        }
+    }
+
+    /// The number of recorded interactions that haven't been replayed yet.
+    pub fn remaining(&self) -> usize {
+        let interactions = self.interactions.lock().expect("cassette poisoned");
+        interactions.iter().filter(|i| i.is_some()).count()
+    }
+}
+
+fn replay(response: RecordedResponse) -> Result<HttpResponse, HttpDispatchError> {
+    let invalid = |what: &str| HttpDispatchError::new(format!("Invalid recorded {}", what));
+    let status = StatusCode::from_u16(response.status).map_err(|_| invalid("status"))?;
+    let mut headers = HeaderMap::default();
+    for (name, value) in response.headers {
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid("header"))?;
+        headers.append(name, value);
+    }
+    let body = base64::decode(&response.body).map_err(|_| invalid("body"))?;
+    Ok(HttpResponse {
+        status,
+        body: ByteStream::from(Bytes::from(body)),
+        headers,
+        timings: None,
+    })
+}
+
+impl DispatchSignedRequest for ReplayDispatcher {
+    fn dispatch(
+        &self,
+        request: SignedRequest,
+        _timeout: Option<Duration>,
+    ) -> DispatchSignedRequestFuture {
+        let mut interactions = self.interactions.lock().expect("cassette poisoned");
+        let interaction = interactions
+            .iter_mut()
+            .find(|i| i.as_ref().is_some_and(|i| matches(&i.request, &request)))
+            .and_then(Option::take);
+        let result = match interaction {
+            Some(interaction) => replay(interaction.response),
+            None => Err(HttpDispatchError::new(format!(
+                "No recorded interaction for {} {} {:?}",
+                request.method(),
+                request.path(),
+                request.params
+            ))),
+        };
+        futures::future::ready(result).boxed()
+    }
+}
@@ -0,0 +1,183 @@
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use futures::{stream, FutureExt};
+use http::{HeaderMap, StatusCode};
+
+use crate::core::request::{DispatchSignedRequestFuture, HttpDispatchError};
+use crate::core::runtime::default_runtime;
+use crate::core::{DispatchSignedRequest, HttpResponse};
+use crate::signature::{ByteStream, SignedRequest};
+
+/// A fault `ChaosDispatcher` can inject.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Fault {
+    Reset,
+    InternalError,
+    ServiceUnavailable,
+    Truncate,
+}
+
+/// A xorshift generator, so a seed reproduces the same sequence of faults.
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A `DispatchSignedRequest` that wraps another dispatcher and makes some of its requests fail,
+/// to check how code built on the client copes with an unreliable connection.
+///
+/// ```rust,ignore
+/// let dispatcher = ChaosDispatcher::new(HttpClient::new()?)
+///     .with_seed(42)
+///     .with_latency(0.2, Duration::from_millis(500))
+///     .with_service_unavailable(0.1)
+///     .with_truncated_bodies(0.05);
+/// let client = S3Client::new_with(dispatcher, credentials, Region::default());
+/// ```
+///
+/// Every probability is per request, and at most one fault is injected into a request, in
+/// this order: a connection reset, a 500 response, a 503 response, a truncated body. Requests
+/// answered with a reset or an error status are not sent. The faults are drawn from a
+/// generator that can be seeded, so a failing run can be replayed.
+pub struct ChaosDispatcher<D> {
+    inner: D,
+    rng: Mutex<Rng>,
+    latency: (f64, Duration),
+    faults: Vec<(Fault, f64)>,
+}
+
+impl<D> ChaosDispatcher<D> {
+    /// Wrap `inner`, injecting no faults until they are configured.
+    pub fn new(inner: D) -> ChaosDispatcher<D> {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        ChaosDispatcher {
+            inner,
+            rng: Mutex::new(Rng(seed | 1)),
+            latency: (0.0, Duration::from_secs(0)),
+            faults: Vec::new(),
+        }
+    }
+
+    /// Seed the generator the faults are drawn from.
+    pub fn with_seed(self, seed: u64) -> ChaosDispatcher<D> {
+        *self.rng.lock().expect("generator poisoned") = Rng(seed | 1);
+        self
+    }
+
+    /// Delay requests by `delay` with probability `probability`.
+    pub fn with_latency(mut self, probability: f64, delay: Duration) -> ChaosDispatcher<D> {
+        self.latency = (probability, delay);
+        self
+    }
+
+    /// Fail requests with a dispatch error, as if the connection was reset, with probability
+    /// `probability`.
+    pub fn with_connection_resets(self, probability: f64) -> ChaosDispatcher<D> {
+        self.with_fault(Fault::Reset, probability)
+    }
+
+    /// Answer requests with a 500 `InternalError` with probability `probability`.
+    pub fn with_internal_errors(self, probability: f64) -> ChaosDispatcher<D> {
+        self.with_fault(Fault::InternalError, probability)
+    }
+
+    /// Answer requests with a 503 `SlowDown` with probability `probability`.
+    pub fn with_service_unavailable(self, probability: f64) -> ChaosDispatcher<D> {
+        self.with_fault(Fault::ServiceUnavailable, probability)
+    }
+
+    /// Cut response bodies in half, ending them with an `UnexpectedEof` error, with
+    /// probability `probability`.
+    pub fn with_truncated_bodies(self, probability: f64) -> ChaosDispatcher<D> {
+        self.with_fault(Fault::Truncate, probability)
+    }
+
+    fn with_fault(mut self, fault: Fault, probability: f64) -> ChaosDispatcher<D> {
+        self.faults.retain(|&(f, _)| f != fault);
+        self.faults.push((fault, probability));
+        self.faults.sort_by_key(|&(f, _)| f as u8);
+        self
+    }
+
+    /// Draw the delay and the fault of a request.
+    fn draw(&self) -> (Option<Duration>, Option<Fault>) {
+        let mut rng = self.rng.lock().expect("generator poisoned");
+        let delay = Some(self.latency.1).filter(|_| rng.next_f64() < self.latency.0);
+        let fault = self
+            .faults
+            .iter()
+            .find(|&&(_, probability)| rng.next_f64() < probability)
+            .map(|&(fault, _)| fault);
+        (delay, fault)
+    }
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str) -> HttpResponse {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <Error><Code>{}</Code><Message>{}</Message></Error>",
+        code, message
+    );
+    HttpResponse {
+        status,
+        body: ByteStream::from(Bytes::from(body)),
+        headers: HeaderMap::default(),
+        timings: None,
+    }
+}
+
+impl<D: DispatchSignedRequest> DispatchSignedRequest for ChaosDispatcher<D> {
+    fn dispatch(
+        &self,
+        request: SignedRequest,
+        timeout: Option<Duration>,
+    ) -> DispatchSignedRequestFuture {
+        let (delay, fault) = self.draw();
+        let response = match fault {
+            Some(Fault::Reset) => futures::future::err(HttpDispatchError::new(
+                "Connection reset by peer (injected)".to_owned(),
+            ))
+            .boxed(),
+            Some(Fault::InternalError) => futures::future::ok(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "InternalError",
+                "We encountered an internal error. Please try again.",
+            ))
+            .boxed(),
+            Some(Fault::ServiceUnavailable) => futures::future::ok(error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "SlowDown",
+                "Please reduce your request rate.",
+            ))
+            .boxed(),
+            _ => self.inner.dispatch(request, timeout),
+        };
+        async move {
+            if let Some(delay) = delay {
+                default_runtime().sleep(delay).await;
+            }
+            let mut response = response.await?;
+            if fault == Some(Fault::Truncate) {
+                let body = response.buffer_body().await?;
+                let half = body.slice(..body.len() / 2);
+                let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "body truncated (injected)");
+                response.body = ByteStream::new(stream::iter(vec![Ok(half), Err(eof)]));
+            }
+            Ok(response)
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::credential::{AwsCredentials, CredentialsError, ProvideAwsCredentials};
+
+#[derive(Clone)]
+enum Answer {
+    Credentials(AwsCredentials),
+    ExpiringIn(AwsCredentials, Duration),
+    Error(CredentialsError),
+}
+
+#[derive(Default)]
+struct Script {
+    answers: VecDeque<Answer>,
+    last: Option<Answer>,
+}
+
+/// A `ProvideAwsCredentials` whose answers are scripted call by call, to test how credentials
+/// are cached and refreshed, e.g. by `AutoRefreshingProvider`.
+///
+/// ```rust,ignore
+/// let provider = MockCredentialsProvider::new()
+///     .then_expiring_in("key1", "secret1", Duration::seconds(-1))
+///     .then_error("instance metadata unavailable")
+///     .then_credentials("key2", "secret2");
+/// let calls = provider.call_count();
+/// ```
+///
+/// Each call returns the next scripted answer; once they are used up, the last one is returned
+/// again. A provider with nothing scripted fails every call.
+#[derive(Default)]
+pub struct MockCredentialsProvider {
+    script: Mutex<Script>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl MockCredentialsProvider {
+    /// Create a provider with nothing scripted.
+    pub fn new() -> MockCredentialsProvider {
+        MockCredentialsProvider::default()
+    }
+
+    /// Answer the next call with `credentials`.
+    pub fn then(self, credentials: AwsCredentials) -> MockCredentialsProvider {
+        self.push(Answer::Credentials(credentials))
+    }
+
+    /// Answer the next call with credentials that never expire.
+    pub fn then_credentials(self, key: &str, secret: &str) -> MockCredentialsProvider {
+        self.then(AwsCredentials::new(key, secret, None, None))
+    }
+
+    /// Answer the next call with credentials expiring at `expires_at`.
+    pub fn then_expiring_at(
+        self,
+        key: &str,
+        secret: &str,
+        expires_at: DateTime<Utc>,
+    ) -> MockCredentialsProvider {
+        self.then(AwsCredentials::new(key, secret, None, Some(expires_at)))
+    }
+
+    /// Answer the next call with credentials expiring `lifetime` after the call, or already
+    /// expired if `lifetime` is negative.
+    pub fn then_expiring_in(
+        self,
+        key: &str,
+        secret: &str,
+        lifetime: Duration,
+    ) -> MockCredentialsProvider {
+        let credentials = AwsCredentials::new(key, secret, None, None);
+        self.push(Answer::ExpiringIn(credentials, lifetime))
+    }
+
+    /// Fail the next call with `message`.
+    pub fn then_error(self, message: &str) -> MockCredentialsProvider {
+        self.push(Answer::Error(CredentialsError::new(message)))
+    }
+
+    /// A counter of the calls made so far, which can be read after the provider was moved into
+    /// a client.
+    pub fn call_count(&self) -> Arc<AtomicUsize> {
+        self.calls.clone()
+    }
+
+    fn push(self, answer: Answer) -> MockCredentialsProvider {
+        self.script
+            .lock()
+            .expect("script poisoned")
+            .answers
+            .push_back(answer);
+        self
+    }
+
+    fn next_answer(&self) -> Option<Answer> {
+        let mut script = self.script.lock().expect("script poisoned");
+        if let Some(answer) = script.answers.pop_front() {
+            script.last = Some(answer);
+        }
+        script.last.clone()
+    }
+}
+
+#[async_trait]
+impl ProvideAwsCredentials for MockCredentialsProvider {
+    async fn credentials(&self) -> Result<AwsCredentials, CredentialsError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match self.next_answer() {
+            Some(Answer::Credentials(credentials)) => Ok(credentials),
+            Some(Answer::ExpiringIn(credentials, lifetime)) => Ok(AwsCredentials::new(
+                credentials.aws_access_key_id(),
+                credentials.aws_secret_access_key(),
+                credentials.token().clone(),
+                Some(Utc::now() + lifetime),
+            )),
+            Some(Answer::Error(err)) => Err(err),
+            None => Err(CredentialsError::new("no credentials scripted")),
+        }
+    }
+}
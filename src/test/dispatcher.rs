@@ -0,0 +1,197 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::FutureExt;
+use http::header::HeaderName;
+use http::{HeaderMap, StatusCode};
+
+use crate::core::request::{DispatchSignedRequestFuture, HttpDispatchError};
+use crate::core::{DispatchSignedRequest, HttpResponse};
+use crate::signature::{ByteStream, SignedRequest};
+
+type RequestChecker = Box<dyn Fn(&SignedRequest) + Send + Sync>;
+
+enum Outcome {
+    Performed {
+        status: StatusCode,
+        body: Bytes,
+        headers: HeaderMap<String>,
+    },
+    Failed(HttpDispatchError),
+}
+
+/// A `DispatchSignedRequest` that answers every request with a canned response instead of
+/// sending it, checking the request against the expectations it was given.
+///
+/// ```rust,ignore
+/// let dispatcher = MockRequestDispatcher::with_status(200)
+///     .with_header("ETag", "\"abc\"")
+///     .expect_method("PUT")
+///     .expect_path("/bucket/key");
+/// let client = S3Client::new_with(dispatcher, credentials, Region::default());
+/// ```
+///
+/// Failed expectations panic inside `dispatch`, failing the test that sent the request.
+pub struct MockRequestDispatcher {
+    outcome: Outcome,
+    checkers: Vec<RequestChecker>,
+    requests: Arc<AtomicUsize>,
+}
+
+impl Default for MockRequestDispatcher {
+    fn default() -> MockRequestDispatcher {
+        MockRequestDispatcher::with_status(200)
+    }
+}
+
+impl MockRequestDispatcher {
+    /// Answer with an empty response with the status `status`.
+    pub fn with_status(status: u16) -> MockRequestDispatcher {
+        MockRequestDispatcher {
+            outcome: Outcome::Performed {
+                status: StatusCode::from_u16(status).expect("invalid status code"),
+                body: Bytes::new(),
+                headers: HeaderMap::default(),
+            },
+            checkers: Vec::new(),
+            requests: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Fail every request with a dispatch error carrying `message`, as if the connection
+    /// failed.
+    pub fn with_dispatch_error<M: Into<String>>(message: M) -> MockRequestDispatcher {
+        MockRequestDispatcher {
+            outcome: Outcome::Failed(HttpDispatchError::new(message.into())),
+            ..MockRequestDispatcher::default()
+        }
+    }
+
+    /// Set the body of the response.
+    pub fn with_body<B: Into<Bytes>>(mut self, body: B) -> MockRequestDispatcher {
+        if let Outcome::Performed {
+            body: ref mut b, ..
+        } = self.outcome
+        {
+            *b = body.into();
+        }
+        self
+    }
+
+    /// Add a header to the response.
+    pub fn with_header(mut self, name: &str, value: &str) -> MockRequestDispatcher {
+        if let Outcome::Performed {
+            ref mut headers, ..
+        } = self.outcome
+        {
+            let name = HeaderName::from_bytes(name.as_bytes()).expect("invalid header name");
+            headers.append(name, value.to_owned());
+        }
+        self
+    }
+
+    /// Run `checker` on every request, e.g. to assert on its payload.
+    pub fn with_request_checker<F>(mut self, checker: F) -> MockRequestDispatcher
+    where
+        F: Fn(&SignedRequest) + Send + Sync + 'static,
+    {
+        self.checkers.push(Box::new(checker));
+        self
+    }
+
+    /// Expect requests with the HTTP method `method`.
+    pub fn expect_method(self, method: &str) -> MockRequestDispatcher {
+        let method = method.to_owned();
+        self.with_request_checker(move |request| {
+            assert_eq!(request.method(), method, "unexpected request method");
+        })
+    }
+
+    /// Expect requests for `path`, e.g. `/bucket/key`.
+    pub fn expect_path(self, path: &str) -> MockRequestDispatcher {
+        let path = path.to_owned();
+        self.with_request_checker(move |request| {
+            assert_eq!(request.path(), path, "unexpected request path");
+        })
+    }
+
+    /// Expect requests with the query parameter `name`, set to `value` if given.
+    pub fn expect_param(self, name: &str, value: Option<&str>) -> MockRequestDispatcher {
+        let name = name.to_owned();
+        let value = value.map(str::to_owned);
+        self.with_request_checker(move |request| match request.params.get(&name) {
+            Some(actual) => assert_eq!(
+                actual, &value,
+                "unexpected value of query parameter {}",
+                name
+            ),
+            None => panic!("missing query parameter {}", name),
+        })
+    }
+
+    /// Expect requests with the header `name` set to `value`.
+    pub fn expect_header(self, name: &str, value: &str) -> MockRequestDispatcher {
+        let name = name.to_owned();
+        let value = value.to_owned();
+        self.with_request_checker(move |request| {
+            let values = request.get_header(&name);
+            assert!(
+                values.iter().any(|v| v.as_bytes() == value.as_bytes()),
+                "expected header {}: {}, got {:?}",
+                name,
+                value,
+                values
+                    .iter()
+                    .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned())
+                    .collect::<Vec<_>>()
+            );
+        })
+    }
+
+    /// Expect requests without the header `name`.
+    pub fn expect_no_header(self, name: &str) -> MockRequestDispatcher {
+        let name = name.to_owned();
+        self.with_request_checker(move |request| {
+            assert!(
+                request.get_header(&name).iter().next().is_none(),
+                "unexpected header {}",
+                name
+            );
+        })
+    }
+
+    /// A counter of the requests dispatched so far, which can be read after the dispatcher was
+    /// moved into a client.
+    pub fn request_count(&self) -> Arc<AtomicUsize> {
+        self.requests.clone()
+    }
+}
+
+impl DispatchSignedRequest for MockRequestDispatcher {
+    fn dispatch(
+        &self,
+        request: SignedRequest,
+        _timeout: Option<Duration>,
+    ) -> DispatchSignedRequestFuture {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        for checker in &self.checkers {
+            checker(&request);
+        }
+        let result = match self.outcome {
+            Outcome::Performed {
+                status,
+                ref body,
+                ref headers,
+            } => Ok(HttpResponse {
+                status,
+                body: ByteStream::from(body.clone()),
+                headers: headers.clone(),
+                timings: None,
+            }),
+            Outcome::Failed(ref err) => Err(err.clone()),
+        };
+        futures::future::ready(result).boxed()
+    }
+}
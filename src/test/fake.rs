@@ -0,0 +1,1587 @@
+#[cfg(feature = "multipart")]
+use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use http::{HeaderMap, StatusCode};
+use percent_encoding::percent_decode_str;
+
+use crate::core::error::Ks3Error;
+use crate::core::BufferedHttpResponse;
+use crate::dates::format_http_date;
+use crate::enums::{CannedAcl, StorageClass};
+use crate::integrity::crc64;
+use crate::metadata::Metadata;
+use crate::range::Range;
+use crate::request::*;
+#[cfg(any(
+    feature = "append-object",
+    feature = "bucket-analytics",
+    feature = "bucket-lifecycle",
+    feature = "bucket-metrics",
+    feature = "multipart",
+    feature = "restore-object"
+))]
+use crate::s3::ops::*;
+use crate::s3::S3;
+use crate::tagging::TagSet;
+
+const DEFAULT_CONTENT_TYPE: &str = "binary/octet-stream";
+
+/// The most metrics or analytics configurations returned by one list call.
+#[cfg(any(feature = "bucket-analytics", feature = "bucket-metrics"))]
+const MAX_CONFIGURATIONS_PER_PAGE: usize = 100;
+
+#[derive(Clone, Debug, Default)]
+struct StoredObject {
+    data: Bytes,
+    version_id: String,
+    e_tag: String,
+    last_modified: DateTime<Utc>,
+    cache_control: Option<String>,
+    content_disposition: Option<String>,
+    content_encoding: Option<String>,
+    content_language: Option<String>,
+    content_type: Option<String>,
+    expires: Option<DateTime<Utc>>,
+    metadata: Metadata,
+    storage_class: Option<StorageClass>,
+    website_redirect_location: Option<String>,
+    acl: Option<CannedAcl>,
+    tags: Vec<Tag>,
+    /// Whether the object was created by `append_object`, and can be appended to.
+    #[cfg(feature = "append-object")]
+    appendable: bool,
+    /// When the restored copy of an archived object expires, once `restore_object` was called.
+    restored_until: Option<DateTime<Utc>>,
+}
+
+impl StoredObject {
+    /// Whether the object can be read: it isn't archived, or a restored copy of it is.
+    fn readable(&self) -> bool {
+        self.storage_class != Some(StorageClass::Archive)
+            || self.restored_until.is_some_and(|until| until > Utc::now())
+    }
+}
+
+#[cfg(feature = "multipart")]
+#[derive(Debug)]
+struct Upload {
+    bucket: String,
+    key: String,
+    initiated: DateTime<Utc>,
+    template: StoredObject,
+    parts: BTreeMap<i64, (Bytes, String)>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    buckets: BTreeMap<String, BTreeMap<String, Vec<StoredObject>>>,
+    #[cfg(feature = "multipart")]
+    uploads: HashMap<String, Upload>,
+    #[cfg(feature = "bucket-metrics")]
+    metrics_configurations: BTreeMap<String, BTreeMap<String, MetricsConfiguration>>,
+    #[cfg(feature = "bucket-analytics")]
+    analytics_configurations: BTreeMap<String, BTreeMap<String, AnalyticsConfiguration>>,
+    #[cfg(feature = "bucket-lifecycle")]
+    lifecycle_rules: BTreeMap<String, Vec<LifecycleRule>>,
+    /// The bucket, configuration ID and idempotency token of the configuration changes applied.
+    #[cfg(any(feature = "bucket-analytics", feature = "bucket-metrics"))]
+    idempotency_tokens: BTreeSet<(String, String, String)>,
+    /// The location constraint buckets were created with, if any.
+    bucket_locations: BTreeMap<String, String>,
+    next_id: u64,
+}
+
+impl State {
+    fn next_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("{:016x}", self.next_id)
+    }
+
+    fn versions(&self, bucket: &str, key: &str) -> Option<&Vec<StoredObject>> {
+        self.buckets.get(bucket)?.get(key)
+    }
+
+    fn object(&self, bucket: &str, key: &str, version_id: Option<&str>) -> Option<&StoredObject> {
+        let versions = self.versions(bucket, key)?;
+        match version_id {
+            Some(id) => versions.iter().find(|v| v.version_id == id),
+            None => versions.last(),
+        }
+    }
+
+    fn object_mut(
+        &mut self,
+        bucket: &str,
+        key: &str,
+        version_id: Option<&str>,
+    ) -> Option<&mut StoredObject> {
+        let versions = self.buckets.get_mut(bucket)?.get_mut(key)?;
+        match version_id {
+            Some(id) => versions.iter_mut().find(|v| v.version_id == id),
+            None => versions.last_mut(),
+        }
+    }
+
+    /// Store `object` as the latest version of `key`, returning its version ID.
+    fn store(&mut self, bucket: &str, key: &str, mut object: StoredObject) -> String {
+        let version_id = self.next_id();
+        object.version_id = version_id.clone();
+        object.last_modified = Utc::now();
+        self.buckets
+            .get_mut(bucket)
+            .expect("bucket checked by the caller")
+            .entry(key.to_owned())
+            .or_default()
+            .push(object);
+        version_id
+    }
+}
+
+/// An in-memory implementation of `S3`, to swap for `S3Client` in application tests.
+///
+/// Buckets have to be created first, with `create_bucket` or `with_bucket`. Every upload keeps
+/// the previous versions of the object, which can be read back through the `version_id` of the
+/// requests; deleting an object without a `version_id` removes all of its versions, as in an
+/// unversioned bucket. Failures are reported the way `S3Client` reports them, by running the
+/// `from_response` constructor of the error type on an XML error body, so they can be matched
+/// the same way.
+///
+/// Request fields that only matter to the service, such as encryption, ACL grants and
+/// conditional headers, are ignored.
+#[derive(Debug, Default)]
+pub struct FakeS3 {
+    state: Mutex<State>,
+}
+
+impl FakeS3 {
+    /// Create an empty backend.
+    pub fn new() -> FakeS3 {
+        FakeS3::default()
+    }
+
+    /// Create the bucket `name`.
+    pub fn with_bucket(self, name: &str) -> FakeS3 {
+        self.state().buckets.entry(name.to_owned()).or_default();
+        self
+    }
+
+    /// The content of the latest version of `key` in `bucket`.
+    pub fn object_data(&self, bucket: &str, key: &str) -> Option<Bytes> {
+        self.state()
+            .object(bucket, key, None)
+            .map(|object| object.data.clone())
+    }
+
+    /// The keys in `bucket`, in lexicographic order.
+    pub fn keys(&self, bucket: &str) -> Vec<String> {
+        self.state()
+            .buckets
+            .get(bucket)
+            .map(|objects| objects.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The IDs of the multipart uploads that were neither completed nor aborted.
+    #[cfg(feature = "multipart")]
+    pub fn pending_uploads(&self) -> Vec<String> {
+        self.state().uploads.keys().cloned().collect()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("fake state poisoned")
+    }
+}
+
+/// Build the error `from_response` makes of a response with `status` and an XML error body.
+fn error<E>(
+    status: u16,
+    code: &str,
+    message: &str,
+    from_response: fn(BufferedHttpResponse) -> Ks3Error<E>,
+) -> Ks3Error<E> {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+         <Error><Code>{}</Code><Message>{}</Message></Error>",
+        code, message
+    );
+    from_response(BufferedHttpResponse {
+        status: StatusCode::from_u16(status).expect("valid status code"),
+        body: Bytes::from(body),
+        headers: HeaderMap::default(),
+    })
+}
+
+fn no_such_bucket<E>(from_response: fn(BufferedHttpResponse) -> Ks3Error<E>) -> Ks3Error<E> {
+    error(
+        404,
+        "NoSuchBucket",
+        "The specified bucket does not exist.",
+        from_response,
+    )
+}
+
+fn no_such_key<E>(from_response: fn(BufferedHttpResponse) -> Ks3Error<E>) -> Ks3Error<E> {
+    error(
+        404,
+        "NoSuchKey",
+        "The specified key does not exist.",
+        from_response,
+    )
+}
+
+#[cfg(any(feature = "bucket-analytics", feature = "bucket-metrics"))]
+fn no_such_configuration<E>(from_response: fn(BufferedHttpResponse) -> Ks3Error<E>) -> Ks3Error<E> {
+    error(
+        404,
+        "NoSuchConfiguration",
+        "The specified configuration does not exist.",
+        from_response,
+    )
+}
+
+/// The entity tag of a metrics or analytics configuration, changing with its content.
+#[cfg(any(feature = "bucket-analytics", feature = "bucket-metrics"))]
+fn configuration_e_tag<T: std::fmt::Debug>(configuration: &T) -> String {
+    e_tag(format!("{:?}", configuration).as_bytes())
+}
+
+/// The error `from_response` makes of the `412 Precondition Failed` answer to a change of a
+/// configuration whose entity tag is `e_tag`.
+#[cfg(any(feature = "bucket-analytics", feature = "bucket-metrics"))]
+fn precondition_failed<E>(
+    e_tag: Option<String>,
+    from_response: fn(BufferedHttpResponse) -> Ks3Error<E>,
+) -> Ks3Error<E> {
+    let mut headers = HeaderMap::default();
+    if let Some(e_tag) = e_tag {
+        headers.insert("ETag", e_tag);
+    }
+    from_response(BufferedHttpResponse {
+        status: StatusCode::PRECONDITION_FAILED,
+        body: Bytes::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <Error><Code>PreconditionFailed</Code>\
+             <Message>At least one of the preconditions you specified did not hold.</Message></Error>",
+        ),
+        headers,
+    })
+}
+
+/// The page of `configurations` starting at `continuation_token`, with the token of the next
+/// page if there are more.
+#[cfg(any(feature = "bucket-analytics", feature = "bucket-metrics"))]
+fn page<T: Clone>(
+    configurations: Option<&BTreeMap<String, T>>,
+    continuation_token: Option<&String>,
+) -> (Vec<T>, Option<String>) {
+    let mut configurations = configurations
+        .into_iter()
+        .flatten()
+        .filter(|(id, _)| continuation_token.is_none_or(|token| *id >= token));
+    let page = configurations
+        .by_ref()
+        .take(MAX_CONFIGURATIONS_PER_PAGE)
+        .map(|(_, configuration)| configuration.clone())
+        .collect();
+    (page, configurations.next().map(|(id, _)| id.clone()))
+}
+
+#[cfg(feature = "multipart")]
+fn no_such_upload<E>(from_response: fn(BufferedHttpResponse) -> Ks3Error<E>) -> Ks3Error<E> {
+    error(
+        404,
+        "NoSuchUpload",
+        "The specified upload does not exist.",
+        from_response,
+    )
+}
+
+async fn read_body<E>(body: Option<StreamingBody>) -> Result<Bytes, Ks3Error<E>> {
+    let mut data = BytesMut::new();
+    if let Some(mut body) = body {
+        while let Some(chunk) = body.next().await {
+            data.extend_from_slice(&chunk?);
+        }
+    }
+    Ok(data.freeze())
+}
+
+fn e_tag(data: &[u8]) -> String {
+    format!("\"{:x}\"", md5::compute(data))
+}
+
+/// Resolve `range` against an object of `len` bytes.
+fn resolve_range(range: &Range, len: usize) -> Option<(usize, usize)> {
+    let (first, last) = range.resolve(len as u64)?;
+    Some((first as usize, last as usize))
+}
+
+/// Split a `/bucket/key` copy source into its percent-decoded bucket and key.
+fn parse_copy_source(copy_source: &str) -> Option<(String, String)> {
+    let decoded = percent_decode_str(copy_source.split('?').next()?)
+        .decode_utf8()
+        .ok()?;
+    let (bucket, key) = decoded.trim_start_matches('/').split_once('/')?;
+    Some((bucket.to_owned(), key.to_owned()))
+}
+
+/// Parse an `x-amz-tagging` value, a URL query string of tags.
+fn parse_tagging(tagging: &str) -> Vec<Tag> {
+    tagging
+        .parse::<TagSet>()
+        .map(|tags| Tagging::from(tags).tag_set)
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl S3 for FakeS3 {
+    async fn create_bucket(
+        &self,
+        input: CreateBucketRequest,
+    ) -> Result<CreateBucketOutput, Ks3Error<CreateBucketError>> {
+        let mut state = self.state();
+        if state.buckets.contains_key(&input.bucket) {
+            return Err(error(
+                409,
+                "BucketAlreadyOwnedByYou",
+                "Your previous request to create the named bucket succeeded and you already own it.",
+                CreateBucketError::from_response,
+            ));
+        }
+        state.buckets.insert(input.bucket.clone(), BTreeMap::new());
+        if let Some(location) = input
+            .create_bucket_configuration
+            .and_then(|configuration| configuration.location_constraint)
+        {
+            state
+                .bucket_locations
+                .insert(input.bucket.clone(), location);
+        }
+        Ok(CreateBucketOutput {
+            location: Some(format!("/{}", input.bucket)),
+            ..Default::default()
+        })
+    }
+
+    async fn put_object(
+        &self,
+        input: PutObjectRequest,
+    ) -> Result<PutObjectOutput, Ks3Error<PutObjectError>> {
+        let data = read_body(input.body).await?;
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(PutObjectError::from_response));
+        }
+        let object = StoredObject {
+            e_tag: e_tag(&data),
+            data,
+            cache_control: input.cache_control,
+            content_disposition: input.content_disposition,
+            content_encoding: input.content_encoding,
+            content_language: input.content_language,
+            content_type: input.content_type,
+            expires: input.expires,
+            metadata: input.metadata.unwrap_or_default(),
+            storage_class: input.storage_class,
+            website_redirect_location: input.website_redirect_location,
+            acl: input.acl,
+            tags: input
+                .tagging
+                .as_deref()
+                .map(parse_tagging)
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        let e_tag = object.e_tag.clone();
+        let checksum = crc64(0, &object.data).to_string();
+        let version_id = state.store(&input.bucket, &input.key, object);
+        Ok(PutObjectOutput {
+            checksum_crc64ecma: Some(checksum),
+            e_tag: Some(e_tag),
+            version_id: Some(version_id),
+            ..Default::default()
+        })
+    }
+
+    async fn list_objects(
+        &self,
+        input: ListObjectsRequest,
+    ) -> Result<ListObjectsOutput, Ks3Error<ListObjectsError>> {
+        let state = self.state();
+        let objects = match state.buckets.get(&input.bucket) {
+            Some(objects) => objects,
+            None => return Err(no_such_bucket(ListObjectsError::from_response)),
+        };
+        let prefix = input.prefix.clone().unwrap_or_default();
+        let max_keys = input.max_keys.unwrap_or(1000).max(0) as usize;
+
+        let mut contents = Vec::new();
+        let mut common_prefixes = BTreeSet::new();
+        let mut last_key = None;
+        let mut is_truncated = false;
+        for (key, versions) in objects {
+            if !key.starts_with(&prefix) || input.marker.as_ref().is_some_and(|m| key <= m) {
+                continue;
+            }
+            let rolled_up = input.delimiter.as_deref().and_then(|delimiter| {
+                key[prefix.len()..]
+                    .find(delimiter)
+                    .map(|i| key[..prefix.len() + i + delimiter.len()].to_owned())
+            });
+            if let Some(ref common_prefix) = rolled_up {
+                if common_prefixes.contains(common_prefix) {
+                    continue;
+                }
+            }
+            if contents.len() + common_prefixes.len() >= max_keys {
+                is_truncated = true;
+                break;
+            }
+            match rolled_up {
+                Some(common_prefix) => {
+                    last_key = Some(common_prefix.clone());
+                    common_prefixes.insert(common_prefix);
+                }
+                None => {
+                    let object = versions.last().expect("stored keys have a version");
+                    last_key = Some(key.clone());
+                    contents.push(Object {
+                        e_tag: Some(object.e_tag.clone()),
+                        key: Some(key.clone()),
+                        last_modified: Some(object.last_modified),
+                        owner: None,
+                        size: Some(object.data.len() as i64),
+                        storage_class: Some(
+                            object
+                                .storage_class
+                                .clone()
+                                .unwrap_or(StorageClass::Standard),
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(ListObjectsOutput {
+            common_prefixes: Some(
+                common_prefixes
+                    .into_iter()
+                    .map(|prefix| CommonPrefix {
+                        prefix: Some(prefix),
+                    })
+                    .collect(),
+            )
+            .filter(|prefixes: &Vec<_>| !prefixes.is_empty()),
+            contents: Some(contents).filter(|contents| !contents.is_empty()),
+            delimiter: input.delimiter,
+            encoding_type: None,
+            is_truncated: Some(is_truncated),
+            marker: input.marker,
+            max_keys: Some(max_keys as i64),
+            name: Some(input.bucket),
+            next_marker: last_key.filter(|_| is_truncated),
+            prefix: input.prefix,
+            ..Default::default()
+        })
+    }
+
+    async fn delete_object(
+        &self,
+        input: DeleteObjectRequest,
+    ) -> Result<DeleteObjectOutput, Ks3Error<DeleteObjectError>> {
+        let mut state = self.state();
+        let objects = match state.buckets.get_mut(&input.bucket) {
+            Some(objects) => objects,
+            None => return Err(no_such_bucket(DeleteObjectError::from_response)),
+        };
+        match input.version_id {
+            Some(ref version_id) => {
+                if let Some(versions) = objects.get_mut(&input.key) {
+                    versions.retain(|v| &v.version_id != version_id);
+                    if versions.is_empty() {
+                        objects.remove(&input.key);
+                    }
+                }
+            }
+            None => {
+                objects.remove(&input.key);
+            }
+        }
+        Ok(DeleteObjectOutput {
+            version_id: input.version_id,
+            ..Default::default()
+        })
+    }
+
+    async fn get_object(
+        &self,
+        input: GetObjectRequest,
+    ) -> Result<GetObjectOutput, Ks3Error<GetObjectError>> {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(GetObjectError::from_response));
+        }
+        let object = match state.object(&input.bucket, &input.key, input.version_id.as_deref()) {
+            Some(object) => object,
+            None => return Err(no_such_key(GetObjectError::from_response)),
+        };
+        if !object.readable() {
+            return Err(error(
+                403,
+                "InvalidObjectState",
+                "The operation is not valid for the object's storage class.",
+                GetObjectError::from_response,
+            ));
+        }
+
+        let len = object.data.len();
+        let (data, content_range) = match input.range {
+            Some(ref range) => match resolve_range(range, len) {
+                Some((first, last)) => (
+                    object.data.slice(first..=last),
+                    Some(format!("bytes {}-{}/{}", first, last, len)),
+                ),
+                None => {
+                    return Err(error(
+                        416,
+                        "InvalidRange",
+                        "The requested range is not satisfiable",
+                        GetObjectError::from_response,
+                    ))
+                }
+            },
+            None => (object.data.clone(), None),
+        };
+        Ok(GetObjectOutput {
+            accept_ranges: Some("bytes".to_owned()),
+            cache_control: input
+                .response_cache_control
+                .or_else(|| object.cache_control.clone()),
+            checksum_crc64ecma: Some(crc64(0, &object.data).to_string()),
+            content_disposition: input
+                .response_content_disposition
+                .or_else(|| object.content_disposition.clone()),
+            content_encoding: input
+                .response_content_encoding
+                .or_else(|| object.content_encoding.clone()),
+            content_language: input
+                .response_content_language
+                .or_else(|| object.content_language.clone()),
+            content_length: Some(data.len() as i64),
+            content_range,
+            content_type: input.response_content_type.or_else(|| {
+                Some(
+                    object
+                        .content_type
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_owned()),
+                )
+            }),
+            e_tag: Some(object.e_tag.clone()),
+            expires: input.response_expires.or(object.expires),
+            last_modified: Some(object.last_modified),
+            metadata: Some(object.metadata.clone()),
+            storage_class: object.storage_class.clone(),
+            tag_count: Some(object.tags.len() as i64).filter(|&n| n > 0),
+            version_id: Some(object.version_id.clone()),
+            website_redirect_location: object.website_redirect_location.clone(),
+            body: Some(StreamingBody::from(data)),
+            ..Default::default()
+        })
+    }
+
+    async fn head_bucket(&self, input: HeadBucketRequest) -> Result<(), Ks3Error<HeadBucketError>> {
+        if self.state().buckets.contains_key(&input.bucket) {
+            Ok(())
+        } else {
+            Err(no_such_bucket(HeadBucketError::from_response))
+        }
+    }
+
+    async fn head_object(
+        &self,
+        input: HeadObjectRequest,
+    ) -> Result<HeadObjectOutput, Ks3Error<HeadObjectError>> {
+        let state = self.state();
+        let object = match state.object(&input.bucket, &input.key, input.version_id.as_deref()) {
+            Some(object) => object,
+            None => return Err(no_such_key(HeadObjectError::from_response)),
+        };
+        Ok(HeadObjectOutput {
+            accept_ranges: Some("bytes".to_owned()),
+            cache_control: object.cache_control.clone(),
+            checksum_crc64ecma: Some(crc64(0, &object.data).to_string()),
+            content_disposition: object.content_disposition.clone(),
+            content_encoding: object.content_encoding.clone(),
+            content_language: object.content_language.clone(),
+            content_length: Some(object.data.len() as i64),
+            content_type: Some(
+                object
+                    .content_type
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_owned()),
+            ),
+            e_tag: Some(object.e_tag.clone()),
+            expires: object.expires,
+            last_modified: Some(object.last_modified),
+            metadata: Some(object.metadata.clone()),
+            restore: object.restored_until.map(|until| {
+                format!(
+                    "ongoing-request=\"false\", expiry-date=\"{}\"",
+                    format_http_date(&until)
+                )
+            }),
+            storage_class: object.storage_class.clone(),
+            version_id: Some(object.version_id.clone()),
+            website_redirect_location: object.website_redirect_location.clone(),
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "multipart")]
+    async fn create_multipart_upload(
+        &self,
+        input: CreateMultipartUploadRequest,
+    ) -> Result<CreateMultipartUploadOutput, Ks3Error<CreateMultipartUploadError>> {
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(CreateMultipartUploadError::from_response));
+        }
+        let upload_id = state.next_id();
+        let template = StoredObject {
+            cache_control: input.cache_control,
+            content_disposition: input.content_disposition,
+            content_encoding: input.content_encoding,
+            content_language: input.content_language,
+            content_type: input.content_type,
+            expires: input.expires,
+            metadata: input.metadata.unwrap_or_default(),
+            storage_class: input.storage_class,
+            website_redirect_location: input.website_redirect_location,
+            acl: input.acl,
+            tags: input
+                .tagging
+                .as_deref()
+                .map(parse_tagging)
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        state.uploads.insert(
+            upload_id.clone(),
+            Upload {
+                bucket: input.bucket.clone(),
+                key: input.key.clone(),
+                initiated: Utc::now(),
+                template,
+                parts: BTreeMap::new(),
+            },
+        );
+        Ok(CreateMultipartUploadOutput {
+            bucket: Some(input.bucket),
+            key: Some(input.key),
+            upload_id: Some(upload_id),
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "multipart")]
+    async fn upload_part(
+        &self,
+        input: UploadPartRequest,
+    ) -> Result<UploadPartOutput, Ks3Error<UploadPartError>> {
+        let data = read_body(input.body).await?;
+        let mut state = self.state();
+        let upload = match state.uploads.get_mut(&input.upload_id) {
+            Some(upload) if upload.bucket == input.bucket && upload.key == input.key => upload,
+            _ => return Err(no_such_upload(UploadPartError::from_response)),
+        };
+        let e_tag = e_tag(&data);
+        let checksum = crc64(0, &data).to_string();
+        upload
+            .parts
+            .insert(input.part_number, (data, e_tag.clone()));
+        Ok(UploadPartOutput {
+            checksum_crc64ecma: Some(checksum),
+            e_tag: Some(e_tag),
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "multipart")]
+    async fn complete_multipart_upload(
+        &self,
+        input: CompleteMultipartUploadRequest,
+    ) -> Result<CompleteMultipartUploadOutput, Ks3Error<CompleteMultipartUploadError>> {
+        let mut state = self.state();
+        let upload = match state.uploads.get(&input.upload_id) {
+            Some(upload) if upload.bucket == input.bucket && upload.key == input.key => upload,
+            _ => return Err(no_such_upload(CompleteMultipartUploadError::from_response)),
+        };
+
+        let parts = input
+            .multipart_upload
+            .and_then(|upload| upload.parts)
+            .unwrap_or_default();
+        if parts.is_empty() {
+            return Err(error(
+                400,
+                "MalformedXML",
+                "The XML you provided was not well-formed or did not validate against our published schema.",
+                CompleteMultipartUploadError::from_response,
+            ));
+        }
+        let mut data = BytesMut::new();
+        let mut digests = Vec::new();
+        let mut previous = 0;
+        for part in &parts {
+            let part_number = part.part_number.unwrap_or(0);
+            if part_number <= previous {
+                return Err(error(
+                    400,
+                    "InvalidPartOrder",
+                    "The list of parts was not in ascending order.",
+                    CompleteMultipartUploadError::from_response,
+                ));
+            }
+            previous = part_number;
+            match upload.parts.get(&part_number) {
+                Some((part_data, e_tag)) if part.e_tag.as_ref() == Some(e_tag) => {
+                    data.extend_from_slice(part_data);
+                    digests.extend_from_slice(&md5::compute(part_data).0);
+                }
+                _ => {
+                    return Err(error(
+                        400,
+                        "InvalidPart",
+                        "One or more of the specified parts could not be found.",
+                        CompleteMultipartUploadError::from_response,
+                    ))
+                }
+            }
+        }
+
+        let upload = state
+            .uploads
+            .remove(&input.upload_id)
+            .expect("upload checked above");
+        let e_tag = format!("\"{:x}-{}\"", md5::compute(&digests), parts.len());
+        let checksum = crc64(0, &data).to_string();
+        let object = StoredObject {
+            data: data.freeze(),
+            e_tag: e_tag.clone(),
+            ..upload.template
+        };
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(CompleteMultipartUploadError::from_response));
+        }
+        let version_id = state.store(&input.bucket, &input.key, object);
+        Ok(CompleteMultipartUploadOutput {
+            bucket: Some(input.bucket.clone()),
+            checksum_crc64ecma: Some(checksum),
+            e_tag: Some(e_tag),
+            key: Some(input.key.clone()),
+            location: Some(format!("/{}/{}", input.bucket, input.key)),
+            version_id: Some(version_id),
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "multipart")]
+    async fn abort_multipart_upload(
+        &self,
+        input: AbortMultipartUploadRequest,
+    ) -> Result<AbortMultipartUploadOutput, Ks3Error<AbortMultipartUploadError>> {
+        let mut state = self.state();
+        match state.uploads.get(&input.upload_id) {
+            Some(upload) if upload.bucket == input.bucket && upload.key == input.key => {
+                state.uploads.remove(&input.upload_id);
+                Ok(AbortMultipartUploadOutput::default())
+            }
+            _ => Err(no_such_upload(AbortMultipartUploadError::from_response)),
+        }
+    }
+
+    async fn put_object_tagging(
+        &self,
+        input: PutObjectTaggingRequest,
+    ) -> Result<PutObjectTaggingOutput, Ks3Error<PutObjectTaggingError>> {
+        let mut state = self.state();
+        match state.object_mut(&input.bucket, &input.key, input.version_id.as_deref()) {
+            Some(object) => {
+                object.tags = input.tagging.tag_set;
+                Ok(PutObjectTaggingOutput {
+                    version_id: Some(object.version_id.clone()),
+                    ..Default::default()
+                })
+            }
+            None => Err(no_such_key(PutObjectTaggingError::from_response)),
+        }
+    }
+
+    async fn put_object_acl(
+        &self,
+        input: PutObjectAclRequest,
+    ) -> Result<PutObjectAclOutput, Ks3Error<PutObjectAclError>> {
+        let mut state = self.state();
+        match state.object_mut(&input.bucket, &input.key, input.version_id.as_deref()) {
+            Some(object) => {
+                object.acl = input.acl;
+                Ok(PutObjectAclOutput::default())
+            }
+            None => Err(no_such_key(PutObjectAclError::from_response)),
+        }
+    }
+
+    async fn copy_object(
+        &self,
+        input: CopyObjectRequest,
+    ) -> Result<CopyObjectOutput, Ks3Error<CopyObjectError>> {
+        let mut state = self.state();
+        let source = parse_copy_source(&input.copy_source)
+            .and_then(|(bucket, key)| state.object(&bucket, &key, None))
+            .cloned();
+        let source = match source {
+            Some(source) => source,
+            None => return Err(no_such_key(CopyObjectError::from_response)),
+        };
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(CopyObjectError::from_response));
+        }
+
+        let mut object = if input.metadata_directive.as_deref() == Some("REPLACE") {
+            StoredObject {
+                data: source.data.clone(),
+                e_tag: source.e_tag.clone(),
+                cache_control: input.cache_control,
+                content_disposition: input.content_disposition,
+                content_encoding: input.content_encoding,
+                content_language: input.content_language,
+                content_type: input.content_type,
+                expires: input.expires,
+                metadata: input.metadata.unwrap_or_default(),
+                website_redirect_location: input.website_redirect_location,
+                tags: source.tags.clone(),
+                ..Default::default()
+            }
+        } else {
+            source.clone()
+        };
+        object.storage_class = input.storage_class;
+        object.acl = input.acl;
+        if input.tagging_directive.as_deref() == Some("REPLACE") {
+            object.tags = input
+                .tagging
+                .as_deref()
+                .map(parse_tagging)
+                .unwrap_or_default();
+        }
+        let e_tag = object.e_tag.clone();
+        let version_id = state.store(&input.bucket, &input.key, object);
+        let last_modified = state
+            .object(&input.bucket, &input.key, None)
+            .map(|object| object.last_modified)
+            .unwrap_or_else(Utc::now);
+        Ok(CopyObjectOutput {
+            copy_object_result: Some(CopyObjectResult {
+                e_tag: Some(e_tag),
+                last_modified: Some(last_modified),
+            }),
+            copy_source_version_id: Some(source.version_id),
+            version_id: Some(version_id),
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "multipart")]
+    async fn upload_part_copy(
+        &self,
+        input: UploadPartCopyRequest,
+    ) -> Result<UploadPartCopyOutput, Ks3Error<UploadPartCopyError>> {
+        let mut state = self.state();
+        let source = parse_copy_source(&input.copy_source)
+            .and_then(|(bucket, key)| state.object(&bucket, &key, None))
+            .cloned();
+        let source = match source {
+            Some(source) => source,
+            None => return Err(no_such_key(UploadPartCopyError::from_response)),
+        };
+        let data = match input.copy_source_range {
+            Some(ref range) => match resolve_range(range, source.data.len()) {
+                Some((first, last)) => source.data.slice(first..=last),
+                None => {
+                    return Err(error(
+                        416,
+                        "InvalidRange",
+                        "The requested range is not satisfiable",
+                        UploadPartCopyError::from_response,
+                    ))
+                }
+            },
+            None => source.data.clone(),
+        };
+        let upload = match state.uploads.get_mut(&input.upload_id) {
+            Some(upload) if upload.bucket == input.bucket && upload.key == input.key => upload,
+            _ => return Err(no_such_upload(UploadPartCopyError::from_response)),
+        };
+        let e_tag = e_tag(&data);
+        upload
+            .parts
+            .insert(input.part_number, (data, e_tag.clone()));
+        Ok(UploadPartCopyOutput {
+            copy_part_result: Some(CopyPartResult {
+                e_tag: Some(e_tag),
+                last_modified: Some(Utc::now()),
+            }),
+            copy_source_version_id: Some(source.version_id),
+            ..Default::default()
+        })
+    }
+
+    async fn delete_bucket(
+        &self,
+        input: DeleteBucketRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketError>> {
+        let mut state = self.state();
+        match state.buckets.get(&input.bucket) {
+            None => Err(no_such_bucket(DeleteBucketError::from_response)),
+            Some(objects) if !objects.is_empty() => Err(error(
+                409,
+                "BucketNotEmpty",
+                "The bucket you tried to delete is not empty.",
+                DeleteBucketError::from_response,
+            )),
+            Some(_) => {
+                state.buckets.remove(&input.bucket);
+                #[cfg(feature = "multipart")]
+                state
+                    .uploads
+                    .retain(|_, upload| upload.bucket != input.bucket);
+                #[cfg(feature = "bucket-metrics")]
+                state.metrics_configurations.remove(&input.bucket);
+                #[cfg(feature = "bucket-analytics")]
+                state.analytics_configurations.remove(&input.bucket);
+                #[cfg(feature = "bucket-lifecycle")]
+                state.lifecycle_rules.remove(&input.bucket);
+                state.bucket_locations.remove(&input.bucket);
+                Ok(())
+            }
+        }
+    }
+
+    async fn list_buckets(&self) -> Result<ListBucketsOutput, Ks3Error<ListBucketsError>> {
+        let state = self.state();
+        Ok(ListBucketsOutput {
+            buckets: Some(
+                state
+                    .buckets
+                    .keys()
+                    .map(|name| Bucket {
+                        name: Some(name.clone()),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        })
+    }
+
+    async fn get_bucket_location(
+        &self,
+        input: GetBucketLocationRequest,
+    ) -> Result<GetBucketLocationOutput, Ks3Error<GetBucketLocationError>> {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(GetBucketLocationError::from_response));
+        }
+        Ok(GetBucketLocationOutput {
+            location_constraint: state.bucket_locations.get(&input.bucket).cloned(),
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "multipart")]
+    async fn list_multipart_uploads(
+        &self,
+        input: ListMultipartUploadsRequest,
+    ) -> Result<ListMultipartUploadsOutput, Ks3Error<ListMultipartUploadsError>> {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(ListMultipartUploadsError::from_response));
+        }
+        let prefix = input.prefix.clone().unwrap_or_default();
+        let key_marker = input.key_marker.clone().unwrap_or_default();
+        let upload_id_marker = input.upload_id_marker.clone().unwrap_or_default();
+        let max_uploads = input.max_uploads.unwrap_or(1000).max(0) as usize;
+
+        let mut uploads = state
+            .uploads
+            .iter()
+            .filter(|(id, upload)| {
+                upload.bucket == input.bucket
+                    && upload.key.starts_with(&prefix)
+                    && (upload.key > key_marker
+                        || (upload.key == key_marker && **id > upload_id_marker))
+            })
+            .collect::<Vec<_>>();
+        uploads.sort_by(|(a_id, a), (b_id, b)| (&a.key, a_id).cmp(&(&b.key, b_id)));
+        let is_truncated = uploads.len() > max_uploads;
+        uploads.truncate(max_uploads);
+        let last = uploads
+            .last()
+            .map(|(id, upload)| (upload.key.clone(), (*id).clone()));
+
+        Ok(ListMultipartUploadsOutput {
+            bucket: Some(input.bucket),
+            is_truncated: Some(is_truncated),
+            key_marker: input.key_marker,
+            max_uploads: Some(max_uploads as i64),
+            next_key_marker: last.clone().map(|(key, _)| key).filter(|_| is_truncated),
+            next_upload_id_marker: last.map(|(_, id)| id).filter(|_| is_truncated),
+            prefix: input.prefix,
+            upload_id_marker: input.upload_id_marker,
+            uploads: Some(
+                uploads
+                    .into_iter()
+                    .map(|(id, upload)| MultipartUpload {
+                        initiated: Some(upload.initiated),
+                        key: Some(upload.key.clone()),
+                        storage_class: upload.template.storage_class.clone(),
+                        upload_id: Some(id.clone()),
+                        ..Default::default()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .filter(|uploads| !uploads.is_empty()),
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "multipart")]
+    async fn list_parts(
+        &self,
+        input: ListPartsRequest,
+    ) -> Result<ListPartsOutput, Ks3Error<ListPartsError>> {
+        let state = self.state();
+        let upload = match state.uploads.get(&input.upload_id) {
+            Some(upload) if upload.bucket == input.bucket && upload.key == input.key => upload,
+            _ => return Err(no_such_upload(ListPartsError::from_response)),
+        };
+        let marker = input.part_number_marker.unwrap_or(0);
+        let max_parts = input.max_parts.unwrap_or(1000).max(0) as usize;
+
+        let mut parts = upload
+            .parts
+            .range(marker + 1..)
+            .map(|(part_number, (data, e_tag))| Part {
+                e_tag: Some(e_tag.clone()),
+                last_modified: Some(upload.initiated),
+                part_number: Some(*part_number),
+                size: Some(data.len() as i64),
+            })
+            .collect::<Vec<_>>();
+        let is_truncated = parts.len() > max_parts;
+        parts.truncate(max_parts);
+
+        Ok(ListPartsOutput {
+            bucket: Some(input.bucket),
+            is_truncated: Some(is_truncated),
+            key: Some(input.key),
+            max_parts: Some(max_parts as i64),
+            next_part_number_marker: parts
+                .last()
+                .and_then(|part| part.part_number)
+                .filter(|_| is_truncated),
+            part_number_marker: input.part_number_marker,
+            parts: Some(parts),
+            storage_class: upload.template.storage_class.clone(),
+            upload_id: Some(input.upload_id),
+            ..Default::default()
+        })
+    }
+
+    async fn list_object_versions(
+        &self,
+        input: ListObjectVersionsRequest,
+    ) -> Result<ListObjectVersionsOutput, Ks3Error<ListObjectVersionsError>> {
+        let state = self.state();
+        let objects = match state.buckets.get(&input.bucket) {
+            Some(objects) => objects,
+            None => return Err(no_such_bucket(ListObjectVersionsError::from_response)),
+        };
+        let prefix = input.prefix.clone().unwrap_or_default();
+        let key_marker = input.key_marker.clone().unwrap_or_default();
+        let max_keys = input.max_keys.unwrap_or(1000).max(0) as usize;
+
+        // The versions of each key, newest first, resuming after the version of the key marker
+        // if one is given.
+        let mut versions = objects
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix) && **key >= key_marker)
+            .flat_map(|(key, versions)| {
+                let latest = versions.len() - 1;
+                let skipped = match input.version_id_marker {
+                    Some(ref id) if *key == key_marker => versions
+                        .iter()
+                        .rev()
+                        .position(|v| &v.version_id == id)
+                        .map_or(0, |i| i + 1),
+                    _ if *key == key_marker && !key_marker.is_empty() => versions.len(),
+                    _ => 0,
+                };
+                versions
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .skip(skipped)
+                    .map(move |(i, object)| (key, i == latest, object))
+            })
+            .peekable();
+        let page = versions.by_ref().take(max_keys).collect::<Vec<_>>();
+        let is_truncated = versions.peek().is_some();
+        let last = page
+            .last()
+            .map(|(key, _, object)| ((*key).clone(), object.version_id.clone()))
+            .filter(|_| is_truncated);
+
+        Ok(ListObjectVersionsOutput {
+            is_truncated: Some(is_truncated),
+            key_marker: input.key_marker,
+            max_keys: Some(max_keys as i64),
+            name: Some(input.bucket),
+            next_key_marker: last.clone().map(|(key, _)| key),
+            next_version_id_marker: last.map(|(_, id)| id),
+            prefix: input.prefix,
+            version_id_marker: input.version_id_marker,
+            versions: Some(
+                page.into_iter()
+                    .map(|(key, is_latest, object)| ObjectVersion {
+                        e_tag: Some(object.e_tag.clone()),
+                        is_latest: Some(is_latest),
+                        key: Some(key.clone()),
+                        last_modified: Some(object.last_modified),
+                        owner: None,
+                        size: Some(object.data.len() as i64),
+                        storage_class: Some(
+                            object
+                                .storage_class
+                                .clone()
+                                .unwrap_or(StorageClass::Standard),
+                        ),
+                        version_id: Some(object.version_id.clone()),
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .filter(|versions| !versions.is_empty()),
+            ..Default::default()
+        })
+    }
+
+    async fn select_object_content(
+        &self,
+        input: SelectObjectContentRequest,
+    ) -> Result<SelectObjectContentOutput, Ks3Error<SelectObjectContentError>> {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(SelectObjectContentError::from_response));
+        }
+        if state.object(&input.bucket, &input.key, None).is_none() {
+            return Err(no_such_key(SelectObjectContentError::from_response));
+        }
+        Err(error(
+            501,
+            "NotImplemented",
+            "Queries aren't supported by the fake.",
+            SelectObjectContentError::from_response,
+        ))
+    }
+
+    #[cfg(feature = "bucket-metrics")]
+    async fn put_bucket_metrics_configuration(
+        &self,
+        input: PutBucketMetricsConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketMetricsConfigurationError>> {
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                PutBucketMetricsConfigurationError::from_response,
+            ));
+        }
+        let token = input
+            .idempotency_token
+            .as_ref()
+            .map(|token| (input.bucket.clone(), input.id.clone(), token.clone()));
+        if token
+            .as_ref()
+            .is_some_and(|token| state.idempotency_tokens.contains(token))
+        {
+            return Ok(());
+        }
+        let current = state
+            .metrics_configurations
+            .get(&input.bucket)
+            .and_then(|configurations| configurations.get(&input.id))
+            .map(configuration_e_tag);
+        if input.if_match.is_some() && input.if_match != current {
+            return Err(precondition_failed(
+                current,
+                PutBucketMetricsConfigurationError::from_response,
+            ));
+        }
+        state
+            .metrics_configurations
+            .entry(input.bucket)
+            .or_default()
+            .insert(input.id, input.metrics_configuration);
+        state.idempotency_tokens.extend(token);
+        Ok(())
+    }
+
+    #[cfg(feature = "bucket-metrics")]
+    async fn get_bucket_metrics_configuration(
+        &self,
+        input: GetBucketMetricsConfigurationRequest,
+    ) -> Result<GetBucketMetricsConfigurationOutput, Ks3Error<GetBucketMetricsConfigurationError>>
+    {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                GetBucketMetricsConfigurationError::from_response,
+            ));
+        }
+        match state
+            .metrics_configurations
+            .get(&input.bucket)
+            .and_then(|configurations| configurations.get(&input.id))
+        {
+            Some(configuration) => Ok(GetBucketMetricsConfigurationOutput {
+                metrics_configuration: Some(configuration.clone()),
+                e_tag: Some(configuration_e_tag(configuration)),
+                ..Default::default()
+            }),
+            None => Err(no_such_configuration(
+                GetBucketMetricsConfigurationError::from_response,
+            )),
+        }
+    }
+
+    #[cfg(feature = "bucket-metrics")]
+    async fn delete_bucket_metrics_configuration(
+        &self,
+        input: DeleteBucketMetricsConfigurationRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketMetricsConfigurationError>> {
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                DeleteBucketMetricsConfigurationError::from_response,
+            ));
+        }
+        match state
+            .metrics_configurations
+            .get_mut(&input.bucket)
+            .and_then(|configurations| configurations.remove(&input.id))
+        {
+            Some(_) => Ok(()),
+            None => Err(no_such_configuration(
+                DeleteBucketMetricsConfigurationError::from_response,
+            )),
+        }
+    }
+
+    #[cfg(feature = "bucket-metrics")]
+    async fn list_bucket_metrics_configurations(
+        &self,
+        input: ListBucketMetricsConfigurationsRequest,
+    ) -> Result<ListBucketMetricsConfigurationsOutput, Ks3Error<ListBucketMetricsConfigurationsError>>
+    {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                ListBucketMetricsConfigurationsError::from_response,
+            ));
+        }
+        let (configurations, next_continuation_token) = page(
+            state.metrics_configurations.get(&input.bucket),
+            input.continuation_token.as_ref(),
+        );
+        Ok(ListBucketMetricsConfigurationsOutput {
+            continuation_token: input.continuation_token,
+            is_truncated: Some(next_continuation_token.is_some()),
+            metrics_configuration_list: Some(configurations).filter(|list| !list.is_empty()),
+            next_continuation_token,
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "bucket-analytics")]
+    async fn put_bucket_analytics_configuration(
+        &self,
+        input: PutBucketAnalyticsConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketAnalyticsConfigurationError>> {
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                PutBucketAnalyticsConfigurationError::from_response,
+            ));
+        }
+        let token = input
+            .idempotency_token
+            .as_ref()
+            .map(|token| (input.bucket.clone(), input.id.clone(), token.clone()));
+        if token
+            .as_ref()
+            .is_some_and(|token| state.idempotency_tokens.contains(token))
+        {
+            return Ok(());
+        }
+        let current = state
+            .analytics_configurations
+            .get(&input.bucket)
+            .and_then(|configurations| configurations.get(&input.id))
+            .map(configuration_e_tag);
+        if input.if_match.is_some() && input.if_match != current {
+            return Err(precondition_failed(
+                current,
+                PutBucketAnalyticsConfigurationError::from_response,
+            ));
+        }
+        state
+            .analytics_configurations
+            .entry(input.bucket)
+            .or_default()
+            .insert(input.id, input.analytics_configuration);
+        state.idempotency_tokens.extend(token);
+        Ok(())
+    }
+
+    #[cfg(feature = "bucket-analytics")]
+    async fn get_bucket_analytics_configuration(
+        &self,
+        input: GetBucketAnalyticsConfigurationRequest,
+    ) -> Result<GetBucketAnalyticsConfigurationOutput, Ks3Error<GetBucketAnalyticsConfigurationError>>
+    {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                GetBucketAnalyticsConfigurationError::from_response,
+            ));
+        }
+        match state
+            .analytics_configurations
+            .get(&input.bucket)
+            .and_then(|configurations| configurations.get(&input.id))
+        {
+            Some(configuration) => Ok(GetBucketAnalyticsConfigurationOutput {
+                analytics_configuration: Some(configuration.clone()),
+                e_tag: Some(configuration_e_tag(configuration)),
+                ..Default::default()
+            }),
+            None => Err(no_such_configuration(
+                GetBucketAnalyticsConfigurationError::from_response,
+            )),
+        }
+    }
+
+    #[cfg(feature = "bucket-analytics")]
+    async fn delete_bucket_analytics_configuration(
+        &self,
+        input: DeleteBucketAnalyticsConfigurationRequest,
+    ) -> Result<(), Ks3Error<DeleteBucketAnalyticsConfigurationError>> {
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                DeleteBucketAnalyticsConfigurationError::from_response,
+            ));
+        }
+        match state
+            .analytics_configurations
+            .get_mut(&input.bucket)
+            .and_then(|configurations| configurations.remove(&input.id))
+        {
+            Some(_) => Ok(()),
+            None => Err(no_such_configuration(
+                DeleteBucketAnalyticsConfigurationError::from_response,
+            )),
+        }
+    }
+
+    #[cfg(feature = "bucket-analytics")]
+    async fn list_bucket_analytics_configurations(
+        &self,
+        input: ListBucketAnalyticsConfigurationsRequest,
+    ) -> Result<
+        ListBucketAnalyticsConfigurationsOutput,
+        Ks3Error<ListBucketAnalyticsConfigurationsError>,
+    > {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                ListBucketAnalyticsConfigurationsError::from_response,
+            ));
+        }
+        let (configurations, next_continuation_token) = page(
+            state.analytics_configurations.get(&input.bucket),
+            input.continuation_token.as_ref(),
+        );
+        Ok(ListBucketAnalyticsConfigurationsOutput {
+            continuation_token: input.continuation_token,
+            is_truncated: Some(next_continuation_token.is_some()),
+            analytics_configuration_list: Some(configurations).filter(|list| !list.is_empty()),
+            next_continuation_token,
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "bucket-lifecycle")]
+    async fn put_bucket_lifecycle_configuration(
+        &self,
+        input: PutBucketLifecycleConfigurationRequest,
+    ) -> Result<(), Ks3Error<PutBucketLifecycleConfigurationError>> {
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                PutBucketLifecycleConfigurationError::from_response,
+            ));
+        }
+        state
+            .lifecycle_rules
+            .insert(input.bucket, input.lifecycle_configuration.rules);
+        Ok(())
+    }
+
+    #[cfg(feature = "bucket-lifecycle")]
+    async fn get_bucket_lifecycle_configuration(
+        &self,
+        input: GetBucketLifecycleConfigurationRequest,
+    ) -> Result<GetBucketLifecycleConfigurationOutput, Ks3Error<GetBucketLifecycleConfigurationError>>
+    {
+        let state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(
+                GetBucketLifecycleConfigurationError::from_response,
+            ));
+        }
+        match state.lifecycle_rules.get(&input.bucket) {
+            Some(rules) => Ok(GetBucketLifecycleConfigurationOutput {
+                rules: Some(rules.clone()),
+                ..Default::default()
+            }),
+            None => Err(error(
+                404,
+                "NoSuchLifecycleConfiguration",
+                "The lifecycle configuration does not exist.",
+                GetBucketLifecycleConfigurationError::from_response,
+            )),
+        }
+    }
+
+    /// Restores complete at once, for the number of days of the request or one day.
+    #[cfg(feature = "restore-object")]
+    async fn restore_object(
+        &self,
+        input: RestoreObjectRequest,
+    ) -> Result<RestoreObjectOutput, Ks3Error<RestoreObjectError>> {
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(RestoreObjectError::from_response));
+        }
+        let object = match state.object_mut(&input.bucket, &input.key, input.version_id.as_deref())
+        {
+            Some(object) => object,
+            None => return Err(no_such_key(RestoreObjectError::from_response)),
+        };
+        if object.storage_class != Some(StorageClass::Archive) {
+            return Err(error(
+                403,
+                "InvalidObjectState",
+                "The object is not archived.",
+                RestoreObjectError::from_response,
+            ));
+        }
+        let already_restored = object.readable();
+        object.restored_until =
+            Some(Utc::now() + chrono::Duration::days(input.days.unwrap_or(1).max(1)));
+        Ok(RestoreObjectOutput {
+            already_restored,
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "append-object")]
+    async fn append_object(
+        &self,
+        input: AppendObjectRequest,
+    ) -> Result<AppendObjectOutput, Ks3Error<AppendObjectError>> {
+        let data = read_body(input.body).await?;
+        let mut state = self.state();
+        if !state.buckets.contains_key(&input.bucket) {
+            return Err(no_such_bucket(AppendObjectError::from_response));
+        }
+        let position_error = || {
+            error(
+                409,
+                "PositionNotEqualToLength",
+                "The position is not equal to the length of the object.",
+                AppendObjectError::from_response,
+            )
+        };
+        let object = match state.object_mut(&input.bucket, &input.key, None) {
+            Some(object) => {
+                if !object.appendable {
+                    return Err(error(
+                        409,
+                        "ObjectNotAppendable",
+                        "The object is not appendable.",
+                        AppendObjectError::from_response,
+                    ));
+                }
+                if input.position != object.data.len() as i64 {
+                    return Err(position_error());
+                }
+                let mut appended = BytesMut::from(&object.data[..]);
+                appended.extend_from_slice(&data);
+                object.data = appended.freeze();
+                object.e_tag = e_tag(&object.data);
+                object.last_modified = Utc::now();
+                object.clone()
+            }
+            None => {
+                if input.position != 0 {
+                    return Err(position_error());
+                }
+                let object = StoredObject {
+                    e_tag: e_tag(&data),
+                    data,
+                    cache_control: input.cache_control,
+                    content_disposition: input.content_disposition,
+                    content_encoding: input.content_encoding,
+                    content_type: input.content_type,
+                    expires: input.expires,
+                    metadata: input.metadata.unwrap_or_default(),
+                    storage_class: input.storage_class,
+                    acl: input.acl,
+                    tags: input
+                        .tagging
+                        .as_deref()
+                        .map(parse_tagging)
+                        .unwrap_or_default(),
+                    appendable: true,
+                    ..Default::default()
+                };
+                state.store(&input.bucket, &input.key, object.clone());
+                object
+            }
+        };
+        Ok(AppendObjectOutput {
+            checksum_crc64ecma: Some(crc64(0, &object.data).to_string()),
+            e_tag: Some(object.e_tag),
+            next_append_position: Some(object.data.len() as i64),
+            ..Default::default()
+        })
+    }
+}
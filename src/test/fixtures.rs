@@ -0,0 +1,102 @@
+//! Parsing captured responses with the deserializers used by `S3Client`.
+//!
+//! ```rust,ignore
+//! let output: ListObjectsOutput = parse_output(fixtures::LIST_OBJECTS.as_bytes())?;
+//! let err = GetObjectError::from_response(error_response(404, fixtures::ERROR_NO_SUCH_KEY));
+//! ```
+//!
+//! Captured KS3 responses can be checked the same way after reading them with `std::fs::read`.
+
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+
+use crate::core::proto::xml::util::{parse_body, XmlParseError, XmlResponse};
+use crate::core::BufferedHttpResponse;
+use crate::request::*;
+#[cfg(feature = "multipart")]
+use crate::s3::ops::*;
+
+/// `ListObjects` response listing one object and one common prefix.
+pub const LIST_OBJECTS: &str = include_str!("fixtures/list_objects.xml");
+/// `CreateMultipartUpload` response.
+#[cfg(feature = "multipart")]
+pub const CREATE_MULTIPART_UPLOAD: &str = include_str!("fixtures/create_multipart_upload.xml");
+/// `CompleteMultipartUpload` response.
+#[cfg(feature = "multipart")]
+pub const COMPLETE_MULTIPART_UPLOAD: &str = include_str!("fixtures/complete_multipart_upload.xml");
+/// `CopyObject` response.
+pub const COPY_OBJECT: &str = include_str!("fixtures/copy_object.xml");
+/// `UploadPartCopy` response.
+#[cfg(feature = "multipart")]
+pub const UPLOAD_PART_COPY: &str = include_str!("fixtures/upload_part_copy.xml");
+/// Error response for a missing object.
+pub const ERROR_NO_SUCH_KEY: &str = include_str!("fixtures/error_no_such_key.xml");
+/// Error response for a missing multipart upload.
+pub const ERROR_NO_SUCH_UPLOAD: &str = include_str!("fixtures/error_no_such_upload.xml");
+
+/// An operation output parsed from an XML response body.
+pub trait XmlOutput: Default {
+    #[doc(hidden)]
+    fn deserialize(tag_name: &str, stack: &mut XmlResponse<'_>) -> Result<Self, XmlParseError>;
+}
+
+macro_rules! xml_output {
+    ($($output:ty => $deserializer:ident),* $(,)?) => {
+        $(
+            impl XmlOutput for $output {
+                fn deserialize(
+                    tag_name: &str,
+                    stack: &mut XmlResponse<'_>,
+                ) -> Result<Self, XmlParseError> {
+                    $deserializer::deserialize(tag_name, stack)
+                }
+            }
+        )*
+    };
+}
+
+xml_output! {
+    ListObjectsOutput => ListObjectsOutputDeserializer,
+    CopyObjectOutput => CopyObjectOutputDeserializer,
+}
+
+#[cfg(feature = "multipart")]
+xml_output! {
+    CreateMultipartUploadOutput => CreateMultipartUploadOutputDeserializer,
+    CompleteMultipartUploadOutput => CompleteMultipartUploadOutputDeserializer,
+    UploadPartCopyOutput => UploadPartCopyOutputDeserializer,
+}
+
+/// Parse the XML response body `xml` of an operation. Only the fields carried by the body are
+/// filled in; those that `S3Client` takes from response headers are left unset.
+pub fn parse_output<T: XmlOutput>(xml: &[u8]) -> Result<T, XmlParseError> {
+    parse_body(xml, T::deserialize)
+}
+
+/// Build a failed response with the status `status` and the body `xml`, to be passed to the
+/// `from_response` constructor of an operation's error type.
+pub fn error_response<B: Into<Bytes>>(status: u16, xml: B) -> BufferedHttpResponse {
+    BufferedHttpResponse {
+        status: StatusCode::from_u16(status).expect("invalid status code"),
+        body: xml.into(),
+        headers: HeaderMap::default(),
+    }
+}
+
+/// The shipped fixture of the operation `name`, e.g. `ListObjects`, or of the error code `name`,
+/// e.g. `NoSuchKey`.
+pub fn fixture(name: &str) -> Option<&'static str> {
+    match name {
+        "ListObjects" => Some(LIST_OBJECTS),
+        #[cfg(feature = "multipart")]
+        "CreateMultipartUpload" => Some(CREATE_MULTIPART_UPLOAD),
+        #[cfg(feature = "multipart")]
+        "CompleteMultipartUpload" => Some(COMPLETE_MULTIPART_UPLOAD),
+        "CopyObject" => Some(COPY_OBJECT),
+        #[cfg(feature = "multipart")]
+        "UploadPartCopy" => Some(UPLOAD_PART_COPY),
+        "NoSuchKey" => Some(ERROR_NO_SUCH_KEY),
+        "NoSuchUpload" => Some(ERROR_NO_SUCH_UPLOAD),
+        _ => None,
+    }
+}
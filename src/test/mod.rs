@@ -0,0 +1,23 @@
+//! Utilities for testing code built on this crate without a live endpoint.
+//!
+//! Enabled by the `testing` feature, typically as a dev-dependency:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! ks3 = { version = "0.1", features = ["testing"] }
+//! ```
+
+mod cassette;
+mod chaos;
+mod credentials;
+mod dispatcher;
+mod fake;
+pub mod fixtures;
+
+pub use self::cassette::{
+    Cassette, Interaction, RecordedRequest, RecordedResponse, RecordingDispatcher, ReplayDispatcher,
+};
+pub use self::chaos::ChaosDispatcher;
+pub use self::credentials::MockCredentialsProvider;
+pub use self::dispatcher::MockRequestDispatcher;
+pub use self::fake::FakeS3;
@@ -0,0 +1,301 @@
+//! Uploading local files, switching to multipart uploads for large files.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+#[cfg(feature = "encoding")]
+use crate::compression::{UploadCompression, GZIP_ENCODING};
+use crate::core::error::Ks3Error;
+use crate::core::runtime;
+use crate::enums::ServerSideEncryption;
+use crate::exists::{translate, ExistsError};
+#[cfg(feature = "encoding")]
+use crate::mime::mime_type_for_key;
+use crate::multipart::{upload_stream, upload_stream_with_progress, MultipartError, MIN_PART_SIZE};
+use crate::progress::{emit, report_outcome, ProgressEvent, ProgressReporter};
+use crate::request::*;
+use crate::s3::ops::*;
+use crate::s3::S3;
+use crate::sharding::KeyTransformer;
+use crate::signature::ByteStream;
+use crate::sync::file_md5;
+
+/// Options of `upload_file`.
+#[derive(Clone, Debug)]
+pub struct UploadOptions {
+    /// Files of at least this many bytes are sent as multipart uploads.
+    pub multipart_threshold: u64,
+    /// The part size of multipart uploads, raised to `MIN_PART_SIZE` if needed.
+    pub part_size: usize,
+    /// HEAD the destination first and skip the upload when the object already has the size and
+    /// ETag the upload would produce. Objects encrypted with SSE-KMS or SSE-C are always
+    /// uploaded, as their ETag isn't derived from the content.
+    pub skip_unchanged: bool,
+    /// Report the progress of the upload here. Nothing is reported for skipped uploads.
+    pub progress: Option<ProgressReporter>,
+    /// Gzip files whose content type, guessed from the key, is accepted, as they are read. The
+    /// compressed length isn't known in advance, so such files are always sent as multipart
+    /// uploads, and are never skipped by `skip_unchanged`.
+    #[cfg(feature = "encoding")]
+    pub compression: Option<UploadCompression>,
+    /// Store the object under the key this gives for `key`, e.g. `HashPrefixSharding`. The
+    /// progress is reported against the stored key.
+    pub key_transformer: Option<Arc<dyn KeyTransformer>>,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        UploadOptions {
+            multipart_threshold: 16 * 1024 * 1024,
+            part_size: 8 * 1024 * 1024,
+            skip_unchanged: false,
+            progress: None,
+            #[cfg(feature = "encoding")]
+            compression: None,
+            key_transformer: None,
+        }
+    }
+}
+
+/// What `upload_file` did.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UploadOutcome {
+    /// The file was uploaded, and the object got this ETag.
+    Uploaded(Option<String>),
+    /// The object was already up to date, with this ETag.
+    Skipped(String),
+}
+
+/// Errors returned by `upload_file`.
+#[derive(Debug)]
+pub enum TransferError {
+    /// The local file couldn't be read.
+    Io(io::Error),
+    /// The destination couldn't be checked.
+    Head(Ks3Error<ExistsError>),
+    /// The single-request upload failed.
+    Put(Ks3Error<PutObjectError>),
+    /// The multipart upload failed.
+    Multipart(MultipartError),
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TransferError::Io(ref err) => write!(f, "{}", err),
+            TransferError::Head(ref err) => write!(f, "{}", err),
+            TransferError::Put(ref err) => write!(f, "{}", err),
+            TransferError::Multipart(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for TransferError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            TransferError::Io(ref err) => Some(err),
+            TransferError::Head(ref err) => Some(err),
+            TransferError::Put(ref err) => Some(err),
+            TransferError::Multipart(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for TransferError {
+    fn from(err: io::Error) -> Self {
+        TransferError::Io(err)
+    }
+}
+
+/// Upload the file at `path` to `bucket` under `key`.
+pub async fn upload_file<C, P>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    path: P,
+    options: &UploadOptions,
+) -> Result<UploadOutcome, TransferError>
+where
+    C: S3 + Sync,
+    P: AsRef<Path>,
+{
+    let stored_key;
+    let key = match options.key_transformer {
+        Some(ref transformer) => {
+            stored_key = transformer.to_stored(key);
+            stored_key.as_str()
+        }
+        None => key,
+    };
+    let path = path.as_ref();
+    let owned = path.to_owned();
+    let size = runtime::unblock(move || std::fs::metadata(owned))
+        .await?
+        .len();
+    let multipart = size >= options.multipart_threshold;
+    let part_size = options.part_size.max(MIN_PART_SIZE);
+
+    #[cfg(feature = "encoding")]
+    {
+        let content_type = mime_type_for_key(key);
+        let compression = options.compression.as_ref().filter(|compression| {
+            size >= compression.min_size as u64 && compression.accepts(content_type)
+        });
+        if let Some(compression) = compression {
+            let body = compression.gzip_stream(open_body(path, size).await?);
+            let input = CreateMultipartUploadRequest {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                content_encoding: Some(GZIP_ENCODING.to_owned()),
+                content_type: content_type.map(str::to_owned),
+                ..Default::default()
+            };
+            let output = match options.progress {
+                Some(ref progress) => {
+                    upload_stream_with_progress(client, input, body, part_size, progress).await
+                }
+                None => upload_stream(client, input, body, part_size).await,
+            }
+            .map_err(TransferError::Multipart)?;
+            return Ok(UploadOutcome::Uploaded(output.e_tag));
+        }
+    }
+
+    if options.skip_unchanged {
+        if let Some(e_tag) =
+            unchanged_etag(client, bucket, key, path, size, multipart, part_size).await?
+        {
+            return Ok(UploadOutcome::Skipped(e_tag));
+        }
+    }
+
+    let body = open_body(path, size).await?;
+    if multipart {
+        let input = CreateMultipartUploadRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            ..Default::default()
+        };
+        let output = match options.progress {
+            Some(ref progress) => {
+                upload_stream_with_progress(client, input, body, part_size, progress).await
+            }
+            None => upload_stream(client, input, body, part_size).await,
+        }
+        .map_err(TransferError::Multipart)?;
+        Ok(UploadOutcome::Uploaded(output.e_tag))
+    } else {
+        let progress = options.progress.as_ref();
+        emit(progress, || ProgressEvent::TransferStarted {
+            key: key.to_owned(),
+            total_bytes: Some(size),
+        });
+        let body = match progress {
+            Some(progress) => progress.track(key, body, Some(size)),
+            None => body,
+        };
+        let result = client
+            .put_object(PutObjectRequest {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                body: Some(body),
+                content_length: Some(size as i64),
+                ..Default::default()
+            })
+            .await;
+        report_outcome(progress, key, &result);
+        let output = result.map_err(TransferError::Put)?;
+        Ok(UploadOutcome::Uploaded(output.e_tag))
+    }
+}
+
+/// The content of the file at `path`, which is `size` bytes long.
+async fn open_body(path: &Path, size: u64) -> io::Result<ByteStream> {
+    ByteStream::from_path_with_len(path, size as usize).await
+}
+
+/// Return the ETag of the destination if it matches the size and the ETag the upload of the file
+/// would produce.
+async fn unchanged_etag<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    path: &Path,
+    size: u64,
+    multipart: bool,
+    part_size: usize,
+) -> Result<Option<String>, TransferError>
+where
+    C: S3 + Sync,
+{
+    let head = client
+        .head_object(HeadObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            ..Default::default()
+        })
+        .await;
+    let head = match head {
+        Ok(head) => head,
+        Err(err) => {
+            return match translate(err) {
+                None => Ok(None),
+                Some(err) => Err(TransferError::Head(err)),
+            }
+        }
+    };
+    if head.content_length != Some(size as i64)
+        || head.sse_customer_algorithm.is_some()
+        || head.server_side_encryption == Some(ServerSideEncryption::AwsKms)
+    {
+        return Ok(None);
+    }
+    let e_tag = match head.e_tag {
+        Some(e_tag) => e_tag.trim_matches('"').to_owned(),
+        None => return Ok(None),
+    };
+    let expected = if multipart {
+        multipart_etag(path, part_size).await?
+    } else {
+        file_md5(path).await?
+    };
+    Ok(Some(e_tag).filter(|e_tag| e_tag.eq_ignore_ascii_case(&expected)))
+}
+
+/// Compute the ETag of the file at `path` uploaded in parts of `part_size` bytes: the MD5 of
+/// the concatenated MD5 digests of the parts, followed by the number of parts.
+async fn multipart_etag(path: &Path, part_size: usize) -> io::Result<String> {
+    let path = path.to_owned();
+    runtime::unblock(move || {
+        let mut file = std::fs::File::open(path)?;
+        let mut digests = Vec::new();
+        let mut parts = 0;
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let mut context = md5::Context::new();
+            let mut len = 0;
+            while len < part_size {
+                let want = buf.len().min(part_size - len);
+                let n = io::Read::read(&mut file, &mut buf[..want])?;
+                if n == 0 {
+                    break;
+                }
+                context.consume(&buf[..n]);
+                len += n;
+            }
+            if len == 0 && parts > 0 {
+                break;
+            }
+            digests.extend_from_slice(&context.compute().0);
+            parts += 1;
+            if len < part_size {
+                break;
+            }
+        }
+        Ok(format!("{:x}-{}", md5::compute(&digests), parts))
+    })
+    .await
+}
@@ -0,0 +1,217 @@
+//! Operating on the versions of an object in a versioned bucket, e.g. to erase every trace of
+//! an object for good:
+//!
+//! ```rust,ignore
+//! let report = purge_versions(&client, "users", "exports/42.json", &BatchOptions::default())
+//!     .await?;
+//! assert!(report.failed.is_empty());
+//! ```
+//!
+//! Deleting a key of a versioned bucket only adds a delete marker in front of its versions,
+//! which can still be read by ID; `purge_versions` removes the versions and the markers alike.
+
+use std::error::Error;
+use std::fmt;
+
+use futures::{stream, StreamExt};
+use tracing::{debug_span, Instrument};
+
+use crate::batch::BatchOptions;
+use crate::core::error::Ks3Error;
+use crate::request::*;
+use crate::retry::Retries;
+use crate::s3::S3;
+
+/// Read the version `version_id` of the object `key` in `bucket`.
+pub async fn get_object_version<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    version_id: &str,
+) -> Result<GetObjectOutput, Ks3Error<GetObjectError>>
+where
+    C: S3 + Sync,
+{
+    client
+        .get_object(GetObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            version_id: Some(version_id.to_owned()),
+            ..Default::default()
+        })
+        .await
+}
+
+/// Permanently delete the version `version_id` of the object `key` in `bucket`, which may be a
+/// delete marker. Unlike deleting the key, no delete marker is added.
+pub async fn delete_object_version<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    version_id: &str,
+) -> Result<DeleteObjectOutput, Ks3Error<DeleteObjectError>>
+where
+    C: S3 + Sync,
+{
+    client
+        .delete_object(DeleteObjectRequest {
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            version_id: Some(version_id.to_owned()),
+            ..Default::default()
+        })
+        .await
+}
+
+/// A version `purge_versions` kept failing to delete.
+#[derive(Debug)]
+pub struct VersionFailure {
+    /// The ID of the version or delete marker.
+    pub version_id: String,
+    /// How many times the deletion was attempted.
+    pub attempts: u32,
+    /// The error of the last attempt.
+    pub error: Ks3Error<DeleteObjectError>,
+}
+
+/// The outcome of `purge_versions`, keyed by version ID.
+#[derive(Debug, Default)]
+pub struct PurgeReport {
+    /// Versions and delete markers that were deleted.
+    pub deleted: Vec<String>,
+    /// Versions and delete markers whose deletion failed, even after retrying.
+    pub failed: Vec<VersionFailure>,
+}
+
+/// A listing error that stopped `purge_versions` before any version was deleted.
+#[derive(Debug)]
+pub struct PurgeError {
+    /// The error of the listing request.
+    pub error: Ks3Error<ListObjectVersionsError>,
+}
+
+impl fmt::Display for PurgeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl Error for PurgeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The IDs of every version and delete marker of exactly `key`, leaving out the keys it is a
+/// prefix of.
+pub async fn list_version_ids<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+) -> Result<Vec<String>, Ks3Error<ListObjectVersionsError>>
+where
+    C: S3 + Sync,
+{
+    let mut version_ids = Vec::new();
+    let mut key_marker = None;
+    let mut version_id_marker = None;
+    loop {
+        let output = client
+            .list_object_versions(ListObjectVersionsRequest {
+                bucket: bucket.to_owned(),
+                prefix: Some(key.to_owned()),
+                key_marker: key_marker.take(),
+                version_id_marker: version_id_marker.take(),
+                ..Default::default()
+            })
+            .await?;
+        let versions = output
+            .versions
+            .into_iter()
+            .flatten()
+            .map(|version| (version.key, version.version_id));
+        let delete_markers = output
+            .delete_markers
+            .into_iter()
+            .flatten()
+            .map(|marker| (marker.key, marker.version_id));
+        version_ids.extend(
+            versions
+                .chain(delete_markers)
+                .filter(|(version_key, _)| version_key.as_deref() == Some(key))
+                .filter_map(|(_, version_id)| version_id),
+        );
+
+        // Keys are listed in order: past `key`, only the keys it is a prefix of are left.
+        if !output.is_truncated.unwrap_or(false)
+            || output
+                .next_key_marker
+                .as_deref()
+                .is_none_or(|next| next > key)
+        {
+            break;
+        }
+        key_marker = output.next_key_marker;
+        version_id_marker = output.next_version_id_marker;
+    }
+    Ok(version_ids)
+}
+
+/// Permanently delete every version and delete marker of the object `key` in `bucket`, so
+/// that nothing of it can be read back, e.g. to honour a request for erasure.
+///
+/// All the versions are listed first, then deleted up to `BatchOptions::concurrency` at a
+/// time. A deletion that fails is retried with an increasing delay, as long as
+/// `BatchOptions::retry_budget` allows; versions that still fail are recorded in the report,
+/// and purging again deletes them. Only a failure of the listing stops the purge, before
+/// anything is deleted.
+pub async fn purge_versions<C>(
+    client: &C,
+    bucket: &str,
+    key: &str,
+    options: &BatchOptions,
+) -> Result<PurgeReport, PurgeError>
+where
+    C: S3 + Sync,
+{
+    let version_ids = list_version_ids(client, bucket, key)
+        .await
+        .map_err(|error| PurgeError { error })?;
+
+    let retries = Retries {
+        max_retries: options.max_retries,
+        delay: options.retry_delay,
+        clock: &*options.clock,
+        budget: options.retry_budget.as_ref(),
+        metrics: options.metrics.as_deref(),
+        operation: "PurgeVersions",
+    };
+    let retries = &retries;
+    let results = stream::iter(version_ids)
+        .map(|version_id| async move {
+            let (attempts, result) = retries
+                .run(|attempt| {
+                    delete_object_version(client, bucket, key, &version_id).instrument(
+                        debug_span!("attempt", version_id = %version_id, attempt = attempt),
+                    )
+                })
+                .await;
+            (version_id, attempts, result)
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut report = PurgeReport::default();
+    for (version_id, attempts, result) in results {
+        match result {
+            Ok(_) => report.deleted.push(version_id),
+            Err(error) => report.failed.push(VersionFailure {
+                version_id,
+                attempts,
+                error,
+            }),
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,307 @@
+//! Uploading an object by writing to it, as an `AsyncWrite` backed by a multipart upload:
+//!
+//! ```rust,ignore
+//! let mut writer = UploadWriter::new(Arc::new(client), input);
+//! tokio::io::copy(&mut reader, &mut writer).await?;
+//! writer.shutdown().await?;
+//! ```
+//!
+//! The object only becomes visible once `shutdown` has completed the upload. A writer dropped
+//! before that aborts the upload, following its `DropPolicy`, so that a partially written
+//! object is never seen in its place.
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::BytesMut;
+use tokio::io::AsyncWrite;
+
+use crate::core::runtime::default_runtime;
+use crate::multipart::{abort, complete, upload_part, MultipartError, MAX_PARTS, MIN_PART_SIZE};
+use crate::s3::ops::*;
+use crate::s3::S3;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// What happens to the upload of an `UploadWriter` dropped before it was shut down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Abort the upload in the background right away, then once more after `grace`: a part
+    /// still being received by the service when the writer was dropped may be stored after
+    /// the first abort.
+    Abort {
+        /// The delay before the second abort.
+        grace: Duration,
+    },
+    /// Leave the upload and its parts in place, e.g. for a lifecycle rule to clean up. The
+    /// object stays invisible either way.
+    Leave,
+}
+
+impl Default for DropPolicy {
+    fn default() -> DropPolicy {
+        DropPolicy::Abort {
+            grace: Duration::from_secs(10),
+        }
+    }
+}
+
+/// The request of an `UploadWriter` in flight, if any.
+enum State {
+    Idle,
+    Creating(BoxFuture<Result<String, MultipartError>>),
+    Uploading(BoxFuture<Result<CompletedPart, MultipartError>>),
+    Completing(BoxFuture<Result<CompleteMultipartUploadOutput, MultipartError>>),
+    Completed,
+    Failed,
+}
+
+/// An `AsyncWrite` uploading the bytes written to it as the object described by a
+/// `CreateMultipartUploadRequest`.
+///
+/// The bytes are sent in parts of `part_size` bytes, one at a time, so at most one part and the
+/// bytes of a write are held in memory. Writes wait while a part is being sent. The methods of
+/// `AsyncWrite` mean:
+///
+/// * `flush` waits for the part being sent, if any. Bytes short of a part stay buffered, as
+///   only the last part may be smaller than `MIN_PART_SIZE`.
+/// * `shutdown` sends the remaining bytes and completes the upload, making the object visible.
+///   The output of the completion is then available from `output`.
+/// * Dropping the writer before `shutdown` has returned aborts the upload, see `DropPolicy`.
+///   A completion already sent may still make the object visible.
+///
+/// Once a request fails, the upload is aborted and every further call returns an error.
+pub struct UploadWriter<C: S3 + Send + Sync + 'static> {
+    client: Arc<C>,
+    input: Option<CreateMultipartUploadRequest>,
+    bucket: String,
+    key: String,
+    part_size: usize,
+    drop_policy: DropPolicy,
+    upload_id: Option<String>,
+    buffer: BytesMut,
+    parts: Vec<CompletedPart>,
+    state: State,
+    shutting_down: bool,
+    output: Option<CompleteMultipartUploadOutput>,
+}
+
+impl<C: S3 + Send + Sync + 'static> UploadWriter<C> {
+    /// A writer uploading to the object described by `input` with `client`, in parts of 8 MiB.
+    /// Nothing is sent until a part is full or the writer is shut down.
+    pub fn new(client: Arc<C>, input: CreateMultipartUploadRequest) -> UploadWriter<C> {
+        UploadWriter {
+            client,
+            bucket: input.bucket.clone(),
+            key: input.key.clone(),
+            input: Some(input),
+            part_size: 8 * 1024 * 1024,
+            drop_policy: DropPolicy::default(),
+            upload_id: None,
+            buffer: BytesMut::new(),
+            parts: Vec::new(),
+            state: State::Idle,
+            shutting_down: false,
+            output: None,
+        }
+    }
+
+    /// The writer sending parts of `part_size` bytes instead, raised to `MIN_PART_SIZE` if
+    /// needed.
+    pub fn with_part_size(mut self, part_size: usize) -> UploadWriter<C> {
+        self.part_size = part_size.max(MIN_PART_SIZE);
+        self
+    }
+
+    /// The writer following `drop_policy` instead when dropped before being shut down.
+    pub fn with_drop_policy(mut self, drop_policy: DropPolicy) -> UploadWriter<C> {
+        self.drop_policy = drop_policy;
+        self
+    }
+
+    /// The ID of the upload, once it has been initiated.
+    pub fn upload_id(&self) -> Option<&str> {
+        self.upload_id.as_deref()
+    }
+
+    /// The output of the completion of the upload, once `shutdown` has returned successfully.
+    pub fn output(&self) -> Option<&CompleteMultipartUploadOutput> {
+        self.output.as_ref()
+    }
+
+    /// Abort the upload now, discarding what was written, instead of when dropped.
+    pub async fn abort(mut self) {
+        self.state = State::Failed;
+        if let Some(upload_id) = self.upload_id.take() {
+            abort(&*self.client, &self.bucket, &self.key, &upload_id).await;
+        }
+    }
+
+    /// Wait for the request in flight, if any.
+    fn poll_request(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let result = match self.state {
+            State::Idle | State::Completed => return Poll::Ready(Ok(())),
+            State::Failed => return Poll::Ready(Err(failed())),
+            State::Creating(ref mut request) => futures::ready!(request.as_mut().poll(cx))
+                .map(|upload_id| self.upload_id = Some(upload_id)),
+            State::Uploading(ref mut request) => {
+                futures::ready!(request.as_mut().poll(cx)).map(|part| self.parts.push(part))
+            }
+            State::Completing(ref mut request) => {
+                futures::ready!(request.as_mut().poll(cx)).map(|output| self.output = Some(output))
+            }
+        };
+        match result {
+            Ok(()) => {
+                self.state = match self.state {
+                    State::Completing(_) => State::Completed,
+                    _ => State::Idle,
+                };
+                Poll::Ready(Ok(()))
+            }
+            Err(err) => {
+                self.fail();
+                Poll::Ready(Err(io::Error::other(err)))
+            }
+        }
+    }
+
+    /// Send the next request: initiate the upload, send a full part or, when shutting down,
+    /// the last part and the completion.
+    fn send_next(&mut self) {
+        let client = self.client.clone();
+        let bucket = self.bucket.clone();
+        let key = self.key.clone();
+        let upload_id = match self.upload_id {
+            Some(ref upload_id) => upload_id.clone(),
+            None => {
+                let input = self.input.take().unwrap_or_default();
+                self.state = State::Creating(Box::pin(async move {
+                    let output = client
+                        .create_multipart_upload(input)
+                        .await
+                        .map_err(MultipartError::Create)?;
+                    Ok(output.upload_id.unwrap_or_default())
+                }));
+                return;
+            }
+        };
+        // Every upload has at least one part, even if nothing was written.
+        if self.buffer.len() >= self.part_size
+            || (self.shutting_down && (!self.buffer.is_empty() || self.parts.is_empty()))
+        {
+            let part_number = self.parts.len() as i64 + 1;
+            let len = self.buffer.len().min(self.part_size);
+            let data = self.buffer.split_to(len).freeze();
+            self.state = State::Uploading(Box::pin(async move {
+                if part_number > MAX_PARTS {
+                    return Err(MultipartError::TooManyParts);
+                }
+                upload_part(&*client, &bucket, &key, &upload_id, part_number, data).await
+            }));
+        } else if self.shutting_down {
+            let parts = self.parts.clone();
+            self.state = State::Completing(Box::pin(async move {
+                complete(&*client, &bucket, &key, &upload_id, parts).await
+            }));
+        }
+    }
+
+    /// Give up on the upload after a failed request, aborting it in the background.
+    fn fail(&mut self) {
+        self.state = State::Failed;
+        if let Some(upload_id) = self.upload_id.take() {
+            let client = self.client.clone();
+            let bucket = self.bucket.clone();
+            let key = self.key.clone();
+            default_runtime().spawn(Box::pin(async move {
+                abort(&*client, &bucket, &key, &upload_id).await;
+            }));
+        }
+    }
+}
+
+/// The error returned by a writer whose upload failed.
+fn failed() -> io::Error {
+    io::Error::other("the upload failed and was aborted")
+}
+
+impl<C: S3 + Send + Sync + 'static> AsyncWrite for UploadWriter<C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.shutting_down {
+            return Poll::Ready(Err(io::Error::other("write after shutdown")));
+        }
+        loop {
+            futures::ready!(this.poll_request(cx))?;
+            if this.buffer.len() < this.part_size {
+                this.buffer.extend_from_slice(buf);
+                return Poll::Ready(Ok(buf.len()));
+            }
+            this.send_next();
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_request(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.shutting_down = true;
+        loop {
+            futures::ready!(this.poll_request(cx))?;
+            if let State::Completed = this.state {
+                return Poll::Ready(Ok(()));
+            }
+            this.send_next();
+        }
+    }
+}
+
+impl<C: S3 + Send + Sync + 'static> Drop for UploadWriter<C> {
+    fn drop(&mut self) {
+        if let State::Completed | State::Failed = self.state {
+            return;
+        }
+        let grace = match self.drop_policy {
+            DropPolicy::Abort { grace } => grace,
+            DropPolicy::Leave => return,
+        };
+        // An upload still being initiated can't be aborted without its ID.
+        let upload_id = match self.upload_id.take() {
+            Some(upload_id) => upload_id,
+            None => return,
+        };
+        let client = self.client.clone();
+        let bucket = self.bucket.clone();
+        let key = self.key.clone();
+        let runtime = default_runtime();
+        runtime.clone().spawn(Box::pin(async move {
+            abort(&*client, &bucket, &key, &upload_id).await;
+            runtime.sleep(grace).await;
+            abort(&*client, &bucket, &key, &upload_id).await;
+        }));
+    }
+}
+
+impl<C: S3 + Send + Sync + 'static> fmt::Debug for UploadWriter<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UploadWriter")
+            .field("bucket", &self.bucket)
+            .field("key", &self.key)
+            .field("upload_id", &self.upload_id)
+            .field("buffered", &self.buffer.len())
+            .field("parts", &self.parts.len())
+            .finish()
+    }
+}